@@ -1,20 +1,23 @@
 //! traur-hook: ALPM pre-transaction hook binary.
 //! Reads package names from stdin (passed by pacman/paru via NeedsTargets),
 //! filters to AUR-only packages, scans each silently, then shows a summary.
+//! Upgrades are diffed against the PKGBUILD of the currently installed version.
 //! Detail is only printed for SKETCHY+ packages. No prompt when all clean.
 //!
 //! All output goes to /dev/tty — pacman buffers both stdout and stderr from
-//! hooks, so we must write directly to the terminal.
+//! hooks, so we must write directly to the terminal. MALICIOUS detections are
+//! also sent to any notification channels configured under `[notify]`.
 
 use std::fs::OpenOptions;
 use std::io::{self, BufRead, BufReader, Write};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::process::Command;
 use colored::Colorize;
 use traur::coordinator;
-use traur::shared::bulk;
+use traur::shared::{aur_git, bulk, cache, hook_state, known_good};
 use traur::shared::config::{self, is_whitelisted_in};
-use traur::shared::output;
+use traur::shared::notify;
+use traur::shared::output::{self, OutputFormat, RenderOptions};
 use traur::shared::scoring::{ScanResult, Tier};
 
 fn main() {
@@ -27,7 +30,7 @@ fn main() {
     let packages: Vec<String> = stdin
         .lock()
         .lines()
-        .filter_map(|line| line.ok())
+        .map_while(Result::ok)
         .map(|l| l.trim().to_string())
         .filter(|l| !l.is_empty())
         .collect();
@@ -47,15 +50,19 @@ fn main() {
         return;
     }
 
+    let config = config::load_config();
+
     // Open /dev/tty for ALL output — pacman buffers both stdout and stderr
-    // from hooks, so only direct tty writes appear immediately.
-    let mut tty = match OpenOptions::new().read(true).write(true).open("/dev/tty") {
-        Ok(f) => f,
-        Err(_) => return, // non-interactive, skip silently
+    // from hooks, so only direct tty writes appear immediately. Without a
+    // terminal (headless servers, unattended upgrades) we only scan when a
+    // notification channel is configured, writing to stderr instead.
+    let tty_in = OpenOptions::new().read(true).write(true).open("/dev/tty").ok();
+    let mut tty: Box<dyn Write> = match tty_in.as_ref().and_then(|f| f.try_clone().ok()) {
+        Some(f) => Box::new(f),
+        None if config.notify.is_enabled() => Box::new(io::stderr()),
+        None => return, // non-interactive, skip silently
     };
 
-    let config = config::load_config();
-
     let _ = writeln!(
         tty,
         "{}",
@@ -100,19 +107,55 @@ fn main() {
             not_found.join(", ")
         );
     }
-    let scan_packages: Vec<String> = to_scan
+    let mut scan_packages: Vec<String> = to_scan
         .into_iter()
         .filter(|n| metadata.contains_key(n.as_str()))
         .collect();
 
-    let any_scanned = !scan_packages.is_empty();
+    // [hook] skip_unchanged: fetch each repo up front, so packages still at the commit
+    // recorded last time skip the upstream prefetch as well as the analysis.
+    let mut head_commits = hook_state::ScannedCommits::new();
+    let mut unchanged_count: u32 = 0;
+    let fingerprint = config::fingerprint(&config);
+    if config.hook.skip_unchanged {
+        let recorded = hook_state::load(&fingerprint).unwrap_or_else(|| {
+            let _ = writeln!(tty, "  {}", "Config changed since the last hook scan; scanning every package again.".dimmed());
+            hook_state::ScannedCommits::new()
+        });
+        let git_cache = cache::git_cache_dir();
+        for pkg in &scan_packages {
+            let base = metadata[pkg.as_str()].package_base.as_deref().unwrap_or(pkg);
+            if let Some(head) = aur_git::ensure_repo(base, &git_cache.to_string_lossy())
+                .ok()
+                .and_then(|repo| aur_git::head_commit(&repo))
+            {
+                head_commits.insert(pkg.clone(), head);
+            }
+        }
+        scan_packages.retain(|pkg| {
+            let unchanged = head_commits.get(pkg).is_some_and(|head| recorded.get(pkg) == Some(head));
+            unchanged_count += u32::from(unchanged);
+            !unchanged
+        });
+        if unchanged_count > 0 {
+            let _ = writeln!(tty, "  {} package(s) unchanged on the AUR since last scan, skipping.", unchanged_count);
+        }
+    }
+
     let total_scan = scan_packages.len();
 
+    // Installed versions, to detect upgrades
+    let installed = installed_versions();
+
     // Pre-fetch maintainer data for all packages
-    let maintainer_packages = bulk::prefetch_maintainer_packages(&metadata);
+    let maintainer_packages = bulk::prefetch_maintainer_packages(&metadata, &config);
+    let upstream = bulk::prefetch_upstream(&metadata, &config);
 
     let mut results: Vec<ScanResult> = Vec::new();
     let mut scan_errors: Vec<(String, String)> = Vec::new();
+    let mut stale_pins: Vec<&str> = Vec::new();
+    // PKGBUILD hash per scanned package, for `[hook] remember_approvals`
+    let mut pkgbuild_hashes: HashMap<String, String> = HashMap::new();
     let mut tier_counts: [u32; 5] = [0, 0, 0, 0, 0]; // Trusted, Ok, Sketchy, Suspicious, Malicious
 
     for (i, pkg) in scan_packages.iter().enumerate() {
@@ -127,10 +170,33 @@ fn main() {
             .and_then(|m| maintainer_packages.get(m))
             .cloned()
             .unwrap_or_default();
-
-        match bulk::clone_with_retry(pkg, meta, maint_pkgs) {
-            Ok(ctx) => {
-                let result = coordinator::run_analysis_with_config(&ctx, &config);
+        let upstream_data = upstream.get(pkg.as_str()).cloned().unwrap_or_default();
+
+        let deadline = coordinator::Deadline::start(&config);
+
+        match bulk::clone_with_retry(pkg, meta, maint_pkgs, upstream_data, deadline, &config) {
+            Ok(mut ctx) => {
+                // Pinned whitelist entries hold only while the PKGBUILD is unchanged
+                let current = ctx
+                    .pkgbuild_content
+                    .as_deref()
+                    .map(|p| known_good::sha256_hex(&aur_git::strip_inlined(p)));
+                if let Some(pin) = config::whitelist_pin(&config, pkg) {
+                    if current.as_deref() == Some(pin) {
+                        whitelisted_count += 1;
+                        continue;
+                    }
+                    stale_pins.push(pkg);
+                }
+                if let Some(hash) = current {
+                    pkgbuild_hashes.insert(pkg.clone(), hash);
+                }
+                // Upgrade: diff against the installed version's PKGBUILD so
+                // T-DIFF-* signals cover everything changed since install.
+                if let Some(version) = installed.get(pkg.as_str()) {
+                    coordinator::use_installed_baseline(&mut ctx, version);
+                }
+                let result = coordinator::run_analysis_within(&ctx, &config, deadline);
                 let idx = match result.tier {
                     Tier::Trusted => 0,
                     Tier::Ok => 1,
@@ -151,9 +217,19 @@ fn main() {
     let _ = write!(tty, "\r{}\r", " ".repeat(72));
     let _ = tty.flush();
 
+    if !stale_pins.is_empty() {
+        let _ = writeln!(
+            tty,
+            "  PKGBUILD changed since whitelisted, scanning again: {}",
+            stale_pins.join(", ")
+        );
+    }
+
     // --- Phase 2: Output + decision ---
 
-    // Case 1: All whitelisted
+    let any_scanned = !results.is_empty() || !scan_errors.is_empty();
+
+    // Case 1: All whitelisted or unchanged
     if !any_scanned {
         if whitelisted_count > 0 {
             let _ = writeln!(
@@ -197,10 +273,10 @@ fn main() {
 
     // Full detail for all results
     if !results.is_empty() {
-        results.sort_by(|a, b| a.score.cmp(&b.score));
+        results.sort_by_key(|a| a.score);
         for result in &results {
             let _ = writeln!(tty);
-            output::write_text(&mut tty, result, false);
+            output::render(&mut tty, result, OutputFormat::Text, &RenderOptions::default());
         }
     }
 
@@ -213,10 +289,17 @@ fn main() {
     }
 
     let has_malicious = tier_counts[4] > 0;
-    let has_flagged = tier_counts[2] > 0 || tier_counts[3] > 0; // SKETCHY or SUSPICIOUS
+    // Packages whose scan hit the timeout were never fully analyzed, whatever their tier
+    let timed_out: Vec<&str> = results
+        .iter()
+        .filter(|r| !r.skipped_features.is_empty())
+        .map(|r| r.package.as_str())
+        .collect();
+    let has_flagged = tier_counts[2] > 0 || tier_counts[3] > 0 || !timed_out.is_empty(); // SKETCHY or SUSPICIOUS
 
     // Case 2: MALICIOUS detected -> hard block, must whitelist
     if has_malicious {
+        notify::notify_results(&config.notify, "transaction blocked", &results);
         let _ = writeln!(tty);
         let _ = writeln!(
             tty,
@@ -245,13 +328,35 @@ fn main() {
         std::process::exit(1);
     }
 
-    // Case 4: SKETCHY or SUSPICIOUS -> prompt [y/N]
+    // Case 4: SKETCHY, SUSPICIOUS or timed out -> prompt [y/N]
     if has_flagged {
         let _ = writeln!(tty);
+        if !timed_out.is_empty() {
+            let _ = writeln!(
+                tty,
+                "{}",
+                format!("traur: scan timed out before every feature ran: {}", timed_out.join(", ")).yellow()
+            );
+        }
+        // Headless: nobody to ask. A timed-out scan is blocked like a failed one (fail
+        // closed); otherwise only MALICIOUS blocks (handled above).
+        if tty_in.is_none() && !timed_out.is_empty() {
+            let _ = writeln!(
+                tty,
+                "{}",
+                "traur: no terminal to confirm incomplete scans — blocking transaction".red().bold()
+            );
+            std::process::exit(1);
+        }
         let _ = write!(tty, "{} ", "traur: Continue with installation? [y/N]".bold());
         let _ = tty.flush();
 
-        let mut reader = BufReader::new(tty);
+        let Some(tty_in) = tty_in else {
+            let _ = writeln!(tty, "traur: no terminal available, continuing");
+            record_scanned(&mut tty, &head_commits, &results, &fingerprint);
+            return;
+        };
+        let mut reader = BufReader::new(tty_in);
         let mut line = String::new();
         let response = match reader.read_line(&mut line) {
             Ok(0) => "",
@@ -265,11 +370,82 @@ fn main() {
             eprintln!("traur: aborting transaction");
             std::process::exit(1);
         }
+        if config.hook.remember_approvals {
+            remember_approvals(&mut tty, &results, &pkgbuild_hashes);
+        }
+        record_scanned(&mut tty, &head_commits, &results, &fingerprint);
         return;
     }
 
     // Case 5: All clean -> no prompt
     let _ = writeln!(tty, "\n  {}", "All packages look clean.".green());
+    record_scanned(&mut tty, &head_commits, &results, &fingerprint);
+}
+
+/// Remember the commits of the packages let through, for `[hook] skip_unchanged`.
+/// `head_commits` is empty unless the option is on. Timed-out scans are not
+/// remembered, so the next transaction scans them again.
+fn record_scanned(tty: &mut dyn Write, head_commits: &hook_state::ScannedCommits, results: &[ScanResult], fingerprint: &str) {
+    let scanned: hook_state::ScannedCommits = results
+        .iter()
+        .filter(|r| r.skipped_features.is_empty())
+        .filter_map(|r| Some((r.package.clone(), head_commits.get(&r.package)?.clone())))
+        .collect();
+    if let Err(e) = hook_state::record(&scanned, fingerprint) {
+        let _ = writeln!(tty, "  {}", format!("traur: {e}").dimmed());
+    }
+}
+
+/// Whitelist the SKETCHY and SUSPICIOUS packages the user just approved, pinned to the
+/// PKGBUILD they were scanned at, for `[hook] remember_approvals`. Timed-out scans
+/// are left out: approving what was never fully analyzed is not pinned.
+fn remember_approvals(tty: &mut dyn Write, results: &[ScanResult], pkgbuild_hashes: &HashMap<String, String>) {
+    if config::load_system_config().is_some() {
+        // Only the system whitelist applies then, and the hook doesn't edit policy
+        let _ = writeln!(
+            tty,
+            "  {}",
+            format!("traur: {} is present, approvals are not remembered", config::SYSTEM_CONFIG_PATH).dimmed()
+        );
+        return;
+    }
+    for result in results
+        .iter()
+        .filter(|r| matches!(r.tier, Tier::Sketchy | Tier::Suspicious) && r.skipped_features.is_empty())
+    {
+        let Some(hash) = pkgbuild_hashes.get(&result.package) else {
+            continue;
+        };
+        match config::add_to_whitelist(&result.package, Some(hash.clone())) {
+            Ok(()) => {
+                let _ = writeln!(
+                    tty,
+                    "  {}",
+                    format!("traur: remembered approval of {} (pinned to PKGBUILD {})", result.package, &hash[..12]).dimmed()
+                );
+            }
+            Err(e) => {
+                let _ = writeln!(tty, "  {}", format!("traur: {e}").dimmed());
+            }
+        }
+    }
+}
+
+/// Get installed package versions (name -> `[epoch:]pkgver-pkgrel`) in one call.
+fn installed_versions() -> HashMap<String, String> {
+    Command::new("pacman")
+        .arg("-Q")
+        .output()
+        .map(|out| {
+            String::from_utf8_lossy(&out.stdout)
+                .lines()
+                .filter_map(|line| {
+                    let mut parts = line.split_whitespace();
+                    Some((parts.next()?.to_string(), parts.next()?.to_string()))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
 }
 
 /// Get all package names from official sync databases in one call.
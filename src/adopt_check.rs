@@ -0,0 +1,326 @@
+use crate::coordinator;
+use crate::shared::config::Config;
+use crate::shared::output::{self, OutputFormat, RenderOptions};
+use crate::shared::scoring::{ScanResult, Tier};
+use crate::shared::{aur_git, cache};
+use colored::Colorize;
+use serde::Serialize;
+use std::io::Write;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Number of recent commits whose diff is shown to the prospective maintainer.
+const RECENT_COMMITS: usize = 5;
+
+/// An out-of-date flag older than this suggests upstream moved on long ago.
+const STALE_FLAG_DAYS: u64 = 180;
+
+/// Phrases in AUR comments that point at unresolved build or packaging problems.
+const COMPLAINT_KEYWORDS: &[&str] = &[
+    "fails to build",
+    "failed to build",
+    "doesn't build",
+    "does not build",
+    "build fails",
+    "won't compile",
+    "compile error",
+    "validity check",
+    "checksum",
+    "404",
+    "please update",
+    "is outdated",
+    "build is broken",
+    "package is broken",
+    "link is broken",
+    "broken link",
+    "broken since",
+    "broken again",
+    "segfault",
+    "crashes",
+];
+
+/// Overall recommendation for adopting the package.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+enum Verdict {
+    Go,
+    Caution,
+    NoGo,
+}
+
+impl Verdict {
+    /// Downgrade GO to CAUTION; a NO-GO stays NO-GO.
+    fn max_caution(self) -> Verdict {
+        match self {
+            Verdict::Go => Verdict::Caution,
+            other => other,
+        }
+    }
+}
+
+impl std::fmt::Display for Verdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Verdict::Go => write!(f, "GO"),
+            Verdict::Caution => write!(f, "CAUTION"),
+            Verdict::NoGo => write!(f, "NO-GO"),
+        }
+    }
+}
+
+/// Everything `traur adopt-check` reports about a package.
+#[derive(Debug, Serialize)]
+struct AdoptReport {
+    package: String,
+    verdict: Verdict,
+    reasons: Vec<String>,
+    orphaned: bool,
+    maintainer: Option<String>,
+    /// Days since the package was flagged out-of-date, if it is.
+    out_of_date_days: Option<u64>,
+    /// Recent AUR comments that look like unresolved build or packaging complaints.
+    complaints: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    recent_diff: Option<String>,
+    scan: ScanResult,
+}
+
+/// Scan a package with checks aimed at someone about to adopt it.
+pub fn run(package: &str, json: bool, config: &Config) -> i32 {
+    let deadline = coordinator::Deadline::start(config);
+    let ctx = match coordinator::build_context(package, config, deadline) {
+        Ok(ctx) => ctx,
+        Err(e) => {
+            eprintln!("Error checking {package}: {e}");
+            return 1;
+        }
+    };
+    let scan = coordinator::run_analysis_within(&ctx, config, deadline);
+
+    let Some(metadata) = ctx.metadata.as_ref() else {
+        eprintln!("Error checking {package}: no AUR metadata");
+        return 1;
+    };
+    let package_base = metadata.package_base.as_deref().unwrap_or(package);
+    let recent_diff =
+        aur_git::get_recent_log_patch(&cache::git_cache_dir().join(package_base), RECENT_COMMITS);
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let out_of_date_days = metadata.out_of_date.map(|flagged| now.saturating_sub(flagged) / 86400);
+    let complaints = find_complaints(&ctx.aur_comments);
+
+    let (verdict, reasons) = assess(
+        scan.tier,
+        metadata.maintainer.as_deref(),
+        out_of_date_days,
+        complaints.len(),
+    );
+
+    let report = AdoptReport {
+        package: package.to_string(),
+        verdict,
+        reasons,
+        orphaned: metadata.maintainer.is_none(),
+        maintainer: metadata.maintainer.clone(),
+        out_of_date_days,
+        complaints,
+        recent_diff,
+        scan,
+    };
+
+    write_report(&mut std::io::stdout(), &report, json);
+
+    if report.verdict == Verdict::NoGo { 1 } else { 0 }
+}
+
+/// Decide the verdict. A SUSPICIOUS or worse scan is a no-go; anything else that
+/// needs the adopter's attention downgrades GO to CAUTION.
+fn assess(
+    tier: Tier,
+    maintainer: Option<&str>,
+    out_of_date_days: Option<u64>,
+    complaint_count: usize,
+) -> (Verdict, Vec<String>) {
+    let mut reasons = Vec::new();
+    let mut verdict = Verdict::Go;
+
+    if tier >= Tier::Suspicious {
+        verdict = Verdict::NoGo;
+        reasons.push(format!("scan rated the package {tier}; review the signals before touching it"));
+    } else if tier == Tier::Sketchy {
+        verdict = Verdict::Caution;
+        reasons.push("scan rated the package SKETCHY".to_string());
+    }
+
+    if let Some(m) = maintainer {
+        verdict = verdict.max_caution();
+        reasons.push(format!("maintained by {m}; it must be orphaned before you can adopt it"));
+    }
+
+    match out_of_date_days {
+        Some(days) if days >= STALE_FLAG_DAYS => {
+            verdict = verdict.max_caution();
+            reasons.push(format!("flagged out-of-date {days} days ago; upstream may have moved far ahead"));
+        }
+        Some(days) => reasons.push(format!("flagged out-of-date {days} days ago")),
+        None => {}
+    }
+
+    if complaint_count > 0 {
+        verdict = verdict.max_caution();
+        reasons.push(format!("{complaint_count} recent comment(s) report build or packaging problems"));
+    }
+
+    (verdict, reasons)
+}
+
+/// Comments mentioning a known complaint phrase, truncated for display.
+fn find_complaints(comments: &[String]) -> Vec<String> {
+    comments
+        .iter()
+        .filter(|c| {
+            let lower = c.to_lowercase();
+            COMPLAINT_KEYWORDS.iter().any(|k| lower.contains(k))
+        })
+        .map(|c| {
+            if c.chars().count() > 120 {
+                format!("{}...", c.chars().take(120).collect::<String>())
+            } else {
+                c.clone()
+            }
+        })
+        .collect()
+}
+
+/// Write the report as JSON alone, or as the text scan report followed by the
+/// adoption summary; the two are never mixed on one stream.
+fn write_report(w: &mut dyn Write, report: &AdoptReport, json: bool) {
+    if json {
+        let json_str = serde_json::to_string_pretty(report).expect("Failed to serialize");
+        let _ = writeln!(w, "{json_str}");
+        return;
+    }
+    output::render(w, &report.scan, OutputFormat::Text, &RenderOptions::default());
+
+    let _ = writeln!(w);
+    let _ = writeln!(w, "{}", "=== Adoption check ===".bold());
+    let maintainer = match report.maintainer {
+        Some(ref m) => m.clone(),
+        None => "(orphan)".green().to_string(),
+    };
+    let _ = writeln!(w, "  Maintainer: {maintainer}");
+    match report.out_of_date_days {
+        Some(days) => {
+            let _ = writeln!(w, "  Out-of-date: flagged {days} days ago");
+        }
+        None => {
+            let _ = writeln!(w, "  Out-of-date: not flagged");
+        }
+    }
+    if report.complaints.is_empty() {
+        let _ = writeln!(w, "  Complaints: none in recent comments");
+    } else {
+        let _ = writeln!(w, "  Complaints:");
+        for complaint in &report.complaints {
+            let _ = writeln!(w, "    - {complaint}");
+        }
+    }
+
+    if let Some(ref diff) = report.recent_diff {
+        let _ = writeln!(w);
+        let _ = writeln!(w, "{}", format!("=== Last {RECENT_COMMITS} commits ===").bold());
+        let _ = writeln!(w, "{}", diff.trim_end());
+    }
+
+    let verdict = match report.verdict {
+        Verdict::Go => report.verdict.to_string().green().bold(),
+        Verdict::Caution => report.verdict.to_string().yellow().bold(),
+        Verdict::NoGo => report.verdict.to_string().red().bold(),
+    };
+    let _ = writeln!(w);
+    let _ = writeln!(w, "{} {verdict}", "Verdict:".bold());
+    for reason in &report.reasons {
+        let _ = writeln!(w, "  - {reason}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared::scoring;
+
+    #[test]
+    fn clean_orphan_is_go() {
+        let (verdict, reasons) = assess(Tier::Trusted, None, None, 0);
+        assert_eq!(verdict, Verdict::Go);
+        assert!(reasons.is_empty());
+    }
+
+    #[test]
+    fn suspicious_scan_is_no_go() {
+        let (verdict, _) = assess(Tier::Suspicious, Some("alice"), Some(400), 3);
+        assert_eq!(verdict, Verdict::NoGo);
+    }
+
+    #[test]
+    fn maintained_package_needs_caution() {
+        let (verdict, reasons) = assess(Tier::Ok, Some("alice"), None, 0);
+        assert_eq!(verdict, Verdict::Caution);
+        assert!(reasons[0].contains("orphaned"));
+    }
+
+    #[test]
+    fn stale_flag_needs_caution_but_fresh_flag_does_not() {
+        assert_eq!(assess(Tier::Ok, None, Some(STALE_FLAG_DAYS), 0).0, Verdict::Caution);
+        let (verdict, reasons) = assess(Tier::Ok, None, Some(3), 0);
+        assert_eq!(verdict, Verdict::Go);
+        assert_eq!(reasons.len(), 1);
+    }
+
+    #[test]
+    fn finds_build_complaints() {
+        let comments = vec![
+            "Thanks for packaging this!".to_string(),
+            "Fails to build with gcc 14: error: implicit declaration".to_string(),
+            "The source URL returns 404 now".to_string(),
+        ];
+        let complaints = find_complaints(&comments);
+        assert_eq!(complaints.len(), 2);
+        assert!(complaints[0].starts_with("Fails to build"));
+    }
+
+    #[test]
+    fn broken_counts_only_as_part_of_a_complaint() {
+        let comments = vec![
+            "Unbroken record of timely updates, thanks!".to_string(),
+            "This replaces the broken upstream desktop file.".to_string(),
+            "The build is broken since the 2.0 release".to_string(),
+        ];
+        assert_eq!(find_complaints(&comments), ["The build is broken since the 2.0 release"]);
+    }
+
+    #[test]
+    fn json_report_is_only_json() {
+        let report = AdoptReport {
+            package: "foo".to_string(),
+            verdict: Verdict::Caution,
+            reasons: vec!["maintained by alice; it must be orphaned before you can adopt it".to_string()],
+            orphaned: false,
+            maintainer: Some("alice".to_string()),
+            out_of_date_days: Some(3),
+            complaints: vec!["fails to build".to_string()],
+            recent_diff: Some("diff --git a/PKGBUILD b/PKGBUILD".to_string()),
+            scan: scoring::compute_score("foo", &[]),
+        };
+        let mut out = Vec::new();
+        write_report(&mut out, &report, true);
+        let value: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(value["verdict"], "caution");
+
+        let mut text = Vec::new();
+        write_report(&mut text, &report, false);
+        assert!(serde_json::from_slice::<serde_json::Value>(&text).is_err());
+    }
+}
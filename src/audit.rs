@@ -0,0 +1,427 @@
+use crate::coordinator::{self, Deadline};
+use crate::shared::bulk::{
+    clone_with_retry, prefetch_maintainer_packages, prefetch_upstream, try_batch_fetch_metadata, MetadataBatch,
+};
+use crate::shared::config::Config;
+use crate::shared::output::{self, OutputFormat, RenderOptions};
+use crate::shared::scoring::{ScanResult, Tier};
+use colored::Colorize;
+use indicatif::{ProgressBar, ProgressStyle};
+use rayon::prelude::*;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::process::Command;
+use std::sync::LazyLock;
+
+pub const DEFAULT_LOG_PATH: &str = "/var/log/pacman.log";
+
+/// `[2025-01-15T10:23:45+0100] [ALPM] upgraded foo (1.0-1 -> 1.1-1)`
+/// Also accepts the pre-2013 format `[2012-06-01 10:23] installed foo (1.0-1)`.
+static LOG_LINE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^\[(\d{4}-\d{2}-\d{2})[T ][^\]]*\](?: \[ALPM\])? (installed|upgraded|downgraded|reinstalled|removed) (\S+) \((?:\S+ -> )?([^)]+)\)",
+    )
+    .unwrap()
+});
+
+/// The most recent install/upgrade of a package still installed according to the log.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct LogEntry {
+    name: String,
+    version: String,
+    date: String,
+    action: String,
+}
+
+/// Why an audited package was flagged.
+#[derive(Debug, Serialize)]
+struct AuditFinding {
+    package: String,
+    installed_version: String,
+    date: String,
+    /// Last log action for the package (`installed`, `upgraded`, ...).
+    action: String,
+    /// `"removed-from-aur"` or `"malicious"`.
+    reason: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    scan: Option<ScanResult>,
+}
+
+/// A package that could not be checked: its metadata fetch, clone or scan failed.
+#[derive(Debug, Serialize)]
+struct AuditError {
+    package: String,
+    error: String,
+}
+
+/// `--json` output.
+#[derive(Serialize)]
+struct AuditReport<'a> {
+    findings: &'a [AuditFinding],
+    errors: &'a [AuditError],
+}
+
+/// Retroactively scan AUR packages installed or upgraded according to a pacman log.
+pub fn run(log_path: &str, since: Option<&str>, jobs: usize, json: bool, config: &Config) -> i32 {
+    if let Some(date) = since
+        && !is_iso_date(date)
+    {
+        eprintln!("Invalid --since date: {date} (expected YYYY-MM-DD)");
+        return 1;
+    }
+
+    let content = match std::fs::read_to_string(log_path) {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("Error: failed to read {log_path}: {e}");
+            return 1;
+        }
+    };
+
+    let repo_packages = match sync_db_packages() {
+        Ok(p) => p,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            return 1;
+        }
+    };
+
+    let entries: Vec<LogEntry> = parse_pacman_log(&content, since)
+        .into_iter()
+        .filter(|e| !repo_packages.contains(&e.name))
+        .collect();
+
+    if entries.is_empty() {
+        eprintln!("No foreign packages installed or upgraded in {log_path}{}.", since_suffix(since));
+        return 0;
+    }
+
+    let names: Vec<String> = entries.iter().map(|e| e.name.clone()).collect();
+    eprintln!(
+        "{}",
+        format!("Auditing {} foreign packages from {log_path}{}...", names.len(), since_suffix(since)).bold()
+    );
+    let batch = try_batch_fetch_metadata(&names);
+    let (mut findings, on_aur, unknown) = classify(&entries, &batch);
+    let mut errors: Vec<AuditError> = unknown
+        .iter()
+        .map(|entry| AuditError {
+            package: entry.name.clone(),
+            error: format!("metadata fetch failed: {}", batch.failed[&entry.name]),
+        })
+        .collect();
+    for error in &errors {
+        eprintln!("  error: {}: {}", error.package, error.error);
+    }
+    let metadata = batch.found;
+    let maintainer_packages = prefetch_maintainer_packages(&metadata, config);
+    let upstream = prefetch_upstream(&metadata, config);
+
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(jobs)
+        .build()
+        .expect("Failed to build thread pool");
+
+    let pb = ProgressBar::new(on_aur.len() as u64);
+    pb.set_style(
+        ProgressStyle::default_bar()
+            .template("[{elapsed_precise}] {bar:40.cyan/blue} {pos}/{len} ({per_sec})")
+            .unwrap()
+            .progress_chars("##-"),
+    );
+
+    let scanned: Vec<Result<Option<AuditFinding>, AuditError>> = pool.install(|| {
+        on_aur
+            .par_iter()
+            .filter_map(|entry| {
+                let meta = metadata.get(&entry.name)?.clone();
+                let maint_pkgs = meta
+                    .maintainer
+                    .as_deref()
+                    .and_then(|m| maintainer_packages.get(m))
+                    .cloned()
+                    .unwrap_or_default();
+                let upstream_data = upstream.get(&entry.name).cloned().unwrap_or_default();
+
+                let deadline = Deadline::start(config);
+
+                let finding = match clone_with_retry(&entry.name, meta, maint_pkgs, upstream_data, deadline, config) {
+                    Ok(ctx) => {
+                        let scan = coordinator::run_analysis_within(&ctx, config, deadline);
+                        Ok((scan.tier == Tier::Malicious).then(|| AuditFinding {
+                            package: entry.name.clone(),
+                            installed_version: entry.version.clone(),
+                            date: entry.date.clone(),
+                            action: entry.action.clone(),
+                            reason: "malicious",
+                            scan: Some(scan),
+                        }))
+                    }
+                    Err(e) => {
+                        pb.suspend(|| eprintln!("  error: {}: {e}", entry.name));
+                        Err(AuditError { package: entry.name.clone(), error: e })
+                    }
+                };
+                pb.inc(1);
+                Some(finding)
+            })
+            .collect()
+    });
+
+    pb.finish_and_clear();
+    for result in scanned {
+        match result {
+            Ok(finding) => findings.extend(finding),
+            Err(error) => errors.push(error),
+        }
+    }
+    findings.sort_by(|a, b| b.date.cmp(&a.date).then_with(|| a.package.cmp(&b.package)));
+    errors.sort_by(|a, b| a.package.cmp(&b.package));
+
+    if json {
+        let report = AuditReport { findings: &findings, errors: &errors };
+        let json_str = serde_json::to_string_pretty(&report).expect("Failed to serialize");
+        println!("{json_str}");
+    } else {
+        print_findings(&findings, &errors, entries.len());
+    }
+
+    if !errors.is_empty() {
+        eprintln!("Error: could not check {} packages (metadata fetch, clone or scan failed)", errors.len());
+        return 1;
+    }
+    if findings.is_empty() { 0 } else { 1 }
+}
+
+/// Split log entries by the RPC's answer: findings for packages it answered for
+/// without listing (removed from the AUR), entries it listed, and entries whose
+/// request failed, which are neither.
+fn classify<'a>(
+    entries: &'a [LogEntry],
+    batch: &MetadataBatch,
+) -> (Vec<AuditFinding>, Vec<&'a LogEntry>, Vec<&'a LogEntry>) {
+    let removed = entries
+        .iter()
+        .filter(|e| batch.is_absent(&e.name))
+        .map(|e| AuditFinding {
+            package: e.name.clone(),
+            installed_version: e.version.clone(),
+            date: e.date.clone(),
+            action: e.action.clone(),
+            reason: "removed-from-aur",
+            scan: None,
+        })
+        .collect();
+    let on_aur = entries.iter().filter(|e| batch.found.contains_key(&e.name)).collect();
+    let unknown = entries.iter().filter(|e| batch.failed.contains_key(&e.name)).collect();
+    (removed, on_aur, unknown)
+}
+
+fn print_findings(findings: &[AuditFinding], errors: &[AuditError], audited: usize) {
+    println!();
+    println!("{}", "=== traur audit results ===".bold());
+    println!("  Audited: {audited} foreign packages");
+    if !errors.is_empty() {
+        let names: Vec<&str> = errors.iter().map(|e| e.package.as_str()).collect();
+        println!("  {} {}", "Could not check:".red(), names.join(", "));
+    }
+
+    if findings.is_empty() {
+        println!();
+        if errors.is_empty() {
+            println!("{}", "No removed or malicious packages found.".green());
+        } else {
+            println!("No removed or malicious packages among the ones checked.");
+        }
+        return;
+    }
+
+    println!();
+    println!("{}", format!("=== {} flagged packages ===", findings.len()).bold());
+    for finding in findings {
+        println!();
+        match finding.reason {
+            "removed-from-aur" => println!(
+                "{} {} ({} {} on {})\n  {} No longer on the AUR — removed packages are often malware takedowns",
+                "traur:".bold(),
+                finding.package.bold(),
+                finding.action,
+                finding.installed_version,
+                finding.date,
+                "!!".red().bold(),
+            ),
+            _ => {
+                if let Some(ref scan) = finding.scan {
+                    output::print(scan, OutputFormat::Text, &RenderOptions::default());
+                }
+                println!("  {} {} on {}", finding.action, finding.installed_version, finding.date);
+            }
+        }
+    }
+}
+
+/// Parse install/upgrade events, keeping the latest event per package. A package
+/// whose latest event is a removal is no longer installed and is left out.
+/// Entries dated before `since` (YYYY-MM-DD) are skipped; that day itself is kept.
+fn parse_pacman_log(content: &str, since: Option<&str>) -> Vec<LogEntry> {
+    let mut latest: HashMap<String, LogEntry> = HashMap::new();
+    for line in content.lines() {
+        let Some(caps) = LOG_LINE_RE.captures(line) else {
+            continue;
+        };
+        let date = &caps[1];
+        if since.is_some_and(|s| date < s) {
+            continue;
+        }
+        if &caps[2] == "removed" {
+            latest.remove(&caps[3]);
+            continue;
+        }
+        latest.insert(
+            caps[3].to_string(),
+            LogEntry {
+                name: caps[3].to_string(),
+                version: caps[4].to_string(),
+                date: date.to_string(),
+                action: caps[2].to_string(),
+            },
+        );
+    }
+    let mut entries: Vec<LogEntry> = latest.into_values().collect();
+    entries.sort_by(|a, b| a.name.cmp(&b.name));
+    entries
+}
+
+/// Names of every package in the sync databases (`pacman -Slq`).
+/// Anything in the log but not here is foreign (AUR or local).
+fn sync_db_packages() -> Result<HashSet<String>, String> {
+    let output = Command::new("pacman")
+        .args(["-Slq"])
+        .output()
+        .map_err(|e| format!("Failed to run pacman: {e}"))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("pacman -Slq failed: {stderr}"));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|l| l.trim().to_string())
+        .filter(|l| !l.is_empty())
+        .collect())
+}
+
+fn is_iso_date(s: &str) -> bool {
+    let bytes = s.as_bytes();
+    bytes.len() == 10
+        && bytes.iter().enumerate().all(|(i, b)| match i {
+            4 | 7 => *b == b'-',
+            _ => b.is_ascii_digit(),
+        })
+}
+
+fn since_suffix(since: Option<&str>) -> String {
+    since.map(|s| format!(" since {s}")).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOG: &str = "\
+[2025-01-10T09:00:00+0100] [PACMAN] Running 'pacman -U foo-1.0-1-x86_64.pkg.tar.zst'
+[2025-01-10T09:00:01+0100] [ALPM] installed foo (1.0-1)
+[2025-01-12T09:00:01+0100] [ALPM] installed linux (6.12.1-1)
+[2025-02-01T12:30:00+0100] [ALPM] upgraded foo (1.0-1 -> 1.1-1)
+[2025-02-03T12:30:00+0100] [ALPM] removed bar (2.0-1)
+[2025-02-05T12:30:00+0100] [ALPM] downgraded baz-bin (3.0-1 -> 2.9-1)
+[2012-06-01 10:23] installed oldpkg (0.1-1)
+";
+
+    #[test]
+    fn parses_latest_event_per_package() {
+        let entries = parse_pacman_log(LOG, None);
+        let foo = entries.iter().find(|e| e.name == "foo").unwrap();
+        assert_eq!(foo.version, "1.1-1");
+        assert_eq!(foo.date, "2025-02-01");
+        assert_eq!(foo.action, "upgraded");
+        let baz = entries.iter().find(|e| e.name == "baz-bin").unwrap();
+        assert_eq!(baz.version, "2.9-1");
+    }
+
+    #[test]
+    fn ignores_removals_and_non_alpm_lines() {
+        let entries = parse_pacman_log(LOG, None);
+        assert!(entries.iter().all(|e| e.name != "bar"));
+        assert!(entries.iter().all(|e| !e.name.contains("pacman")));
+    }
+
+    #[test]
+    fn removed_packages_are_left_out() {
+        let log = "\
+[2025-03-01T10:00:00+0100] [ALPM] installed qux (1.0-1)
+[2025-03-02T10:00:00+0100] [ALPM] removed qux (1.0-1)
+[2025-03-01T10:00:00+0100] [ALPM] installed quux (1.0-1)
+[2025-03-02T10:00:00+0100] [ALPM] removed quux (1.0-1)
+[2025-03-03T10:00:00+0100] [ALPM] installed quux (1.1-1)
+";
+        let entries = parse_pacman_log(log, None);
+        assert_eq!(entries.iter().map(|e| e.name.as_str()).collect::<Vec<_>>(), ["quux"]);
+        assert_eq!(entries[0].version, "1.1-1");
+    }
+
+    #[test]
+    fn parses_legacy_format() {
+        let entries = parse_pacman_log(LOG, None);
+        assert!(entries.iter().any(|e| e.name == "oldpkg" && e.date == "2012-06-01"));
+    }
+
+    #[test]
+    fn since_filters_older_entries() {
+        let entries = parse_pacman_log(LOG, Some("2025-02-01"));
+        let names: Vec<&str> = entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, ["baz-bin", "foo"]);
+    }
+
+    #[test]
+    fn validates_iso_date() {
+        assert!(is_iso_date("2025-01-31"));
+        assert!(!is_iso_date("2025-1-31"));
+        assert!(!is_iso_date("31/01/2025"));
+    }
+
+    #[test]
+    fn json_report_lists_errors() {
+        let errors = [AuditError { package: "foo".into(), error: "git clone failed".into() }];
+        let json = serde_json::to_value(AuditReport { findings: &[], errors: &errors }).unwrap();
+        assert_eq!(json["findings"], serde_json::json!([]));
+        assert_eq!(json["errors"][0]["package"], "foo");
+        assert_eq!(json["errors"][0]["error"], "git clone failed");
+    }
+
+    #[test]
+    fn failed_fetch_is_not_reported_as_removed() {
+        let entries = parse_pacman_log(LOG, None);
+        let foo: crate::shared::models::AurPackage = serde_json::from_value(serde_json::json!({
+            "Name": "foo", "PackageBase": null, "URL": null, "NumVotes": 1, "Popularity": 0.0,
+            "OutOfDate": null, "Maintainer": null, "Submitter": null, "FirstSubmitted": 0,
+            "LastModified": 0, "License": null, "Description": null,
+        }))
+        .unwrap();
+        let mut batch = MetadataBatch::default();
+        batch.found.insert("foo".into(), foo);
+        // The chunk holding baz-bin and oldpkg failed; linux was answered and absent
+        batch.failed.insert("baz-bin".into(), "HTTP 429".into());
+        batch.failed.insert("oldpkg".into(), "HTTP 429".into());
+
+        let (removed, on_aur, unknown) = classify(&entries, &batch);
+        let removed: Vec<&str> = removed.iter().map(|f| f.package.as_str()).collect();
+        assert_eq!(removed, ["linux"]);
+        assert_eq!(on_aur.iter().map(|e| e.name.as_str()).collect::<Vec<_>>(), ["foo"]);
+        let mut unknown: Vec<&str> = unknown.iter().map(|e| e.name.as_str()).collect();
+        unknown.sort_unstable();
+        assert_eq!(unknown, ["baz-bin", "oldpkg"]);
+    }
+}
@@ -1,18 +1,31 @@
 use crate::coordinator;
+use crate::shared::age_cohorts::{self, AgeCohorts};
 use crate::shared::bulk::{
-    batch_fetch_metadata, clone_with_retry, prefetch_maintainer_packages, RPC_BATCH_SIZE,
+    batch_fetch_metadata, clone_with_retry, prefetch_maintainer_packages, prefetch_upstream,
+    RPC_BATCH_SIZE,
 };
+use crate::shared::checkpoint::Checkpoint;
 use crate::shared::models::MetaDumpPackage;
-use crate::shared::output;
+use crate::shared::output::{self, OutputFormat, RenderOptions};
+use crate::shared::profile::{self, Timing};
+use crate::shared::score_distribution::{self, ScoreDistribution};
 use crate::shared::scoring::{ScanResult, Tier};
 use colored::Colorize;
 use indicatif::{ProgressBar, ProgressStyle};
 use rayon::prelude::*;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::{Duration, Instant};
 
+/// How many of the slowest patterns the report lists.
+const SLOWEST_PATTERNS: usize = 10;
+
+/// How many of the most frequent signals the report lists.
+const TOP_SIGNALS: usize = 15;
+
 const META_DUMP_URL: &str = "https://aur.archlinux.org/packages-meta-v1.json.gz";
 
 struct BenchStats {
@@ -25,13 +38,47 @@ struct BenchStats {
     clone_time_us: u64,
     analysis_time_us: u64,
     scan_wall_time: Duration,
+    /// Results carried over from an interrupted run's checkpoint.
+    resumed: usize,
+    /// Cumulative analysis time per feature, slowest first.
+    feature_times: Vec<(&'static str, Timing)>,
+    /// Patterns with the most cumulative matching time, slowest first.
+    slow_patterns: Vec<(String, Timing)>,
+    /// Number of packages each signal fired on, most frequent first.
+    signal_hits: Vec<(String, usize)>,
     error_samples: Vec<(String, String)>,
+    /// Packages came from a local corpus: there was no prefetch or clone phase.
+    corpus: bool,
 }
 
-fn fetch_recent_packages(count: usize) -> Result<Vec<MetaDumpPackage>, String> {
+/// The `count` most recently updated package bases on the AUR. With `save_snapshots`,
+/// the vote levels by package age are measured on the whole dump and saved for
+/// metadata_analysis.
+fn fetch_recent_packages(count: usize, save_snapshots: bool) -> Result<Vec<MetaDumpPackage>, String> {
+    let mut packages = fetch_meta_dump()?;
+
+    if save_snapshots {
+        let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+        if let Err(e) = age_cohorts::save(&AgeCohorts::from_dump(&packages, now)) {
+            eprintln!("  Warning: failed to save age cohorts: {e}");
+        }
+    }
+    packages.sort_unstable_by_key(|p| std::cmp::Reverse(p.last_modified));
+
+    let mut seen = HashSet::new();
+    packages.retain(|p| seen.insert(p.package_base.clone()));
+
+    packages.truncate(count);
+    Ok(packages)
+}
+
+/// Every package on the AUR, from the daily metadata dump.
+pub fn fetch_meta_dump() -> Result<Vec<MetaDumpPackage>, String> {
     eprintln!("  Fetching AUR package metadata dump...");
 
-    let response = reqwest::blocking::get(META_DUMP_URL)
+    let response = crate::shared::runtime::blocking_client()
+        .get(META_DUMP_URL)
+        .send()
         .map_err(|e| format!("Failed to fetch metadata dump: {e}"))?;
 
     let decoder = flate2::read::GzDecoder::new(response);
@@ -40,25 +87,26 @@ fn fetch_recent_packages(count: usize) -> Result<Vec<MetaDumpPackage>, String> {
         .read_to_string(&mut json_str)
         .map_err(|e| format!("Failed to decompress metadata: {e}"))?;
 
-    let mut packages: Vec<MetaDumpPackage> = serde_json::from_str(&json_str)
-        .map_err(|e| format!("Failed to parse metadata JSON: {e}"))?;
-
-    packages.sort_unstable_by(|a, b| b.last_modified.cmp(&a.last_modified));
-
-    let mut seen = HashSet::new();
-    packages.retain(|p| seen.insert(p.package_base.clone()));
-
-    packages.truncate(count);
-    Ok(packages)
+    serde_json::from_str(&json_str).map_err(|e| format!("Failed to parse metadata JSON: {e}"))
 }
 
-pub fn run(count: usize, jobs: usize) -> i32 {
+pub fn run(count: usize, jobs: usize, resume: bool, save_snapshots: bool) -> i32 {
     let start = Instant::now();
+    profile::enable();
+    let config = crate::shared::config::load_config();
+
+    let (checkpoint, done) = match Checkpoint::open("bench", resume, &crate::shared::config::fingerprint(&config)) {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            return 1;
+        }
+    };
 
     // Phase 1: prefetch all metadata
     eprintln!("{}", "Phase 1: Prefetching metadata...".bold());
 
-    let packages = match fetch_recent_packages(count) {
+    let mut packages = match fetch_recent_packages(count, save_snapshots) {
         Ok(p) => p,
         Err(e) => {
             eprintln!("Error: {e}");
@@ -69,27 +117,38 @@ pub fn run(count: usize, jobs: usize) -> i32 {
     let total = packages.len();
     eprintln!("  Selected {} packages", total);
 
+    // Results of packages no longer in the selection (a newer dump) are dropped.
+    let selected: HashSet<&str> = packages.iter().map(|p| p.name.as_str()).collect();
+    let done: Vec<ScanResult> = done.into_iter().filter(|r| selected.contains(r.package.as_str())).collect();
+    let done_names: HashSet<String> = done.iter().map(|r| r.package.clone()).collect();
+    packages.retain(|p| !done_names.contains(&p.name));
+    let resumed = done.len();
+    if resumed > 0 {
+        eprintln!("  Resuming: {resumed} already scanned, {} to go", packages.len());
+    }
+
     let names: Vec<String> = packages.iter().map(|p| p.name.clone()).collect();
 
     eprintln!("  Batch-fetching package metadata ({} RPC calls)...",
-        (names.len() + RPC_BATCH_SIZE - 1) / RPC_BATCH_SIZE);
+        names.len().div_ceil(RPC_BATCH_SIZE));
     let metadata = batch_fetch_metadata(&names);
     eprintln!("  Got metadata for {} packages", metadata.len());
 
-    let maintainer_packages = prefetch_maintainer_packages(&metadata);
+    let maintainer_packages = prefetch_maintainer_packages(&metadata, &config);
+    let upstream = prefetch_upstream(&metadata, &config);
 
     let prefetch_time = start.elapsed();
     eprintln!("  Prefetch done in {:.1}s\n", prefetch_time.as_secs_f64());
 
     // Phase 2: parallel git clone + analysis
-    eprintln!("{}", format!("Phase 2: Scanning {} packages ({} threads)...", total, jobs).bold());
+    eprintln!("{}", format!("Phase 2: Scanning {} packages ({} threads)...", packages.len(), jobs).bold());
 
     let pool = rayon::ThreadPoolBuilder::new()
         .num_threads(jobs)
         .build()
         .expect("Failed to build thread pool");
 
-    let pb = ProgressBar::new(total as u64);
+    let pb = ProgressBar::new(packages.len() as u64);
     pb.set_style(
         ProgressStyle::default_bar()
             .template("[{elapsed_precise}] {bar:40.cyan/blue} {pos}/{len} ({per_sec})")
@@ -103,8 +162,20 @@ pub fn run(count: usize, jobs: usize) -> i32 {
     let tier_counts: [AtomicU64; 5] = std::array::from_fn(|_| AtomicU64::new(0));
     let clone_time_us = AtomicU64::new(0);
     let analysis_time_us = AtomicU64::new(0);
-    let error_samples = std::sync::Mutex::new(Vec::<(String, String)>::new());
-    let flagged = std::sync::Mutex::new(Vec::<ScanResult>::new());
+    let error_samples = Mutex::new(Vec::<(String, String)>::new());
+    let flagged = Mutex::new(Vec::<ScanResult>::new());
+    let scores = Mutex::new(Vec::<u32>::new());
+    let hits = Mutex::new(HashMap::<String, usize>::new());
+
+    let record = |scan: ScanResult| {
+        scores.lock().unwrap().push(scan.score);
+        count_hits(&hits, &scan);
+        tier_counts[tier_to_index(scan.tier)].fetch_add(1, Ordering::Relaxed);
+        if scan.tier >= Tier::Sketchy {
+            flagged.lock().unwrap().push(scan);
+        }
+    };
+    done.into_iter().for_each(record);
 
     pool.install(|| {
         packages.par_iter().for_each(|pkg| {
@@ -117,16 +188,18 @@ pub fn run(count: usize, jobs: usize) -> i32 {
                     .and_then(|m| maintainer_packages.get(m))
                     .cloned()
                     .unwrap_or_default();
+                let upstream_data = upstream.get(name).cloned().unwrap_or_default();
 
                 // Time clone separately from analysis
+                let deadline = coordinator::Deadline::start(&config);
                 let t0 = Instant::now();
-                let ctx = clone_with_retry(name, meta, maint_pkgs);
+                let ctx = clone_with_retry(name, meta, maint_pkgs, upstream_data, deadline, &config);
                 clone_time_us.fetch_add(t0.elapsed().as_micros() as u64, Ordering::Relaxed);
 
                 match ctx {
                     Ok(ctx) => {
                         let t1 = Instant::now();
-                        let scan = coordinator::run_analysis(&ctx);
+                        let scan = coordinator::run_analysis_within(&ctx, &config, deadline);
                         analysis_time_us.fetch_add(t1.elapsed().as_micros() as u64, Ordering::Relaxed);
                         Ok(scan)
                     }
@@ -138,12 +211,8 @@ pub fn run(count: usize, jobs: usize) -> i32 {
 
             match result {
                 Ok(scan) => {
-                    let idx = tier_to_index(scan.tier);
-                    tier_counts[idx].fetch_add(1, Ordering::Relaxed);
-
-                    if scan.tier >= Tier::Sketchy {
-                        flagged.lock().unwrap().push(scan);
-                    }
+                    checkpoint.record(&scan);
+                    record(scan);
                 }
                 Err(e) => {
                     error_count.fetch_add(1, Ordering::Relaxed);
@@ -172,26 +241,190 @@ pub fn run(count: usize, jobs: usize) -> i32 {
         clone_time_us: clone_time_us.load(Ordering::Relaxed),
         analysis_time_us: analysis_time_us.load(Ordering::Relaxed),
         scan_wall_time,
+        resumed,
+        feature_times: profile::feature_times(),
+        slow_patterns: profile::slowest_patterns(SLOWEST_PATTERNS),
+        signal_hits: top_hits(hits.into_inner().unwrap()),
         error_samples: error_samples.into_inner().unwrap(),
+        corpus: false,
     };
 
     print_report(&stats);
 
+    // Single-package scans rank their score against this run.
+    let scores = scores.into_inner().unwrap();
+    if save_snapshots
+        && !scores.is_empty()
+        && let Err(e) = score_distribution::save(&ScoreDistribution::from_scores(scores))
+    {
+        eprintln!("  Warning: failed to save score distribution: {e}");
+    }
+
+    // Keep the checkpoint while some packages failed, so --resume retries just those.
+    if stats.errors == 0 {
+        checkpoint.finish();
+    } else {
+        eprintln!("\n  {} packages failed; run again with --resume to retry only those", stats.errors);
+    }
+
     // Print detailed output for HIGH/CRITICAL/MALICIOUS packages
     let mut flagged = flagged.into_inner().unwrap();
     if !flagged.is_empty() {
-        flagged.sort_by(|a, b| a.score.cmp(&b.score));
+        flagged.sort_by_key(|a| a.score);
         println!();
         println!("{}", format!("=== {} flagged packages (SKETCHY+) ===", flagged.len()).bold());
         for result in &flagged {
             println!();
-            output::print_text(result, false);
+            output::print(result, OutputFormat::Text, &RenderOptions::default());
         }
     }
 
+    // A bench clones thousands of repos; trim the cache back to its configured size.
+    let gc = crate::shared::cache::gc_git_cache(config.cache.max_size_bytes(), None);
+    if gc.removed > 0 {
+        eprintln!(
+            "\n  Git cache: evicted {} clones ({} MiB), {} MiB kept",
+            gc.removed,
+            gc.freed_bytes / (1024 * 1024),
+            gc.kept_bytes / (1024 * 1024)
+        );
+    }
+
     0
 }
 
+/// Benchmark analysis alone on the package directories under `root`, offline: no
+/// metadata, clone or upstream phase, so runs on the same corpus are comparable (in
+/// CI, or before and after a pattern change).
+pub fn run_corpus(root: &Path, jobs: usize) -> i32 {
+    let start = Instant::now();
+    profile::enable();
+    let config = crate::shared::config::load_config();
+
+    let dirs = find_package_dirs(root);
+    if dirs.is_empty() {
+        eprintln!("Error: no PKGBUILD found under {}", root.display());
+        return 1;
+    }
+    eprintln!("{}", format!("Scanning {} packages from {} ({} threads)...", dirs.len(), root.display(), jobs).bold());
+
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(jobs)
+        .build()
+        .expect("Failed to build thread pool");
+
+    let pb = ProgressBar::new(dirs.len() as u64);
+    pb.set_style(
+        ProgressStyle::default_bar()
+            .template("[{elapsed_precise}] {bar:40.cyan/blue} {pos}/{len} ({per_sec})")
+            .unwrap()
+            .progress_chars("##-"),
+    );
+
+    let scan_start = Instant::now();
+    let error_count = AtomicU64::new(0);
+    let tier_counts: [AtomicU64; 5] = std::array::from_fn(|_| AtomicU64::new(0));
+    let analysis_time_us = AtomicU64::new(0);
+    let error_samples = Mutex::new(Vec::<(String, String)>::new());
+    let hits = Mutex::new(HashMap::<String, usize>::new());
+
+    pool.install(|| {
+        dirs.par_iter().for_each(|dir| {
+            let t0 = Instant::now();
+            match coordinator::scan_local_dir(dir, &config) {
+                Ok(scan) => {
+                    analysis_time_us.fetch_add(t0.elapsed().as_micros() as u64, Ordering::Relaxed);
+                    count_hits(&hits, &scan);
+                    tier_counts[tier_to_index(scan.tier)].fetch_add(1, Ordering::Relaxed);
+                }
+                Err(e) => {
+                    error_count.fetch_add(1, Ordering::Relaxed);
+                    let mut samples = error_samples.lock().unwrap();
+                    if samples.len() < 10 {
+                        let name = dir.strip_prefix(root).unwrap_or(dir).display().to_string();
+                        samples.push((name, e));
+                    }
+                }
+            }
+            pb.inc(1);
+        });
+    });
+
+    pb.finish_and_clear();
+    let scan_wall_time = scan_start.elapsed();
+
+    let stats = BenchStats {
+        total: dirs.len(),
+        scanned: dirs.len() - error_count.load(Ordering::Relaxed) as usize,
+        errors: error_count.load(Ordering::Relaxed) as usize,
+        tier_counts: std::array::from_fn(|i| tier_counts[i].load(Ordering::Relaxed) as usize),
+        total_time: start.elapsed(),
+        prefetch_time: Duration::ZERO,
+        clone_time_us: 0,
+        analysis_time_us: analysis_time_us.load(Ordering::Relaxed),
+        scan_wall_time,
+        resumed: 0,
+        feature_times: profile::feature_times(),
+        slow_patterns: profile::slowest_patterns(SLOWEST_PATTERNS),
+        signal_hits: top_hits(hits.into_inner().unwrap()),
+        error_samples: error_samples.into_inner().unwrap(),
+        corpus: true,
+    };
+
+    print_report(&stats);
+    0
+}
+
+/// Directories under `root` (itself included) that hold a PKGBUILD, sorted. Hidden
+/// directories such as `.git` are skipped, and a package directory is not searched
+/// further.
+fn find_package_dirs(root: &Path) -> Vec<PathBuf> {
+    let mut found = Vec::new();
+    let mut stack = vec![root.to_path_buf()];
+    while let Some(dir) = stack.pop() {
+        if dir.join("PKGBUILD").is_file() {
+            found.push(dir);
+            continue;
+        }
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let hidden = entry.file_name().to_string_lossy().starts_with('.');
+            if !hidden && entry.file_type().is_ok_and(|t| t.is_dir()) {
+                stack.push(entry.path());
+            }
+        }
+    }
+    found.sort();
+    found
+}
+
+/// Count each signal of `scan` once toward its package total.
+fn count_hits(hits: &Mutex<HashMap<String, usize>>, scan: &ScanResult) {
+    let ids: HashSet<&str> = scan.signals.iter().map(|s| s.id.as_str()).collect();
+    let mut hits = hits.lock().unwrap();
+    for id in ids {
+        *hits.entry(id.to_string()).or_default() += 1;
+    }
+}
+
+/// The `TOP_SIGNALS` most frequent signals, ties by ID.
+fn top_hits(hits: HashMap<String, usize>) -> Vec<(String, usize)> {
+    let mut hits: Vec<(String, usize)> = hits.into_iter().collect();
+    hits.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    hits.truncate(TOP_SIGNALS);
+    hits
+}
+
+fn avg_ms(timing: &Timing) -> f64 {
+    if timing.calls == 0 {
+        0.0
+    } else {
+        timing.total.as_secs_f64() * 1_000.0 / timing.calls as f64
+    }
+}
+
 fn tier_to_index(tier: Tier) -> usize {
     match tier {
         Tier::Trusted => 0,
@@ -211,15 +444,17 @@ fn print_report(stats: &BenchStats) {
         }
     };
 
+    // Timings cover only the packages scanned in this run, not those resumed.
+    let fresh = stats.scanned - stats.resumed;
     let clone_secs = stats.clone_time_us as f64 / 1_000_000.0;
     let analysis_secs = stats.analysis_time_us as f64 / 1_000_000.0;
-    let avg_clone_ms = if stats.scanned > 0 {
-        stats.clone_time_us as f64 / stats.scanned as f64 / 1_000.0
+    let avg_clone_ms = if fresh > 0 {
+        stats.clone_time_us as f64 / fresh as f64 / 1_000.0
     } else {
         0.0
     };
-    let avg_analysis_ms = if stats.scanned > 0 {
-        stats.analysis_time_us as f64 / stats.scanned as f64 / 1_000.0
+    let avg_analysis_ms = if fresh > 0 {
+        stats.analysis_time_us as f64 / fresh as f64 / 1_000.0
     } else {
         0.0
     };
@@ -228,19 +463,27 @@ fn print_report(stats: &BenchStats) {
     println!("{}", "=== traur bench results ===".bold());
     println!();
     println!(
-        "  Packages:    {} requested, {} scanned, {} errors",
-        stats.total, stats.scanned, stats.errors
+        "  Packages:    {} {}, {} scanned, {} errors",
+        stats.total,
+        if stats.corpus { "found" } else { "requested" },
+        stats.scanned,
+        stats.errors
     );
+    if stats.resumed > 0 {
+        println!("               {} of them resumed from a checkpoint", stats.resumed);
+    }
     println!();
     println!("{}", "  Timing:".bold());
-    println!(
-        "    Prefetch:    {:>7.1}s  (metadata + maintainer data)",
-        stats.prefetch_time.as_secs_f64()
-    );
-    println!(
-        "    Git clone:   {:>7.1}s cumulative, {:>7.1}ms avg/pkg",
-        clone_secs, avg_clone_ms
-    );
+    if !stats.corpus {
+        println!(
+            "    Prefetch:    {:>7.1}s  (metadata + maintainer data)",
+            stats.prefetch_time.as_secs_f64()
+        );
+        println!(
+            "    Git clone:   {:>7.1}s cumulative, {:>7.1}ms avg/pkg",
+            clone_secs, avg_clone_ms
+        );
+    }
     println!(
         "    Analysis:    {:>7.1}s cumulative, {:>7.1}ms avg/pkg",
         analysis_secs, avg_analysis_ms
@@ -255,8 +498,40 @@ fn print_report(stats: &BenchStats) {
     );
     println!(
         "    Throughput:  {:>7.1} pkg/s",
-        stats.scanned as f64 / stats.scan_wall_time.as_secs_f64()
+        fresh as f64 / stats.scan_wall_time.as_secs_f64()
     );
+    if !stats.feature_times.is_empty() {
+        println!();
+        println!("{}", "  Analysis time by feature:".bold());
+        for (name, timing) in &stats.feature_times {
+            println!(
+                "    {:<28} {:>7.1}s cumulative, {:>7.2}ms avg/pkg",
+                name,
+                timing.total.as_secs_f64(),
+                avg_ms(timing)
+            );
+        }
+    }
+    if !stats.slow_patterns.is_empty() {
+        println!();
+        println!("{}", format!("  Slowest {} patterns:", stats.slow_patterns.len()).bold());
+        for (id, timing) in &stats.slow_patterns {
+            println!(
+                "    {:<28} {:>7.2}s cumulative, {:>7.3}ms avg/call, {} calls",
+                id,
+                timing.total.as_secs_f64(),
+                avg_ms(timing),
+                timing.calls
+            );
+        }
+    }
+    if !stats.signal_hits.is_empty() {
+        println!();
+        println!("{}", format!("  Top {} signals:", stats.signal_hits.len()).bold());
+        for (id, count) in &stats.signal_hits {
+            println!("    {:<28} {:>5}  ({:.1}%)", id, count, pct(*count));
+        }
+    }
     println!();
     println!("{}", "  Trust distribution:".bold());
     println!("    TRUSTED:    {:>5}  ({:.1}%)", stats.tier_counts[0], pct(stats.tier_counts[0]));
@@ -273,3 +548,48 @@ fn print_report(stats: &BenchStats) {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn corpus_finds_package_dirs() {
+        let root = std::env::temp_dir().join(format!("traur-bench-corpus-{}", std::process::id()));
+        for dir in ["a/foo", "a/foo/nested", "bar", ".git/baz", "empty"] {
+            std::fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        for dir in ["a/foo", "a/foo/nested", "bar", ".git/baz"] {
+            std::fs::write(root.join(dir).join("PKGBUILD"), "pkgname=x\n").unwrap();
+        }
+
+        let found = find_package_dirs(&root);
+        std::fs::remove_dir_all(&root).unwrap();
+        assert_eq!(found, vec![root.join("a/foo"), root.join("bar")]);
+    }
+
+    #[test]
+    fn hits_count_packages_not_signals() {
+        let hits = Mutex::new(HashMap::new());
+        let scan = |ids: &[&str]| {
+            let signals: Vec<_> = ids
+                .iter()
+                .map(|id| crate::shared::scoring::Signal {
+                    id: id.to_string(),
+                    category: crate::shared::scoring::SignalCategory::Pkgbuild,
+                    points: 10,
+                    description: String::new(),
+                    is_override_gate: false,
+                    matched_line: None,
+                    location: None,
+                    emitted_by: String::new(),
+                })
+                .collect();
+            crate::shared::scoring::compute_score("pkg", &signals)
+        };
+        count_hits(&hits, &scan(&["P-A", "P-B", "P-A"]));
+        count_hits(&hits, &scan(&["P-B"]));
+        let top = top_hits(hits.into_inner().unwrap());
+        assert_eq!(top, vec![("P-B".to_string(), 2), ("P-A".to_string(), 1)]);
+    }
+}
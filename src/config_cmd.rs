@@ -0,0 +1,611 @@
+use crate::features::FeatureRegistry;
+use crate::shared::config::{self, Config};
+use crate::shared::{binary_repo, scoring, signal_registry};
+use clap::Subcommand;
+
+#[derive(Subcommand)]
+pub enum ConfigAction {
+    /// Show every setting with its effective value
+    List,
+    /// Print one setting
+    Get {
+        /// Setting key, e.g. scan.jobs or features.github_stars
+        key: String,
+    },
+    /// Change a setting in the user config (list settings take one or more values and replace the list)
+    Set {
+        /// Setting key, e.g. scan.jobs or features.github_stars
+        key: String,
+
+        #[arg(required = true, num_args = 1..)]
+        values: Vec<String>,
+    },
+    /// Reset a setting in the user config to its default
+    Unset {
+        /// Setting key, e.g. scan.timeout
+        key: String,
+    },
+}
+
+/// Fixed settings keys and what they control. `features.<name>` and
+/// `features.timeouts.<name>` are added per registered feature.
+const KEYS: &[(&str, &str)] = &[
+    ("thresholds.block_at", "Tier at which the hook blocks"),
+    ("thresholds.warn_at", "Tier at which the hook warns"),
+    ("thresholds.incomplete_as_sketchy", "Treat scans missing the PKGBUILD, git log or AUR metadata as at least SKETCHY"),
+    ("scoring.newness_signals", "Signals that only mean a package is new; packages with nothing else are capped at OK"),
+    ("scoring.demoted_gates", "Override gates scored as ordinary signals instead of forcing MALICIOUS (see traur signals --gates)"),
+    ("scan.jobs", "Concurrent scan threads when --jobs is not given"),
+    ("scan.timeout", "Seconds one package may take end to end"),
+    ("scan.exclude", ".gitignore-style patterns for repo files that are not analyzed"),
+    ("scan.max_file_kb", "KiB of a PKGBUILD or install script analyzed; larger files keep their head and function bodies"),
+    ("scan.deep", "Also download the upstream GitHub tarball from source=() and analyze its top-level build scripts"),
+    ("scan.bulk_comments", "Scrape AUR comments in bulk scans (--all, audit, top, the hook)"),
+    ("cache.maintainer_ttl", "Seconds a cached maintainer package list is reused"),
+    ("cache.comments_ttl", "Seconds a cached AUR comments snapshot is reused"),
+    ("cache.max_size_mb", "Git clone cache size limit in MiB (traur cache gc, after bench)"),
+    ("known_good.url", "Where to fetch the known-good PKGBUILD hash database"),
+    ("known_good.max_age_hours", "Hours the cached known-good database is used before refetching"),
+    ("geoip.asn_db", "GeoLite2-ASN database for ASN details on source-URL signals"),
+    ("geoip.country_db", "GeoLite2-Country/City database for country details on source-URL signals"),
+    ("threat_intel.urlhaus", "Local URLhaus dump (URL list, CSV or hostfile) that source URLs are checked against"),
+    ("threat_intel.spamhaus_dbl", "Local Spamhaus DBL domain list that source hosts are checked against"),
+    ("trusted_domains", "Extra source domains exempt from domain-mismatch and plain-HTTP signals"),
+    ("names.protected", "Extra product names guarded against impersonation and typosquats"),
+    ("report.endpoint", "URL that receives `traur report-fp` reports as JSON instead of a GitHub issue"),
+    ("policy.trusted_keys", "Ed25519 public keys (hex) that `traur policy import` requires a signature from"),
+    ("whitelist.packages", "Packages that are never scanned"),
+    ("ignored.signals", "Signal IDs excluded from scoring and output"),
+    ("ignored.categories", "Signal categories excluded from scoring and output"),
+    ("hook.skip_unchanged", "Skip hook scans of packages whose AUR repo has not changed since the hook last let them through"),
+    ("hook.remember_approvals", "Whitelist packages approved at the hook prompt, pinned to their PKGBUILD hash"),
+    ("maintainer.aur_session", "AURSID cookie of a logged-in AUR session; enables maintainer account page lookups"),
+    ("http.proxy", "Proxy URL for all requests (default: HTTPS_PROXY/HTTP_PROXY from the environment)"),
+    ("http.ca_file", "PEM file of extra root certificates to trust"),
+    ("http.timeout", "Seconds a single HTTP request may take"),
+    ("http.user_agent", "User-Agent header sent with every request"),
+    ("http.aur_web_qps", "Requests per second to AUR web pages (comments, accounts); 0 disables the throttle"),
+    ("notify.desktop", "Send desktop notifications"),
+    ("notify.webhook_url", "Webhook URL for JSON notifications"),
+    ("notify.ntfy_url", "ntfy topic URL for notifications"),
+    ("notify.min_tier", "Lowest tier that triggers a notification"),
+    ("notify.template", "Notification message template"),
+];
+
+/// A parsed settings key.
+#[derive(Debug, PartialEq)]
+enum Key {
+    Fixed(&'static str),
+    Feature(String),
+    FeatureTimeout(String),
+    /// `repos.<name>.url`; any repo name is accepted.
+    RepoUrl(String),
+}
+
+pub fn run(action: ConfigAction) -> i32 {
+    let features: Vec<&str> = FeatureRegistry::builtin().names().collect();
+    let result = match action {
+        ConfigAction::List => {
+            let config = config::load_config();
+            for key in all_keys(&features) {
+                println!("{key} = {}", get(&config, &parse_key(&key, &features).unwrap()));
+            }
+            Ok(())
+        }
+        ConfigAction::Get { key } => parse_key(&key, &features).map(|key| {
+            println!("{}", get(&config::load_config(), &key));
+        }),
+        ConfigAction::Set { key, values } => parse_key(&key, &features).and_then(|parsed| {
+            let mut user = config::load_user_config();
+            set(&mut user, &parsed, &values)?;
+            config::save_config(&user)?;
+            eprintln!("Set {key} = {}", get(&user, &parsed));
+            report_saved(&parsed, user);
+            Ok(())
+        }),
+        ConfigAction::Unset { key } => parse_key(&key, &features).and_then(|parsed| {
+            let mut user = config::load_user_config();
+            unset(&mut user, &parsed);
+            config::save_config(&user)?;
+            eprintln!("Reset {key} to {}", get(&user, &parsed));
+            report_saved(&parsed, user);
+            Ok(())
+        }),
+    };
+
+    match result {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            1
+        }
+    }
+}
+
+fn report_saved(key: &Key, user: Config) {
+    eprintln!("  Saved to {}", config::config_path().display());
+    if let Some(system) = config::load_system_config()
+        && let Some(effective) = system_override(system, user, key)
+    {
+        eprintln!(
+            "  Note: merged with {}, the effective value is {effective}",
+            config::SYSTEM_CONFIG_PATH
+        );
+    }
+}
+
+/// The effective value of `key` when `merge_configs` gives it a different value than
+/// the user config holds, i.e. where the system config wins or adds to it.
+fn system_override(system: Config, user: Config, key: &Key) -> Option<String> {
+    let own = get(&user, key);
+    let effective = get(&config::merge_configs(system, user), key);
+    (effective != own).then_some(effective)
+}
+
+fn all_keys(features: &[&str]) -> Vec<String> {
+    let mut keys: Vec<String> = KEYS.iter().map(|(k, _)| k.to_string()).collect();
+    keys.extend(features.iter().map(|f| format!("features.{f}")));
+    keys.extend(features.iter().map(|f| format!("features.timeouts.{f}")));
+    keys.extend(binary_repo::repo_names(&config::load_config()).into_iter().map(|r| format!("repos.{r}.url")));
+    keys
+}
+
+/// Resolve a key, or explain why it isn't one (with the closest match for typos).
+fn parse_key(key: &str, features: &[&str]) -> Result<Key, String> {
+    if let Some(&(fixed, _)) = KEYS.iter().find(|(k, _)| *k == key) {
+        return Ok(Key::Fixed(fixed));
+    }
+    if let Some(name) = key.strip_prefix("features.timeouts.")
+        && features.contains(&name)
+    {
+        return Ok(Key::FeatureTimeout(name.to_string()));
+    }
+    if let Some(name) = key.strip_prefix("features.")
+        && features.contains(&name)
+    {
+        return Ok(Key::Feature(name.to_string()));
+    }
+    if let Some(name) = key.strip_prefix("repos.").and_then(|k| k.strip_suffix(".url"))
+        && !name.is_empty()
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || "-_".contains(c))
+    {
+        return Ok(Key::RepoUrl(name.to_string()));
+    }
+
+    let mut message = format!("Unknown config key: {key}");
+    if let Some(closest) = all_keys(features)
+        .into_iter()
+        .map(|k| (strsim::levenshtein(key, &k), k))
+        .filter(|(distance, _)| *distance <= 3)
+        .min()
+    {
+        message.push_str(&format!("\nDid you mean '{}'?", closest.1));
+    }
+    message.push_str("\nUse 'traur config list' to see all keys.");
+    Err(message)
+}
+
+/// Effective value of a key, as shown by `get` and `list`.
+fn get(config: &Config, key: &Key) -> String {
+    match key {
+        Key::Feature(name) => config.features.is_enabled(name).to_string(),
+        Key::FeatureTimeout(name) => optional(config.features.timeouts.get(name)),
+        Key::RepoUrl(name) => optional(binary_repo::repo_url(config, name).as_ref()),
+        Key::Fixed(key) => match *key {
+            "thresholds.block_at" => config.thresholds.block_at.clone(),
+            "thresholds.warn_at" => config.thresholds.warn_at.clone(),
+            "thresholds.incomplete_as_sketchy" => config.thresholds.incomplete_as_sketchy.to_string(),
+            "scoring.newness_signals" => list(&config.scoring.newness_signals().iter().map(|s| s.to_string()).collect::<Vec<_>>()),
+            "scoring.demoted_gates" => list(&config.scoring.demoted_gates),
+            "scan.jobs" => config.scan.jobs().to_string(),
+            "scan.timeout" => optional(config.scan.timeout.as_ref()),
+            "scan.exclude" => list(&config.scan.exclude),
+            "scan.max_file_kb" => (config.scan.max_file_bytes() / 1024).to_string(),
+            "scan.deep" => config.scan.deep.to_string(),
+            "scan.bulk_comments" => config.scan.bulk_comments.to_string(),
+            "cache.maintainer_ttl" => config.cache.maintainer_ttl().as_secs().to_string(),
+            "cache.comments_ttl" => config.cache.comments_ttl().as_secs().to_string(),
+            "cache.max_size_mb" => (config.cache.max_size_bytes() / (1024 * 1024)).to_string(),
+            "known_good.url" => optional(config.known_good.url.as_ref()),
+            "known_good.max_age_hours" => (config.known_good.max_age().as_secs() / 3600).to_string(),
+            "geoip.asn_db" => optional(config.geoip.asn_db.as_ref()),
+            "geoip.country_db" => optional(config.geoip.country_db.as_ref()),
+            "threat_intel.urlhaus" => optional(config.threat_intel.urlhaus.as_ref()),
+            "threat_intel.spamhaus_dbl" => optional(config.threat_intel.spamhaus_dbl.as_ref()),
+            "trusted_domains" => list(&config.trusted_domains),
+            "names.protected" => list(&config.names.protected),
+            "report.endpoint" => optional(config.report.endpoint.as_ref()),
+            "policy.trusted_keys" => list(&config.policy.trusted_keys),
+            "whitelist.packages" => list(&config.whitelist.packages),
+            "ignored.signals" => list(&config.ignored.signals),
+            "ignored.categories" => list(&config.ignored.categories),
+            "hook.skip_unchanged" => config.hook.skip_unchanged.to_string(),
+            "hook.remember_approvals" => config.hook.remember_approvals.to_string(),
+            "maintainer.aur_session" => optional(config.maintainer.aur_session.as_ref()),
+            "http.proxy" => optional(config.http.proxy.as_ref()),
+            "http.ca_file" => optional(config.http.ca_file.as_ref()),
+            "http.timeout" => config.http.timeout().as_secs().to_string(),
+            "http.user_agent" => config.http.user_agent().to_string(),
+            "http.aur_web_qps" => config.http.aur_web_qps().to_string(),
+            "notify.desktop" => config.notify.desktop.to_string(),
+            "notify.webhook_url" => optional(config.notify.webhook_url.as_ref()),
+            "notify.ntfy_url" => optional(config.notify.ntfy_url.as_ref()),
+            "notify.min_tier" => config.notify.min_tier.clone(),
+            "notify.template" => optional(config.notify.template.as_ref()),
+            _ => unreachable!("unhandled config key {key}"),
+        },
+    }
+}
+
+fn optional(value: Option<&impl ToString>) -> String {
+    value.map(ToString::to_string).unwrap_or_else(|| "(unset)".to_string())
+}
+
+fn list(values: &[String]) -> String {
+    format!("[{}]", values.join(", "))
+}
+
+/// Validate `values` for `key` and store them.
+fn set(config: &mut Config, key: &Key, values: &[String]) -> Result<(), String> {
+    let is_list = matches!(
+        key,
+        Key::Fixed(
+            "scan.exclude"
+                | "scoring.newness_signals"
+                | "scoring.demoted_gates"
+                | "trusted_domains"
+                | "names.protected"
+                | "policy.trusted_keys"
+                | "whitelist.packages"
+                | "ignored.signals"
+                | "ignored.categories"
+        )
+    );
+    if !is_list && values.len() != 1 {
+        return Err("This setting takes exactly one value".to_string());
+    }
+    let value = values[0].as_str();
+
+    match key {
+        Key::Feature(name) => {
+            config.features.enabled.insert(name.clone(), parse_bool(value)?);
+        }
+        Key::FeatureTimeout(name) => {
+            config.features.timeouts.insert(name.clone(), parse_secs(value)?);
+        }
+        Key::RepoUrl(name) => {
+            config.repos.insert(name.clone(), config::RepoConfig { url: parse_url(value)? });
+        }
+        Key::Fixed(key) => match *key {
+            "thresholds.block_at" => config.thresholds.block_at = parse_tier(value)?,
+            "thresholds.warn_at" => config.thresholds.warn_at = parse_tier(value)?,
+            "thresholds.incomplete_as_sketchy" => config.thresholds.incomplete_as_sketchy = parse_bool(value)?,
+            "scan.jobs" => {
+                let jobs: usize = value
+                    .parse()
+                    .ok()
+                    .filter(|&n| n > 0)
+                    .ok_or_else(|| format!("Invalid thread count: {value} (expected a positive integer)"))?;
+                config.scan.jobs = Some(jobs);
+            }
+            "scoring.newness_signals" => {
+                if let Some(bad) = values.iter().find(|id| !signal_registry::is_known_signal(id)) {
+                    return Err(format!(
+                        "Unknown signal: {bad}\nUse 'traur signals' to list available signal IDs."
+                    ));
+                }
+                config.scoring.newness_signals = Some(sorted(values));
+            }
+            "scoring.demoted_gates" => {
+                let gates = signal_registry::all_signal_definitions();
+                let is_gate = |id: &str| {
+                    let base = id.strip_prefix("IS-").unwrap_or(id);
+                    gates.iter().any(|d| d.is_override_gate && d.id == base)
+                };
+                if let Some(bad) = values.iter().find(|id| !is_gate(id)) {
+                    return Err(format!(
+                        "Not an override gate: {bad}\nUse 'traur signals --gates' to list them."
+                    ));
+                }
+                config.scoring.demoted_gates = sorted(values);
+            }
+            "scan.timeout" => config.scan.timeout = Some(parse_secs(value)?),
+            "scan.exclude" => config.scan.exclude = values.to_vec(),
+            "scan.max_file_kb" => {
+                let kb = value
+                    .parse()
+                    .ok()
+                    .filter(|&kb| kb > 0)
+                    .ok_or_else(|| format!("Invalid size: {value} (expected KiB as a whole number above 0)"))?;
+                config.scan.max_file_kb = Some(kb);
+            }
+            "scan.deep" => config.scan.deep = parse_bool(value)?,
+            "scan.bulk_comments" => config.scan.bulk_comments = parse_bool(value)?,
+            "cache.maintainer_ttl" => config.cache.maintainer_ttl = Some(parse_secs(value)?),
+            "cache.comments_ttl" => config.cache.comments_ttl = Some(parse_secs(value)?),
+            "cache.max_size_mb" => {
+                let mb = value
+                    .parse()
+                    .map_err(|_| format!("Invalid size: {value} (expected MiB as a whole number)"))?;
+                config.cache.max_size_mb = Some(mb);
+            }
+            "known_good.url" => config.known_good.url = Some(parse_url(value)?),
+            "known_good.max_age_hours" => {
+                let hours = value
+                    .parse()
+                    .map_err(|_| format!("Invalid age: {value} (expected hours as a whole number)"))?;
+                config.known_good.max_age_hours = Some(hours);
+            }
+            "geoip.asn_db" => config.geoip.asn_db = Some(parse_file(value)?),
+            "geoip.country_db" => config.geoip.country_db = Some(parse_file(value)?),
+            "threat_intel.urlhaus" => config.threat_intel.urlhaus = Some(parse_file(value)?),
+            "threat_intel.spamhaus_dbl" => config.threat_intel.spamhaus_dbl = Some(parse_file(value)?),
+            "trusted_domains" => {
+                if let Some(bad) = values.iter().find(|d| d.contains("://") || d.contains('/')) {
+                    return Err(format!("Invalid domain: {bad} (give a host name, e.g. dl.example.org)"));
+                }
+                config.trusted_domains = values.iter().map(|d| d.to_ascii_lowercase()).collect();
+            }
+            "names.protected" => {
+                config.names.protected = sorted(&values.iter().map(|n| n.to_ascii_lowercase()).collect::<Vec<_>>());
+            }
+            "report.endpoint" => config.report.endpoint = Some(parse_url(value)?),
+            "policy.trusted_keys" => {
+                if let Some(bad) = values.iter().find(|k| k.len() != 64 || !k.chars().all(|c| c.is_ascii_hexdigit())) {
+                    return Err(format!("Invalid key: {bad} (expected 64 hex digits from `traur policy keygen`)"));
+                }
+                config.policy.trusted_keys = values.iter().map(|k| k.to_ascii_lowercase()).collect();
+            }
+            "whitelist.packages" => {
+                config.whitelist.packages = sorted(values);
+                let packages = &config.whitelist.packages;
+                config.whitelist.pins.retain(|pkg, _| packages.contains(pkg));
+            }
+            "ignored.signals" => {
+                if let Some(bad) = values.iter().find(|id| !signal_registry::is_known_signal(id)) {
+                    return Err(format!(
+                        "Unknown signal: {bad}\nUse 'traur signals' to list available signal IDs."
+                    ));
+                }
+                config.ignored.signals = sorted(values);
+            }
+            "ignored.categories" => {
+                if let Some(bad) = values.iter().find(|c| signal_registry::category_from_str(c).is_none()) {
+                    return Err(format!(
+                        "Unknown category: {bad}\nValid categories: Metadata, Pkgbuild, Behavioral, Temporal, InstallScript"
+                    ));
+                }
+                config.ignored.categories = sorted(values);
+            }
+            "hook.skip_unchanged" => config.hook.skip_unchanged = parse_bool(value)?,
+            "hook.remember_approvals" => config.hook.remember_approvals = parse_bool(value)?,
+            "maintainer.aur_session" => config.maintainer.aur_session = Some(value.trim().to_string()),
+            "http.proxy" => config.http.proxy = Some(parse_url(value)?),
+            "http.ca_file" => config.http.ca_file = Some(parse_file(value)?),
+            "http.timeout" => config.http.timeout = Some(parse_secs(value)?),
+            "http.user_agent" => config.http.user_agent = Some(value.to_string()),
+            "http.aur_web_qps" => {
+                let qps = value
+                    .parse()
+                    .map_err(|_| format!("Invalid rate: {value} (expected requests per second as a whole number)"))?;
+                config.http.aur_web_qps = Some(qps);
+            }
+            "notify.desktop" => config.notify.desktop = parse_bool(value)?,
+            "notify.webhook_url" => config.notify.webhook_url = Some(parse_url(value)?),
+            "notify.ntfy_url" => config.notify.ntfy_url = Some(parse_url(value)?),
+            "notify.min_tier" => config.notify.min_tier = parse_tier(value)?,
+            "notify.template" => config.notify.template = Some(value.to_string()),
+            _ => unreachable!("unhandled config key {key}"),
+        },
+    }
+    Ok(())
+}
+
+/// Put a key back to its default by removing it from the config.
+fn unset(config: &mut Config, key: &Key) {
+    let defaults = Config::default();
+    match key {
+        Key::Feature(name) => {
+            config.features.enabled.remove(name);
+        }
+        Key::FeatureTimeout(name) => {
+            config.features.timeouts.remove(name);
+        }
+        Key::RepoUrl(name) => {
+            config.repos.remove(name);
+        }
+        Key::Fixed(key) => match *key {
+            "thresholds.block_at" => config.thresholds.block_at = defaults.thresholds.block_at,
+            "thresholds.warn_at" => config.thresholds.warn_at = defaults.thresholds.warn_at,
+            "thresholds.incomplete_as_sketchy" => {
+                config.thresholds.incomplete_as_sketchy = defaults.thresholds.incomplete_as_sketchy
+            }
+            "scoring.newness_signals" => config.scoring.newness_signals = None,
+            "scoring.demoted_gates" => config.scoring.demoted_gates.clear(),
+            "scan.jobs" => config.scan.jobs = None,
+            "scan.timeout" => config.scan.timeout = None,
+            "scan.exclude" => config.scan.exclude.clear(),
+            "scan.max_file_kb" => config.scan.max_file_kb = None,
+            "scan.deep" => config.scan.deep = defaults.scan.deep,
+            "scan.bulk_comments" => config.scan.bulk_comments = defaults.scan.bulk_comments,
+            "cache.maintainer_ttl" => config.cache.maintainer_ttl = None,
+            "cache.comments_ttl" => config.cache.comments_ttl = None,
+            "cache.max_size_mb" => config.cache.max_size_mb = None,
+            "known_good.url" => config.known_good.url = None,
+            "known_good.max_age_hours" => config.known_good.max_age_hours = None,
+            "geoip.asn_db" => config.geoip.asn_db = None,
+            "geoip.country_db" => config.geoip.country_db = None,
+            "threat_intel.urlhaus" => config.threat_intel.urlhaus = None,
+            "threat_intel.spamhaus_dbl" => config.threat_intel.spamhaus_dbl = None,
+            "trusted_domains" => config.trusted_domains.clear(),
+            "names.protected" => config.names.protected.clear(),
+            "report.endpoint" => config.report.endpoint = None,
+            "policy.trusted_keys" => config.policy.trusted_keys.clear(),
+            "whitelist.packages" => {
+                config.whitelist.packages.clear();
+                config.whitelist.pins.clear();
+            }
+            "ignored.signals" => config.ignored.signals.clear(),
+            "ignored.categories" => config.ignored.categories.clear(),
+            "hook.skip_unchanged" => config.hook.skip_unchanged = defaults.hook.skip_unchanged,
+            "hook.remember_approvals" => config.hook.remember_approvals = defaults.hook.remember_approvals,
+            "maintainer.aur_session" => config.maintainer.aur_session = None,
+            "http.proxy" => config.http.proxy = None,
+            "http.ca_file" => config.http.ca_file = None,
+            "http.timeout" => config.http.timeout = None,
+            "http.user_agent" => config.http.user_agent = None,
+            "http.aur_web_qps" => config.http.aur_web_qps = None,
+            "notify.desktop" => config.notify.desktop = defaults.notify.desktop,
+            "notify.webhook_url" => config.notify.webhook_url = None,
+            "notify.ntfy_url" => config.notify.ntfy_url = None,
+            "notify.min_tier" => config.notify.min_tier = defaults.notify.min_tier,
+            "notify.template" => config.notify.template = None,
+            _ => unreachable!("unhandled config key {key}"),
+        },
+    }
+}
+
+fn sorted(values: &[String]) -> Vec<String> {
+    let mut values = values.to_vec();
+    values.sort();
+    values.dedup();
+    values
+}
+
+fn parse_bool(value: &str) -> Result<bool, String> {
+    match value.to_ascii_lowercase().as_str() {
+        "true" | "yes" | "on" | "1" => Ok(true),
+        "false" | "no" | "off" | "0" => Ok(false),
+        _ => Err(format!("Invalid boolean: {value} (expected true or false)")),
+    }
+}
+
+fn parse_secs(value: &str) -> Result<u64, String> {
+    value
+        .parse()
+        .map_err(|_| format!("Invalid number of seconds: {value}"))
+}
+
+fn parse_tier(value: &str) -> Result<String, String> {
+    scoring::tier_from_str(value)
+        .map(|tier| tier.to_string().to_lowercase())
+        .ok_or_else(|| {
+            format!("Unknown tier: {value}\nValid tiers: trusted, ok, sketchy, suspicious, malicious")
+        })
+}
+
+fn parse_file(value: &str) -> Result<String, String> {
+    if std::path::Path::new(value).is_file() {
+        Ok(value.to_string())
+    } else {
+        Err(format!("No such file: {value}"))
+    }
+}
+
+fn parse_url(value: &str) -> Result<String, String> {
+    if value.starts_with("https://") || value.starts_with("http://") {
+        Ok(value.to_string())
+    } else {
+        Err(format!("Invalid URL: {value} (expected http:// or https://)"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FEATURES: &[&str] = &["github_stars", "shell_analysis"];
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn parses_fixed_and_feature_keys() {
+        assert_eq!(parse_key("scan.jobs", FEATURES), Ok(Key::Fixed("scan.jobs")));
+        assert_eq!(parse_key("features.github_stars", FEATURES), Ok(Key::Feature("github_stars".into())));
+        assert_eq!(
+            parse_key("features.timeouts.shell_analysis", FEATURES),
+            Ok(Key::FeatureTimeout("shell_analysis".into()))
+        );
+        assert!(parse_key("features.bogus", FEATURES).is_err());
+    }
+
+    #[test]
+    fn precedence_note_only_where_the_system_value_wins() {
+        let user = || {
+            let mut user = Config::default();
+            set(&mut user, &Key::Fixed("notify.min_tier"), &strings(&["suspicious"])).unwrap();
+            set(&mut user, &Key::Fixed("thresholds.block_at"), &strings(&["suspicious"])).unwrap();
+            user
+        };
+        let system = || {
+            let mut system = Config::default();
+            set(&mut system, &Key::Fixed("notify.min_tier"), &strings(&["malicious"])).unwrap();
+            system
+        };
+        assert_eq!(system_override(system(), user(), &Key::Fixed("notify.min_tier")), None);
+        assert_eq!(
+            system_override(system(), user(), &Key::Fixed("thresholds.block_at")),
+            Some(Config::default().thresholds.block_at)
+        );
+    }
+
+    #[test]
+    fn typo_suggests_closest_key() {
+        let err = parse_key("scan.jbos", FEATURES).unwrap_err();
+        assert!(err.contains("Did you mean 'scan.jobs'?"), "{err}");
+        let err = parse_key("features.github_star", FEATURES).unwrap_err();
+        assert!(err.contains("'features.github_stars'"), "{err}");
+        let err = parse_key("completely.unrelated.thing", FEATURES).unwrap_err();
+        assert!(!err.contains("Did you mean"), "{err}");
+    }
+
+    #[test]
+    fn set_validates_and_stores_values() {
+        let mut config = Config::default();
+        set(&mut config, &Key::Fixed("scan.jobs"), &strings(&["8"])).unwrap();
+        assert_eq!(config.scan.jobs(), 8);
+        assert!(set(&mut config, &Key::Fixed("scan.jobs"), &strings(&["0"])).is_err());
+
+        set(&mut config, &Key::Fixed("thresholds.block_at"), &strings(&["Suspicious"])).unwrap();
+        assert_eq!(config.thresholds.block_at, "suspicious");
+        assert!(set(&mut config, &Key::Fixed("thresholds.block_at"), &strings(&["severe"])).is_err());
+
+        set(&mut config, &Key::Feature("github_stars".into()), &strings(&["off"])).unwrap();
+        assert!(!config.features.is_enabled("github_stars"));
+
+        set(&mut config, &Key::Fixed("trusted_domains"), &strings(&["Dl.Example.org", "*.cdn.example.net"])).unwrap();
+        assert_eq!(get(&config, &Key::Fixed("trusted_domains")), "[dl.example.org, *.cdn.example.net]");
+        assert!(set(&mut config, &Key::Fixed("trusted_domains"), &strings(&["https://x.example"])).is_err());
+
+        assert!(set(&mut config, &Key::Fixed("ignored.signals"), &strings(&["NOT-A-SIGNAL"])).is_err());
+        set(&mut config, &Key::Fixed("scoring.demoted_gates"), &strings(&["P-CURL-PIPE", "G-TAR-CHECKPOINT"])).unwrap();
+        assert_eq!(get(&config, &Key::Fixed("scoring.demoted_gates")), "[G-TAR-CHECKPOINT, P-CURL-PIPE]");
+        assert!(set(&mut config, &Key::Fixed("scoring.demoted_gates"), &strings(&["M-VOTES-ZERO"])).is_err());
+        assert!(set(&mut config, &Key::Fixed("scan.timeout"), &strings(&["60", "90"])).is_err());
+
+        set(&mut config, &Key::Fixed("http.aur_web_qps"), &strings(&["0"])).unwrap();
+        assert_eq!(config.http.aur_web_interval(), None);
+        assert!(set(&mut config, &Key::Fixed("http.aur_web_qps"), &strings(&["0.5"])).is_err());
+    }
+
+    #[test]
+    fn unset_restores_defaults() {
+        let mut config = Config::default();
+        set(&mut config, &Key::Fixed("scan.timeout"), &strings(&["60"])).unwrap();
+        set(&mut config, &Key::Feature("github_stars".into()), &strings(&["false"])).unwrap();
+        unset(&mut config, &Key::Fixed("scan.timeout"));
+        unset(&mut config, &Key::Feature("github_stars".into()));
+        assert_eq!(get(&config, &Key::Fixed("scan.timeout")), "(unset)");
+        assert!(config.features.enabled.is_empty());
+    }
+
+    #[test]
+    fn every_fixed_key_has_a_value() {
+        let config = Config::default();
+        for key in all_keys(FEATURES) {
+            get(&config, &parse_key(&key, FEATURES).unwrap());
+        }
+    }
+}
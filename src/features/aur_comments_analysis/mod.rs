@@ -47,6 +47,8 @@ impl Feature for AurCommentsAnalysis {
                         ),
                         is_override_gate: false,
                         matched_line: Some(truncated),
+                        location: None,
+                        emitted_by: String::new(),
                     }];
                 }
             }
@@ -76,6 +78,15 @@ mod tests {
             github_stars: None,
             github_not_found: false,
             aur_comments: comments.into_iter().map(|s| s.to_string()).collect(),
+            artifact: None,
+            github_releases: None,
+            repo_files: Vec::new(),
+            trusted_domains: Vec::new(),
+            protected_names: Vec::new(),
+            sibling_commits: Vec::new(),
+            maintainer_account: None,
+            upstream_build_files: Vec::new(),
+            history_rewrite: None,
         };
         AurCommentsAnalysis
             .analyze(&ctx)
@@ -1,19 +1,11 @@
 use crate::features::Feature;
-use crate::shared::models::PackageContext;
+use crate::shared::github;
+use crate::shared::models::{GitHubRelease, PackageContext};
 use crate::shared::scoring::{Signal, SignalCategory};
+use crate::shared::{source_arrays, trusted_domains};
 use regex::Regex;
 use std::sync::LazyLock;
 
-/// Matches all source array variants: source=(), source_x86_64=(), etc.
-static SOURCE_ARRAYS_RE: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(r"(?ms)^source(?:_[a-zA-Z0-9_]+)?\s*=\s*\((.*?)\)").unwrap()
-});
-
-/// Extracts URLs from quoted or unquoted tokens inside a source array.
-static URL_TOKEN_RE: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(r#"['"]([^'"]+)['"]|(\S+)"#).unwrap()
-});
-
 /// Matches ${url} or $url variable references.
 static URL_VAR_RE: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(r"\$\{url\}|\$url").unwrap()
@@ -24,6 +16,22 @@ static UNRESOLVED_VAR_RE: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(r"\$\{?\w+\}?").unwrap()
 });
 
+/// Upstream GitHub repo: github.com/{owner}/{repo}
+static RELEASE_REPO_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)github\.com/([^/\s]+)/([^/\s#?]+)").unwrap()
+});
+
+/// Simple top-level assignments with a literal value: pkgver=1.2.3, _tag='v1.2.3'
+static SIMPLE_ASSIGN_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?m)^\s*([A-Za-z_][A-Za-z0-9_]*)=(?:"([^"$`]*)"|'([^']*)'|([^\s"'$`();]+))\s*$"#)
+        .unwrap()
+});
+
+/// GitHub release download URL: github.com/{owner}/{repo}/releases/download/{tag}/{asset}
+static RELEASE_DOWNLOAD_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)github\.com/([^/]+)/([^/]+)/releases/download/([^/]+)/([^/?#]+)").unwrap()
+});
+
 pub struct BinSourceVerification;
 
 impl Feature for BinSourceVerification {
@@ -31,94 +39,205 @@ impl Feature for BinSourceVerification {
         if !ctx.name.ends_with("-bin") {
             return Vec::new();
         }
+        verify(ctx)
+    }
+}
 
-        let Some(ref content) = ctx.pkgbuild_content else {
-            return Vec::new();
-        };
-
-        let Some(ref meta) = ctx.metadata else {
-            return Vec::new();
-        };
-
-        let Some(ref upstream_url) = meta.url else {
-            return Vec::new();
-        };
+/// The source-vs-upstream checks behind the feature, without the `-bin` name gate:
+/// `traur verify-upstream` runs them on any package.
+pub fn verify(ctx: &PackageContext) -> Vec<Signal> {
+    let Some(ref content) = ctx.pkgbuild_content else {
+        return Vec::new();
+    };
+
+    let Some(ref meta) = ctx.metadata else {
+        return Vec::new();
+    };
+
+    let Some(ref upstream_url) = meta.url else {
+        return Vec::new();
+    };
+
+    let upstream_domain = match extract_domain(upstream_url) {
+        Some(d) => d,
+        None => return Vec::new(),
+    };
+    let upstream_org = extract_github_org(upstream_url);
+
+    let source_urls = extract_source_urls(content, upstream_url);
+    let mut signals = Vec::new();
+    let mut saw_github_org_mismatch = false;
+
+    for raw_url in &source_urls {
+        // Skip non-HTTP sources (local files, etc.)
+        if !raw_url.contains("://") {
+            continue;
+        }
 
-        let upstream_domain = match extract_domain(upstream_url) {
-            Some(d) => d,
-            None => return Vec::new(),
+        let Some(src_domain) = extract_domain(raw_url) else {
+            continue;
         };
-        let upstream_org = extract_github_org(upstream_url);
-
-        let source_urls = extract_source_urls(content, upstream_url);
-        let mut signals = Vec::new();
-        let mut saw_github_org_mismatch = false;
 
-        for raw_url in &source_urls {
-            // Skip non-HTTP sources (local files, etc.)
-            if !raw_url.contains("://") {
-                continue;
-            }
-
-            let Some(src_domain) = extract_domain(raw_url) else {
-                continue;
-            };
-
-            // GitHub org comparison (higher confidence)
-            if normalize_domain(&src_domain) == "github.com"
-                && normalize_domain(&upstream_domain) == "github.com"
+        // GitHub org comparison (higher confidence)
+        if normalize_domain(&src_domain) == "github.com"
+            && normalize_domain(&upstream_domain) == "github.com"
+        {
+            let src_org = extract_github_org(raw_url);
+            if let (Some(u_org), Some(s_org)) = (&upstream_org, &src_org)
+                && !u_org.eq_ignore_ascii_case(s_org)
+                && !saw_github_org_mismatch
             {
-                let src_org = extract_github_org(raw_url);
-                if let (Some(u_org), Some(s_org)) = (&upstream_org, &src_org) {
-                    if !u_org.eq_ignore_ascii_case(s_org) && !saw_github_org_mismatch {
-                        saw_github_org_mismatch = true;
-                        signals.push(Signal {
-                            id: "B-BIN-GITHUB-ORG-MISMATCH".to_string(),
-                            category: SignalCategory::Behavioral,
-                            points: 50,
-                            description: format!(
-                                "-bin package upstream is github.com/{u_org} but source downloads from github.com/{s_org}"
-                            ),
-                            is_override_gate: false,
-                            matched_line: Some(raw_url.clone()),
-                        });
-                    }
-                }
-                continue; // Already compared at org level, skip domain check
-            }
-
-            // Domain-level comparison
-            if normalize_domain(&src_domain) != normalize_domain(&upstream_domain) {
+                saw_github_org_mismatch = true;
                 signals.push(Signal {
-                    id: "B-BIN-DOMAIN-MISMATCH".to_string(),
+                    id: "B-BIN-GITHUB-ORG-MISMATCH".to_string(),
                     category: SignalCategory::Behavioral,
-                    points: 30,
+                    points: 50,
                     description: format!(
-                        "-bin package upstream is {upstream_domain} but source downloads from {src_domain}"
+                        "-bin package upstream is github.com/{u_org} but source downloads from github.com/{s_org}"
                     ),
                     is_override_gate: false,
                     matched_line: Some(raw_url.clone()),
+                    location: None,
+                    emitted_by: String::new(),
                 });
             }
+            continue; // Already compared at org level, skip domain check
         }
 
-        signals
+        // Domain-level comparison; shared release CDNs and mirrors are not a mismatch
+        if normalize_domain(&src_domain) != normalize_domain(&upstream_domain)
+            && !trusted_domains::is_trusted(&src_domain, &ctx.trusted_domains)
+        {
+            signals.push(Signal {
+                id: "B-BIN-DOMAIN-MISMATCH".to_string(),
+                category: SignalCategory::Behavioral,
+                points: 30,
+                description: format!(
+                    "-bin package upstream is {upstream_domain} but source downloads from {src_domain}"
+                ),
+                is_override_gate: false,
+                matched_line: Some(raw_url.clone()),
+                location: None,
+                emitted_by: String::new(),
+            });
+        }
     }
+
+    if let Some(ref releases) = ctx.github_releases
+        && let Some(signal) = check_release_assets(content, upstream_url, releases)
+    {
+        signals.push(signal);
+    }
+
+    signals
+}
+
+/// Verify that release downloads from the upstream GitHub repo reference a tag and
+/// asset that actually exist in an official release. Org-match alone misses
+/// "official org, but nonexistent/renamed asset" redirectors.
+fn check_release_assets(
+    content: &str,
+    upstream_url: &str,
+    releases: &[GitHubRelease],
+) -> Option<Signal> {
+    let upstream = RELEASE_REPO_RE.captures(upstream_url)?;
+    let (u_owner, u_repo) = (&upstream[1], upstream[2].trim_end_matches(".git"));
+    // A full page may not reach back to the referenced tag; only trust absence otherwise
+    let complete = releases.len() < github::RELEASES_PER_PAGE;
+
+    for url in extract_source_urls_resolved(content, upstream_url) {
+        let Some(caps) = RELEASE_DOWNLOAD_RE.captures(&url) else {
+            continue;
+        };
+        if !caps[1].eq_ignore_ascii_case(u_owner) {
+            continue; // different owner: covered by the org mismatch check
+        }
+        let (tag, asset) = (percent_decode(&caps[3]), percent_decode(&caps[4]));
+
+        // Same owner, other repo: the org check passes it, and upstream's releases say
+        // nothing about what that repo publishes
+        let problem = if !caps[2].eq_ignore_ascii_case(u_repo) {
+            format!("release comes from github.com/{}/{}, not the upstream repo {u_owner}/{u_repo}", &caps[1], &caps[2])
+        } else {
+            match releases.iter().find(|r| r.tag_name == tag) {
+                Some(release) if !release.assets.contains(&asset) => {
+                    format!("asset '{asset}' does not exist in upstream release {tag}")
+                }
+                None if complete => format!("upstream has no release tagged {tag}"),
+                _ => continue,
+            }
+        };
+
+        return Some(Signal {
+            id: "B-BIN-RELEASE-ASSET-MISSING".to_string(),
+            category: SignalCategory::Behavioral,
+            points: 45,
+            description: format!("-bin package downloads a release asset not published upstream: {problem}"),
+            is_override_gate: false,
+            matched_line: Some(url),
+            location: None,
+            emitted_by: String::new(),
+        });
+    }
+
+    None
+}
+
+/// Decode `%XX` escapes in a URL path segment (`tool%2B1.0` -> `tool+1.0`); GitHub
+/// lists asset names and tags unescaped.
+fn percent_decode(segment: &str) -> String {
+    let bytes = segment.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes.get(i + 1..i + 3).and_then(|h| std::str::from_utf8(h).ok());
+        match hex.filter(|_| bytes[i] == b'%').and_then(|h| u8::from_str_radix(h, 16).ok()) {
+            Some(byte) => {
+                out.push(byte);
+                i += 3;
+            }
+            None => {
+                out.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
 }
 
-/// Extract all URLs from source=() arrays, resolving $url/${url} variables.
+/// Extract source URLs like `extract_source_urls`, additionally resolving simple
+/// literal assignments (`pkgver=1.2.3`, `_tag=v1.2.3`) so release download URLs
+/// built from `${pkgver}` can be checked.
+fn extract_source_urls_resolved(content: &str, upstream_url: &str) -> Vec<String> {
+    let mut vars: Vec<(String, String)> = SIMPLE_ASSIGN_RE
+        .captures_iter(content)
+        .map(|caps| {
+            let value = caps.get(2).or_else(|| caps.get(3)).or_else(|| caps.get(4));
+            (caps[1].to_string(), value.map(|m| m.as_str()).unwrap_or("").to_string())
+        })
+        .collect();
+    // Longest names first so $pkgver doesn't clobber $pkgver_suffix
+    vars.sort_by_key(|(name, _)| std::cmp::Reverse(name.len()));
+
+    let mut resolved = content.to_string();
+    for (name, value) in &vars {
+        if name == "url" {
+            continue; // resolved to the upstream URL below
+        }
+        resolved = resolved
+            .replace(&format!("${{{name}}}"), value)
+            .replace(&format!("${name}"), value);
+    }
+
+    extract_source_urls(&resolved, upstream_url)
+}
+
+/// Extract all URLs from source=() and source_<arch>=() arrays, resolving $url/${url} variables.
 fn extract_source_urls(content: &str, upstream_url: &str) -> Vec<String> {
     let mut urls = Vec::new();
 
-    for caps in SOURCE_ARRAYS_RE.captures_iter(content) {
-        let body = &caps[1];
-        for token_cap in URL_TOKEN_RE.captures_iter(body) {
-            let raw = token_cap
-                .get(1)
-                .or_else(|| token_cap.get(2))
-                .unwrap()
-                .as_str();
-
+    for array in source_arrays::parse_arrays(content, "source") {
+        for raw in &array.entries {
             // Strip VCS prefix (git+https://, svn+https://, etc.)
             let raw = raw
                 .split_once("+http")
@@ -198,14 +317,51 @@ mod tests {
             out_of_date: None,
             maintainer: Some("user".into()),
             submitter: Some("user".into()),
+            version: String::new(),
             first_submitted: 0,
             last_modified: 0,
             license: None,
+            description: None,
+            depends: Vec::new(),
+            opt_depends: Vec::new(),
+            make_depends: Vec::new(),
+            check_depends: Vec::new(),
         }
     }
 
     fn analyze(name: &str, url: Option<&str>, pkgbuild: &str) -> Vec<Signal> {
+        analyze_with_releases(name, url, pkgbuild, None)
+    }
+
+    fn analyze_trusting(url: &str, pkgbuild: &str, trusted: &[&str]) -> Vec<Signal> {
         let ctx = PackageContext {
+            trusted_domains: trusted.iter().map(|d| d.to_string()).collect(),
+            protected_names: Vec::new(),
+            sibling_commits: Vec::new(),
+            maintainer_account: None,
+            upstream_build_files: Vec::new(),
+            history_rewrite: None,
+            ..context("tool-bin", Some(url), pkgbuild, None)
+        };
+        BinSourceVerification.analyze(&ctx)
+    }
+
+    fn analyze_with_releases(
+        name: &str,
+        url: Option<&str>,
+        pkgbuild: &str,
+        releases: Option<Vec<GitHubRelease>>,
+    ) -> Vec<Signal> {
+        BinSourceVerification.analyze(&context(name, url, pkgbuild, releases))
+    }
+
+    fn context(
+        name: &str,
+        url: Option<&str>,
+        pkgbuild: &str,
+        releases: Option<Vec<GitHubRelease>>,
+    ) -> PackageContext {
+        PackageContext {
             name: name.into(),
             metadata: Some(make_pkg(url)),
             pkgbuild_content: Some(pkgbuild.into()),
@@ -216,8 +372,16 @@ mod tests {
             github_stars: None,
             github_not_found: false,
             aur_comments: vec![],
-        };
-        BinSourceVerification.analyze(&ctx)
+            artifact: None,
+            github_releases: releases,
+            repo_files: Vec::new(),
+            trusted_domains: Vec::new(),
+            protected_names: Vec::new(),
+            sibling_commits: Vec::new(),
+            maintainer_account: None,
+            upstream_build_files: Vec::new(),
+            history_rewrite: None,
+        }
     }
 
     fn ids(signals: &[Signal]) -> Vec<String> {
@@ -280,6 +444,22 @@ mod tests {
         assert!(has(&ids, "B-BIN-DOMAIN-MISMATCH"));
     }
 
+    #[test]
+    fn trusted_cdn_is_not_a_domain_mismatch() {
+        let pkgbuild = "source=('https://objects.githubusercontent.com/x/tool.tar.gz'\n        'https://netix.dl.sourceforge.net/project/tool/tool.tar.gz')";
+        let signals = analyze("tool-bin", Some("https://example.com/tool"), pkgbuild);
+        assert!(!has(&ids(&signals), "B-BIN-DOMAIN-MISMATCH"));
+    }
+
+    #[test]
+    fn configured_trusted_domain_is_not_a_domain_mismatch() {
+        let pkgbuild = "source=('https://eu.cdn.example.net/tool.tar.gz')";
+        let signals = analyze_trusting("https://example.com/tool", pkgbuild, &[]);
+        assert!(has(&ids(&signals), "B-BIN-DOMAIN-MISMATCH"));
+        let signals = analyze_trusting("https://example.com/tool", pkgbuild, &["cdn.example.net"]);
+        assert!(!has(&ids(&signals), "B-BIN-DOMAIN-MISMATCH"));
+    }
+
     #[test]
     fn domain_match() {
         let signals = analyze(
@@ -406,4 +586,99 @@ mod tests {
             .collect();
         assert_eq!(org_signals.len(), 1);
     }
+
+    fn release(tag: &str, assets: &[&str]) -> GitHubRelease {
+        GitHubRelease {
+            tag_name: tag.to_string(),
+            assets: assets.iter().map(|a| a.to_string()).collect(),
+        }
+    }
+
+    const RELEASE_PKGBUILD: &str = "pkgver=1.2.0\nsource=(\"https://github.com/official/tool/releases/download/v${pkgver}/tool-${pkgver}-linux.tar.gz\")";
+
+    #[test]
+    fn release_asset_exists() {
+        let signals = analyze_with_releases(
+            "tool-bin",
+            Some("https://github.com/official/tool"),
+            RELEASE_PKGBUILD,
+            Some(vec![release("v1.2.0", &["tool-1.2.0-linux.tar.gz"])]),
+        );
+        assert!(signals.is_empty(), "got: {:?}", ids(&signals));
+    }
+
+    #[test]
+    fn release_asset_missing_from_release() {
+        let signals = analyze_with_releases(
+            "tool-bin",
+            Some("https://github.com/official/tool"),
+            RELEASE_PKGBUILD,
+            Some(vec![release("v1.2.0", &["tool-1.2.0-linux-x64.tar.gz"])]),
+        );
+        let ids = ids(&signals);
+        assert!(has(&ids, "B-BIN-RELEASE-ASSET-MISSING"));
+    }
+
+    #[test]
+    fn release_tag_missing() {
+        let signals = analyze_with_releases(
+            "tool-bin",
+            Some("https://github.com/official/tool"),
+            RELEASE_PKGBUILD,
+            Some(vec![release("v1.1.0", &["tool-1.1.0-linux.tar.gz"])]),
+        );
+        let ids = ids(&signals);
+        assert!(has(&ids, "B-BIN-RELEASE-ASSET-MISSING"));
+    }
+
+    #[test]
+    fn release_tag_missing_but_list_truncated() {
+        let releases = (0..github::RELEASES_PER_PAGE)
+            .map(|i| release(&format!("v2.{i}.0"), &[]))
+            .collect();
+        let signals = analyze_with_releases(
+            "tool-bin",
+            Some("https://github.com/official/tool"),
+            RELEASE_PKGBUILD,
+            Some(releases),
+        );
+        assert!(signals.is_empty(), "got: {:?}", ids(&signals));
+    }
+
+    #[test]
+    fn release_from_another_repo_of_the_same_owner() {
+        let signals = analyze_with_releases(
+            "tool-bin",
+            Some("https://github.com/official/tool"),
+            "source=('https://github.com/official/tool-nightly/releases/download/v1.2.0/tool-1.2.0-linux.tar.gz')",
+            Some(vec![release("v1.2.0", &["tool-1.2.0-linux.tar.gz"])]),
+        );
+        assert!(has(&ids(&signals), "B-BIN-RELEASE-ASSET-MISSING"));
+
+        let signals = analyze_with_releases(
+            "tool-bin",
+            Some("https://github.com/Official/Tool.git"),
+            "source=('https://github.com/official/tool/releases/download/v1.2.0/tool-1.2.0-linux.tar.gz')",
+            Some(vec![release("v1.2.0", &["tool-1.2.0-linux.tar.gz"])]),
+        );
+        assert!(signals.is_empty(), "got: {:?}", ids(&signals));
+    }
+
+    #[test]
+    fn release_asset_and_tag_names_are_url_decoded() {
+        let signals = analyze_with_releases(
+            "tool-bin",
+            Some("https://github.com/official/tool"),
+            "source=('https://github.com/official/tool/releases/download/v1.2.0%2Bbuild/Tool%20Setup%2B1.2.0.tar.gz')",
+            Some(vec![release("v1.2.0+build", &["Tool Setup+1.2.0.tar.gz"])]),
+        );
+        assert!(signals.is_empty(), "got: {:?}", ids(&signals));
+        assert_eq!(percent_decode("a%2bb%zz%"), "a+b%zz%");
+    }
+
+    #[test]
+    fn release_check_skipped_without_release_data() {
+        let signals = analyze("tool-bin", Some("https://github.com/official/tool"), RELEASE_PKGBUILD);
+        assert!(signals.is_empty(), "got: {:?}", ids(&signals));
+    }
 }
@@ -1,6 +1,7 @@
 use crate::features::Feature;
 use crate::shared::models::PackageContext;
 use crate::shared::scoring::{Signal, SignalCategory};
+use crate::shared::source_arrays;
 use regex::Regex;
 use std::sync::LazyLock;
 
@@ -9,25 +10,21 @@ static HAS_CHECKSUMS_RE: LazyLock<Regex> = LazyLock::new(|| {
 });
 
 static WEAK_CHECKSUMS_RE: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(r"(?m)^(md5|sha1)sums=").unwrap()
+    Regex::new(r"(?m)^(md5|sha1)sums(_[a-zA-Z0-9_]+)?=").unwrap()
 });
 
 static STRONG_CHECKSUMS_RE: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(r"(?m)^(sha(256|384|512)|b2)sums=").unwrap()
+    Regex::new(r"(?m)^(sha(256|384|512)|b2)sums(_[a-zA-Z0-9_]+)?=").unwrap()
 });
 
 static CHECKSUM_ARRAY_RE: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(r"(?ms)^(md5|sha\d+|b2)sums=\((.*?)\)").unwrap()
+    Regex::new(r"(?ms)^(md5|sha\d+|b2)sums(?:_[a-zA-Z0-9_]+)?=\((.*?)\)").unwrap()
 });
 
 static ENTRY_RE: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(r"'([^']*)'").unwrap()
 });
 
-static TOKEN_RE: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(r#"['"][^'"]*['"]|[^\s'")()]+"#).unwrap()
-});
-
 pub struct ChecksumAnalysis;
 
 impl Feature for ChecksumAnalysis {
@@ -51,6 +48,8 @@ impl Feature for ChecksumAnalysis {
                 description: "No checksum array found in PKGBUILD".to_string(),
                 is_override_gate: false,
                 matched_line: None,
+                location: None,
+                emitted_by: String::new(),
             });
         }
 
@@ -63,6 +62,8 @@ impl Feature for ChecksumAnalysis {
                 description: "All checksums are SKIP (no integrity verification)".to_string(),
                 is_override_gate: false,
                 matched_line: None,
+                location: None,
+                emitted_by: String::new(),
             });
         }
 
@@ -76,17 +77,24 @@ impl Feature for ChecksumAnalysis {
                     .to_string(),
                 is_override_gate: false,
                 matched_line: None,
+                location: None,
+                emitted_by: String::new(),
             });
         }
 
         // Check source count vs checksum count mismatch (including arch-specific arrays)
-        'outer: for suffix in find_array_suffixes(content) {
-            let source_name = format!("source{suffix}");
-            let src_count = count_array_entries(content, &source_name);
+        'outer: for source in source_arrays::parse_arrays(content, "source") {
+            let source_name = source.full_name();
+            let src_count = static_len(&source);
             if src_count > 0 {
                 for algo in &["md5sums", "sha256sums", "sha512sums", "b2sums"] {
-                    let checksum_name = format!("{algo}{suffix}");
-                    let cksum_count = count_array_entries(content, &checksum_name);
+                    let Some(checksums) =
+                        source_arrays::parse_arrays(content, algo).into_iter().find(|a| a.arch == source.arch)
+                    else {
+                        continue;
+                    };
+                    let checksum_name = checksums.full_name();
+                    let cksum_count = static_len(&checksums);
                     if cksum_count > 0 && cksum_count != src_count {
                         signals.push(Signal {
                             id: "P-CHECKSUM-MISMATCH".to_string(),
@@ -97,6 +105,8 @@ impl Feature for ChecksumAnalysis {
                             ),
                             is_override_gate: false,
                             matched_line: None,
+                            location: None,
+                            emitted_by: String::new(),
                         });
                         break 'outer;
                     }
@@ -134,32 +144,10 @@ fn has_all_skip_checksums(content: &str) -> bool {
     found_any
 }
 
-/// Find all source array suffixes (e.g. "", "_x86_64", "_i686").
-fn find_array_suffixes(content: &str) -> Vec<String> {
-    let re = Regex::new(r"(?m)^source(_[a-zA-Z0-9_]+)?\s*=\s*\(").unwrap();
-    re.captures_iter(content)
-        .map(|c| c.get(1).map_or(String::new(), |m| m.as_str().to_string()))
-        .collect()
-}
-
-static DYNAMIC_BASH_RE: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(r"\$\(|`|\$\{[^}]*\[@\]|\$\{[^}]*\[\*\]").unwrap()
-});
-
-/// Count entries in a bash array like source=(...) or sha256sums=(...)
-/// Returns 0 for arrays with dynamic bash constructs (command substitution,
-/// array expansion) since static token counting would be unreliable.
-fn count_array_entries(content: &str, array_name: &str) -> usize {
-    let pattern = format!(r"(?ms)^{array_name}=\((.*?)\)");
-    let re = Regex::new(&pattern).unwrap();
-    let Some(caps) = re.captures(content) else {
-        return 0;
-    };
-    let body = &caps[1];
-    if DYNAMIC_BASH_RE.is_match(body) {
-        return 0;
-    }
-    TOKEN_RE.find_iter(body).count()
+/// Number of entries in an array, or 0 for arrays with dynamic bash constructs
+/// (command substitution, array expansion) since static counting would be unreliable.
+fn static_len(array: &source_arrays::BashArray) -> usize {
+    if array.dynamic { 0 } else { array.entries.len() }
 }
 
 #[cfg(test)]
@@ -178,6 +166,15 @@ mod tests {
             github_stars: None,
             github_not_found: false,
             aur_comments: vec![],
+            artifact: None,
+            github_releases: None,
+            repo_files: Vec::new(),
+            trusted_domains: Vec::new(),
+            protected_names: Vec::new(),
+            sibling_commits: Vec::new(),
+            maintainer_account: None,
+            upstream_build_files: Vec::new(),
+            history_rewrite: None,
         };
         ChecksumAnalysis.analyze(&ctx).iter().map(|s| s.id.clone()).collect()
     }
@@ -238,6 +235,15 @@ mod tests {
             github_stars: None,
             github_not_found: false,
             aur_comments: vec![],
+            artifact: None,
+            github_releases: None,
+            repo_files: Vec::new(),
+            trusted_domains: Vec::new(),
+            protected_names: Vec::new(),
+            sibling_commits: Vec::new(),
+            maintainer_account: None,
+            upstream_build_files: Vec::new(),
+            history_rewrite: None,
         };
         let signals = ChecksumAnalysis.analyze(&ctx);
         let mismatch = signals.iter().find(|s| s.id == "P-CHECKSUM-MISMATCH").unwrap();
@@ -264,6 +270,15 @@ mod tests {
         assert!(!has(&ids, "P-NO-CHECKSUMS"), "arch-specific checksums should count, got: {ids:?}");
     }
 
+    #[test]
+    fn arch_only_weak_and_skip_checksums() {
+        let ids = analyze("test-bin", "source_x86_64=('a.tar.gz')\nmd5sums_x86_64=('SKIP')\n");
+        assert!(has(&ids, "P-WEAK-CHECKSUMS"), "got: {ids:?}");
+        assert!(has(&ids, "P-SKIP-ALL"), "got: {ids:?}");
+        let ids = analyze("test-bin", "source_x86_64=('a.tar.gz')\nmd5sums_x86_64=('h1')\nsha256sums_x86_64=('h2')\n");
+        assert!(!has(&ids, "P-WEAK-CHECKSUMS"), "got: {ids:?}");
+    }
+
     #[test]
     fn dynamic_source_array_no_mismatch() {
         // source uses bash array expansion — static counting is unreliable, skip mismatch
@@ -292,6 +307,15 @@ mod tests {
             github_stars: None,
             github_not_found: false,
             aur_comments: vec![],
+            artifact: None,
+            github_releases: None,
+            repo_files: Vec::new(),
+            trusted_domains: Vec::new(),
+            protected_names: Vec::new(),
+            sibling_commits: Vec::new(),
+            maintainer_account: None,
+            upstream_build_files: Vec::new(),
+            history_rewrite: None,
         };
         let signals = ChecksumAnalysis.analyze(&ctx);
         let mismatch_count = signals.iter().filter(|s| s.id == "P-CHECKSUM-MISMATCH").count();
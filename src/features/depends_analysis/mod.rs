@@ -0,0 +1,194 @@
+pub mod srcinfo;
+
+use crate::features::Feature;
+use crate::shared::models::{AurPackage, PackageContext};
+use crate::shared::scoring::{Signal, SignalCategory};
+
+/// Network scanning, relaying, anonymizing and tunneling tools: routine for a
+/// pentest or proxy package, out of character for a wallpaper changer.
+const NETWORK_TOOLS: &[&str] = &[
+    "nmap",
+    "masscan",
+    "zmap",
+    "socat",
+    "gnu-netcat",
+    "openbsd-netcat",
+    "netcat",
+    "ncat",
+    "tor",
+    "torsocks",
+    "proxychains-ng",
+    "sshpass",
+    "ngrok",
+    "cloudflared",
+    "frp",
+    "chisel",
+    "hydra",
+    "i2pd",
+];
+
+/// Words in a package's name or description that make the tools above expected.
+/// Matched as whole words, except stems ending in `*`.
+const NETWORK_CONTEXT: &[&str] = &[
+    "network*", "secur*", "pentest*", "penetration", "scan*", "prox*", "tor", "onion*",
+    "vpn", "anonym*", "privacy", "tunnel*", "firewall", "audit*", "forensic*", "hack*",
+    "exploit*", "ssh", "remote", "server", "relay*", "socks*", "ports", "packet*",
+    "sniff*", "recon*", "ctf",
+];
+
+/// Packages with at least this many votes are established enough to skip the check.
+const LOW_REPUTATION_VOTES: u32 = 10;
+
+pub struct DependsAnalysis;
+
+impl Feature for DependsAnalysis {
+    fn analyze(&self, ctx: &PackageContext) -> Vec<Signal> {
+        let Some(ref meta) = ctx.metadata else {
+            return Vec::new();
+        };
+        let mut signals = Vec::new();
+        srcinfo::analyze(ctx, &mut signals);
+        if meta.num_votes >= LOW_REPUTATION_VOTES || has_network_context(meta) {
+            return signals;
+        }
+
+        let depends = network_tools(&meta.depends);
+        if !depends.is_empty() {
+            signals.push(Signal {
+                id: "B-DEPENDS-NETTOOL".to_string(),
+                category: SignalCategory::Behavioral,
+                points: 35,
+                description: format!(
+                    "Depends on network/exfiltration tooling unrelated to its description: {}",
+                    depends.join(", ")
+                ),
+                is_override_gate: false,
+                matched_line: Some(format!("depends: {}", depends.join(" "))),
+                location: None,
+                emitted_by: String::new(),
+            });
+        }
+        let opt_depends = network_tools(&meta.opt_depends);
+        if !opt_depends.is_empty() {
+            signals.push(Signal {
+                id: "B-OPTDEPENDS-NETTOOL".to_string(),
+                category: SignalCategory::Behavioral,
+                points: 15,
+                description: format!(
+                    "Optionally depends on network/exfiltration tooling unrelated to its description: {}",
+                    opt_depends.join(", ")
+                ),
+                is_override_gate: false,
+                matched_line: Some(format!("optdepends: {}", opt_depends.join(" "))),
+                location: None,
+                emitted_by: String::new(),
+            });
+        }
+        signals
+    }
+}
+
+/// Dependency names from `NETWORK_TOOLS`, with version constraints (`tor>=0.4`) and
+/// optdepends reasons (`nmap: for scanning`) stripped.
+fn network_tools(deps: &[String]) -> Vec<String> {
+    let mut found: Vec<String> = deps
+        .iter()
+        .map(|dep| {
+            let name = dep.split(':').next().unwrap_or(dep);
+            name.split(['<', '>', '=']).next().unwrap_or(name).trim().to_string()
+        })
+        .filter(|name| NETWORK_TOOLS.contains(&name.as_str()))
+        .collect();
+    found.dedup();
+    found
+}
+
+fn has_network_context(meta: &AurPackage) -> bool {
+    let text = format!("{} {}", meta.name, meta.description.as_deref().unwrap_or("")).to_lowercase();
+    let words: Vec<&str> = text.split(|c: char| !c.is_ascii_alphanumeric()).filter(|w| !w.is_empty()).collect();
+    NETWORK_CONTEXT.iter().any(|ctx| match ctx.strip_suffix('*') {
+        Some(stem) => words.iter().any(|w| w.starts_with(stem)),
+        None => words.contains(ctx),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn meta(name: &str, description: &str, votes: u32, depends: &[&str], opt_depends: &[&str]) -> AurPackage {
+        AurPackage {
+            name: name.into(),
+            package_base: None,
+            version: String::new(),
+            url: None,
+            num_votes: votes,
+            popularity: 0.0,
+            out_of_date: None,
+            maintainer: Some("someone".into()),
+            submitter: None,
+            first_submitted: 0,
+            last_modified: 0,
+            license: None,
+            description: Some(description.into()),
+            depends: depends.iter().map(|s| s.to_string()).collect(),
+            opt_depends: opt_depends.iter().map(|s| s.to_string()).collect(),
+            make_depends: Vec::new(),
+            check_depends: Vec::new(),
+        }
+    }
+
+    fn analyze(meta: AurPackage) -> Vec<Signal> {
+        let ctx = PackageContext {
+            name: meta.name.clone(),
+            metadata: Some(meta),
+            pkgbuild_content: None,
+            install_script_content: None,
+            prior_pkgbuild_content: None,
+            git_log: vec![],
+            maintainer_packages: vec![],
+            github_stars: None,
+            github_not_found: false,
+            aur_comments: vec![],
+            artifact: None,
+            github_releases: None,
+            repo_files: Vec::new(),
+            trusted_domains: Vec::new(),
+            protected_names: Vec::new(),
+            sibling_commits: Vec::new(),
+            maintainer_account: None,
+            upstream_build_files: Vec::new(),
+            history_rewrite: None,
+        };
+        DependsAnalysis.analyze(&ctx)
+    }
+
+    #[test]
+    fn flags_wallpaper_changer_depending_on_socat() {
+        let signals = analyze(meta(
+            "wallpaper-rotate",
+            "Rotate your desktop wallpaper every hour",
+            0,
+            &["python", "socat", "tor>=0.4"],
+            &["nmap: for scanning", "feh: image backend"],
+        ));
+        assert_eq!(signals[0].id, "B-DEPENDS-NETTOOL");
+        assert_eq!(signals[0].matched_line.as_deref(), Some("depends: socat tor"));
+        assert_eq!(signals[1].id, "B-OPTDEPENDS-NETTOOL");
+        assert!(signals[1].description.ends_with(": nmap"));
+    }
+
+    #[test]
+    fn network_tools_expected_from_network_packages() {
+        assert!(analyze(meta("onionshare-cli", "Securely and anonymously share files over Tor", 2, &["tor"], &[])).is_empty());
+        assert!(analyze(meta("autorecon", "Multi-threaded network reconnaissance tool", 0, &["nmap"], &[])).is_empty());
+    }
+
+    #[test]
+    fn established_packages_are_skipped() {
+        assert!(analyze(meta("wallpaper-rotate", "Rotate wallpapers", 250, &["socat"], &[])).is_empty());
+        assert!(analyze(meta("wallpaper-rotate", "Rotate wallpapers", 0, &["feh"], &[])).is_empty());
+        // "torrent" is not "tor"
+        assert!(!analyze(meta("torrent-notify", "Notify on finished torrents", 0, &["socat"], &[])).is_empty());
+    }
+}
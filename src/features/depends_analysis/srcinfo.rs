@@ -0,0 +1,134 @@
+//! Dependencies declared in .SRCINFO but nowhere in the PKGBUILD.
+//!
+//! AUR helpers resolve dependencies from the RPC, which is built from .SRCINFO, while
+//! a reviewer reads the PKGBUILD. A dependency only .SRCINFO declares gets installed
+//! without ever appearing in what the reviewer saw.
+
+use crate::shared::models::{AurPackage, PackageContext};
+use crate::shared::scoring::{Signal, SignalCategory};
+use crate::shared::source_arrays;
+use regex::Regex;
+use std::collections::HashSet;
+use std::sync::LazyLock;
+
+/// The opening of `depends=(`, `makedepends_x86_64+=(` and the like, also indented
+/// inside a split package's `package_*()` function. `source_arrays::array_body` reads
+/// the rest.
+static DEPENDS_ARRAY_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?m)^[ \t]*(?:depends|makedepends|checkdepends)(?:_[A-Za-z0-9_]+)?\s*\+?=\s*\(").unwrap()
+});
+
+/// Dependencies (`depends`, `makedepends` and `checkdepends` from the RPC) that no
+/// dependency array of the PKGBUILD names, in RPC order. Empty when the PKGBUILD
+/// builds an array at run time or names a dependency through a variable, since the
+/// comparison would then be guesswork.
+pub fn srcinfo_only(meta: &AurPackage, pkgbuild: &str) -> Vec<String> {
+    let mut declared = HashSet::new();
+    for opening in DEPENDS_ARRAY_RE.find_iter(pkgbuild) {
+        let Some(body) = source_arrays::array_body(&pkgbuild[opening.end()..]) else {
+            continue;
+        };
+        if source_arrays::is_dynamic(body) {
+            return Vec::new();
+        }
+        for entry in source_arrays::entries(body) {
+            if entry.contains('$') {
+                return Vec::new();
+            }
+            declared.insert(dep_name(&entry).to_string());
+        }
+    }
+
+    let mut extra: Vec<String> = Vec::new();
+    for dep in meta.depends.iter().chain(&meta.make_depends).chain(&meta.check_depends) {
+        let name = dep_name(dep);
+        if !name.is_empty() && !declared.contains(name) && !extra.iter().any(|e| e == name) {
+            extra.push(name.to_string());
+        }
+    }
+    extra
+}
+
+/// A dependency without its version constraint (`python>=3.11` -> `python`).
+fn dep_name(dep: &str) -> &str {
+    dep.split(['<', '>', '=']).next().unwrap_or(dep).trim()
+}
+
+pub fn analyze(ctx: &PackageContext, signals: &mut Vec<Signal>) {
+    let (Some(meta), Some(pkgbuild)) = (&ctx.metadata, &ctx.pkgbuild_content) else {
+        return;
+    };
+    let extra = srcinfo_only(meta, pkgbuild);
+    if extra.is_empty() {
+        return;
+    }
+    signals.push(Signal {
+        id: "B-SRCINFO-DEPENDS".to_string(),
+        category: SignalCategory::Behavioral,
+        points: 50,
+        description: format!(
+            "Dependencies declared in .SRCINFO but not in the PKGBUILD (installed by AUR helpers, unseen in review): {}",
+            extra.join(", ")
+        ),
+        is_override_gate: false,
+        matched_line: Some(format!("srcinfo-only: {}", extra.join(" "))),
+        location: None,
+        emitted_by: String::new(),
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn meta(depends: &[&str], make_depends: &[&str]) -> AurPackage {
+        AurPackage {
+            name: "foo".into(),
+            package_base: None,
+            version: String::new(),
+            url: None,
+            num_votes: 0,
+            popularity: 0.0,
+            out_of_date: None,
+            maintainer: None,
+            submitter: None,
+            first_submitted: 0,
+            last_modified: 0,
+            license: None,
+            description: None,
+            depends: depends.iter().map(|s| s.to_string()).collect(),
+            opt_depends: Vec::new(),
+            make_depends: make_depends.iter().map(|s| s.to_string()).collect(),
+            check_depends: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn finds_dependencies_missing_from_pkgbuild() {
+        let pkgbuild = "pkgname=foo\ndepends=('python>=3.11' # runtime\n         gtk3)\nmakedepends=(git)\n";
+        let extra = srcinfo_only(&meta(&["python>=3.11", "gtk3", "python-colorama-fix"], &["git", "cmake"]), pkgbuild);
+        assert_eq!(extra, ["python-colorama-fix", "cmake"]);
+    }
+
+    #[test]
+    fn comment_with_parenthesis_does_not_end_the_array() {
+        let pkgbuild = "depends=(gtk3 # needs the (legacy) theme engine\n         'python>=3.11'\n         libnotify)\n";
+        assert!(srcinfo_only(&meta(&["gtk3", "python>=3.11", "libnotify"], &[]), pkgbuild).is_empty());
+        assert_eq!(srcinfo_only(&meta(&["gtk3", "libnotify", "evil"], &[]), pkgbuild), ["evil"]);
+    }
+
+    #[test]
+    fn split_packages_arch_arrays_and_appends_count() {
+        let pkgbuild = "pkgbase=foo\nmakedepends=(git)\ndepends_x86_64=(lib32-glibc)\n\
+            package_foo() {\n  depends=(gtk3)\n  depends+=('libfoo=1.0')\n}\n";
+        assert!(srcinfo_only(&meta(&["gtk3", "libfoo", "lib32-glibc"], &["git"]), pkgbuild).is_empty());
+    }
+
+    #[test]
+    fn variable_or_generated_dependencies_skip_the_check() {
+        let extra = &["foo-common", "evil"];
+        assert!(srcinfo_only(&meta(extra, &[]), "depends=(\"${pkgbase}-common\")\n").is_empty());
+        assert!(srcinfo_only(&meta(extra, &[]), "depends=($(cat deps.txt))\n").is_empty());
+        assert_eq!(srcinfo_only(&meta(extra, &[]), "depends=(foo-common)\n"), ["evil"]);
+    }
+}
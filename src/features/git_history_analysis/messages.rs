@@ -0,0 +1,294 @@
+//! Red flags in commit messages: a trivial message on a commit that rewrites the
+//! PKGBUILD, invisible or homoglyph characters, messages naming another package, and
+//! one message pushed to many of the maintainer's packages at once.
+
+use crate::shared::models::{GitCommit, PackageContext};
+use crate::shared::scoring::{Signal, SignalCategory};
+use regex::Regex;
+use std::collections::HashSet;
+use std::sync::LazyLock;
+
+/// Messages that say nothing about what changed.
+const TRIVIAL_MESSAGES: &[&str] = &[
+    "", ".", "..", "-", "fix", "fixes", "fixed", "small fix", "minor fix", "update", "updated", "updates",
+    "minor update", "upd", "bump", "minor", "cleanup", "typo", "wip", "change", "changes", "misc", "tweak",
+    "commit",
+];
+
+/// Share of PKGBUILD lines a commit must change before a trivial message on it is misleading.
+const REWRITE_PCT: u32 = 60;
+
+/// How many of the newest commits are checked for messages naming another package.
+const RECENT_COMMITS: usize = 3;
+
+/// Sibling packages that must share the newest commit's message, within
+/// `CAMPAIGN_WINDOW_SECS`, before it looks like a campaign.
+const CAMPAIGN_MIN_PACKAGES: usize = 3;
+
+pub const CAMPAIGN_WINDOW_SECS: u64 = 2 * 86400;
+
+/// Shortest message worth comparing across packages; "update" is everywhere.
+const CAMPAIGN_MIN_LEN: usize = 12;
+
+/// `upgpkg: foo 1.2-1` (aurpublish, pkgctl) or `Update foo to 1.2`.
+static NAMED_PACKAGE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)^(?:(?:upgpkg|addpkg|updpkg):\s*([a-z0-9@._+-]+)|(?:update|upgrade|bump)\s+([a-z0-9@._+-]+)\s+to\s+v?\d)")
+        .unwrap()
+});
+
+static PKGNAME_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?m)^pkgname=\(?([^)\n]*)\)?").unwrap());
+
+pub fn analyze(ctx: &PackageContext, signals: &mut Vec<Signal>) {
+    let Some(newest) = ctx.git_log.first() else {
+        return;
+    };
+
+    // T-COMMIT-MSG-MISMATCH: "fix" on a commit that replaces most of the PKGBUILD
+    if is_trivial(&newest.message)
+        && let (Some(new), Some(old)) = (&ctx.pkgbuild_content, &ctx.prior_pkgbuild_content)
+        && let Some(changed) = changed_pct(new, old)
+        && changed >= REWRITE_PCT
+    {
+        signals.push(Signal {
+            id: "T-COMMIT-MSG-MISMATCH".to_string(),
+            category: SignalCategory::Temporal,
+            points: 25,
+            description: format!(
+                "Latest commit message {:?} understates a commit that changes {changed}% of the PKGBUILD",
+                newest.message
+            ),
+            is_override_gate: false,
+            matched_line: Some(newest.message.clone()),
+            location: None,
+            emitted_by: String::new(),
+        });
+    }
+
+    // T-COMMIT-MSG-SPAM: invisible characters or Latin words salted with look-alikes
+    if let Some(commit) = ctx.git_log.iter().find(|c| is_spam(&c.message)) {
+        signals.push(Signal {
+            id: "T-COMMIT-MSG-SPAM".to_string(),
+            category: SignalCategory::Temporal,
+            points: 15,
+            description: "Commit message contains invisible or look-alike (homoglyph) characters".to_string(),
+            is_override_gate: false,
+            matched_line: Some(format!("{:?}", commit.message)),
+            location: None,
+            emitted_by: String::new(),
+        });
+    }
+
+    // T-COMMIT-MSG-OTHER-PKG: a recent message names a package this repo doesn't build
+    let own_names = own_names(ctx);
+    if let Some((commit, named)) = ctx.git_log.iter().take(RECENT_COMMITS).find_map(|c| {
+        named_package(&c.message)
+            .filter(|named| !own_names.iter().any(|own| related(own, named)))
+            .map(|named| (c, named))
+    }) {
+        signals.push(Signal {
+            id: "T-COMMIT-MSG-OTHER-PKG".to_string(),
+            category: SignalCategory::Temporal,
+            points: 20,
+            description: format!("Recent commit message refers to an unrelated package ({named})"),
+            is_override_gate: false,
+            matched_line: Some(commit.message.clone()),
+            location: None,
+            emitted_by: String::new(),
+        });
+    }
+
+    // T-COMMIT-MSG-CAMPAIGN: the same message pushed to several of the maintainer's
+    // packages within a short window
+    let campaign = campaign_packages(newest, &ctx.sibling_commits);
+    if campaign.len() >= CAMPAIGN_MIN_PACKAGES {
+        signals.push(Signal {
+            id: "T-COMMIT-MSG-CAMPAIGN".to_string(),
+            category: SignalCategory::Temporal,
+            points: 30,
+            description: format!(
+                "Latest commit message also pushed to {} other packages of this maintainer within {} hours: {}",
+                campaign.len(),
+                CAMPAIGN_WINDOW_SECS / 3600,
+                campaign.join(", ")
+            ),
+            is_override_gate: false,
+            matched_line: Some(newest.message.clone()),
+            location: None,
+            emitted_by: String::new(),
+        });
+    }
+}
+
+fn normalize(message: &str) -> String {
+    message.trim().trim_end_matches(['.', '!']).trim().to_lowercase()
+}
+
+fn is_trivial(message: &str) -> bool {
+    let message = message.trim();
+    TRIVIAL_MESSAGES.contains(&message) || TRIVIAL_MESSAGES.contains(&normalize(message).as_str())
+}
+
+/// Percentage of distinct non-blank lines that differ between two PKGBUILDs.
+fn changed_pct(new: &str, old: &str) -> Option<u32> {
+    let lines = |s: &str| -> HashSet<String> {
+        s.lines().map(|l| l.trim().to_string()).filter(|l| !l.is_empty()).collect()
+    };
+    let (new, old) = (lines(new), lines(old));
+    let total = new.len().max(old.len());
+    if old.is_empty() || total == 0 {
+        return None;
+    }
+    let common = new.intersection(&old).count();
+    Some(((total - common) * 100 / total) as u32)
+}
+
+/// Zero-width and bidi control characters, or a word mixing Latin letters with
+/// Cyrillic or Greek ones. Messages written in another script are not flagged.
+fn is_spam(message: &str) -> bool {
+    let invisible = message.chars().any(|c| {
+        matches!(c, '\u{200B}'..='\u{200F}' | '\u{202A}'..='\u{202E}' | '\u{2060}'..='\u{2064}' | '\u{2066}'..='\u{2069}' | '\u{FEFF}')
+    });
+    let homoglyph = message.split_whitespace().any(|word| {
+        let latin = word.chars().any(|c| c.is_ascii_alphabetic());
+        let lookalike = word.chars().any(|c| matches!(c, '\u{0370}'..='\u{03FF}' | '\u{0400}'..='\u{04FF}'));
+        latin && lookalike
+    });
+    invisible || homoglyph
+}
+
+/// Package named by a structured message (`upgpkg: foo 1.2-1`, `Update foo to 1.2`).
+fn named_package(message: &str) -> Option<String> {
+    let caps = NAMED_PACKAGE_RE.captures(message.trim())?;
+    let name = caps.get(1).or_else(|| caps.get(2))?.as_str().to_lowercase();
+    // "Update to 1.2" style messages have no name; "Update pkgver to 2" names a field.
+    (!matches!(name.as_str(), "to" | "pkgver" | "pkgrel" | "version" | "checksums" | "sums" | "deps" | "dependencies"))
+        .then_some(name)
+}
+
+/// Names this repo builds: the scanned name, its package base and every `pkgname`.
+fn own_names(ctx: &PackageContext) -> Vec<String> {
+    let mut names = vec![ctx.name.to_lowercase()];
+    if let Some(ref meta) = ctx.metadata {
+        names.push(meta.name.to_lowercase());
+        names.extend(meta.package_base.as_deref().map(str::to_lowercase));
+    }
+    if let Some(caps) = ctx.pkgbuild_content.as_deref().and_then(|p| PKGNAME_RE.captures(p)) {
+        names.extend(
+            caps[1]
+                .split_whitespace()
+                .map(|n| n.trim_matches(['"', '\'']).to_lowercase())
+                .filter(|n| !n.is_empty() && !n.contains('$')),
+        );
+    }
+    names
+}
+
+/// `foo` and `foo-git`, `python-foo` and `foo`: one name contains the other.
+fn related(a: &str, b: &str) -> bool {
+    a.contains(b) || b.contains(a)
+}
+
+/// Sibling package bases whose commits carry `newest`'s message within the window.
+/// Messages with version numbers (suites released in lockstep) and rebuilds are
+/// routine across many packages and not compared.
+fn campaign_packages(newest: &GitCommit, siblings: &[(String, GitCommit)]) -> Vec<String> {
+    let message = normalize(&newest.message);
+    if message.len() < CAMPAIGN_MIN_LEN
+        || message.chars().any(|c| c.is_ascii_digit())
+        || message.contains("rebuild")
+        || message.contains(".srcinfo")
+    {
+        return Vec::new();
+    }
+    let mut packages: Vec<String> = siblings
+        .iter()
+        .filter(|(_, c)| newest.timestamp.abs_diff(c.timestamp) <= CAMPAIGN_WINDOW_SECS && normalize(&c.message) == message)
+        .map(|(base, _)| base.clone())
+        .collect();
+    packages.sort();
+    packages.dedup();
+    packages
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commit(message: &str, ts: u64) -> GitCommit {
+        GitCommit {
+            author: "a".into(),
+            email: "a@example.com".into(),
+            timestamp: ts,
+            message: message.into(),
+            diff: None,
+        }
+    }
+
+    fn ctx(log: Vec<GitCommit>, new: &str, old: &str, siblings: Vec<(String, GitCommit)>) -> PackageContext {
+        PackageContext {
+            name: "foo".into(),
+            metadata: None,
+            pkgbuild_content: Some(new.into()),
+            install_script_content: None,
+            prior_pkgbuild_content: Some(old.into()),
+            git_log: log,
+            maintainer_packages: vec![],
+            github_stars: None,
+            github_not_found: false,
+            aur_comments: vec![],
+            artifact: None,
+            github_releases: None,
+            repo_files: Vec::new(),
+            trusted_domains: Vec::new(),
+            protected_names: Vec::new(),
+            sibling_commits: siblings,
+            maintainer_account: None,
+            upstream_build_files: Vec::new(),
+            history_rewrite: None,
+        }
+    }
+
+    fn ids(ctx: &PackageContext) -> Vec<String> {
+        let mut signals = Vec::new();
+        analyze(ctx, &mut signals);
+        signals.into_iter().map(|s| s.id).collect()
+    }
+
+    const OLD: &str = "pkgname=foo\npkgver=1.0\nsource=(foo.tar.gz)\npackage() {\n  install -Dm755 foo \"$pkgdir/usr/bin/foo\"\n}";
+    const NEW: &str = "pkgname=(foo foo-docs)\npkgver=1.0\nsource=(https://x.example/a.sh)\nprepare() {\n  sh a.sh\n}\npackage() {\n  cp -r . \"$pkgdir/opt\"\n}";
+
+    #[test]
+    fn trivial_message_on_rewrite() {
+        assert_eq!(ids(&ctx(vec![commit("Fix.", 10)], NEW, OLD, vec![])), ["T-COMMIT-MSG-MISMATCH"]);
+        assert!(ids(&ctx(vec![commit("Switch to upstream install script", 10)], NEW, OLD, vec![])).is_empty());
+        assert!(ids(&ctx(vec![commit("fix", 10)], OLD, OLD, vec![])).is_empty());
+    }
+
+    #[test]
+    fn spam_and_unrelated_package() {
+        assert_eq!(ids(&ctx(vec![commit("Update\u{200B} sources", 10)], OLD, OLD, vec![])), ["T-COMMIT-MSG-SPAM"]);
+        // Cyrillic "о" inside a Latin word; a fully Cyrillic message is fine
+        assert_eq!(ids(&ctx(vec![commit("Imprоve build", 10)], OLD, OLD, vec![])), ["T-COMMIT-MSG-SPAM"]);
+        assert!(ids(&ctx(vec![commit("Обновление до новой версии", 10)], OLD, OLD, vec![])).is_empty());
+
+        assert_eq!(ids(&ctx(vec![commit("upgpkg: discord-ptb 0.0.90-1", 10)], NEW, NEW, vec![])), ["T-COMMIT-MSG-OTHER-PKG"]);
+        for own in ["upgpkg: foo 1.0-1", "addpkg: foo-docs", "Update foo-git to 2.0", "Update to 1.2"] {
+            assert!(ids(&ctx(vec![commit(own, 10)], NEW, NEW, vec![])).is_empty(), "{own}");
+        }
+    }
+
+    #[test]
+    fn same_message_across_maintainer_packages() {
+        let day = 86400;
+        let message = "Improve packaging security";
+        let siblings = |ts: u64| -> Vec<(String, GitCommit)> {
+            ["bar", "baz", "qux"].iter().map(|b| (b.to_string(), commit(message, ts))).collect()
+        };
+        let log = vec![commit(message, 10 * day)];
+        assert_eq!(ids(&ctx(log.clone(), OLD, OLD, siblings(10 * day + 3600))), ["T-COMMIT-MSG-CAMPAIGN"]);
+        assert!(ids(&ctx(log, OLD, OLD, siblings(day))).is_empty());
+        let suite = vec![commit("Update to upstream release 24.08.1", 10 * day)];
+        assert!(ids(&ctx(suite, OLD, OLD, siblings(10 * day))).is_empty());
+    }
+}
@@ -1,3 +1,5 @@
+pub mod messages;
+
 use crate::features::Feature;
 use crate::shared::models::PackageContext;
 use crate::shared::scoring::{Signal, SignalCategory};
@@ -13,6 +15,55 @@ static NET_CONTENT_RE: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(r"(curl|wget|nc\s|ncat|socat|/dev/tcp|python.*socket|ruby.*socket)").unwrap()
 });
 
+static SOURCE_CHANGE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?m)^[+-]\s*(source(_\w+)?\s*\+?=|.*(https?|ftp|git\+\w+)://)").unwrap()
+});
+
+/// Throwaway-inbox providers; nobody maintaining a package long-term commits from these.
+const DISPOSABLE_EMAIL_DOMAINS: &[&str] = &[
+    "mailinator.com",
+    "guerrillamail.com",
+    "guerrillamail.net",
+    "guerrillamail.org",
+    "guerrillamailblock.com",
+    "sharklasers.com",
+    "grr.la",
+    "10minutemail.com",
+    "temp-mail.org",
+    "tempmail.com",
+    "yopmail.com",
+    "trashmail.com",
+    "getnada.com",
+    "dispostable.com",
+    "maildrop.cc",
+    "throwawaymail.com",
+    "mailnesia.com",
+    "fakeinbox.com",
+    "emailondeck.com",
+];
+
+/// Commits by a single prior identity needed before a switch away from it is notable.
+const ESTABLISHED_IDENTITY_COMMITS: usize = 3;
+
+/// A commit this soon after the out-of-date flag, with the flag still set, didn't fix anything.
+const OUT_OF_DATE_WINDOW_SECS: u64 = 15 * 60;
+
+/// Commits whose author date is older than their parent's before the history looks rewritten.
+const MAX_OUT_OF_ORDER_COMMITS: usize = 3;
+
+/// Clock skew tolerated before a commit date counts as being in the future.
+const FUTURE_SKEW_SECS: u64 = 86400;
+
+/// 2005-01-01, before the AUR existed.
+const AUR_EPOCH: u64 = 1_104_537_600;
+
+/// A commit-anchored signal keeps full points while its commit is this close to the
+/// package's last update.
+const DECAY_GRACE_DAYS: f64 = 30.0;
+
+/// After the grace period the points halve every this many days (about 5% after a year).
+const DECAY_HALF_LIFE_DAYS: f64 = 75.0;
+
 pub struct GitHistoryAnalysis;
 
 impl Feature for GitHistoryAnalysis {
@@ -37,6 +88,8 @@ impl Feature for GitHistoryAnalysis {
                 description: "Git history has only 1 commit".to_string(),
                 is_override_gate: false,
                 matched_line: None,
+                location: None,
+                emitted_by: String::new(),
             });
         }
 
@@ -48,68 +101,270 @@ impl Feature for GitHistoryAnalysis {
             .map(|m| m.first_submitted)
             .or_else(|| ctx.git_log.last().map(|c| c.timestamp));
 
-        if let Some(created) = creation_time {
-            if now > created {
-                let age_days = (now - created) / 86400;
-                if age_days < 7 {
-                    signals.push(Signal {
-                        id: "T-NEW-PACKAGE".to_string(),
-                        category: SignalCategory::Temporal,
-                        points: 25,
-                        description: format!("Package is very new ({age_days} days old)"),
-                        is_override_gate: false,
-                        matched_line: None,
-                    });
-                }
+        if let Some(created) = creation_time
+            && now > created
+        {
+            let age_days = (now - created) / 86400;
+            if age_days < 7 {
+                signals.push(Signal {
+                    id: "T-NEW-PACKAGE".to_string(),
+                    category: SignalCategory::Temporal,
+                    points: 25,
+                    description: format!("Package is very new ({age_days} days old)"),
+                    is_override_gate: false,
+                    matched_line: None,
+                    location: None,
+                    emitted_by: String::new(),
+                });
             }
         }
 
         // T-MALICIOUS-DIFF: latest commit introduces network-related code
-        if let Some(newest) = ctx.git_log.first() {
-            if let Some(ref diff) = newest.diff {
-                if NET_DIFF_RE.is_match(diff) {
-                    // Check if the prior PKGBUILD already had network code
-                    let has_prior_net = ctx
-                        .prior_pkgbuild_content
-                        .as_ref()
-                        .is_some_and(|content| NET_CONTENT_RE.is_match(content));
-
-                    if !has_prior_net {
-                        signals.push(Signal {
-                            id: "T-MALICIOUS-DIFF".to_string(),
-                            category: SignalCategory::Temporal,
-                            points: 55,
-                            description:
-                                "Latest commit introduces network code not present in prior history"
-                                    .to_string(),
-                            is_override_gate: false,
-                            matched_line: None,
-                        });
-                    }
-                }
+        if let Some(newest) = ctx.git_log.first()
+            && let Some(ref diff) = newest.diff
+            && NET_DIFF_RE.is_match(diff)
+        {
+            // Check if the prior PKGBUILD already had network code
+            let has_prior_net = ctx
+                .prior_pkgbuild_content
+                .as_ref()
+                .is_some_and(|content| NET_CONTENT_RE.is_match(content));
+
+            if !has_prior_net {
+                signals.push(Signal {
+                    id: "T-MALICIOUS-DIFF".to_string(),
+                    category: SignalCategory::Temporal,
+                    points: 55,
+                    description:
+                        "Latest commit introduces network code not present in prior history"
+                            .to_string(),
+                    is_override_gate: false,
+                    matched_line: None,
+                    location: None,
+                    emitted_by: String::new(),
+                });
             }
         }
 
-        // T-AUTHOR-CHANGE: different author between commits
-        if ctx.git_log.len() >= 2 {
-            let authors: Vec<&str> = ctx.git_log.iter().map(|c| c.author.as_str()).collect();
-            let unique: std::collections::HashSet<&&str> = authors.iter().collect();
-            if unique.len() > 1 {
-                signals.push(Signal {
+        // T-AUTHOR-CHANGE: different author between commits, weighted by how long ago
+        // the most recent change of author happened
+        let last_update = last_update(ctx);
+        if let Some(pair) = ctx.git_log.windows(2).find(|pair| pair[0].author != pair[1].author) {
+            signals.push(decayed(
+                Signal {
                     id: "T-AUTHOR-CHANGE".to_string(),
                     category: SignalCategory::Temporal,
                     points: 25,
                     description: "Git history shows multiple different authors".to_string(),
                     is_override_gate: false,
                     matched_line: None,
-                });
-            }
+                    location: None,
+                    emitted_by: String::new(),
+                },
+                pair[0].timestamp,
+                last_update,
+            ));
+        }
+
+        // T-DISPOSABLE-EMAIL: any commit authored from a throwaway inbox (newest first)
+        if let Some(commit) = ctx.git_log.iter().find(|c| is_disposable_email(&c.email)) {
+            signals.push(decayed(
+                Signal {
+                    id: "T-DISPOSABLE-EMAIL".to_string(),
+                    category: SignalCategory::Temporal,
+                    points: 40,
+                    description: "Commit author uses a disposable email domain".to_string(),
+                    is_override_gate: false,
+                    matched_line: Some(format!("{} <{}>", commit.author, commit.email)),
+                    location: None,
+                    emitted_by: String::new(),
+                },
+                commit.timestamp,
+                last_update,
+            ));
+        }
+
+        // T-IDENTITY-SWITCH-SOURCE: a long-standing identity is replaced by a fresh one
+        // in the same commit that changes source URLs
+        let maintainer = ctx.metadata.as_ref().and_then(|m| m.maintainer.as_deref());
+        if let Some(newest) = ctx.git_log.first()
+            && is_fresh_identity_switch(&ctx.git_log, maintainer)
+            && newest.diff.as_deref().is_some_and(|d| SOURCE_CHANGE_RE.is_match(d))
+        {
+            signals.push(Signal {
+                id: "T-IDENTITY-SWITCH-SOURCE".to_string(),
+                category: SignalCategory::Temporal,
+                points: 50,
+                description: format!(
+                    "Latest commit by a new identity ({}) changes source URLs after {} commits by {}",
+                    newest.email,
+                    ctx.git_log.len() - 1,
+                    ctx.git_log[1].email
+                ),
+                is_override_gate: false,
+                matched_line: None,
+                location: None,
+                emitted_by: String::new(),
+            });
+        }
+
+        // T-COMMIT-ANOMALY: commit timing that normal maintenance doesn't produce
+        let anomalies = commit_time_anomalies(ctx, now);
+        if !anomalies.is_empty() {
+            signals.push(Signal {
+                id: "T-COMMIT-ANOMALY".to_string(),
+                category: SignalCategory::Temporal,
+                points: 20,
+                description: format!("Anomalous commit timing: {}", anomalies.join("; ")),
+                is_override_gate: false,
+                matched_line: None,
+                location: None,
+                emitted_by: String::new(),
+            });
+        }
+
+        // T-HISTORY-REWRITE: the remote no longer has commits the cached clone had
+        if let Some(ref rewrite) = ctx.history_rewrite {
+            let short = |hash: &str| hash.chars().take(12).collect::<String>();
+            signals.push(Signal {
+                id: "T-HISTORY-REWRITE".to_string(),
+                category: SignalCategory::Temporal,
+                points: 70,
+                description: format!(
+                    "AUR git history was rewritten: commit {} is gone from the remote (force push or deleted commits)",
+                    short(&rewrite.old_head)
+                ),
+                is_override_gate: false,
+                matched_line: Some(format!(
+                    "{} -> {}, stale clone kept at {}",
+                    short(&rewrite.old_head),
+                    short(&rewrite.new_head),
+                    rewrite.kept_at
+                )),
+                location: None,
+                emitted_by: String::new(),
+            });
         }
 
+        messages::analyze(ctx, &mut signals);
+
         signals
     }
 }
 
+/// When the package was last updated: AUR `last_modified`, or the newest commit without metadata.
+fn last_update(ctx: &PackageContext) -> u64 {
+    ctx.metadata
+        .as_ref()
+        .map(|m| m.last_modified)
+        .filter(|&t| t > 0)
+        .or_else(|| ctx.git_log.first().map(|c| c.timestamp))
+        .unwrap_or(0)
+}
+
+/// Scale a signal anchored to one commit by how long before the package's last update
+/// that commit was: full points within `DECAY_GRACE_DAYS`, then halving every
+/// `DECAY_HALF_LIFE_DAYS`, never below 1. A package that kept being maintained after
+/// the event has had time to show whether it mattered.
+fn decayed(mut signal: Signal, event: u64, last_update: u64) -> Signal {
+    let age_days = last_update.saturating_sub(event) / 86400;
+    let factor = decay_factor(age_days);
+    if factor < 1.0 {
+        signal.points = ((signal.points as f64 * factor).round() as u32).max(1);
+        signal.description = format!("{} ({age_days} days before the last update)", signal.description);
+    }
+    signal
+}
+
+fn decay_factor(age_days: u64) -> f64 {
+    let past_grace = age_days as f64 - DECAY_GRACE_DAYS;
+    if past_grace <= 0.0 {
+        1.0
+    } else {
+        0.5f64.powf(past_grace / DECAY_HALF_LIFE_DAYS)
+    }
+}
+
+/// Reasons the commit timestamps look off: a commit right after the package was flagged
+/// out-of-date that left the flag in place, more than `MAX_OUT_OF_ORDER_COMMITS` commits
+/// dated before their parent (rebased or force-pushed history keeps the original author
+/// dates), or dates in the future or before the package existed.
+fn commit_time_anomalies(ctx: &PackageContext, now: u64) -> Vec<String> {
+    let mut reasons = Vec::new();
+    let metadata = ctx.metadata.as_ref();
+
+    if let Some(flagged) = metadata.and_then(|m| m.out_of_date)
+        && ctx
+            .git_log
+            .iter()
+            .any(|c| c.timestamp >= flagged && c.timestamp - flagged <= OUT_OF_DATE_WINDOW_SECS)
+    {
+        reasons.push("commit pushed within minutes of the out-of-date flag, which is still set".to_string());
+    }
+
+    let out_of_order = ctx
+        .git_log
+        .windows(2)
+        .filter(|pair| pair[0].timestamp < pair[1].timestamp)
+        .count();
+    if out_of_order > MAX_OUT_OF_ORDER_COMMITS {
+        reasons.push(format!("{out_of_order} commits dated before their parent (rewritten history)"));
+    }
+
+    if ctx.git_log.iter().any(|c| c.timestamp > now + FUTURE_SKEW_SECS) {
+        reasons.push("commit dated in the future".to_string());
+    }
+
+    // Allow a day of slack: the first commit is pushed moments before the RPC records it.
+    let earliest = metadata
+        .map(|m| m.first_submitted.saturating_sub(86400))
+        .unwrap_or(0)
+        .max(AUR_EPOCH);
+    if ctx.git_log.iter().any(|c| c.timestamp > 0 && c.timestamp < earliest) {
+        reasons.push("commit dated before the package was submitted".to_string());
+    }
+
+    reasons
+}
+
+/// Email domain (lowercased), or None if the address has no `@`.
+fn email_domain(email: &str) -> Option<String> {
+    email.rsplit_once('@').map(|(_, d)| d.trim().to_lowercase())
+}
+
+fn is_disposable_email(email: &str) -> bool {
+    email_domain(email).is_some_and(|domain| {
+        DISPOSABLE_EMAIL_DOMAINS
+            .iter()
+            .any(|d| domain == *d || domain.ends_with(&format!(".{d}")))
+    })
+}
+
+/// True when every earlier commit (at least `ESTABLISHED_IDENTITY_COMMITS`) shares one
+/// email, the newest commit uses an email never seen before, and the new identity
+/// doesn't carry the AUR maintainer's name (a maintainer changing address usually does).
+fn is_fresh_identity_switch(git_log: &[crate::shared::models::GitCommit], maintainer: Option<&str>) -> bool {
+    let Some((newest, prior)) = git_log.split_first() else {
+        return false;
+    };
+    if prior.len() < ESTABLISHED_IDENTITY_COMMITS {
+        return false;
+    }
+    let established = prior[0].email.to_lowercase();
+    if established.is_empty() || prior.iter().any(|c| c.email.to_lowercase() != established) {
+        return false;
+    }
+    let fresh = newest.email.to_lowercase();
+    if fresh.is_empty() || fresh == established {
+        return false;
+    }
+    let names_maintainer = maintainer.is_some_and(|m| {
+        let m = m.to_lowercase();
+        fresh.contains(&m) || newest.author.to_lowercase().contains(&m)
+    });
+    !names_maintainer
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -122,7 +377,9 @@ mod tests {
     fn make_commit(author: &str, ts: u64, diff: Option<&str>) -> GitCommit {
         GitCommit {
             author: author.into(),
+            email: format!("{author}@example.com"),
             timestamp: ts,
+            message: String::new(),
             diff: diff.map(|s| s.to_string()),
         }
     }
@@ -141,6 +398,15 @@ mod tests {
             github_stars: None,
             github_not_found: false,
             aur_comments: vec![],
+            artifact: None,
+            github_releases: None,
+            repo_files: Vec::new(),
+            trusted_domains: Vec::new(),
+            protected_names: Vec::new(),
+            sibling_commits: Vec::new(),
+            maintainer_account: None,
+            upstream_build_files: Vec::new(),
+            history_rewrite: None,
         };
         let ids: Vec<String> = GitHistoryAnalysis.analyze(&ctx).iter().map(|s| s.id.clone()).collect();
         assert!(has(&ids, "T-SINGLE-COMMIT"));
@@ -163,6 +429,15 @@ mod tests {
             github_stars: None,
             github_not_found: false,
             aur_comments: vec![],
+            artifact: None,
+            github_releases: None,
+            repo_files: Vec::new(),
+            trusted_domains: Vec::new(),
+            protected_names: Vec::new(),
+            sibling_commits: Vec::new(),
+            maintainer_account: None,
+            upstream_build_files: Vec::new(),
+            history_rewrite: None,
         };
         let ids: Vec<String> = GitHistoryAnalysis.analyze(&ctx).iter().map(|s| s.id.clone()).collect();
         assert!(has(&ids, "T-MALICIOUS-DIFF"));
@@ -185,6 +460,15 @@ mod tests {
             github_stars: None,
             github_not_found: false,
             aur_comments: vec![],
+            artifact: None,
+            github_releases: None,
+            repo_files: Vec::new(),
+            trusted_domains: Vec::new(),
+            protected_names: Vec::new(),
+            sibling_commits: Vec::new(),
+            maintainer_account: None,
+            upstream_build_files: Vec::new(),
+            history_rewrite: None,
         };
         let ids: Vec<String> = GitHistoryAnalysis.analyze(&ctx).iter().map(|s| s.id.clone()).collect();
         assert!(has(&ids, "T-AUTHOR-CHANGE"));
@@ -207,8 +491,218 @@ mod tests {
             github_stars: None,
             github_not_found: false,
             aur_comments: vec![],
+            artifact: None,
+            github_releases: None,
+            repo_files: Vec::new(),
+            trusted_domains: Vec::new(),
+            protected_names: Vec::new(),
+            sibling_commits: Vec::new(),
+            maintainer_account: None,
+            upstream_build_files: Vec::new(),
+            history_rewrite: None,
         };
         let ids: Vec<String> = GitHistoryAnalysis.analyze(&ctx).iter().map(|s| s.id.clone()).collect();
         assert!(!has(&ids, "T-MALICIOUS-DIFF"), "Should not flag when prior PKGBUILD already had network code");
     }
+
+    fn ctx_with_log(git_log: Vec<GitCommit>, maintainer: Option<&str>) -> PackageContext {
+        use crate::shared::models::AurPackage;
+        PackageContext {
+            name: "test".into(),
+            metadata: maintainer.map(|m| AurPackage {
+                name: "test".into(),
+                package_base: None,
+                url: None,
+                num_votes: 10,
+                popularity: 0.1,
+                out_of_date: None,
+                maintainer: Some(m.into()),
+                submitter: Some(m.into()),
+                version: String::new(),
+                first_submitted: 0,
+                last_modified: 0,
+                license: None,
+                description: None,
+                depends: Vec::new(),
+                opt_depends: Vec::new(),
+                make_depends: Vec::new(),
+                check_depends: Vec::new(),
+            }),
+            pkgbuild_content: None,
+            install_script_content: None,
+            prior_pkgbuild_content: None,
+            git_log,
+            maintainer_packages: vec![],
+            github_stars: None,
+            github_not_found: false,
+            aur_comments: vec![],
+            artifact: None,
+            github_releases: None,
+            repo_files: Vec::new(),
+            trusted_domains: Vec::new(),
+            protected_names: Vec::new(),
+            sibling_commits: Vec::new(),
+            maintainer_account: None,
+            upstream_build_files: Vec::new(),
+            history_rewrite: None,
+        }
+    }
+
+    fn commit_from(author: &str, email: &str, ts: u64, diff: Option<&str>) -> GitCommit {
+        GitCommit {
+            author: author.into(),
+            email: email.into(),
+            timestamp: ts,
+            message: String::new(),
+            diff: diff.map(|s| s.to_string()),
+        }
+    }
+
+    const SOURCE_DIFF: &str = "-source=(\"https://github.com/foo/foo/archive/v1.0.tar.gz\")\n+source=(\"https://foo-mirror.example/foo-1.0.tar.gz\")";
+
+    fn established_history(ts: u64) -> Vec<GitCommit> {
+        (1..=4)
+            .map(|i| commit_from("Alice", "alice@posteo.de", ts - 86400 * 30 * i, None))
+            .collect()
+    }
+
+    #[test]
+    fn decay_factor_curve() {
+        assert_eq!(decay_factor(0), 1.0);
+        assert_eq!(decay_factor(30), 1.0);
+        assert!((decay_factor(105) - 0.5).abs() < 1e-9);
+        assert!(decay_factor(365) < 0.06);
+    }
+
+    #[test]
+    fn old_author_change_decays() {
+        let ts = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        let points = |log: Vec<GitCommit>| {
+            GitHistoryAnalysis
+                .analyze(&ctx_with_log(log, None))
+                .into_iter()
+                .find(|s| s.id == "T-AUTHOR-CHANGE")
+                .map(|s| s.points)
+        };
+
+        // The handoff is the latest update: full points.
+        let recent = vec![make_commit("new", ts, None), make_commit("old", ts - 86400 * 400, None)];
+        assert_eq!(points(recent), Some(25));
+
+        // Handed off two years ago, maintained by the new author since.
+        let stale = vec![
+            make_commit("new", ts, None),
+            make_commit("new", ts - 86400 * 700, None),
+            make_commit("old", ts - 86400 * 900, None),
+        ];
+        assert_eq!(points(stale), Some(1));
+    }
+
+    fn analyze_ids(ctx: &PackageContext) -> Vec<String> {
+        GitHistoryAnalysis.analyze(ctx).iter().map(|s| s.id.clone()).collect()
+    }
+
+    #[test]
+    fn disposable_email_flagged() {
+        let ts = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        let log = vec![commit_from("x", "x8f2@mailinator.com", ts - 3600, None)];
+        assert!(has(&analyze_ids(&ctx_with_log(log, None)), "T-DISPOSABLE-EMAIL"));
+    }
+
+    #[test]
+    fn disposable_email_subdomain_flagged() {
+        assert!(is_disposable_email("a@mx.guerrillamail.com"));
+        assert!(!is_disposable_email("a@notguerrillamail.com"));
+        assert!(!is_disposable_email("alice@posteo.de"));
+    }
+
+    #[test]
+    fn identity_switch_with_source_change() {
+        let ts = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        let mut log = vec![commit_from("pkgfixer", "pkgfixer@proton.me", ts - 3600, Some(SOURCE_DIFF))];
+        log.extend(established_history(ts));
+        assert!(has(&analyze_ids(&ctx_with_log(log, Some("alice"))), "T-IDENTITY-SWITCH-SOURCE"));
+    }
+
+    #[test]
+    fn identity_switch_without_source_change_not_flagged() {
+        let ts = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        let diff = "-pkgrel=1\n+pkgrel=2";
+        let mut log = vec![commit_from("pkgfixer", "pkgfixer@proton.me", ts - 3600, Some(diff))];
+        log.extend(established_history(ts));
+        assert!(!has(&analyze_ids(&ctx_with_log(log, Some("alice"))), "T-IDENTITY-SWITCH-SOURCE"));
+    }
+
+    #[test]
+    fn maintainer_changing_address_not_flagged() {
+        let ts = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        let mut log = vec![commit_from("Alice", "alice@newmail.org", ts - 3600, Some(SOURCE_DIFF))];
+        log.extend(established_history(ts));
+        assert!(!has(&analyze_ids(&ctx_with_log(log, Some("alice"))), "T-IDENTITY-SWITCH-SOURCE"));
+    }
+
+    #[test]
+    fn short_history_not_established() {
+        let ts = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        let log = vec![
+            commit_from("pkgfixer", "pkgfixer@proton.me", ts - 3600, Some(SOURCE_DIFF)),
+            commit_from("Alice", "alice@posteo.de", ts - 86400 * 30, None),
+        ];
+        assert!(!has(&analyze_ids(&ctx_with_log(log, None)), "T-IDENTITY-SWITCH-SOURCE"));
+    }
+
+    #[test]
+    fn commit_right_after_out_of_date_flag() {
+        let ts = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        let mut log = vec![commit_from("Alice", "alice@posteo.de", ts - 3600 + 300, None)];
+        log.extend(established_history(ts));
+        let mut ctx = ctx_with_log(log, Some("alice"));
+        ctx.metadata.as_mut().unwrap().out_of_date = Some(ts - 3600);
+        assert!(has(&analyze_ids(&ctx), "T-COMMIT-ANOMALY"));
+
+        ctx.metadata.as_mut().unwrap().out_of_date = Some(ts - 7200);
+        assert!(!has(&analyze_ids(&ctx), "T-COMMIT-ANOMALY"));
+    }
+
+    #[test]
+    fn rewritten_history_flagged() {
+        let ts = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        // Newest first, but every other commit predates its parent.
+        let log: Vec<GitCommit> = [10, 50, 20, 60, 30, 70, 40, 80, 45, 90]
+            .iter()
+            .map(|d| commit_from("Alice", "alice@posteo.de", ts - 86400 * d, None))
+            .collect();
+        assert!(has(&analyze_ids(&ctx_with_log(log, None)), "T-COMMIT-ANOMALY"));
+        assert!(!has(&analyze_ids(&ctx_with_log(established_history(ts), None)), "T-COMMIT-ANOMALY"));
+    }
+
+    #[test]
+    fn history_rewrite_flagged() {
+        let ts = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        let mut ctx = ctx_with_log(established_history(ts), None);
+        assert!(!has(&analyze_ids(&ctx), "T-HISTORY-REWRITE"));
+
+        ctx.history_rewrite = Some(crate::shared::models::HistoryRewrite {
+            old_head: "0123456789abcdef0123".into(),
+            new_head: "fedcba9876543210fedc".into(),
+            kept_at: "/home/u/.cache/traur/rewritten/foo-1700000000".into(),
+            detected_at: ts,
+        });
+        let signals = GitHistoryAnalysis.analyze(&ctx);
+        let signal = signals.iter().find(|s| s.id == "T-HISTORY-REWRITE").unwrap();
+        assert!(signal.description.contains("commit 0123456789ab is gone"), "{}", signal.description);
+        assert!(signal.matched_line.as_deref().unwrap().ends_with("kept at /home/u/.cache/traur/rewritten/foo-1700000000"));
+    }
+
+    #[test]
+    fn future_and_backdated_commits_flagged() {
+        let ts = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        let log = vec![commit_from("Alice", "alice@posteo.de", ts + 86400 * 400, None)];
+        assert!(has(&analyze_ids(&ctx_with_log(log, None)), "T-COMMIT-ANOMALY"));
+
+        let log = vec![commit_from("Alice", "alice@posteo.de", ts - 86400 * 90, None)];
+        let mut ctx = ctx_with_log(log, Some("alice"));
+        ctx.metadata.as_mut().unwrap().first_submitted = ts - 86400 * 10;
+        assert!(has(&analyze_ids(&ctx), "T-COMMIT-ANOMALY"));
+    }
 }
@@ -19,6 +19,8 @@ impl Feature for GitHubStars {
                     .metadata
                     .as_ref()
                     .and_then(|m| m.url.clone()),
+                location: None,
+                emitted_by: String::new(),
             });
             return signals;
         }
@@ -32,6 +34,8 @@ impl Feature for GitHubStars {
                     description: "Upstream GitHub repo has 0 stars".to_string(),
                     is_override_gate: false,
                     matched_line: None,
+                    location: None,
+                    emitted_by: String::new(),
                 });
             } else if stars < 10 {
                 signals.push(Signal {
@@ -41,6 +45,8 @@ impl Feature for GitHubStars {
                     description: format!("Upstream GitHub repo has very few stars ({stars})"),
                     is_override_gate: false,
                     matched_line: None,
+                    location: None,
+                    emitted_by: String::new(),
                 });
             }
         }
@@ -69,6 +75,15 @@ mod tests {
             github_stars: stars,
             github_not_found: not_found,
             aur_comments: vec![],
+            artifact: None,
+            github_releases: None,
+            repo_files: Vec::new(),
+            trusted_domains: Vec::new(),
+            protected_names: Vec::new(),
+            sibling_commits: Vec::new(),
+            maintainer_account: None,
+            upstream_build_files: Vec::new(),
+            history_rewrite: None,
         };
         GitHubStars
             .analyze(&ctx)
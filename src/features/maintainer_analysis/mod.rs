@@ -1,8 +1,15 @@
 use crate::features::Feature;
-use crate::shared::models::PackageContext;
+use crate::shared::models::{AurAccount, PackageContext};
 use crate::shared::scoring::{Signal, SignalCategory};
 use std::time::{SystemTime, UNIX_EPOCH};
 
+/// An account registered at most this long before the package was submitted is new to it.
+const NEW_ACCOUNT_WINDOW_SECS: u64 = 14 * 86400;
+
+/// Packages submitted longer ago than this are past the point where a fresh account
+/// says anything about them.
+const NEW_ACCOUNT_MAX_PACKAGE_AGE_SECS: u64 = 180 * 86400;
+
 pub struct MaintainerAnalysis;
 
 impl Feature for MaintainerAnalysis {
@@ -33,6 +40,8 @@ impl Feature for MaintainerAnalysis {
                     ),
                     is_override_gate: false,
                     matched_line: None,
+                    location: None,
+                    emitted_by: String::new(),
                 });
             } else {
                 signals.push(Signal {
@@ -42,6 +51,8 @@ impl Feature for MaintainerAnalysis {
                     description: "Maintainer has only 1 package".to_string(),
                     is_override_gate: false,
                     matched_line: None,
+                    location: None,
+                    emitted_by: String::new(),
                 });
             }
         }
@@ -63,13 +74,60 @@ impl Feature for MaintainerAnalysis {
                 ),
                 is_override_gate: false,
                 matched_line: None,
+                location: None,
+                emitted_by: String::new(),
             });
         }
 
+        if let Some(ref account) = ctx.maintainer_account {
+            account_signals(account, &ctx.name, meta.first_submitted, now, &mut signals);
+        }
+
         signals
     }
 }
 
+/// Signals from the maintainer's account page: an account registered shortly before
+/// the package was submitted (worse for a -bin package, the usual shape of a trojaned
+/// repackage of a popular tool), or a suspended account.
+fn account_signals(account: &AurAccount, name: &str, first_submitted: u64, now: u64, signals: &mut Vec<Signal>) {
+    if let Some(registered_at) = account.registered_at {
+        let lead = first_submitted.saturating_sub(registered_at);
+        if lead <= NEW_ACCOUNT_WINDOW_SECS
+            && now.saturating_sub(first_submitted) <= NEW_ACCOUNT_MAX_PACKAGE_AGE_SECS
+        {
+            let bin = name.ends_with("-bin");
+            signals.push(Signal {
+                id: "B-MAINTAINER-ACCOUNT-NEW".to_string(),
+                category: SignalCategory::Behavioral,
+                points: if bin { 50 } else { 30 },
+                description: format!(
+                    "Maintainer account was registered {} days before submitting this {}package",
+                    lead / 86400,
+                    if bin { "-bin " } else { "" }
+                ),
+                is_override_gate: false,
+                matched_line: None,
+                location: None,
+                emitted_by: String::new(),
+            });
+        }
+    }
+
+    if account.suspended {
+        signals.push(Signal {
+            id: "B-MAINTAINER-SUSPENDED".to_string(),
+            category: SignalCategory::Behavioral,
+            points: 40,
+            description: "Maintainer's AUR account is suspended".to_string(),
+            is_override_gate: false,
+            matched_line: None,
+            location: None,
+            emitted_by: String::new(),
+        });
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -85,9 +143,15 @@ mod tests {
             out_of_date: None,
             maintainer: Some("testuser".into()),
             submitter: None,
+            version: String::new(),
             first_submitted,
             last_modified: first_submitted,
             license: None,
+            description: None,
+            depends: Vec::new(),
+            opt_depends: Vec::new(),
+            make_depends: Vec::new(),
+            check_depends: Vec::new(),
         }
     }
 
@@ -114,6 +178,15 @@ mod tests {
             github_stars: None,
             github_not_found: false,
             aur_comments: vec![],
+            artifact: None,
+            github_releases: None,
+            repo_files: Vec::new(),
+            trusted_domains: Vec::new(),
+            protected_names: Vec::new(),
+            sibling_commits: Vec::new(),
+            maintainer_account: None,
+            upstream_build_files: Vec::new(),
+            history_rewrite: None,
         };
         let ids: Vec<String> = MaintainerAnalysis.analyze(&ctx).iter().map(|s| s.id.clone()).collect();
         assert!(has(&ids, "B-MAINTAINER-NEW"));
@@ -134,6 +207,15 @@ mod tests {
             github_stars: None,
             github_not_found: false,
             aur_comments: vec![],
+            artifact: None,
+            github_releases: None,
+            repo_files: Vec::new(),
+            trusted_domains: Vec::new(),
+            protected_names: Vec::new(),
+            sibling_commits: Vec::new(),
+            maintainer_account: None,
+            upstream_build_files: Vec::new(),
+            history_rewrite: None,
         };
         let ids: Vec<String> = MaintainerAnalysis.analyze(&ctx).iter().map(|s| s.id.clone()).collect();
         assert!(has(&ids, "B-MAINTAINER-SINGLE"));
@@ -158,8 +240,39 @@ mod tests {
             github_stars: None,
             github_not_found: false,
             aur_comments: vec![],
+            artifact: None,
+            github_releases: None,
+            repo_files: Vec::new(),
+            trusted_domains: Vec::new(),
+            protected_names: Vec::new(),
+            sibling_commits: Vec::new(),
+            maintainer_account: None,
+            upstream_build_files: Vec::new(),
+            history_rewrite: None,
         };
         let ids: Vec<String> = MaintainerAnalysis.analyze(&ctx).iter().map(|s| s.id.clone()).collect();
         assert!(has(&ids, "B-MAINTAINER-BATCH"));
     }
+
+    #[test]
+    fn account_registered_just_before_submission() {
+        let ts = now();
+        let fresh = AurAccount { registered_at: Some(ts - 5 * 86400), suspended: false };
+        let mut signals = Vec::new();
+        account_signals(&fresh, "tool-bin", ts - 3 * 86400, ts, &mut signals);
+        assert_eq!(signals.len(), 1);
+        assert_eq!(signals[0].id, "B-MAINTAINER-ACCOUNT-NEW");
+        assert_eq!(signals[0].points, 50);
+        assert!(signals[0].description.contains("2 days before"), "{}", signals[0].description);
+
+        let mut signals = Vec::new();
+        account_signals(&fresh, "tool", ts - 3 * 86400, ts, &mut signals);
+        assert_eq!(signals[0].points, 30);
+
+        let veteran = AurAccount { registered_at: Some(ts - 400 * 86400), suspended: true };
+        let mut signals = Vec::new();
+        account_signals(&veteran, "tool-bin", ts - 3 * 86400, ts, &mut signals);
+        let ids: Vec<String> = signals.iter().map(|s| s.id.clone()).collect();
+        assert_eq!(ids, ["B-MAINTAINER-SUSPENDED"]);
+    }
 }
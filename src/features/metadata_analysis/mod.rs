@@ -1,6 +1,8 @@
 use crate::features::Feature;
-use crate::shared::models::PackageContext;
+use crate::shared::age_cohorts::{self, AgeCohorts, MAX_LOW_VOTES};
+use crate::shared::models::{AurPackage, PackageContext};
 use crate::shared::scoring::{Signal, SignalCategory};
+use std::time::{SystemTime, UNIX_EPOCH};
 
 pub struct MetadataAnalysis;
 
@@ -9,98 +11,136 @@ impl Feature for MetadataAnalysis {
         let Some(ref meta) = ctx.metadata else {
             return Vec::new();
         };
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+        analyze_with_cohorts(meta, age_cohorts::current(), now)
+    }
+}
 
-        let mut signals = Vec::new();
-
-        // Vote signals
-        if meta.num_votes == 0 {
-            signals.push(Signal {
-                id: "M-VOTES-ZERO".to_string(),
-                category: SignalCategory::Metadata,
-                points: 30,
-                description: "Package has zero votes".to_string(),
-                is_override_gate: false,
-                matched_line: None,
-            });
-        } else if meta.num_votes < 5 {
-            signals.push(Signal {
-                id: "M-VOTES-LOW".to_string(),
-                category: SignalCategory::Metadata,
-                points: 20,
-                description: format!("Package has very few votes ({})", meta.num_votes),
-                is_override_gate: false,
-                matched_line: None,
-            });
-        }
+/// Metadata signals, with votes and popularity judged against AUR packages of the
+/// same age: points shrink by the share of that cohort with zero votes or popularity,
+/// and votes are only low when they are in the cohort's bottom quarter.
+fn analyze_with_cohorts(meta: &AurPackage, cohorts: &AgeCohorts, now: u64) -> Vec<Signal> {
+    let age = age_cohorts::age_days(meta.first_submitted, now);
+    let cohort = cohorts.cohort(age);
+    let mut signals = Vec::new();
 
-        // Popularity
-        if meta.popularity == 0.0 {
-            signals.push(Signal {
-                id: "M-POP-ZERO".to_string(),
-                category: SignalCategory::Metadata,
-                points: 25,
-                description: "Popularity is 0 (no recent usage)".to_string(),
-                is_override_gate: false,
-                matched_line: None,
-            });
-        }
+    // Vote signals
+    let zero_votes_points = scaled(30, cohort.zero_votes_share);
+    if meta.num_votes == 0 && zero_votes_points > 0 {
+        signals.push(Signal {
+            id: "M-VOTES-ZERO".to_string(),
+            category: SignalCategory::Metadata,
+            points: zero_votes_points,
+            description: with_share("Package has zero votes", cohort.zero_votes_share),
+            is_override_gate: false,
+            matched_line: None,
+            location: None,
+            emitted_by: String::new(),
+        });
+    } else if meta.num_votes > 0 && meta.num_votes < cohort.low_votes.min(MAX_LOW_VOTES) {
+        signals.push(Signal {
+            id: "M-VOTES-LOW".to_string(),
+            category: SignalCategory::Metadata,
+            points: 20,
+            description: format!("Package has very few votes ({}) for its age ({age} days)", meta.num_votes),
+            is_override_gate: false,
+            matched_line: None,
+            location: None,
+            emitted_by: String::new(),
+        });
+    }
 
-        // Orphaned
-        if meta.maintainer.is_none() {
-            signals.push(Signal {
-                id: "M-NO-MAINTAINER".to_string(),
-                category: SignalCategory::Metadata,
-                points: 20,
-                description: "Package is orphaned (no maintainer)".to_string(),
-                is_override_gate: false,
-                matched_line: None,
-            });
-        }
+    // Popularity
+    let zero_pop_points = scaled(25, cohort.zero_popularity_share);
+    if meta.popularity == 0.0 && zero_pop_points > 0 {
+        signals.push(Signal {
+            id: "M-POP-ZERO".to_string(),
+            category: SignalCategory::Metadata,
+            points: zero_pop_points,
+            description: with_share("Popularity is 0 (no recent usage)", cohort.zero_popularity_share),
+            is_override_gate: false,
+            matched_line: None,
+            location: None,
+            emitted_by: String::new(),
+        });
+    }
 
-        // Missing URL
-        if meta.url.as_ref().is_none_or(|u| u.is_empty()) {
-            signals.push(Signal {
-                id: "M-NO-URL".to_string(),
-                category: SignalCategory::Metadata,
-                points: 15,
-                description: "No upstream URL provided".to_string(),
-                is_override_gate: false,
-                matched_line: None,
-            });
-        }
+    // Orphaned
+    if meta.maintainer.is_none() {
+        signals.push(Signal {
+            id: "M-NO-MAINTAINER".to_string(),
+            category: SignalCategory::Metadata,
+            points: 20,
+            description: "Package is orphaned (no maintainer)".to_string(),
+            is_override_gate: false,
+            matched_line: None,
+            location: None,
+            emitted_by: String::new(),
+        });
+    }
 
-        // Missing license
-        if meta.license.as_ref().is_none_or(|l| l.is_empty()) {
-            signals.push(Signal {
-                id: "M-NO-LICENSE".to_string(),
-                category: SignalCategory::Metadata,
-                points: 10,
-                description: "No license specified".to_string(),
-                is_override_gate: false,
-                matched_line: None,
-            });
-        }
+    // Missing URL
+    if meta.url.as_ref().is_none_or(|u| u.is_empty()) {
+        signals.push(Signal {
+            id: "M-NO-URL".to_string(),
+            category: SignalCategory::Metadata,
+            points: 15,
+            description: "No upstream URL provided".to_string(),
+            is_override_gate: false,
+            matched_line: None,
+            location: None,
+            emitted_by: String::new(),
+        });
+    }
 
-        // Out of date
-        if meta.out_of_date.is_some() {
-            signals.push(Signal {
-                id: "M-OUT-OF-DATE".to_string(),
-                category: SignalCategory::Metadata,
-                points: 5,
-                description: "Package is flagged as out of date".to_string(),
-                is_override_gate: false,
-                matched_line: None,
-            });
-        }
+    // Missing license
+    if meta.license.as_ref().is_none_or(|l| l.is_empty()) {
+        signals.push(Signal {
+            id: "M-NO-LICENSE".to_string(),
+            category: SignalCategory::Metadata,
+            points: 10,
+            description: "No license specified".to_string(),
+            is_override_gate: false,
+            matched_line: None,
+            location: None,
+            emitted_by: String::new(),
+        });
+    }
+
+    // Out of date
+    if meta.out_of_date.is_some() {
+        signals.push(Signal {
+            id: "M-OUT-OF-DATE".to_string(),
+            category: SignalCategory::Metadata,
+            points: 5,
+            description: "Package is flagged as out of date".to_string(),
+            is_override_gate: false,
+            matched_line: None,
+            location: None,
+            emitted_by: String::new(),
+        });
+    }
+
+    signals
+}
+
+/// `points` less the share of the cohort that would fire the signal too.
+fn scaled(points: u32, share: f64) -> u32 {
+    (points as f64 * (1.0 - share.clamp(0.0, 1.0))).round() as u32
+}
 
-        signals
+/// `description`, plus how common that is among packages of the same age.
+fn with_share(description: &str, share: f64) -> String {
+    if share > 0.0 {
+        format!("{description}, like {:.0}% of AUR packages of similar age", share * 100.0)
+    } else {
+        description.to_string()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::shared::models::AurPackage;
 
     fn make_meta(votes: u32, popularity: f64, maintainer: Option<&str>, url: Option<&str>, license: Option<Vec<String>>, out_of_date: Option<u64>) -> AurPackage {
         let now = std::time::SystemTime::now()
@@ -116,26 +156,26 @@ mod tests {
             out_of_date,
             maintainer: maintainer.map(|s| s.to_string()),
             submitter: None,
-            first_submitted: now - 86400, // 1 day ago
+            version: String::new(),
+            first_submitted: now - 2 * 365 * 86400, // 2 years ago: the absolute thresholds
             last_modified: now,
             license,
+            description: None,
+            depends: Vec::new(),
+            opt_depends: Vec::new(),
+            make_depends: Vec::new(),
+            check_depends: Vec::new(),
         }
     }
 
+    /// IDs fired with the default cohorts, which don't depend on a bench snapshot.
     fn analyze_meta(meta: AurPackage) -> Vec<String> {
-        let ctx = PackageContext {
-            name: "test-pkg".into(),
-            metadata: Some(meta),
-            pkgbuild_content: None,
-            install_script_content: None,
-            prior_pkgbuild_content: None,
-            git_log: vec![],
-            maintainer_packages: vec![],
-            github_stars: None,
-            github_not_found: false,
-            aur_comments: vec![],
-        };
-        MetadataAnalysis.analyze(&ctx).iter().map(|s| s.id.clone()).collect()
+        signals(meta).iter().map(|s| s.id.clone()).collect()
+    }
+
+    fn signals(meta: AurPackage) -> Vec<Signal> {
+        let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs();
+        analyze_with_cohorts(&meta, &AgeCohorts::default(), now)
     }
 
     fn has(ids: &[String], id: &str) -> bool {
@@ -189,4 +229,33 @@ mod tests {
         let ids = analyze_meta(make_meta(100, 5.0, Some("user"), Some("https://example.com"), Some(vec!["MIT".into()]), None));
         assert!(ids.is_empty(), "Healthy package should trigger no signals, got: {ids:?}");
     }
+
+    #[test]
+    fn new_package_votes_and_popularity_are_eased() {
+        let mut meta = make_meta(0, 0.0, Some("user"), Some("https://example.com"), Some(vec!["MIT".into()]), None);
+        let old = signals(meta.clone());
+        assert_eq!(old.iter().map(|s| s.points).collect::<Vec<_>>(), [30, 25]);
+
+        meta.first_submitted = meta.last_modified - 14 * 86400;
+        let new = signals(meta.clone());
+        // 70% of packages under 30 days have none
+        assert_eq!(new.iter().map(|s| s.points).collect::<Vec<_>>(), [9, 8]);
+        assert!(new[0].description.contains("like 70% of AUR packages of similar age"), "{}", new[0].description);
+
+        meta.num_votes = 1;
+        meta.popularity = 0.5;
+        assert!(analyze_meta(meta).is_empty(), "one vote in two weeks is not low");
+    }
+
+    #[test]
+    fn measured_cohorts_replace_defaults() {
+        let meta = make_meta(2, 1.0, Some("user"), Some("https://example.com"), Some(vec!["MIT".into()]), None);
+        let now = meta.last_modified;
+        let mut cohorts = AgeCohorts::default();
+        for cohort in &mut cohorts.cohorts {
+            cohort.low_votes = 1;
+        }
+        assert!(analyze_with_cohorts(&meta, &cohorts, now).is_empty());
+        assert!(has(&analyze_meta(meta), "M-VOTES-LOW"));
+    }
 }
@@ -1,6 +1,12 @@
+#[cfg(feature = "anomaly")]
+pub mod anomaly_analysis;
+pub mod artifact_analysis;
 pub mod aur_comments_analysis;
 pub mod bin_source_verification;
 pub mod checksum_analysis;
+pub mod depends_analysis;
+pub mod desktop_entry_analysis;
+pub mod dkms_analysis;
 pub mod git_history_analysis;
 pub mod github_stars;
 pub mod gtfobins_analysis;
@@ -11,35 +17,124 @@ pub mod name_analysis;
 pub mod orphan_takeover_analysis;
 pub mod pkgbuild_analysis;
 pub mod pkgbuild_diff_analysis;
+pub mod pkgver_analysis;
+pub mod secrets_analysis;
 pub mod shell_analysis;
 pub mod source_url_analysis;
+pub mod upstream_source_analysis;
 
+use crate::shared::config::FeaturesConfig;
 use crate::shared::models::PackageContext;
 use crate::shared::scoring::Signal;
+use std::sync::Arc;
 
 /// Trait implemented by every analysis feature.
 /// Each feature receives a PackageContext and returns signals it detected.
-pub trait Feature {
+pub trait Feature: Send + Sync {
     fn analyze(&self, ctx: &PackageContext) -> Vec<Signal>;
 }
 
-/// Returns all registered features.
-pub fn all_features() -> Vec<Box<dyn Feature>> {
-    vec![
-        Box::new(pkgbuild_analysis::PkgbuildAnalysis),
-        Box::new(install_script_analysis::InstallScriptAnalysis),
-        Box::new(source_url_analysis::SourceUrlAnalysis),
-        Box::new(checksum_analysis::ChecksumAnalysis),
-        Box::new(metadata_analysis::MetadataAnalysis),
-        Box::new(name_analysis::NameAnalysis),
-        Box::new(maintainer_analysis::MaintainerAnalysis),
-        Box::new(orphan_takeover_analysis::OrphanTakeoverAnalysis),
-        Box::new(git_history_analysis::GitHistoryAnalysis),
-        Box::new(shell_analysis::ShellAnalysis),
-        Box::new(gtfobins_analysis::GtfobinsAnalysis),
-        Box::new(bin_source_verification::BinSourceVerification),
-        Box::new(pkgbuild_diff_analysis::PkgbuildDiffAnalysis),
-        Box::new(github_stars::GitHubStars),
-        Box::new(aur_comments_analysis::AurCommentsAnalysis),
-    ]
+/// A feature with the name used to refer to it in config (`[features] github_stars = false`).
+pub struct RegisteredFeature {
+    pub name: &'static str,
+    pub feature: Arc<dyn Feature>,
+}
+
+/// Named, ordered set of features to run against a package.
+#[derive(Default)]
+pub struct FeatureRegistry {
+    features: Vec<RegisteredFeature>,
+}
+
+impl FeatureRegistry {
+    /// Add a feature. Later registrations with the same name replace earlier ones.
+    pub fn register(&mut self, name: &'static str, feature: impl Feature + 'static) {
+        let entry = RegisteredFeature { name, feature: Arc::new(feature) };
+        match self.features.iter_mut().find(|f| f.name == name) {
+            Some(existing) => *existing = entry,
+            None => self.features.push(entry),
+        }
+    }
+
+    /// All built-in features, in execution order.
+    pub fn builtin() -> Self {
+        let mut registry = Self::default();
+        registry.register("pkgbuild_analysis", pkgbuild_analysis::PkgbuildAnalysis);
+        registry.register("install_script_analysis", install_script_analysis::InstallScriptAnalysis);
+        registry.register("source_url_analysis", source_url_analysis::SourceUrlAnalysis);
+        registry.register("checksum_analysis", checksum_analysis::ChecksumAnalysis);
+        registry.register("metadata_analysis", metadata_analysis::MetadataAnalysis);
+        registry.register("name_analysis", name_analysis::NameAnalysis);
+        registry.register("maintainer_analysis", maintainer_analysis::MaintainerAnalysis);
+        registry.register("orphan_takeover_analysis", orphan_takeover_analysis::OrphanTakeoverAnalysis);
+        registry.register("depends_analysis", depends_analysis::DependsAnalysis);
+        registry.register("git_history_analysis", git_history_analysis::GitHistoryAnalysis);
+        registry.register("shell_analysis", shell_analysis::ShellAnalysis);
+        registry.register("gtfobins_analysis", gtfobins_analysis::GtfobinsAnalysis);
+        registry.register("bin_source_verification", bin_source_verification::BinSourceVerification);
+        registry.register("pkgbuild_diff_analysis", pkgbuild_diff_analysis::PkgbuildDiffAnalysis);
+        registry.register("pkgver_analysis", pkgver_analysis::PkgverAnalysis);
+        registry.register("dkms_analysis", dkms_analysis::DkmsAnalysis);
+        registry.register("secrets_analysis", secrets_analysis::SecretsAnalysis);
+        registry.register("desktop_entry_analysis", desktop_entry_analysis::DesktopEntryAnalysis);
+        registry.register("github_stars", github_stars::GitHubStars);
+        registry.register("aur_comments_analysis", aur_comments_analysis::AurCommentsAnalysis);
+        registry.register("artifact_analysis", artifact_analysis::ArtifactAnalysis);
+        registry.register("upstream_source_analysis", upstream_source_analysis::UpstreamSourceAnalysis);
+        #[cfg(feature = "anomaly")]
+        registry.register("anomaly_analysis", anomaly_analysis::AnomalyAnalysis);
+        registry
+    }
+
+    /// Names of all registered features.
+    pub fn names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.features.iter().map(|f| f.name)
+    }
+
+    /// Features not disabled in config, in registration order.
+    pub fn enabled<'a>(
+        &'a self,
+        config: &'a FeaturesConfig,
+    ) -> impl Iterator<Item = &'a RegisteredFeature> + 'a {
+        self.features.iter().filter(|f| config.is_enabled(f.name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Dummy;
+    impl Feature for Dummy {
+        fn analyze(&self, _ctx: &PackageContext) -> Vec<Signal> {
+            Vec::new()
+        }
+    }
+
+    #[test]
+    fn builtin_registers_every_feature_once() {
+        let registry = FeatureRegistry::builtin();
+        let names: Vec<&str> = registry.names().collect();
+        assert_eq!(names.len(), if cfg!(feature = "anomaly") { 23 } else { 22 });
+        let unique: std::collections::HashSet<&str> = names.iter().copied().collect();
+        assert_eq!(unique.len(), names.len());
+        assert!(names.contains(&"github_stars"));
+    }
+
+    #[test]
+    fn register_replaces_same_name() {
+        let mut registry = FeatureRegistry::default();
+        registry.register("dummy", Dummy);
+        registry.register("dummy", Dummy);
+        assert_eq!(registry.names().count(), 1);
+    }
+
+    #[test]
+    fn disabled_features_are_skipped() {
+        let registry = FeatureRegistry::builtin();
+        let mut config = FeaturesConfig::default();
+        config.enabled.insert("github_stars".to_string(), false);
+        assert!(registry.enabled(&config).all(|f| f.name != "github_stars"));
+        assert_eq!(registry.enabled(&config).count(), if cfg!(feature = "anomaly") { 22 } else { 21 });
+    }
 }
@@ -0,0 +1,186 @@
+//! Brand names in the AUR description (`pkgdesc`): a package that says it is an
+//! official or fixed build of a brand its upstream URL has nothing to do with, or
+//! that spells the brand with look-alike characters so a plain text search misses it.
+
+use super::targets::NameTargets;
+use crate::shared::models::PackageContext;
+use crate::shared::scoring::{Signal, SignalCategory};
+
+/// Cyrillic and Greek letters drawn like Latin ones, mapped to the Latin letter.
+const LOOKALIKES: &[(char, char)] = &[
+    ('а', 'a'), ('е', 'e'), ('о', 'o'), ('р', 'p'), ('с', 'c'), ('у', 'y'), ('х', 'x'),
+    ('і', 'i'), ('ј', 'j'), ('ѕ', 's'), ('ԁ', 'd'), ('һ', 'h'), ('ӏ', 'l'), ('ԛ', 'q'), ('ԝ', 'w'),
+    ('А', 'A'), ('В', 'B'), ('Е', 'E'), ('К', 'K'), ('М', 'M'), ('Н', 'H'), ('О', 'O'),
+    ('Р', 'P'), ('С', 'C'), ('Т', 'T'), ('Х', 'X'), ('І', 'I'), ('Ј', 'J'), ('Ѕ', 'S'),
+    ('α', 'a'), ('ε', 'e'), ('ι', 'i'), ('κ', 'k'), ('ν', 'v'), ('ο', 'o'), ('ρ', 'p'), ('υ', 'u'),
+    ('Α', 'A'), ('Β', 'B'), ('Ε', 'E'), ('Ζ', 'Z'), ('Η', 'H'), ('Ι', 'I'), ('Κ', 'K'),
+    ('Μ', 'M'), ('Ν', 'N'), ('Ο', 'O'), ('Ρ', 'P'), ('Τ', 'T'), ('Υ', 'Y'), ('Χ', 'X'),
+    ('ı', 'i'), ('ɡ', 'g'),
+];
+
+/// B-DESC-BRAND-MISMATCH: the description names a brand (or a `[names] protected`
+/// name) that the upstream URL doesn't contain, and either claims to be an official
+/// or improved build or writes the brand with look-alike or invisible characters.
+pub fn analyze(ctx: &PackageContext, targets: &NameTargets) -> Vec<Signal> {
+    let Some(meta) = ctx.metadata.as_ref() else {
+        return Vec::new();
+    };
+    let Some(description) = meta.description.as_deref() else {
+        return Vec::new();
+    };
+    let folded: String = description.chars().filter_map(fold).collect();
+    let folded_words = words(&folded);
+    let plain_words: Vec<String> = words(description).iter().map(|w| w.to_lowercase()).collect();
+    let url = meta.url.as_deref().unwrap_or_default().to_lowercase().replace('-', "");
+    let claim = folded_words
+        .iter()
+        .map(|w| w.to_lowercase())
+        .find(|w| targets.description_claims.contains(w));
+
+    for brand in targets.brands.iter().chain(&ctx.protected_names) {
+        let brand = brand.to_lowercase();
+        let parts: Vec<&str> = brand.split('-').filter(|p| !p.is_empty()).collect();
+        let ambiguous = targets.ambiguous_brands.contains(&brand);
+        let Some(start) = find_words(&folded_words, &parts) else {
+            continue;
+        };
+        if ambiguous && !folded_words[start].starts_with(|c: char| c.is_uppercase()) {
+            continue;
+        }
+        if url.contains(&parts.concat()) {
+            continue;
+        }
+        let plain: Vec<&str> = plain_words.iter().map(String::as_str).collect();
+        let lookalike = find_words(&plain, &parts).is_none();
+        if !lookalike && claim.is_none() {
+            continue;
+        }
+
+        let (points, how) = if lookalike {
+            (60, "written with look-alike characters".to_string())
+        } else {
+            (35, format!("claims '{}'", claim.unwrap_or_default()))
+        };
+        return vec![Signal {
+            id: "B-DESC-BRAND-MISMATCH".to_string(),
+            category: SignalCategory::Behavioral,
+            points,
+            description: format!(
+                "Description names '{brand}' ({how}) but the upstream URL does not"
+            ),
+            is_override_gate: false,
+            matched_line: Some(description.to_string()),
+            location: None,
+            emitted_by: String::new(),
+        }];
+    }
+
+    Vec::new()
+}
+
+/// `c` with look-alikes and fullwidth forms mapped to ASCII; None for invisible
+/// characters (zero-width, bidi controls, soft hyphen).
+fn fold(c: char) -> Option<char> {
+    match c {
+        '\u{00AD}' | '\u{200B}'..='\u{200F}' | '\u{202A}'..='\u{202E}' | '\u{2060}'..='\u{2064}' | '\u{FEFF}' => None,
+        '\u{FF01}'..='\u{FF5E}' => char::from_u32(c as u32 - 0xFEE0),
+        _ => Some(LOOKALIKES.iter().find(|(l, _)| *l == c).map_or(c, |&(_, latin)| latin)),
+    }
+}
+
+/// Runs of letters and digits.
+fn words(text: &str) -> Vec<&str> {
+    text.split(|c: char| !c.is_alphanumeric()).filter(|w| !w.is_empty()).collect()
+}
+
+/// Index of the first word where `parts` appear in a row, compared case-insensitively.
+fn find_words(words: &[&str], parts: &[&str]) -> Option<usize> {
+    if parts.is_empty() {
+        return None;
+    }
+    words.windows(parts.len()).position(|window| {
+        window.iter().zip(parts).all(|(word, part)| word.eq_ignore_ascii_case(part))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared::models::AurPackage;
+
+    fn analyze_description(description: &str, url: Option<&str>) -> Vec<Signal> {
+        let ctx = PackageContext {
+            name: "fastfox-bin".into(),
+            metadata: Some(AurPackage {
+                name: "fastfox-bin".into(),
+                package_base: None,
+                url: url.map(String::from),
+                num_votes: 0,
+                popularity: 0.0,
+                out_of_date: None,
+                maintainer: None,
+                submitter: None,
+                version: String::new(),
+                first_submitted: 0,
+                last_modified: 0,
+                license: None,
+                description: Some(description.into()),
+                depends: Vec::new(),
+                opt_depends: Vec::new(),
+                make_depends: Vec::new(),
+                check_depends: Vec::new(),
+            }),
+            pkgbuild_content: None,
+            install_script_content: None,
+            prior_pkgbuild_content: None,
+            git_log: vec![],
+            maintainer_packages: vec![],
+            github_stars: None,
+            github_not_found: false,
+            aur_comments: vec![],
+            artifact: None,
+            github_releases: None,
+            repo_files: Vec::new(),
+            trusted_domains: Vec::new(),
+            protected_names: Vec::new(),
+            sibling_commits: Vec::new(),
+            maintainer_account: None,
+            upstream_build_files: Vec::new(),
+            history_rewrite: None,
+        };
+        analyze(&ctx, super::super::targets::builtin())
+    }
+
+    #[test]
+    fn official_build_claim_with_unrelated_url() {
+        let signals = analyze_description("Official Firefox build with fixes", Some("https://github.com/ffx-dev/fastfox"));
+        assert_eq!(signals.len(), 1);
+        assert_eq!(signals[0].points, 35);
+        assert!(signals[0].description.contains("'firefox'"), "{}", signals[0].description);
+    }
+
+    #[test]
+    fn lookalike_and_invisible_characters() {
+        // Cyrillic і
+        let signals = analyze_description("Fіrefox with privacy tweaks", Some("https://github.com/ffx-dev/fastfox"));
+        assert_eq!(signals.len(), 1);
+        assert_eq!(signals[0].points, 60);
+        let signals = analyze_description("Fire\u{200B}fox, hardened", None);
+        assert_eq!(signals.len(), 1, "{signals:?}");
+        let signals = analyze_description("Ｄｉｓｃｏｒｄ client", None);
+        assert_eq!(signals.len(), 1, "{signals:?}");
+    }
+
+    #[test]
+    fn brand_in_upstream_url_or_plain_mention_no_signal() {
+        assert!(analyze_description("Official Firefox build with fixes", Some("https://www.mozilla.org/firefox/")).is_empty());
+        assert!(analyze_description("Tab manager extension for Firefox", Some("https://github.com/a/tabs")).is_empty());
+        assert!(analyze_description("Official Tor Browser bundle", Some("https://www.torproject.org/tor-browser")).is_empty());
+    }
+
+    #[test]
+    fn ambiguous_brands_need_capitals() {
+        assert!(analyze_description("Original signal handling library", None).is_empty());
+        assert_eq!(analyze_description("Original Signal desktop client", None).len(), 1);
+    }
+}
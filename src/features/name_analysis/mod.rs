@@ -1,123 +1,33 @@
+pub mod description;
+pub mod targets;
+
 use crate::features::Feature;
 use crate::shared::models::PackageContext;
 use crate::shared::scoring::{Signal, SignalCategory};
-use std::sync::LazyLock;
 use strsim::levenshtein;
 
-/// Suspicious suffixes that indicate impersonation attempts.
-const IMPERSONATION_SUFFIXES: &[&str] = &[
-    "-fix",
-    "-fixed",
-    "-patch",
-    "-patched",
-    "-updated",
-    "-secure",
-    "-plus",
-    "-mod",
-    "-modded",
-    "-pro",
-    "-premium",
-    "-free",
-    "-cracked",
-    "-hack",
-    "-custom",
-    "-lite",
-];
-
-/// Popular brand names commonly targeted for impersonation.
-const BRAND_NAMES: &[&str] = &[
-    "firefox",
-    "chromium",
-    "chrome",
-    "brave",
-    "librewolf",
-    "zen-browser",
-    "discord",
-    "slack",
-    "telegram",
-    "signal",
-    "vscode",
-    "code",
-    "steam",
-    "spotify",
-    "obsidian",
-    "1password",
-    "bitwarden",
-    "keepass",
-    "vlc",
-    "mpv",
-    "neovim",
-    "gimp",
-    "blender",
-    "thunderbird",
-    "protonvpn",
-    "mullvad",
-    "nordvpn",
-    "tor-browser",
-];
-
-static TOP_PACKAGES: LazyLock<Vec<String>> = LazyLock::new(|| {
-    [
-        "yay",
-        "paru",
-        "google-chrome",
-        "spotify",
-        "visual-studio-code-bin",
-        "brave-bin",
-        "discord",
-        "slack-desktop",
-        "zoom",
-        "teams",
-        "librewolf-bin",
-        "zen-browser-bin",
-        "firefox",
-        "chromium",
-        "steam",
-        "lutris",
-        "mangohud",
-        "gamemode",
-        "proton-ge-custom",
-        "timeshift",
-        "pamac-aur",
-        "octopi",
-        "downgrade",
-        "nerd-fonts-complete",
-        "ttf-ms-fonts",
-        "obs-studio",
-        "vlc",
-        "mpv",
-        "neovim",
-        "vim",
-        "emacs",
-        "gimp",
-        "blender",
-        "thunderbird",
-        "protonvpn",
-        "mullvad-vpn",
-        "nordvpn-bin",
-        "tor-browser",
-    ]
-    .iter()
-    .map(|s| s.to_string())
-    .collect()
-});
-
 pub struct NameAnalysis;
 
 impl Feature for NameAnalysis {
     fn analyze(&self, ctx: &PackageContext) -> Vec<Signal> {
-        if let Some(ref meta) = ctx.metadata {
-            if meta.num_votes >= 10 {
-                return Vec::new();
-            }
+        if let Some(ref meta) = ctx.metadata
+            && meta.num_votes >= 10
+        {
+            return Vec::new();
         }
 
         let mut signals = Vec::new();
         let name = &ctx.name;
+        let targets = targets::builtin();
+        // Names protected in config are guarded both as brands and as popular packages.
+        let brands = || targets.brands.iter().chain(&ctx.protected_names);
+        let top_packages = || targets.top_packages.iter().chain(&ctx.protected_names);
+
+        signals.extend(description::analyze(ctx, targets));
 
         // Check impersonation suffixes against brand names
-        for brand in BRAND_NAMES {
-            for suffix in IMPERSONATION_SUFFIXES {
+        for brand in brands() {
+            for suffix in &targets.impersonation_suffixes {
                 let impersonation = format!("{brand}{suffix}");
                 if name == &impersonation
                     || name == &format!("{impersonation}-bin")
@@ -132,6 +42,8 @@ impl Feature for NameAnalysis {
                         ),
                         is_override_gate: false,
                         matched_line: None,
+                        location: None,
+                        emitted_by: String::new(),
                     });
                     // Only fire once per package
                     return signals;
@@ -140,7 +52,7 @@ impl Feature for NameAnalysis {
         }
 
         // Check typosquatting against top packages
-        for top in TOP_PACKAGES.iter() {
+        for top in top_packages() {
             if top == name {
                 continue;
             }
@@ -155,13 +67,15 @@ impl Feature for NameAnalysis {
                     ),
                     is_override_gate: false,
                     matched_line: None,
+                    location: None,
+                    emitted_by: String::new(),
                 });
                 break;
             }
         }
 
         // Check if name embeds a popular package as prefix/suffix (no hyphen boundary)
-        for top in TOP_PACKAGES.iter() {
+        for top in top_packages() {
             if name == top.as_str() || name.len() <= top.len() {
                 continue;
             }
@@ -177,6 +91,8 @@ impl Feature for NameAnalysis {
                     ),
                     is_override_gate: false,
                     matched_line: None,
+                    location: None,
+                    emitted_by: String::new(),
                 });
                 break;
             }
@@ -191,6 +107,10 @@ mod tests {
     use super::*;
 
     fn analyze(name: &str) -> Vec<String> {
+        analyze_protecting(name, &[])
+    }
+
+    fn analyze_protecting(name: &str, protected: &[&str]) -> Vec<String> {
         let ctx = PackageContext {
             name: name.into(),
             metadata: None,
@@ -202,6 +122,15 @@ mod tests {
             github_stars: None,
             github_not_found: false,
             aur_comments: vec![],
+            artifact: None,
+            github_releases: None,
+            repo_files: Vec::new(),
+            trusted_domains: Vec::new(),
+            protected_names: protected.iter().map(|n| n.to_string()).collect(),
+            sibling_commits: Vec::new(),
+            maintainer_account: None,
+            upstream_build_files: Vec::new(),
+            history_rewrite: None,
         };
         NameAnalysis.analyze(&ctx).iter().map(|s| s.id.clone()).collect()
     }
@@ -219,9 +148,15 @@ mod tests {
                 out_of_date: None,
                 maintainer: None,
                 submitter: None,
+                version: String::new(),
                 first_submitted: 0,
                 last_modified: 0,
                 license: None,
+                description: None,
+                depends: Vec::new(),
+                opt_depends: Vec::new(),
+                make_depends: Vec::new(),
+                check_depends: Vec::new(),
             }),
             pkgbuild_content: None,
             install_script_content: None,
@@ -231,6 +166,15 @@ mod tests {
             github_stars: None,
             github_not_found: false,
             aur_comments: vec![],
+            artifact: None,
+            github_releases: None,
+            repo_files: Vec::new(),
+            trusted_domains: Vec::new(),
+            protected_names: Vec::new(),
+            sibling_commits: Vec::new(),
+            maintainer_account: None,
+            upstream_build_files: Vec::new(),
+            history_rewrite: None,
         };
         NameAnalysis.analyze(&ctx).iter().map(|s| s.id.clone()).collect()
     }
@@ -246,6 +190,22 @@ mod tests {
         assert!(has(&analyze("librewolf-patched-bin"), "B-NAME-IMPERSONATE"));
     }
 
+    #[test]
+    fn protected_names_from_config() {
+        assert!(has(&analyze_protecting("acme-vpn-fix-bin", &["acme-vpn"]), "B-NAME-IMPERSONATE"));
+        assert!(has(&analyze_protecting("acme-vpm", &["acme-vpn"]), "B-TYPOSQUAT"));
+        assert!(!has(&analyze("acme-vpn-fix-bin"), "B-NAME-IMPERSONATE"));
+        assert!(analyze_protecting("acme-vpn", &["acme-vpn"]).is_empty());
+    }
+
+    #[test]
+    fn builtin_targets_load_from_data_file() {
+        let targets = targets::builtin();
+        assert!(targets.brands.iter().any(|b| b == "librewolf"));
+        assert!(targets.impersonation_suffixes.iter().all(|s| s.starts_with('-')));
+        assert!(targets.top_packages.iter().any(|p| p == "paru"));
+    }
+
     #[test]
     fn typosquat_edit_distance() {
         assert!(has(&analyze("pary"), "B-TYPOSQUAT")); // 1 edit from "paru"
@@ -0,0 +1,22 @@
+use serde::Deserialize;
+use std::sync::LazyLock;
+
+/// Impersonation and typosquat targets from `data/names.toml`.
+#[derive(Debug, Deserialize)]
+pub struct NameTargets {
+    pub impersonation_suffixes: Vec<String>,
+    pub brands: Vec<String>,
+    pub top_packages: Vec<String>,
+    /// Brands that are ordinary words, matched in descriptions only when capitalized.
+    pub ambiguous_brands: Vec<String>,
+    /// Description words claiming to be the official or an improved build.
+    pub description_claims: Vec<String>,
+}
+
+static TARGETS: LazyLock<NameTargets> = LazyLock::new(|| {
+    toml::from_str(include_str!("../../../data/names.toml")).expect("Failed to parse names.toml")
+});
+
+pub fn builtin() -> &'static NameTargets {
+    &TARGETS
+}
@@ -35,6 +35,8 @@ impl Feature for OrphanTakeoverAnalysis {
             ),
             is_override_gate: false,
             matched_line: None,
+            location: None,
+            emitted_by: String::new(),
         });
 
         // Composite: orphan takeover pattern
@@ -56,6 +58,8 @@ impl Feature for OrphanTakeoverAnalysis {
                     ),
                     is_override_gate: false,
                     matched_line: None,
+                    location: None,
+                    emitted_by: String::new(),
                 });
             }
         }
@@ -93,16 +97,24 @@ mod tests {
             out_of_date: None,
             maintainer: Some(maintainer.into()),
             submitter: submitter.map(|s| s.into()),
+            version: String::new(),
             first_submitted,
             last_modified: now(),
             license: None,
+            description: None,
+            depends: Vec::new(),
+            opt_depends: Vec::new(),
+            make_depends: Vec::new(),
+            check_depends: Vec::new(),
         }
     }
 
     fn make_commit(author: &str, ts: u64) -> GitCommit {
         GitCommit {
             author: author.into(),
+            email: format!("{author}@example.com"),
             timestamp: ts,
+            message: String::new(),
             diff: None,
         }
     }
@@ -133,6 +145,15 @@ mod tests {
             github_stars: None,
             github_not_found: false,
             aur_comments: vec![],
+            artifact: None,
+            github_releases: None,
+            repo_files: Vec::new(),
+            trusted_domains: Vec::new(),
+            protected_names: Vec::new(),
+            sibling_commits: Vec::new(),
+            maintainer_account: None,
+            upstream_build_files: Vec::new(),
+            history_rewrite: None,
         };
         assert!(signal_ids(&ctx).is_empty());
     }
@@ -151,6 +172,15 @@ mod tests {
             github_stars: None,
             github_not_found: false,
             aur_comments: vec![],
+            artifact: None,
+            github_releases: None,
+            repo_files: Vec::new(),
+            trusted_domains: Vec::new(),
+            protected_names: Vec::new(),
+            sibling_commits: Vec::new(),
+            maintainer_account: None,
+            upstream_build_files: Vec::new(),
+            history_rewrite: None,
         };
         assert!(signal_ids(&ctx).is_empty());
     }
@@ -169,6 +199,15 @@ mod tests {
             github_stars: None,
             github_not_found: false,
             aur_comments: vec![],
+            artifact: None,
+            github_releases: None,
+            repo_files: Vec::new(),
+            trusted_domains: Vec::new(),
+            protected_names: Vec::new(),
+            sibling_commits: Vec::new(),
+            maintainer_account: None,
+            upstream_build_files: Vec::new(),
+            history_rewrite: None,
         };
         let ids = signal_ids(&ctx);
         assert!(has(&ids, "B-SUBMITTER-CHANGED"));
@@ -192,6 +231,15 @@ mod tests {
             github_stars: None,
             github_not_found: false,
             aur_comments: vec![],
+            artifact: None,
+            github_releases: None,
+            repo_files: Vec::new(),
+            trusted_domains: Vec::new(),
+            protected_names: Vec::new(),
+            sibling_commits: Vec::new(),
+            maintainer_account: None,
+            upstream_build_files: Vec::new(),
+            history_rewrite: None,
         };
         let ids = signal_ids(&ctx);
         assert!(has(&ids, "B-SUBMITTER-CHANGED"));
@@ -215,6 +263,15 @@ mod tests {
             github_stars: None,
             github_not_found: false,
             aur_comments: vec![],
+            artifact: None,
+            github_releases: None,
+            repo_files: Vec::new(),
+            trusted_domains: Vec::new(),
+            protected_names: Vec::new(),
+            sibling_commits: Vec::new(),
+            maintainer_account: None,
+            upstream_build_files: Vec::new(),
+            history_rewrite: None,
         };
         let ids = signal_ids(&ctx);
         assert!(has(&ids, "B-SUBMITTER-CHANGED"));
@@ -238,6 +295,15 @@ mod tests {
             github_stars: None,
             github_not_found: false,
             aur_comments: vec![],
+            artifact: None,
+            github_releases: None,
+            repo_files: Vec::new(),
+            trusted_domains: Vec::new(),
+            protected_names: Vec::new(),
+            sibling_commits: Vec::new(),
+            maintainer_account: None,
+            upstream_build_files: Vec::new(),
+            history_rewrite: None,
         };
         let ids = signal_ids(&ctx);
         assert!(has(&ids, "B-SUBMITTER-CHANGED"));
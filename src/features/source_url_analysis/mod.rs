@@ -1,13 +1,13 @@
-pub mod patterns;
-
 use crate::features::Feature;
 use crate::shared::models::PackageContext;
+use crate::shared::patterns::PatternEngine;
 use crate::shared::scoring::{Signal, SignalCategory};
+use crate::shared::{source_arrays, trusted_domains};
 use regex::Regex;
 use std::sync::LazyLock;
 
-static SOURCE_ARRAY_RE: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(r"(?ms)^source=\((.*?)\)").unwrap()
+static HTTP_URL_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"http://[^\s'"()]+"#).unwrap()
 });
 
 pub struct SourceUrlAnalysis;
@@ -18,46 +18,64 @@ impl Feature for SourceUrlAnalysis {
             return Vec::new();
         };
 
-        // Only match against the source=() array, not comments or other code
-        let source_content = match SOURCE_ARRAY_RE.captures(content) {
-            Some(caps) => caps[1].to_string(),
-            None => return Vec::new(),
-        };
-
-        let compiled = patterns::compiled_patterns();
-        let mut signals = Vec::new();
-
-        for pat in compiled {
-            if pat.regex.is_match(&source_content) {
-                let matched_line = source_content
-                    .lines()
-                    .find(|line| pat.regex.is_match(line))
-                    .map(|line| line.trim().to_string());
-                signals.push(Signal {
-                    id: pat.id.clone(),
-                    category: SignalCategory::Pkgbuild,
-                    points: pat.points,
-                    description: pat.description.clone(),
-                    is_override_gate: pat.override_gate,
-                    matched_line,
-                });
+        // Only match against the source arrays (source=() and source_<arch>=()),
+        // not comments or other code
+        let arrays = source_arrays::parse_arrays(content, "source");
+        if arrays.is_empty() {
+            return Vec::new();
+        }
+        let source_content = arrays.iter().map(|a| a.body.as_str()).collect::<Vec<_>>().join("\n");
+
+        // Plain HTTP from a trusted mirror is not worth flagging on its own
+        let mut signals = PatternEngine::scan_with(
+            &source_content,
+            "source_url_analysis",
+            SignalCategory::Pkgbuild,
+            "",
+            |pat, line| pat.id != "P-HTTP-SOURCE" || !all_http_hosts_trusted(line, &ctx.trusted_domains),
+        );
+
+        for signal in &mut signals {
+            let Some(ref line) = signal.matched_line else { continue };
+            let arch = arrays
+                .iter()
+                .find(|a| a.body.lines().any(|l| l.trim() == line))
+                .and_then(|a| a.arch.as_deref());
+            if let Some(arch) = arch {
+                signal.matched_line = Some(format!("{line} ({arch} only)"));
             }
         }
-
         signals
     }
 }
 
+fn all_http_hosts_trusted(line: &str, extra: &[String]) -> bool {
+    HTTP_URL_RE.find_iter(line).all(|url| {
+        trusted_domains::url_host(url.as_str())
+            .is_some_and(|host| trusted_domains::is_trusted(&host, extra))
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     fn analyze(source_url: &str) -> Vec<String> {
-        let content = format!("pkgname=test\nsource=('{source_url}')\n");
+        analyze_trusting(source_url, &[])
+    }
+
+    fn analyze_trusting(source_url: &str, trusted: &[&str]) -> Vec<String> {
+        analyze_pkgbuild(&format!("pkgname=test\nsource=('{source_url}')\n"), trusted)
+            .into_iter()
+            .map(|s| s.id)
+            .collect()
+    }
+
+    fn analyze_pkgbuild(content: &str, trusted: &[&str]) -> Vec<Signal> {
         let ctx = PackageContext {
             name: "test-pkg".into(),
             metadata: None,
-            pkgbuild_content: Some(content),
+            pkgbuild_content: Some(content.into()),
             install_script_content: None,
             prior_pkgbuild_content: None,
             git_log: vec![],
@@ -65,8 +83,17 @@ mod tests {
             github_stars: None,
             github_not_found: false,
             aur_comments: vec![],
+            artifact: None,
+            github_releases: None,
+            repo_files: Vec::new(),
+            trusted_domains: trusted.iter().map(|d| d.to_string()).collect(),
+            protected_names: Vec::new(),
+            sibling_commits: Vec::new(),
+            maintainer_account: None,
+            upstream_build_files: Vec::new(),
+            history_rewrite: None,
         };
-        SourceUrlAnalysis.analyze(&ctx).iter().map(|s| s.id.clone()).collect()
+        SourceUrlAnalysis.analyze(&ctx)
     }
 
     fn has(ids: &[String], id: &str) -> bool {
@@ -121,6 +148,13 @@ mod tests {
         assert!(has(&ids, "P-HTTP-SOURCE"));
     }
 
+    #[test]
+    fn http_source_from_trusted_mirror() {
+        assert!(!has(&analyze("http://ftp.gnu.org/gnu/hello/hello-2.12.tar.gz"), "P-HTTP-SOURCE"));
+        let ids = analyze_trusting("http://mirror.example.org/tool.tar.gz", &["mirror.example.org"]);
+        assert!(!has(&ids, "P-HTTP-SOURCE"));
+    }
+
     #[test]
     fn filehost_source() {
         let ids = analyze("https://transfer.sh/abc123/payload.tar.gz");
@@ -139,6 +173,18 @@ mod tests {
         assert!(has(&ids, "P-MEGA-SOURCE"));
     }
 
+    #[test]
+    fn arch_specific_sources() {
+        let content = "pkgname=test\nsource=('https://github.com/user/repo/archive/v1.0.tar.gz')\n\
+                       source_x86_64=('https://abc123.ngrok.io/payload-x86_64.tar.gz')\n";
+        let signals = analyze_pkgbuild(content, &[]);
+        let tunnel = signals.iter().find(|s| s.id == "P-TUNNEL-SERVICE").expect("x86_64 source is analyzed");
+        assert_eq!(
+            tunnel.matched_line.as_deref(),
+            Some("'https://abc123.ngrok.io/payload-x86_64.tar.gz' (x86_64 only)")
+        );
+    }
+
     #[test]
     fn github_no_signals() {
         let ids = analyze("https://github.com/user/repo/archive/v1.0.tar.gz");
@@ -159,6 +205,15 @@ mod tests {
             github_stars: None,
             github_not_found: false,
             aur_comments: vec![],
+            artifact: None,
+            github_releases: None,
+            repo_files: Vec::new(),
+            trusted_domains: Vec::new(),
+            protected_names: Vec::new(),
+            sibling_commits: Vec::new(),
+            maintainer_account: None,
+            upstream_build_files: Vec::new(),
+            history_rewrite: None,
         };
         let ids: Vec<String> = SourceUrlAnalysis.analyze(&ctx).iter().map(|s| s.id.clone()).collect();
         assert!(!has(&ids, "P-PASTEBIN"), "Should not detect pastebin URL in comment");
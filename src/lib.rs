@@ -1,3 +1,5 @@
 pub mod coordinator;
 pub mod features;
 pub mod shared;
+#[cfg(feature = "wasm")]
+pub mod wasm;
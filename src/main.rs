@@ -1,9 +1,25 @@
+mod adopt_check;
+mod audit;
 mod bench;
+mod config_cmd;
 mod coordinator;
+mod diff_cmd;
 mod features;
+mod graph;
+mod known_good_cmd;
+mod lint;
+mod policy;
+mod report_fp;
+mod schedule;
+mod serve;
 mod shared;
+mod top;
+mod upgrade_check;
+mod verify_upstream;
+mod why;
 
-use clap::{Parser, Subcommand};
+use clap::{ArgGroup, Parser, Subcommand};
+use shared::output::{OutputFormat, RenderOptions};
 use std::process;
 
 #[derive(Parser)]
@@ -15,39 +31,232 @@ struct Cli {
 
 #[derive(Subcommand)]
 enum Commands {
-    /// Scan a package (or all installed AUR packages if none specified)
+    /// Scan packages (or all installed AUR packages if none specified)
+    #[command(group(ArgGroup::new("named").args(["packages", "from_file"]).multiple(true)))]
     Scan {
-        /// Package name to scan (or --pkgbuild for local)
-        package: Option<String>,
+        /// Package names to scan; several are scanned in parallel with shared prefetching
+        /// (or --pkgbuild for local)
+        packages: Vec<String>,
 
-        /// Scan a local PKGBUILD directory
+        /// Scan a local PKGBUILD, or a package directory including its other files
         #[arg(long)]
         pkgbuild: Option<String>,
 
+        /// Scan a built package archive (.pkg.tar.zst) from pacman's cache or makepkg
+        #[arg(long, value_name = "PATH")]
+        package_file: Option<String>,
+
+        /// Scan the package as this prebuilt AUR repo ships it (e.g. chaotic-aur): checks the
+        /// build came from the AUR's current PKGBUILD and scans the downloaded archive
+        #[arg(long, value_name = "REPO", requires = "packages")]
+        repo: Option<String>,
+
         /// Scan all installed AUR packages (default when no package given)
         #[arg(long)]
         all_installed: bool,
 
-        /// Number of concurrent scan threads (for bulk scanning)
-        #[arg(long, default_value_t = 4)]
-        jobs: usize,
+        /// Scan the packages listed in a file (`-` for stdin), one per line, along with any
+        /// given as arguments. Blank lines, `#` comments and text after the name are ignored
+        #[arg(long, value_name = "PATH", conflicts_with_all = ["pkgbuild", "package_file", "repo", "all_installed"])]
+        from_file: Option<String>,
 
-        /// Output as JSON
+        /// Number of concurrent scan threads for bulk scanning (default: [scan] jobs, or 4)
+        #[arg(long)]
+        jobs: Option<usize>,
+
+        /// Output as JSON (shorthand for --format json)
         #[arg(long)]
         json: bool,
 
-        /// Show the exact line that triggered each signal
+        /// Output format
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+
+        /// Print JSON on a single line instead of pretty-printed
+        #[arg(long)]
+        compact: bool,
+
+        /// Show the exact line that triggered each signal, and the PKGBUILD diff behind T-DIFF signals
         #[arg(short = 'v', long)]
         verbose: bool,
 
+        /// Show how the score was computed: category totals, caps, weights, override
+        /// gate, rounding and later adjustments (added as score_explanation to JSON)
+        #[arg(long)]
+        explain_score: bool,
+
         /// Only show flagged packages (SKETCHY and above)
         #[arg(short = 'f', long)]
         flagged_only: bool,
+
+        /// Send configured notifications (desktop, webhook, ntfy) for flagged packages
+        #[arg(long)]
+        notify: bool,
+
+        /// Ignore a signal for this scan only (repeatable; config is not modified)
+        #[arg(long = "ignore-signal", value_name = "ID")]
+        ignore_signals: Vec<String>,
+
+        /// Ignore a signal category for this scan only (repeatable; config is not modified)
+        #[arg(long = "ignore-category", value_name = "CATEGORY")]
+        ignore_categories: Vec<String>,
+
+        /// Give up on a package after this many seconds (overrides [scan] timeout)
+        #[arg(long, value_name = "SECS")]
+        timeout: Option<u64>,
+
+        /// Also download the upstream GitHub tarball from source=() and analyze its
+        /// top-level build scripts (like [scan] deep = true)
+        #[arg(long)]
+        deep: bool,
+
+        /// When scanning several or all installed packages, skip those already scanned
+        /// by an interrupted or partly failed run
+        #[arg(long)]
+        resume: bool,
+
+        /// Also scan AUR dependencies the packages declare only in .SRCINFO, which AUR
+        /// helpers install although the PKGBUILD never mentions them
+        #[arg(long, requires = "named")]
+        with_deps: bool,
+    },
+    /// Rank all installed AUR packages by risk, riskiest first
+    Top {
+        /// Show only the N riskiest packages
+        #[arg(long, value_name = "N")]
+        limit: Option<usize>,
+
+        /// Number of concurrent scan threads (default: [scan] jobs, or 4)
+        #[arg(long)]
+        jobs: Option<usize>,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+
+        /// Give up on a package after this many seconds (overrides [scan] timeout)
+        #[arg(long, value_name = "SECS")]
+        timeout: Option<u64>,
+    },
+    /// Export how scanned packages connect through maintainers, source domains and shared payload URLs
+    Graph {
+        /// Packages to include (default: all installed AUR packages)
+        packages: Vec<String>,
+
+        /// Output format
+        #[arg(long, value_enum, default_value_t = graph::GraphFormat::Dot)]
+        format: graph::GraphFormat,
+
+        /// Number of concurrent scan threads (default: [scan] jobs, or 4)
+        #[arg(long)]
+        jobs: Option<usize>,
+
+        /// Give up on a package after this many seconds (overrides [scan] timeout)
+        #[arg(long, value_name = "SECS")]
+        timeout: Option<u64>,
+    },
+    /// Retroactively scan AUR packages installed or upgraded according to pacman's log
+    Audit {
+        /// Path to the pacman log
+        #[arg(long, value_name = "PATH", default_value = audit::DEFAULT_LOG_PATH)]
+        log: String,
+
+        /// Only consider transactions on or after this date (YYYY-MM-DD)
+        #[arg(long, value_name = "DATE")]
+        since: Option<String>,
+
+        /// Number of concurrent scan threads (default: [scan] jobs, or 4)
+        #[arg(long)]
+        jobs: Option<usize>,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+
+        /// Give up on a package after this many seconds (overrides [scan] timeout)
+        #[arg(long, value_name = "SECS")]
+        timeout: Option<u64>,
+    },
+    /// Scan only the installed AUR packages with pending updates, before upgrading
+    UpgradeCheck {
+        /// Number of concurrent scan threads (default: [scan] jobs, or 4)
+        #[arg(long)]
+        jobs: Option<usize>,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+
+        /// Give up on a package after this many seconds (overrides [scan] timeout)
+        #[arg(long, value_name = "SECS")]
+        timeout: Option<u64>,
+    },
+    /// Show what the last AUR update changed in a package's PKGBUILD and score only the change
+    Diff {
+        /// AUR package name
+        package: String,
+
+        /// Compare against the installed version (or the PKGBUILD an AUR helper last
+        /// built) instead of the previous commit
+        #[arg(long)]
+        installed: bool,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Explain a package's tier: which signals dominate, the score without each, and the
+    /// fewest ignores that would change the tier
+    Why {
+        /// AUR package name
+        package: String,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Check a package before adopting it: full scan plus orphan status, out-of-date flag,
+    /// comment complaints and the last 5 commits, ending in a go/no-go verdict
+    AdoptCheck {
+        /// AUR package name
+        package: String,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Check a package against its upstream: URL reachable, GitHub repo and stars, source
+    /// domains, release tags and assets, and whether upstream ever tagged the AUR version
+    VerifyUpstream {
+        /// AUR package name
+        package: String,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Report a false positive: rescan the package and open a prefilled GitHub issue with the
+    /// (sanitized) matched line, PKGBUILD hash and version, or POST it to [report] endpoint
+    ReportFp {
+        /// AUR package name
+        package: String,
+
+        /// The signal that fired wrongly (e.g. P-CURL-PIPE)
+        signal_id: String,
+
+        /// Only print the report JSON; send nothing
+        #[arg(long)]
+        print: bool,
     },
     /// Whitelist a package (skip future scans)
     Allow {
         /// Package name to whitelist
         package: String,
+
+        /// Pin to the current PKGBUILD: the whitelist lapses, and the package is
+        /// scanned again, once the PKGBUILD changes
+        #[arg(long)]
+        pin: bool,
     },
     /// Benchmark scanning the N most recently modified AUR packages
     Bench {
@@ -55,22 +264,43 @@ enum Commands {
         #[arg(long, default_value_t = 1000)]
         count: usize,
 
+        /// Benchmark the package directories (with a PKGBUILD) under DIR instead, offline
+        #[arg(long, value_name = "DIR", conflicts_with_all = ["count", "resume"])]
+        corpus: Option<String>,
+
         /// Number of concurrent scan threads
         #[arg(long, default_value_t = 8)]
         jobs: usize,
+
+        /// Skip packages already scanned by an interrupted or partly failed run
+        #[arg(long)]
+        resume: bool,
+
+        /// Save this run's score distribution and the AUR's vote and popularity levels by
+        /// package age to the cache directory, for later scans to compare against
+        #[arg(long, conflicts_with = "corpus")]
+        save_snapshots: bool,
     },
     /// List all available signals
     Signals {
         /// Output as JSON
         #[arg(long)]
         json: bool,
+
+        /// Group by the feature that emits each signal instead of by category
+        #[arg(long)]
+        by_feature: bool,
+
+        /// List only override gates, with their regexes and why they mean MALICIOUS
+        #[arg(long, conflicts_with = "by_feature")]
+        gates: bool,
     },
     /// Ignore a signal or category (exclude from scoring and output)
     Ignore {
         /// Signal ID to ignore (e.g. P-PYTHON-INLINE)
         signal_id: Option<String>,
 
-        /// Ignore all signals in a category (Metadata, Pkgbuild, Behavioral, Temporal)
+        /// Ignore all signals in a category (Metadata, Pkgbuild, Behavioral, Temporal, InstallScript)
         #[arg(long)]
         category: Option<String>,
     },
@@ -83,6 +313,70 @@ enum Commands {
         #[arg(long)]
         category: Option<String>,
     },
+    /// Lint a package directory for CI: file:line findings with severities and fixes, no network access
+    Lint {
+        /// Package directory containing the PKGBUILD
+        #[arg(default_value = ".")]
+        dir: String,
+
+        /// Exit with status 1 if any finding is more severe than this
+        #[arg(long, value_enum, default_value_t = lint::Severity::Medium)]
+        max_severity: lint::Severity,
+
+        /// Ignore a signal for this run only (repeatable; config is not modified)
+        #[arg(long = "ignore-signal", value_name = "ID")]
+        ignore_signals: Vec<String>,
+
+        /// Ignore a signal category for this run only (repeatable; config is not modified)
+        #[arg(long = "ignore-category", value_name = "CATEGORY")]
+        ignore_categories: Vec<String>,
+    },
+    /// Serve JSON-RPC scan requests for editor integrations
+    Serve {
+        /// Speak newline-delimited JSON-RPC 2.0 on stdin/stdout (the only transport)
+        #[arg(long, required = true)]
+        stdio: bool,
+    },
+    /// Manage traur's on-disk cache
+    Cache {
+        #[command(subcommand)]
+        action: CacheAction,
+    },
+    /// Show or change config settings (thresholds, jobs, trusted domains, feature toggles, ...)
+    Config {
+        #[command(subcommand)]
+        action: config_cmd::ConfigAction,
+    },
+    /// Run a periodic scan of installed AUR packages from a user systemd timer
+    Schedule {
+        #[command(subcommand)]
+        action: schedule::ScheduleAction,
+    },
+    /// Fetch or generate the known-good PKGBUILD hash database for popular packages
+    KnownGood {
+        #[command(subcommand)]
+        action: known_good_cmd::KnownGoodAction,
+    },
+    /// Export or import the whole policy (thresholds, ignores, trusted domains, whitelist
+    /// with pins) as one versioned, optionally signed file
+    Policy {
+        #[command(subcommand)]
+        action: policy::PolicyAction,
+    },
+}
+
+#[derive(Subcommand)]
+enum CacheAction {
+    /// Evict cached AUR git clones by age and total size (they are re-cloned on the next scan)
+    Gc {
+        /// Size limit in MiB (default: [cache] max_size_mb, or 1024)
+        #[arg(long, value_name = "MIB")]
+        max_size_mb: Option<u64>,
+
+        /// Also evict clones not fetched in this many days
+        #[arg(long, value_name = "DAYS")]
+        max_age_days: Option<u64>,
+    },
 }
 
 fn main() {
@@ -90,34 +384,199 @@ fn main() {
 
     let exit_code = match cli.command {
         Commands::Scan {
-            package,
+            packages,
             pkgbuild,
+            package_file,
+            repo,
             all_installed,
+            from_file,
             jobs,
             json,
+            format,
+            compact,
             verbose,
+            explain_score,
             flagged_only,
-        } => cmd_scan(package, pkgbuild, all_installed, jobs, json, verbose, flagged_only),
-        Commands::Allow { package } => cmd_allow(&package),
-        Commands::Bench { count, jobs } => bench::run(count, jobs),
-        Commands::Signals { json } => cmd_signals(json),
+            notify,
+            ignore_signals,
+            ignore_categories,
+            timeout,
+            deep,
+            resume,
+            with_deps,
+        } => {
+            let format = if json { OutputFormat::Json } else { format };
+            let opts = RenderOptions { verbose, compact, catalog: shared::i18n::from_env(), explain_score };
+            let config = match scan_config(&ignore_signals, &ignore_categories) {
+                Ok(c) => shared::config::with_deep_scan(shared::config::with_scan_timeout(c, timeout), deep),
+                Err(e) => {
+                    eprintln!("{e}");
+                    process::exit(1);
+                }
+            };
+            if let Some(path) = package_file {
+                cmd_scan_package_file(&path, format, &opts, &config)
+            } else if let Some(repo) = repo {
+                match packages.as_slice() {
+                    [package] => cmd_scan_repo(package, &repo, format, &opts, &config),
+                    _ => {
+                        eprintln!("--repo takes a single package");
+                        1
+                    }
+                }
+            } else {
+                let mut packages = packages;
+                if let Some(path) = from_file {
+                    match read_package_list(&path) {
+                        Ok(listed) if listed.is_empty() && packages.is_empty() => {
+                            eprintln!("No packages listed in {}", if path == "-" { "stdin" } else { &path });
+                            process::exit(1);
+                        }
+                        Ok(listed) => packages.extend(listed),
+                        Err(e) => {
+                            eprintln!("Error: {e}");
+                            process::exit(1);
+                        }
+                    }
+                }
+                if with_deps {
+                    let extra = srcinfo_only_aur_deps(&packages);
+                    if !extra.is_empty() {
+                        eprintln!("  Also scanning .SRCINFO-only AUR dependencies: {}", extra.join(", "));
+                        packages.extend(extra);
+                    }
+                }
+                let jobs = jobs.unwrap_or(config.scan.jobs());
+                cmd_scan(packages, pkgbuild, all_installed, jobs, format, &opts, flagged_only, notify, resume, &config)
+            }
+        }
+        Commands::Top { limit, jobs, json, timeout } => {
+            let config = shared::config::with_scan_timeout(shared::config::load_config(), timeout);
+            top::run(limit, jobs.unwrap_or(config.scan.jobs()), json, &config)
+        }
+        Commands::Graph { packages, format, jobs, timeout } => {
+            let config = shared::config::with_scan_timeout(shared::config::load_config(), timeout);
+            graph::run(packages, format, jobs.unwrap_or(config.scan.jobs()), &config)
+        }
+        Commands::Audit { log, since, jobs, json, timeout } => {
+            let config = shared::config::with_scan_timeout(shared::config::load_config(), timeout);
+            audit::run(&log, since.as_deref(), jobs.unwrap_or(config.scan.jobs()), json, &config)
+        }
+        Commands::UpgradeCheck { jobs, json, timeout } => {
+            let config = shared::config::with_scan_timeout(shared::config::load_config(), timeout);
+            upgrade_check::run(jobs.unwrap_or(config.scan.jobs()), json, &config)
+        }
+        Commands::Diff { package, installed, json } => {
+            diff_cmd::run(&package, installed, json, &shared::config::load_config())
+        }
+        Commands::Why { package, json } => why::run(&package, json, &shared::config::load_config()),
+        Commands::AdoptCheck { package, json } => {
+            adopt_check::run(&package, json, &shared::config::load_config())
+        }
+        Commands::VerifyUpstream { package, json } => {
+            verify_upstream::run(&package, json, &shared::config::load_config())
+        }
+        Commands::ReportFp { package, signal_id, print } => {
+            report_fp::run(&package, &signal_id, print, &shared::config::load_config())
+        }
+        Commands::Allow { package, pin } => cmd_allow(&package, pin),
+        Commands::Bench { count, corpus, jobs, resume, save_snapshots } => match corpus {
+            Some(dir) => bench::run_corpus(std::path::Path::new(&dir), jobs),
+            None => bench::run(count, jobs, resume, save_snapshots),
+        },
+        Commands::Signals { json, by_feature, gates } => {
+            if gates {
+                cmd_signal_gates(json)
+            } else {
+                cmd_signals(json, by_feature)
+            }
+        }
         Commands::Ignore { signal_id, category } => cmd_ignore(signal_id.as_deref(), category.as_deref()),
         Commands::Unignore { signal_id, category } => cmd_unignore(signal_id.as_deref(), category.as_deref()),
+        Commands::Lint { dir, max_severity, ignore_signals, ignore_categories } => {
+            match scan_config(&ignore_signals, &ignore_categories) {
+                Ok(config) => lint::run(&dir, max_severity, &config),
+                Err(e) => {
+                    eprintln!("{e}");
+                    1
+                }
+            }
+        }
+        Commands::Serve { stdio: _ } => serve::run(&shared::config::load_config()),
+        Commands::Cache { action: CacheAction::Gc { max_size_mb, max_age_days } } => {
+            cmd_cache_gc(max_size_mb, max_age_days)
+        }
+        Commands::Config { action } => config_cmd::run(action),
+        Commands::Policy { action } => policy::run(action),
+        Commands::Schedule { action } => schedule::run(action),
+        Commands::KnownGood { action } => known_good_cmd::run(action, &shared::config::load_config()),
     };
 
     process::exit(exit_code);
 }
 
+/// Load config and layer per-invocation `--ignore-signal`/`--ignore-category` flags on top.
+fn scan_config(
+    ignore_signals: &[String],
+    ignore_categories: &[String],
+) -> Result<shared::config::Config, String> {
+    check_ignores(ignore_signals, ignore_categories)?;
+    let config = shared::config::load_config();
+    let registry = features::FeatureRegistry::builtin();
+    let known: Vec<&str> = registry.names().collect();
+    for name in config.features.enabled.keys().chain(config.features.timeouts.keys()) {
+        if !known.contains(&name.as_str()) {
+            eprintln!("Warning: unknown feature '{name}' in [features] config");
+        }
+    }
+    Ok(shared::config::with_extra_ignores(config, ignore_signals, ignore_categories))
+}
+
+/// Reject `--ignore-signal` IDs and `--ignore-category` names traur doesn't know.
+fn check_ignores(ignore_signals: &[String], ignore_categories: &[String]) -> Result<(), String> {
+    for id in ignore_signals {
+        if !shared::signal_registry::is_known_signal(id) {
+            return Err(format!(
+                "Unknown signal: {id}\nUse 'traur signals' to list available signal IDs."
+            ));
+        }
+    }
+    for cat in ignore_categories {
+        if shared::signal_registry::category_from_str(cat).is_none() {
+            return Err(format!(
+                "Unknown category: {cat}\nValid categories: Metadata, Pkgbuild, Behavioral, Temporal, InstallScript"
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
 fn cmd_scan(
-    package: Option<String>,
+    mut packages: Vec<String>,
     pkgbuild: Option<String>,
     _all_installed: bool,
     jobs: usize,
-    json: bool,
-    verbose: bool,
+    format: OutputFormat,
+    opts: &RenderOptions,
     flagged_only: bool,
+    notify: bool,
+    resume: bool,
+    config: &shared::config::Config,
 ) -> i32 {
     if let Some(path) = pkgbuild {
+        if std::path::Path::new(&path).is_dir() {
+            return match coordinator::scan_local_dir(std::path::Path::new(&path), config) {
+                Ok(result) => {
+                    shared::output::print(&result, format, opts);
+                    if result.tier >= shared::scoring::Tier::Suspicious { 1 } else { 0 }
+                }
+                Err(e) => {
+                    eprintln!("Error reading {path}: {e}");
+                    1
+                }
+            };
+        }
         let content = match std::fs::read_to_string(&path) {
             Ok(c) => c,
             Err(e) => {
@@ -130,25 +589,62 @@ fn cmd_scan(
             .and_then(|p| p.file_name())
             .and_then(|n| n.to_str())
             .unwrap_or("local");
-        let result = coordinator::scan_pkgbuild(name, &content);
-        if json {
-            shared::output::print_json(&result);
-        } else {
-            shared::output::print_text(&result, verbose);
-        }
+        let result = coordinator::scan_pkgbuild_with_config(name, &content, config);
+        shared::output::print(&result, format, opts);
         return if result.tier >= shared::scoring::Tier::Suspicious { 1 } else { 0 };
     }
 
-    if let Some(pkg) = package {
-        return cmd_scan_single(&pkg, json, verbose);
+    let mut seen = std::collections::HashSet::new();
+    packages.retain(|p| seen.insert(p.clone()));
+    match packages.len() {
+        // No package, no pkgbuild -> scan all installed AUR packages
+        0 => cmd_scan_all_installed(jobs, format, opts, flagged_only, notify, resume, config),
+        1 => cmd_scan_single(&packages[0], format, opts, config),
+        _ => cmd_scan_batch(packages, true, jobs, format, opts, flagged_only, notify, resume, config),
+    }
+}
+
+fn cmd_scan_package_file(
+    path: &str,
+    format: OutputFormat,
+    opts: &RenderOptions,
+    config: &shared::config::Config,
+) -> i32 {
+    match coordinator::scan_package_file(std::path::Path::new(path), config) {
+        Ok(result) => {
+            shared::output::print(&result, format, opts);
+            if result.tier >= shared::scoring::Tier::Suspicious { 1 } else { 0 }
+        }
+        Err(e) => {
+            eprintln!("Error scanning {path}: {e}");
+            1
+        }
     }
+}
 
-    // No package, no pkgbuild -> scan all installed AUR packages
-    cmd_scan_all_installed(jobs, json, verbose, flagged_only)
+fn cmd_scan_repo(
+    pkg: &str,
+    repo: &str,
+    format: OutputFormat,
+    opts: &RenderOptions,
+    config: &shared::config::Config,
+) -> i32 {
+    match coordinator::scan_repo_package(pkg, repo, format, opts, config) {
+        Ok(tier) => if tier >= shared::scoring::Tier::Suspicious { 1 } else { 0 },
+        Err(e) => {
+            eprintln!("Error scanning {pkg} from {repo}: {e}");
+            1
+        }
+    }
 }
 
-fn cmd_scan_single(pkg: &str, json: bool, verbose: bool) -> i32 {
-    match coordinator::scan_package(pkg, json, verbose) {
+fn cmd_scan_single(
+    pkg: &str,
+    format: OutputFormat,
+    opts: &RenderOptions,
+    config: &shared::config::Config,
+) -> i32 {
+    match coordinator::scan_package(pkg, format, opts, config) {
         Ok(tier) => {
             use shared::scoring::Tier;
             match tier {
@@ -163,53 +659,97 @@ fn cmd_scan_single(pkg: &str, json: bool, verbose: bool) -> i32 {
     }
 }
 
-fn cmd_scan_all_installed(jobs: usize, json: bool, verbose: bool, flagged_only: bool) -> i32 {
-    use crate::shared::bulk::{batch_fetch_metadata, clone_with_retry, prefetch_maintainer_packages};
+fn cmd_scan_all_installed(
+    jobs: usize,
+    format: OutputFormat,
+    opts: &RenderOptions,
+    flagged_only: bool,
+    notify: bool,
+    resume: bool,
+    config: &shared::config::Config,
+) -> i32 {
+    let names = match get_installed_aur_packages() {
+        Ok(names) if names.is_empty() => {
+            eprintln!("No AUR packages installed.");
+            return 0;
+        }
+        Ok(names) => names,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            return 1;
+        }
+    };
+    cmd_scan_batch(names, false, jobs, format, opts, flagged_only, notify, resume, config)
+}
+
+/// Scan several packages with one metadata batch, shared maintainer/upstream prefetch
+/// and a thread pool, then print a combined summary. `named` is true when the user
+/// listed the packages: those missing from the AUR are errors rather than skipped
+/// local packages, and any error fails the run.
+#[allow(clippy::too_many_arguments)]
+fn cmd_scan_batch(
+    mut names: Vec<String>,
+    named: bool,
+    jobs: usize,
+    format: OutputFormat,
+    opts: &RenderOptions,
+    flagged_only: bool,
+    notify: bool,
+    resume: bool,
+    config: &shared::config::Config,
+) -> i32 {
+    use crate::shared::bulk::{
+        clone_with_retry, prefetch_maintainer_packages, prefetch_upstream, try_batch_fetch_metadata,
+    };
+    use crate::shared::checkpoint::Checkpoint;
     use crate::shared::scoring::{ScanResult, Tier};
     use colored::Colorize;
     use indicatif::{ProgressBar, ProgressStyle};
     use rayon::prelude::*;
     use std::sync::atomic::{AtomicU64, Ordering};
 
-    let mut names = match get_installed_aur_packages() {
-        Ok(names) if names.is_empty() => {
-            eprintln!("No AUR packages installed.");
-            return 0;
-        }
-        Ok(names) => names,
+    let job = if named { "scan-packages" } else { "scan-installed" };
+    let (checkpoint, done) = match Checkpoint::open(job, resume, &shared::config::fingerprint(config)) {
+        Ok(c) => c,
         Err(e) => {
             eprintln!("Error: {e}");
             return 1;
         }
     };
+    // Results for packages uninstalled (or not listed) since the interrupted run are dropped.
+    let done: Vec<ScanResult> = done.into_iter().filter(|r| names.contains(&r.package)).collect();
+    names.retain(|n| !done.iter().any(|r| &r.package == n));
+    if !done.is_empty() {
+        eprintln!("  Resuming: {} already scanned, {} to go", done.len(), names.len());
+    }
 
-    eprintln!("  Fetching package metadata for {} installed packages...", names.len());
-    let metadata = batch_fetch_metadata(&names);
-    let not_found: Vec<&str> = names
-        .iter()
-        .filter(|n| !metadata.contains_key(n.as_str()))
-        .map(|n| n.as_str())
-        .collect();
-    if !not_found.is_empty() {
-        eprintln!("  Skipping {} not on AUR: {}", not_found.len(), not_found.join(", "));
-        names.retain(|n| metadata.contains_key(n.as_str()));
+    eprintln!("  Fetching package metadata for {} packages...", names.len());
+    let batch = try_batch_fetch_metadata(&names);
+    let (errors, skipped) = unscannable(&names, &batch, named);
+    for error in &errors {
+        eprintln!("  error: {error}");
     }
-    let total = names.len();
+    if !skipped.is_empty() {
+        eprintln!("  Skipping {} not on AUR: {}", skipped.len(), skipped.join(", "));
+    }
+    let missing = errors.len();
+    let metadata = batch.found;
+    names.retain(|n| metadata.contains_key(n.as_str()));
+    let total = names.len() + done.len() + missing;
     eprintln!(
         "{}",
-        format!("Scanning {} AUR packages...", total).bold()
+        format!("Scanning {} AUR packages...", names.len()).bold()
     );
 
-    let maintainer_packages = prefetch_maintainer_packages(&metadata);
-
-    let config = shared::config::load_config();
+    let maintainer_packages = prefetch_maintainer_packages(&metadata, config);
+    let upstream = prefetch_upstream(&metadata, config);
 
     let pool = rayon::ThreadPoolBuilder::new()
         .num_threads(jobs)
         .build()
         .expect("Failed to build thread pool");
 
-    let pb = ProgressBar::new(total as u64);
+    let pb = ProgressBar::new(names.len() as u64);
     pb.set_style(
         ProgressStyle::default_bar()
             .template("[{elapsed_precise}] {bar:40.cyan/blue} {pos}/{len} ({per_sec})")
@@ -218,9 +758,25 @@ fn cmd_scan_all_installed(jobs: usize, json: bool, verbose: bool, flagged_only:
     );
 
     let tier_counts: [AtomicU64; 5] = std::array::from_fn(|_| AtomicU64::new(0));
-    let error_count = AtomicU64::new(0);
+    let error_count = AtomicU64::new(missing as u64);
     let flagged = std::sync::Mutex::new(Vec::<ScanResult>::new());
 
+    let record = |scan: ScanResult| {
+        let idx = match scan.tier {
+            Tier::Trusted => 0,
+            Tier::Ok => 1,
+            Tier::Sketchy => 2,
+            Tier::Suspicious => 3,
+            Tier::Malicious => 4,
+        };
+        tier_counts[idx].fetch_add(1, Ordering::Relaxed);
+
+        if !flagged_only || scan.tier >= Tier::Sketchy {
+            flagged.lock().unwrap().push(scan);
+        }
+    };
+    done.into_iter().for_each(record);
+
     pool.install(|| {
         names.par_iter().for_each(|name| {
             let result = if let Some(meta) = metadata.get(name).cloned() {
@@ -230,29 +786,22 @@ fn cmd_scan_all_installed(jobs: usize, json: bool, verbose: bool, flagged_only:
                     .and_then(|m| maintainer_packages.get(m))
                     .cloned()
                     .unwrap_or_default();
+                let upstream_data = upstream.get(name).cloned().unwrap_or_default();
 
-                match clone_with_retry(name, meta, maint_pkgs) {
-                    Ok(ctx) => Ok(coordinator::run_analysis_with_config(&ctx, &config)),
-                    Err(e) => Err(e),
-                }
+                let deadline = coordinator::Deadline::start(config);
+
+                clone_with_retry(name, meta, maint_pkgs, upstream_data, deadline, config).map(|mut ctx| {
+                    coordinator::use_helper_baseline(&mut ctx);
+                    coordinator::run_analysis_within(&ctx, config, deadline)
+                })
             } else {
                 Err("not found on AUR".to_string())
             };
 
             match result {
                 Ok(scan) => {
-                    let idx = match scan.tier {
-                        Tier::Trusted => 0,
-                        Tier::Ok => 1,
-                        Tier::Sketchy => 2,
-                        Tier::Suspicious => 3,
-                        Tier::Malicious => 4,
-                    };
-                    tier_counts[idx].fetch_add(1, Ordering::Relaxed);
-
-                    if !flagged_only || scan.tier >= Tier::Sketchy {
-                        flagged.lock().unwrap().push(scan);
-                    }
+                    checkpoint.record(&scan);
+                    record(scan);
                 }
                 Err(e) => {
                     eprintln!("  error: {name}: {e}");
@@ -269,11 +818,24 @@ fn cmd_scan_all_installed(jobs: usize, json: bool, verbose: bool, flagged_only:
     let mut flagged = flagged.into_inner().unwrap();
     let errors = error_count.load(Ordering::Relaxed) as usize;
     let scanned = total - errors;
+    // Keep the checkpoint while some packages failed, so --resume retries just those.
+    if errors == 0 {
+        checkpoint.finish();
+    } else {
+        eprintln!("  {errors} packages failed; run again with --resume to retry only those");
+    }
 
-    if json {
-        flagged.sort_by(|a, b| a.score.cmp(&b.score));
-        let json_str = serde_json::to_string_pretty(&flagged).expect("Failed to serialize");
-        println!("{json_str}");
+    if notify {
+        use crate::shared::notify::notify_results;
+        if !config.notify.is_enabled() {
+            eprintln!("  Warning: --notify given but no [notify] channels configured");
+        }
+        notify_results(&config.notify, "found by scheduled scan", &flagged);
+    }
+
+    if format != OutputFormat::Text {
+        flagged.sort_by_key(|a| a.score);
+        shared::output::print_many(&flagged, format, opts);
     } else {
         println!();
         println!("{}", "=== traur scan results ===".bold());
@@ -288,7 +850,7 @@ fn cmd_scan_all_installed(jobs: usize, json: bool, verbose: bool, flagged_only:
         );
 
         if !flagged.is_empty() {
-            flagged.sort_by(|a, b| a.score.cmp(&b.score));
+            flagged.sort_by_key(|a| a.score);
             println!();
             println!(
                 "{}",
@@ -301,7 +863,7 @@ fn cmd_scan_all_installed(jobs: usize, json: bool, verbose: bool, flagged_only:
             );
             for result in &flagged {
                 println!();
-                shared::output::print_text(result, verbose);
+                shared::output::print(result, format, opts);
             }
         } else {
             println!();
@@ -311,7 +873,87 @@ fn cmd_scan_all_installed(jobs: usize, json: bool, verbose: bool, flagged_only:
 
     let has_critical = tier_counts[3].load(Ordering::Relaxed) > 0
         || tier_counts[4].load(Ordering::Relaxed) > 0;
-    if has_critical { 1 } else { 0 }
+    if has_critical || (named && errors > 0) { 1 } else { 0 }
+}
+
+/// Names a batch scan has no metadata for: errors for those whose RPC request failed,
+/// and for named packages the AUR does not have; installed packages not on the AUR
+/// (locally built ones) are only skipped. Returns the error messages and the skipped names.
+fn unscannable(names: &[String], batch: &shared::bulk::MetadataBatch, named: bool) -> (Vec<String>, Vec<String>) {
+    let mut errors = Vec::new();
+    let mut skipped = Vec::new();
+    for name in names.iter().filter(|n| !batch.found.contains_key(n.as_str())) {
+        match batch.failed.get(name) {
+            Some(e) => errors.push(format!("{name}: metadata fetch failed: {e}")),
+            None if named => errors.push(format!("{name}: not found on AUR")),
+            None => skipped.push(name.clone()),
+        }
+    }
+    (errors, skipped)
+}
+
+/// Package names from a file, or stdin for `-`, as `parse_package_list` reads them.
+fn read_package_list(path: &str) -> Result<Vec<String>, String> {
+    read_package_list_from(path, std::io::stdin())
+}
+
+fn read_package_list_from(path: &str, mut stdin: impl std::io::Read) -> Result<Vec<String>, String> {
+    let mut content = String::new();
+    if path == "-" {
+        stdin
+            .read_to_string(&mut content)
+            .map_err(|e| format!("Failed to read stdin: {e}"))?;
+    } else {
+        content = std::fs::read_to_string(path).map_err(|e| format!("Failed to read {path}: {e}"))?;
+    }
+    Ok(parse_package_list(&content))
+}
+
+/// The first word of each line, skipping blank lines and `#` comments, so `pacman -Qm`
+/// output works as is. Repeated names are kept once, in first-seen order.
+fn parse_package_list(content: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for name in content
+        .lines()
+        .filter_map(|line| line.split_whitespace().next())
+        .filter(|name| !name.starts_with('#'))
+    {
+        if !names.iter().any(|n| n == name) {
+            names.push(name.to_string());
+        }
+    }
+    names
+}
+
+/// AUR packages that `packages` depend on only through .SRCINFO (`scan --with-deps`),
+/// leaving out those already listed and names that are not on the AUR.
+fn srcinfo_only_aur_deps(packages: &[String]) -> Vec<String> {
+    use shared::{aur_git, bulk, cache};
+
+    let metadata = bulk::batch_fetch_metadata(packages);
+    let git_cache = cache::git_cache_dir();
+    let cache_str = git_cache.to_str().unwrap_or("/tmp/traur-git");
+    let mut extra: Vec<String> = Vec::new();
+    for meta in packages.iter().filter_map(|name| metadata.get(name)) {
+        let package_base = meta.package_base.as_deref().unwrap_or(&meta.name);
+        let Some(pkgbuild) = aur_git::ensure_repo(package_base, cache_str)
+            .and_then(|repo| aur_git::read_pkgbuild(&repo))
+            .ok()
+        else {
+            continue;
+        };
+        for dep in features::depends_analysis::srcinfo::srcinfo_only(meta, &pkgbuild) {
+            if !packages.contains(&dep) && !extra.contains(&dep) {
+                extra.push(dep);
+            }
+        }
+    }
+    if extra.is_empty() {
+        return extra;
+    }
+    let on_aur = bulk::batch_fetch_metadata(&extra);
+    extra.retain(|dep| on_aur.contains_key(dep));
+    extra
 }
 
 /// Get list of installed AUR (foreign) package names via `pacman -Qm`.
@@ -344,11 +986,31 @@ fn get_installed_aur_packages() -> Result<Vec<String>, String> {
     Ok(names)
 }
 
-fn cmd_allow(package: &str) -> i32 {
-    match shared::config::add_to_whitelist(package) {
+fn cmd_allow(package: &str, pin: bool) -> i32 {
+    let pin_hash = if pin {
+        match current_pkgbuild_hash(package) {
+            Ok(hash) => Some(hash),
+            Err(e) => {
+                eprintln!("Error: {e}");
+                return 1;
+            }
+        }
+    } else {
+        None
+    };
+    match shared::config::add_to_whitelist(package, pin_hash.clone()) {
         Ok(()) => {
-            eprintln!("Whitelisted: {package}");
+            match pin_hash {
+                Some(hash) => eprintln!("Whitelisted: {package} (pinned to PKGBUILD {})", &hash[..12]),
+                None => eprintln!("Whitelisted: {package}"),
+            }
             eprintln!("  Saved to {}", shared::config::config_path().display());
+            if shared::config::load_system_config().is_some() {
+                eprintln!(
+                    "  Note: {} is present; only its whitelist applies, so this entry has no effect",
+                    shared::config::SYSTEM_CONFIG_PATH
+                );
+            }
             0
         }
         Err(e) => {
@@ -358,7 +1020,35 @@ fn cmd_allow(package: &str) -> i32 {
     }
 }
 
-fn cmd_signals(json: bool) -> i32 {
+/// SHA-256 of the package's current PKGBUILD as committed, as pinned by `allow --pin`.
+fn current_pkgbuild_hash(package: &str) -> Result<String, String> {
+    use shared::{aur_git, aur_rpc, cache, known_good, runtime};
+
+    let metadata = runtime::block_on(aur_rpc::fetch_package_info(package))?;
+    let base = metadata.package_base.as_deref().unwrap_or(package);
+    let git_cache = cache::git_cache_dir();
+    let repo_path = aur_git::ensure_repo(base, git_cache.to_str().unwrap_or("/tmp/traur-git"))?;
+    let pkgbuild = aur_git::read_pkgbuild(&repo_path)?;
+    Ok(known_good::sha256_hex(&aur_git::strip_inlined(&pkgbuild)))
+}
+
+fn cmd_cache_gc(max_size_mb: Option<u64>, max_age_days: Option<u64>) -> i32 {
+    let config = shared::config::load_config();
+    let max_bytes = max_size_mb.map_or(config.cache.max_size_bytes(), |mb| mb * 1024 * 1024);
+    let max_age = max_age_days.map(|days| std::time::Duration::from_secs(days * 86400));
+    let stats = shared::cache::gc_git_cache(max_bytes, max_age);
+    eprintln!(
+        "Evicted {} clones ({} MiB); {} kept ({} MiB) in {}",
+        stats.removed,
+        stats.freed_bytes / (1024 * 1024),
+        stats.kept,
+        stats.kept_bytes / (1024 * 1024),
+        shared::cache::git_cache_dir().display()
+    );
+    0
+}
+
+fn cmd_signals(json: bool, by_feature: bool) -> i32 {
     use shared::scoring::SignalCategory;
     use shared::signal_registry::all_signal_definitions;
 
@@ -374,6 +1064,8 @@ fn cmd_signals(json: bool) -> i32 {
         let cat_str = format!("{:?}", d.category);
         ignored_categories.iter().any(|c| c.eq_ignore_ascii_case(&cat_str))
     };
+    // Signals of a feature turned off in `[features]` can't fire
+    let is_disabled = |d: &shared::signal_registry::SignalDef| !config.features.is_enabled(d.emitted_by);
 
     if json {
         let entries: Vec<serde_json::Value> = defs
@@ -383,7 +1075,9 @@ fn cmd_signals(json: bool) -> i32 {
                     "id": d.id,
                     "category": format!("{:?}", d.category),
                     "description": d.description,
+                    "emitted_by": d.emitted_by,
                     "ignored": is_ignored(d),
+                    "disabled": is_disabled(d),
                 })
             })
             .collect();
@@ -394,25 +1088,49 @@ fn cmd_signals(json: bool) -> i32 {
         return 0;
     }
 
-    let categories = [
-        (SignalCategory::Metadata, "Metadata (weight 0.15)"),
-        (SignalCategory::Pkgbuild, "Pkgbuild (weight 0.45)"),
-        (SignalCategory::Behavioral, "Behavioral (weight 0.25)"),
-        (SignalCategory::Temporal, "Temporal (weight 0.15)"),
-    ];
+    let groups: Vec<(String, Vec<_>)> = if by_feature {
+        let mut emitters: Vec<&str> = defs.iter().map(|d| d.emitted_by).collect();
+        emitters.sort_unstable();
+        emitters.dedup();
+        emitters
+            .into_iter()
+            .map(|name| {
+                let label = if config.features.is_enabled(name) { name.to_string() } else { format!("{name} (disabled)") };
+                (label, defs.iter().filter(|d| d.emitted_by == name).collect())
+            })
+            .collect()
+    } else {
+        [
+            (SignalCategory::Metadata, "Metadata (weight 0.15)"),
+            (SignalCategory::Pkgbuild, "Pkgbuild (weight 0.45)"),
+            (SignalCategory::Behavioral, "Behavioral (weight 0.25)"),
+            (SignalCategory::Temporal, "Temporal (weight 0.15)"),
+            (SignalCategory::InstallScript, "InstallScript (weight 0.60)"),
+        ]
+        .into_iter()
+        .map(|(cat, label)| (label.to_string(), defs.iter().filter(|d| d.category == cat).collect()))
+        .collect()
+    };
 
     let mut total = 0;
     let mut ignored_count = 0;
+    let mut disabled_count = 0;
 
-    for (cat, label) in &categories {
-        let cat_defs: Vec<_> = defs.iter().filter(|d| d.category == *cat).collect();
-        if cat_defs.is_empty() {
+    for (label, group_defs) in &groups {
+        if group_defs.is_empty() {
             continue;
         }
         println!("\n  {label}");
-        for d in &cat_defs {
+        for d in group_defs {
             let sig_ignored = is_ignored(d);
-            let marker = if sig_ignored { " [IGNORED]" } else { "" };
+            let sig_disabled = is_disabled(d);
+            let marker = if sig_ignored {
+                " [IGNORED]".to_string()
+            } else if sig_disabled && !by_feature {
+                format!(" [DISABLED: {} is off]", d.emitted_by)
+            } else {
+                String::new()
+            };
             println!(
                 "  {:<36} {}{}",
                 d.id, d.description, marker
@@ -420,15 +1138,88 @@ fn cmd_signals(json: bool) -> i32 {
             total += 1;
             if sig_ignored {
                 ignored_count += 1;
+            } else if sig_disabled {
+                disabled_count += 1;
             }
         }
     }
 
     println!();
+    let mut notes = Vec::new();
     if ignored_count > 0 {
-        println!("  {total} signals ({ignored_count} ignored)");
-    } else {
+        notes.push(format!("{ignored_count} ignored"));
+    }
+    if disabled_count > 0 {
+        notes.push(format!("{disabled_count} from disabled features"));
+    }
+    if notes.is_empty() {
         println!("  {total} signals");
+    } else {
+        println!("  {total} signals ({})", notes.join(", "));
+    }
+    0
+}
+
+/// `traur signals --gates`: the override gates, which send a package straight to
+/// MALICIOUS, for review before demoting any with `[scoring] demoted_gates`.
+fn cmd_signal_gates(json: bool) -> i32 {
+    use shared::signal_registry::{all_signal_definitions, gate_rationale};
+
+    let config = shared::config::load_config();
+    let gates: Vec<_> = all_signal_definitions().into_iter().filter(|d| d.is_override_gate).collect();
+    let demoted = |id: &str| shared::config::is_gate_demoted(&config, id);
+    let ignored = |id: &str| config.ignored.signals.iter().any(|s| s == id);
+
+    if json {
+        let entries: Vec<serde_json::Value> = gates
+            .iter()
+            .map(|d| {
+                serde_json::json!({
+                    "id": d.id,
+                    "category": format!("{:?}", d.category),
+                    "points": d.points,
+                    "description": d.description,
+                    "pattern": d.pattern,
+                    "rationale": gate_rationale(&d.id),
+                    "emitted_by": d.emitted_by,
+                    "demoted": demoted(&d.id),
+                    "ignored": ignored(&d.id),
+                })
+            })
+            .collect();
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&entries).expect("Failed to serialize")
+        );
+        return 0;
+    }
+
+    let mut demoted_count = 0;
+    for d in &gates {
+        let marker = if ignored(&d.id) {
+            " [IGNORED]"
+        } else if demoted(&d.id) {
+            demoted_count += 1;
+            " [DEMOTED]"
+        } else {
+            ""
+        };
+        println!("\n  {} ({} points){marker}", d.id, d.points);
+        println!("    {}", d.description);
+        match d.pattern {
+            Some(ref pattern) => println!("    regex: {pattern}"),
+            None => println!("    detected in {} code", d.emitted_by),
+        }
+        if let Some(rationale) = gate_rationale(&d.id) {
+            println!("    why:   {rationale}");
+        }
+    }
+
+    println!();
+    if demoted_count > 0 {
+        println!("  {} override gates ({demoted_count} demoted)", gates.len());
+    } else {
+        println!("  {} override gates", gates.len());
     }
     0
 }
@@ -453,7 +1244,7 @@ fn cmd_ignore(signal_id: Option<&str>, category: Option<&str>) -> i32 {
         (None, Some(cat)) => {
             if shared::signal_registry::category_from_str(cat).is_none() {
                 eprintln!("Unknown category: {cat}");
-                eprintln!("Valid categories: Metadata, Pkgbuild, Behavioral, Temporal");
+                eprintln!("Valid categories: Metadata, Pkgbuild, Behavioral, Temporal, InstallScript");
                 return 1;
             }
             match shared::config::add_category_to_ignored(cat) {
@@ -479,6 +1270,11 @@ fn cmd_unignore(signal_id: Option<&str>, category: Option<&str>) -> i32 {
                 Ok(()) => {
                     eprintln!("Unignored: {id}");
                     eprintln!("  Saved to {}", shared::config::config_path().display());
+                    if shared::config::load_system_config()
+                        .is_some_and(|system| system.ignored.signals.iter().any(|s| s == id))
+                    {
+                        eprintln!("  Note: still ignored by {}", shared::config::SYSTEM_CONFIG_PATH);
+                    }
                     0
                 }
                 Err(e) => { eprintln!("Error: {e}"); 1 }
@@ -487,13 +1283,18 @@ fn cmd_unignore(signal_id: Option<&str>, category: Option<&str>) -> i32 {
         (None, Some(cat)) => {
             if shared::signal_registry::category_from_str(cat).is_none() {
                 eprintln!("Unknown category: {cat}");
-                eprintln!("Valid categories: Metadata, Pkgbuild, Behavioral, Temporal");
+                eprintln!("Valid categories: Metadata, Pkgbuild, Behavioral, Temporal, InstallScript");
                 return 1;
             }
             match shared::config::remove_category_from_ignored(cat) {
                 Ok(()) => {
                     eprintln!("Unignored category: {cat}");
                     eprintln!("  Saved to {}", shared::config::config_path().display());
+                    if shared::config::load_system_config().is_some_and(|system| {
+                        system.ignored.categories.iter().any(|c| c.eq_ignore_ascii_case(cat))
+                    }) {
+                        eprintln!("  Note: still ignored by {}", shared::config::SYSTEM_CONFIG_PATH);
+                    }
                     0
                 }
                 Err(e) => { eprintln!("Error: {e}"); 1 }
@@ -505,3 +1306,82 @@ fn cmd_unignore(signal_id: Option<&str>, category: Option<&str>) -> i32 {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::bulk::MetadataBatch;
+
+    fn batch() -> MetadataBatch {
+        let mut batch = MetadataBatch::default();
+        batch.found.insert(
+            "foo".into(),
+            serde_json::from_value(serde_json::json!({
+                "Name": "foo", "PackageBase": null, "URL": null, "NumVotes": 1, "Popularity": 0.0,
+                "OutOfDate": null, "Maintainer": null, "Submitter": null, "FirstSubmitted": 0,
+                "LastModified": 0, "License": null, "Description": null,
+            }))
+            .unwrap(),
+        );
+        batch.failed.insert("bar".into(), "HTTP 503".into());
+        batch
+    }
+
+    #[test]
+    fn package_list_skips_comments_blanks_and_repeats() {
+        let content = "# pacman -Qm\nfoo 1.0-1\n\n   \n\tbar-git r12.abc-1  # pinned\n#baz\nfoo 1.1-1\n";
+        assert_eq!(parse_package_list(content), ["foo", "bar-git"]);
+        assert!(parse_package_list("# nothing\n\n").is_empty());
+    }
+
+    #[test]
+    fn package_list_from_stdin_or_file() {
+        let stdin = std::io::Cursor::new("foo\nbar\n");
+        assert_eq!(read_package_list_from("-", stdin).unwrap(), ["foo", "bar"]);
+
+        let path = std::env::temp_dir().join(format!("traur-package-list-{}", std::process::id()));
+        std::fs::write(&path, "baz 2.0-1\n").unwrap();
+        let listed = read_package_list_from(path.to_str().unwrap(), std::io::empty());
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(listed.unwrap(), ["baz"]);
+
+        let missing = read_package_list_from("/nonexistent/traur-list", std::io::empty());
+        assert!(missing.unwrap_err().starts_with("Failed to read /nonexistent/traur-list"));
+    }
+
+    #[test]
+    fn named_batch_tells_failed_fetch_from_absence() {
+        let names: Vec<String> = ["foo", "bar", "nope"].map(String::from).into();
+        let (errors, skipped) = unscannable(&names, &batch(), true);
+        assert_eq!(errors, ["bar: metadata fetch failed: HTTP 503", "nope: not found on AUR"]);
+        assert!(skipped.is_empty());
+    }
+
+    #[test]
+    fn installed_batch_skips_absent_but_reports_failed_fetch() {
+        let names: Vec<String> = ["foo", "bar", "local-debug"].map(String::from).into();
+        let (errors, skipped) = unscannable(&names, &batch(), false);
+        assert_eq!(errors, ["bar: metadata fetch failed: HTTP 503"]);
+        assert_eq!(skipped, ["local-debug"]);
+    }
+
+    #[test]
+    fn with_deps_needs_packages_or_a_list() {
+        let parse = |args: &[&str]| Cli::try_parse_from([&["traur", "scan"], args].concat());
+        assert!(parse(&["foo", "--with-deps"]).is_ok());
+        assert!(parse(&["--from-file", "list.txt", "--with-deps"]).is_ok());
+        assert!(parse(&["--with-deps"]).is_err());
+        assert!(parse(&["--all-installed", "--with-deps"]).is_err());
+    }
+
+    #[test]
+    fn ignore_flags_accept_known_ids_and_reject_unknown_ones() {
+        let ids = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert!(check_ignores(&ids(&["M-VOTES-ZERO", "IS-P-CURL-PIPE"]), &ids(&["Temporal", "InstallScript"])).is_ok());
+
+        let err = check_ignores(&ids(&["M-VOTES-ZERO", "M-NO-SUCH-SIGNAL"]), &[]).unwrap_err();
+        assert!(err.starts_with("Unknown signal: M-NO-SUCH-SIGNAL"), "{err}");
+        let err = check_ignores(&[], &ids(&["Social"])).unwrap_err();
+        assert!(err.starts_with("Unknown category: Social"), "{err}");
+    }
+}
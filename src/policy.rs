@@ -0,0 +1,330 @@
+//! `traur policy`: share thresholds, ignores, trusted domains and the whitelist as
+//! one versioned, optionally signed file.
+//!
+//! A bundle is TOML. Its last line may be `# signature: ed25519 <public key> <signature>`
+//! (hex), an Ed25519 signature over every byte before that line, made with a key from
+//! `traur policy keygen`. Machines that set `[policy] trusted_keys` refuse bundles not
+//! signed by one of those keys.
+
+use crate::shared::config::{self, Config, IgnoredConfig, ThresholdConfig, WhitelistConfig};
+use clap::Subcommand;
+use ring::rand::SystemRandom;
+use ring::signature::{self, Ed25519KeyPair, KeyPair, UnparsedPublicKey};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Bundle layout version; bundles of another format are refused.
+const FORMAT: u32 = 1;
+
+const SIGNATURE_PREFIX: &str = "# signature: ed25519 ";
+
+#[derive(Subcommand)]
+pub enum PolicyAction {
+    /// Write the effective thresholds, ignores, trusted domains and whitelist (with pins) to a bundle
+    Export {
+        /// Bundle file to write
+        file: String,
+        /// Sign the bundle with this private key (from `traur policy keygen`)
+        #[arg(long, value_name = "KEY_FILE")]
+        sign: Option<String>,
+        /// Policy version; defaults to the current Unix time, so later exports supersede earlier ones
+        #[arg(long)]
+        version: Option<u64>,
+    },
+    /// Replace this machine's thresholds, ignores, trusted domains and whitelist with a bundle's
+    Import {
+        /// Bundle file to read
+        file: String,
+        /// Write the system policy (/etc/traur/config.toml) instead of the user config
+        #[arg(long)]
+        system: bool,
+        /// Require a signature by this public key (hex), on top of [policy] trusted_keys
+        #[arg(long, value_name = "PUBLIC_KEY")]
+        key: Option<String>,
+        /// Import even if the bundle is older than the installed policy
+        #[arg(long)]
+        force: bool,
+    },
+    /// Generate an Ed25519 signing key; prints the public key to put in [policy] trusted_keys
+    Keygen {
+        /// Private key file to create
+        file: String,
+    },
+}
+
+/// What a bundle carries.
+#[derive(Debug, Serialize, Deserialize)]
+struct PolicyBundle {
+    format: u32,
+    version: u64,
+    /// Unix time of the export.
+    created: u64,
+    #[serde(default)]
+    trusted_domains: Vec<String>,
+    #[serde(default)]
+    thresholds: ThresholdConfig,
+    #[serde(default)]
+    ignored: IgnoredConfig,
+    #[serde(default)]
+    whitelist: WhitelistConfig,
+}
+
+pub fn run(action: PolicyAction) -> i32 {
+    let result = match action {
+        PolicyAction::Export { file, sign, version } => export(Path::new(&file), sign.as_deref(), version),
+        PolicyAction::Import { file, system, key, force } => import(Path::new(&file), system, key, force),
+        PolicyAction::Keygen { file } => keygen(Path::new(&file)),
+    };
+    match result {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            1
+        }
+    }
+}
+
+fn export(file: &Path, sign: Option<&str>, version: Option<u64>) -> Result<(), String> {
+    let config = config::load_config();
+    let created = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+    let bundle = PolicyBundle {
+        format: FORMAT,
+        version: version.unwrap_or(created),
+        created,
+        trusted_domains: config.trusted_domains,
+        thresholds: config.thresholds,
+        ignored: config.ignored,
+        whitelist: config.whitelist,
+    };
+    let key = sign.map(read_key).transpose()?;
+    let text = render(&bundle, key.as_ref())?;
+    std::fs::write(file, text).map_err(|e| format!("Failed to write {}: {e}", file.display()))?;
+    eprintln!(
+        "Exported policy version {} to {}{}",
+        bundle.version,
+        file.display(),
+        if key.is_some() { " (signed)" } else { " (unsigned)" }
+    );
+    Ok(())
+}
+
+fn import(file: &Path, system: bool, key: Option<String>, force: bool) -> Result<(), String> {
+    let text = std::fs::read_to_string(file).map_err(|e| format!("Failed to read {}: {e}", file.display()))?;
+    let mut trusted = config::load_config().policy.trusted_keys;
+    trusted.extend(key.map(|k| k.to_ascii_lowercase()));
+    let (bundle, signer) = parse(&text, &trusted)?;
+
+    let mut target = if system {
+        config::load_system_config().unwrap_or_default()
+    } else {
+        config::load_user_config()
+    };
+    let summary = format!(
+        "{} whitelisted ({} pinned), {} ignored signals, {} trusted domains, block at {}",
+        bundle.whitelist.packages.len(),
+        bundle.whitelist.pins.len(),
+        bundle.ignored.signals.len(),
+        bundle.trusted_domains.len(),
+        bundle.thresholds.block_at
+    );
+    let version = bundle.version;
+    apply(&mut target, bundle, force)?;
+    if system {
+        config::save_system_config(&target)?;
+    } else {
+        config::save_config(&target)?;
+    }
+
+    let path = if system { Path::new(config::SYSTEM_CONFIG_PATH).to_path_buf() } else { config::config_path() };
+    eprintln!("Imported policy version {version} into {}: {summary}", path.display());
+    match signer {
+        Some(key) => eprintln!("  Signed by {key}"),
+        None => eprintln!("  Warning: the bundle is unsigned; set [policy] trusted_keys to require signatures"),
+    }
+    if !system && config::load_system_config().is_some() {
+        eprintln!(
+            "  Note: {} is present; only its thresholds and whitelist apply (use --system)",
+            config::SYSTEM_CONFIG_PATH
+        );
+    }
+    Ok(())
+}
+
+fn keygen(file: &Path) -> Result<(), String> {
+    if file.exists() {
+        return Err(format!("{} already exists", file.display()));
+    }
+    let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new()).map_err(|_| "Failed to generate key".to_string())?;
+    let pair = Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).map_err(|_| "Failed to generate key".to_string())?;
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut out = options.open(file).map_err(|e| format!("Failed to create {}: {e}", file.display()))?;
+    writeln!(out, "{}", to_hex(pkcs8.as_ref())).map_err(|e| format!("Failed to write {}: {e}", file.display()))?;
+
+    eprintln!("Wrote private key to {} (keep it secret)", file.display());
+    eprintln!("Public key, for [policy] trusted_keys on machines that import your bundles:");
+    println!("{}", to_hex(pair.public_key().as_ref()));
+    Ok(())
+}
+
+fn read_key(file: &str) -> Result<Ed25519KeyPair, String> {
+    let text = std::fs::read_to_string(file).map_err(|e| format!("Failed to read {file}: {e}"))?;
+    let pkcs8 = from_hex(text.trim()).ok_or_else(|| format!("{file} is not a key from `traur policy keygen`"))?;
+    Ed25519KeyPair::from_pkcs8(&pkcs8).map_err(|_| format!("{file} is not a key from `traur policy keygen`"))
+}
+
+/// The bundle as TOML, with a signature line when `key` is given.
+fn render(bundle: &PolicyBundle, key: Option<&Ed25519KeyPair>) -> Result<String, String> {
+    let mut text = format!(
+        "# traur policy bundle; import with `traur policy import <file>`\n{}",
+        toml::to_string_pretty(bundle).map_err(|e| format!("Failed to serialize policy: {e}"))?
+    );
+    if let Some(key) = key {
+        if !text.ends_with('\n') {
+            text.push('\n');
+        }
+        let sig = key.sign(text.as_bytes());
+        text += &format!("{SIGNATURE_PREFIX}{} {}\n", to_hex(key.public_key().as_ref()), to_hex(sig.as_ref()));
+    }
+    Ok(text)
+}
+
+/// Parse and verify a bundle. Returns it with the signing key, if signed. With
+/// `trusted` keys given, the bundle must be signed by one of them.
+fn parse(text: &str, trusted: &[String]) -> Result<(PolicyBundle, Option<String>), String> {
+    let (body, signer) = match text.rfind(SIGNATURE_PREFIX) {
+        Some(pos) if pos == 0 || text[..pos].ends_with('\n') => {
+            let (body, line) = text.split_at(pos);
+            let mut fields = line[SIGNATURE_PREFIX.len()..].split_whitespace();
+            let (Some(key), Some(sig)) = (fields.next(), fields.next()) else {
+                return Err("Malformed signature line".to_string());
+            };
+            let (key_bytes, sig_bytes) = from_hex(key).zip(from_hex(sig)).ok_or("Malformed signature line")?;
+            UnparsedPublicKey::new(&signature::ED25519, key_bytes)
+                .verify(body.as_bytes(), &sig_bytes)
+                .map_err(|_| "Bad signature: the bundle was modified after signing".to_string())?;
+            (body, Some(key.to_ascii_lowercase()))
+        }
+        _ => (text, None),
+    };
+
+    if !trusted.is_empty() {
+        match signer {
+            None => return Err("The bundle is unsigned, but [policy] trusted_keys requires a signature".to_string()),
+            Some(ref key) if !trusted.iter().any(|t| t.eq_ignore_ascii_case(key)) => {
+                return Err(format!("The bundle is signed by {key}, which is not in [policy] trusted_keys"));
+            }
+            Some(_) => {}
+        }
+    }
+
+    let bundle: PolicyBundle = toml::from_str(body).map_err(|e| format!("Invalid policy bundle: {e}"))?;
+    if bundle.format != FORMAT {
+        return Err(format!(
+            "Unsupported policy format {} (this traur reads format {FORMAT})",
+            bundle.format
+        ));
+    }
+    Ok((bundle, signer))
+}
+
+/// Replace `config`'s policy settings with the bundle's. Older versions than the one
+/// installed are refused unless `force`d.
+fn apply(config: &mut Config, bundle: PolicyBundle, force: bool) -> Result<(), String> {
+    if let Some(installed) = config.policy.version
+        && bundle.version < installed
+        && !force
+    {
+        return Err(format!(
+            "The bundle is version {}, older than the installed policy version {installed} (use --force to import anyway)",
+            bundle.version
+        ));
+    }
+    config.policy.version = Some(bundle.version);
+    config.trusted_domains = bundle.trusted_domains;
+    config.thresholds = bundle.thresholds;
+    config.ignored = bundle.ignored;
+    config.whitelist = bundle.whitelist;
+    Ok(())
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len()).step_by(2).map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bundle(version: u64) -> PolicyBundle {
+        let mut whitelist = WhitelistConfig { packages: vec!["foo-bin".into(), "bar".into()], ..Default::default() };
+        whitelist.pins.insert("foo-bin".into(), "ab".repeat(32));
+        PolicyBundle {
+            format: FORMAT,
+            version,
+            created: 1_700_000_000,
+            trusted_domains: vec!["dl.example.org".into()],
+            thresholds: ThresholdConfig { block_at: "high".into(), warn_at: "medium".into(), ..Default::default() },
+            ignored: IgnoredConfig { signals: vec!["P-HTTP-SOURCE".into()], categories: vec![] },
+            whitelist,
+        }
+    }
+
+    fn key() -> Ed25519KeyPair {
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new()).unwrap();
+        Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap()
+    }
+
+    #[test]
+    fn signed_bundle_round_trips_and_detects_tampering() {
+        let key = key();
+        let public = to_hex(key.public_key().as_ref());
+        let text = render(&bundle(7), Some(&key)).unwrap();
+
+        let (parsed, signer) = parse(&text, std::slice::from_ref(&public)).unwrap();
+        assert_eq!(signer.as_deref(), Some(public.as_str()));
+        assert_eq!(parsed.version, 7);
+        assert_eq!(parsed.whitelist.pins["foo-bin"], "ab".repeat(32));
+
+        let tampered = text.replace("block_at = \"high\"", "block_at = \"malicious\"");
+        assert!(parse(&tampered, &[]).unwrap_err().starts_with("Bad signature"));
+        let other = to_hex(self::key().public_key().as_ref());
+        assert!(parse(&text, &[other]).unwrap_err().contains("not in [policy] trusted_keys"));
+    }
+
+    #[test]
+    fn unsigned_bundle_only_without_trusted_keys() {
+        let text = render(&bundle(1), None).unwrap();
+        assert!(parse(&text, &[]).unwrap().1.is_none());
+        assert!(parse(&text, &["00".repeat(32)]).unwrap_err().contains("unsigned"));
+        assert!(parse(&text.replace("format = 1", "format = 2"), &[]).unwrap_err().contains("Unsupported policy format"));
+    }
+
+    #[test]
+    fn apply_replaces_policy_and_refuses_downgrade() {
+        let mut config = Config::default();
+        config.whitelist.packages.push("old".into());
+        config.notify.desktop = true;
+        apply(&mut config, bundle(5), false).unwrap();
+        assert_eq!(config.whitelist.packages, ["foo-bin", "bar"]);
+        assert_eq!(config.thresholds.block_at, "high");
+        assert_eq!(config.policy.version, Some(5));
+        assert!(config.notify.desktop, "settings outside the policy are kept");
+
+        assert!(apply(&mut config, bundle(4), false).unwrap_err().contains("older than the installed"));
+        apply(&mut config, bundle(4), true).unwrap();
+        assert_eq!(config.policy.version, Some(4));
+    }
+}
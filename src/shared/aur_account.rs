@@ -0,0 +1,93 @@
+use crate::shared::models::AurAccount;
+use crate::shared::runtime;
+use regex::Regex;
+use std::sync::LazyLock;
+
+static REGISTERED_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"<th>\s*Registration date:\s*</th>\s*<td>\s*(\d{4})-(\d{2})-(\d{2})").unwrap()
+});
+
+static STATUS_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"<th>\s*Status:\s*</th>\s*<td>\s*([^<]*)").unwrap()
+});
+
+/// Fetch the AUR account page of `username`. aurweb only shows account pages to
+/// logged-in users, so this needs the value of an `AURSID` session cookie.
+/// None on error or when the page has no account details (expired session).
+pub async fn fetch_account(username: &str, session: &str) -> Option<AurAccount> {
+    let url = format!("https://aur.archlinux.org/account/{username}");
+    runtime::throttle_aur_web().await;
+
+    let resp = runtime::client()
+        .get(&url)
+        .header(reqwest::header::COOKIE, format!("AURSID={session}"))
+        .timeout(runtime::request_timeout())
+        .send()
+        .await
+        .ok()?;
+    if !resp.status().is_success() {
+        return None;
+    }
+
+    parse_account(&resp.text().await.ok()?)
+}
+
+/// Registration date and suspension status from account page HTML.
+fn parse_account(html: &str) -> Option<AurAccount> {
+    let registered_at = REGISTERED_RE.captures(html).and_then(|caps| {
+        let (year, month, day) = (caps[1].parse().ok()?, caps[2].parse().ok()?, caps[3].parse().ok()?);
+        unix_date(year, month, day)
+    });
+    let status = STATUS_RE.captures(html).map(|caps| caps[1].trim().to_string());
+    if registered_at.is_none() && status.is_none() {
+        return None;
+    }
+
+    Some(AurAccount {
+        registered_at,
+        suspended: status.is_some_and(|s| s.eq_ignore_ascii_case("suspended")),
+    })
+}
+
+/// Unix seconds at midnight UTC of a calendar date (proleptic Gregorian).
+fn unix_date(year: i64, month: i64, day: i64) -> Option<u64> {
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    // Days since 1970-01-01, counting years from March so leap days fall at the end
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let year_of_era = y - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146_097 + day_of_era - 719_468;
+    u64::try_from(days * 86_400).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_account_page() {
+        let html = r#"
+        <table class="arch-bordered">
+          <tr><th>Username:</th><td>alice</td></tr>
+          <tr><th>Status:</th>
+            <td>Suspended</td></tr>
+          <tr><th>Registration date:</th><td>2024-03-01</td></tr>
+        </table>"#;
+        let account = parse_account(html).unwrap();
+        assert_eq!(account.registered_at, Some(1_709_251_200));
+        assert!(account.suspended);
+
+        let active = parse_account("<th>Status:</th><td>Active</td><th>Registration date:</th><td>1970-01-02</td>").unwrap();
+        assert_eq!(active.registered_at, Some(86_400));
+        assert!(!active.suspended);
+    }
+
+    #[test]
+    fn login_page_has_no_account() {
+        assert!(parse_account("<p>You must log in to view user information.</p>").is_none());
+    }
+}
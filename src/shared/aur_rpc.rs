@@ -1,4 +1,5 @@
 use crate::shared::models::AurPackage;
+use crate::shared::runtime;
 use serde::Deserialize;
 
 const AUR_RPC_BASE: &str = "https://aur.archlinux.org/rpc/v5";
@@ -11,12 +12,9 @@ struct RpcResponse {
 }
 
 /// Fetch info for a single package from the AUR RPC API.
-pub fn fetch_package_info(package_name: &str) -> Result<AurPackage, String> {
+pub async fn fetch_package_info(package_name: &str) -> Result<AurPackage, String> {
     let url = format!("{AUR_RPC_BASE}/info?arg[]={package_name}");
-    let resp: RpcResponse = reqwest::blocking::get(&url)
-        .map_err(|e| format!("HTTP request failed: {e}"))?
-        .json()
-        .map_err(|e| format!("Failed to parse AUR response: {e}"))?;
+    let resp = rpc_get(&url).await?;
 
     if resp.result_count == 0 {
         return Err(format!("Package '{package_name}' not found on AUR"));
@@ -29,24 +27,25 @@ pub fn fetch_package_info(package_name: &str) -> Result<AurPackage, String> {
 }
 
 /// Fetch info for multiple packages in a single request.
-pub fn fetch_packages_info(names: &[&str]) -> Result<Vec<AurPackage>, String> {
+pub async fn fetch_packages_info(names: &[&str]) -> Result<Vec<AurPackage>, String> {
     let args: String = names.iter().map(|n| format!("arg[]={n}")).collect::<Vec<_>>().join("&");
     let url = format!("{AUR_RPC_BASE}/info?{args}");
-    let resp: RpcResponse = reqwest::blocking::get(&url)
-        .map_err(|e| format!("HTTP request failed: {e}"))?
-        .json()
-        .map_err(|e| format!("Failed to parse AUR response: {e}"))?;
-
-    Ok(resp.results)
+    Ok(rpc_get(&url).await?.results)
 }
 
 /// Fetch all packages maintained by a given user.
-pub fn fetch_maintainer_packages(maintainer: &str) -> Result<Vec<AurPackage>, String> {
+pub async fn fetch_maintainer_packages(maintainer: &str) -> Result<Vec<AurPackage>, String> {
     let url = format!("{AUR_RPC_BASE}/search/{maintainer}?by=maintainer");
-    let resp: RpcResponse = reqwest::blocking::get(&url)
+    Ok(rpc_get(&url).await?.results)
+}
+
+async fn rpc_get(url: &str) -> Result<RpcResponse, String> {
+    runtime::client()
+        .get(url)
+        .send()
+        .await
         .map_err(|e| format!("HTTP request failed: {e}"))?
         .json()
-        .map_err(|e| format!("Failed to parse AUR response: {e}"))?;
-
-    Ok(resp.results)
+        .await
+        .map_err(|e| format!("Failed to parse AUR response: {e}"))
 }
@@ -0,0 +1,282 @@
+//! Prebuilt AUR binary repositories (Chaotic-AUR and the like).
+//!
+//! Users of such a repo install a package built by someone else from a PKGBUILD they
+//! never see. makepkg records the SHA-256 of the PKGBUILD it built from in the
+//! package's `.BUILDINFO`; comparing that with the AUR's current PKGBUILD shows
+//! whether the binary was built from what the AUR serves. The downloaded package is
+//! also handed to artifact analysis, so what was actually built gets scanned too.
+
+use crate::shared::config::Config;
+use crate::shared::package_file::{self, PackageFile};
+use crate::shared::runtime;
+use crate::shared::scoring::{Signal, SignalCategory};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Repositories known without configuration; `[repos.<name>] url` adds or overrides.
+const BUILTIN_REPOS: &[(&str, &str)] = &[("chaotic-aur", "https://cdn-mirror.chaotic.cx/chaotic-aur/x86_64")];
+
+/// Package archives can be large; the per-request default would cut them off.
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// A package entry in a repo database (`<repo>.db`).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RepoEntry {
+    pub name: String,
+    pub base: String,
+    pub version: String,
+    pub filename: String,
+    /// Compressed size in bytes.
+    pub csize: u64,
+}
+
+/// What makepkg recorded about the build in `.BUILDINFO`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BuildInfo {
+    /// `[epoch:]pkgver-pkgrel`.
+    pub version: String,
+    pub pkgbuild_sha256: Option<String>,
+    pub packager: Option<String>,
+}
+
+/// A package fetched from a binary repo.
+pub struct RepoPackage {
+    pub repo: String,
+    pub entry: RepoEntry,
+    pub build_info: BuildInfo,
+    pub file: PackageFile,
+}
+
+/// Base URL of a repo by name, from config or the built-in list.
+pub fn repo_url<'a>(config: &'a Config, repo: &str) -> Option<&'a str> {
+    config
+        .repos
+        .get(repo)
+        .map(|r| r.url.as_str())
+        .or_else(|| BUILTIN_REPOS.iter().find(|(name, _)| *name == repo).map(|(_, url)| *url))
+        .map(|url| url.trim_end_matches('/'))
+}
+
+/// Names of all repos usable with `--repo`.
+pub fn repo_names(config: &Config) -> Vec<String> {
+    let mut names: Vec<String> = BUILTIN_REPOS.iter().map(|(n, _)| n.to_string()).collect();
+    names.extend(config.repos.keys().cloned());
+    names.sort();
+    names.dedup();
+    names
+}
+
+/// Download `package` from `repo` and read its build metadata and contents.
+pub fn fetch(repo: &str, package: &str, config: &Config) -> Result<RepoPackage, String> {
+    let url = repo_url(config, repo).ok_or_else(|| {
+        format!(
+            "Unknown repo: {repo} (known: {}; add one with [repos.{repo}] url = \"...\")",
+            repo_names(config).join(", ")
+        )
+    })?;
+
+    let workdir = std::env::temp_dir().join(format!("traur-repo-{}", std::process::id()));
+    std::fs::create_dir_all(&workdir).map_err(|e| format!("Failed to create {}: {e}", workdir.display()))?;
+    let result = fetch_into(&workdir, repo, url, package);
+    let _ = std::fs::remove_dir_all(&workdir);
+    result
+}
+
+fn fetch_into(workdir: &Path, repo: &str, url: &str, package: &str) -> Result<RepoPackage, String> {
+    let db_path = download(&format!("{url}/{repo}.db"), &workdir.join("repo.db"))?;
+    let db = package_file::extract_all(&db_path).ok_or_else(|| format!("Failed to read the {repo} database"))?;
+    let entry = parse_db(&db)
+        .into_iter()
+        .find(|e| e.name == package)
+        .ok_or_else(|| format!("{package} is not in {repo}"))?;
+
+    eprintln!("  Downloading {} ({:.1} MiB) from {repo}...", entry.filename, entry.csize as f64 / 1048576.0);
+    let pkg_path = download(&format!("{url}/{}", entry.filename), &workdir.join(&entry.filename))?;
+    let build_info = package_file::extract_file(&pkg_path.to_string_lossy(), ".BUILDINFO")
+        .map(|text| parse_buildinfo(&text))
+        .ok_or_else(|| format!("{} has no .BUILDINFO", entry.filename))?;
+    let file = package_file::read_package_file(&pkg_path)?;
+
+    Ok(RepoPackage { repo: repo.to_string(), entry, build_info, file })
+}
+
+fn download(url: &str, dest: &Path) -> Result<PathBuf, String> {
+    let bytes = runtime::block_on(async {
+        runtime::client()
+            .get(url)
+            .timeout(DOWNLOAD_TIMEOUT)
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| format!("HTTP request failed: {e}"))?
+            .bytes()
+            .await
+            .map_err(|e| format!("Failed to download {url}: {e}"))
+    })?;
+    std::fs::write(dest, &bytes).map_err(|e| format!("Failed to write {}: {e}", dest.display()))?;
+    Ok(dest.to_path_buf())
+}
+
+/// Parse the concatenated `desc` files of a repo database (`%KEY%` headers followed
+/// by value lines, blank-line separated).
+pub fn parse_db(text: &str) -> Vec<RepoEntry> {
+    let mut entries = Vec::new();
+    let mut current: Option<RepoEntry> = None;
+    let mut key = "";
+    for line in text.lines() {
+        if line.starts_with('%') && line.ends_with('%') && line.len() > 2 {
+            key = &line[1..line.len() - 1];
+            if key == "FILENAME" {
+                entries.extend(current.take());
+                current = Some(RepoEntry::default());
+            }
+            continue;
+        }
+        let (Some(entry), false) = (current.as_mut(), line.is_empty()) else {
+            continue;
+        };
+        match key {
+            "FILENAME" => entry.filename = line.to_string(),
+            "NAME" => entry.name = line.to_string(),
+            "BASE" => entry.base = line.to_string(),
+            "VERSION" => entry.version = line.to_string(),
+            "CSIZE" => entry.csize = line.parse().unwrap_or(0),
+            _ => {}
+        }
+    }
+    entries.extend(current);
+    entries
+}
+
+pub fn parse_buildinfo(text: &str) -> BuildInfo {
+    let mut info = BuildInfo::default();
+    for line in text.lines() {
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let value = value.trim().to_string();
+        match key.trim() {
+            "pkgver" => info.version = value,
+            "pkgbuild_sha256sum" => info.pkgbuild_sha256 = Some(value),
+            "packager" => info.packager = Some(value),
+            _ => {}
+        }
+    }
+    info
+}
+
+/// Compare the repo build with the AUR's current revision: `aur_version` from the RPC
+/// and the PKGBUILD as committed.
+pub fn check(pkg: &RepoPackage, aur_version: &str, aur_pkgbuild_sha256: &str) -> Vec<Signal> {
+    let built = &pkg.build_info;
+    if built.version != aur_version {
+        return vec![Signal {
+            id: "M-REPO-VERSION-DIFFERS".to_string(),
+            category: SignalCategory::Metadata,
+            points: 10,
+            description: format!(
+                "{} build is {} but the AUR is at {aur_version}; its PKGBUILD could not be verified",
+                pkg.repo, built.version
+            ),
+            is_override_gate: false,
+            matched_line: Some(pkg.entry.filename.clone()),
+            location: None,
+            emitted_by: String::new(),
+        }];
+    }
+    match built.pkgbuild_sha256.as_deref() {
+        Some(hash) if hash == aur_pkgbuild_sha256 => Vec::new(),
+        Some(hash) => vec![Signal {
+            id: "P-REPO-PKGBUILD-MISMATCH".to_string(),
+            category: SignalCategory::Pkgbuild,
+            points: 60,
+            description: format!(
+                "{} built {} from a PKGBUILD that differs from the AUR's (patched or substituted before building)",
+                pkg.repo, built.version
+            ),
+            is_override_gate: false,
+            matched_line: Some(match &built.packager {
+                Some(packager) => format!("pkgbuild_sha256sum {hash}, AUR {aur_pkgbuild_sha256} (packager {packager})"),
+                None => format!("pkgbuild_sha256sum {hash}, AUR {aur_pkgbuild_sha256}"),
+            }),
+            location: None,
+            emitted_by: String::new(),
+        }],
+        None => vec![Signal {
+            id: "M-REPO-NO-BUILDINFO-HASH".to_string(),
+            category: SignalCategory::Metadata,
+            points: 15,
+            description: format!("{} package does not record which PKGBUILD it was built from", pkg.repo),
+            is_override_gate: false,
+            matched_line: None,
+            location: None,
+            emitted_by: String::new(),
+        }],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared::models::PackageArtifact;
+
+    const DB: &str = "%FILENAME%\nfoo-bin-1.2-1-x86_64.pkg.tar.zst\n\n%NAME%\nfoo-bin\n\n%BASE%\nfoo-bin\n\n%VERSION%\n1.2-1\n\n%CSIZE%\n2097152\n\n%DEPENDS%\nglibc\ngtk3\n\n\
+%FILENAME%\nbar-git-r10.abc-1-x86_64.pkg.tar.zst\n\n%NAME%\nbar-git\n\n%VERSION%\nr10.abc-1\n\n";
+
+    fn repo_package(build_info: BuildInfo) -> RepoPackage {
+        RepoPackage {
+            repo: "chaotic-aur".to_string(),
+            entry: RepoEntry::default(),
+            build_info,
+            file: PackageFile { name: "foo-bin".to_string(), install_script: None, artifact: PackageArtifact::default() },
+        }
+    }
+
+    #[test]
+    fn parses_repo_db() {
+        let entries = parse_db(DB);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(
+            entries[0],
+            RepoEntry {
+                name: "foo-bin".to_string(),
+                base: "foo-bin".to_string(),
+                version: "1.2-1".to_string(),
+                filename: "foo-bin-1.2-1-x86_64.pkg.tar.zst".to_string(),
+                csize: 2097152,
+            }
+        );
+        assert_eq!(entries[1].name, "bar-git");
+    }
+
+    #[test]
+    fn parses_buildinfo() {
+        let info = parse_buildinfo("format = 2\npkgname = foo-bin\npkgver = 1:1.2-1\npkgbuild_sha256sum = abc123\npackager = Builder <b@example.org>\n");
+        assert_eq!(info.version, "1:1.2-1");
+        assert_eq!(info.pkgbuild_sha256.as_deref(), Some("abc123"));
+    }
+
+    #[test]
+    fn compares_build_with_aur_head() {
+        let built = |version: &str, hash: Option<&str>| {
+            repo_package(BuildInfo { version: version.to_string(), pkgbuild_sha256: hash.map(str::to_string), packager: None })
+        };
+        assert!(check(&built("1.2-1", Some("abc")), "1.2-1", "abc").is_empty());
+        assert_eq!(check(&built("1.2-1", Some("def")), "1.2-1", "abc")[0].id, "P-REPO-PKGBUILD-MISMATCH");
+        assert_eq!(check(&built("1.1-1", Some("def")), "1.2-1", "abc")[0].id, "M-REPO-VERSION-DIFFERS");
+        assert_eq!(check(&built("1.2-1", None), "1.2-1", "abc")[0].id, "M-REPO-NO-BUILDINFO-HASH");
+    }
+
+    #[test]
+    fn builtin_and_configured_repos() {
+        let mut config = Config::default();
+        assert_eq!(repo_url(&config, "chaotic-aur"), Some("https://cdn-mirror.chaotic.cx/chaotic-aur/x86_64"));
+        config.repos.insert(
+            "myrepo".to_string(),
+            crate::shared::config::RepoConfig { url: "https://repo.example.org/x86_64/".to_string() },
+        );
+        assert_eq!(repo_url(&config, "myrepo"), Some("https://repo.example.org/x86_64"));
+        assert_eq!(repo_names(&config), ["chaotic-aur", "myrepo"]);
+        assert!(repo_url(&config, "nope").is_none());
+    }
+}
@@ -1,73 +1,225 @@
-use crate::coordinator;
-use crate::shared::aur_rpc;
-use crate::shared::models::{AurPackage, PackageContext};
-use rayon::prelude::*;
-use std::collections::{HashMap, HashSet};
-use std::time::Duration;
+use crate::coordinator::{self, Deadline};
+use crate::shared::{aur_rpc, cache};
+use crate::shared::config::Config;
+use crate::shared::models::{AurPackage, PackageContext, UpstreamData};
+use crate::shared::runtime::{self, MAX_CONCURRENT_REQUESTS};
+use std::collections::HashMap;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 pub const RPC_BATCH_SIZE: usize = 150;
 pub const MAX_RETRIES: u32 = 3;
 pub const RETRY_BASE_DELAY: Duration = Duration::from_secs(2);
 
+/// AUR metadata for a batch of names, telling names the AUR does not have apart from
+/// names whose request failed.
+#[derive(Debug, Default)]
+pub struct MetadataBatch {
+    pub found: HashMap<String, AurPackage>,
+    /// Names in chunks whose RPC request failed, with the error. Whether they are on
+    /// the AUR is unknown.
+    pub failed: HashMap<String, String>,
+}
+
+impl MetadataBatch {
+    /// Whether the AUR answered for `name` and does not have it.
+    pub fn is_absent(&self, name: &str) -> bool {
+        !self.found.contains_key(name) && !self.failed.contains_key(name)
+    }
+}
+
 /// Fetch AUR metadata for a batch of package names via the RPC API.
+/// Chunks of `RPC_BATCH_SIZE` are requested concurrently. Failed chunks are only
+/// warned about; use `try_batch_fetch_metadata` where a missing name must mean
+/// the package is not on the AUR.
 pub fn batch_fetch_metadata(names: &[String]) -> HashMap<String, AurPackage> {
-    let mut map = HashMap::new();
+    let batch = try_batch_fetch_metadata(names);
+    let mut errors: Vec<&String> = batch.failed.values().collect();
+    errors.sort_unstable();
+    errors.dedup();
+    for e in errors {
+        eprintln!("  Warning: batch metadata fetch failed: {e}");
+    }
+    batch.found
+}
 
-    for chunk in names.chunks(RPC_BATCH_SIZE) {
-        let refs: Vec<&str> = chunk.iter().map(|s| s.as_str()).collect();
-        match aur_rpc::fetch_packages_info(&refs) {
+/// `batch_fetch_metadata`, keeping the names of failed chunks in `failed`.
+pub fn try_batch_fetch_metadata(names: &[String]) -> MetadataBatch {
+    let chunks: Vec<Vec<&str>> = names
+        .chunks(RPC_BATCH_SIZE)
+        .map(|chunk| chunk.iter().map(|s| s.as_str()).collect())
+        .collect();
+
+    // Chunks finish in any order, so each result carries its names
+    let fetches = runtime::map_bounded(chunks, MAX_CONCURRENT_REQUESTS, |chunk| async move {
+        let result = aur_rpc::fetch_packages_info(&chunk).await;
+        (chunk, result)
+    });
+
+    let mut batch = MetadataBatch::default();
+    for (chunk, result) in runtime::block_on(fetches) {
+        match result {
             Ok(packages) => {
                 for pkg in packages {
-                    map.insert(pkg.name.clone(), pkg);
+                    batch.found.insert(pkg.name.clone(), pkg);
                 }
             }
             Err(e) => {
-                eprintln!("  Warning: batch metadata fetch failed: {e}");
+                batch.failed.extend(chunk.iter().map(|name| (name.to_string(), e.clone())));
             }
         }
     }
 
-    map
+    batch
 }
 
-/// Pre-fetch all maintainer package lists in parallel.
+/// Pre-fetch all maintainer package lists concurrently. Snapshots cached on disk
+/// within `[cache] maintainer_ttl` are reused unless the scanned packages show the
+/// list has changed since (see `snapshot_is_current`).
 pub fn prefetch_maintainer_packages(
     metadata: &HashMap<String, AurPackage>,
+    config: &Config,
 ) -> HashMap<String, Vec<AurPackage>> {
-    let maintainers: Vec<&str> = metadata
-        .values()
-        .filter_map(|pkg| pkg.maintainer.as_deref())
-        .collect::<HashSet<&str>>()
-        .into_iter()
-        .collect();
+    let mut scanned: HashMap<&str, Vec<&AurPackage>> = HashMap::new();
+    for pkg in metadata.values() {
+        if let Some(m) = pkg.maintainer.as_deref() {
+            scanned.entry(m).or_default().push(pkg);
+        }
+    }
+
+    let ttl = config.cache.maintainer_ttl();
+    let now = SystemTime::now();
+    let mut result = HashMap::new();
+    let mut stale = HashMap::new();
+    let mut to_fetch = Vec::new();
+    for (&m, pkgs) in &scanned {
+        match load_maintainer_snapshot(m) {
+            Some((fetched, snapshot)) if snapshot_is_current(fetched, &snapshot, pkgs, ttl, now) => {
+                result.insert(m.to_string(), snapshot);
+            }
+            Some((_, snapshot)) => {
+                stale.insert(m, snapshot);
+                to_fetch.push(m);
+            }
+            None => to_fetch.push(m),
+        }
+    }
 
     eprintln!(
-        "  Fetching maintainer data for {} unique maintainers...",
-        maintainers.len()
+        "  Fetching maintainer data for {} unique maintainers ({} cached)...",
+        to_fetch.len(),
+        result.len()
     );
 
-    maintainers
-        .par_iter()
-        .filter_map(|m| {
-            aur_rpc::fetch_maintainer_packages(m)
-                .ok()
-                .map(|pkgs| (m.to_string(), pkgs))
-        })
-        .collect()
+    let fetches = runtime::map_bounded(to_fetch, MAX_CONCURRENT_REQUESTS, |m| async move {
+        (m, aur_rpc::fetch_maintainer_packages(m).await)
+    });
+
+    for (m, fetched) in runtime::block_on(fetches) {
+        match fetched {
+            Ok(pkgs) => {
+                save_maintainer_snapshot(m, &pkgs);
+                result.insert(m.to_string(), pkgs);
+            }
+            // A stale list beats none when the AUR is rate-limiting us
+            Err(_) => {
+                if let Some(snapshot) = stale.remove(m) {
+                    result.insert(m.to_string(), snapshot);
+                }
+            }
+        }
+    }
+
+    result
+}
+
+/// A cached maintainer snapshot can be reused if it is younger than `ttl` and every
+/// scanned package of that maintainer is in it, unmodified since the snapshot was taken.
+/// A new or updated package means the list (or its vote/popularity data) moved on.
+fn snapshot_is_current(
+    fetched: SystemTime,
+    snapshot: &[AurPackage],
+    scanned: &[&AurPackage],
+    ttl: Duration,
+    now: SystemTime,
+) -> bool {
+    if now.duration_since(fetched).map_or(true, |age| age >= ttl) {
+        return false;
+    }
+    let fetched_secs = fetched.duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+    scanned.iter().all(|pkg| {
+        pkg.last_modified <= fetched_secs && snapshot.iter().any(|s| s.name == pkg.name)
+    })
+}
+
+/// Maintainer names become file names; anything outside AUR's username charset is not cached.
+fn maintainer_snapshot_path(maintainer: &str) -> Option<std::path::PathBuf> {
+    let valid = !maintainer.is_empty()
+        && !maintainer.starts_with('.')
+        && maintainer.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    valid.then(|| cache::maintainer_cache_dir().join(format!("{maintainer}.json")))
+}
+
+/// The cached package list for a maintainer and when it was written.
+fn load_maintainer_snapshot(maintainer: &str) -> Option<(SystemTime, Vec<AurPackage>)> {
+    let path = maintainer_snapshot_path(maintainer)?;
+    let fetched = std::fs::metadata(&path).and_then(|m| m.modified()).ok()?;
+    let content = std::fs::read_to_string(&path).ok()?;
+    Some((fetched, serde_json::from_str(&content).ok()?))
+}
+
+fn save_maintainer_snapshot(maintainer: &str, packages: &[AurPackage]) {
+    if let Some(path) = maintainer_snapshot_path(maintainer)
+        && let Ok(json) = serde_json::to_string(packages)
+    {
+        let _ = std::fs::write(path, json);
+    }
+}
+
+/// Pre-fetch GitHub stars/releases and AUR comments for every package concurrently,
+/// so the rayon scan phase only clones and analyzes. Comments are skipped with
+/// `[scan] bulk_comments = false`.
+pub fn prefetch_upstream(
+    metadata: &HashMap<String, AurPackage>,
+    config: &Config,
+) -> HashMap<String, UpstreamData> {
+    let comments = config.scan.bulk_comments;
+    eprintln!(
+        "  Fetching upstream data ({}) for {} packages...",
+        if comments { "GitHub, AUR comments" } else { "GitHub" },
+        metadata.len()
+    );
+
+    let fetches = runtime::map_bounded(metadata, MAX_CONCURRENT_REQUESTS, |(name, meta)| async move {
+        (name.clone(), coordinator::fetch_upstream(name, meta, comments, config).await)
+    });
+
+    runtime::block_on(fetches).into_iter().collect()
 }
 
 /// Clone repo with retry + exponential backoff. Returns PackageContext or error.
+/// No retry is attempted once its backoff would run past `deadline`.
 pub fn clone_with_retry(
     name: &str,
     metadata: AurPackage,
     maintainer_packages: Vec<AurPackage>,
+    upstream: UpstreamData,
+    deadline: Deadline,
+    config: &Config,
 ) -> Result<PackageContext, String> {
     for attempt in 0..MAX_RETRIES {
-        match coordinator::build_context_prefetched(name, metadata.clone(), maintainer_packages.clone())
-        {
+        match coordinator::build_context_prefetched(
+            name,
+            metadata.clone(),
+            maintainer_packages.clone(),
+            upstream.clone(),
+            config,
+        ) {
             Ok(ctx) => return Ok(ctx),
             Err(e) if attempt + 1 < MAX_RETRIES => {
                 let delay = RETRY_BASE_DELAY * 2u32.pow(attempt);
+                if deadline.remaining().is_some_and(|left| left <= delay) {
+                    return Err(format!("{e} (scan timeout reached, not retrying)"));
+                }
                 std::thread::sleep(delay);
                 continue;
             }
@@ -76,3 +228,68 @@ pub fn clone_with_retry(
     }
     unreachable!()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pkg(name: &str, last_modified: u64) -> AurPackage {
+        AurPackage {
+            name: name.into(),
+            package_base: None,
+            url: None,
+            num_votes: 0,
+            popularity: 0.0,
+            out_of_date: None,
+            maintainer: Some("alice".into()),
+            submitter: None,
+            version: String::new(),
+            first_submitted: 0,
+            last_modified,
+            license: None,
+            description: None,
+            depends: Vec::new(),
+            opt_depends: Vec::new(),
+            make_depends: Vec::new(),
+            check_depends: Vec::new(),
+        }
+    }
+
+    const TTL: Duration = Duration::from_secs(3600);
+
+    fn at(secs: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(secs)
+    }
+
+    #[test]
+    fn fresh_snapshot_covering_scanned_packages_is_reused() {
+        let snapshot = vec![pkg("foo", 500), pkg("bar", 900)];
+        let foo = pkg("foo", 500);
+        assert!(snapshot_is_current(at(1000), &snapshot, &[&foo], TTL, at(1600)));
+    }
+
+    #[test]
+    fn expired_snapshot_is_refetched() {
+        let snapshot = vec![pkg("foo", 500)];
+        let foo = pkg("foo", 500);
+        assert!(!snapshot_is_current(at(1000), &snapshot, &[&foo], TTL, at(1000 + 3600)));
+        assert!(!snapshot_is_current(at(1000), &snapshot, &[&foo], Duration::ZERO, at(1000)));
+    }
+
+    #[test]
+    fn new_or_updated_package_forces_refresh() {
+        let snapshot = vec![pkg("foo", 500)];
+        let updated = pkg("foo", 1200);
+        let added = pkg("baz", 800);
+        assert!(!snapshot_is_current(at(1000), &snapshot, &[&updated], TTL, at(1300)));
+        assert!(!snapshot_is_current(at(1000), &snapshot, &[&added], TTL, at(1300)));
+    }
+
+    #[test]
+    fn snapshot_path_rejects_unsafe_names() {
+        assert!(maintainer_snapshot_path("../etc").is_none());
+        assert!(maintainer_snapshot_path("a/b").is_none());
+        assert!(maintainer_snapshot_path("").is_none());
+        assert!(maintainer_snapshot_path("alice_01").is_some());
+    }
+}
@@ -1,4 +1,7 @@
-use std::path::PathBuf;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
 
 /// Returns the cache directory, creating it if needed.
 pub fn cache_dir() -> PathBuf {
@@ -14,6 +17,124 @@ pub fn git_cache_dir() -> PathBuf {
     dir
 }
 
+/// Returns the maintainer package-list snapshot subdirectory.
+pub fn maintainer_cache_dir() -> PathBuf {
+    let dir = cache_dir().join("maintainers");
+    std::fs::create_dir_all(&dir).ok();
+    dir
+}
+
+/// Returns the AUR comments snapshot subdirectory.
+pub fn comments_cache_dir() -> PathBuf {
+    let dir = cache_dir().join("comments");
+    std::fs::create_dir_all(&dir).ok();
+    dir
+}
+
+/// Returns the directory stale clones are moved to when the AUR history of a package
+/// was rewritten. Kept for inspection until `gc_git_cache` evicts them with the clones.
+pub fn rewritten_cache_dir() -> PathBuf {
+    let dir = cache_dir().join("rewritten");
+    std::fs::create_dir_all(&dir).ok();
+    dir
+}
+
+/// Replace the JSON snapshot `file` in the cache directory with `value`.
+pub fn save_snapshot<T: Serialize>(file: &str, value: &T) -> Result<(), String> {
+    write_json(&cache_dir().join(file), value)
+}
+
+/// The JSON snapshot `file` in the cache directory; None when it is missing or
+/// doesn't parse (an older layout is simply measured again).
+pub fn load_snapshot<T: DeserializeOwned>(file: &str) -> Option<T> {
+    read_json(&cache_dir().join(file))
+}
+
+fn write_json<T: Serialize>(path: &Path, value: &T) -> Result<(), String> {
+    let json = serde_json::to_string(value).map_err(|e| e.to_string())?;
+    std::fs::write(path, json).map_err(|e| format!("Failed to write {}: {e}", path.display()))
+}
+
+fn read_json<T: DeserializeOwned>(path: &Path) -> Option<T> {
+    serde_json::from_str(&std::fs::read_to_string(path).ok()?).ok()
+}
+
+/// When traur last fetched a package's AUR repo into the git cache (clone or pull).
+/// `None` if the package has never been scanned on this machine.
+pub fn last_inspected(package_base: &str) -> Option<SystemTime> {
+    last_fetched(&git_cache_dir().join(package_base))
+}
+
+fn last_fetched(repo: &Path) -> Option<SystemTime> {
+    let git_dir = repo.join(".git");
+    // `git pull` rewrites FETCH_HEAD even when nothing changed; a fresh clone only has HEAD.
+    ["FETCH_HEAD", "HEAD"]
+        .iter()
+        .find_map(|f| std::fs::metadata(git_dir.join(f)).and_then(|m| m.modified()).ok())
+}
+
+/// Outcome of a git cache clean-up.
+#[derive(Debug, Default, PartialEq)]
+pub struct GcStats {
+    pub removed: usize,
+    pub freed_bytes: u64,
+    pub kept: usize,
+    pub kept_bytes: u64,
+}
+
+/// Evict clones from the git cache and the stale clones kept after history rewrites:
+/// those not fetched within `max_age`, then the least recently fetched until both
+/// together are at most `max_bytes`. Clones are fetched again on their next scan.
+pub fn gc_git_cache(max_bytes: u64, max_age: Option<Duration>) -> GcStats {
+    gc_dirs(&[&git_cache_dir(), &rewritten_cache_dir()], max_bytes, max_age, SystemTime::now())
+}
+
+fn gc_dirs(dirs: &[&Path], max_bytes: u64, max_age: Option<Duration>, now: SystemTime) -> GcStats {
+    let mut repos: Vec<(PathBuf, SystemTime, u64)> = dirs
+        .iter()
+        .flat_map(|dir| std::fs::read_dir(dir).into_iter().flatten().flatten())
+        .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+        .map(|e| {
+            let path = e.path();
+            let fetched = last_fetched(&path)
+                .or_else(|| e.metadata().and_then(|m| m.modified()).ok())
+                .unwrap_or(SystemTime::UNIX_EPOCH);
+            let size = dir_size(&path);
+            (path, fetched, size)
+        })
+        .collect();
+    repos.sort_by_key(|(_, fetched, _)| *fetched);
+
+    let mut total: u64 = repos.iter().map(|(_, _, size)| size).sum();
+    let mut stats = GcStats::default();
+    for (path, fetched, size) in repos {
+        let expired = max_age.is_some_and(|age| now.duration_since(fetched).is_ok_and(|d| d > age));
+        if (expired || total > max_bytes) && std::fs::remove_dir_all(&path).is_ok() {
+            total -= size;
+            stats.removed += 1;
+            stats.freed_bytes += size;
+        } else {
+            stats.kept += 1;
+            stats.kept_bytes += size;
+        }
+    }
+    stats
+}
+
+/// Total size of the files under `path`. Symlinks are not followed.
+fn dir_size(path: &Path) -> u64 {
+    std::fs::read_dir(path)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|e| match e.file_type() {
+            Ok(t) if t.is_dir() => dir_size(&e.path()),
+            Ok(t) if t.is_file() => e.metadata().map(|m| m.len()).unwrap_or(0),
+            _ => 0,
+        })
+        .sum()
+}
+
 fn dirs_or_default() -> PathBuf {
     if let Ok(xdg) = std::env::var("XDG_CACHE_HOME") {
         PathBuf::from(xdg).join("traur")
@@ -23,3 +144,80 @@ fn dirs_or_default() -> PathBuf {
         PathBuf::from("/tmp/traur-cache")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn fake_clone(dir: &Path, name: &str, bytes: usize, fetched: SystemTime) {
+        let git = dir.join(name).join(".git");
+        std::fs::create_dir_all(&git).unwrap();
+        std::fs::write(dir.join(name).join("PKGBUILD"), vec![b'x'; bytes]).unwrap();
+        let head = std::fs::File::create(git.join("HEAD")).unwrap();
+        head.set_modified(fetched).unwrap();
+    }
+
+    #[test]
+    fn gc_evicts_expired_then_least_recently_fetched() {
+        let dir = std::env::temp_dir().join(format!("traur-gc-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let now = SystemTime::now();
+        let day = Duration::from_secs(86400);
+        fake_clone(&dir, "ancient", 100, now - day * 90);
+        fake_clone(&dir, "old", 1000, now - day * 5);
+        fake_clone(&dir, "recent", 1000, now - day);
+        fake_clone(&dir, "fresh", 1000, now);
+
+        let stats = gc_dirs(&[&dir], 2500, Some(day * 30), now);
+        let mut left: Vec<String> = std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        left.sort();
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert_eq!(left, ["fresh", "recent"]);
+        assert_eq!((stats.removed, stats.freed_bytes), (2, 1100));
+        assert_eq!((stats.kept, stats.kept_bytes), (2, 2000));
+    }
+
+    #[test]
+    fn gc_evicts_rewritten_clones_with_the_cache() {
+        let root = std::env::temp_dir().join(format!("traur-gc-rewritten-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        let (git, rewritten) = (root.join("git"), root.join("rewritten"));
+        let now = SystemTime::now();
+        let day = Duration::from_secs(86400);
+        fake_clone(&git, "current", 1000, now);
+        fake_clone(&rewritten, "expired-1700000000", 100, now - day * 90);
+        fake_clone(&rewritten, "older-1710000000", 1000, now - day * 5);
+        fake_clone(&rewritten, "recent-1720000000", 1000, now - day);
+
+        let stats = gc_dirs(&[&git, &rewritten], 2000, Some(day * 30), now);
+        let left = |dir: &Path| std::fs::read_dir(dir).unwrap().count();
+        let (left_git, left_rewritten) = (left(&git), left(&rewritten));
+        let _ = std::fs::remove_dir_all(&root);
+
+        assert_eq!((left_git, left_rewritten), (1, 1));
+        assert_eq!((stats.removed, stats.kept), (2, 2));
+    }
+
+    #[test]
+    fn snapshots_round_trip_and_ignore_other_layouts() {
+        let dir = std::env::temp_dir().join(format!("traur-snapshot-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("histogram.json");
+
+        write_json(&path, &vec![1u64, 2, 3]).unwrap();
+        let read: Option<Vec<u64>> = read_json(&path);
+        let other_layout: Option<HashMap<String, u64>> = read_json(&path);
+        let missing: Option<Vec<u64>> = read_json(&dir.join("missing.json"));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert_eq!(read, Some(vec![1, 2, 3]));
+        assert_eq!(other_layout, None);
+        assert_eq!(missing, None);
+    }
+}
@@ -0,0 +1,157 @@
+//! Checkpoints for long bulk scans (`bench`, `scan` of all installed packages).
+//!
+//! Each completed result is appended to an NDJSON file in the cache directory as soon
+//! as it is known, so an interrupted run (Ctrl-C, network failure) can be picked up
+//! with `--resume`. Failed packages are not recorded and are retried on resume. The
+//! file is removed when the run finishes. Its first line holds the config fingerprint
+//! (`config::fingerprint`); results recorded under another config are not resumed.
+
+use crate::shared::cache;
+use crate::shared::scoring::ScanResult;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// An open checkpoint file for one kind of bulk scan.
+pub struct Checkpoint {
+    path: PathBuf,
+    file: Mutex<File>,
+}
+
+impl Checkpoint {
+    /// Open the checkpoint for `job` (e.g. "bench"). With `resume`, returns the results
+    /// recorded by the previous, unfinished run if it ran with the same config
+    /// `fingerprint`; otherwise any old checkpoint is discarded.
+    pub fn open(job: &str, resume: bool, fingerprint: &str) -> Result<(Self, Vec<ScanResult>), String> {
+        let dir = cache::cache_dir().join("checkpoints");
+        std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+        Self::open_at(dir.join(format!("{job}.ndjson")), resume, fingerprint)
+    }
+
+    fn open_at(path: PathBuf, resume: bool, fingerprint: &str) -> Result<(Self, Vec<ScanResult>), String> {
+        let mut done = if resume { load(&path) } else { Vec::new() };
+        if !done.is_empty() && recorded_fingerprint(&path).as_deref() != Some(fingerprint) {
+            eprintln!("  Config changed since the interrupted run; not resuming, scanning everything again.");
+            done.clear();
+        }
+        // Rewrite the kept results so a line cut short by the interruption is dropped
+        // rather than glued to the next record.
+        let mut file = File::create(&path)
+            .map_err(|e| format!("Failed to open checkpoint {}: {e}", path.display()))?;
+        writeln!(file, "{}", serde_json::json!({ "config_fingerprint": fingerprint }))
+            .map_err(|e| format!("Failed to write checkpoint {}: {e}", path.display()))?;
+        let checkpoint = Checkpoint { path, file: Mutex::new(file) };
+        for result in &done {
+            checkpoint.record(result);
+        }
+        Ok((checkpoint, done))
+    }
+
+    /// Record a completed result. Write errors are only warned about: a lost checkpoint
+    /// line just means that package is scanned again on resume.
+    pub fn record(&self, result: &ScanResult) {
+        let Ok(line) = serde_json::to_string(result) else {
+            return;
+        };
+        let mut file = self.file.lock().unwrap();
+        if let Err(e) = writeln!(file, "{line}") {
+            eprintln!("  Warning: failed to write checkpoint {}: {e}", self.path.display());
+        }
+    }
+
+    /// The run completed: remove the checkpoint so the next run starts fresh.
+    pub fn finish(self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// The config fingerprint in the first line of a checkpoint file.
+fn recorded_fingerprint(path: &Path) -> Option<String> {
+    let mut line = String::new();
+    BufReader::new(File::open(path).ok()?).read_line(&mut line).ok()?;
+    let header: serde_json::Value = serde_json::from_str(&line).ok()?;
+    Some(header.get("config_fingerprint")?.as_str()?.to_string())
+}
+
+/// Results recorded in a checkpoint file. The header and a line cut short by an
+/// interrupted write are skipped.
+fn load(path: &Path) -> Vec<ScanResult> {
+    let Ok(file) = File::open(path) else {
+        return Vec::new();
+    };
+    BufReader::new(file)
+        .lines()
+        .map_while(Result::ok)
+        .filter_map(|line| serde_json::from_str(&line).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared::scoring::{compute_score, Signal, SignalCategory};
+
+    fn result(name: &str) -> ScanResult {
+        compute_score(name, &[Signal {
+            id: "P-CURL-PIPE".into(),
+            category: SignalCategory::Pkgbuild,
+            points: 90,
+            description: "curl piped to shell".into(),
+            is_override_gate: true,
+            matched_line: Some("curl x | sh".into()),
+            location: None,
+            emitted_by: String::new(),
+        }])
+    }
+
+    #[test]
+    fn resume_returns_recorded_results() {
+        let dir = std::env::temp_dir().join(format!("traur-checkpoint-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("bench.ndjson");
+
+        let (checkpoint, done) = Checkpoint::open_at(path.clone(), true, "cfg").unwrap();
+        assert!(done.is_empty());
+        checkpoint.record(&result("foo"));
+        checkpoint.record(&result("bar"));
+        drop(checkpoint);
+        // An interrupted write leaves a partial last line.
+        std::fs::OpenOptions::new().append(true).open(&path).unwrap().write_all(b"{\"package\":\"ba").unwrap();
+
+        let (checkpoint, done) = Checkpoint::open_at(path.clone(), true, "cfg").unwrap();
+        let names: Vec<&str> = done.iter().map(|r| r.package.as_str()).collect();
+        assert_eq!(names, ["foo", "bar"]);
+        assert_eq!(done[0].override_gate_fired.as_deref(), Some("P-CURL-PIPE"));
+        assert_eq!(done[0].signals[0].matched_line.as_deref(), Some("curl x | sh"));
+        checkpoint.record(&result("baz"));
+        drop(checkpoint);
+        assert_eq!(load(&path).len(), 3);
+
+        // Without --resume the old checkpoint is discarded.
+        let (checkpoint, done) = Checkpoint::open_at(path.clone(), false, "cfg").unwrap();
+        assert!(done.is_empty());
+        assert!(load(&path).is_empty());
+        checkpoint.finish();
+        assert!(!path.exists());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn config_change_discards_checkpoint() {
+        let dir = std::env::temp_dir().join(format!("traur-checkpoint-config-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("scan-installed.ndjson");
+
+        let (checkpoint, _) = Checkpoint::open_at(path.clone(), true, "old").unwrap();
+        checkpoint.record(&result("foo"));
+        drop(checkpoint);
+        assert_eq!(recorded_fingerprint(&path).as_deref(), Some("old"));
+
+        let (checkpoint, done) = Checkpoint::open_at(path.clone(), true, "new").unwrap();
+        assert!(done.is_empty());
+        assert_eq!(recorded_fingerprint(&path).as_deref(), Some("new"));
+        checkpoint.finish();
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -1,13 +1,51 @@
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
 
 #[derive(Debug, Deserialize, Serialize, Default)]
 pub struct Config {
     #[serde(default)]
     pub thresholds: ThresholdConfig,
     #[serde(default)]
+    pub scoring: ScoringConfig,
+    #[serde(default)]
     pub whitelist: WhitelistConfig,
     #[serde(default)]
     pub ignored: IgnoredConfig,
+    #[serde(default)]
+    pub notify: NotifyConfig,
+    #[serde(default)]
+    pub hook: HookConfig,
+    #[serde(default)]
+    pub maintainer: MaintainerConfig,
+    #[serde(default)]
+    pub http: HttpConfig,
+    #[serde(default)]
+    pub features: FeaturesConfig,
+    #[serde(default)]
+    pub scan: ScanConfig,
+    #[serde(default)]
+    pub cache: CacheConfig,
+    #[serde(default)]
+    pub known_good: KnownGoodConfig,
+    #[serde(default)]
+    pub geoip: GeoIpConfig,
+    #[serde(default)]
+    pub threat_intel: ThreatIntelConfig,
+    #[serde(default)]
+    pub names: NamesConfig,
+    #[serde(default)]
+    pub report: ReportConfig,
+    #[serde(default)]
+    pub policy: PolicyConfig,
+    /// Prebuilt AUR binary repos for `traur scan --repo <name>`, on top of the
+    /// built-in ones (`shared/binary_repo.rs`).
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub repos: BTreeMap<String, RepoConfig>,
+    /// Extra source domains (and their subdomains) that are not reported as
+    /// `-bin` domain mismatches or plain-HTTP sources.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub trusted_domains: Vec<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -16,6 +54,10 @@ pub struct ThresholdConfig {
     pub block_at: String,
     #[serde(default = "default_warn_at")]
     pub warn_at: String,
+    /// Raise scans that could not get the PKGBUILD, git log or AUR metadata to at
+    /// least SKETCHY instead of reporting what little was analyzed as clean.
+    #[serde(default = "default_incomplete_as_sketchy")]
+    pub incomplete_as_sketchy: bool,
 }
 
 impl Default for ThresholdConfig {
@@ -23,6 +65,7 @@ impl Default for ThresholdConfig {
         Self {
             block_at: default_block_at(),
             warn_at: default_warn_at(),
+            incomplete_as_sketchy: default_incomplete_as_sketchy(),
         }
     }
 }
@@ -35,10 +78,46 @@ fn default_warn_at() -> String {
     "medium".to_string()
 }
 
+fn default_incomplete_as_sketchy() -> bool {
+    true
+}
+
+/// Score adjustments.
+///
+/// ```toml
+/// [scoring]
+/// newness_signals = ["M-VOTES-ZERO", "M-POP-ZERO", "T-NEW-PACKAGE"]
+/// demoted_gates = ["G-TAR-CHECKPOINT"]
+/// ```
+#[derive(Debug, Deserialize, Serialize, Default)]
+pub struct ScoringConfig {
+    /// Signals that only say a package is new. A package whose signals are all in this
+    /// set, none about the PKGBUILD, is capped at OK. Unset: `scoring::DEFAULT_NEWNESS_SIGNALS`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub newness_signals: Option<Vec<String>>,
+    /// Override gates scored as ordinary signals (their points, no MALICIOUS verdict).
+    /// A demoted gate that fires is listed in the result's `demoted_gates`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub demoted_gates: Vec<String>,
+}
+
+impl ScoringConfig {
+    pub fn newness_signals(&self) -> Vec<&str> {
+        match &self.newness_signals {
+            Some(ids) => ids.iter().map(String::as_str).collect(),
+            None => crate::shared::scoring::DEFAULT_NEWNESS_SIGNALS.to_vec(),
+        }
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize, Default)]
 pub struct WhitelistConfig {
     #[serde(default)]
     pub packages: Vec<String>,
+    /// PKGBUILD SHA-256 (as committed) that a whitelisted package is pinned to. A
+    /// pinned entry only applies while the package's PKGBUILD still hashes to it.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub pins: BTreeMap<String, String>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Default)]
@@ -49,47 +128,755 @@ pub struct IgnoredConfig {
     pub categories: Vec<String>,
 }
 
-/// Load config from ~/.config/traur/config.toml, falling back to defaults.
+/// Per-feature switches and timeouts, keyed by feature name:
+///
+/// ```toml
+/// [features]
+/// github_stars = false
+///
+/// [features.timeouts]
+/// aur_comments_analysis = 5
+/// ```
+#[derive(Debug, Deserialize, Serialize, Default)]
+pub struct FeaturesConfig {
+    /// `name = false` disables a feature (and its network fetch). Unlisted features run.
+    #[serde(flatten)]
+    pub enabled: HashMap<String, bool>,
+    /// Seconds a feature may take before it is skipped for that package.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub timeouts: HashMap<String, u64>,
+}
+
+impl FeaturesConfig {
+    pub fn is_enabled(&self, name: &str) -> bool {
+        self.enabled.get(name).copied().unwrap_or(true)
+    }
+
+    pub fn timeout(&self, name: &str) -> Option<Duration> {
+        self.timeouts.get(name).map(|&secs| Duration::from_secs(secs))
+    }
+}
+
+/// Per-package scan limits.
+///
+/// ```toml
+/// [scan]
+/// jobs = 8
+/// timeout = 120
+/// exclude = ["*.png", "vendor/"]
+/// max_file_kb = 256
+/// deep = true
+/// bulk_comments = false
+/// ```
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ScanConfig {
+    /// Concurrent scan threads for bulk scans when `--jobs` is not given.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jobs: Option<usize>,
+    /// Seconds one package may take end to end (fetch, clone, analysis). Features
+    /// still pending at the deadline are skipped and listed in the result.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<u64>,
+    /// .gitignore-style patterns for package repo files that are not analyzed
+    /// (vendored blobs, images), on top of `BUILTIN_EXCLUDE`. PKGBUILD and the
+    /// install script are always read.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exclude: Vec<String>,
+    /// KiB of a PKGBUILD or install script that is analyzed. Larger files are cut
+    /// down to their head and function bodies (`shared/scan_limits.rs`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_file_kb: Option<u64>,
+    /// Also download the upstream GitHub tarball `source=()` points at (size-capped)
+    /// and analyze its top-level build scripts (`shared/upstream_source.rs`).
+    #[serde(default)]
+    pub deep: bool,
+    /// Scrape AUR comments in bulk scans (`--all`, audit, top, the hook). `false`
+    /// skips the one web page request per package there; single-package scans still
+    /// fetch them.
+    #[serde(default = "default_bulk_comments")]
+    pub bulk_comments: bool,
+}
+
+impl Default for ScanConfig {
+    fn default() -> Self {
+        Self {
+            jobs: None,
+            timeout: None,
+            exclude: Vec::new(),
+            max_file_kb: None,
+            deep: false,
+            bulk_comments: default_bulk_comments(),
+        }
+    }
+}
+
+fn default_bulk_comments() -> bool {
+    true
+}
+
+/// Default `[scan] jobs`.
+const DEFAULT_JOBS: usize = 4;
+
+/// Repo files never analyzed, whatever `[scan] exclude` says: images, compressed
+/// patches and the `keys/` directory of PGP keys are high-entropy by nature and hold
+/// nothing a shell analysis can read.
+pub const BUILTIN_EXCLUDE: &[&str] = &[
+    "*.png", "*.jpg", "*.jpeg", "*.gif", "*.bmp", "*.webp", "*.ico", "*.icns", "*.svgz",
+    "*.patch.gz", "*.patch.xz", "*.patch.bz2", "*.patch.zst", "*.diff.gz", "*.diff.xz",
+    "/keys/",
+];
+
+impl ScanConfig {
+    pub fn jobs(&self) -> usize {
+        self.jobs.filter(|&n| n > 0).unwrap_or(DEFAULT_JOBS)
+    }
+
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout.map(Duration::from_secs)
+    }
+
+    pub fn max_file_bytes(&self) -> usize {
+        self.max_file_kb
+            .filter(|&kb| kb > 0)
+            .map_or(crate::shared::scan_limits::DEFAULT_MAX_FILE_BYTES, |kb| kb as usize * 1024)
+    }
+
+    /// Whether a repo-relative path (`/`-separated, directories with a trailing `/`)
+    /// matches any `exclude` pattern.
+    ///
+    /// Follows .gitignore rules: a pattern without a `/` matches a file or directory
+    /// name at any depth, a pattern containing one is anchored at the repo root, a
+    /// trailing `/` matches directories only, and `*`, `?`, `**` are wildcards.
+    /// Everything under an excluded directory is excluded. `BUILTIN_EXCLUDE` always
+    /// applies.
+    pub fn is_excluded(&self, path: &str) -> bool {
+        BUILTIN_EXCLUDE
+            .iter()
+            .copied()
+            .chain(self.exclude.iter().map(String::as_str))
+            .any(|pattern| exclude_matches(pattern, path))
+    }
+}
+
+fn exclude_matches(pattern: &str, path: &str) -> bool {
+    let pattern = pattern.trim();
+    let (pattern, dir_only) = match pattern.strip_suffix('/') {
+        Some(p) => (p, true),
+        None => (pattern, false),
+    };
+    let anchored = pattern.contains('/');
+    let pattern = pattern.trim_start_matches('/');
+    if pattern.is_empty() {
+        return false;
+    }
+
+    let components: Vec<&str> = path.split('/').filter(|c| !c.is_empty()).collect();
+    (1..=components.len()).any(|depth| {
+        let is_dir = depth < components.len() || path.ends_with('/');
+        if dir_only && !is_dir {
+            return false;
+        }
+        if anchored {
+            glob_match(pattern.as_bytes(), components[..depth].join("/").as_bytes())
+        } else {
+            glob_match(pattern.as_bytes(), components[depth - 1].as_bytes())
+        }
+    })
+}
+
+/// Glob match where `*` and `?` stay within one path component and `**` spans several.
+fn glob_match(pattern: &[u8], text: &[u8]) -> bool {
+    match pattern {
+        [] => text.is_empty(),
+        [b'*', b'*', rest @ ..] => {
+            let rest = rest.strip_prefix(b"/").unwrap_or(rest);
+            (0..=text.len()).any(|i| {
+                (i == 0 || text[i - 1] == b'/') && glob_match(rest, &text[i..])
+            })
+        }
+        [b'*', rest @ ..] => {
+            for i in 0..=text.len() {
+                if glob_match(rest, &text[i..]) {
+                    return true;
+                }
+                if i < text.len() && text[i] == b'/' {
+                    break;
+                }
+            }
+            false
+        }
+        [b'?', rest @ ..] => {
+            matches!(text, [c, tail @ ..] if *c != b'/' && glob_match(rest, tail))
+        }
+        [p, rest @ ..] => matches!(text, [c, tail @ ..] if c == p && glob_match(rest, tail)),
+    }
+}
+
+/// Lifetimes of on-disk caches.
+///
+/// ```toml
+/// [cache]
+/// maintainer_ttl = 21600
+/// comments_ttl = 3600
+/// max_size_mb = 1024
+/// ```
+#[derive(Debug, Deserialize, Serialize, Default)]
+pub struct CacheConfig {
+    /// Seconds a maintainer's package list snapshot is reused by bulk scans before
+    /// it is refetched. `0` always refetches.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub maintainer_ttl: Option<u64>,
+    /// Seconds a package's AUR comments snapshot is reused before the page is
+    /// scraped again. `0` always refetches.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comments_ttl: Option<u64>,
+    /// Size limit for the git clone cache in MiB. `traur cache gc` (and every `bench`
+    /// run) evicts the least recently fetched clones until the cache fits.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_size_mb: Option<u64>,
+}
+
+/// Default `[cache] maintainer_ttl`: six hours.
+const DEFAULT_MAINTAINER_TTL: Duration = Duration::from_secs(6 * 3600);
+
+/// Default `[cache] comments_ttl`: one hour.
+const DEFAULT_COMMENTS_TTL: Duration = Duration::from_secs(3600);
+
+/// Default `[cache] max_size_mb`.
+const DEFAULT_MAX_SIZE_MB: u64 = 1024;
+
+impl CacheConfig {
+    pub fn maintainer_ttl(&self) -> Duration {
+        self.maintainer_ttl.map(Duration::from_secs).unwrap_or(DEFAULT_MAINTAINER_TTL)
+    }
+
+    pub fn comments_ttl(&self) -> Duration {
+        self.comments_ttl.map(Duration::from_secs).unwrap_or(DEFAULT_COMMENTS_TTL)
+    }
+
+    pub fn max_size_bytes(&self) -> u64 {
+        self.max_size_mb.unwrap_or(DEFAULT_MAX_SIZE_MB) * 1024 * 1024
+    }
+}
+
+/// Known-good PKGBUILD hash database (`shared/known_good.rs`).
+///
+/// ```toml
+/// [known_good]
+/// url = "https://example.org/traur/known-good.json"
+/// max_age_hours = 24
+/// ```
+#[derive(Debug, Deserialize, Serialize, Default)]
+pub struct KnownGoodConfig {
+    /// Where to fetch the database from. Unset disables the check unless a database
+    /// was installed with `traur known-good update --from <file>`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Hours the cached database is used before it is fetched again from `url`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_age_hours: Option<u64>,
+}
+
+/// Default `[known_good] max_age_hours`.
+const DEFAULT_KNOWN_GOOD_MAX_AGE: Duration = Duration::from_secs(24 * 3600);
+
+impl KnownGoodConfig {
+    pub fn max_age(&self) -> Duration {
+        self.max_age_hours.map(|h| Duration::from_secs(h * 3600)).unwrap_or(DEFAULT_KNOWN_GOOD_MAX_AGE)
+    }
+}
+
+/// Extra impersonation/typosquat targets for name_analysis, on top of `data/names.toml`.
+///
+/// ```toml
+/// [names]
+/// protected = ["acme-vpn", "acme-agent"]
+/// ```
+#[derive(Debug, Deserialize, Serialize, Default)]
+pub struct NamesConfig {
+    /// Product names to protect (e.g. org-internal tools): impersonations with a
+    /// suspicious suffix and near-miss spellings of them are flagged.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub protected: Vec<String>,
+}
+
+/// A prebuilt AUR binary repo.
+///
+/// ```toml
+/// [repos.chaotic-aur]
+/// url = "https://cdn-mirror.chaotic.cx/chaotic-aur/x86_64"
+/// ```
+#[derive(Debug, Deserialize, Serialize)]
+pub struct RepoConfig {
+    /// Directory holding `<name>.db` and the package files.
+    pub url: String,
+}
+
+/// Where `traur report-fp` sends false-positive reports.
+///
+/// ```toml
+/// [report]
+/// endpoint = "https://fp.example.org/traur"
+/// ```
+#[derive(Debug, Deserialize, Serialize, Default)]
+pub struct ReportConfig {
+    /// Receives each report as a JSON POST. Without it, `report-fp` opens a
+    /// prefilled GitHub issue instead.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub endpoint: Option<String>,
+}
+
+/// Shared policy bundles (`traur policy import`).
+///
+/// ```toml
+/// [policy]
+/// trusted_keys = ["3b6a27bc..."]
+/// ```
+#[derive(Debug, Deserialize, Serialize, Default)]
+pub struct PolicyConfig {
+    /// Version of the last imported bundle; older bundles are refused.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<u64>,
+    /// Ed25519 public keys (hex) that may sign bundles. When set, unsigned bundles
+    /// and bundles signed by other keys are refused.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub trusted_keys: Vec<String>,
+}
+
+/// Local MaxMind databases for ASN/country details on source-URL signals (`shared/geoip.rs`).
+///
+/// ```toml
+/// [geoip]
+/// asn_db = "/usr/share/GeoIP/GeoLite2-ASN.mmdb"
+/// country_db = "/usr/share/GeoIP/GeoLite2-Country.mmdb"
+/// ```
+#[derive(Debug, Deserialize, Serialize, Default)]
+pub struct GeoIpConfig {
+    /// GeoLite2-ASN (or GeoIP2-ISP) database.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub asn_db: Option<String>,
+    /// GeoLite2-Country or -City database.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub country_db: Option<String>,
+}
+
+/// Locally cached threat-intel feeds that source URLs are checked against
+/// (`shared/threat_intel.rs`). Opt-in: nothing is checked without a path.
+///
+/// ```toml
+/// [threat_intel]
+/// urlhaus = "/var/cache/traur/urlhaus.txt"
+/// spamhaus_dbl = "/var/cache/traur/dbl.txt"
+/// ```
+#[derive(Debug, Deserialize, Serialize, Default)]
+pub struct ThreatIntelConfig {
+    /// URLhaus dump: the plain-text URL list, the CSV export or the hostfile.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub urlhaus: Option<String>,
+    /// Spamhaus DBL domains, one per line.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub spamhaus_dbl: Option<String>,
+}
+
+/// ALPM hook behaviour (`hook/traur-hook.rs`).
+///
+/// ```toml
+/// [hook]
+/// skip_unchanged = true
+/// remember_approvals = true
+/// ```
+#[derive(Debug, Deserialize, Serialize, Default)]
+pub struct HookConfig {
+    /// Skip packages whose AUR repo is still at the commit recorded when the hook last
+    /// let them through (`shared/hook_state.rs`).
+    #[serde(default)]
+    pub skip_unchanged: bool,
+    /// When the user answers yes to the SKETCHY/SUSPICIOUS prompt, whitelist those
+    /// packages pinned to their current PKGBUILD hash, so they are not asked about
+    /// again until the PKGBUILD changes.
+    #[serde(default)]
+    pub remember_approvals: bool,
+}
+
+/// Optional AUR account page lookups for `maintainer_analysis`.
+///
+/// ```toml
+/// [maintainer]
+/// aur_session = "<value of the AURSID cookie>"
+/// ```
+#[derive(Debug, Deserialize, Serialize, Default)]
+pub struct MaintainerConfig {
+    /// Session cookie of a logged-in AUR account. aurweb only shows account pages
+    /// (registration date, suspension) to logged-in users; unset skips the fetch.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aur_session: Option<String>,
+}
+
+/// Settings for every HTTP client traur builds (`shared/runtime.rs`), for networks
+/// that need a proxy or their own root CA.
+///
+/// ```toml
+/// [http]
+/// proxy = "http://proxy.corp.example:3128"
+/// ca_file = "/etc/ssl/certs/corp-root.pem"
+/// timeout = 30
+/// user_agent = "traur (corp)"
+/// aur_web_qps = 1
+/// ```
+#[derive(Debug, Deserialize, Serialize, Default)]
+pub struct HttpConfig {
+    /// Proxy for all requests. Unset uses `HTTPS_PROXY`/`HTTP_PROXY`/`NO_PROXY`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<String>,
+    /// PEM file of extra root certificates, trusted on top of the system ones.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ca_file: Option<String>,
+    /// Seconds a single request (GitHub, AUR pages, notifications) may take.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_agent: Option<String>,
+    /// Requests per second to the AUR web UI (comment and account pages), across all
+    /// concurrent fetches. `0` disables the throttle. The RPC API is not throttled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aur_web_qps: Option<u32>,
+}
+
+/// Default `[http] timeout`.
+pub const DEFAULT_HTTP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Default `[http] aur_web_qps`.
+const DEFAULT_AUR_WEB_QPS: u32 = 2;
+
+impl HttpConfig {
+    pub fn timeout(&self) -> Duration {
+        self.timeout.map(Duration::from_secs).unwrap_or(DEFAULT_HTTP_TIMEOUT)
+    }
+
+    pub fn user_agent(&self) -> &str {
+        self.user_agent.as_deref().unwrap_or("traur")
+    }
+
+    pub fn aur_web_qps(&self) -> u32 {
+        self.aur_web_qps.unwrap_or(DEFAULT_AUR_WEB_QPS)
+    }
+
+    /// Time between AUR web page requests; None when unthrottled.
+    pub fn aur_web_interval(&self) -> Option<Duration> {
+        let qps = self.aur_web_qps();
+        (qps > 0).then(|| Duration::from_secs(1) / qps)
+    }
+}
+
+/// Notification channels for MALICIOUS detections (hook blocks, scheduled scans).
+#[derive(Debug, Deserialize, Serialize)]
+pub struct NotifyConfig {
+    /// Send a desktop notification via notify-send.
+    #[serde(default)]
+    pub desktop: bool,
+    /// Generic webhook URL; receives a JSON POST with a `text` field.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub webhook_url: Option<String>,
+    /// ntfy topic URL (e.g. https://ntfy.sh/my-topic); receives a plain-text POST.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ntfy_url: Option<String>,
+    /// Lowest tier that triggers a notification.
+    #[serde(default = "default_notify_min_tier", deserialize_with = "tier_name")]
+    pub min_tier: String,
+    /// Message body template. See `notify::DEFAULT_TEMPLATE` for placeholders.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
+}
+
+impl Default for NotifyConfig {
+    fn default() -> Self {
+        Self {
+            desktop: false,
+            webhook_url: None,
+            ntfy_url: None,
+            min_tier: default_notify_min_tier(),
+            template: None,
+        }
+    }
+}
+
+impl NotifyConfig {
+    /// Whether any notification channel is configured.
+    pub fn is_enabled(&self) -> bool {
+        self.desktop || self.webhook_url.is_some() || self.ntfy_url.is_some()
+    }
+}
+
+fn default_notify_min_tier() -> String {
+    "malicious".to_string()
+}
+
+/// A tier name, rejected at load time if misspelled rather than treated as a default.
+fn tier_name<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    let name = String::deserialize(deserializer)?;
+    match crate::shared::scoring::tier_from_str(&name) {
+        Some(_) => Ok(name),
+        None => Err(serde::de::Error::custom(format!(
+            "unknown tier {name:?}, expected trusted, ok, sketchy, suspicious or malicious"
+        ))),
+    }
+}
+
+/// System-wide policy shared by every user on the machine.
+pub const SYSTEM_CONFIG_PATH: &str = "/etc/traur/config.toml";
+
+/// The settings that change scan results, for `fingerprint`.
+#[derive(Serialize)]
+struct ResultSettings<'a> {
+    version: &'static str,
+    thresholds: &'a ThresholdConfig,
+    scoring: &'a ScoringConfig,
+    ignored: &'a IgnoredConfig,
+    features: &'a FeaturesConfig,
+    names: &'a NamesConfig,
+    trusted_domains: &'a [String],
+    exclude: &'a [String],
+    max_file_bytes: usize,
+    deep: bool,
+    bulk_comments: bool,
+    threat_intel: &'a ThreatIntelConfig,
+    geoip: &'a GeoIpConfig,
+    known_good: &'a KnownGoodConfig,
+}
+
+/// Short digest of the settings that change scan results: thresholds, scoring, ignored
+/// signals and categories, enabled features, protected names, trusted domains, repo
+/// file excludes, the file size cap, deep mode, bulk comment scraping, threat-intel
+/// feeds, GeoIP databases and the known-good database source. The traur version is
+/// included too, since it pins the pattern database. Results cached under another
+/// fingerprint may not be what a scan would report now.
+pub fn fingerprint(config: &Config) -> String {
+    let settings = ResultSettings {
+        version: env!("CARGO_PKG_VERSION"),
+        thresholds: &config.thresholds,
+        scoring: &config.scoring,
+        ignored: &config.ignored,
+        features: &config.features,
+        names: &config.names,
+        trusted_domains: &config.trusted_domains,
+        exclude: &config.scan.exclude,
+        max_file_bytes: config.scan.max_file_bytes(),
+        deep: config.scan.deep,
+        bulk_comments: config.scan.bulk_comments,
+        threat_intel: &config.threat_intel,
+        geoip: &config.geoip,
+        known_good: &config.known_good,
+    };
+    // Through serde_json::Value, whose maps are sorted, so HashMap order doesn't matter
+    let json = serde_json::to_value(&settings).map(|v| v.to_string()).unwrap_or_default();
+    crate::shared::known_good::sha256_hex(&json)[..16].to_string()
+}
+
+/// Load the effective config: the system config (if present) merged with the
+/// user's ~/.config/traur/config.toml. See `merge_configs` for precedence.
 pub fn load_config() -> Config {
-    let path = config_path();
-    match std::fs::read_to_string(&path) {
-        Ok(content) => toml::from_str(&content).unwrap_or_default(),
-        Err(_) => Config::default(),
+    let user = load_user_config();
+    match load_system_config() {
+        Some(system) => merge_configs(system, user),
+        None => user,
+    }
+}
+
+/// Load only the user's config file, falling back to defaults.
+/// This is what the `allow`/`ignore` commands edit and save back.
+pub fn load_user_config() -> Config {
+    read_config_file(&config_path()).unwrap_or_default()
+}
+
+/// Load /etc/traur/config.toml, if it exists.
+pub fn load_system_config() -> Option<Config> {
+    read_config_file(std::path::Path::new(SYSTEM_CONFIG_PATH))
+}
+
+fn read_config_file(path: &std::path::Path) -> Option<Config> {
+    let content = std::fs::read_to_string(path).ok()?;
+    match toml::from_str(&content) {
+        Ok(config) => Some(config),
+        Err(e) => {
+            eprintln!("traur: ignoring invalid config {}: {e}", path.display());
+            None
+        }
+    }
+}
+
+/// Layer a user config over the system policy, section by section.
+///
+/// - Policy: the system config is authoritative for thresholds, scoring, whitelist
+///   and features — a user cannot allow a package the system doesn't.
+/// - Additive: the user may add ignored signals/categories, protected names, trusted
+///   domains, `scan.exclude` patterns and binary repos on top of the system's (a
+///   system repo wins a name clash).
+/// - User scope: `[notify]`, `[maintainer]`, `[cache]`, `[report]` and the `[scan]`
+///   limits are the user's own; their values win and the system's fill the gaps.
+/// - Environment: `[http]`, `[hook]`, `[known_good]`, `[geoip]`, `[threat_intel]` and
+///   `[policy]` describe the machine; the system's values win and the user's fill in
+///   the ones it leaves unset.
+pub fn merge_configs(system: Config, user: Config) -> Config {
+    let mut config = with_extra_ignores(system, &user.ignored.signals, &user.ignored.categories);
+    add_missing(&mut config.names.protected, user.names.protected);
+    add_missing(&mut config.trusted_domains, user.trusted_domains);
+    add_missing(&mut config.scan.exclude, user.scan.exclude);
+    for (name, repo) in user.repos {
+        config.repos.entry(name).or_insert(repo);
+    }
+
+    let scan = &mut config.scan;
+    scan.jobs = user.scan.jobs.or(scan.jobs);
+    scan.timeout = user.scan.timeout.or(scan.timeout);
+    scan.max_file_kb = user.scan.max_file_kb.or(scan.max_file_kb);
+    let scan_defaults = ScanConfig::default();
+    if user.scan.deep != scan_defaults.deep {
+        scan.deep = user.scan.deep;
+    }
+    if user.scan.bulk_comments != scan_defaults.bulk_comments {
+        scan.bulk_comments = user.scan.bulk_comments;
+    }
+
+    let notify = &mut config.notify;
+    let notify_defaults = NotifyConfig::default();
+    notify.desktop |= user.notify.desktop;
+    notify.webhook_url = user.notify.webhook_url.or(notify.webhook_url.take());
+    notify.ntfy_url = user.notify.ntfy_url.or(notify.ntfy_url.take());
+    if user.notify.min_tier != notify_defaults.min_tier {
+        notify.min_tier = user.notify.min_tier;
+    }
+    notify.template = user.notify.template.or(notify.template.take());
+
+    config.maintainer.aur_session = user.maintainer.aur_session.or(config.maintainer.aur_session.take());
+    let cache = &mut config.cache;
+    cache.maintainer_ttl = user.cache.maintainer_ttl.or(cache.maintainer_ttl);
+    cache.comments_ttl = user.cache.comments_ttl.or(cache.comments_ttl);
+    cache.max_size_mb = user.cache.max_size_mb.or(cache.max_size_mb);
+    config.report.endpoint = user.report.endpoint.or(config.report.endpoint.take());
+
+    let http = &mut config.http;
+    http.proxy = http.proxy.take().or(user.http.proxy);
+    http.ca_file = http.ca_file.take().or(user.http.ca_file);
+    http.timeout = http.timeout.or(user.http.timeout);
+    http.user_agent = http.user_agent.take().or(user.http.user_agent);
+    http.aur_web_qps = http.aur_web_qps.or(user.http.aur_web_qps);
+    config.hook.skip_unchanged |= user.hook.skip_unchanged;
+    config.hook.remember_approvals |= user.hook.remember_approvals;
+    let known_good = &mut config.known_good;
+    known_good.url = known_good.url.take().or(user.known_good.url);
+    known_good.max_age_hours = known_good.max_age_hours.or(user.known_good.max_age_hours);
+    config.geoip.asn_db = config.geoip.asn_db.take().or(user.geoip.asn_db);
+    config.geoip.country_db = config.geoip.country_db.take().or(user.geoip.country_db);
+    let threat_intel = &mut config.threat_intel;
+    threat_intel.urlhaus = threat_intel.urlhaus.take().or(user.threat_intel.urlhaus);
+    threat_intel.spamhaus_dbl = threat_intel.spamhaus_dbl.take().or(user.threat_intel.spamhaus_dbl);
+    config.policy.version = config.policy.version.or(user.policy.version);
+    if config.policy.trusted_keys.is_empty() {
+        config.policy.trusted_keys = user.policy.trusted_keys;
+    }
+    config
+}
+
+/// Append the values of `extra` that `list` doesn't hold yet, in order.
+fn add_missing(list: &mut Vec<String>, extra: Vec<String>) {
+    for value in extra {
+        if !list.contains(&value) {
+            list.push(value);
+        }
     }
 }
 
 /// Save config to ~/.config/traur/config.toml, creating directory if needed.
+/// Under sudo/doas the file (and any directory created for it) is handed to the
+/// invoking user, so a root process like the hook doesn't leave them a config they
+/// can no longer edit.
 pub fn save_config(config: &Config) -> Result<(), String> {
-    let path = config_path();
+    save_config_at(&config_path(), config, calling_user_ids())
+}
+
+/// Save the system-wide policy to /etc/traur/config.toml.
+pub fn save_system_config(config: &Config) -> Result<(), String> {
+    save_config_at(std::path::Path::new(SYSTEM_CONFIG_PATH), config, None)
+}
+
+/// Write `config` to `path`, chowning what it creates to `owner` (uid, gid).
+fn save_config_at(path: &std::path::Path, config: &Config, owner: Option<(u32, u32)>) -> Result<(), String> {
+    let mut created = Vec::new();
     if let Some(parent) = path.parent() {
+        created = parent.ancestors().take_while(|dir| !dir.exists()).map(|dir| dir.to_path_buf()).collect();
         std::fs::create_dir_all(parent)
             .map_err(|e| format!("Failed to create config directory: {e}"))?;
     }
     let toml_str =
         toml::to_string_pretty(config).map_err(|e| format!("Failed to serialize config: {e}"))?;
-    std::fs::write(&path, toml_str).map_err(|e| format!("Failed to write config: {e}"))?;
+    std::fs::write(path, toml_str).map_err(|e| format!("Failed to write {}: {e}", path.display()))?;
+    if let Some((uid, gid)) = owner {
+        for target in created.iter().map(|dir| dir.as_path()).chain([path]) {
+            chown(target, uid, gid)?;
+        }
+    }
     Ok(())
 }
 
-/// Add a package to the whitelist and persist to disk.
-pub fn add_to_whitelist(package: &str) -> Result<(), String> {
-    let mut config = load_config();
+#[cfg(unix)]
+fn chown(path: &std::path::Path, uid: u32, gid: u32) -> Result<(), String> {
+    use std::os::unix::fs::MetadataExt;
+    match std::fs::metadata(path) {
+        Ok(meta) if meta.uid() == uid && meta.gid() == gid => Ok(()),
+        _ => std::os::unix::fs::chown(path, Some(uid), Some(gid))
+            .map_err(|e| format!("Failed to hand {} to uid {uid}: {e}", path.display())),
+    }
+}
+
+#[cfg(not(unix))]
+fn chown(_path: &std::path::Path, _uid: u32, _gid: u32) -> Result<(), String> {
+    Ok(())
+}
+
+/// Add a package to the whitelist and persist to disk. With `pin`, the entry only
+/// holds while the PKGBUILD hashes to it; without, any earlier pin is dropped.
+pub fn add_to_whitelist(package: &str, pin: Option<String>) -> Result<(), String> {
+    add_to_whitelist_at(&config_path(), calling_user_ids(), package, pin)
+}
+
+fn add_to_whitelist_at(
+    path: &std::path::Path,
+    owner: Option<(u32, u32)>,
+    package: &str,
+    pin: Option<String>,
+) -> Result<(), String> {
+    let mut config = read_config_file(path).unwrap_or_default();
     if !config.whitelist.packages.contains(&package.to_string()) {
         config.whitelist.packages.push(package.to_string());
         config.whitelist.packages.sort();
     }
-    save_config(&config)
+    match pin {
+        Some(hash) => config.whitelist.pins.insert(package.to_string(), hash),
+        None => config.whitelist.pins.remove(package),
+    };
+    save_config_at(path, &config, owner)
 }
 
-/// Check if a package is whitelisted in the given config.
-#[allow(dead_code)] // Used by traur-hook binary
+/// Check if a package is whitelisted in the given config regardless of its PKGBUILD.
+/// Pinned entries are not: see `whitelist_pin`.
 pub fn is_whitelisted_in(config: &Config, package: &str) -> bool {
-    config.whitelist.packages.iter().any(|p| p == package)
+    config.whitelist.packages.iter().any(|p| p == package) && !config.whitelist.pins.contains_key(package)
+}
+
+/// The PKGBUILD hash a whitelisted package is pinned to, if any.
+pub fn whitelist_pin<'a>(config: &'a Config, package: &str) -> Option<&'a str> {
+    config
+        .whitelist
+        .packages
+        .iter()
+        .any(|p| p == package)
+        .then(|| config.whitelist.pins.get(package).map(String::as_str))
+        .flatten()
 }
 
 /// Add a signal ID to the ignored list and persist to disk.
 pub fn add_to_ignored(signal_id: &str) -> Result<(), String> {
-    let mut config = load_config();
+    let mut config = load_user_config();
     if !config.ignored.signals.contains(&signal_id.to_string()) {
         config.ignored.signals.push(signal_id.to_string());
         config.ignored.signals.sort();
@@ -99,7 +886,7 @@ pub fn add_to_ignored(signal_id: &str) -> Result<(), String> {
 
 /// Remove a signal ID from the ignored list and persist to disk.
 pub fn remove_from_ignored(signal_id: &str) -> Result<(), String> {
-    let mut config = load_config();
+    let mut config = load_user_config();
     config.ignored.signals.retain(|s| s != signal_id);
     save_config(&config)
 }
@@ -134,9 +921,46 @@ pub fn is_signal_ignored(
     false
 }
 
+/// Add per-invocation ignores (e.g. from `--ignore-signal`/`--ignore-category`)
+/// on top of the loaded config. Nothing is persisted.
+pub fn with_extra_ignores(mut config: Config, signals: &[String], categories: &[String]) -> Config {
+    for id in signals {
+        if !config.ignored.signals.contains(id) {
+            config.ignored.signals.push(id.clone());
+        }
+    }
+    for cat in categories {
+        if !config.ignored.categories.iter().any(|c| c.eq_ignore_ascii_case(cat)) {
+            config.ignored.categories.push(cat.clone());
+        }
+    }
+    config
+}
+
+/// Turn on deep mode for this invocation (`--deep`). Nothing is persisted.
+pub fn with_deep_scan(mut config: Config, deep: bool) -> Config {
+    config.scan.deep |= deep;
+    config
+}
+
+/// Whether `[scoring] demoted_gates` demotes this override gate. Demoting "P-FOO"
+/// also demotes "IS-P-FOO".
+pub fn is_gate_demoted(config: &Config, signal_id: &str) -> bool {
+    let base = signal_id.strip_prefix("IS-").unwrap_or(signal_id);
+    config.scoring.demoted_gates.iter().any(|s| s == signal_id || s == base)
+}
+
+/// Override the per-package scan budget for this invocation (`--timeout`). Nothing is persisted.
+pub fn with_scan_timeout(mut config: Config, secs: Option<u64>) -> Config {
+    if secs.is_some() {
+        config.scan.timeout = secs;
+    }
+    config
+}
+
 /// Add a category to the ignored list and persist to disk.
 pub fn add_category_to_ignored(category: &str) -> Result<(), String> {
-    let mut config = load_config();
+    let mut config = load_user_config();
     if !config.ignored.categories.iter().any(|c| c.eq_ignore_ascii_case(category)) {
         config.ignored.categories.push(category.to_string());
         config.ignored.categories.sort();
@@ -146,7 +970,7 @@ pub fn add_category_to_ignored(category: &str) -> Result<(), String> {
 
 /// Remove a category from the ignored list and persist to disk.
 pub fn remove_category_from_ignored(category: &str) -> Result<(), String> {
-    let mut config = load_config();
+    let mut config = load_user_config();
     config.ignored.categories.retain(|c| !c.eq_ignore_ascii_case(category));
     save_config(&config)
 }
@@ -173,7 +997,7 @@ pub fn config_path() -> std::path::PathBuf {
 }
 
 /// Resolve the invoking user's home directory when running under sudo or doas.
-fn calling_user_home() -> Option<std::path::PathBuf> {
+pub(crate) fn calling_user_home() -> Option<std::path::PathBuf> {
     let user = std::env::var("SUDO_USER")
         .or_else(|_| std::env::var("DOAS_USER"))
         .ok()?;
@@ -192,3 +1016,266 @@ fn calling_user_home() -> Option<std::path::PathBuf> {
     let home = line.split(':').nth(5)?;
     Some(std::path::PathBuf::from(home))
 }
+
+/// The uid and gid of the non-root user that invoked sudo (`SUDO_UID`/`SUDO_GID`)
+/// or doas (`DOAS_USER`, looked up with getent).
+fn calling_user_ids() -> Option<(u32, u32)> {
+    let ids = match (std::env::var("SUDO_UID"), std::env::var("SUDO_GID")) {
+        (Ok(uid), Ok(gid)) => (uid.parse().ok()?, gid.parse().ok()?),
+        _ => {
+            let user = std::env::var("DOAS_USER").ok()?;
+            let output = std::process::Command::new("getent").args(["passwd", &user]).output().ok()?;
+            let line = String::from_utf8(output.stdout).ok()?;
+            let mut fields = line.split(':').skip(2);
+            (fields.next()?.parse().ok()?, fields.next()?.parse().ok()?)
+        }
+    };
+    (ids.0 != 0).then_some(ids)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_feature_switches_and_timeouts() {
+        let config: Config = toml::from_str(
+            "[features]\ngithub_stars = false\n\n[features.timeouts]\naur_comments_analysis = 5\n",
+        )
+        .unwrap();
+        assert!(!config.features.is_enabled("github_stars"));
+        assert!(config.features.is_enabled("pkgbuild_analysis"));
+        assert_eq!(config.features.timeout("aur_comments_analysis"), Some(Duration::from_secs(5)));
+        assert_eq!(config.features.timeout("github_stars"), None);
+    }
+
+    #[test]
+    fn feature_config_round_trips() {
+        let mut config = Config::default();
+        config.features.enabled.insert("github_stars".to_string(), false);
+        config.features.timeouts.insert("github_stars".to_string(), 3);
+        let serialized = toml::to_string_pretty(&config).unwrap();
+        let parsed: Config = toml::from_str(&serialized).unwrap();
+        assert!(!parsed.features.is_enabled("github_stars"));
+        assert_eq!(parsed.features.timeout("github_stars"), Some(Duration::from_secs(3)));
+    }
+
+    #[test]
+    fn fingerprint_tracks_result_settings_only() {
+        let base = config_from("[features]\ngithub_stars = false\naur_comments_analysis = false\n");
+        let reordered = config_from("[features]\naur_comments_analysis = false\ngithub_stars = false\n");
+        assert_eq!(fingerprint(&base), fingerprint(&reordered));
+
+        let notify = config_from("[features]\ngithub_stars = false\naur_comments_analysis = false\n[notify]\ndesktop = true\n");
+        assert_eq!(fingerprint(&base), fingerprint(&notify));
+
+        let ignored = config_from("[features]\ngithub_stars = false\naur_comments_analysis = false\n[ignored]\nsignals = [\"M-VOTES-ZERO\"]\n");
+        assert_ne!(fingerprint(&base), fingerprint(&ignored));
+    }
+
+    #[test]
+    fn fingerprint_tracks_excludes_geoip_and_known_good() {
+        let base = fingerprint(&Config::default());
+        for changed in [
+            "[scan]\nexclude = [\"vendor/\"]\n",
+            "[geoip]\nasn_db = \"/usr/share/GeoIP/GeoLite2-ASN.mmdb\"\n",
+            "[known_good]\nurl = \"https://example.org/known-good.json\"\n",
+        ] {
+            assert_ne!(fingerprint(&config_from(changed)), base, "{changed}");
+        }
+        assert_ne!(
+            fingerprint(&config_from("[scan]\nexclude = [\"vendor/\"]\n")),
+            fingerprint(&config_from("[scan]\nexclude = [\"*.bin\"]\n"))
+        );
+    }
+
+    fn config_from(toml_str: &str) -> Config {
+        toml::from_str(toml_str).unwrap()
+    }
+
+    #[test]
+    fn misspelled_notify_tier_is_rejected() {
+        assert_eq!(config_from("[notify]\nmin_tier = \"Suspicious\"\n").notify.min_tier, "Suspicious");
+        let err = toml::from_str::<Config>("[notify]\nmin_tier = \"suspicous\"\n").unwrap_err();
+        assert!(err.to_string().contains("unknown tier"), "{err}");
+    }
+
+    #[test]
+    fn system_whitelist_and_thresholds_win() {
+        let system = config_from("[thresholds]\nblock_at = \"high\"\n[whitelist]\npackages = [\"corp-tool\"]\n");
+        let user = config_from("[thresholds]\nblock_at = \"malicious\"\n[whitelist]\npackages = [\"sketchy-thing\"]\n");
+        let merged = merge_configs(system, user);
+        assert_eq!(merged.thresholds.block_at, "high");
+        assert!(is_whitelisted_in(&merged, "corp-tool"));
+        assert!(!is_whitelisted_in(&merged, "sketchy-thing"));
+    }
+
+    #[test]
+    fn user_can_add_ignores() {
+        let system = config_from("[ignored]\nsignals = [\"P-PYTHON-INLINE\"]\n");
+        let user = config_from("[ignored]\nsignals = [\"M-VOTES-ZERO\", \"P-PYTHON-INLINE\"]\ncategories = [\"Temporal\"]\n");
+        let merged = merge_configs(system, user);
+        assert_eq!(merged.ignored.signals, ["P-PYTHON-INLINE", "M-VOTES-ZERO"]);
+        assert_eq!(merged.ignored.categories, ["Temporal"]);
+    }
+
+    #[test]
+    fn extra_ignores_layer_on_the_config_without_repeats() {
+        let config = config_from("[ignored]\nsignals = [\"M-VOTES-ZERO\"]\ncategories = [\"temporal\"]\n");
+        let flags = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        let config = with_extra_ignores(config, &flags(&["P-PYTHON-INLINE", "M-VOTES-ZERO"]), &flags(&["Temporal", "Metadata"]));
+        assert_eq!(config.ignored.signals, ["M-VOTES-ZERO", "P-PYTHON-INLINE"]);
+        assert_eq!(config.ignored.categories, ["temporal", "Metadata"]);
+        use crate::shared::scoring::SignalCategory;
+        assert!(is_signal_ignored(&config, "P-PYTHON-INLINE", &SignalCategory::Pkgbuild));
+        assert!(is_signal_ignored(&config, "M-POP-ZERO", &SignalCategory::Metadata));
+        assert!(!is_signal_ignored(&config, "P-CURL-PIPE", &SignalCategory::Pkgbuild));
+    }
+
+    #[test]
+    fn user_can_add_protected_names() {
+        let system = config_from("[names]\nprotected = [\"acme-vpn\"]\n");
+        let user = config_from("[names]\nprotected = [\"acme-agent\", \"acme-vpn\"]\n");
+        let merged = merge_configs(system, user);
+        assert_eq!(merged.names.protected, ["acme-vpn", "acme-agent"]);
+    }
+
+    #[test]
+    fn user_http_settings_fill_in_system_gaps() {
+        let system = config_from("[http]\nproxy = \"http://proxy.corp.example:3128\"\n");
+        let user = config_from("[http]\nproxy = \"http://127.0.0.1:8080\"\ntimeout = 30\n");
+        let merged = merge_configs(system, user);
+        assert_eq!(merged.http.proxy.as_deref(), Some("http://proxy.corp.example:3128"));
+        assert_eq!(merged.http.timeout(), Duration::from_secs(30));
+        assert_eq!(merged.http.user_agent(), "traur");
+    }
+
+    #[test]
+    fn every_section_merges_field_by_field() {
+        let system = config_from(
+            "trusted_domains = [\"corp.example\"]\n\
+             [scan]\nexclude = [\"vendor/\"]\njobs = 2\ntimeout = 60\n\
+             [notify]\nwebhook_url = \"https://hooks.corp.example/traur\"\nmin_tier = \"suspicious\"\n\
+             [cache]\nmax_size_mb = 512\n\
+             [geoip]\nasn_db = \"/usr/share/GeoIP/corp-ASN.mmdb\"\n\
+             [repos.corp]\nurl = \"https://pkgs.corp.example/x86_64\"\n",
+        );
+        let user = config_from(
+            "trusted_domains = [\"example.org\"]\n\
+             [scan]\nexclude = [\"*.bin\"]\njobs = 8\ndeep = true\n\
+             [notify]\ndesktop = true\nmin_tier = \"sketchy\"\n\
+             [cache]\ncomments_ttl = 60\n\
+             [geoip]\nasn_db = \"/tmp/ASN.mmdb\"\ncountry_db = \"/tmp/Country.mmdb\"\n\
+             [known_good]\nurl = \"https://kg.example.org/db.json\"\n\
+             [hook]\nskip_unchanged = true\n\
+             [repos.corp]\nurl = \"https://evil.example/x86_64\"\n[repos.mine]\nurl = \"https://mine.example/x86_64\"\n",
+        );
+        let merged = merge_configs(system, user);
+
+        assert_eq!(merged.trusted_domains, ["corp.example", "example.org"]);
+        assert_eq!(merged.scan.exclude, ["vendor/", "*.bin"]);
+        assert_eq!((merged.scan.jobs, merged.scan.timeout, merged.scan.deep), (Some(8), Some(60), true));
+        assert!(merged.notify.desktop);
+        assert_eq!(merged.notify.webhook_url.as_deref(), Some("https://hooks.corp.example/traur"));
+        assert_eq!(merged.notify.min_tier, "sketchy");
+        assert_eq!((merged.cache.max_size_mb, merged.cache.comments_ttl), (Some(512), Some(60)));
+        assert_eq!(merged.geoip.asn_db.as_deref(), Some("/usr/share/GeoIP/corp-ASN.mmdb"));
+        assert_eq!(merged.geoip.country_db.as_deref(), Some("/tmp/Country.mmdb"));
+        assert_eq!(merged.known_good.url.as_deref(), Some("https://kg.example.org/db.json"));
+        assert!(merged.hook.skip_unchanged);
+        assert_eq!(merged.repos["corp"].url, "https://pkgs.corp.example/x86_64");
+        assert_eq!(merged.repos["mine"].url, "https://mine.example/x86_64");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn whitelist_write_keeps_settings_and_goes_to_the_owner() {
+        use std::os::unix::fs::MetadataExt;
+        let dir = std::env::temp_dir().join(format!("traur-whitelist-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let path = dir.join("traur").join("config.toml");
+        // Root can hand the file to anyone; others can only test keeping their own ids
+        let me = std::fs::metadata(std::env::temp_dir()).unwrap();
+        let owner = if me.uid() == 0 { (65534, 65534) } else { (me.uid(), me.gid()) };
+
+        save_config_at(&path, &config_from("[notify]\ndesktop = true\n"), None).unwrap();
+        add_to_whitelist_at(&path, Some(owner), "foo-bin", Some("ab".repeat(32))).unwrap();
+        add_to_whitelist_at(&path, Some(owner), "bar", None).unwrap();
+        let config = read_config_file(&path).unwrap();
+        let file = std::fs::metadata(&path).unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert_eq!(config.whitelist.packages, ["bar", "foo-bin"]);
+        assert_eq!(whitelist_pin(&config, "foo-bin"), Some("ab".repeat(32).as_str()));
+        assert!(is_whitelisted_in(&config, "bar"));
+        assert!(config.notify.desktop);
+        assert_eq!((file.uid(), file.gid()), owner);
+    }
+
+    #[test]
+    fn scan_timeout_from_config_and_flag() {
+        let config = config_from("[scan]\ntimeout = 90\n");
+        assert_eq!(config.scan.timeout(), Some(Duration::from_secs(90)));
+        let config = with_scan_timeout(config, None);
+        assert_eq!(config.scan.timeout(), Some(Duration::from_secs(90)));
+        let config = with_scan_timeout(config, Some(5));
+        assert_eq!(config.scan.timeout(), Some(Duration::from_secs(5)));
+    }
+
+    #[test]
+    fn maintainer_ttl_defaults_and_overrides() {
+        assert_eq!(Config::default().cache.maintainer_ttl(), DEFAULT_MAINTAINER_TTL);
+        let config = config_from("[cache]\nmaintainer_ttl = 0\n");
+        assert_eq!(config.cache.maintainer_ttl(), Duration::ZERO);
+    }
+
+    #[test]
+    fn aur_web_throttle_and_bulk_comments_default_on() {
+        let config = Config::default();
+        assert_eq!(config.http.aur_web_interval(), Some(Duration::from_millis(500)));
+        assert!(config.scan.bulk_comments);
+        assert_eq!(config.cache.comments_ttl(), DEFAULT_COMMENTS_TTL);
+
+        let config = config_from("[http]\naur_web_qps = 0\n[scan]\nbulk_comments = false\n");
+        assert_eq!(config.http.aur_web_interval(), None);
+        assert!(!config.scan.bulk_comments);
+        assert_ne!(fingerprint(&config), fingerprint(&Config::default()));
+    }
+
+    #[test]
+    fn cache_size_limit_defaults_and_overrides() {
+        assert_eq!(Config::default().cache.max_size_bytes(), DEFAULT_MAX_SIZE_MB * 1024 * 1024);
+        let config = config_from("[cache]\nmax_size_mb = 10\n");
+        assert_eq!(config.cache.max_size_bytes(), 10 * 1024 * 1024);
+    }
+
+    #[test]
+    fn exclude_follows_gitignore_rules() {
+        let config = config_from(
+            "[scan]\nexclude = [\"*.png\", \"vendor/\", \"/assets/*.bin\", \"**/testdata/\"]\n",
+        );
+        let scan = &config.scan;
+        assert!(scan.is_excluded("logo.png"));
+        assert!(scan.is_excluded("docs/img/logo.png"));
+        assert!(scan.is_excluded("vendor/lib/blob.js"));
+        assert!(scan.is_excluded("third_party/vendor/x.c"));
+        assert!(scan.is_excluded("assets/fw.bin"));
+        assert!(scan.is_excluded("src/pkg/testdata/sample.gz"));
+        assert!(!scan.is_excluded("nested/assets/fw.bin"));
+        assert!(!scan.is_excluded("assets/sub/fw.bin"));
+        assert!(scan.is_excluded("vendor/"));
+        assert!(!scan.is_excluded("vendor"));
+        assert!(!scan.is_excluded("helper.sh"));
+    }
+
+    #[test]
+    fn builtin_excludes_apply_without_config() {
+        let scan = ScanConfig::default();
+        assert!(scan.is_excluded("icons/foo.svgz"));
+        assert!(scan.is_excluded("fix-build.patch.gz"));
+        assert!(scan.is_excluded("keys/pgp/ABCDEF.asc"));
+        assert!(!scan.is_excluded("src/keys/pgp/ABCDEF.asc"));
+        assert!(!scan.is_excluded("fix-build.patch"));
+        assert!(!scan.is_excluded("payload.bin"));
+    }
+}
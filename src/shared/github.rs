@@ -1,7 +1,15 @@
+#[cfg(feature = "native")]
+use crate::shared::models::GitHubRelease;
+#[cfg(feature = "native")]
+use crate::shared::runtime;
 use regex::Regex;
+#[cfg(feature = "native")]
 use serde::Deserialize;
 use std::sync::LazyLock;
 
+/// Releases requested per page. A full page means older releases may be missing.
+pub const RELEASES_PER_PAGE: usize = 100;
+
 static GITHUB_URL_RE: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(r#"(?i)github\.com/([^/\s]+)/([^/\s#?.]+)"#).unwrap()
 });
@@ -12,31 +20,34 @@ pub struct GitHubInfo {
     pub found: bool,
 }
 
+#[cfg(feature = "native")]
 #[derive(Deserialize)]
 struct RepoResponse {
     stargazers_count: u32,
 }
 
+#[cfg(feature = "native")]
+#[derive(Deserialize)]
+struct ReleaseResponse {
+    tag_name: String,
+    #[serde(default)]
+    assets: Vec<AssetResponse>,
+}
+
+#[cfg(feature = "native")]
+#[derive(Deserialize)]
+struct AssetResponse {
+    name: String,
+}
+
 /// Fetch GitHub star count for a URL. Returns None for non-GitHub URLs or errors.
-pub fn fetch_github_stars(url: &str) -> Option<GitHubInfo> {
+#[cfg(feature = "native")]
+pub async fn fetch_github_stars(url: &str) -> Option<GitHubInfo> {
     let (owner, repo) = parse_github_url(url)?;
 
     let api_url = format!("https://api.github.com/repos/{owner}/{repo}");
 
-    let client = reqwest::blocking::Client::new();
-    let mut request = client
-        .get(&api_url)
-        .header("User-Agent", "traur")
-        .header("Accept", "application/vnd.github.v3+json");
-
-    // Support GITHUB_TOKEN for higher rate limits
-    if let Ok(token) = std::env::var("GITHUB_TOKEN") {
-        if !token.is_empty() {
-            request = request.header("Authorization", format!("Bearer {token}"));
-        }
-    }
-
-    let resp = match request.timeout(std::time::Duration::from_secs(10)).send() {
+    let resp = match api_request(&api_url).send().await {
         Ok(r) => r,
         Err(_) => return None, // network error, graceful skip
     };
@@ -52,7 +63,7 @@ pub fn fetch_github_stars(url: &str) -> Option<GitHubInfo> {
         return None; // rate limit or other error, graceful skip
     }
 
-    let repo_data: RepoResponse = match resp.json() {
+    let repo_data: RepoResponse = match resp.json().await {
         Ok(d) => d,
         Err(_) => return None,
     };
@@ -63,8 +74,73 @@ pub fn fetch_github_stars(url: &str) -> Option<GitHubInfo> {
     })
 }
 
+/// Fetch the most recent releases (tag + asset names) of the GitHub repo behind a URL.
+/// Returns None for non-GitHub URLs, missing repos, or errors.
+#[cfg(feature = "native")]
+pub async fn fetch_github_releases(url: &str) -> Option<Vec<GitHubRelease>> {
+    let (owner, repo) = parse_github_url(url)?;
+    let api_url =
+        format!("https://api.github.com/repos/{owner}/{repo}/releases?per_page={RELEASES_PER_PAGE}");
+
+    let resp = api_request(&api_url).send().await.ok()?;
+    if !resp.status().is_success() {
+        return None; // not found, rate limit or other error, graceful skip
+    }
+
+    let releases: Vec<ReleaseResponse> = resp.json().await.ok()?;
+    Some(
+        releases
+            .into_iter()
+            .map(|r| GitHubRelease {
+                tag_name: r.tag_name,
+                assets: r.assets.into_iter().map(|a| a.name).collect(),
+            })
+            .collect(),
+    )
+}
+
+#[cfg(feature = "native")]
+#[derive(Deserialize)]
+struct TagResponse {
+    name: String,
+}
+
+/// Fetch the most recent tag names of the GitHub repo behind a URL, which also covers
+/// projects that tag versions without publishing releases. Returns None for non-GitHub
+/// URLs, missing repos, or errors.
+#[cfg(feature = "native")]
+pub async fn fetch_github_tags(url: &str) -> Option<Vec<String>> {
+    let (owner, repo) = parse_github_url(url)?;
+    let api_url = format!("https://api.github.com/repos/{owner}/{repo}/tags?per_page={RELEASES_PER_PAGE}");
+
+    let resp = api_request(&api_url).send().await.ok()?;
+    if !resp.status().is_success() {
+        return None;
+    }
+
+    let tags: Vec<TagResponse> = resp.json().await.ok()?;
+    Some(tags.into_iter().map(|t| t.name).collect())
+}
+
+/// Build a GitHub API GET request with standard headers.
+#[cfg(feature = "native")]
+fn api_request(api_url: &str) -> reqwest::RequestBuilder {
+    let mut request = runtime::client()
+        .get(api_url)
+        .header("Accept", "application/vnd.github.v3+json");
+
+    // Support GITHUB_TOKEN for higher rate limits
+    if let Ok(token) = std::env::var("GITHUB_TOKEN")
+        && !token.is_empty()
+    {
+        request = request.header("Authorization", format!("Bearer {token}"));
+    }
+
+    request.timeout(runtime::request_timeout())
+}
+
 /// Parse a GitHub URL to extract owner and repo.
-fn parse_github_url(url: &str) -> Option<(String, String)> {
+pub fn parse_github_url(url: &str) -> Option<(String, String)> {
     let caps = GITHUB_URL_RE.captures(url)?;
     let owner = caps[1].to_string();
     let mut repo = caps[2].to_string();
@@ -0,0 +1,51 @@
+use std::path::{Path, PathBuf};
+
+/// Per-package build directories of AUR helpers, relative to the user's home. Each
+/// keeps the clone it last built from, so its PKGBUILD is the one that is installed
+/// until the next upgrade pulls the repo.
+const HELPER_BUILD_DIRS: &[&str] = &[
+    ".cache/yay",
+    ".cache/paru/clone",
+    ".cache/pikaur/build",
+    ".local/share/pikaur/aur_repos",
+];
+
+/// The PKGBUILD an AUR helper last built `package_base` from, if any helper cache
+/// of the invoking user has one.
+pub fn built_pkgbuild(package_base: &str) -> Option<String> {
+    let home = crate::shared::config::calling_user_home()
+        .or_else(|| std::env::var_os("HOME").map(PathBuf::from))?;
+    built_pkgbuild_in(&home, package_base)
+}
+
+fn built_pkgbuild_in(home: &Path, package_base: &str) -> Option<String> {
+    if package_base.is_empty() || package_base.contains('/') || package_base.starts_with('.') {
+        return None;
+    }
+    HELPER_BUILD_DIRS
+        .iter()
+        .find_map(|dir| std::fs::read_to_string(home.join(dir).join(package_base).join("PKGBUILD")).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_pkgbuild_in_helper_caches() {
+        let home = std::env::temp_dir().join(format!("traur-helper-cache-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&home);
+        let paru = home.join(".cache/paru/clone/foo");
+        std::fs::create_dir_all(&paru).unwrap();
+        std::fs::write(paru.join("PKGBUILD"), "pkgname=foo\npkgver=1.0\n").unwrap();
+
+        let found = built_pkgbuild_in(&home, "foo");
+        let missing = built_pkgbuild_in(&home, "bar");
+        let escaped = built_pkgbuild_in(&home, "../clone/foo");
+        let _ = std::fs::remove_dir_all(&home);
+
+        assert_eq!(found.as_deref(), Some("pkgname=foo\npkgver=1.0\n"));
+        assert!(missing.is_none());
+        assert!(escaped.is_none());
+    }
+}
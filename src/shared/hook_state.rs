@@ -0,0 +1,91 @@
+//! AUR HEAD commits the ALPM hook last let through, for `[hook] skip_unchanged`.
+//!
+//! After a transaction the hook did not block, each scanned package's commit is
+//! recorded in `hook-scanned.json` in the cache directory. On the next transaction a
+//! package whose AUR repo is still at that commit (a rebuild, a reinstall, a
+//! dependency bump on our side) is not scanned again. The commits are kept with the
+//! config fingerprint (`config::fingerprint`) they were scanned under; once the
+//! config changes, every package is scanned again.
+
+use crate::shared::cache;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Package name -> AUR HEAD commit at its last accepted scan.
+pub type ScannedCommits = BTreeMap<String, String>;
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+struct State {
+    config_fingerprint: String,
+    commits: ScannedCommits,
+}
+
+fn state_path() -> PathBuf {
+    cache::cache_dir().join("hook-scanned.json")
+}
+
+/// The commits recorded under config `fingerprint`; empty if none were recorded or the
+/// file is unreadable. None if they were recorded under a different config.
+#[allow(dead_code)] // Used by traur-hook binary
+pub fn load(fingerprint: &str) -> Option<ScannedCommits> {
+    load_at(&state_path(), fingerprint)
+}
+
+/// Record `commits` on top of the existing ones, or in their place if those were
+/// recorded under a different config.
+#[allow(dead_code)] // Used by traur-hook binary
+pub fn record(commits: &ScannedCommits, fingerprint: &str) -> Result<(), String> {
+    record_at(&state_path(), commits, fingerprint)
+}
+
+fn read_state(path: &Path) -> Option<State> {
+    serde_json::from_str(&std::fs::read_to_string(path).ok()?).ok()
+}
+
+fn load_at(path: &Path, fingerprint: &str) -> Option<ScannedCommits> {
+    match read_state(path) {
+        Some(state) if state.config_fingerprint != fingerprint => None,
+        Some(state) => Some(state.commits),
+        None => Some(ScannedCommits::new()),
+    }
+}
+
+fn record_at(path: &Path, commits: &ScannedCommits, fingerprint: &str) -> Result<(), String> {
+    if commits.is_empty() {
+        return Ok(());
+    }
+    let mut state = read_state(path)
+        .filter(|state| state.config_fingerprint == fingerprint)
+        .unwrap_or_else(|| State { config_fingerprint: fingerprint.to_string(), ..Default::default() });
+    state.commits.extend(commits.iter().map(|(k, v)| (k.clone(), v.clone())));
+    let json = serde_json::to_string_pretty(&state).map_err(|e| format!("Failed to serialize hook state: {e}"))?;
+    std::fs::write(path, json).map_err(|e| format!("Failed to write {}: {e}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_on_top_of_existing_commits() {
+        let dir = std::env::temp_dir().join(format!("traur-hook-state-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("hook-scanned.json");
+
+        assert!(load_at(&path, "cfg").unwrap().is_empty());
+        record_at(&path, &ScannedCommits::from([("foo".into(), "aaa".into()), ("bar".into(), "bbb".into())]), "cfg").unwrap();
+        record_at(&path, &ScannedCommits::from([("foo".into(), "ccc".into())]), "cfg").unwrap();
+
+        let state = load_at(&path, "cfg").unwrap();
+        assert_eq!(state.get("foo").map(String::as_str), Some("ccc"));
+        assert_eq!(state.get("bar").map(String::as_str), Some("bbb"));
+
+        // A config change drops everything recorded under the old one
+        assert!(load_at(&path, "new").is_none());
+        record_at(&path, &ScannedCommits::from([("baz".into(), "ddd".into())]), "new").unwrap();
+        let state = load_at(&path, "new").unwrap();
+        assert_eq!(state.keys().collect::<Vec<_>>(), ["baz"]);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
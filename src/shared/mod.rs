@@ -1,12 +1,40 @@
+pub mod age_cohorts;
+#[cfg(feature = "native")]
+pub mod aur_account;
+#[cfg(feature = "native")]
 pub mod aur_comments;
 pub mod aur_git;
+#[cfg(feature = "native")]
 pub mod aur_rpc;
+#[cfg(feature = "native")]
+pub mod binary_repo;
+#[cfg(feature = "native")]
 pub mod bulk;
 pub mod cache;
+pub mod checkpoint;
 pub mod config;
+#[cfg(feature = "native")]
+pub mod geoip;
 pub mod github;
+pub mod helper_cache;
+pub mod hook_state;
+pub mod i18n;
+pub mod known_good;
 pub mod models;
+#[cfg(feature = "native")]
+pub mod notify;
 pub mod output;
+pub mod package_file;
 pub mod patterns;
+pub mod profile;
+#[cfg(feature = "native")]
+pub mod runtime;
+pub mod scan_limits;
+pub mod score_distribution;
 pub mod scoring;
 pub mod signal_registry;
+pub mod source_arrays;
+pub mod threat_intel;
+pub mod trusted_domains;
+#[cfg(feature = "native")]
+pub mod upstream_source;
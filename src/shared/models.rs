@@ -1,6 +1,7 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 /// All data a feature needs to run its analysis.
+#[derive(Clone)]
 pub struct PackageContext {
     pub name: String,
     pub metadata: Option<AurPackage>,
@@ -12,14 +13,51 @@ pub struct PackageContext {
     pub github_stars: Option<u32>,
     pub github_not_found: bool,
     pub aur_comments: Vec<String>,
+    pub artifact: Option<PackageArtifact>,
+    /// Upstream GitHub releases (most recent first), fetched for -bin packages only.
+    pub github_releases: Option<Vec<GitHubRelease>>,
+    /// Other files committed to the package repo (helper scripts, patches, assets),
+    /// keyed by repo-relative path. Files matching `scan.exclude` are left out.
+    pub repo_files: Vec<(String, Vec<u8>)>,
+    /// Source domains trusted via config (`trusted_domains`), on top of
+    /// `trusted_domains::BUILTIN_TRUSTED_DOMAINS`.
+    pub trusted_domains: Vec<String>,
+    /// Org-internal names from config (`[names] protected`), guarded against
+    /// impersonation and typosquats like the built-in targets in `data/names.toml`.
+    pub protected_names: Vec<String>,
+    /// Recent commits to the maintainer's other packages, keyed by package base. Read
+    /// only from clones already in the git cache, so it is partial and may be stale.
+    pub sibling_commits: Vec<(String, GitCommit)>,
+    /// The maintainer's AUR account page, fetched only when `[maintainer] aur_session` is set.
+    pub maintainer_account: Option<AurAccount>,
+    /// Build scripts at the top of the upstream GitHub tarball, as (path in the
+    /// archive, content). Fetched only in deep mode (`[scan] deep`, `scan --deep`).
+    pub upstream_build_files: Vec<(String, String)>,
+    /// Set when the cached clone of the package's AUR repo was replaced because the
+    /// remote history no longer contained the commit it was at.
+    pub history_rewrite: Option<HistoryRewrite>,
+}
+
+/// Per-package network data for features, fetched ahead of the git clone
+/// (in bulk scans, for all packages at once with bounded concurrency).
+#[derive(Debug, Clone, Default)]
+pub struct UpstreamData {
+    pub github_stars: Option<u32>,
+    pub github_not_found: bool,
+    pub aur_comments: Vec<String>,
+    pub github_releases: Option<Vec<GitHubRelease>>,
+    pub maintainer_account: Option<AurAccount>,
 }
 
 /// Package metadata from AUR RPC API v5.
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct AurPackage {
     pub name: String,
     pub package_base: Option<String>,
+    /// `[epoch:]pkgver-pkgrel` of the current AUR revision.
+    #[serde(default)]
+    pub version: String,
     #[serde(rename = "URL")]
     pub url: Option<String>,
     pub num_votes: u32,
@@ -31,6 +69,19 @@ pub struct AurPackage {
     #[allow(dead_code)]
     pub last_modified: u64,
     pub license: Option<Vec<String>>,
+    pub description: Option<String>,
+    /// Runtime dependencies, possibly with version constraints (`tor>=0.4`).
+    #[serde(default)]
+    pub depends: Vec<String>,
+    /// Optional dependencies as `name: reason`.
+    #[serde(default)]
+    pub opt_depends: Vec<String>,
+    /// Build-time dependencies, as `depends`.
+    #[serde(default)]
+    pub make_depends: Vec<String>,
+    /// Dependencies of `check()`, as `depends`.
+    #[serde(default)]
+    pub check_depends: Vec<String>,
 }
 
 /// Lightweight entry from the AUR metadata dump (packages-meta-v1.json.gz).
@@ -42,12 +93,69 @@ pub struct MetaDumpPackage {
     pub last_modified: u64,
     #[serde(rename = "PackageBase")]
     pub package_base: String,
+    #[serde(rename = "Version", default)]
+    pub version: String,
+    #[serde(rename = "Popularity", default)]
+    pub popularity: f64,
+    #[serde(rename = "NumVotes", default)]
+    pub num_votes: u32,
+    #[serde(rename = "FirstSubmitted", default)]
+    pub first_submitted: u64,
 }
 
 /// A single git commit from the AUR package repo.
 #[derive(Debug, Clone)]
 pub struct GitCommit {
     pub author: String,
+    pub email: String,
     pub timestamp: u64,
+    /// Subject line of the commit message.
+    pub message: String,
     pub diff: Option<String>,
 }
+
+/// Contents of a built package archive (.pkg.tar.zst) for artifact analysis.
+#[derive(Debug, Clone, Default)]
+pub struct PackageArtifact {
+    pub entries: Vec<ArtifactEntry>,
+    /// Text content of inspected files (systemd units, autostart entries, hooks), keyed by path.
+    pub file_contents: Vec<(String, String)>,
+}
+
+/// A single file entry from a package archive listing.
+#[derive(Debug, Clone)]
+pub struct ArtifactEntry {
+    /// Path inside the package, without leading slash (e.g. `usr/bin/foo`).
+    pub path: String,
+    /// ls-style mode string (e.g. `-rwsr-xr-x`).
+    pub mode: String,
+}
+
+/// A non-fast-forward update of a cached AUR clone: commits it had are gone from the
+/// remote (force push, deleted history).
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct HistoryRewrite {
+    /// The commit the stale clone was at.
+    pub old_head: String,
+    /// The remote head it was replaced with.
+    pub new_head: String,
+    /// Where the stale clone was kept for inspection.
+    pub kept_at: String,
+    /// Unix seconds.
+    pub detected_at: u64,
+}
+
+/// What the AUR account page of a maintainer shows beyond the RPC API.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AurAccount {
+    /// Registration date (Unix seconds, midnight UTC).
+    pub registered_at: Option<u64>,
+    pub suspended: bool,
+}
+
+/// A release of the upstream GitHub repo with its downloadable asset names.
+#[derive(Debug, Clone)]
+pub struct GitHubRelease {
+    pub tag_name: String,
+    pub assets: Vec<String>,
+}
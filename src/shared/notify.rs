@@ -0,0 +1,230 @@
+use crate::shared::config::NotifyConfig;
+use crate::shared::runtime;
+use crate::shared::scoring::{ScanResult, Tier, tier_from_str};
+use std::process::Command;
+
+/// Default message body. Placeholders: {event}, {package}, {tier}, {score}, {gate}, {signals}.
+pub const DEFAULT_TEMPLATE: &str = "traur: {package} is {tier} (trust: {score}/100) — {event}";
+
+/// Send notifications for every result at or above the configured tier. `event` says
+/// what triggered them ("transaction blocked", "found by scheduled scan").
+/// Delivery failures are reported on stderr but never abort the caller.
+pub fn notify_results(config: &NotifyConfig, event: &str, results: &[ScanResult]) {
+    if !config.is_enabled() {
+        return;
+    }
+    // Validated when the config is loaded
+    let min_tier = tier_from_str(&config.min_tier).unwrap_or(Tier::Malicious);
+
+    for result in results.iter().filter(|r| r.tier >= min_tier) {
+        let body = render_template(
+            config.template.as_deref().unwrap_or(DEFAULT_TEMPLATE),
+            event,
+            result,
+        );
+        let title = format!("traur: {} {}", result.tier, result.package);
+
+        if config.desktop
+            && let Err(e) = send_desktop(&title, &body, result.tier)
+        {
+            eprintln!("traur: desktop notification failed: {e}");
+        }
+        if let Some(ref url) = config.webhook_url
+            && let Err(e) = send_webhook(url, event, result, &body)
+        {
+            eprintln!("traur: webhook notification failed: {e}");
+        }
+        if let Some(ref url) = config.ntfy_url
+            && let Err(e) = send_ntfy(url, &title, &body, result.tier)
+        {
+            eprintln!("traur: ntfy notification failed: {e}");
+        }
+    }
+}
+
+/// Substitute template placeholders with values from a scan result.
+pub fn render_template(template: &str, event: &str, result: &ScanResult) -> String {
+    let signals = result
+        .signals
+        .iter()
+        .map(|s| s.id.as_str())
+        .collect::<Vec<_>>()
+        .join(", ");
+    template
+        .replace("{event}", event)
+        .replace("{package}", &result.package)
+        .replace("{tier}", &result.tier.to_string())
+        .replace("{score}", &result.score.to_string())
+        .replace(
+            "{gate}",
+            result.override_gate_fired.as_deref().unwrap_or("none"),
+        )
+        .replace("{signals}", &signals)
+}
+
+/// Show a desktop notification via notify-send. When running as root under
+/// sudo/doas (the ALPM hook), deliver to the invoking user's session bus.
+fn send_desktop(title: &str, body: &str, tier: Tier) -> Result<(), String> {
+    let urgency = if tier >= Tier::Suspicious {
+        "critical"
+    } else {
+        "normal"
+    };
+    let args = ["--app-name=traur", "-u", urgency, title, body];
+
+    let mut cmd = match invoking_user() {
+        Some((user, uid)) => {
+            let mut c = Command::new("runuser");
+            c.args(["-u", &user, "--", "notify-send"]).args(args).env(
+                "DBUS_SESSION_BUS_ADDRESS",
+                format!("unix:path=/run/user/{uid}/bus"),
+            );
+            c
+        }
+        None => {
+            let mut c = Command::new("notify-send");
+            c.args(args);
+            c
+        }
+    };
+
+    let status = cmd
+        .status()
+        .map_err(|e| format!("failed to run notify-send: {e}"))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("notify-send exited with {status}"))
+    }
+}
+
+/// POST a JSON payload to a generic webhook (Slack/Mattermost/Discord-compatible `text` field).
+fn send_webhook(url: &str, event: &str, result: &ScanResult, body: &str) -> Result<(), String> {
+    let payload = serde_json::json!({
+        "text": body,
+        "content": body,
+        "event": event,
+        "package": result.package,
+        "tier": result.tier,
+        "score": result.score,
+        "override_gate_fired": result.override_gate_fired,
+    });
+    let resp = runtime::blocking_client()
+        .post(url)
+        .timeout(runtime::request_timeout())
+        .json(&payload)
+        .send()
+        .map_err(|e| format!("HTTP request failed: {e}"))?;
+    if resp.status().is_success() {
+        Ok(())
+    } else {
+        Err(format!("HTTP {}", resp.status()))
+    }
+}
+
+/// Publish to an ntfy.sh (or self-hosted ntfy) topic URL.
+fn send_ntfy(url: &str, title: &str, body: &str, tier: Tier) -> Result<(), String> {
+    let priority = if tier >= Tier::Malicious {
+        "urgent"
+    } else {
+        "high"
+    };
+    let resp = runtime::blocking_client()
+        .post(url)
+        .timeout(runtime::request_timeout())
+        .header("Title", title)
+        .header("Priority", priority)
+        .header("Tags", "warning")
+        .body(body.to_string())
+        .send()
+        .map_err(|e| format!("HTTP request failed: {e}"))?;
+    if resp.status().is_success() {
+        Ok(())
+    } else {
+        Err(format!("HTTP {}", resp.status()))
+    }
+}
+
+/// Resolve the non-root user that invoked sudo/doas, with their uid.
+fn invoking_user() -> Option<(String, String)> {
+    let user = std::env::var("SUDO_USER")
+        .or_else(|_| std::env::var("DOAS_USER"))
+        .ok()?;
+    if user == "root" {
+        return None;
+    }
+    let output = Command::new("id").args(["-u", &user]).output().ok()?;
+    let uid = String::from_utf8(output.stdout).ok()?.trim().to_string();
+    if uid.is_empty() {
+        return None;
+    }
+    Some((user, uid))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared::scoring::{SCHEMA_VERSION, Signal, SignalCategory};
+
+    fn result(tier: Tier) -> ScanResult {
+        ScanResult {
+            schema_version: SCHEMA_VERSION,
+            package: "evil-pkg".to_string(),
+            score: 5,
+            tier,
+            signals: vec![Signal {
+                id: "P-CURL-PIPE".to_string(),
+                category: SignalCategory::Pkgbuild,
+                points: 90,
+                description: String::new(),
+                is_override_gate: true,
+                matched_line: None,
+                location: None,
+                emitted_by: String::new(),
+            }],
+            override_gate_fired: Some("P-CURL-PIPE".to_string()),
+            category_scores: Default::default(),
+            skipped_features: Vec::new(),
+            pkgbuild_diff: None,
+            note: None,
+            demoted_gates: Vec::new(),
+            analysis_completeness: Default::default(),
+        }
+    }
+
+    #[test]
+    fn renders_default_template() {
+        let body = render_template(
+            DEFAULT_TEMPLATE,
+            "transaction blocked",
+            &result(Tier::Malicious),
+        );
+        assert_eq!(
+            body,
+            "traur: evil-pkg is MALICIOUS (trust: 5/100) — transaction blocked"
+        );
+    }
+
+    #[test]
+    fn renders_all_placeholders() {
+        let body = render_template(
+            "{package}|{tier}|{score}|{gate}|{signals}|{event}",
+            "found by scheduled scan",
+            &result(Tier::Malicious),
+        );
+        assert_eq!(
+            body,
+            "evil-pkg|MALICIOUS|5|P-CURL-PIPE|P-CURL-PIPE|found by scheduled scan"
+        );
+    }
+
+    #[test]
+    fn disabled_config_sends_nothing() {
+        // No channels configured: must return without touching the network or spawning processes.
+        notify_results(
+            &NotifyConfig::default(),
+            "transaction blocked",
+            &[result(Tier::Malicious)],
+        );
+    }
+}
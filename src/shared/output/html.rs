@@ -0,0 +1,95 @@
+use super::RenderOptions;
+use crate::shared::scoring::{ScanResult, Tier};
+use std::io::Write;
+
+/// Write a self-contained HTML report (inline CSS, no external assets).
+pub fn write(w: &mut dyn Write, results: &[ScanResult], opts: &RenderOptions) {
+    let _ = writeln!(
+        w,
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>traur report</title>\n<style>\n\
+body{{font-family:sans-serif;margin:2em;}}\n\
+table{{border-collapse:collapse;margin-bottom:2em;}}\n\
+td,th{{border:1px solid #ccc;padding:4px 8px;text-align:left;}}\n\
+.trusted{{color:#2a2;}} .ok{{color:#aa2;}} .sketchy{{color:#f90;}} .suspicious{{color:#d22;}} .malicious{{color:#d22;font-weight:bold;}}\n\
+code{{background:#f4f4f4;}}\n\
+</style>\n</head>\n<body>\n<h1>traur report</h1>"
+    );
+
+    for result in results {
+        let _ = writeln!(
+            w,
+            "<h2>{} <span class=\"{}\">{}</span> (trust: {}/100)</h2>",
+            escape(&result.package),
+            tier_class(result.tier),
+            result.tier,
+            result.score
+        );
+        if !result.analysis_completeness.is_complete() {
+            let _ = writeln!(
+                w,
+                "<p><strong>Incomplete analysis, unavailable:</strong> {}</p>",
+                escape(&result.analysis_completeness.unavailable.join(", "))
+            );
+        }
+        if let Some(ref gate) = result.override_gate_fired {
+            let _ = writeln!(w, "<p><strong>Override gate fired:</strong> {}</p>", escape(gate));
+        }
+        if !result.demoted_gates.is_empty() {
+            let _ = writeln!(
+                w,
+                "<p><strong>Override gates demoted by config:</strong> {}</p>",
+                escape(&result.demoted_gates.join(", "))
+            );
+        }
+        if let Some(ref note) = result.note {
+            let _ = writeln!(w, "<p><em>{}</em></p>", escape(note));
+        }
+        if !result.skipped_features.is_empty() {
+            let _ = writeln!(
+                w,
+                "<p><strong>Not analyzed (timed out):</strong> {}</p>",
+                escape(&result.skipped_features.join(", "))
+            );
+        }
+        if result.signals.is_empty() {
+            let _ = writeln!(w, "<p>No negative signals found.</p>");
+            continue;
+        }
+        let _ = writeln!(w, "<table>\n<tr><th>Signal</th><th>Points</th><th>Description</th></tr>");
+        for signal in &result.signals {
+            let matched = match (&signal.matched_line, opts.verbose) {
+                (Some(line), true) => format!("<br><code>{}</code>", escape(line)),
+                _ => String::new(),
+            };
+            let _ = writeln!(
+                w,
+                "<tr><td>{}</td><td>{}</td><td>{}{matched}</td></tr>",
+                escape(&signal.id),
+                signal.points,
+                escape(&signal.description)
+            );
+        }
+        let _ = writeln!(w, "</table>");
+    }
+
+    let _ = writeln!(w, "</body>\n</html>");
+}
+
+fn tier_class(tier: Tier) -> &'static str {
+    match tier {
+        Tier::Trusted => "trusted",
+        Tier::Ok => "ok",
+        Tier::Sketchy => "sketchy",
+        Tier::Suspicious => "suspicious",
+        Tier::Malicious => "malicious",
+    }
+}
+
+/// Escape text for HTML element content and attribute values.
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
@@ -0,0 +1,47 @@
+use super::RenderOptions;
+use crate::shared::scoring::{self, ScanResult, ScoreExplanation};
+use serde::Serialize;
+use std::io::Write;
+
+/// A result with its `score_explanation` (`--explain-score`).
+#[derive(Serialize)]
+pub(super) struct Explained<'a> {
+    #[serde(flatten)]
+    result: &'a ScanResult,
+    score_explanation: ScoreExplanation,
+}
+
+impl<'a> Explained<'a> {
+    pub(super) fn new(result: &'a ScanResult) -> Self {
+        Self { result, score_explanation: scoring::explain_score(result) }
+    }
+}
+
+/// Write a single result as a JSON object, pretty-printed unless `compact`.
+pub fn write_one(w: &mut dyn Write, result: &ScanResult, opts: &RenderOptions) {
+    if opts.explain_score {
+        write_value(w, &Explained::new(result), opts.compact);
+    } else {
+        write_value(w, result, opts.compact);
+    }
+}
+
+/// Write results as a JSON array, pretty-printed unless `compact`.
+pub fn write(w: &mut dyn Write, results: &[ScanResult], opts: &RenderOptions) {
+    if opts.explain_score {
+        let explained: Vec<Explained> = results.iter().map(Explained::new).collect();
+        write_value(w, &explained, opts.compact);
+    } else {
+        write_value(w, results, opts.compact);
+    }
+}
+
+fn write_value(w: &mut dyn Write, value: &(impl Serialize + ?Sized), compact: bool) {
+    let json = if compact {
+        serde_json::to_string(value)
+    } else {
+        serde_json::to_string_pretty(value)
+    }
+    .expect("Failed to serialize");
+    let _ = writeln!(w, "{json}");
+}
@@ -0,0 +1,190 @@
+//! Scan result formatters. Each format lives in its own file; `render` and
+//! `render_many` are the only entry points callers should use.
+
+mod html;
+mod json;
+mod ndjson;
+mod sarif;
+mod summary;
+mod text;
+
+use crate::shared::i18n::Catalog;
+use crate::shared::scoring::ScanResult;
+use std::io::Write;
+
+/// Output format selectable with `--format`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum OutputFormat {
+    /// Colored human-readable report
+    #[default]
+    Text,
+    /// JSON (object for one package, array for several), pretty-printed unless `--compact`
+    Json,
+    /// One compact JSON object per line
+    Ndjson,
+    /// SARIF 2.1.0 log for code-scanning dashboards
+    Sarif,
+    /// Self-contained HTML report
+    Html,
+    /// One line per package
+    Summary,
+}
+
+impl OutputFormat {
+    /// Human-oriented formats go to stderr (stdout stays clean for piping);
+    /// machine-readable ones go to stdout.
+    pub fn is_human(self) -> bool {
+        matches!(self, OutputFormat::Text | OutputFormat::Summary)
+    }
+}
+
+/// Options shared by all formatters.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RenderOptions {
+    /// Include the line that triggered each signal.
+    pub verbose: bool,
+    /// Print `json` output on a single line.
+    pub compact: bool,
+    /// Language of `text` reports; None for English.
+    pub catalog: Option<&'static Catalog>,
+    /// Show how the score was computed (`text`), or add it as `score_explanation`
+    /// (`json`, `ndjson`).
+    pub explain_score: bool,
+}
+
+/// Render a single scan result.
+pub fn render(w: &mut dyn Write, result: &ScanResult, format: OutputFormat, opts: &RenderOptions) {
+    match format {
+        OutputFormat::Json => json::write_one(w, result, opts),
+        _ => render_many(w, std::slice::from_ref(result), format, opts),
+    }
+}
+
+/// Render several scan results as one document (JSON array, single SARIF run, one HTML page, ...).
+pub fn render_many(w: &mut dyn Write, results: &[ScanResult], format: OutputFormat, opts: &RenderOptions) {
+    match format {
+        OutputFormat::Text => text::write(w, results, opts),
+        OutputFormat::Json => json::write(w, results, opts),
+        OutputFormat::Ndjson => ndjson::write(w, results, opts.explain_score),
+        OutputFormat::Sarif => sarif::write(w, results),
+        OutputFormat::Html => html::write(w, results, opts),
+        OutputFormat::Summary => summary::write(w, results),
+    }
+}
+
+/// Render a single result to stderr or stdout depending on the format.
+pub fn print(result: &ScanResult, format: OutputFormat, opts: &RenderOptions) {
+    if format.is_human() {
+        render(&mut std::io::stderr(), result, format, opts);
+    } else {
+        render(&mut std::io::stdout(), result, format, opts);
+    }
+}
+
+/// Render several results to stderr or stdout depending on the format.
+pub fn print_many(results: &[ScanResult], format: OutputFormat, opts: &RenderOptions) {
+    if format.is_human() {
+        render_many(&mut std::io::stderr(), results, format, opts);
+    } else {
+        render_many(&mut std::io::stdout(), results, format, opts);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared::scoring::{Signal, SignalCategory, Tier, SCHEMA_VERSION};
+
+    fn result() -> ScanResult {
+        ScanResult {
+            schema_version: SCHEMA_VERSION,
+            package: "evil<pkg>".to_string(),
+            score: 5,
+            tier: Tier::Malicious,
+            signals: vec![Signal {
+                id: "P-CURL-PIPE".to_string(),
+                category: SignalCategory::Pkgbuild,
+                points: 90,
+                description: "curl piped to shell".to_string(),
+                is_override_gate: true,
+                matched_line: Some("curl x | sh".to_string()),
+                location: None,
+                emitted_by: String::new(),
+            }],
+            override_gate_fired: Some("P-CURL-PIPE".to_string()),
+            category_scores: Default::default(),
+            skipped_features: Vec::new(),
+            pkgbuild_diff: None,
+            note: None,
+            demoted_gates: Vec::new(),
+            analysis_completeness: Default::default(),
+        }
+    }
+
+    fn render_to_string(results: &[ScanResult], format: OutputFormat) -> String {
+        colored::control::set_override(false);
+        let mut buf = Vec::new();
+        render_many(&mut buf, results, format, &RenderOptions { verbose: true, ..Default::default() });
+        String::from_utf8(buf).unwrap()
+    }
+
+    #[test]
+    fn json_single_is_object_many_is_array() {
+        let mut buf = Vec::new();
+        render(&mut buf, &result(), OutputFormat::Json, &RenderOptions::default());
+        let one: serde_json::Value = serde_json::from_slice(&buf).unwrap();
+        assert!(one.is_object());
+        let many: serde_json::Value =
+            serde_json::from_str(&render_to_string(&[result(), result()], OutputFormat::Json)).unwrap();
+        assert_eq!(many.as_array().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn json_has_schema_version_and_compact_is_one_line() {
+        let mut buf = Vec::new();
+        render(&mut buf, &result(), OutputFormat::Json, &RenderOptions { compact: true, ..Default::default() });
+        let out = String::from_utf8(buf).unwrap();
+        assert_eq!(out.lines().count(), 1);
+        assert!(out.starts_with(&format!("{{\"schema_version\":{SCHEMA_VERSION},\"package\":")));
+
+        let mut buf = Vec::new();
+        render_many(&mut buf, &[result(), result()], OutputFormat::Json, &RenderOptions { compact: true, ..Default::default() });
+        let many: serde_json::Value = serde_json::from_slice(&buf).unwrap();
+        assert_eq!(many[1]["schema_version"], SCHEMA_VERSION);
+        assert_eq!(String::from_utf8(buf).unwrap().lines().count(), 1);
+    }
+
+    #[test]
+    fn ndjson_one_line_per_result() {
+        let out = render_to_string(&[result(), result()], OutputFormat::Ndjson);
+        assert_eq!(out.lines().count(), 2);
+        for line in out.lines() {
+            serde_json::from_str::<serde_json::Value>(line).unwrap();
+        }
+    }
+
+    #[test]
+    fn sarif_has_rule_and_result() {
+        let out = render_to_string(&[result()], OutputFormat::Sarif);
+        let log: serde_json::Value = serde_json::from_str(&out).unwrap();
+        assert_eq!(log["version"], "2.1.0");
+        let run = &log["runs"][0];
+        assert_eq!(run["tool"]["driver"]["rules"][0]["id"], "P-CURL-PIPE");
+        assert_eq!(run["results"][0]["ruleId"], "P-CURL-PIPE");
+        assert_eq!(run["results"][0]["level"], "error");
+    }
+
+    #[test]
+    fn html_escapes_content() {
+        let out = render_to_string(&[result()], OutputFormat::Html);
+        assert!(out.contains("evil&lt;pkg&gt;"));
+        assert!(!out.contains("evil<pkg>"));
+        assert!(out.contains("<code>curl x | sh</code>"));
+    }
+
+    #[test]
+    fn summary_is_one_line() {
+        let out = render_to_string(&[result()], OutputFormat::Summary);
+        assert_eq!(out, "MALICIOUS    5/100  evil<pkg>  (1 signals, gate P-CURL-PIPE)\n");
+    }
+}
@@ -0,0 +1,17 @@
+use super::json::Explained;
+use crate::shared::scoring::ScanResult;
+use std::io::Write;
+
+/// Write one compact JSON object per line, for streaming into log pipelines. With
+/// `explain_score`, each carries its `score_explanation`.
+pub fn write(w: &mut dyn Write, results: &[ScanResult], explain_score: bool) {
+    for result in results {
+        let json = if explain_score {
+            serde_json::to_string(&Explained::new(result))
+        } else {
+            serde_json::to_string(result)
+        }
+        .expect("Failed to serialize");
+        let _ = writeln!(w, "{json}");
+    }
+}
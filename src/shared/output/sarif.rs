@@ -0,0 +1,78 @@
+use crate::shared::scoring::{ScanResult, Signal};
+use std::collections::BTreeMap;
+use std::io::Write;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// Write results as a SARIF 2.1.0 log (one run, one rule per distinct signal ID),
+/// for code-scanning dashboards such as GitHub's.
+pub fn write(w: &mut dyn Write, results: &[ScanResult]) {
+    let mut rules: BTreeMap<&str, &Signal> = BTreeMap::new();
+    for signal in results.iter().flat_map(|r| &r.signals) {
+        rules.entry(signal.id.as_str()).or_insert(signal);
+    }
+
+    let rules_json: Vec<serde_json::Value> = rules
+        .values()
+        .map(|s| {
+            serde_json::json!({
+                "id": s.id,
+                "shortDescription": { "text": s.description },
+                "properties": { "category": format!("{:?}", s.category), "points": s.points },
+            })
+        })
+        .collect();
+
+    let results_json: Vec<serde_json::Value> = results
+        .iter()
+        .flat_map(|result| {
+            result.signals.iter().map(move |s| {
+                let mut entry = serde_json::json!({
+                    "ruleId": s.id,
+                    "level": level(s),
+                    "message": { "text": format!("{}: {}", result.package, s.description) },
+                    "locations": [{
+                        "logicalLocations": [{ "name": result.package, "kind": "package" }],
+                    }],
+                    "properties": {
+                        "package": result.package,
+                        "tier": result.tier,
+                        "score": result.score,
+                    },
+                });
+                if let Some(ref line) = s.matched_line {
+                    entry["properties"]["matchedLine"] = serde_json::json!(line);
+                }
+                entry
+            })
+        })
+        .collect();
+
+    let log = serde_json::json!({
+        "$schema": SARIF_SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "traur",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": rules_json,
+                }
+            },
+            "results": results_json,
+        }],
+    });
+    let json = serde_json::to_string_pretty(&log).expect("Failed to serialize");
+    let _ = writeln!(w, "{json}");
+}
+
+/// Map signal severity onto SARIF levels, using the same thresholds as the text output.
+fn level(signal: &Signal) -> &'static str {
+    if signal.is_override_gate || signal.points >= 60 {
+        "error"
+    } else if signal.points >= 30 {
+        "warning"
+    } else {
+        "note"
+    }
+}
@@ -0,0 +1,29 @@
+use crate::shared::scoring::ScanResult;
+use std::io::Write;
+
+/// Write one line per package: tier, trust score, name, signal count.
+pub fn write(w: &mut dyn Write, results: &[ScanResult]) {
+    for result in results {
+        let gate = result
+            .override_gate_fired
+            .as_deref()
+            .map(|g| format!(", gate {g}"))
+            .unwrap_or_default();
+        let skipped = match result.skipped_features.len() {
+            0 => String::new(),
+            n => format!(", {n} features timed out"),
+        };
+        let incomplete = match result.analysis_completeness.unavailable.as_slice() {
+            [] => String::new(),
+            missing => format!(", no {}", missing.join(", no ")),
+        };
+        let _ = writeln!(
+            w,
+            "{:<10} {:>3}/100  {}  ({} signals{gate}{skipped}{incomplete})",
+            result.tier.to_string(),
+            result.score,
+            result.package,
+            result.signals.len(),
+        );
+    }
+}
@@ -0,0 +1,233 @@
+use super::RenderOptions;
+use crate::shared::i18n::{self, Catalog};
+use crate::shared::scoring::{self, ScanResult, ScoreExplanation, Signal, Tier};
+use colored::Colorize;
+use std::io::Write;
+
+/// Write results as colored terminal text, separated by blank lines.
+pub fn write(w: &mut dyn Write, results: &[ScanResult], opts: &RenderOptions) {
+    for (i, result) in results.iter().enumerate() {
+        if i > 0 {
+            let _ = writeln!(w);
+        }
+        write_text(w, result, opts.verbose, opts.catalog);
+        if opts.explain_score {
+            write_score_math(w, result, &scoring::explain_score(result), opts.catalog);
+        }
+    }
+}
+
+/// Write one scan result as colored terminal text.
+fn write_text(w: &mut dyn Write, result: &ScanResult, verbose: bool, catalog: Option<&Catalog>) {
+    let tr = |msgid| i18n::tr(catalog, msgid);
+    let tier_colored = match result.tier {
+        Tier::Trusted => result.tier.to_string().green(),
+        Tier::Ok => result.tier.to_string().yellow(),
+        Tier::Sketchy => result.tier.to_string().truecolor(255, 165, 0), // orange
+        Tier::Suspicious => result.tier.to_string().red(),
+        Tier::Malicious => result.tier.to_string().red().bold(),
+    };
+
+    let _ = writeln!(
+        w,
+        "{} {} ({}: {}/100)",
+        "traur:".bold(),
+        result.package.bold(),
+        tr("trust"),
+        result.score
+    );
+    let _ = writeln!(w, "  {}: {tier_colored}", tr("Trust"));
+
+    if !result.analysis_completeness.is_complete() {
+        let _ = writeln!(
+            w,
+            "  {} {}: {}",
+            "!!".yellow().bold(),
+            tr("Incomplete analysis, unavailable"),
+            result.analysis_completeness.unavailable.join(", ")
+        );
+    }
+
+    if let Some(ref gate) = result.override_gate_fired {
+        let _ = writeln!(w, "  {} {}: {gate}", "!!".red().bold(), tr("Override gate fired"));
+    }
+
+    if !result.demoted_gates.is_empty() {
+        let _ = writeln!(
+            w,
+            "  {} {}: {}",
+            " i".dimmed(),
+            tr("Override gates demoted by config"),
+            result.demoted_gates.join(", ")
+        );
+    }
+
+    if let Some(ref note) = result.note {
+        let _ = writeln!(w, "  {} {note}", " i".dimmed());
+    }
+
+    if !result.skipped_features.is_empty() {
+        let _ = writeln!(
+            w,
+            "  {} {}: {}",
+            " !".yellow(),
+            tr("Not analyzed (timed out)"),
+            result.skipped_features.join(", ")
+        );
+    }
+
+    if !result.category_scores.is_empty() {
+        let _ = writeln!(w, "  {}:", tr("Breakdown"));
+        for (category, score) in &result.category_scores {
+            let _ = writeln!(
+                w,
+                "    {:<13} {} {:>4.1}/{:<2} ({} {})",
+                format!("{category:?}"),
+                breakdown_bar(score.raw),
+                score.weighted,
+                (category.weight() * 100.0).round(),
+                tr("raw"),
+                score.raw
+            );
+        }
+    }
+
+    if result.signals.is_empty() {
+        let _ = writeln!(w, "  {}", tr("No negative signals found."));
+    } else {
+        let _ = writeln!(w, "  {}:", tr("Negative signals"));
+        for signal in &result.signals {
+            let _ = writeln!(
+                w,
+                "    {} {}: {}",
+                severity_marker(signal),
+                signal.id,
+                catalog.map_or(signal.description.as_str().into(), |c| c.signal_description(signal))
+            );
+            if verbose {
+                write_matched(w, signal);
+            }
+        }
+    }
+
+    if verbose && let Some(ref diff) = result.pkgbuild_diff {
+        write_diff(w, diff, &result.signals, tr("PKGBUILD changes since baseline"));
+    }
+}
+
+/// Lines of context shown around a matched line in verbose output.
+const CONTEXT_LINES: usize = 2;
+
+/// The matched line with the lines around it when its location is known, otherwise
+/// just the (trimmed) matched line.
+fn write_matched(w: &mut dyn Write, signal: &Signal) {
+    let Some(ref line) = signal.matched_line else {
+        return;
+    };
+    let Some(ref location) = signal.location else {
+        let _ = writeln!(w, "         {} {}", ">".dimmed(), line.dimmed());
+        return;
+    };
+    for (number, text) in location.context(CONTEXT_LINES) {
+        if number == location.line + 1 {
+            let _ = writeln!(w, "       {} {:>4} | {}", ">".yellow().bold(), number, text.yellow());
+        } else {
+            let _ = writeln!(w, "         {}", format!("{number:>4} | {text}").dimmed());
+        }
+    }
+}
+
+/// Two-column marker for a signal: red for override gates and 60+ points, yellow for 30+.
+fn severity_marker(signal: &Signal) -> String {
+    if signal.is_override_gate {
+        "!!".red().bold().to_string()
+    } else if signal.points >= 60 {
+        "!!".red().to_string()
+    } else if signal.points >= 30 {
+        " !".yellow().to_string()
+    } else {
+        "  ".to_string()
+    }
+}
+
+/// PKGBUILD diff hunks behind the `T-DIFF-*` signals. Added lines a signal matched are
+/// highlighted and carry that signal's severity marker.
+fn write_diff(w: &mut dyn Write, diff: &str, signals: &[Signal], heading: &str) {
+    let matched: Vec<(&str, &Signal)> = signals
+        .iter()
+        .filter(|s| s.id.starts_with("T-DIFF-"))
+        .filter_map(|s| s.matched_line.as_deref().map(|line| (line.trim(), s)))
+        .collect();
+
+    let _ = writeln!(w, "  {heading}:");
+    for line in diff.lines() {
+        if line.starts_with("@@") {
+            let _ = writeln!(w, "       {}", line.cyan());
+        } else if let Some(added) = line.strip_prefix('+') {
+            match matched.iter().find(|(m, _)| !m.is_empty() && added.trim() == *m) {
+                Some((_, signal)) => {
+                    let _ = writeln!(w, "    {} {}", severity_marker(signal), line.green().bold().underline());
+                }
+                None => {
+                    let _ = writeln!(w, "       {}", line.green());
+                }
+            }
+        } else if line.starts_with('-') {
+            let _ = writeln!(w, "       {}", line.red());
+        } else {
+            let _ = writeln!(w, "       {}", line.dimmed());
+        }
+    }
+}
+
+/// The arithmetic from category totals to the final tier (`--explain-score`).
+fn write_score_math(w: &mut dyn Write, result: &ScanResult, math: &ScoreExplanation, catalog: Option<&Catalog>) {
+    let tr = |msgid| i18n::tr(catalog, msgid);
+    let _ = writeln!(w, "  {}:", tr("Score math"));
+    for (category, c) in &result.category_scores {
+        let _ = writeln!(
+            w,
+            "    {:<13} {} {:>3} -> {} {:>3} x {:.2} = {:>6.2}",
+            format!("{category:?}"),
+            tr("raw"),
+            c.raw,
+            tr("capped"),
+            c.capped,
+            c.weight,
+            c.weighted
+        );
+    }
+    let _ = writeln!(
+        w,
+        "    {}: {:.2}, {} {}",
+        tr("Weighted risk"),
+        math.weighted_sum,
+        tr("rounded to"),
+        math.weighted_risk
+    );
+    if let Some(ref gate) = math.override_gate {
+        let _ = writeln!(
+            w,
+            "    {} {} ({} pts): {} = max({}, {}) = {} -> {}",
+            tr("Override gate"),
+            gate.id,
+            gate.points,
+            tr("risk"),
+            gate.points,
+            math.weighted_risk,
+            math.risk,
+            math.tier
+        );
+    }
+    let _ = writeln!(w, "    {}: 100 - {} = {} -> {}", tr("Trust"), math.risk, math.trust, math.tier);
+    for adjustment in &math.adjustments {
+        let _ = writeln!(w, "    {adjustment}");
+    }
+    let _ = writeln!(w, "    {}: {}/100, {}", tr("Final"), math.final_score, math.final_tier);
+}
+
+/// Ten-cell bar showing how close a category is to its cap of 100 points.
+fn breakdown_bar(raw: u32) -> String {
+    let filled = (raw.min(100) as usize + 5) / 10;
+    format!("[{}{}]", "#".repeat(filled), "-".repeat(10 - filled))
+}
@@ -0,0 +1,193 @@
+use crate::shared::models::{ArtifactEntry, PackageArtifact};
+use std::path::Path;
+use std::process::Command;
+
+/// Path prefixes whose text content is extracted for artifact analysis.
+const INSPECTED_PREFIXES: &[&str] = &[
+    "usr/lib/systemd/",
+    "etc/systemd/",
+    "etc/xdg/autostart/",
+    "usr/share/libalpm/hooks/",
+    "etc/pacman.d/hooks/",
+    "etc/profile.d/",
+    "etc/cron.d/",
+    "etc/sudoers.d/",
+    "etc/ld.so.preload",
+];
+
+/// Files larger than this are listed but not extracted.
+const MAX_INSPECTED_SIZE: u64 = 64 * 1024;
+
+/// A built package archive read via bsdtar (libarchive, always present alongside pacman).
+pub struct PackageFile {
+    pub name: String,
+    pub install_script: Option<String>,
+    pub artifact: PackageArtifact,
+}
+
+/// List and partially extract a .pkg.tar.{zst,xz,gz} archive without unpacking it to disk.
+pub fn read_package_file(path: &Path) -> Result<PackageFile, String> {
+    let path_str = path.to_str().ok_or("package path is not valid UTF-8")?;
+
+    let listing = Command::new("bsdtar")
+        .args(["-tvf", path_str])
+        .output()
+        .map_err(|e| format!("failed to run bsdtar: {e}"))?;
+    if !listing.status.success() {
+        let stderr = String::from_utf8_lossy(&listing.stderr);
+        return Err(format!("bsdtar failed to list {path_str}: {stderr}"));
+    }
+
+    let listed = parse_listing(&String::from_utf8_lossy(&listing.stdout));
+
+    let pkginfo = extract_file(path_str, ".PKGINFO");
+    let name = pkginfo
+        .as_deref()
+        .and_then(pkginfo_name)
+        .unwrap_or_else(|| name_from_filename(path));
+
+    let install_script = if listed.iter().any(|(e, _)| e.path == ".INSTALL") {
+        extract_file(path_str, ".INSTALL")
+    } else {
+        None
+    };
+
+    let mut file_contents = Vec::new();
+    for (entry, size) in &listed {
+        let is_regular = entry.mode.starts_with('-');
+        let inspected = INSPECTED_PREFIXES.iter().any(|p| entry.path.starts_with(p));
+        if is_regular
+            && inspected
+            && *size <= MAX_INSPECTED_SIZE
+            && let Some(content) = extract_file(path_str, &entry.path)
+        {
+            file_contents.push((entry.path.clone(), content));
+        }
+    }
+
+    Ok(PackageFile {
+        name,
+        install_script,
+        artifact: PackageArtifact {
+            entries: listed.into_iter().map(|(e, _)| e).collect(),
+            file_contents,
+        },
+    })
+}
+
+/// Extract a single member's content as text.
+pub fn extract_file(archive: &str, member: &str) -> Option<String> {
+    let output = Command::new("bsdtar")
+        .args(["-xOf", archive, member])
+        .output()
+        .ok()?;
+    if output.status.success() {
+        Some(String::from_utf8_lossy(&output.stdout).to_string())
+    } else {
+        None
+    }
+}
+
+/// Every member's content, concatenated (e.g. the `desc` files of a repo database).
+pub fn extract_all(archive: &Path) -> Option<String> {
+    let output = Command::new("bsdtar").arg("-xOf").arg(archive).output().ok()?;
+    output.status.success().then(|| String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Parse `bsdtar -tv` output into entries with their sizes.
+/// Line format: `-rwsr-xr-x  0 root root 12345 Jan  1 00:00 usr/bin/foo`
+pub(crate) fn parse_listing(stdout: &str) -> Vec<(ArtifactEntry, u64)> {
+    stdout
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() < 9 {
+                return None;
+            }
+            let mut mode = fields[0].to_string();
+            let size = fields[4].parse().unwrap_or(0);
+            let mut path = fields[8..].join(" ");
+            // Symlinks: "usr/lib/foo -> bar". Hardlinks: "usr/bin/a link to usr/bin/b" with
+            // an `h` mode; they are a second path to a regular file, listed as one.
+            if let Some((link, _)) = path.split_once(" -> ") {
+                path = link.to_string();
+            } else if let Some(perms) = mode.strip_prefix('h')
+                && let Some((link, _)) = path.split_once(" link to ")
+            {
+                mode = format!("-{perms}");
+                path = link.to_string();
+            }
+            let path = path.trim_start_matches("./").trim_end_matches('/').to_string();
+            if path.is_empty() {
+                return None;
+            }
+            Some((ArtifactEntry { path, mode }, size))
+        })
+        .collect()
+}
+
+/// Read `pkgname = ...` from .PKGINFO.
+fn pkginfo_name(pkginfo: &str) -> Option<String> {
+    pkginfo.lines().find_map(|line| {
+        let (key, value) = line.split_once('=')?;
+        (key.trim() == "pkgname").then(|| value.trim().to_string())
+    })
+}
+
+/// Fallback name: `foo-1.0-1-x86_64.pkg.tar.zst` -> `foo`.
+fn name_from_filename(path: &Path) -> String {
+    let file = path.file_name().and_then(|f| f.to_str()).unwrap_or("local");
+    let stem = file.split(".pkg.tar").next().unwrap_or(file);
+    // Strip trailing -pkgver-pkgrel-arch
+    let parts: Vec<&str> = stem.rsplitn(4, '-').collect();
+    if parts.len() == 4 {
+        parts[3].to_string()
+    } else {
+        stem.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_bsdtar_listing() {
+        let out = "\
+-rw-r--r--  0 root   root      512 Jan  1  2025 .PKGINFO
+drwxr-xr-x  0 root   root        0 Jan  1  2025 usr/
+-rwsr-xr-x  0 root   root    12345 Jan  1  2025 usr/bin/foo
+lrwxrwxrwx  0 root   root        0 Jan  1  2025 usr/bin/bar -> foo
+";
+        let entries = parse_listing(out);
+        assert_eq!(entries.len(), 4);
+        assert_eq!(entries[1].0.path, "usr");
+        assert_eq!(entries[2].0.path, "usr/bin/foo");
+        assert_eq!(entries[2].0.mode, "-rwsr-xr-x");
+        assert_eq!(entries[2].1, 12345);
+        assert_eq!(entries[3].0.path, "usr/bin/bar");
+    }
+
+    #[test]
+    fn hardlinks_are_listed_as_regular_files() {
+        let out = "\
+-rwsr-xr-x  0 root   root    12345 Jan  1  2025 usr/bin/foo
+hrwsr-xr-x  0 root   root        0 Jan  1  2025 usr/bin/foo-helper link to usr/bin/foo
+";
+        let entries = parse_listing(out);
+        assert_eq!(entries[1].0.path, "usr/bin/foo-helper");
+        assert_eq!(entries[1].0.mode, "-rwsr-xr-x");
+    }
+
+    #[test]
+    fn reads_pkgname_from_pkginfo() {
+        let pkginfo = "# Generated by makepkg\npkgname = evil-tool\npkgbase = evil-tool\npkgver = 1.0-1\n";
+        assert_eq!(pkginfo_name(pkginfo).as_deref(), Some("evil-tool"));
+    }
+
+    #[test]
+    fn name_from_package_filename() {
+        let name = name_from_filename(Path::new("/var/cache/pacman/pkg/my-tool-bin-1.2.3-1-x86_64.pkg.tar.zst"));
+        assert_eq!(name, "my-tool-bin");
+    }
+}
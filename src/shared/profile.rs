@@ -0,0 +1,127 @@
+//! Opt-in timing of features and patterns, for `traur bench`.
+//!
+//! Off by default: a disabled profiler costs one relaxed atomic load per feature run
+//! or pattern match. Bench enables it to report where analysis time goes, so a slow
+//! new pattern shows up before it reaches hook latency.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Cumulative time per feature name.
+static FEATURES: LazyLock<Mutex<HashMap<&'static str, Timing>>> = LazyLock::new(Default::default);
+
+/// Every compiled pattern's counters. A pattern loaded by several features (the
+/// high-severity pkgbuild patterns) appears once per load.
+static PATTERNS: LazyLock<Mutex<Vec<Arc<PatternTiming>>>> = LazyLock::new(Default::default);
+
+/// Cumulative time and number of runs.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Timing {
+    pub total: Duration,
+    pub calls: u64,
+}
+
+/// Counters for one compiled pattern, updated without locking.
+#[derive(Debug, Default)]
+pub struct PatternTiming {
+    id: String,
+    nanos: AtomicU64,
+    calls: AtomicU64,
+}
+
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Run `f`, adding its duration to `feature` when profiling is on.
+pub fn time_feature<T>(feature: &'static str, f: impl FnOnce() -> T) -> T {
+    if !enabled() {
+        return f();
+    }
+    let start = Instant::now();
+    let result = f();
+    let elapsed = start.elapsed();
+    let mut features = FEATURES.lock().unwrap();
+    let timing = features.entry(feature).or_default();
+    timing.total += elapsed;
+    timing.calls += 1;
+    result
+}
+
+/// Counters for a newly compiled pattern.
+pub fn register_pattern(id: &str) -> Arc<PatternTiming> {
+    let timing = Arc::new(PatternTiming { id: id.to_string(), ..Default::default() });
+    PATTERNS.lock().unwrap().push(Arc::clone(&timing));
+    timing
+}
+
+impl PatternTiming {
+    /// Run `f`, adding its duration to these counters when profiling is on.
+    pub fn time<T>(&self, f: impl FnOnce() -> T) -> T {
+        if !enabled() {
+            return f();
+        }
+        let start = Instant::now();
+        let result = f();
+        self.nanos.fetch_add(start.elapsed().as_nanos() as u64, Ordering::Relaxed);
+        self.calls.fetch_add(1, Ordering::Relaxed);
+        result
+    }
+
+    fn timing(&self) -> Timing {
+        Timing {
+            total: Duration::from_nanos(self.nanos.load(Ordering::Relaxed)),
+            calls: self.calls.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Features by cumulative time, slowest first.
+pub fn feature_times() -> Vec<(&'static str, Timing)> {
+    let mut times: Vec<_> = FEATURES.lock().unwrap().iter().map(|(name, t)| (*name, *t)).collect();
+    times.sort_by(|a, b| b.1.total.cmp(&a.1.total).then(a.0.cmp(b.0)));
+    times
+}
+
+/// The `n` patterns with the most cumulative matching time, slowest first.
+pub fn slowest_patterns(n: usize) -> Vec<(String, Timing)> {
+    let patterns = PATTERNS.lock().unwrap();
+    merge_by_id(patterns.iter().map(|t| (t.id.as_str(), t.timing())), n)
+}
+
+fn merge_by_id<'a>(timings: impl Iterator<Item = (&'a str, Timing)>, n: usize) -> Vec<(String, Timing)> {
+    let mut by_id: HashMap<&str, Timing> = HashMap::new();
+    for (id, t) in timings.filter(|(_, t)| t.calls > 0) {
+        let entry = by_id.entry(id).or_default();
+        entry.total += t.total;
+        entry.calls += t.calls;
+    }
+    let mut merged: Vec<(String, Timing)> = by_id.into_iter().map(|(id, t)| (id.to_string(), t)).collect();
+    merged.sort_by(|a, b| b.1.total.cmp(&a.1.total).then_with(|| a.0.cmp(&b.0)));
+    merged.truncate(n);
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merges_pattern_loads_and_ranks_slowest() {
+        let t = |ms: u64, calls: u64| Timing { total: Duration::from_millis(ms), calls };
+        let merged = merge_by_id(
+            [("P-A", t(5, 10)), ("P-B", t(8, 10)), ("P-A", t(4, 3)), ("P-C", t(0, 0))].into_iter(),
+            10,
+        );
+        assert_eq!(merged, [("P-A".to_string(), t(9, 13)), ("P-B".to_string(), t(8, 10))]);
+        assert_eq!(merge_by_id([("P-A", t(5, 1)), ("P-B", t(8, 1))].into_iter(), 1)[0].0, "P-B");
+    }
+}
@@ -0,0 +1,195 @@
+use crate::shared::config::{self, HttpConfig};
+use futures_util::stream::{self, StreamExt};
+use std::future::Future;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+/// Upper bound on in-flight HTTP requests during bulk prefetch.
+pub const MAX_CONCURRENT_REQUESTS: usize = 16;
+
+/// `[http]` settings, read once per process.
+static HTTP: LazyLock<HttpConfig> = LazyLock::new(|| config::load_config().http);
+
+/// Shared tokio runtime for network I/O. Analysis stays on rayon; only fetches run here.
+static RUNTIME: LazyLock<tokio::runtime::Runtime> = LazyLock::new(|| {
+    tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .thread_name("traur-net")
+        .build()
+        .expect("Failed to build tokio runtime")
+});
+
+/// Shared async HTTP client (connection pooling across all requests).
+static CLIENT: LazyLock<reqwest::Client> = LazyLock::new(|| {
+    build_client(&HTTP).unwrap_or_else(|e| {
+        eprintln!("traur: ignoring [http] settings: {e}");
+        build_client(&HttpConfig::default()).expect("Failed to build HTTP client")
+    })
+});
+
+/// Blocking client for synchronous callers outside the runtime (notifications, bench).
+static BLOCKING_CLIENT: LazyLock<reqwest::blocking::Client> = LazyLock::new(|| {
+    build_blocking_client(&HTTP).unwrap_or_else(|e| {
+        eprintln!("traur: ignoring [http] settings: {e}");
+        build_blocking_client(&HttpConfig::default()).expect("Failed to build HTTP client")
+    })
+});
+
+fn build_client(http: &HttpConfig) -> Result<reqwest::Client, String> {
+    let mut builder = reqwest::Client::builder().user_agent(http.user_agent()).connect_timeout(http.timeout());
+    if let Some(proxy) = proxy(http)? {
+        builder = builder.proxy(proxy);
+    }
+    for cert in root_certificates(http)? {
+        builder = builder.add_root_certificate(cert);
+    }
+    builder.build().map_err(|e| format!("Failed to build HTTP client: {e}"))
+}
+
+fn build_blocking_client(http: &HttpConfig) -> Result<reqwest::blocking::Client, String> {
+    let mut builder =
+        reqwest::blocking::Client::builder().user_agent(http.user_agent()).connect_timeout(http.timeout());
+    if let Some(proxy) = proxy(http)? {
+        builder = builder.proxy(proxy);
+    }
+    for cert in root_certificates(http)? {
+        builder = builder.add_root_certificate(cert);
+    }
+    builder.build().map_err(|e| format!("Failed to build HTTP client: {e}"))
+}
+
+/// The configured proxy for all schemes. None leaves reqwest's default: the
+/// `HTTPS_PROXY`/`HTTP_PROXY`/`NO_PROXY` environment variables.
+fn proxy(http: &HttpConfig) -> Result<Option<reqwest::Proxy>, String> {
+    http.proxy
+        .as_deref()
+        .map(|url| reqwest::Proxy::all(url).map_err(|e| format!("invalid proxy {url}: {e}")))
+        .transpose()
+}
+
+/// Certificates from the configured CA file; empty if none is set.
+fn root_certificates(http: &HttpConfig) -> Result<Vec<reqwest::Certificate>, String> {
+    let Some(path) = &http.ca_file else {
+        return Ok(Vec::new());
+    };
+    let pem = std::fs::read(path).map_err(|e| format!("failed to read CA file {path}: {e}"))?;
+    let certs = reqwest::Certificate::from_pem_bundle(&pem).map_err(|e| format!("invalid CA file {path}: {e}"))?;
+    if certs.is_empty() {
+        return Err(format!("no certificates in CA file {path}"));
+    }
+    Ok(certs)
+}
+
+/// Run a future to completion from synchronous code.
+/// Must not be called from inside the runtime (e.g. within an async fetch).
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    RUNTIME.block_on(future)
+}
+
+pub fn client() -> &'static reqwest::Client {
+    &CLIENT
+}
+
+pub fn blocking_client() -> &'static reqwest::blocking::Client {
+    &BLOCKING_CLIENT
+}
+
+/// Per-request timeout for GitHub API calls, AUR page scrapes and notifications
+/// (`[http] timeout`).
+pub fn request_timeout() -> Duration {
+    HTTP.timeout()
+}
+
+/// When the next AUR web page request may start (see `throttle_aur_web`).
+static NEXT_AUR_WEB_REQUEST: Mutex<Option<Instant>> = Mutex::new(None);
+
+/// Wait for a slot under `[http] aur_web_qps` before requesting an AUR web page.
+/// Bulk prefetch runs up to `MAX_CONCURRENT_REQUESTS` of these at once; the slots
+/// space them out across all of them.
+pub async fn throttle_aur_web() {
+    let Some(interval) = HTTP.aur_web_interval() else {
+        return;
+    };
+    let slot = {
+        let mut next = NEXT_AUR_WEB_REQUEST.lock().unwrap_or_else(|e| e.into_inner());
+        take_slot(&mut next, Instant::now(), interval)
+    };
+    tokio::time::sleep_until(slot.into()).await;
+}
+
+/// The start time for a request made at `now`, pushing `next` one interval past it.
+fn take_slot(next: &mut Option<Instant>, now: Instant, interval: Duration) -> Instant {
+    let slot = next.map_or(now, |n| n.max(now));
+    *next = Some(slot + interval);
+    slot
+}
+
+/// Map `f` over `items` with at most `limit` futures in flight.
+/// Results come back in completion order, not input order.
+pub async fn map_bounded<T, F, Fut>(
+    items: impl IntoIterator<Item = T>,
+    limit: usize,
+    f: F,
+) -> Vec<Fut::Output>
+where
+    F: FnMut(T) -> Fut,
+    Fut: Future,
+{
+    stream::iter(items).map(f).buffer_unordered(limit.max(1)).collect().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_bad_http_settings() {
+        let proxy = HttpConfig { proxy: Some("not a url".into()), ..Default::default() };
+        assert!(build_client(&proxy).unwrap_err().contains("invalid proxy"));
+
+        let path = std::env::temp_dir().join(format!("traur-ca-{}.pem", std::process::id()));
+        std::fs::write(&path, "not a certificate\n").unwrap();
+        let ca = HttpConfig { ca_file: Some(path.display().to_string()), ..Default::default() };
+        let err = build_blocking_client(&ca).unwrap_err();
+        std::fs::remove_file(&path).unwrap();
+        assert!(err.contains("CA file"), "{err}");
+
+        let fine = HttpConfig { proxy: Some("http://proxy.example:3128".into()), timeout: Some(30), ..Default::default() };
+        assert!(build_client(&fine).is_ok());
+    }
+
+    #[test]
+    fn aur_web_slots_are_an_interval_apart() {
+        let interval = Duration::from_millis(500);
+        let start = Instant::now();
+        let mut next = None;
+        assert_eq!(take_slot(&mut next, start, interval), start);
+        assert_eq!(take_slot(&mut next, start, interval), start + interval);
+        assert_eq!(take_slot(&mut next, start + Duration::from_millis(100), interval), start + interval * 2);
+        // After a quiet spell a request goes out at once
+        let later = start + Duration::from_secs(10);
+        assert_eq!(take_slot(&mut next, later, interval), later);
+    }
+
+    #[test]
+    fn map_bounded_runs_every_item() {
+        let mut out = block_on(map_bounded(1..=20, 4, |n| async move { n * 2 }));
+        out.sort_unstable();
+        assert_eq!(out, (1..=20).map(|n| n * 2).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn map_bounded_limits_concurrency() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let in_flight = AtomicUsize::new(0);
+        let peak = AtomicUsize::new(0);
+        block_on(map_bounded(0..12, 3, |_| async {
+            let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            peak.fetch_max(now, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(5)).await;
+            in_flight.fetch_sub(1, Ordering::SeqCst);
+        }));
+        assert!(peak.load(Ordering::SeqCst) <= 3);
+    }
+}
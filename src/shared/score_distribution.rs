@@ -0,0 +1,101 @@
+//! Trust-score distribution from the last `traur bench` run.
+//!
+//! A bench scans the most recently updated AUR packages; with `--save-snapshots` the
+//! scores it saw are kept as a histogram in the cache directory. A single-package scan then puts its own score
+//! in context ("lower than 97% of recently updated AUR packages"), which says more than
+//! the bare number: a score of 42 is alarming when most packages sit above 80.
+
+use crate::shared::cache;
+use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScoreDistribution {
+    /// Unix time of the bench that produced the snapshot.
+    pub generated: u64,
+    /// Number of packages with each trust score, indexed by score (0..=100).
+    pub histogram: Vec<u64>,
+}
+
+impl ScoreDistribution {
+    pub fn from_scores(scores: impl IntoIterator<Item = u32>) -> Self {
+        let mut histogram = vec![0; 101];
+        for score in scores {
+            histogram[score.min(100) as usize] += 1;
+        }
+        let generated = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+        ScoreDistribution { generated, histogram }
+    }
+
+    pub fn total(&self) -> u64 {
+        self.histogram.iter().sum()
+    }
+
+    /// Percentage of packages that scored higher and lower than `score`.
+    pub fn rank(&self, score: u32) -> (f64, f64) {
+        let total = self.total();
+        if total == 0 {
+            return (0.0, 0.0);
+        }
+        let score = (score.min(100) as usize).min(self.histogram.len());
+        let lower: u64 = self.histogram[..score].iter().sum();
+        let higher: u64 = self.histogram.iter().skip(score + 1).sum();
+        let pct = |n: u64| n as f64 / total as f64 * 100.0;
+        (pct(higher), pct(lower))
+    }
+
+    /// One line putting `score` in context, e.g. "trust 42/100 is lower than 97% of
+    /// 3000 recently updated AUR packages (bench 3 days ago)".
+    pub fn describe(&self, score: u32) -> String {
+        let (higher, lower) = self.rank(score);
+        let comparison = if higher >= lower {
+            format!("lower than {higher:.0}%")
+        } else {
+            format!("higher than {lower:.0}%")
+        };
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+        let age = match now.saturating_sub(self.generated) / 86_400 {
+            0 => "bench today".to_string(),
+            1 => "bench 1 day ago".to_string(),
+            days => format!("bench {days} days ago"),
+        };
+        format!(
+            "trust {score}/100 is {comparison} of {} recently updated AUR packages ({age})",
+            self.total()
+        )
+    }
+}
+
+/// Cache file the histogram of the last `traur bench --save-snapshots` is kept in.
+const SNAPSHOT_FILE: &str = "score-distribution.json";
+
+/// Replace the snapshot with `dist`.
+pub fn save(dist: &ScoreDistribution) -> Result<(), String> {
+    cache::save_snapshot(SNAPSHOT_FILE, dist)
+}
+
+/// The last saved snapshot, if a bench has saved one on this machine.
+pub fn load() -> Option<ScoreDistribution> {
+    cache::load_snapshot::<ScoreDistribution>(SNAPSHOT_FILE).filter(|d| d.total() > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ranks_score_against_histogram() {
+        let dist = ScoreDistribution::from_scores([10, 42, 80, 85, 90, 90, 95, 100, 100, 100]);
+        assert_eq!(dist.total(), 10);
+        let (higher, lower) = dist.rank(42);
+        assert_eq!((higher, lower), (80.0, 10.0));
+        assert!(dist.describe(42).starts_with("trust 42/100 is lower than 80% of 10 recently updated AUR packages"));
+        assert!(dist.describe(100).starts_with("trust 100/100 is higher than 70%"));
+    }
+
+    #[test]
+    fn empty_distribution_ranks_nothing() {
+        let dist = ScoreDistribution::from_scores([]);
+        assert_eq!(dist.rank(50), (0.0, 0.0));
+    }
+}
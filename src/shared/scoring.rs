@@ -1,28 +1,77 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Arc;
 
 /// A signal emitted by a feature during analysis.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Signal {
     pub id: String,
     pub category: SignalCategory,
     pub points: u32,
     pub description: String,
     pub is_override_gate: bool,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub matched_line: Option<String>,
+    /// Where `matched_line` sits in the scanned file, for context in verbose output.
+    /// Set by the coordinator; not serialized.
+    #[serde(skip)]
+    pub location: Option<SourceLocation>,
+    /// The feature (or shared check, e.g. `known_good`) that emitted the signal. Set by
+    /// the coordinator; empty for signals built elsewhere.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub emitted_by: String,
+}
+
+/// A line in a scanned file (PKGBUILD, install script), sharing the file content
+/// between all signals that point into it.
+#[derive(Debug, Clone)]
+pub struct SourceLocation {
+    pub content: Arc<str>,
+    /// 0-based index of the line in `content`.
+    pub line: usize,
+}
+
+impl SourceLocation {
+    /// Find the first line of `content` that trims to `matched`, or failing that
+    /// contains it (features trim and sometimes shorten the line they report).
+    pub fn find(content: &Arc<str>, matched: &str) -> Option<Self> {
+        let matched = matched.trim();
+        if matched.is_empty() {
+            return None;
+        }
+        let line = content
+            .lines()
+            .position(|l| l.trim() == matched)
+            .or_else(|| content.lines().position(|l| l.contains(matched)))?;
+        Some(SourceLocation { content: Arc::clone(content), line })
+    }
+
+    /// The line with up to `radius` lines either side, as (1-based line number, text).
+    pub fn context(&self, radius: usize) -> Vec<(usize, &str)> {
+        let first = self.line.saturating_sub(radius);
+        self.content
+            .lines()
+            .enumerate()
+            .skip(first)
+            .take(self.line - first + radius + 1)
+            .map(|(i, l)| (i + 1, l))
+            .collect()
+    }
 }
 
-/// The four weighted signal categories.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+/// The five weighted signal categories.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum SignalCategory {
     Metadata,
     Pkgbuild,
     Behavioral,
     Temporal,
+    /// Code in the `.install` script, which pacman runs as root at install time.
+    InstallScript,
 }
 
 /// Trust tier derived from the final score.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum Tier {
     Trusted,
     Ok,
@@ -31,86 +80,299 @@ pub enum Tier {
     Malicious,
 }
 
+/// Version of the `ScanResult` JSON layout, emitted as `schema_version`.
+///
+/// Within a version, changes are additive only: new fields may appear (optional ones
+/// are omitted when empty), but existing fields keep their name, type and meaning.
+/// Consumers should ignore fields they don't know. Removing, renaming or retyping a
+/// field, or a new `Tier`/`SignalCategory` value, bumps the version.
+pub const SCHEMA_VERSION: u32 = 2;
+
 /// Complete result of scanning a package.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ScanResult {
+    /// `SCHEMA_VERSION` the result was produced with. Results saved before the field
+    /// existed (checkpoints) read back as version 1.
+    #[serde(default = "first_schema_version")]
+    pub schema_version: u32,
     pub package: String,
     pub score: u32,
     pub tier: Tier,
     pub signals: Vec<Signal>,
     pub override_gate_fired: Option<String>,
+    /// Per-category contribution to the risk score (only categories with signals).
+    pub category_scores: BTreeMap<SignalCategory, CategoryScore>,
+    /// Features that did not run because they hit a timeout or the scan's deadline.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub skipped_features: Vec<String>,
+    /// Unified diff from the baseline PKGBUILD to the scanned one, when `T-DIFF-*` signals fired.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pkgbuild_diff: Option<String>,
+    /// Why the tier differs from what the score alone gives (new-package grace).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    /// Override gates that fired but were demoted to ordinary signals by
+    /// `[scoring] demoted_gates`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub demoted_gates: Vec<String>,
+    /// Inputs the scan could not get. Without them a clean result is not a clean package.
+    #[serde(default, skip_serializing_if = "AnalysisCompleteness::is_complete")]
+    pub analysis_completeness: AnalysisCompleteness,
+}
+
+/// Inputs of an AUR scan that were unavailable, e.g. a cloned repo without a PKGBUILD.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AnalysisCompleteness {
+    /// "AUR metadata", "PKGBUILD", "git log" or "results from N timed-out features".
+    pub unavailable: Vec<String>,
+}
+
+impl AnalysisCompleteness {
+    pub fn is_complete(&self) -> bool {
+        self.unavailable.is_empty()
+    }
+}
+
+fn first_schema_version() -> u32 {
+    1
+}
+
+/// How much one category contributed to the weighted risk score.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CategoryScore {
+    /// Sum of signal points, before the per-category cap of 100.
+    pub raw: u32,
+    /// `raw` after the per-category cap of 100.
+    #[serde(default)]
+    pub capped: u32,
+    /// Weight of the category in the composite score.
+    #[serde(default)]
+    pub weight: f64,
+    /// Capped total multiplied by the category weight.
+    pub weighted: f64,
+}
+
+impl SignalCategory {
+    /// Weight of this category in the composite score.
+    pub fn weight(self) -> f64 {
+        match self {
+            SignalCategory::Metadata => WEIGHT_METADATA,
+            SignalCategory::Pkgbuild => WEIGHT_PKGBUILD,
+            SignalCategory::Behavioral => WEIGHT_BEHAVIORAL,
+            SignalCategory::Temporal => WEIGHT_TEMPORAL,
+            SignalCategory::InstallScript => WEIGHT_INSTALL_SCRIPT,
+        }
+    }
 }
 
-/// Category weights for the composite score.
+/// Category weights for the composite score. The install script weighs more than
+/// the PKGBUILD: it runs as root on every install, not in a build chroot. The sum
+/// may exceed 1, the risk is capped at 100.
 const WEIGHT_METADATA: f64 = 0.15;
 const WEIGHT_PKGBUILD: f64 = 0.45;
 const WEIGHT_BEHAVIORAL: f64 = 0.25;
 const WEIGHT_TEMPORAL: f64 = 0.15;
+const WEIGHT_INSTALL_SCRIPT: f64 = 0.60;
+
+/// Signals every newly published package fires, however clean: no votes or
+/// popularity yet, a fresh repo and often a fresh maintainer account. Together they
+/// reach SKETCHY on their own. Overridable with `[scoring] newness_signals`.
+pub const DEFAULT_NEWNESS_SIGNALS: &[&str] = &[
+    "M-VOTES-ZERO",
+    "M-VOTES-LOW",
+    "M-POP-ZERO",
+    "M-GITHUB-STARS-ZERO",
+    "M-GITHUB-STARS-LOW",
+    "T-NEW-PACKAGE",
+    "T-SINGLE-COMMIT",
+    "B-MAINTAINER-NEW",
+    "B-MAINTAINER-SINGLE",
+];
 
 /// Compute the final score and tier from a list of signals.
+#[allow(dead_code)] // Public library API
 pub fn compute_score(package_name: &str, signals: &[Signal]) -> ScanResult {
-    let weighted_score = compute_weighted(signals);
+    compute_score_with(package_name, signals, DEFAULT_NEWNESS_SIGNALS)
+}
 
-    // Find the highest-scoring override gate
-    let best_override = signals
-        .iter()
-        .filter(|s| s.is_override_gate)
-        .max_by_key(|s| s.points);
+/// `compute_score` with a configured newness set. When every signal is in it and none
+/// is about the PKGBUILD or install script, the package is new but clean: the tier is
+/// capped at OK and `note` says why. The score itself is left as computed.
+pub fn compute_score_with(package_name: &str, signals: &[Signal], newness: &[impl AsRef<str>]) -> ScanResult {
+    let category_scores = compute_category_scores(signals);
+    let math = risk_math(&category_scores, signals);
 
-    if let Some(signal) = best_override {
-        // Use the higher of the override gate score and the weighted score
-        let risk = signal.points.max(weighted_score).min(100);
+    if let Some(gate) = math.override_gate {
         return ScanResult {
+            schema_version: SCHEMA_VERSION,
             package: package_name.to_string(),
-            score: 100 - risk,
-            tier: Tier::Malicious,
+            score: math.trust,
+            tier: math.tier,
             signals: signals.to_vec(),
-            override_gate_fired: Some(signal.id.clone()),
+            override_gate_fired: Some(gate.id),
+            category_scores,
+            skipped_features: Vec::new(),
+            pkgbuild_diff: None,
+            note: None,
+            demoted_gates: Vec::new(),
+            analysis_completeness: AnalysisCompleteness::default(),
         };
     }
 
-    let trust = 100 - weighted_score;
-    let tier = score_to_tier(trust);
+    let trust = math.trust;
+    let mut tier = math.tier;
+    let mut note = None;
+    if tier > Tier::Ok && is_new_but_clean(signals, newness) {
+        tier = Tier::Ok;
+        note = Some(format!(
+            "New package with nothing flagged in its PKGBUILD: every signal is about its newness, so the tier is capped at {}",
+            Tier::Ok
+        ));
+    }
 
     ScanResult {
+        schema_version: SCHEMA_VERSION,
         package: package_name.to_string(),
         score: trust,
         tier,
         signals: signals.to_vec(),
         override_gate_fired: None,
+        category_scores,
+        skipped_features: Vec::new(),
+        pkgbuild_diff: None,
+        note,
+        demoted_gates: Vec::new(),
+        analysis_completeness: AnalysisCompleteness::default(),
     }
 }
 
-/// Compute the weighted composite score from signals (without override gate logic).
-fn compute_weighted(signals: &[Signal]) -> u32 {
-    let mut meta_total: u32 = 0;
-    let mut pkgbuild_total: u32 = 0;
-    let mut behavioral_total: u32 = 0;
-    let mut temporal_total: u32 = 0;
+/// Raise an incomplete scan's tier to at least SKETCHY (`[thresholds]
+/// incomplete_as_sketchy`): missing inputs can hide what would have been flagged.
+pub fn floor_incomplete(result: &mut ScanResult) {
+    if result.analysis_completeness.is_complete() || result.tier >= Tier::Sketchy {
+        return;
+    }
+    result.tier = Tier::Sketchy;
+    result.note = Some(format!(
+        "Not fully analyzed (no {}), so the tier is raised to {}",
+        result.analysis_completeness.unavailable.join(", no "),
+        Tier::Sketchy
+    ));
+}
 
-    for signal in signals {
-        match signal.category {
-            SignalCategory::Metadata => meta_total += signal.points,
-            SignalCategory::Pkgbuild => pkgbuild_total += signal.points,
-            SignalCategory::Behavioral => behavioral_total += signal.points,
-            SignalCategory::Temporal => temporal_total += signal.points,
-        }
+/// Step-by-step account of how a result's signals became its score and tier, for
+/// `scan --explain-score`, picking up from the result's `category_scores`. Derived
+/// from the final result, so later adjustments (known-good credit, the new-package
+/// cap, the incomplete-scan floor) show up too.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ScoreExplanation {
+    /// Sum of the weighted category totals, before rounding.
+    pub weighted_sum: f64,
+    /// `weighted_sum` rounded, at most 100.
+    pub weighted_risk: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub override_gate: Option<GateMath>,
+    /// `weighted_risk`, or the larger of it and the gate's points when a gate fired.
+    pub risk: u32,
+    /// `100 - risk`.
+    pub trust: u32,
+    /// Tier the trust score falls in, or MALICIOUS when a gate fired.
+    pub tier: Tier,
+    /// What changed the score or tier after that, in order.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub adjustments: Vec<String>,
+    pub final_score: u32,
+    pub final_tier: Tier,
+}
+
+/// The override gate that decided a `ScoreExplanation`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GateMath {
+    pub id: String,
+    pub points: u32,
+}
+
+/// From category totals and override gates to the risk, trust and tier, before any
+/// later adjustment. `compute_score_with` scores with it and `explain_score` shows it.
+struct RiskMath {
+    weighted_sum: f64,
+    weighted_risk: u32,
+    override_gate: Option<GateMath>,
+    risk: u32,
+    trust: u32,
+    tier: Tier,
+}
+
+fn risk_math(category_scores: &BTreeMap<SignalCategory, CategoryScore>, signals: &[Signal]) -> RiskMath {
+    let weighted_sum: f64 = category_scores.values().map(|c| c.weighted).sum();
+    let weighted_risk = (weighted_sum.round() as u32).min(100);
+
+    // The highest-scoring override gate, if any, makes the package MALICIOUS with
+    // the higher of its points and the weighted risk
+    let override_gate = signals
+        .iter()
+        .filter(|s| s.is_override_gate)
+        .max_by_key(|s| s.points)
+        .map(|s| GateMath { id: s.id.clone(), points: s.points });
+    let risk = override_gate.as_ref().map_or(weighted_risk, |gate| gate.points.max(weighted_risk).min(100));
+    let trust = 100 - risk;
+    let tier = if override_gate.is_some() { Tier::Malicious } else { score_to_tier(trust) };
+
+    RiskMath { weighted_sum, weighted_risk, override_gate, risk, trust, tier }
+}
+
+/// Explain how `result` was scored.
+pub fn explain_score(result: &ScanResult) -> ScoreExplanation {
+    let math = risk_math(&result.category_scores, &result.signals);
+
+    let mut adjustments = Vec::new();
+    if result.score != math.trust {
+        adjustments.push(format!("Known-good credit: trust {} -> {}", math.trust, result.score));
+    }
+    let tier_from_score = if math.override_gate.is_some() { Tier::Malicious } else { score_to_tier(result.score) };
+    if result.tier != tier_from_score {
+        adjustments.push(match result.note {
+            Some(ref note) => note.clone(),
+            None => format!("Tier {tier_from_score} -> {}", result.tier),
+        });
     }
 
-    meta_total = meta_total.min(100);
-    pkgbuild_total = pkgbuild_total.min(100);
-    behavioral_total = behavioral_total.min(100);
-    temporal_total = temporal_total.min(100);
+    ScoreExplanation {
+        weighted_sum: math.weighted_sum,
+        weighted_risk: math.weighted_risk,
+        override_gate: math.override_gate,
+        risk: math.risk,
+        trust: math.trust,
+        tier: math.tier,
+        adjustments,
+        final_score: result.score,
+        final_tier: result.tier,
+    }
+}
 
-    let weighted = (WEIGHT_METADATA * meta_total as f64)
-        + (WEIGHT_PKGBUILD * pkgbuild_total as f64)
-        + (WEIGHT_BEHAVIORAL * behavioral_total as f64)
-        + (WEIGHT_TEMPORAL * temporal_total as f64);
+fn is_new_but_clean(signals: &[Signal], newness: &[impl AsRef<str>]) -> bool {
+    !signals.is_empty()
+        && signals.iter().all(|s| {
+            !matches!(s.category, SignalCategory::Pkgbuild | SignalCategory::InstallScript)
+                && newness.iter().any(|id| id.as_ref() == s.id)
+        })
+}
 
-    (weighted.round() as u32).min(100)
+/// Sum signal points per category and apply the category cap and weight.
+fn compute_category_scores(signals: &[Signal]) -> BTreeMap<SignalCategory, CategoryScore> {
+    let mut raw: BTreeMap<SignalCategory, u32> = BTreeMap::new();
+    for signal in signals {
+        *raw.entry(signal.category).or_insert(0) += signal.points;
+    }
+    raw.into_iter()
+        .map(|(category, total)| {
+            let (capped, weight) = (total.min(100), category.weight());
+            (category, CategoryScore { raw: total, capped, weight, weighted: weight * capped as f64 })
+        })
+        .collect()
 }
 
-fn score_to_tier(trust: u32) -> Tier {
+pub fn score_to_tier(trust: u32) -> Tier {
     match trust {
         0..=20 => Tier::Malicious,
         21..=40 => Tier::Suspicious,
@@ -120,6 +382,18 @@ fn score_to_tier(trust: u32) -> Tier {
     }
 }
 
+/// Parse a tier name (case-insensitive).
+pub fn tier_from_str(s: &str) -> Option<Tier> {
+    match s.to_lowercase().as_str() {
+        "trusted" => Some(Tier::Trusted),
+        "ok" => Some(Tier::Ok),
+        "sketchy" => Some(Tier::Sketchy),
+        "suspicious" => Some(Tier::Suspicious),
+        "malicious" => Some(Tier::Malicious),
+        _ => None,
+    }
+}
+
 impl std::fmt::Display for Tier {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -144,9 +418,73 @@ mod tests {
             description: String::new(),
             is_override_gate: override_gate,
             matched_line: None,
+            location: None,
+            emitted_by: String::new(),
         }
     }
 
+    #[test]
+    fn parses_tier_names() {
+        assert_eq!(tier_from_str("Suspicious"), Some(Tier::Suspicious));
+        assert_eq!(tier_from_str("MALICIOUS"), Some(Tier::Malicious));
+        assert_eq!(tier_from_str("bogus"), None);
+    }
+
+    #[test]
+    fn explanation_shows_caps_weights_and_rounding() {
+        let result = compute_score(
+            "pkg",
+            &[
+                signal("P-A", SignalCategory::Pkgbuild, 80, false),
+                signal("P-B", SignalCategory::Pkgbuild, 55, false),
+                signal("M-A", SignalCategory::Metadata, 15, false),
+            ],
+        );
+        let math = explain_score(&result);
+        let pkgbuild = &result.category_scores[&SignalCategory::Pkgbuild];
+        assert_eq!((pkgbuild.raw, pkgbuild.capped), (135, 100));
+        assert!((pkgbuild.weighted - 45.0).abs() < 1e-9);
+        // 45 + 2.25 rounds down to 47
+        assert!((math.weighted_sum - 47.25).abs() < 1e-9);
+        assert_eq!((math.weighted_risk, math.risk, math.trust), (47, 47, 53));
+        assert_eq!(math.tier, Tier::Sketchy);
+        assert_eq!((math.final_score, math.final_tier), (result.score, result.tier));
+        assert!(math.override_gate.is_none() && math.adjustments.is_empty());
+    }
+
+    #[test]
+    fn explanation_records_gate_and_later_adjustments() {
+        let gated = compute_score(
+            "pkg",
+            &[signal("P-CURL-PIPE", SignalCategory::Pkgbuild, 95, true), signal("M-A", SignalCategory::Metadata, 10, false)],
+        );
+        let math = explain_score(&gated);
+        assert_eq!(math.override_gate, Some(GateMath { id: "P-CURL-PIPE".into(), points: 95 }));
+        assert_eq!((math.risk, math.trust, math.tier), (95, 5, Tier::Malicious));
+
+        let mut capped = compute_score(
+            "pkg",
+            &[signal("M-VOTES-ZERO", SignalCategory::Metadata, 100, false), signal("T-NEW-PACKAGE", SignalCategory::Temporal, 100, false), signal("B-MAINTAINER-NEW", SignalCategory::Behavioral, 100, false)],
+        );
+        capped.score += 10;
+        let math = explain_score(&capped);
+        assert_eq!(math.tier, Tier::Sketchy);
+        assert_eq!(math.adjustments.len(), 2, "{:?}", math.adjustments);
+        assert!(math.adjustments[0].starts_with("Known-good credit"));
+        assert!(math.adjustments[1].contains("capped at OK"));
+    }
+
+    #[test]
+    fn source_location_context() {
+        let content: Arc<str> = "a\nb\n  c | sh\nd\ne\nf\n".into();
+        let location = SourceLocation::find(&content, "c | sh").unwrap();
+        assert_eq!(location.line, 2);
+        assert_eq!(location.context(2), vec![(1, "a"), (2, "b"), (3, "  c | sh"), (4, "d"), (5, "e")]);
+        let first = SourceLocation::find(&content, "a").unwrap();
+        assert_eq!(first.context(1), vec![(1, "a"), (2, "b")]);
+        assert!(SourceLocation::find(&content, "zzz").is_none());
+    }
+
     #[test]
     fn no_signals_scores_full_trust() {
         let result = compute_score("pkg", &[]);
@@ -196,6 +534,21 @@ mod tests {
         assert_eq!(result.tier, Tier::Sketchy);
     }
 
+    #[test]
+    fn install_script_outweighs_pkgbuild() {
+        let pkgbuild = compute_score("pkg", &[signal("P-INSTALL-CURL", SignalCategory::Pkgbuild, 50, false)]);
+        let install = compute_score("pkg", &[signal("P-INSTALL-CURL", SignalCategory::InstallScript, 50, false)]);
+        // 0.45 * 50 = 22.5 -> 77 trust; 0.60 * 50 = 30 -> 70 trust
+        assert_eq!(pkgbuild.score, 77);
+        assert_eq!(install.score, 70);
+        // Capped separately from the PKGBUILD
+        let both = compute_score("pkg", &[
+            signal("P-A", SignalCategory::Pkgbuild, 100, false),
+            signal("IS-SA-A", SignalCategory::InstallScript, 100, false),
+        ]);
+        assert_eq!(both.score, 0);
+    }
+
     #[test]
     fn tier_boundaries() {
         assert_eq!(score_to_tier(0), Tier::Malicious);
@@ -221,4 +574,65 @@ mod tests {
         let result = compute_score("pkg", &signals);
         assert_eq!(result.score, 0);
     }
+
+    #[test]
+    fn category_scores_report_raw_and_weighted() {
+        let signals = vec![
+            signal("P-A", SignalCategory::Pkgbuild, 80, false),
+            signal("P-B", SignalCategory::Pkgbuild, 80, false),
+            signal("M-A", SignalCategory::Metadata, 20, false),
+        ];
+        let result = compute_score("pkg", &signals);
+        let pkgbuild = result.category_scores[&SignalCategory::Pkgbuild];
+        assert_eq!(pkgbuild.raw, 160);
+        assert!((pkgbuild.weighted - 45.0).abs() < 1e-9);
+        let metadata = result.category_scores[&SignalCategory::Metadata];
+        assert_eq!(metadata.raw, 20);
+        assert!((metadata.weighted - 3.0).abs() < 1e-9);
+        assert!(!result.category_scores.contains_key(&SignalCategory::Temporal));
+        // 45 + 3 = 48 risk
+        assert_eq!(result.score, 52);
+    }
+
+    #[test]
+    fn incomplete_scan_is_raised_to_sketchy_only() {
+        let mut clean = compute_score("pkg", &[]);
+        floor_incomplete(&mut clean);
+        assert_eq!(clean.tier, Tier::Trusted, "complete scans are left alone");
+
+        clean.analysis_completeness.unavailable = vec!["git log".to_string()];
+        floor_incomplete(&mut clean);
+        assert_eq!(clean.tier, Tier::Sketchy);
+        assert_eq!(clean.score, 100, "score is left as computed");
+
+        let mut gated = compute_score("pkg", &[signal("P-CURL-PIPE", SignalCategory::Pkgbuild, 95, true)]);
+        gated.analysis_completeness.unavailable = vec!["git log".to_string()];
+        floor_incomplete(&mut gated);
+        assert_eq!(gated.tier, Tier::Malicious);
+        assert!(gated.note.is_none());
+    }
+
+    #[test]
+    fn new_but_clean_package_is_capped_at_ok() {
+        let newness = vec![
+            signal("M-VOTES-ZERO", SignalCategory::Metadata, 100, false),
+            signal("B-MAINTAINER-NEW", SignalCategory::Behavioral, 100, false),
+            signal("T-NEW-PACKAGE", SignalCategory::Temporal, 100, false),
+        ];
+        let result = compute_score("pkg", &newness);
+        assert_eq!(result.score, 45, "score is left as computed");
+        assert_eq!(result.tier, Tier::Ok);
+        assert!(result.note.is_some());
+
+        let mut with_finding = newness.clone();
+        with_finding.push(signal("B-TYPOSQUAT", SignalCategory::Behavioral, 55, false));
+        assert_eq!(compute_score("pkg", &with_finding).tier, Tier::Sketchy);
+
+        let install = vec![signal("M-VOTES-ZERO", SignalCategory::InstallScript, 100, false)];
+        assert!(compute_score("pkg", &install).note.is_none());
+
+        let result = compute_score_with("pkg", &newness, &["M-VOTES-ZERO"]);
+        assert_eq!(result.tier, Tier::Sketchy);
+        assert!(result.note.is_none());
+    }
 }
@@ -10,6 +10,10 @@ pub struct SignalDef {
     pub description: String,
     #[allow(dead_code)]
     pub is_override_gate: bool,
+    /// Feature (or shared check) that emits the signal, as in `Signal::emitted_by`.
+    pub emitted_by: &'static str,
+    /// Regex from patterns.toml; None for signals computed in feature code.
+    pub pattern: Option<String>,
 }
 
 /// Return all known signal definitions (pattern-based + hardcoded).
@@ -27,9 +31,16 @@ fn pattern_signals() -> Vec<SignalDef> {
 
     let mut defs = Vec::new();
     for (section, rules) in &db.sections {
+        // Each section is scanned by the feature of the same name
+        let emitted_by = match section.as_str() {
+            "install_script_analysis" => "install_script_analysis",
+            "source_url_analysis" => "source_url_analysis",
+            "gtfobins_analysis" => "gtfobins_analysis",
+            _ => "pkgbuild_analysis",
+        };
         let category = match section.as_str() {
-            "pkgbuild_analysis" | "install_script_analysis" | "source_url_analysis"
-            | "gtfobins_analysis" => SignalCategory::Pkgbuild,
+            "install_script_analysis" => SignalCategory::InstallScript,
+            "pkgbuild_analysis" | "source_url_analysis" | "gtfobins_analysis" => SignalCategory::Pkgbuild,
             _ => SignalCategory::Pkgbuild, // safe default for any future sections
         };
         for rule in rules {
@@ -39,84 +50,217 @@ fn pattern_signals() -> Vec<SignalDef> {
                 points: rule.points,
                 description: rule.description.clone(),
                 is_override_gate: rule.override_gate,
+                emitted_by,
+                pattern: Some(rule.pattern.clone()),
             });
         }
     }
     defs
 }
 
-/// Hardcoded signals defined directly in feature code.
+/// ID, category, points, description and override gate of a hardcoded signal.
+type HardcodedDef = (&'static str, SignalCategory, u32, &'static str, bool);
+
+/// Hardcoded signals defined directly in feature code, grouped by emitter.
 /// Keep in sync when adding/changing signals in feature analyze() methods.
 fn hardcoded_signals() -> Vec<SignalDef> {
     use SignalCategory::*;
-    let defs: Vec<(&str, SignalCategory, u32, &str, bool)> = vec![
-        // metadata_analysis
-        ("M-VOTES-ZERO", Metadata, 30, "Package has zero votes", false),
-        ("M-VOTES-LOW", Metadata, 20, "Package has very few votes", false),
-        ("M-POP-ZERO", Metadata, 25, "Popularity is 0 (no recent usage)", false),
-        ("M-NO-MAINTAINER", Metadata, 20, "Package is orphaned (no maintainer)", false),
-        ("M-NO-URL", Metadata, 15, "No upstream URL provided", false),
-        ("M-NO-LICENSE", Metadata, 10, "No license specified", false),
-        ("M-OUT-OF-DATE", Metadata, 5, "Package is flagged as out of date", false),
-        // name_analysis
-        ("B-NAME-IMPERSONATE", Behavioral, 65, "Name looks like impersonation of a popular package", false),
-        ("B-TYPOSQUAT", Behavioral, 55, "Name is suspiciously similar to a popular package", false),
-        // maintainer_analysis
-        ("B-MAINTAINER-NEW", Behavioral, 30, "Maintainer has only 1 package, created recently", false),
-        ("B-MAINTAINER-SINGLE", Behavioral, 15, "Maintainer has only 1 package", false),
-        ("B-MAINTAINER-BATCH", Behavioral, 45, "Maintainer created 3+ packages in the last 48 hours", false),
-        // orphan_takeover_analysis
-        ("B-SUBMITTER-CHANGED", Behavioral, 15, "Package maintainer differs from original submitter", false),
-        ("B-ORPHAN-TAKEOVER", Behavioral, 50, "Adopted package with new git author (orphan takeover pattern)", false),
-        // bin_source_verification
-        ("B-BIN-GITHUB-ORG-MISMATCH", Behavioral, 50, "-bin package source downloads from different GitHub org than upstream", false),
-        ("B-BIN-DOMAIN-MISMATCH", Behavioral, 30, "-bin package source downloads from different domain than upstream", false),
-        // git_history_analysis
-        ("T-SINGLE-COMMIT", Temporal, 20, "Git history has only 1 commit", false),
-        ("T-NEW-PACKAGE", Temporal, 25, "Package is very new (< 7 days old)", false),
-        ("T-MALICIOUS-DIFF", Temporal, 55, "Latest commit introduces network code not present in prior history", false),
-        ("T-AUTHOR-CHANGE", Temporal, 25, "Git history shows multiple different authors", false),
-        // aur_comments_analysis
-        ("M-COMMENTS-SECURITY", Metadata, 40, "Recent AUR comments contain security-related warnings", false),
-        // github_stars
-        ("M-GITHUB-STARS-ZERO", Metadata, 20, "Upstream GitHub repo has 0 stars", false),
-        ("M-GITHUB-STARS-LOW", Metadata, 10, "Upstream GitHub repo has very few stars (<10)", false),
-        ("M-GITHUB-NOT-FOUND", Metadata, 25, "Upstream URL points to GitHub but repo does not exist", false),
-        // pkgbuild_diff_analysis
-        ("T-DIFF-NEW-SUSPICIOUS", Temporal, 40, "Newly introduced suspicious pattern not in prior version", false),
-        ("T-DIFF-CHECKSUM-REMOVED", Temporal, 35, "Checksum array removed or all entries changed to SKIP", false),
-        ("T-DIFF-SOURCE-DOMAIN-CHANGED", Temporal, 30, "Source URLs changed to a different domain", false),
-        ("T-DIFF-MAJOR-REWRITE", Temporal, 15, ">50% of PKGBUILD lines changed (unusual for version bump)", false),
-        // checksum_analysis
-        ("P-NO-CHECKSUMS", Pkgbuild, 30, "No checksum array found in PKGBUILD", false),
-        ("P-SKIP-ALL", Pkgbuild, 25, "All checksums are SKIP (no integrity verification)", false),
-        ("P-WEAK-CHECKSUMS", Pkgbuild, 10, "Using weak checksums (md5/sha1) without stronger alternative", false),
-        ("P-CHECKSUM-MISMATCH", Pkgbuild, 25, "Source count != checksum count", false),
-        // shell_analysis
-        ("SA-VAR-CONCAT-EXEC", Pkgbuild, 85, "Variable concatenation resolves to download-and-execute", true),
-        ("SA-VAR-CONCAT-CMD", Pkgbuild, 55, "Variable concatenation resolves to dangerous command", false),
-        ("SA-INDIRECT-EXEC", Pkgbuild, 70, "Variable with dangerous command in execution position", false),
-        ("SA-CHARBYCHAR-CONSTRUCT", Pkgbuild, 75, "Printf/echo subshell char-by-char command construction", false),
-        ("SA-DATA-BLOB-HEX", Pkgbuild, 50, "Embedded long hex string (possible encoded payload)", false),
-        ("SA-DATA-BLOB-BASE64", Pkgbuild, 50, "Embedded long base64 string (possible encoded payload)", false),
-        ("SA-HIGH-ENTROPY-HEREDOC", Pkgbuild, 55, "Heredoc with high entropy content", false),
-        ("SA-BINARY-DOWNLOAD-NOCOMPILE", Pkgbuild, 60, "Downloads file and chmod +x with no compilation step", false),
+    let groups: Vec<(&str, Vec<HardcodedDef>)> = vec![
+        ("metadata_analysis", vec![
+            ("M-VOTES-ZERO", Metadata, 30, "Package has zero votes", false),
+            ("M-VOTES-LOW", Metadata, 20, "Package has very few votes", false),
+            ("M-POP-ZERO", Metadata, 25, "Popularity is 0 (no recent usage)", false),
+            ("M-NO-MAINTAINER", Metadata, 20, "Package is orphaned (no maintainer)", false),
+            ("M-NO-URL", Metadata, 15, "No upstream URL provided", false),
+            ("M-NO-LICENSE", Metadata, 10, "No license specified", false),
+            ("M-OUT-OF-DATE", Metadata, 5, "Package is flagged as out of date", false),
+        ]),
+        ("name_analysis", vec![
+            ("B-NAME-IMPERSONATE", Behavioral, 65, "Name looks like impersonation of a popular package", false),
+            ("B-TYPOSQUAT", Behavioral, 55, "Name is suspiciously similar to a popular package", false),
+            ("B-DESC-BRAND-MISMATCH", Behavioral, 35, "Description claims a brand (officially, or in look-alike characters) its upstream URL doesn't mention", false),
+        ]),
+        ("maintainer_analysis", vec![
+            ("B-MAINTAINER-NEW", Behavioral, 30, "Maintainer has only 1 package, created recently", false),
+            ("B-MAINTAINER-SINGLE", Behavioral, 15, "Maintainer has only 1 package", false),
+            ("B-MAINTAINER-BATCH", Behavioral, 45, "Maintainer created 3+ packages in the last 48 hours", false),
+            ("B-MAINTAINER-ACCOUNT-NEW", Behavioral, 30, "Maintainer account registered shortly before submitting the package", false),
+            ("B-MAINTAINER-SUSPENDED", Behavioral, 40, "Maintainer's AUR account is suspended", false),
+        ]),
+        ("orphan_takeover_analysis", vec![
+            ("B-SUBMITTER-CHANGED", Behavioral, 15, "Package maintainer differs from original submitter", false),
+            ("B-ORPHAN-TAKEOVER", Behavioral, 50, "Adopted package with new git author (orphan takeover pattern)", false),
+        ]),
+        ("depends_analysis", vec![
+            ("B-DEPENDS-NETTOOL", Behavioral, 35, "Low-reputation package depends on network/exfiltration tooling unrelated to its description", false),
+            ("B-OPTDEPENDS-NETTOOL", Behavioral, 15, "Low-reputation package optionally depends on network/exfiltration tooling unrelated to its description", false),
+            ("B-SRCINFO-DEPENDS", Behavioral, 50, "Dependencies declared in .SRCINFO but not in the PKGBUILD", false),
+        ]),
+        ("bin_source_verification", vec![
+            ("B-BIN-GITHUB-ORG-MISMATCH", Behavioral, 50, "-bin package source downloads from different GitHub org than upstream", false),
+            ("B-BIN-DOMAIN-MISMATCH", Behavioral, 30, "-bin package source downloads from different domain than upstream", false),
+            ("B-BIN-RELEASE-ASSET-MISSING", Behavioral, 45, "-bin package downloads a release tag/asset not published by the upstream GitHub repo", false),
+        ]),
+        ("git_history_analysis", vec![
+            ("T-SINGLE-COMMIT", Temporal, 20, "Git history has only 1 commit", false),
+            ("T-NEW-PACKAGE", Temporal, 25, "Package is very new (< 7 days old)", false),
+            ("T-MALICIOUS-DIFF", Temporal, 55, "Latest commit introduces network code not present in prior history", false),
+            ("T-AUTHOR-CHANGE", Temporal, 25, "Git history shows multiple different authors", false),
+            ("T-DISPOSABLE-EMAIL", Temporal, 40, "Commit author uses a disposable email domain", false),
+            ("T-IDENTITY-SWITCH-SOURCE", Temporal, 50, "Latest commit by a new identity changes source URLs", false),
+            ("T-COMMIT-ANOMALY", Temporal, 20, "Anomalous commit timing (right after out-of-date flag, rewritten or misdated history)", false),
+            ("T-HISTORY-REWRITE", Temporal, 70, "AUR git history was rewritten: the cached clone's commits are gone from the remote", false),
+            ("T-COMMIT-MSG-MISMATCH", Temporal, 25, "Trivial commit message on a commit that rewrites most of the PKGBUILD", false),
+            ("T-COMMIT-MSG-SPAM", Temporal, 15, "Commit message contains invisible or look-alike (homoglyph) characters", false),
+            ("T-COMMIT-MSG-OTHER-PKG", Temporal, 20, "Recent commit message refers to an unrelated package", false),
+            ("T-COMMIT-MSG-CAMPAIGN", Temporal, 30, "Same commit message pushed to several of the maintainer's packages at once", false),
+        ]),
+        ("aur_comments_analysis", vec![
+            ("M-COMMENTS-SECURITY", Metadata, 40, "Recent AUR comments contain security-related warnings", false),
+        ]),
+        ("github_stars", vec![
+            ("M-GITHUB-STARS-ZERO", Metadata, 20, "Upstream GitHub repo has 0 stars", false),
+            ("M-GITHUB-STARS-LOW", Metadata, 10, "Upstream GitHub repo has very few stars (<10)", false),
+            ("M-GITHUB-NOT-FOUND", Metadata, 25, "Upstream URL points to GitHub but repo does not exist", false),
+        ]),
+        ("pkgbuild_diff_analysis", vec![
+            ("T-DIFF-NEW-SUSPICIOUS", Temporal, 40, "Newly introduced suspicious pattern not in prior version", false),
+            ("T-DIFF-CHECKSUM-REMOVED", Temporal, 35, "Checksum array removed or all entries changed to SKIP", false),
+            ("T-DIFF-SOURCE-DOMAIN-CHANGED", Temporal, 30, "Source URLs changed to a different domain", false),
+            ("T-DIFF-MAJOR-REWRITE", Temporal, 15, ">50% of PKGBUILD lines changed (unusual for version bump)", false),
+        ]),
+        ("pkgver_analysis", vec![
+            ("P-PKGVER-SUSPICIOUS", Pkgbuild, 75, "High-severity pattern inside pkgver() (points boosted)", false),
+            ("P-PKGVER-NETWORK", Pkgbuild, 45, "pkgver() accesses the network", false),
+        ]),
+        ("dkms_analysis", vec![
+            ("P-DKMS-PREBUILT-KO", Pkgbuild, 75, "Prebuilt kernel module in the package repo with no C sources", false),
+            ("P-DKMS-HOOK-SCRIPT", Pkgbuild, 55, "dkms.conf build hook runs a script as root on every kernel update", false),
+            ("P-DKMS-NETWORK", Pkgbuild, 60, "DKMS module source accesses the network", false),
+        ]),
+        ("secrets_analysis", vec![
+            ("P-SECRET-PRIVATE-KEY", Pkgbuild, 50, "Private key committed to the package repo (leaked credential or attacker infrastructure key)", false),
+            ("P-SECRET-AWS-KEY", Pkgbuild, 45, "AWS access key committed to the package repo (leaked credential or attacker infrastructure key)", false),
+            ("P-SECRET-GITHUB-TOKEN", Pkgbuild, 45, "GitHub token committed to the package repo (leaked credential or attacker infrastructure key)", false),
+            ("P-SECRET-NETRC", Pkgbuild, 40, ".netrc credentials committed to the package repo or written by it", false),
+        ]),
+        ("desktop_entry_analysis", vec![
+            ("P-DESKTOP-EXEC-TMP", Pkgbuild, 55, "Desktop entry runs a program from /tmp, /var/tmp or /dev/shm", false),
+            ("P-DESKTOP-EXEC-NETWORK", Pkgbuild, 60, "Desktop entry runs a network command (curl, wget, nc, /dev/tcp)", false),
+            ("P-DESKTOP-HIDDEN-AUTOSTART", Pkgbuild, 40, "Autostart desktop entry hidden from menus (NoDisplay=true)", false),
+        ]),
+        // cargo feature `anomaly`
+        ("anomaly_analysis", vec![
+            ("P-ANOMALY", Pkgbuild, 10, "PKGBUILD is unlike typical AUR packages (experimental anomaly score)", false),
+        ]),
+        ("checksum_analysis", vec![
+            ("P-NO-CHECKSUMS", Pkgbuild, 30, "No checksum array found in PKGBUILD", false),
+            ("P-SKIP-ALL", Pkgbuild, 25, "All checksums are SKIP (no integrity verification)", false),
+            ("P-WEAK-CHECKSUMS", Pkgbuild, 10, "Using weak checksums (md5/sha1) without stronger alternative", false),
+            ("P-CHECKSUM-MISMATCH", Pkgbuild, 25, "Source count != checksum count", false),
+        ]),
+        // package() hygiene
+        ("pkgbuild_analysis", vec![
+            ("P-PACKAGE-OUTSIDE-PKGDIR", Pkgbuild, 55, "package() writes to the live filesystem instead of $pkgdir", false),
+            ("P-PACKAGE-SYSTEM-CMD", Pkgbuild, 50, "package() runs systemctl/useradd/groupadd directly instead of from an .install hook", false),
+        ]),
+        ("shell_analysis", vec![
+            ("SA-VAR-CONCAT-EXEC", Pkgbuild, 85, "Variable concatenation resolves to download-and-execute", true),
+            ("SA-VAR-CONCAT-CMD", Pkgbuild, 55, "Variable concatenation resolves to dangerous command", false),
+            ("SA-INDIRECT-EXEC", Pkgbuild, 70, "Variable with dangerous command in execution position", false),
+            ("SA-CHARBYCHAR-CONSTRUCT", Pkgbuild, 75, "Printf/echo subshell char-by-char command construction", false),
+            ("SA-XOR-DECODE-LOOP", Pkgbuild, 75, "XOR arithmetic turned into characters with printf", false),
+            ("SA-AWK-CHAR-DECODE", Pkgbuild, 65, "awk prints characters computed by arithmetic", false),
+            ("SA-TR-DECODE-EXEC", Pkgbuild, 75, "Executes tr output mapping one large alphabet onto another", false),
+            ("SA-DATA-BLOB-HEX", Pkgbuild, 50, "Embedded long hex string (possible encoded payload)", false),
+            ("SA-DATA-BLOB-BASE64", Pkgbuild, 50, "Embedded long base64 string (possible encoded payload)", false),
+            ("SA-HIGH-ENTROPY-HEREDOC", Pkgbuild, 55, "Heredoc with high entropy content", false),
+            ("SA-HIGH-ENTROPY-STRING", Pkgbuild, 50, "Long quoted string with high entropy (possible encrypted payload)", false),
+            ("SA-HIGH-ENTROPY-FILE", Pkgbuild, 25, "Repo file with high entropy (possible encrypted payload or binary blob)", false),
+            ("SA-BINARY-DOWNLOAD-NOCOMPILE", Pkgbuild, 60, "Downloads file and chmod +x with no compilation step", false),
+            ("SA-OPAQUE-BINARY-NOSTRIP", Pkgbuild, 55, "Non -bin package ships prebuilt binaries with !strip and no build step", false),
+            ("SA-DECODE-THEN-EXEC", Pkgbuild, 85, "Executes a file written earlier by a decode/decrypt/decompress command", false),
+            ("SA-EMBED-PYTHON-SOCKET-EXEC", Pkgbuild, 75, "Python heredoc opens a socket and runs processes (reverse shell)", false),
+            ("SA-EMBED-PYTHON-MEMEXEC", Pkgbuild, 80, "Python heredoc maps executable memory through ctypes", false),
+            ("SA-EMBED-PYTHON-DECODE-EXEC", Pkgbuild, 80, "Python heredoc decodes data and runs it with exec/eval", false),
+            ("SA-EMBED-PERL-SOCKET-EXEC", Pkgbuild, 75, "Perl heredoc opens a socket and runs processes (reverse shell)", false),
+            ("SA-EMBED-PERL-DECODE-EVAL", Pkgbuild, 80, "Perl heredoc decodes data and runs it with eval", false),
+        ]),
+        // oversized files cut down by shared/scan_limits.rs
+        ("coordinator", vec![
+            ("P-SCAN-TRUNCATED", Pkgbuild, 25, "PKGBUILD or install script is too large to analyze in full ([scan] max_file_kb)", false),
+        ]),
+        // shared/known_good.rs, applied by the coordinator
+        ("known_good", vec![
+            ("M-KNOWN-GOOD-HASH", Metadata, 0, "PKGBUILD matches the consensus hash for its version (+10 trust)", false),
+            ("P-KNOWN-GOOD-MISMATCH", Pkgbuild, 70, "PKGBUILD differs from the consensus hash at the same version", false),
+            ("M-KNOWN-GOOD-EDITED", Metadata, 0, "PKGBUILD changed on the AUR at the same version after the consensus hash was taken (informational)", false),
+        ]),
+        // shared/binary_repo.rs, traur scan --repo
+        ("binary_repo", vec![
+            ("P-REPO-PKGBUILD-MISMATCH", Pkgbuild, 60, "Binary repo package was built from a PKGBUILD that differs from the AUR's", false),
+            ("M-REPO-VERSION-DIFFERS", Metadata, 10, "Binary repo package version differs from the AUR's; its PKGBUILD could not be verified", false),
+            ("M-REPO-NO-BUILDINFO-HASH", Metadata, 15, "Binary repo package does not record which PKGBUILD it was built from", false),
+        ]),
+        // shared/threat_intel.rs, with [threat_intel] feeds configured
+        ("threat_intel", vec![
+            ("P-URLHAUS-URL", Pkgbuild, 95, "URL is listed in the URLhaus malware feed", false),
+            ("P-URLHAUS-HOST", Pkgbuild, 85, "Host is listed in the URLhaus malware feed", false),
+            ("P-SPAMHAUS-DBL", Pkgbuild, 80, "Domain is listed in the Spamhaus DBL", false),
+        ]),
+        // traur scan --package-file
+        ("artifact_analysis", vec![
+            ("A-SETUID-BINARY", Pkgbuild, 60, "Built package ships setuid/setgid files", false),
+            ("A-WORLD-WRITABLE", Pkgbuild, 30, "Built package ships world-writable paths", false),
+            ("A-HIDDEN-EXECUTABLE", Pkgbuild, 45, "Built package ships hidden or temp-dir executables", false),
+            ("A-LD-PRELOAD", Pkgbuild, 85, "Built package ships /etc/ld.so.preload", false),
+            ("A-SUDOERS-DROPIN", Pkgbuild, 75, "Built package ships a sudoers drop-in", false),
+            ("A-AUTOSTART-ENTRY", Pkgbuild, 40, "Built package ships an XDG autostart entry", false),
+            ("A-PACMAN-HOOK", Pkgbuild, 50, "Built package ships a pacman hook", false),
+            ("A-PROFILE-SCRIPT", Pkgbuild, 35, "Built package ships a login shell profile script", false),
+            ("A-CRON-ENTRY", Pkgbuild, 45, "Built package ships a cron job", false),
+            ("A-HOME-FILE", Pkgbuild, 50, "Built package ships files into a user home directory", false),
+            ("A-SYSTEMD-UNIT-SUSPICIOUS", Pkgbuild, 60, "Shipped systemd unit executes from a writable path or fetches code", false),
+        ]),
     ];
 
-    defs.into_iter()
-        .map(|(id, cat, pts, desc, gate)| SignalDef {
-            id: id.to_string(),
-            category: cat,
-            points: pts,
-            description: desc.to_string(),
-            is_override_gate: gate,
+    groups
+        .into_iter()
+        .flat_map(|(emitted_by, defs)| {
+            defs.into_iter().map(move |(id, cat, pts, desc, gate)| SignalDef {
+                id: id.to_string(),
+                category: cat,
+                points: pts,
+                description: desc.to_string(),
+                is_override_gate: gate,
+                emitted_by,
+                pattern: None,
+            })
         })
         .collect()
 }
 
-/// Check if a signal ID is known (either exact match or IS-prefixed variant).
+/// Why an override gate sends a package straight to MALICIOUS, by gate family.
+/// None for IDs outside the known families (and for ordinary signals).
+pub fn gate_rationale(id: &str) -> Option<&'static str> {
+    let id = id.strip_prefix("IS-").or_else(|| id.strip_prefix("UP-")).unwrap_or(id);
+    if id.contains("REVSHELL") || id == "P-DEV-UDP" {
+        Some("Connects a shell or raw socket to a remote host; no build or install step needs one")
+    } else if id.contains("BINDSHELL") {
+        Some("Opens a listening shell that anyone who can reach the machine can use")
+    } else if id == "G-TAR-CHECKPOINT" {
+        Some("Makes tar run an arbitrary command mid-extraction, a known way to hide execution")
+    } else if id.contains("PIPE") || id.contains("EXEC") || id == "P-SOURCE-REMOTE" {
+        Some("Runs code fetched at build or install time that no checksum covers and no reviewer of the PKGBUILD sees")
+    } else {
+        None
+    }
+}
+
+/// Check if a signal ID is known (either exact match, or an IS-prefixed install
+/// script or UP-prefixed upstream build script variant).
 pub fn is_known_signal(id: &str) -> bool {
-    let base = id.strip_prefix("IS-").unwrap_or(id);
+    let base = id.strip_prefix("IS-").or_else(|| id.strip_prefix("UP-")).unwrap_or(id);
     all_signal_definitions().iter().any(|d| d.id == base)
 }
 
@@ -127,6 +271,7 @@ pub fn category_from_str(s: &str) -> Option<SignalCategory> {
         "pkgbuild" => Some(SignalCategory::Pkgbuild),
         "behavioral" => Some(SignalCategory::Behavioral),
         "temporal" => Some(SignalCategory::Temporal),
+        "installscript" | "install_script" | "install-script" => Some(SignalCategory::InstallScript),
         _ => None,
     }
 }
@@ -147,10 +292,39 @@ mod tests {
         assert!(defs.len() > 250, "Expected 250+ signals, got {}", defs.len());
     }
 
+    #[test]
+    fn every_signal_has_a_known_emitter() {
+        let features: Vec<&str> = crate::features::FeatureRegistry::builtin().names().collect();
+        for def in all_signal_definitions() {
+            let known = features.contains(&def.emitted_by)
+                || ["coordinator", "known_good", "binary_repo", "threat_intel", "anomaly_analysis"].contains(&def.emitted_by);
+            assert!(known, "{} is emitted by unknown {}", def.id, def.emitted_by);
+        }
+    }
+
+    #[test]
+    fn every_override_gate_has_a_rationale() {
+        for def in all_signal_definitions().iter().filter(|d| d.is_override_gate) {
+            assert!(gate_rationale(&def.id).is_some(), "{} has no gate rationale", def.id);
+        }
+        assert!(gate_rationale("IS-P-CURL-PIPE").is_some());
+    }
+
     #[test]
     fn known_signal_check() {
         assert!(is_known_signal("P-CURL-PIPE"));
         assert!(is_known_signal("IS-SA-VAR-CONCAT-EXEC"));
+        assert!(is_known_signal("UP-P-CURL-PIPE"));
         assert!(!is_known_signal("NONEXISTENT"));
     }
+
+    #[test]
+    fn install_script_patterns_have_their_own_category() {
+        let defs = all_signal_definitions();
+        let category = |id: &str| defs.iter().find(|d| d.id == id).map(|d| d.category);
+        assert_eq!(category("P-INSTALL-CURL"), Some(SignalCategory::InstallScript));
+        assert_eq!(category("P-CURL-PIPE"), Some(SignalCategory::Pkgbuild));
+        assert_eq!(category_from_str("install_script"), Some(SignalCategory::InstallScript));
+        assert_eq!(category_from_str("InstallScript"), Some(SignalCategory::InstallScript));
+    }
 }
@@ -0,0 +1,198 @@
+//! PKGBUILD arrays with per-architecture variants: `source=()` next to
+//! `source_x86_64=()`, `sha256sums=()` next to `sha256sums_aarch64=()`, and so on.
+//!
+//! makepkg builds from the plain array plus the one for the target architecture, so
+//! an analysis that only reads `source=()` misses whatever a package downloads on
+//! x86_64 alone.
+
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// `name=(` or `name_<arch>=(` at the start of a line; `array_body` finds the rest.
+/// The name is lowercase alphanumerics, so `sha256sums_x86_64` splits into
+/// `sha256sums` and `x86_64`.
+static ARRAY_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?m)^([a-z0-9]+)(?:_([A-Za-z0-9_]+))?\s*=\s*\(").unwrap()
+});
+
+/// One quoted or bare word inside an array.
+static ENTRY_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"'([^']*)'|"([^"]*)"|([^\s'"()]+)"#).unwrap()
+});
+
+/// Command substitution or array expansion: the entries are only known at build time.
+static DYNAMIC_BASH_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\$\(|`|\$\{[^}]*\[@\]|\$\{[^}]*\[\*\]").unwrap()
+});
+
+/// One array as written in the PKGBUILD.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BashArray {
+    /// `source`, `sha256sums`, ...
+    pub name: String,
+    /// `x86_64` for `source_x86_64`; `None` for the array every architecture uses.
+    pub arch: Option<String>,
+    /// Text between the parentheses.
+    pub body: String,
+    /// Entries with quotes removed; comment lines are skipped.
+    pub entries: Vec<String>,
+    /// Whether the body uses command substitution or array expansion, so `entries`
+    /// need not be what makepkg sees.
+    pub dynamic: bool,
+}
+
+impl BashArray {
+    /// `source_x86_64`, or `source` for the common array.
+    pub fn full_name(&self) -> String {
+        match &self.arch {
+            Some(arch) => format!("{}_{arch}", self.name),
+            None => self.name.clone(),
+        }
+    }
+}
+
+/// Every `name=()` / `name_<arch>=()` array in `content`, in order.
+pub fn parse_arrays(content: &str, name: &str) -> Vec<BashArray> {
+    ARRAY_RE
+        .captures_iter(content)
+        .filter(|caps| &caps[1] == name)
+        .filter_map(|caps| {
+            let body = array_body(&content[caps.get(0)?.end()..])?.to_string();
+            Some(BashArray {
+                name: name.to_string(),
+                arch: caps.get(2).map(|m| m.as_str().to_string()),
+                entries: entries(&body),
+                dynamic: is_dynamic(&body),
+                body,
+            })
+        })
+        .collect()
+}
+
+/// The body of an array, given the text right after its `(`: everything up to the
+/// matching `)`. Quoted strings, `#` comments and nested `$(...)` are stepped over, so
+/// a `)` inside them doesn't end the array. None when the array is never closed.
+pub fn array_body(rest: &str) -> Option<&str> {
+    let mut depth = 0usize;
+    let mut quote = None;
+    let mut comment = false;
+    let mut prev = ' ';
+    for (i, c) in rest.char_indices() {
+        if comment {
+            comment = c != '\n';
+        } else if let Some(q) = quote {
+            if c == q && !(q == '"' && prev == '\\') {
+                quote = None;
+            }
+        } else {
+            match c {
+                '\'' | '"' => quote = Some(c),
+                '#' if prev.is_whitespace() || prev == '(' => comment = true,
+                '(' => depth += 1,
+                ')' if depth == 0 => return Some(&rest[..i]),
+                ')' => depth -= 1,
+                _ => {}
+            }
+        }
+        prev = c;
+    }
+    None
+}
+
+/// Whether an array body uses command substitution or array expansion.
+pub fn is_dynamic(body: &str) -> bool {
+    DYNAMIC_BASH_RE.is_match(body)
+}
+
+/// Entries of an array body, quotes removed, stopping each line at a `#` comment.
+pub fn entries(body: &str) -> Vec<String> {
+    let mut entries = Vec::new();
+    for line in body.lines() {
+        for caps in ENTRY_RE.captures_iter(line) {
+            let Some(entry) = caps.get(1).or_else(|| caps.get(2)).or_else(|| caps.get(3)) else {
+                continue;
+            };
+            if caps.get(3).is_some() && entry.as_str().starts_with('#') {
+                break;
+            }
+            entries.push(entry.as_str().to_string());
+        }
+    }
+    entries
+}
+
+/// Download URLs per source array, `None` first when present. `name::` rename prefixes
+/// and VCS prefixes (`git+https://` -> `https://`) are removed; local files are left out.
+/// Variables are not expanded.
+pub fn source_urls(content: &str) -> Vec<(Option<String>, Vec<String>)> {
+    let mut arrays = parse_arrays(content, "source");
+    arrays.sort_by_key(|a| a.arch.is_some());
+    arrays
+        .into_iter()
+        .map(|array| {
+            let urls = array.entries.iter().filter_map(|entry| entry_url(entry)).collect();
+            (array.arch, urls)
+        })
+        .collect()
+}
+
+/// The URL in a source entry, without rename or VCS prefix.
+pub fn entry_url(entry: &str) -> Option<String> {
+    let url = entry.split_once("::").map_or(entry, |(_, url)| url);
+    if !url.contains("://") {
+        return None;
+    }
+    let url = match url.split_once("://") {
+        Some((scheme, rest)) if scheme.contains('+') => {
+            format!("{}://{rest}", scheme.rsplit('+').next().unwrap_or(scheme))
+        }
+        _ => url.to_string(),
+    };
+    Some(url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PKGBUILD: &str = "pkgname=foo-bin
+source=('foo.desktop'
+        # upstream tarball
+        \"foo-$pkgver.tar.gz::https://dl.example.org/foo-$pkgver.tar.gz\")
+source_x86_64=(git+https://github.com/owner/foo.git#tag=v1)
+source_aarch64=(https://mirror.example.net/foo-arm.tar.gz)
+sha256sums=('SKIP' 'abc')
+sha256sums_x86_64=('SKIP')
+";
+
+    #[test]
+    fn parses_common_and_arch_arrays() {
+        let arrays = parse_arrays(PKGBUILD, "source");
+        assert_eq!(arrays.len(), 3);
+        assert_eq!(arrays[0].arch, None);
+        assert_eq!(arrays[0].entries, ["foo.desktop", "foo-$pkgver.tar.gz::https://dl.example.org/foo-$pkgver.tar.gz"]);
+        assert_eq!(arrays[1].full_name(), "source_x86_64");
+        assert_eq!(parse_arrays(PKGBUILD, "sha256sums")[1].entries, ["SKIP"]);
+        assert!(parse_arrays("source=($(ls *.patch))", "source")[0].dynamic);
+    }
+
+    #[test]
+    fn array_ends_at_its_own_paren() {
+        let pkgbuild = "source=(foo.tar.gz # mirror (see below)\n  'bar).patch' \"baz (1).diff\")\nsha256sums=(SKIP)\n";
+        assert_eq!(parse_arrays(pkgbuild, "source")[0].entries, ["foo.tar.gz", "bar).patch", "baz (1).diff"]);
+        assert_eq!(array_body("a $(ls) b) c"), Some("a $(ls) b"));
+        assert_eq!(array_body("a 'b)"), None);
+    }
+
+    #[test]
+    fn extracts_urls_per_arch() {
+        assert_eq!(
+            source_urls(PKGBUILD),
+            [
+                (None, vec!["https://dl.example.org/foo-$pkgver.tar.gz".to_string()]),
+                (Some("x86_64".to_string()), vec!["https://github.com/owner/foo.git#tag=v1".to_string()]),
+                (Some("aarch64".to_string()), vec!["https://mirror.example.net/foo-arm.tar.gz".to_string()]),
+            ]
+        );
+    }
+}
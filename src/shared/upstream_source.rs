@@ -0,0 +1,309 @@
+//! Upstream source tarballs for `[scan] deep` (`traur scan --deep`).
+//!
+//! A clean PKGBUILD can still build from a compromised personal fork. Deep mode
+//! downloads the GitHub tarball the PKGBUILD's `source=()` points at, up to
+//! `MAX_TARBALL_BYTES`, and reads the build scripts at the top of the tree (Makefile,
+//! configure, setup.py, build.rs, ...) for `upstream_source_analysis`. Archives are
+//! read with bsdtar, like package files (`shared/package_file.rs`).
+
+use crate::shared::github::parse_github_url;
+use crate::shared::models::ArtifactEntry;
+use crate::shared::{package_file, runtime, source_arrays};
+use regex::Regex;
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::LazyLock;
+use std::time::Duration;
+
+/// Largest tarball that is downloaded; bigger ones are skipped.
+pub const MAX_TARBALL_BYTES: u64 = 25 * 1024 * 1024;
+
+/// Archives listing more members than this are not read (a tarball of empty files
+/// can hold millions).
+const MAX_ARCHIVE_ENTRIES: usize = 100_000;
+
+/// Build scripts larger than this are skipped.
+const MAX_BUILD_SCRIPT_BYTES: u64 = 256 * 1024;
+
+/// Total bytes of build scripts extracted from one archive.
+const MAX_BUILD_FILES_BYTES: u64 = 1024 * 1024;
+
+/// Time a tarball download may take.
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Build scripts read from the top of the upstream tree.
+pub const BUILD_SCRIPTS: &[&str] = &[
+    "Makefile", "GNUmakefile", "configure", "setup.py", "build.rs", "CMakeLists.txt", "meson.build",
+];
+
+/// Simple top-level `name=value` assignments, for expanding source URLs.
+static ASSIGN_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?m)^([A-Za-z_][A-Za-z0-9_]*)=(?:"([^"$`]*)"|'([^']*)'|([^\s'"()$`;]+))\s*$"#).unwrap()
+});
+
+/// `$name` or `${name}`.
+static VAR_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\$\{([A-Za-z_][A-Za-z0-9_]*)\}|\$([A-Za-z_][A-Za-z0-9_]*)").unwrap());
+
+/// Archive names GitHub serves under `/archive/`.
+static ARCHIVE_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)^https://(?:github\.com/[^/]+/[^/]+/archive/|codeload\.github\.com/)\S+$").unwrap());
+
+/// URL of the GitHub tarball the PKGBUILD builds from: a `github.com/.../archive/...`
+/// or `codeload.github.com` download, or a `git+https://github.com/...` source as the
+/// archive of its `#tag=`, `#commit=` or `#branch=` (HEAD without one). The common
+/// `source=()` comes before arch-specific arrays. None if no GitHub source resolves
+/// without unknown variables.
+pub fn tarball_url(pkgbuild: &str) -> Option<String> {
+    let vars: HashMap<&str, &str> = ASSIGN_RE
+        .captures_iter(pkgbuild)
+        .filter_map(|caps| {
+            let value = caps.get(2).or_else(|| caps.get(3)).or_else(|| caps.get(4))?;
+            Some((caps.get(1)?.as_str(), value.as_str()))
+        })
+        .collect();
+
+    let mut arrays = source_arrays::parse_arrays(pkgbuild, "source");
+    arrays.sort_by_key(|a| a.arch.is_some());
+    arrays
+        .iter()
+        .flat_map(|a| &a.entries)
+        .filter_map(|entry| {
+            let entry = expand(entry, &vars)?;
+            let entry = entry.split_once("::").map_or(entry.as_str(), |(_, url)| url);
+            github_tarball(entry)
+        })
+        .next()
+}
+
+/// `entry` with known variables substituted; None if any is unknown or it runs a command.
+fn expand(entry: &str, vars: &HashMap<&str, &str>) -> Option<String> {
+    let mut unknown = false;
+    let expanded = VAR_RE.replace_all(entry, |caps: &regex::Captures| {
+        let name = caps.get(1).or_else(|| caps.get(2)).map_or("", |m| m.as_str());
+        vars.get(name).copied().unwrap_or_else(|| {
+            unknown = true;
+            ""
+        })
+    });
+    (!unknown && !expanded.contains(['$', '`'])).then(|| expanded.into_owned())
+}
+
+fn github_tarball(url: &str) -> Option<String> {
+    if let Some(git) = url.strip_prefix("git+") {
+        if !git.to_lowercase().starts_with("https://github.com/") {
+            return None;
+        }
+        let (repo_url, fragment) = git.split_once('#').unwrap_or((git, ""));
+        let (owner, repo) = parse_github_url(repo_url)?;
+        let reference = fragment
+            .split('&')
+            .find_map(|kv| match kv.split_once('=') {
+                Some(("tag" | "commit" | "branch", value)) if !value.is_empty() => Some(value),
+                _ => None,
+            })
+            .unwrap_or("HEAD");
+        return Some(format!("https://github.com/{owner}/{repo}/archive/{reference}.tar.gz"));
+    }
+    ARCHIVE_RE.is_match(url).then(|| url.to_string())
+}
+
+/// Download `url`, giving up once it exceeds `max_bytes`.
+pub async fn download(url: &str, max_bytes: u64) -> Result<Vec<u8>, String> {
+    let mut response = runtime::client()
+        .get(url)
+        .timeout(DOWNLOAD_TIMEOUT)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Failed to download {url}: {e}"))?;
+    let too_large = || format!("{url} is larger than {} MiB, skipped", max_bytes / (1024 * 1024));
+    if response.content_length().is_some_and(|len| len > max_bytes) {
+        return Err(too_large());
+    }
+    let mut bytes = Vec::new();
+    while let Some(chunk) = response.chunk().await.map_err(|e| format!("Failed to download {url}: {e}"))? {
+        bytes.extend_from_slice(&chunk);
+        if bytes.len() as u64 > max_bytes {
+            return Err(too_large());
+        }
+    }
+    Ok(bytes)
+}
+
+/// The top-level build scripts in an archive, as (path in the archive, content).
+pub fn build_files(archive: &[u8]) -> Result<Vec<(String, String)>, String> {
+    let path = write_temp_archive(archive)?;
+    let path_str = path.to_string_lossy().to_string();
+
+    let result = Command::new("bsdtar")
+        .args(["-tvf", &path_str])
+        .output()
+        .map_err(|e| format!("failed to run bsdtar: {e}"))
+        .and_then(|listing| {
+            if !listing.status.success() {
+                return Err("bsdtar could not read the upstream tarball".to_string());
+            }
+            let listed = package_file::parse_listing(&String::from_utf8_lossy(&listing.stdout));
+            Ok(readable_build_scripts(&listed)?
+                .into_iter()
+                .filter_map(|member| Some((member.to_string(), package_file::extract_file(&path_str, member)?)))
+                .collect())
+        });
+    let _ = std::fs::remove_file(&path);
+    result
+}
+
+/// Write `archive` to a new file in the temp dir for bsdtar. The name is predictable,
+/// so the file is created exclusively (never through an existing file or symlink).
+fn write_temp_archive(archive: &[u8]) -> Result<PathBuf, String> {
+    static SEQ: AtomicUsize = AtomicUsize::new(0);
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    for _ in 0..100 {
+        let path = std::env::temp_dir().join(format!(
+            "traur-upstream-{}-{}.tar",
+            std::process::id(),
+            SEQ.fetch_add(1, Ordering::Relaxed)
+        ));
+        match options.open(&path) {
+            Ok(mut file) => {
+                return match file.write_all(archive) {
+                    Ok(()) => Ok(path),
+                    Err(e) => {
+                        let _ = std::fs::remove_file(&path);
+                        Err(format!("Failed to write {}: {e}", path.display()))
+                    }
+                };
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(format!("Failed to create {}: {e}", path.display())),
+        }
+    }
+    Err("Failed to create a temporary file for the upstream tarball".to_string())
+}
+
+/// The regular-file build scripts of a `bsdtar -tv` listing that get extracted:
+/// each at most `MAX_BUILD_SCRIPT_BYTES`, together at most `MAX_BUILD_FILES_BYTES`.
+/// An error when the archive lists more than `MAX_ARCHIVE_ENTRIES` members.
+fn readable_build_scripts(listed: &[(ArtifactEntry, u64)]) -> Result<Vec<&str>, String> {
+    if listed.len() > MAX_ARCHIVE_ENTRIES {
+        return Err(format!("upstream tarball lists more than {MAX_ARCHIVE_ENTRIES} entries, skipped"));
+    }
+    let files: Vec<&str> = listed
+        .iter()
+        .filter(|(entry, size)| entry.mode.starts_with('-') && *size <= MAX_BUILD_SCRIPT_BYTES)
+        .map(|(entry, _)| entry.path.as_str())
+        .collect();
+    let sizes: HashMap<&str, u64> = listed.iter().map(|(entry, size)| (entry.path.as_str(), *size)).collect();
+    let mut total = 0;
+    Ok(build_scripts(&files)
+        .into_iter()
+        .filter(|member| {
+            let fits = total + sizes[member] <= MAX_BUILD_FILES_BYTES;
+            if fits {
+                total += sizes[member];
+            }
+            fits
+        })
+        .collect())
+}
+
+/// Members of an archive listing that are `BUILD_SCRIPTS` at the top of the tree,
+/// which in a GitHub archive is inside its single `<repo>-<ref>/` directory.
+fn build_scripts<'a>(listing: &[&'a str]) -> Vec<&'a str> {
+    listing
+        .iter()
+        .copied()
+        .filter(|member| {
+            let parts: Vec<&str> = member.trim_start_matches("./").split('/').collect();
+            let name = match parts.as_slice() {
+                [name] | [_, name] => name,
+                _ => return false,
+            };
+            BUILD_SCRIPTS.contains(name)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_github_archive_and_git_sources() {
+        let pkgbuild = "pkgname=foo\npkgver=1.2.3\n_commit=abc123\n\
+            source=(\"$pkgname-$pkgver.tar.gz::https://github.com/someone/foo/archive/v${pkgver}.tar.gz\")\n";
+        assert_eq!(tarball_url(pkgbuild).as_deref(), Some("https://github.com/someone/foo/archive/v1.2.3.tar.gz"));
+
+        let pkgbuild = "pkgname=foo\nsource=('foo.desktop' \"git+https://github.com/someone/foo.git#commit=$_commit\")\n_commit=abc123\n";
+        assert_eq!(tarball_url(pkgbuild).as_deref(), Some("https://github.com/someone/foo/archive/abc123.tar.gz"));
+
+        let pkgbuild = "source=(git+https://github.com/someone/foo)\n";
+        assert_eq!(tarball_url(pkgbuild).as_deref(), Some("https://github.com/someone/foo/archive/HEAD.tar.gz"));
+    }
+
+    #[test]
+    fn no_tarball_for_other_hosts_or_unknown_variables() {
+        assert_eq!(tarball_url("source=(https://example.org/foo-1.0.tar.gz)\n"), None);
+        assert_eq!(tarball_url("source=(https://github.com/a/b/releases/download/v1/b-linux.tar.gz)\n"), None);
+        assert_eq!(tarball_url("source=(https://github.com/a/b/archive/v$(date).tar.gz)\n"), None);
+        assert_eq!(tarball_url("source=(\"https://github.com/a/b/archive/v$unset.tar.gz\")\n"), None);
+    }
+
+    #[test]
+    fn picks_top_level_build_scripts() {
+        let listing = [
+            "foo-1.2.3/",
+            "foo-1.2.3/Makefile",
+            "foo-1.2.3/configure",
+            "foo-1.2.3/src/Makefile",
+            "foo-1.2.3/setup.py.in",
+            "foo-1.2.3/build.rs",
+        ];
+        assert_eq!(build_scripts(&listing), ["foo-1.2.3/Makefile", "foo-1.2.3/configure", "foo-1.2.3/build.rs"]);
+    }
+
+    fn listed(entries: &[(&str, &str, u64)]) -> Vec<(ArtifactEntry, u64)> {
+        entries
+            .iter()
+            .map(|&(mode, path, size)| (ArtifactEntry { path: path.to_string(), mode: mode.to_string() }, size))
+            .collect()
+    }
+
+    #[test]
+    fn build_scripts_are_capped_by_size_and_entry_count() {
+        let archive = listed(&[
+            ("drwxr-xr-x", "foo-1.0", 0),
+            ("-rw-r--r--", "foo-1.0/Makefile", 4096),
+            ("-rwxr-xr-x", "foo-1.0/configure", MAX_BUILD_SCRIPT_BYTES + 1),
+            ("lrwxrwxrwx", "foo-1.0/build.rs", 0),
+            ("-rw-r--r--", "foo-1.0/setup.py", MAX_BUILD_SCRIPT_BYTES),
+            ("-rw-r--r--", "foo-1.0/meson.build", MAX_BUILD_SCRIPT_BYTES),
+            ("-rw-r--r--", "foo-1.0/CMakeLists.txt", MAX_BUILD_SCRIPT_BYTES),
+            ("-rw-r--r--", "foo-1.0/GNUmakefile", MAX_BUILD_SCRIPT_BYTES),
+        ]);
+        assert_eq!(
+            readable_build_scripts(&archive).unwrap(),
+            ["foo-1.0/Makefile", "foo-1.0/setup.py", "foo-1.0/meson.build", "foo-1.0/CMakeLists.txt"]
+        );
+
+        let bomb = vec![(ArtifactEntry { path: "x".into(), mode: "-rw-r--r--".into() }, 0); MAX_ARCHIVE_ENTRIES + 1];
+        assert!(readable_build_scripts(&bomb).unwrap_err().contains("entries"));
+    }
+
+    #[test]
+    fn temp_archives_get_fresh_files() {
+        let first = write_temp_archive(b"one").unwrap();
+        let second = write_temp_archive(b"two").unwrap();
+        let contents = (std::fs::read(&first).unwrap(), std::fs::read(&second).unwrap());
+        let _ = std::fs::remove_file(&first);
+        let _ = std::fs::remove_file(&second);
+        assert_ne!(first, second);
+        assert_eq!(contents, (b"one".to_vec(), b"two".to_vec()));
+    }
+}
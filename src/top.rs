@@ -0,0 +1,263 @@
+use crate::coordinator::{self, Deadline};
+use crate::shared::bulk::{
+    batch_fetch_metadata, clone_with_retry, prefetch_maintainer_packages, prefetch_upstream,
+};
+use crate::shared::config::{self, Config};
+use crate::shared::{aur_git, cache, known_good};
+use crate::shared::scoring::Tier;
+use colored::Colorize;
+use indicatif::{ProgressBar, ProgressStyle};
+use rayon::prelude::*;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::time::SystemTime;
+
+/// One line of the `traur top` table.
+#[derive(Debug, Clone, Serialize)]
+struct TopRow {
+    package: String,
+    score: u32,
+    tier: Tier,
+    votes: u32,
+    maintainer: Option<String>,
+    /// Days since traur last fetched this package, measured before this run refreshed it.
+    days_since_inspected: Option<u64>,
+}
+
+/// Scan all installed AUR packages and print them ranked riskiest-first.
+pub fn run(limit: Option<usize>, jobs: usize, json: bool, config: &Config) -> i32 {
+    let mut names = match crate::get_installed_aur_packages() {
+        Ok(names) if names.is_empty() => {
+            eprintln!("No AUR packages installed.");
+            return 0;
+        }
+        Ok(names) => names,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            return 1;
+        }
+    };
+
+    eprintln!("  Fetching package metadata for {} installed packages...", names.len());
+    let metadata = batch_fetch_metadata(&names);
+    names.retain(|n| metadata.contains_key(n.as_str()));
+    // Plain whitelist entries are skipped up front, pinned ones after the clone
+    let before = names.len();
+    names.retain(|n| !config::is_whitelisted_in(config, n));
+    let whitelisted_count = before - names.len();
+    if whitelisted_count > 0 {
+        eprintln!("  Skipping {whitelisted_count} whitelisted packages");
+    }
+    let maintainer_packages = prefetch_maintainer_packages(&metadata, config);
+    let upstream = prefetch_upstream(&metadata, config);
+
+    // Read cache ages before scanning — the scan itself refreshes every repo.
+    let now = SystemTime::now();
+    let inspected: HashMap<&str, u64> = names
+        .iter()
+        .filter_map(|name| {
+            let base = metadata[name].package_base.as_deref().unwrap_or(name);
+            let age = now.duration_since(cache::last_inspected(base)?).ok()?;
+            Some((name.as_str(), age.as_secs() / 86400))
+        })
+        .collect();
+
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(jobs)
+        .build()
+        .expect("Failed to build thread pool");
+
+    let pb = ProgressBar::new(names.len() as u64);
+    pb.set_style(
+        ProgressStyle::default_bar()
+            .template("[{elapsed_precise}] {bar:40.cyan/blue} {pos}/{len} ({per_sec})")
+            .unwrap()
+            .progress_chars("##-"),
+    );
+
+    let rows: Vec<TopRow> = pool.install(|| {
+        names
+            .par_iter()
+            .filter_map(|name| {
+                let meta = metadata.get(name)?.clone();
+                let maint_pkgs = meta
+                    .maintainer
+                    .as_deref()
+                    .and_then(|m| maintainer_packages.get(m))
+                    .cloned()
+                    .unwrap_or_default();
+                let upstream_data = upstream.get(name).cloned().unwrap_or_default();
+                let votes = meta.num_votes;
+                let maintainer = meta.maintainer.clone();
+
+                let deadline = Deadline::start(config);
+
+                let row = match clone_with_retry(name, meta, maint_pkgs, upstream_data, deadline, config) {
+                    Ok(ctx) if whitelisted(config, name, ctx.pkgbuild_content.as_deref()) => None,
+                    Ok(mut ctx) => {
+                        coordinator::use_helper_baseline(&mut ctx);
+                        let scan = coordinator::run_analysis_within(&ctx, config, deadline);
+                        Some(TopRow {
+                            package: scan.package,
+                            score: scan.score,
+                            tier: scan.tier,
+                            votes,
+                            maintainer,
+                            days_since_inspected: inspected.get(name.as_str()).copied(),
+                        })
+                    }
+                    Err(e) => {
+                        pb.suspend(|| eprintln!("  error: {name}: {e}"));
+                        None
+                    }
+                };
+                pb.inc(1);
+                row
+            })
+            .collect()
+    });
+
+    pb.finish_and_clear();
+
+    let rows = rank(rows, limit);
+
+    if json {
+        let json_str = serde_json::to_string_pretty(&rows).expect("Failed to serialize");
+        println!("{json_str}");
+    } else {
+        print_table(&rows);
+    }
+
+    0
+}
+
+/// Whether a package stays out of the ranking, as in the hook: plain whitelist entries
+/// always, pinned ones while the PKGBUILD still hashes to the pin.
+fn whitelisted(config: &Config, package: &str, pkgbuild: Option<&str>) -> bool {
+    if config::is_whitelisted_in(config, package) {
+        return true;
+    }
+    let Some(pin) = config::whitelist_pin(config, package) else {
+        return false;
+    };
+    pkgbuild.is_some_and(|p| known_good::sha256_hex(&aur_git::strip_inlined(p)) == pin)
+}
+
+/// Sort riskiest (lowest trust score) first; ties broken by fewer votes, then name.
+fn rank(mut rows: Vec<TopRow>, limit: Option<usize>) -> Vec<TopRow> {
+    rows.sort_by(|a, b| {
+        a.score
+            .cmp(&b.score)
+            .then(a.votes.cmp(&b.votes))
+            .then_with(|| a.package.cmp(&b.package))
+    });
+    if let Some(n) = limit {
+        rows.truncate(n);
+    }
+    rows
+}
+
+fn print_table(rows: &[TopRow]) {
+    let name_width = rows.iter().map(|r| r.package.len()).max().unwrap_or(0).max(7);
+    let maint_width = rows
+        .iter()
+        .map(|r| r.maintainer.as_deref().unwrap_or("(orphan)").len())
+        .max()
+        .unwrap_or(0)
+        .max(10);
+
+    println!(
+        "{}",
+        format!(
+            "{:>5}  {:<10}  {:>6}  {:<maint_width$}  {:>9}  {:<name_width$}",
+            "SCORE", "TIER", "VOTES", "MAINTAINER", "INSPECTED", "PACKAGE"
+        )
+        .bold()
+    );
+    for row in rows {
+        let tier = format!("{:<10}", row.tier.to_string());
+        let tier = match row.tier {
+            Tier::Trusted => tier.green(),
+            Tier::Ok => tier.yellow(),
+            Tier::Sketchy => tier.truecolor(255, 165, 0),
+            Tier::Suspicious => tier.red(),
+            Tier::Malicious => tier.red().bold(),
+        };
+        println!(
+            "{:>5}  {}  {:>6}  {:<maint_width$}  {:>9}  {}",
+            row.score,
+            tier,
+            row.votes,
+            row.maintainer.as_deref().unwrap_or("(orphan)"),
+            format_age(row.days_since_inspected),
+            row.package,
+        );
+    }
+}
+
+/// Human-readable age for the INSPECTED column.
+fn format_age(days: Option<u64>) -> String {
+    match days {
+        None => "never".to_string(),
+        Some(0) => "today".to_string(),
+        Some(d) => format!("{d}d ago"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(package: &str, score: u32, votes: u32) -> TopRow {
+        TopRow {
+            package: package.to_string(),
+            score,
+            tier: Tier::Ok,
+            votes,
+            maintainer: None,
+            days_since_inspected: None,
+        }
+    }
+
+    #[test]
+    fn ranks_lowest_score_first() {
+        let rows = vec![row("safe", 90, 100), row("risky", 30, 5), row("meh", 55, 10)];
+        let ranked: Vec<String> = rank(rows, None).into_iter().map(|r| r.package).collect();
+        assert_eq!(ranked, ["risky", "meh", "safe"]);
+    }
+
+    #[test]
+    fn ties_broken_by_votes() {
+        let rows = vec![row("popular", 50, 500), row("obscure", 50, 1)];
+        assert_eq!(rank(rows, None)[0].package, "obscure");
+    }
+
+    #[test]
+    fn limit_truncates() {
+        let rows = vec![row("a", 10, 0), row("b", 20, 0), row("c", 30, 0)];
+        assert_eq!(rank(rows, Some(2)).len(), 2);
+    }
+
+    #[test]
+    fn whitelisted_packages_are_left_out() {
+        let mut config = Config::default();
+        config.whitelist.packages = vec!["plain".to_string(), "pinned".to_string()];
+        let pkgbuild = "pkgname=pinned\npkgver=1.0\n";
+        config
+            .whitelist
+            .pins
+            .insert("pinned".to_string(), known_good::sha256_hex(&aur_git::strip_inlined(pkgbuild)));
+
+        assert!(whitelisted(&config, "plain", None));
+        assert!(whitelisted(&config, "pinned", Some(pkgbuild)));
+        assert!(!whitelisted(&config, "pinned", Some("pkgname=pinned\npkgver=1.1\n")));
+        assert!(!whitelisted(&config, "other", Some(pkgbuild)));
+    }
+
+    #[test]
+    fn formats_age() {
+        assert_eq!(format_age(None), "never");
+        assert_eq!(format_age(Some(0)), "today");
+        assert_eq!(format_age(Some(12)), "12d ago");
+    }
+}
@@ -0,0 +1,266 @@
+use crate::coordinator::{self, Deadline};
+use crate::shared::bulk::{
+    batch_fetch_metadata, clone_with_retry, prefetch_maintainer_packages, prefetch_upstream,
+};
+use crate::shared::config::Config;
+use crate::shared::models::AurPackage;
+use crate::shared::output::{self, OutputFormat, RenderOptions};
+use crate::shared::scoring::{ScanResult, Tier};
+use colored::Colorize;
+use indicatif::{ProgressBar, ProgressStyle};
+use rayon::prelude::*;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::Command;
+
+/// pacman's local database; each installed package has a `<name>-<version>/desc` file.
+const LOCAL_DB_PATH: &str = "/var/lib/pacman/local";
+
+/// An installed AUR package whose AUR entry changed after it was installed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct PendingUpdate {
+    name: String,
+    installed_version: String,
+    aur_version: String,
+}
+
+#[derive(Debug, Serialize)]
+struct UpgradeFinding {
+    package: String,
+    installed_version: String,
+    aur_version: String,
+    scan: ScanResult,
+}
+
+/// Scan the AUR updates pending for installed foreign packages, before upgrading.
+pub fn run(jobs: usize, json: bool, config: &Config) -> i32 {
+    let installed = match installed_foreign() {
+        Ok(p) => p,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            return 1;
+        }
+    };
+
+    if installed.is_empty() {
+        eprintln!("No foreign packages installed.");
+        return 0;
+    }
+
+    let names: Vec<String> = installed.iter().map(|(name, _)| name.clone()).collect();
+    eprintln!("{}", format!("Checking {} foreign packages for AUR updates...", names.len()).bold());
+    let metadata = batch_fetch_metadata(&names);
+    let install_dates: HashMap<String, u64> = installed
+        .iter()
+        .filter_map(|(name, version)| Some((name.clone(), install_date(name, version)?)))
+        .collect();
+
+    let pending = pending_updates(&installed, &install_dates, &metadata);
+    if pending.is_empty() {
+        if json {
+            println!("[]");
+        } else {
+            println!("{}", "No pending AUR updates.".green());
+        }
+        return 0;
+    }
+
+    eprintln!("{}", format!("Scanning {} pending updates...", pending.len()).bold());
+    let maintainer_packages = prefetch_maintainer_packages(&metadata, config);
+    let upstream = prefetch_upstream(&metadata, config);
+
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(jobs)
+        .build()
+        .expect("Failed to build thread pool");
+
+    let pb = ProgressBar::new(pending.len() as u64);
+    pb.set_style(
+        ProgressStyle::default_bar()
+            .template("[{elapsed_precise}] {bar:40.cyan/blue} {pos}/{len} ({per_sec})")
+            .unwrap()
+            .progress_chars("##-"),
+    );
+
+    let mut findings: Vec<UpgradeFinding> = pool.install(|| {
+        pending
+            .par_iter()
+            .filter_map(|update| {
+                let meta = metadata.get(&update.name)?.clone();
+                let maint_pkgs = meta
+                    .maintainer
+                    .as_deref()
+                    .and_then(|m| maintainer_packages.get(m))
+                    .cloned()
+                    .unwrap_or_default();
+                let upstream_data = upstream.get(&update.name).cloned().unwrap_or_default();
+
+                let deadline = Deadline::start(config);
+
+                let finding = match clone_with_retry(&update.name, meta, maint_pkgs, upstream_data, deadline, config) {
+                    Ok(mut ctx) => {
+                        // Diff against the installed version so the report covers everything
+                        // the upgrade brings in, not just the latest commit.
+                        coordinator::use_installed_baseline(&mut ctx, &update.installed_version);
+                        Some(UpgradeFinding {
+                            package: update.name.clone(),
+                            installed_version: update.installed_version.clone(),
+                            aur_version: update.aur_version.clone(),
+                            scan: coordinator::run_analysis_within(&ctx, config, deadline),
+                        })
+                    }
+                    Err(e) => {
+                        pb.suspend(|| eprintln!("  error: {}: {e}", update.name));
+                        None
+                    }
+                };
+                pb.inc(1);
+                finding
+            })
+            .collect()
+    });
+
+    pb.finish_and_clear();
+    findings.sort_by(|a, b| b.scan.tier.cmp(&a.scan.tier).then_with(|| a.package.cmp(&b.package)));
+
+    if json {
+        let json_str = serde_json::to_string_pretty(&findings).expect("Failed to serialize");
+        println!("{json_str}");
+    } else {
+        print_findings(&findings, pending.len());
+    }
+
+    if findings.iter().any(|f| f.scan.tier >= Tier::Suspicious) { 1 } else { 0 }
+}
+
+fn print_findings(findings: &[UpgradeFinding], pending: usize) {
+    for finding in findings {
+        println!();
+        output::print(&finding.scan, OutputFormat::Text, &RenderOptions::default());
+        println!("  {} -> {}", finding.installed_version, finding.aur_version);
+    }
+
+    let flagged = findings.iter().filter(|f| f.scan.tier >= Tier::Suspicious).count();
+    println!();
+    println!("{}", "=== traur upgrade-check results ===".bold());
+    println!("  Pending updates: {pending}");
+    println!("  Scanned:         {}", findings.len());
+    if flagged == 0 {
+        println!("  {}", "No suspicious updates.".green());
+    } else {
+        println!("  {}", format!("Review {flagged} update(s) before upgrading.").red().bold());
+    }
+}
+
+/// Installed foreign packages and their versions (`pacman -Qm`).
+fn installed_foreign() -> Result<Vec<(String, String)>, String> {
+    let output = Command::new("pacman")
+        .args(["-Qm"])
+        .output()
+        .map_err(|e| format!("Failed to run pacman: {e}"))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("pacman -Qm failed: {stderr}"));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let (name, version) = line.split_once(' ')?;
+            Some((name.to_string(), version.trim().to_string()))
+        })
+        .collect())
+}
+
+/// When the installed version was installed, from pacman's local database.
+fn install_date(name: &str, version: &str) -> Option<u64> {
+    let desc = std::fs::read_to_string(Path::new(LOCAL_DB_PATH).join(format!("{name}-{version}")).join("desc")).ok()?;
+    parse_install_date(&desc)
+}
+
+/// The `%INSTALLDATE%` field of a local database `desc` file (Unix seconds).
+fn parse_install_date(desc: &str) -> Option<u64> {
+    let mut lines = desc.lines();
+    lines.find(|line| line.trim() == "%INSTALLDATE%")?;
+    lines.next()?.trim().parse().ok()
+}
+
+/// Installed packages whose AUR entry was modified after they were installed. This
+/// also catches -git packages and PKGBUILD changes without a version bump. Packages
+/// with no known install date fall back to comparing versions; packages no longer on
+/// the AUR are left to `traur audit`.
+fn pending_updates(
+    installed: &[(String, String)],
+    install_dates: &HashMap<String, u64>,
+    metadata: &HashMap<String, AurPackage>,
+) -> Vec<PendingUpdate> {
+    installed
+        .iter()
+        .filter_map(|(name, version)| {
+            let meta = metadata.get(name)?;
+            let pending = match install_dates.get(name) {
+                Some(&installed_at) => meta.last_modified > installed_at,
+                None => meta.version != *version,
+            };
+            pending.then(|| PendingUpdate {
+                name: name.clone(),
+                installed_version: version.clone(),
+                aur_version: meta.version.clone(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn aur(name: &str, version: &str, last_modified: u64) -> AurPackage {
+        serde_json::from_value(serde_json::json!({
+            "Name": name,
+            "Version": version,
+            "NumVotes": 1,
+            "Popularity": 0.0,
+            "FirstSubmitted": 1,
+            "LastModified": last_modified,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn parses_install_date() {
+        let desc = "%NAME%\nfoo\n\n%VERSION%\n1.0-1\n\n%INSTALLDATE%\n1736935425\n\n%SIZE%\n1024\n";
+        assert_eq!(parse_install_date(desc), Some(1736935425));
+        assert_eq!(parse_install_date("%NAME%\nfoo\n"), None);
+    }
+
+    #[test]
+    fn selects_packages_modified_since_install() {
+        let installed = vec![
+            ("bumped".to_string(), "1.0-1".to_string()),
+            ("current".to_string(), "2.0-1".to_string()),
+            ("foo-git".to_string(), "r10.abc-1".to_string()),
+            ("no-date".to_string(), "1.0-1".to_string()),
+            ("gone".to_string(), "1.0-1".to_string()),
+        ];
+        let install_dates = HashMap::from([
+            ("bumped".to_string(), 1000),
+            ("current".to_string(), 5000),
+            ("foo-git".to_string(), 1000),
+        ]);
+        let metadata = HashMap::from([
+            ("bumped".to_string(), aur("bumped", "1.1-1", 2000)),
+            ("current".to_string(), aur("current", "2.0-1", 4000)),
+            ("foo-git".to_string(), aur("foo-git", "r10.abc-1", 3000)),
+            ("no-date".to_string(), aur("no-date", "1.2-1", 100)),
+        ]);
+
+        let pending = pending_updates(&installed, &install_dates, &metadata);
+        let names: Vec<&str> = pending.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["bumped", "foo-git", "no-date"]);
+        assert_eq!(pending[0].installed_version, "1.0-1");
+        assert_eq!(pending[0].aur_version, "1.1-1");
+    }
+}
@@ -0,0 +1,345 @@
+//! `traur verify-upstream <pkg>`: every check that correlates an AUR package with its
+//! upstream project, in one report. The last one, the phantom version check, catches an
+//! AUR "update" to a version upstream never tagged, which no scan signal covers.
+
+use crate::coordinator;
+use crate::features::bin_source_verification;
+use crate::shared::config::Config;
+use crate::shared::{github, runtime};
+use colored::Colorize;
+use serde::Serialize;
+use std::cmp::Ordering;
+
+/// Outcome of one check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Status {
+    Pass,
+    Warn,
+    Fail,
+    Skip,
+}
+
+impl std::fmt::Display for Status {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Status::Pass => write!(f, "PASS"),
+            Status::Warn => write!(f, "WARN"),
+            Status::Fail => write!(f, "FAIL"),
+            Status::Skip => write!(f, "SKIP"),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct Check {
+    check: &'static str,
+    status: Status,
+    detail: String,
+}
+
+impl Check {
+    fn new(check: &'static str, status: Status, detail: impl Into<String>) -> Self {
+        Check { check, status, detail: detail.into() }
+    }
+}
+
+/// Everything `traur verify-upstream` reports about a package.
+#[derive(Debug, Serialize)]
+struct UpstreamReport {
+    package: String,
+    version: String,
+    upstream_url: Option<String>,
+    checks: Vec<Check>,
+    /// No check failed.
+    passed: bool,
+}
+
+/// Run the upstream checks for one package. Exits 1 if any check fails.
+pub fn run(package: &str, json: bool, config: &Config) -> i32 {
+    let deadline = coordinator::Deadline::start(config);
+    let mut ctx = match coordinator::build_context(package, config, deadline) {
+        Ok(ctx) => ctx,
+        Err(e) => {
+            eprintln!("Error checking {package}: {e}");
+            return 1;
+        }
+    };
+    let Some(metadata) = ctx.metadata.clone() else {
+        eprintln!("Error checking {package}: no AUR metadata");
+        return 1;
+    };
+    let upstream_url = metadata.url.clone();
+
+    // The scan only fetches releases for -bin packages; this command wants them for all
+    let (reachable, stars, releases, tags) = runtime::block_on(async {
+        let url = upstream_url.as_deref();
+        futures_util::future::join4(
+            async { Some(check_reachable(url?).await) },
+            async { github::fetch_github_stars(url?).await },
+            async { github::fetch_github_releases(url?).await },
+            async { github::fetch_github_tags(url?).await },
+        )
+        .await
+    });
+    ctx.github_releases = releases.clone();
+
+    let mut checks = Vec::new();
+    checks.push(match (&upstream_url, reachable) {
+        (Some(url), Some(Ok(status))) => Check::new("upstream URL", Status::Pass, format!("{url} answered HTTP {status}")),
+        (Some(url), Some(Err(e))) => Check::new("upstream URL", Status::Fail, format!("{url}: {e}")),
+        _ => Check::new("upstream URL", Status::Warn, "package declares no upstream URL"),
+    });
+
+    let on_github = upstream_url.as_deref().and_then(github::parse_github_url).is_some();
+    checks.push(match stars {
+        _ if !on_github => Check::new("GitHub repo", Status::Skip, "upstream is not on GitHub"),
+        Some(info) if !info.found => Check::new("GitHub repo", Status::Fail, "upstream GitHub repo does not exist"),
+        Some(info) if info.stars == 0 => Check::new("GitHub repo", Status::Warn, "repo exists but has no stars"),
+        Some(info) => Check::new("GitHub repo", Status::Pass, format!("repo exists, {} stars", info.stars)),
+        None => Check::new("GitHub repo", Status::Skip, "GitHub API unavailable (rate limit or network)"),
+    });
+
+    let mismatches = bin_source_verification::verify(&ctx);
+    if mismatches.is_empty() {
+        let detail = if ctx.pkgbuild_content.is_some() && upstream_url.is_some() {
+            "source downloads match the upstream (or trusted mirrors), release tags and assets exist"
+        } else {
+            "nothing to compare"
+        };
+        checks.push(Check::new("sources", Status::Pass, detail));
+    }
+    for signal in mismatches {
+        let status = if signal.id == "B-BIN-DOMAIN-MISMATCH" { Status::Warn } else { Status::Fail };
+        let detail = signal.description.trim_start_matches("-bin package ").to_string();
+        let detail = match signal.matched_line {
+            Some(url) => format!("{detail} ({url})"),
+            None => detail,
+        };
+        checks.push(Check::new("sources", status, detail));
+    }
+
+    checks.push(if is_vcs(package) {
+        Check::new("upstream version", Status::Skip, "VCS package, pkgver follows the latest commit")
+    } else if !on_github {
+        Check::new("upstream version", Status::Skip, "upstream versions are only known for GitHub repos")
+    } else {
+        let release_tags = releases.iter().flatten().map(|r| r.tag_name.clone());
+        let upstream: Vec<String> = tags.iter().flatten().cloned().chain(release_tags).collect();
+        let complete = tags.as_ref().is_some_and(|t| t.len() < github::RELEASES_PER_PAGE);
+        check_version(&pkgver(&metadata.version), package, &upstream, complete)
+    });
+
+    let report = UpstreamReport {
+        package: package.to_string(),
+        version: metadata.version.clone(),
+        upstream_url,
+        passed: !checks.iter().any(|c| c.status == Status::Fail),
+        checks,
+    };
+
+    if json {
+        let json_str = serde_json::to_string_pretty(&report).expect("Failed to serialize");
+        println!("{json_str}");
+    } else {
+        print_report(&report);
+    }
+
+    if report.passed { 0 } else { 1 }
+}
+
+/// HTTP status of the upstream URL, trying GET where HEAD isn't allowed.
+async fn check_reachable(url: &str) -> Result<u16, String> {
+    let client = runtime::client();
+    let head = client.head(url).send().await.map_err(|e| e.to_string())?;
+    let resp = if matches!(head.status().as_u16(), 403 | 405 | 501) {
+        client.get(url).send().await.map_err(|e| e.to_string())?
+    } else {
+        head
+    };
+    let status = resp.status();
+    if status.is_success() || status.is_redirection() {
+        Ok(status.as_u16())
+    } else {
+        Err(format!("HTTP {}", status.as_u16()))
+    }
+}
+
+/// `-git`, `-svn`, `-hg`, `-bzr`: versions come from commits, not releases.
+fn is_vcs(package: &str) -> bool {
+    ["-git", "-svn", "-hg", "-bzr"].iter().any(|suffix| package.ends_with(suffix))
+}
+
+/// `[epoch:]pkgver-pkgrel` without epoch and pkgrel.
+fn pkgver(version: &str) -> String {
+    let version = version.split_once(':').map_or(version, |(_, v)| v);
+    version.rsplit_once('-').map_or(version, |(v, _)| v).to_string()
+}
+
+/// A tag as a pkgver would write it: `v1.2.0`, `release-1.2.0` and `foo-1.2.0` become
+/// `1.2.0`; `-` and `_` become `.` on both sides, since pkgver can't contain `-`.
+fn normalize_version(tag: &str, package: &str) -> String {
+    let lower = tag.to_lowercase();
+    let base = package.trim_end_matches("-bin");
+    let mut rest = lower.as_str();
+    for prefix in [format!("{base}-"), format!("{base}_"), "release-".to_string(), "version-".to_string()] {
+        rest = rest.strip_prefix(prefix.as_str()).unwrap_or(rest);
+    }
+    let rest = rest.strip_prefix('v').unwrap_or(rest);
+    rest.replace(['-', '_'], ".")
+}
+
+/// Phantom version check: is the AUR pkgver an upstream tag or release? One newer than
+/// everything upstream has tagged fails; an older one that isn't listed only warns.
+fn check_version(pkgver: &str, package: &str, upstream: &[String], complete: bool) -> Check {
+    const NAME: &str = "upstream version";
+    let versions: Vec<String> = upstream
+        .iter()
+        .map(|t| normalize_version(t, package))
+        .filter(|v| v.starts_with(|c: char| c.is_ascii_digit()))
+        .collect();
+    let Some(latest) = versions.iter().max_by(|a, b| vercmp(a, b)) else {
+        return Check::new(NAME, Status::Skip, "upstream has no version tags or releases");
+    };
+    let wanted = normalize_version(pkgver, package);
+    if versions.contains(&wanted) {
+        return Check::new(NAME, Status::Pass, format!("{pkgver} is tagged upstream (latest {latest})"));
+    }
+    if vercmp(&wanted, latest) == Ordering::Greater {
+        return Check::new(
+            NAME,
+            Status::Fail,
+            format!("AUR pkgver {pkgver} is newer than anything upstream tagged or released (latest {latest}): phantom version"),
+        );
+    }
+    if complete {
+        Check::new(NAME, Status::Warn, format!("{pkgver} is not among upstream tags or releases (latest {latest})"))
+    } else {
+        Check::new(
+            NAME,
+            Status::Skip,
+            format!("{pkgver} is older than the {} most recent upstream tags (latest {latest})", github::RELEASES_PER_PAGE),
+        )
+    }
+}
+
+/// Compare versions like pacman's vercmp: runs of digits numerically, runs of letters
+/// alphabetically, a digit run beating a letter run. When one runs out, the other is
+/// newer unless it continues with letters (`1.0rc1` < `1.0`).
+fn vercmp(a: &str, b: &str) -> Ordering {
+    let (a, b) = (segments(a), segments(b));
+    for (x, y) in a.iter().zip(&b) {
+        let order = match (x.parse::<u64>(), y.parse::<u64>()) {
+            (Ok(x), Ok(y)) => x.cmp(&y),
+            (Ok(_), Err(_)) => Ordering::Greater,
+            (Err(_), Ok(_)) => Ordering::Less,
+            (Err(_), Err(_)) => x.cmp(y),
+        };
+        if order != Ordering::Equal {
+            return order;
+        }
+    }
+    let longer_is_letters = |rest: Option<&&str>| rest.is_some_and(|s| s.starts_with(|c: char| c.is_alphabetic()));
+    match a.len().cmp(&b.len()) {
+        Ordering::Greater if longer_is_letters(a.get(b.len())) => Ordering::Less,
+        Ordering::Less if longer_is_letters(b.get(a.len())) => Ordering::Greater,
+        order => order,
+    }
+}
+
+/// Runs of digits and runs of letters; everything else separates them.
+fn segments(version: &str) -> Vec<&str> {
+    let mut segments = Vec::new();
+    let mut start = None;
+    for (i, c) in version.char_indices() {
+        match start {
+            Some(s) if !c.is_alphanumeric() => {
+                segments.push(&version[s..i]);
+                start = None;
+            }
+            Some(s) if version[s..].starts_with(|p: char| p.is_ascii_digit()) != c.is_ascii_digit() => {
+                segments.push(&version[s..i]);
+                start = Some(i);
+            }
+            None if c.is_alphanumeric() => start = Some(i),
+            _ => {}
+        }
+    }
+    if let Some(s) = start {
+        segments.push(&version[s..]);
+    }
+    segments
+}
+
+fn print_report(report: &UpstreamReport) {
+    println!("{}", format!("=== Upstream check: {} {} ===", report.package, report.version).bold());
+    if let Some(ref url) = report.upstream_url {
+        println!("  Upstream: {url}");
+    }
+    for check in &report.checks {
+        let status = match check.status {
+            Status::Pass => check.status.to_string().green(),
+            Status::Warn => check.status.to_string().yellow(),
+            Status::Fail => check.status.to_string().red().bold(),
+            Status::Skip => check.status.to_string().dimmed(),
+        };
+        println!("  {status:<4}  {:<16} {}", check.check, check.detail);
+    }
+    println!();
+    if report.passed {
+        println!("{}", "No upstream inconsistencies found.".green());
+    } else {
+        println!("{}", "Upstream inconsistencies found; review the failed checks.".red().bold());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tags(names: &[&str]) -> Vec<String> {
+        names.iter().map(|n| n.to_string()).collect()
+    }
+
+    #[test]
+    fn strips_epoch_and_pkgrel() {
+        assert_eq!(pkgver("1:2.3.4-2"), "2.3.4");
+        assert_eq!(pkgver("0.9_rc1-1"), "0.9_rc1");
+    }
+
+    #[test]
+    fn normalizes_tag_prefixes() {
+        assert_eq!(normalize_version("v1.2.0", "foo-bin"), "1.2.0");
+        assert_eq!(normalize_version("foo-1.2.0", "foo-bin"), "1.2.0");
+        assert_eq!(normalize_version("release-1.2.0-rc1", "foo"), "1.2.0.rc1");
+        assert_eq!(normalize_version("1.2.0_rc1", "foo"), "1.2.0.rc1");
+    }
+
+    #[test]
+    fn compares_versions_like_vercmp() {
+        assert_eq!(vercmp("1.10.0", "1.9.9"), Ordering::Greater);
+        assert_eq!(vercmp("1.0", "1.0.1"), Ordering::Less);
+        assert_eq!(vercmp("1.0rc1", "1.0"), Ordering::Less);
+        assert_eq!(vercmp("2.0", "2.0"), Ordering::Equal);
+    }
+
+    #[test]
+    fn tagged_version_passes() {
+        let check = check_version("1.4.2", "foo", &tags(&["v1.4.2", "v1.4.1"]), true);
+        assert_eq!(check.status, Status::Pass);
+    }
+
+    #[test]
+    fn version_newer_than_upstream_is_phantom() {
+        let check = check_version("1.5.0", "foo", &tags(&["v1.4.2", "v1.4.1"]), true);
+        assert_eq!(check.status, Status::Fail);
+        assert!(check.detail.contains("phantom"), "{}", check.detail);
+    }
+
+    #[test]
+    fn older_untagged_version_warns_or_skips() {
+        assert_eq!(check_version("1.3.9", "foo", &tags(&["v1.4.2", "v1.4.1"]), true).status, Status::Warn);
+        assert_eq!(check_version("1.3.9", "foo", &tags(&["v1.4.2"]), false).status, Status::Skip);
+        assert_eq!(check_version("1.0", "foo", &tags(&["nightly", "latest"]), true).status, Status::Skip);
+    }
+}
